@@ -7,21 +7,24 @@
 sp1_zkvm::entrypoint!(main);
 
 use arithmetic_core::{ArithmeticInput, run};
+use commit_codec::Commits;
 
 pub fn main() {
     // 1. Read JSON input from SP1 I/O
     let input_bytes = sp1_zkvm::io::read::<Vec<u8>>();
-    let input: ArithmeticInput = serde_json::from_slice(&input_bytes)
-        .expect("Failed to deserialize ArithmeticInput");
+    let input: ArithmeticInput = if input_bytes.is_empty() {
+        // Typed encoding: an empty marker, then the struct written directly
+        sp1_zkvm::io::read::<ArithmeticInput>()
+    } else {
+        serde_json::from_slice(&input_bytes).expect("Failed to deserialize ArithmeticInput")
+    };
 
     // 2. Run the plain Rust core (zkVM-agnostic business logic)
     let output = run(input);
 
-    // 3. Commit outputs in order (matching native runner)
-    sp1_zkvm::io::commit(&output.result);
-    
-    // Commit bool as u32: 0 for false, 1 for true
-    let overflowed_u32 = if output.overflowed { 1u32 } else { 0u32 };
-    sp1_zkvm::io::commit(&overflowed_u32);
+    // 3. Commit outputs in order (the core's `Commits` impl, shared with the native runner)
+    for value in output.commits() {
+        sp1_zkvm::io::commit(&value);
+    }
 }
 
@@ -0,0 +1,29 @@
+//! SP1 guest adapter for binary_decode core
+//!
+//! This adapter wraps the plain Rust binary_decode core with SP1's I/O layer.
+//! It reads input from SP1's stdin, runs the core, and commits outputs.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use binary_decode_core::{BinaryDecodeInput, run};
+use commit_codec::Commits;
+
+pub fn main() {
+    // 1. Read JSON input from SP1 I/O
+    let input_bytes = sp1_zkvm::io::read::<Vec<u8>>();
+    let input: BinaryDecodeInput = if input_bytes.is_empty() {
+        // Typed encoding: an empty marker, then the struct written directly
+        sp1_zkvm::io::read::<BinaryDecodeInput>()
+    } else {
+        serde_json::from_slice(&input_bytes).expect("Failed to deserialize BinaryDecodeInput")
+    };
+
+    // 2. Run the plain Rust core
+    let output = run(input);
+
+    // 3. Commit outputs in order (the core's `Commits` impl, shared with the native runner)
+    for value in output.commits() {
+        sp1_zkvm::io::commit(&value);
+    }
+}
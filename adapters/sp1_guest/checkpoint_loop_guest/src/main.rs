@@ -0,0 +1,29 @@
+//! SP1 guest adapter for checkpoint_loop core
+//!
+//! This adapter wraps the plain Rust checkpoint_loop core with SP1's I/O layer.
+//! It reads input from SP1's stdin, runs the core, and commits outputs.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use checkpoint_loop_core::{CheckpointLoopInput, run_with_checkpoints};
+use commit_codec::CommitValue;
+
+pub fn main() {
+    // 1. Read JSON input from SP1 I/O
+    let input_bytes = sp1_zkvm::io::read::<Vec<u8>>();
+    let input: CheckpointLoopInput = if input_bytes.is_empty() {
+        // Typed encoding: an empty marker, then the struct written directly
+        sp1_zkvm::io::read::<CheckpointLoopInput>()
+    } else {
+        serde_json::from_slice(&input_bytes).expect("Failed to deserialize CheckpointLoopInput")
+    };
+
+    // 2. Run the plain Rust core, committing each checkpoint as it is reached
+    let final_checksum = run_with_checkpoints(input, |checksum| {
+        sp1_zkvm::io::commit(&CommitValue::from(checksum));
+    });
+
+    // 3. Commit the final checksum last (matching native runner)
+    sp1_zkvm::io::commit(&CommitValue::from(final_checksum));
+}
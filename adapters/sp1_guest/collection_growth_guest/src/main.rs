@@ -0,0 +1,29 @@
+//! SP1 guest adapter for collection_growth core
+//!
+//! This adapter wraps the plain Rust collection_growth core with SP1's I/O layer.
+//! It reads input from SP1's stdin, runs the core, and commits outputs.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use collection_growth_core::{CollectionGrowthInput, run};
+use commit_codec::Commits;
+
+pub fn main() {
+    // 1. Read JSON input from SP1 I/O
+    let input_bytes = sp1_zkvm::io::read::<Vec<u8>>();
+    let input: CollectionGrowthInput = if input_bytes.is_empty() {
+        // Typed encoding: an empty marker, then the struct written directly
+        sp1_zkvm::io::read::<CollectionGrowthInput>()
+    } else {
+        serde_json::from_slice(&input_bytes).expect("Failed to deserialize CollectionGrowthInput")
+    };
+
+    // 2. Run the plain Rust core
+    let output = run(input);
+
+    // 3. Commit outputs in order (the core's `Commits` impl, shared with the native runner)
+    for value in output.commits() {
+        sp1_zkvm::io::commit(&value);
+    }
+}
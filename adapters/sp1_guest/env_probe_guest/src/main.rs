@@ -0,0 +1,29 @@
+//! SP1 guest adapter for env_probe core
+//!
+//! This adapter wraps the plain Rust env_probe core with SP1's I/O layer.
+//! It reads input from SP1's stdin, runs the core, and commits outputs.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use env_probe_core::{EnvProbeInput, run};
+use commit_codec::Commits;
+
+pub fn main() {
+    // 1. Read JSON input from SP1 I/O
+    let input_bytes = sp1_zkvm::io::read::<Vec<u8>>();
+    let input: EnvProbeInput = if input_bytes.is_empty() {
+        // Typed encoding: an empty marker, then the struct written directly
+        sp1_zkvm::io::read::<EnvProbeInput>()
+    } else {
+        serde_json::from_slice(&input_bytes).expect("Failed to deserialize EnvProbeInput")
+    };
+
+    // 2. Run the plain Rust core (may panic on unsupported syscalls)
+    let output = run(input);
+
+    // 3. Commit outputs in order (the core's `Commits` impl, shared with the native runner)
+    for value in output.commits() {
+        sp1_zkvm::io::commit(&value);
+    }
+}
@@ -7,22 +7,26 @@
 sp1_zkvm::entrypoint!(main);
 
 use fib_core::{FibInput, run};
+use commit_codec::Commits;
 
 pub fn main() {
     // 1. Read input from SP1 I/O (as JSON bytes)
     let input_bytes = sp1_zkvm::io::read::<Vec<u8>>();
     
     // 2. Deserialize into FibInput
-    let input: FibInput = serde_json::from_slice(&input_bytes)
-        .expect("Failed to deserialize FibInput from JSON");
+    let input: FibInput = if input_bytes.is_empty() {
+        // Typed encoding: an empty marker, then the struct written directly
+        sp1_zkvm::io::read::<FibInput>()
+    } else {
+        serde_json::from_slice(&input_bytes).expect("Failed to deserialize FibInput from JSON")
+    };
     
     // 3. Run the core business logic (ZKVM-agnostic)
     let output = run(input);
     
-    // 4. Commit outputs in order (matching the commit stream format)
-    //    This must match exactly what the native runner outputs
-    sp1_zkvm::io::commit(&output.n);
-    sp1_zkvm::io::commit(&output.a);
-    sp1_zkvm::io::commit(&output.b);
+    // 4. Commit outputs in order (the core's `Commits` impl, shared with the native runner)
+    for value in output.commits() {
+        sp1_zkvm::io::commit(&value);
+    }
 }
 
@@ -7,28 +7,28 @@
 sp1_zkvm::entrypoint!(main);
 
 use io_echo_core::{IoEchoInput, run};
+use commit_codec::Commits;
 
 pub fn main() {
     // 1. Read JSON input from SP1 I/O
     let input_bytes = sp1_zkvm::io::read::<Vec<u8>>();
-    let input: IoEchoInput = serde_json::from_slice(&input_bytes)
-        .expect("Failed to deserialize IoEchoInput");
+    let input: IoEchoInput = if input_bytes.is_empty() {
+        // Typed encoding: an empty marker, then the struct written directly
+        sp1_zkvm::io::read::<IoEchoInput>()
+    } else if let Some(header) = stdin_chunks::Header::decode(&input_bytes) {
+        // Chunked encoding: the JSON follows as raw chunks
+        let input_bytes = stdin_chunks::read_chunks(&header, sp1_zkvm::io::read_vec).expect("Malformed chunked input");
+        serde_json::from_slice(&input_bytes).expect("Failed to deserialize IoEchoInput")
+    } else {
+        serde_json::from_slice(&input_bytes).expect("Failed to deserialize IoEchoInput")
+    };
 
     // 2. Run the plain Rust core (zkVM-agnostic business logic)
     let output = run(input);
 
-    // 3. Commit outputs in order (matching native runner)
-    sp1_zkvm::io::commit(&output.length);
-    
-    // Commit Option<u8> as u32: 0 for None, 1+value for Some(value)
-    match output.first_byte {
-        None => sp1_zkvm::io::commit(&0u32),
-        Some(byte) => sp1_zkvm::io::commit(&(1u32 + byte as u32)),
-    }
-    
-    match output.last_byte {
-        None => sp1_zkvm::io::commit(&0u32),
-        Some(byte) => sp1_zkvm::io::commit(&(1u32 + byte as u32)),
+    // 3. Commit outputs in order (the core's `Commits` impl, shared with the native runner)
+    for value in output.commits() {
+        sp1_zkvm::io::commit(&value);
     }
 }
 
@@ -0,0 +1,29 @@
+//! SP1 guest adapter for iterator_fold core
+//!
+//! This adapter wraps the plain Rust iterator_fold core with SP1's I/O layer.
+//! It reads input from SP1's stdin, runs the core, and commits outputs.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use iterator_fold_core::{IteratorFoldInput, run};
+use commit_codec::Commits;
+
+pub fn main() {
+    // 1. Read JSON input from SP1 I/O
+    let input_bytes = sp1_zkvm::io::read::<Vec<u8>>();
+    let input: IteratorFoldInput = if input_bytes.is_empty() {
+        // Typed encoding: an empty marker, then the struct written directly
+        sp1_zkvm::io::read::<IteratorFoldInput>()
+    } else {
+        serde_json::from_slice(&input_bytes).expect("Failed to deserialize IteratorFoldInput")
+    };
+
+    // 2. Run the plain Rust core
+    let output = run(input);
+
+    // 3. Commit outputs in order (the core's `Commits` impl, shared with the native runner)
+    for value in output.commits() {
+        sp1_zkvm::io::commit(&value);
+    }
+}
@@ -0,0 +1,29 @@
+//! SP1 guest adapter for json_adversarial core
+//!
+//! This adapter wraps the plain Rust json_adversarial core with SP1's I/O layer.
+//! It reads input from SP1's stdin, runs the core, and commits outputs.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use json_adversarial_core::{JsonAdversarialInput, run};
+use commit_codec::Commits;
+
+pub fn main() {
+    // 1. Read JSON input from SP1 I/O
+    let input_bytes = sp1_zkvm::io::read::<Vec<u8>>();
+    let input: JsonAdversarialInput = if input_bytes.is_empty() {
+        // Typed encoding: an empty marker, then the struct written directly
+        sp1_zkvm::io::read::<JsonAdversarialInput>()
+    } else {
+        serde_json::from_slice(&input_bytes).expect("Failed to deserialize JsonAdversarialInput")
+    };
+
+    // 2. Run the plain Rust core (parses the embedded document in-guest)
+    let output = run(input);
+
+    // 3. Commit outputs in order (the core's `Commits` impl, shared with the native runner)
+    for value in output.commits() {
+        sp1_zkvm::io::commit(&value);
+    }
+}
@@ -0,0 +1,29 @@
+//! SP1 guest adapter for parse_roundtrip core
+//!
+//! This adapter wraps the plain Rust parse_roundtrip core with SP1's I/O layer.
+//! It reads input from SP1's stdin, runs the core, and commits outputs.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use parse_roundtrip_core::{ParseRoundtripInput, run};
+use commit_codec::Commits;
+
+pub fn main() {
+    // 1. Read JSON input from SP1 I/O
+    let input_bytes = sp1_zkvm::io::read::<Vec<u8>>();
+    let input: ParseRoundtripInput = if input_bytes.is_empty() {
+        // Typed encoding: an empty marker, then the struct written directly
+        sp1_zkvm::io::read::<ParseRoundtripInput>()
+    } else {
+        serde_json::from_slice(&input_bytes).expect("Failed to deserialize ParseRoundtripInput")
+    };
+
+    // 2. Run the plain Rust core (zkVM-agnostic business logic)
+    let output = run(input);
+
+    // 3. Commit outputs in order (the core's `Commits` impl, shared with the native runner)
+    for value in output.commits() {
+        sp1_zkvm::io::commit(&value);
+    }
+}
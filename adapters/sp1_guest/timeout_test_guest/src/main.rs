@@ -2,19 +2,26 @@
 sp1_zkvm::entrypoint!(main);
 
 use timeout_test_core::{TimeoutInput, run};
+use commit_codec::Commits;
 
 pub fn main() {
     // Read input bytes from SP1 I/O
     let input_bytes = sp1_zkvm::io::read::<Vec<u8>>();
     
     // Deserialize input
-    let input: TimeoutInput = serde_json::from_slice(&input_bytes)
-        .expect("Failed to deserialize TimeoutInput");
+    let input: TimeoutInput = if input_bytes.is_empty() {
+        // Typed encoding: an empty marker, then the struct written directly
+        sp1_zkvm::io::read::<TimeoutInput>()
+    } else {
+        serde_json::from_slice(&input_bytes).expect("Failed to deserialize TimeoutInput")
+    };
     
     // Run the core (may timeout if iterations == 0)
     let output = run(input);
     
-    // Commit output
-    sp1_zkvm::io::commit(&output.completed);
+    // Commit outputs in order (the core's `Commits` impl, shared with the native runner)
+    for value in output.commits() {
+        sp1_zkvm::io::commit(&value);
+    }
 }
 
@@ -0,0 +1,612 @@
+//! Canonical commit encoding shared by the native runner and zkVM adapters
+//!
+//! A core's output is committed as a list of [`CommitValue`]s, produced by
+//! the core's [`Commits`] impl. Native dispatch and every adapter iterate
+//! the same list, so the commit order and the encodings (`bool` as 0/1,
+//! `Option<u8>` as a 0- or 1-byte array) are defined once per core instead
+//! of once per runner.
+//!
+//! Serializing a value with bincode (what `sp1_zkvm::io::commit` does)
+//! produces exactly the bytes [`encode`] returns; serializing it with
+//! `serde_json` produces the JSON the SP1 runner decodes from those bytes.
+//! [`decode`] (or [`decode_schema`], for a [`CommitSchema`]) reverses
+//! [`encode`]; the SP1 runner decodes every guest's public values with it,
+//! and the property tests check both directions on arbitrary values.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use serde::{Serialize, Serializer};
+
+/// One committed value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommitValue {
+    /// 1 byte
+    U8(u8),
+    /// 4 little-endian bytes (also `bool`s committed by cores, as 0/1)
+    U32(u32),
+    /// 8 little-endian bytes
+    U64(u64),
+    /// 4 little-endian bytes
+    I32(i32),
+    /// 8 little-endian bytes
+    I64(i64),
+    /// 1 byte, 0 or 1 (a plain `bool` committed by an adapter)
+    Bool(bool),
+    /// A `u64` little-endian length, then the bytes
+    Bytes(Vec<u8>),
+    /// A `u64` little-endian length, then the UTF-8 bytes
+    String(String),
+}
+
+impl CommitValue {
+    /// Commit schema type (`sp1-runner --commit-schema`)
+    pub fn schema_type(&self) -> &'static str {
+        self.commit_type().as_str()
+    }
+
+    pub fn commit_type(&self) -> CommitType {
+        match self {
+            CommitValue::U8(_) => CommitType::U8,
+            CommitValue::U32(_) => CommitType::U32,
+            CommitValue::U64(_) => CommitType::U64,
+            CommitValue::I32(_) => CommitType::I32,
+            CommitValue::I64(_) => CommitType::I64,
+            CommitValue::Bool(_) => CommitType::Bool,
+            CommitValue::Bytes(_) => CommitType::Bytes,
+            CommitValue::String(_) => CommitType::String,
+        }
+    }
+
+    /// Append the public values bytes this commit produces
+    pub fn encode_into(&self, out: &mut Vec<u8>) {
+        let data: &[u8] = match self {
+            CommitValue::U8(value) => return out.push(*value),
+            CommitValue::U32(value) => return out.extend_from_slice(&value.to_le_bytes()),
+            CommitValue::U64(value) => return out.extend_from_slice(&value.to_le_bytes()),
+            CommitValue::I32(value) => return out.extend_from_slice(&value.to_le_bytes()),
+            CommitValue::I64(value) => return out.extend_from_slice(&value.to_le_bytes()),
+            CommitValue::Bool(value) => return out.push(u8::from(*value)),
+            CommitValue::Bytes(bytes) => bytes,
+            CommitValue::String(s) => s.as_bytes(),
+        };
+        out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        out.extend_from_slice(data);
+    }
+}
+
+/// Public values bytes of a commit stream
+pub fn encode(commits: &[CommitValue]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for commit in commits {
+        commit.encode_into(&mut out);
+    }
+    out
+}
+
+/// One committed value's type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitType {
+    U8,
+    U32,
+    U64,
+    I32,
+    I64,
+    Bool,
+    Bytes,
+    String,
+}
+
+impl CommitType {
+    pub const ALL: [CommitType; 8] = [
+        CommitType::U8,
+        CommitType::U32,
+        CommitType::U64,
+        CommitType::I32,
+        CommitType::I64,
+        CommitType::Bool,
+        CommitType::Bytes,
+        CommitType::String,
+    ];
+
+    /// The type named `name` (`u8`, `u32`, `u64`, `i32`, `i64`, `bool`,
+    /// `bytes` or `string`)
+    pub fn parse(name: &str) -> Option<CommitType> {
+        CommitType::ALL.into_iter().find(|ty| ty.as_str() == name)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CommitType::U8 => "u8",
+            CommitType::U32 => "u32",
+            CommitType::U64 => "u64",
+            CommitType::I32 => "i32",
+            CommitType::I64 => "i64",
+            CommitType::Bool => "bool",
+            CommitType::Bytes => "bytes",
+            CommitType::String => "string",
+        }
+    }
+}
+
+/// Expected layout of a commit stream (`sp1-runner --commit-schema`)
+///
+/// A comma-separated list of types; a trailing `*` on the last type repeats
+/// it until the buffer ends (e.g. `u32*` for a variable number of `u32`s).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitSchema {
+    types: Vec<CommitType>,
+    repeat_last: bool,
+}
+
+impl CommitSchema {
+    /// Parse a schema like `u32,u32,bytes` or `u64,string*`
+    pub fn parse(spec: &str) -> Result<CommitSchema, String> {
+        let names: Vec<&str> = spec.split(',').map(str::trim).collect();
+        let mut types = Vec::with_capacity(names.len());
+        let mut repeat_last = false;
+        for (idx, name) in names.iter().enumerate() {
+            let (name, repeat) = match name.strip_suffix('*') {
+                Some(name) => (name, true),
+                None => (*name, false),
+            };
+            if repeat && idx + 1 != names.len() {
+                return Err(format!("only the last type may repeat, got '{}*' at position {}", name, idx));
+            }
+            let ty = CommitType::parse(name)
+                .ok_or_else(|| format!("unknown commit type '{}' in schema '{}'", name, spec))?;
+            types.push(ty);
+            repeat_last = repeat;
+        }
+        Ok(CommitSchema { types, repeat_last })
+    }
+
+    /// `count` `u32`s, or any number of them (the default schema)
+    pub fn u32s(count: Option<usize>) -> CommitSchema {
+        match count {
+            Some(count) => CommitSchema {
+                types: vec![CommitType::U32; count],
+                repeat_last: false,
+            },
+            None => CommitSchema {
+                types: vec![CommitType::U32],
+                repeat_last: true,
+            },
+        }
+    }
+
+    /// Commits the schema requires (a repeated type may occur zero times)
+    pub fn min_commits(&self) -> usize {
+        self.types.len() - usize::from(self.repeat_last)
+    }
+}
+
+impl fmt::Display for CommitSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, ty) in self.types.iter().enumerate() {
+            write!(f, "{}{}", if idx > 0 { "," } else { "" }, ty.as_str())?;
+        }
+        if self.repeat_last {
+            write!(f, "*")?;
+        }
+        Ok(())
+    }
+}
+
+/// Why a public values buffer does not match a schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Schema type other than those of [`CommitType`]
+    UnknownType { commit_index: usize },
+    /// The buffer ended inside commit `commit_index`
+    Truncated { commit_index: usize, offset: usize },
+    /// A `string` commit that is not UTF-8
+    InvalidUtf8 { commit_index: usize, offset: usize },
+    /// A `bool` commit other than 0 or 1
+    InvalidBool { commit_index: usize, offset: usize },
+    /// Bytes left over after `decoded` commits
+    TrailingBytes { decoded: usize, offset: usize },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnknownType { commit_index } => write!(f, "commit {} has an unknown type", commit_index),
+            DecodeError::Truncated { commit_index, offset } => {
+                write!(f, "public values end inside commit {} (at byte {})", commit_index, offset)
+            }
+            DecodeError::InvalidUtf8 { commit_index, offset } => {
+                write!(f, "commit {} (at byte {}) is not a valid string", commit_index, offset)
+            }
+            DecodeError::InvalidBool { commit_index, offset } => {
+                write!(f, "commit {} (at byte {}) is not a valid bool", commit_index, offset)
+            }
+            DecodeError::TrailingBytes { decoded, offset } => {
+                write!(f, "trailing bytes in public values after {} commits (at byte {})", decoded, offset)
+            }
+        }
+    }
+}
+
+/// Decode the public values bytes of a commit stream whose types (as given
+/// by [`CommitValue::schema_type`]) are `types`
+pub fn decode(bytes: &[u8], types: &[&str]) -> Result<Vec<CommitValue>, DecodeError> {
+    let mut reader = Reader { bytes, offset: 0 };
+    let mut commits = Vec::with_capacity(types.len());
+    for (commit_index, ty) in types.iter().enumerate() {
+        let ty = CommitType::parse(ty).ok_or(DecodeError::UnknownType { commit_index })?;
+        commits.push(reader.value(commit_index, ty)?);
+    }
+    if reader.offset < bytes.len() {
+        return Err(DecodeError::TrailingBytes {
+            decoded: commits.len(),
+            offset: reader.offset,
+        });
+    }
+    Ok(commits)
+}
+
+/// Decode the public values bytes of a commit stream following `schema`
+///
+/// Fixed types must all be present; a repeated last type is decoded until
+/// the buffer ends, and a partial value there counts as trailing bytes.
+/// Either way leftover bytes are an error.
+pub fn decode_schema(bytes: &[u8], schema: &CommitSchema) -> Result<Vec<CommitValue>, DecodeError> {
+    let mut reader = Reader { bytes, offset: 0 };
+    let mut commits = Vec::with_capacity(schema.types.len());
+    for (commit_index, ty) in schema.types[..schema.min_commits()].iter().enumerate() {
+        commits.push(reader.value(commit_index, *ty)?);
+    }
+    if schema.repeat_last {
+        let ty = *schema.types.last().expect("a repeating schema has a type");
+        while reader.offset < bytes.len() {
+            let offset = reader.offset;
+            match reader.value(commits.len(), ty) {
+                Ok(value) => commits.push(value),
+                Err(DecodeError::Truncated { .. }) => {
+                    return Err(DecodeError::TrailingBytes {
+                        decoded: commits.len(),
+                        offset,
+                    })
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    if reader.offset < bytes.len() {
+        return Err(DecodeError::TrailingBytes {
+            decoded: commits.len(),
+            offset: reader.offset,
+        });
+    }
+    Ok(commits)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, commit_index: usize, len: usize) -> Result<&'a [u8], DecodeError> {
+        let truncated = DecodeError::Truncated { commit_index, offset: self.offset };
+        let end = self.offset.checked_add(len).filter(|end| *end <= self.bytes.len()).ok_or(truncated)?;
+        let data = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(data)
+    }
+
+    fn array<const N: usize>(&mut self, commit_index: usize) -> Result<[u8; N], DecodeError> {
+        Ok(self.take(commit_index, N)?.try_into().expect("took N bytes"))
+    }
+
+    /// One value of type `ty`
+    fn value(&mut self, commit_index: usize, ty: CommitType) -> Result<CommitValue, DecodeError> {
+        let offset = self.offset;
+        Ok(match ty {
+            CommitType::U8 => CommitValue::U8(self.array::<1>(commit_index)?[0]),
+            CommitType::U32 => CommitValue::U32(u32::from_le_bytes(self.array(commit_index)?)),
+            CommitType::U64 => CommitValue::U64(u64::from_le_bytes(self.array(commit_index)?)),
+            CommitType::I32 => CommitValue::I32(i32::from_le_bytes(self.array(commit_index)?)),
+            CommitType::I64 => CommitValue::I64(i64::from_le_bytes(self.array(commit_index)?)),
+            CommitType::Bool => match self.array::<1>(commit_index)?[0] {
+                0 => CommitValue::Bool(false),
+                1 => CommitValue::Bool(true),
+                _ => return Err(DecodeError::InvalidBool { commit_index, offset }),
+            },
+            CommitType::Bytes => CommitValue::Bytes(self.length_prefixed(commit_index)?.to_vec()),
+            CommitType::String => {
+                let data = self.length_prefixed(commit_index)?;
+                let s = core::str::from_utf8(data).map_err(|_| DecodeError::InvalidUtf8 { commit_index, offset })?;
+                CommitValue::String(String::from(s))
+            }
+        })
+    }
+
+    /// A `u64` little-endian length, then that many bytes
+    fn length_prefixed(&mut self, commit_index: usize) -> Result<&'a [u8], DecodeError> {
+        let start = self.offset;
+        let len = u64::from_le_bytes(self.array(commit_index)?);
+        let len = usize::try_from(len).unwrap_or(usize::MAX);
+        self.take(commit_index, len).map_err(|_| DecodeError::Truncated { commit_index, offset: start })
+    }
+}
+
+impl Serialize for CommitValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            CommitValue::U8(value) => serializer.serialize_u8(*value),
+            CommitValue::U32(value) => serializer.serialize_u32(*value),
+            CommitValue::U64(value) => serializer.serialize_u64(*value),
+            CommitValue::I32(value) => serializer.serialize_i32(*value),
+            CommitValue::I64(value) => serializer.serialize_i64(*value),
+            CommitValue::Bool(value) => serializer.serialize_bool(*value),
+            // Same bincode bytes as a `Vec<u8>`; a JSON array of numbers
+            CommitValue::Bytes(bytes) => serializer.serialize_bytes(bytes),
+            CommitValue::String(s) => serializer.serialize_str(s),
+        }
+    }
+}
+
+impl From<u32> for CommitValue {
+    fn from(value: u32) -> Self {
+        CommitValue::U32(value)
+    }
+}
+
+impl From<u64> for CommitValue {
+    fn from(value: u64) -> Self {
+        CommitValue::U64(value)
+    }
+}
+
+/// 0 for false, 1 for true
+impl From<bool> for CommitValue {
+    fn from(value: bool) -> Self {
+        CommitValue::U32(u32::from(value))
+    }
+}
+
+/// Empty for None, one byte for Some
+impl From<Option<u8>> for CommitValue {
+    fn from(value: Option<u8>) -> Self {
+        CommitValue::Bytes(value.into_iter().collect())
+    }
+}
+
+impl From<Vec<u8>> for CommitValue {
+    fn from(value: Vec<u8>) -> Self {
+        CommitValue::Bytes(value)
+    }
+}
+
+impl From<String> for CommitValue {
+    fn from(value: String) -> Self {
+        CommitValue::String(value)
+    }
+}
+
+/// A core output with a fixed commit layout
+pub trait Commits {
+    /// Values to commit, in order
+    fn commits(&self) -> Vec<CommitValue>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use proptest::collection::vec as vec_of;
+    use proptest::prelude::*;
+
+    fn sample() -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(7u32),
+            CommitValue::from(true),
+            CommitValue::from(u64::MAX - 1),
+            CommitValue::from(Some(0xabu8)),
+            CommitValue::from(None::<u8>),
+            CommitValue::from(String::from("héllo")),
+        ]
+    }
+
+    #[test]
+    fn test_encode_matches_bincode() {
+        let commits = sample();
+        let mut bincode_bytes = Vec::new();
+        for commit in &commits {
+            bincode_bytes.extend(bincode::serialize(commit).unwrap());
+        }
+        assert_eq!(encode(&commits), bincode_bytes);
+
+        // A `Vec<u8>` committed by an adapter encodes like `Bytes`
+        let plain = bincode::serialize(&vec![1u8, 2, 3]).unwrap();
+        assert_eq!(encode(&[CommitValue::Bytes(vec![1, 2, 3])]), plain);
+    }
+
+    #[test]
+    fn test_json_form() {
+        let json = serde_json::to_value(sample()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([7, 1, u64::MAX - 1, [0xab], [], "héllo"])
+        );
+        let types: Vec<&str> = sample().iter().map(CommitValue::schema_type).collect();
+        assert_eq!(types, ["u32", "u32", "u64", "bytes", "bytes", "string"]);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let bytes = encode(&sample());
+        let types = ["u32", "u32", "u64", "bytes", "bytes", "string"];
+        assert_eq!(decode(&bytes, &types), Ok(sample()));
+
+        assert_eq!(
+            decode(&bytes[..bytes.len() - 1], &types),
+            Err(DecodeError::Truncated { commit_index: 5, offset: 33 })
+        );
+        assert_eq!(decode(&bytes, &types[..5]), Err(DecodeError::TrailingBytes { decoded: 5, offset: 33 }));
+        assert_eq!(decode(&bytes, &["u32", "f32"]), Err(DecodeError::UnknownType { commit_index: 1 }));
+        // A huge length prefix must not overflow or allocate
+        assert_eq!(
+            decode(&u64::MAX.to_le_bytes(), &["bytes"]),
+            Err(DecodeError::Truncated { commit_index: 0, offset: 0 })
+        );
+        let invalid = encode(&[CommitValue::Bytes(vec![0xff])]);
+        assert_eq!(
+            decode(&invalid, &["string"]),
+            Err(DecodeError::InvalidUtf8 { commit_index: 0, offset: 0 })
+        );
+    }
+
+    #[test]
+    fn test_plain_adapter_types() {
+        // What an adapter committing plain `u8`, `i32`, `i64` and `bool` produces
+        let mut guest = Vec::new();
+        guest.extend(bincode::serialize(&0xfeu8).unwrap());
+        guest.extend(bincode::serialize(&-2i32).unwrap());
+        guest.extend(bincode::serialize(&i64::MIN).unwrap());
+        guest.extend(bincode::serialize(&true).unwrap());
+        let commits = decode(&guest, &["u8", "i32", "i64", "bool"]).unwrap();
+        assert_eq!(
+            commits,
+            vec![
+                CommitValue::U8(0xfe),
+                CommitValue::I32(-2),
+                CommitValue::I64(i64::MIN),
+                CommitValue::Bool(true)
+            ]
+        );
+        assert_eq!(encode(&commits), guest);
+        assert_eq!(serde_json::to_value(&commits).unwrap(), serde_json::json!([254, -2, i64::MIN, true]));
+
+        assert_eq!(decode(&[2], &["bool"]), Err(DecodeError::InvalidBool { commit_index: 0, offset: 0 }));
+    }
+
+    #[test]
+    fn test_schema() {
+        let schema = CommitSchema::parse("u32, bytes,string*").unwrap();
+        assert_eq!(schema.to_string(), "u32,bytes,string*");
+        assert_eq!(schema.min_commits(), 2);
+        assert_eq!(CommitSchema::u32s(None).to_string(), "u32*");
+        assert_eq!(CommitSchema::u32s(Some(2)).to_string(), "u32,u32");
+        assert!(CommitSchema::parse("u32*,u32").unwrap_err().contains("only the last type may repeat"));
+        assert!(CommitSchema::parse("u32,f64").unwrap_err().contains("unknown commit type 'f64'"));
+    }
+
+    #[test]
+    fn test_decode_schema_errors() {
+        let words = encode(&[CommitValue::U32(1), CommitValue::U32(2), CommitValue::U32(3)]);
+
+        // A fixed schema needs every commit and nothing more
+        let two = CommitSchema::u32s(Some(2));
+        assert_eq!(decode_schema(&words, &two), Err(DecodeError::TrailingBytes { decoded: 2, offset: 8 }));
+        let four = CommitSchema::u32s(Some(4));
+        assert_eq!(decode_schema(&words, &four), Err(DecodeError::Truncated { commit_index: 3, offset: 12 }));
+
+        // A repeated type takes every whole value; a partial one is trailing
+        let any = CommitSchema::u32s(None);
+        assert_eq!(decode_schema(&words, &any).unwrap().len(), 3);
+        assert_eq!(decode_schema(&[], &any), Ok(vec![]));
+        assert_eq!(
+            decode_schema(&words[..10], &any),
+            Err(DecodeError::TrailingBytes { decoded: 2, offset: 8 })
+        );
+
+        // The bytes of a u32 stream read as a string: the length prefix is absurd
+        let mismatch = CommitSchema::parse("string").unwrap();
+        assert_eq!(decode_schema(&words, &mismatch), Err(DecodeError::Truncated { commit_index: 0, offset: 0 }));
+        // 1 0 0 0 are bools, 2 is not
+        let bools = CommitSchema::parse("bool*").unwrap();
+        assert_eq!(decode_schema(&words, &bools), Err(DecodeError::InvalidBool { commit_index: 4, offset: 4 }));
+    }
+
+    fn commit_of(ty: CommitType) -> BoxedStrategy<CommitValue> {
+        match ty {
+            CommitType::U8 => any::<u8>().prop_map(CommitValue::U8).boxed(),
+            CommitType::U32 => any::<u32>().prop_map(CommitValue::U32).boxed(),
+            CommitType::U64 => any::<u64>().prop_map(CommitValue::U64).boxed(),
+            CommitType::I32 => any::<i32>().prop_map(CommitValue::I32).boxed(),
+            CommitType::I64 => any::<i64>().prop_map(CommitValue::I64).boxed(),
+            CommitType::Bool => any::<bool>().prop_map(CommitValue::Bool).boxed(),
+            CommitType::Bytes => vec_of(any::<u8>(), 0..64).prop_map(CommitValue::Bytes).boxed(),
+            CommitType::String => any::<String>().prop_map(CommitValue::String).boxed(),
+        }
+    }
+
+    fn any_type() -> impl Strategy<Value = CommitType> {
+        prop::sample::select(CommitType::ALL.to_vec())
+    }
+
+    fn any_commit() -> impl Strategy<Value = CommitValue> {
+        any_type().prop_flat_map(commit_of)
+    }
+
+    proptest! {
+        /// Native public values (`encode`) match what a guest committing the
+        /// same values produces, whether it commits the `CommitValue`s or the
+        /// plain Rust values behind them
+        #[test]
+        fn prop_native_matches_guest(commits in vec_of(any_commit(), 0..16)) {
+            let mut guest = Vec::new();
+            let mut plain = Vec::new();
+            for commit in &commits {
+                guest.extend(bincode::serialize(commit).unwrap());
+                plain.extend(match commit {
+                    CommitValue::U8(value) => bincode::serialize(value),
+                    CommitValue::U32(value) => bincode::serialize(value),
+                    CommitValue::U64(value) => bincode::serialize(value),
+                    CommitValue::I32(value) => bincode::serialize(value),
+                    CommitValue::I64(value) => bincode::serialize(value),
+                    CommitValue::Bool(value) => bincode::serialize(value),
+                    CommitValue::Bytes(bytes) => bincode::serialize(bytes),
+                    CommitValue::String(s) => bincode::serialize(s),
+                }.unwrap());
+            }
+            prop_assert_eq!(encode(&commits), guest);
+            prop_assert_eq!(encode(&commits), plain);
+        }
+
+        /// Decoding with the values' own schema types gives them back
+        #[test]
+        fn prop_roundtrip(commits in vec_of(any_commit(), 0..16)) {
+            let types: Vec<&str> = commits.iter().map(CommitValue::schema_type).collect();
+            prop_assert_eq!(decode(&encode(&commits), &types), Ok(commits.clone()));
+            if !commits.is_empty() {
+                let schema = CommitSchema::parse(&types.join(",")).unwrap();
+                prop_assert_eq!(decode_schema(&encode(&commits), &schema), Ok(commits));
+            }
+        }
+
+        /// A repeated type decodes any number of its values
+        #[test]
+        fn prop_roundtrip_repeated(
+            fixed in vec_of(any_commit(), 0..4),
+            (ty, repeated) in any_type().prop_flat_map(|ty| (Just(ty), vec_of(commit_of(ty), 0..8))),
+        ) {
+            let mut spec: Vec<String> = fixed.iter().map(|commit| String::from(commit.schema_type())).collect();
+            spec.push(format!("{}*", ty.as_str()));
+            let schema = CommitSchema::parse(&spec.join(",")).unwrap();
+            let commits: Vec<CommitValue> = fixed.into_iter().chain(repeated).collect();
+            prop_assert_eq!(decode_schema(&encode(&commits), &schema), Ok(commits));
+        }
+
+        /// Arbitrary buffers never panic, and whatever decodes re-encodes
+        /// to the same bytes
+        #[test]
+        fn prop_decode_arbitrary(
+            bytes in vec_of(any::<u8>(), 0..64),
+            types in vec_of(prop::sample::select(CommitType::ALL.map(CommitType::as_str).to_vec()), 0..8),
+        ) {
+            if let Ok(commits) = decode(&bytes, &types) {
+                prop_assert_eq!(encode(&commits), bytes);
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use commit_codec::{CommitValue, Commits};
 use serde::{Deserialize, Serialize};
 
 /// Input for arithmetic core
@@ -21,6 +22,15 @@ pub struct ArithmeticOutput {
     pub overflowed: bool,
 }
 
+impl Commits for ArithmeticOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.result),
+            CommitValue::from(self.overflowed),
+        ]
+    }
+}
+
 /// Run the arithmetic core
 /// 
 /// Performs basic arithmetic operations with overflow detection.
@@ -71,7 +81,7 @@ mod tests {
         };
         let output = run(input);
         assert_eq!(output.result, 30);
-        assert_eq!(output.overflowed, false);
+        assert!(!output.overflowed);
     }
 
     #[test]
@@ -83,7 +93,7 @@ mod tests {
         };
         let output = run(input);
         assert_eq!(output.result, 0); // Wrapping
-        assert_eq!(output.overflowed, true);
+        assert!(output.overflowed);
     }
 
     #[test]
@@ -95,7 +105,7 @@ mod tests {
         };
         let output = run(input);
         assert_eq!(output.result, 10);
-        assert_eq!(output.overflowed, false);
+        assert!(!output.overflowed);
     }
 
     #[test]
@@ -107,7 +117,7 @@ mod tests {
         };
         let output = run(input);
         assert_eq!(output.result, u32::MAX); // Wrapping
-        assert_eq!(output.overflowed, true);
+        assert!(output.overflowed);
     }
 
     #[test]
@@ -119,7 +129,7 @@ mod tests {
         };
         let output = run(input);
         assert_eq!(output.result, 200);
-        assert_eq!(output.overflowed, false);
+        assert!(!output.overflowed);
     }
 
     #[test]
@@ -131,7 +141,7 @@ mod tests {
         };
         let output = run(input);
         assert_eq!(output.result, 0); // Wraps to 2^32 % 2^32 = 0
-        assert_eq!(output.overflowed, true);
+        assert!(output.overflowed);
     }
 
     #[test]
@@ -143,7 +153,7 @@ mod tests {
         };
         let output = run(input);
         assert_eq!(output.result, 2);
-        assert_eq!(output.overflowed, false);
+        assert!(!output.overflowed);
     }
 
     #[test]
@@ -0,0 +1,242 @@
+use bincode::Options;
+use borsh::{BorshDeserialize, BorshSerialize};
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+
+/// Input for binary decode core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinaryDecodeInput {
+    /// Bytes every selected codec tries to decode as a [`Record`]
+    pub data: Vec<u8>,
+    /// Codecs to run: any of "rlp", "borsh", "bincode"
+    pub codecs: Vec<String>,
+    /// Byte limit for bincode (0 = unlimited, so huge length prefixes allocate)
+    pub bincode_limit: u64,
+}
+
+/// The value every codec decodes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Record {
+    pub id: u64,
+    pub name: String,
+    pub values: Vec<u32>,
+    pub flag: bool,
+    pub payload: Option<Vec<u8>>,
+}
+
+/// RLP form: `[id, name, [values...], flag, [payload?]]`
+impl rlp::Decodable for Record {
+    fn decode(rlp: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        if rlp.item_count()? != 5 {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+        let name = String::from_utf8(rlp.val_at(1)?).map_err(|_| rlp::DecoderError::Custom("invalid utf-8 name"))?;
+        let mut payload: Vec<Vec<u8>> = rlp.list_at(4)?;
+        if payload.len() > 1 {
+            return Err(rlp::DecoderError::Custom("payload list longer than 1"));
+        }
+        Ok(Record {
+            id: rlp.val_at(0)?,
+            name,
+            values: rlp.list_at(2)?,
+            flag: rlp.val_at(3)?,
+            payload: payload.pop(),
+        })
+    }
+}
+
+/// Outcome of one codec
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodeResult {
+    /// 0 = not run, 1 = decoded, 2 = error
+    pub status: u32,
+    /// Bytes consumed if decoded; FNV-1a of the error message if not
+    pub detail: u32,
+    /// FNV-1a of the decoded record's canonical (bincode) bytes (0 if none)
+    pub digest: u64,
+}
+
+/// Output for binary decode core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinaryDecodeOutput {
+    pub rlp: DecodeResult,
+    pub borsh: DecodeResult,
+    pub bincode: DecodeResult,
+}
+
+impl Commits for BinaryDecodeOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        [self.rlp, self.borsh, self.bincode]
+            .iter()
+            .flat_map(|result| {
+                [
+                    CommitValue::from(result.status),
+                    CommitValue::from(result.detail),
+                    CommitValue::from(result.digest),
+                ]
+            })
+            .collect()
+    }
+}
+
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3))
+}
+
+fn fnv1a_32(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(0x811c_9dc5u32, |hash, &byte| (hash ^ byte as u32).wrapping_mul(0x0100_0193))
+}
+
+/// Summarize a decode attempt that consumed `consumed` bytes on success
+fn summarize<E: std::fmt::Display>(decoded: Result<(Record, usize), E>) -> DecodeResult {
+    match decoded {
+        Ok((record, consumed)) => DecodeResult {
+            status: 1,
+            detail: consumed as u32,
+            digest: fnv1a_64(&bincode::serialize(&record).expect("records always serialize")),
+        },
+        Err(e) => DecodeResult {
+            status: 2,
+            detail: fnv1a_32(e.to_string().as_bytes()),
+            digest: 0,
+        },
+    }
+}
+
+fn decode_rlp(data: &[u8]) -> DecodeResult {
+    let rlp = rlp::Rlp::new(data);
+    summarize(rlp::Decodable::decode(&rlp).and_then(|record| {
+        let info = rlp.payload_info()?;
+        Ok((record, info.header_len + info.value_len))
+    }))
+}
+
+fn decode_borsh(data: &[u8]) -> DecodeResult {
+    let mut rest = data;
+    summarize(<Record as BorshDeserialize>::deserialize(&mut rest).map(|record| (record, data.len() - rest.len())))
+}
+
+fn decode_bincode(data: &[u8], limit: u64) -> DecodeResult {
+    let options = bincode::DefaultOptions::new().with_fixint_encoding().allow_trailing_bytes();
+    let mut rest = data;
+    let decoded: bincode::Result<Record> = if limit == 0 {
+        options.deserialize_from(&mut rest)
+    } else {
+        options.with_limit(limit).deserialize_from(&mut rest)
+    };
+    summarize(decoded.map(|record| (record, data.len() - rest.len())))
+}
+
+/// Run the binary decode core
+///
+/// Decodes the same bytes as a [`Record`] with RLP, borsh and bincode (as
+/// selected) and commits, per codec, whether it decoded, how many bytes it
+/// consumed or which error it hit, and a digest of the decoded value.
+/// Length prefixes are `u64`/`usize` on a 64-bit host but `usize` is 32
+/// bits in the guest, so oversized lengths are where decoders are most
+/// likely to part ways.
+///
+/// Target behaviors:
+/// - Length prefixes that overflow or truncate a 32-bit `usize`
+/// - Allocation for huge declared lengths (bincode without a limit)
+/// - Error paths: invalid bools, option tags, UTF-8, non-canonical RLP
+pub fn run(input: BinaryDecodeInput) -> BinaryDecodeOutput {
+    let skipped = DecodeResult {
+        status: 0,
+        detail: 0,
+        digest: 0,
+    };
+    let mut output = BinaryDecodeOutput {
+        rlp: skipped,
+        borsh: skipped,
+        bincode: skipped,
+    };
+    for codec in &input.codecs {
+        match codec.as_str() {
+            "rlp" => output.rlp = decode_rlp(&input.data),
+            "borsh" => output.borsh = decode_borsh(&input.data),
+            "bincode" => output.bincode = decode_bincode(&input.data, input.bincode_limit),
+            other => panic!("Unknown codec: {}", other),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> Record {
+        Record {
+            id: 7,
+            name: "héllo".to_string(),
+            values: vec![1, 2, 3],
+            flag: true,
+            payload: Some(vec![0xab]),
+        }
+    }
+
+    fn input(data: Vec<u8>, codec: &str) -> BinaryDecodeInput {
+        BinaryDecodeInput {
+            data,
+            codecs: vec![codec.to_string()],
+            bincode_limit: 1 << 20,
+        }
+    }
+
+    fn rlp_bytes(record: &Record) -> Vec<u8> {
+        let mut stream = rlp::RlpStream::new_list(5);
+        stream.append(&record.id).append(&record.name.as_bytes()).append_list(&record.values).append(&record.flag);
+        stream.begin_list(record.payload.iter().count());
+        if let Some(payload) = &record.payload {
+            stream.append(payload);
+        }
+        stream.out().to_vec()
+    }
+
+    #[test]
+    fn test_valid_encodings_decode_to_the_same_digest() {
+        let digest = fnv1a_64(&bincode::serialize(&record()).unwrap());
+        let encodings = [
+            ("rlp", rlp_bytes(&record())),
+            ("borsh", borsh::to_vec(&record()).unwrap()),
+            ("bincode", bincode::serialize(&record()).unwrap()),
+        ];
+        for (codec, data) in encodings {
+            let len = data.len() as u32;
+            let output = run(input(data, codec));
+            let result = [output.rlp, output.borsh, output.bincode].into_iter().find(|r| r.status != 0).unwrap();
+            assert_eq!(result, DecodeResult { status: 1, detail: len, digest }, "{}", codec);
+        }
+    }
+
+    #[test]
+    fn test_errors_and_trailing_bytes() {
+        let mut data = borsh::to_vec(&record()).unwrap();
+        data.push(0xff);
+        let output = run(input(data.clone(), "borsh"));
+        assert_eq!((output.borsh.status, output.borsh.detail), (1, data.len() as u32 - 1));
+        assert_eq!((output.rlp.status, output.bincode.status), (0, 0));
+
+        // An invalid bool, and a length prefix past the limit
+        let mut invalid_flag = bincode::serialize(&record()).unwrap();
+        let flag_offset = invalid_flag.len() - 1 - 1 - 8 - 1;
+        invalid_flag[flag_offset] = 2;
+        assert_eq!(run(input(invalid_flag, "bincode")).bincode.status, 2);
+        let huge = [&7u64.to_le_bytes()[..], &u64::MAX.to_le_bytes()].concat();
+        let output = run(input(huge, "bincode"));
+        assert_eq!((output.bincode.status, output.bincode.digest), (2, 0));
+
+        assert_eq!(run(input(vec![], "rlp")).rlp.status, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown codec")]
+    fn test_unknown_codec() {
+        run(input(vec![], "cbor"));
+    }
+}
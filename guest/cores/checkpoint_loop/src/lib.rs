@@ -0,0 +1,105 @@
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+
+/// Input for checkpoint loop core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointLoopInput {
+    /// Number of loop iterations
+    pub iterations: u64,
+    /// Emit a checkpoint every N iterations (0 = no checkpoints)
+    pub checkpoint_every: u64,
+}
+
+/// Output for checkpoint loop core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointLoopOutput {
+    /// Running checksum at each checkpoint, in order
+    pub checkpoints: Vec<u32>,
+    /// Checksum after the last iteration
+    pub final_checksum: u32,
+}
+
+impl Commits for CheckpointLoopOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        let mut commits: Vec<CommitValue> = self.checkpoints.iter().copied().map(CommitValue::from).collect();
+        commits.push(CommitValue::from(self.final_checksum));
+        commits
+    }
+}
+
+/// Run the checkpoint loop core
+///
+/// Convenience wrapper around [`run_with_checkpoints`] that collects every
+/// checkpoint into the output.
+pub fn run(input: CheckpointLoopInput) -> CheckpointLoopOutput {
+    let mut checkpoints = Vec::new();
+    let final_checksum = run_with_checkpoints(input, |checksum| checkpoints.push(checksum));
+    CheckpointLoopOutput {
+        checkpoints,
+        final_checksum,
+    }
+}
+
+/// Run the loop, calling `on_checkpoint` with the running checksum every
+/// `checkpoint_every` iterations, and return the final checksum
+///
+/// Runners commit each checkpoint as soon as it is reached, so a run that
+/// times out or panics still leaves a prefix of the commit stream behind.
+/// Comparing those prefixes localizes the divergence ("native reached
+/// checkpoint 80, SP1 reached 7").
+///
+/// Target behaviors:
+/// - Long-running loops near the runner timeouts
+/// - Incremental commits (commit stream prefixes)
+/// - 32-bit wrapping and rotate arithmetic
+pub fn run_with_checkpoints<F: FnMut(u32)>(input: CheckpointLoopInput, mut on_checkpoint: F) -> u32 {
+    let mut checksum = 0x9e37_79b9u32;
+
+    for i in 0..input.iterations {
+        checksum = checksum.rotate_left(5) ^ (i as u32);
+        checksum = checksum.wrapping_mul(0x0100_0193);
+
+        if input.checkpoint_every > 0 && (i + 1) % input.checkpoint_every == 0 {
+            on_checkpoint(checksum);
+        }
+    }
+
+    checksum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_count() {
+        let output = run(CheckpointLoopInput {
+            iterations: 1000,
+            checkpoint_every: 100,
+        });
+        assert_eq!(output.checkpoints.len(), 10);
+        assert_eq!(*output.checkpoints.last().unwrap(), output.final_checksum);
+    }
+
+    #[test]
+    fn test_no_checkpoints() {
+        let output = run(CheckpointLoopInput {
+            iterations: 1000,
+            checkpoint_every: 0,
+        });
+        assert!(output.checkpoints.is_empty());
+    }
+
+    #[test]
+    fn test_checkpoints_are_a_prefix() {
+        let short = run(CheckpointLoopInput {
+            iterations: 500,
+            checkpoint_every: 50,
+        });
+        let long = run(CheckpointLoopInput {
+            iterations: 5000,
+            checkpoint_every: 50,
+        });
+        assert_eq!(short.checkpoints[..], long.checkpoints[..short.checkpoints.len()]);
+    }
+}
@@ -0,0 +1,254 @@
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// One scripted operation, applied to both the `VecDeque` and the `Vec`:
+/// `{"push_back": 5}`, `"pop_front"`, `{"reserve": 100}`, `"shrink_to_fit"`, ...
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Op {
+    PushBack(u32),
+    /// `Vec::insert(0, ..)` on the `Vec`
+    PushFront(u32),
+    PopBack,
+    /// `Vec::remove(0)` on the `Vec`
+    PopFront,
+    /// `try_reserve` (failures are counted, not fatal)
+    Reserve(u32),
+    /// `try_reserve_exact`
+    ReserveExact(u32),
+    ShrinkToFit,
+    Truncate(u32),
+    Clear,
+    /// Push `0..n` at the back in one `extend`
+    Extend(u32),
+    /// Rotate left by `n` modulo the length
+    RotateLeft(u32),
+}
+
+/// Input for collection growth core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionGrowthInput {
+    /// Operations, in order
+    pub ops: Vec<Op>,
+}
+
+/// Output for collection growth core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionGrowthOutput {
+    pub deque_len: u32,
+    pub deque_capacity: u32,
+    /// FNV-1a over the deque's capacity after every op
+    pub deque_capacity_hash: u32,
+    /// Ops after which the deque's capacity changed
+    pub deque_capacity_changes: u32,
+    /// Length of the first of `as_slices()` (where the ring buffer wraps)
+    pub deque_front_slice_len: u32,
+    /// FNV-1a over the deque's contents, front to back
+    pub deque_digest: u32,
+    pub vec_len: u32,
+    pub vec_capacity: u32,
+    /// FNV-1a over the vec's capacity after every op
+    pub vec_capacity_hash: u32,
+    /// Ops after which the vec's capacity changed
+    pub vec_capacity_changes: u32,
+    /// FNV-1a over the vec's contents
+    pub vec_digest: u32,
+    /// `try_reserve`/`try_reserve_exact` calls that failed (on either collection)
+    pub reserve_failures: u32,
+    /// FNV-1a over popped values (deque only; the vec pops the same)
+    pub popped_hash: u32,
+}
+
+impl Commits for CollectionGrowthOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.deque_len),
+            CommitValue::from(self.deque_capacity),
+            CommitValue::from(self.deque_capacity_hash),
+            CommitValue::from(self.deque_capacity_changes),
+            CommitValue::from(self.deque_front_slice_len),
+            CommitValue::from(self.deque_digest),
+            CommitValue::from(self.vec_len),
+            CommitValue::from(self.vec_capacity),
+            CommitValue::from(self.vec_capacity_hash),
+            CommitValue::from(self.vec_capacity_changes),
+            CommitValue::from(self.vec_digest),
+            CommitValue::from(self.reserve_failures),
+            CommitValue::from(self.popped_hash),
+        ]
+    }
+}
+
+const FNV_OFFSET: u32 = 0x811c_9dc5;
+
+/// FNV-1a of a `u32`'s little-endian bytes, continued from `hash`
+fn fnv1a(hash: u32, value: u32) -> u32 {
+    value
+        .to_le_bytes()
+        .iter()
+        .fold(hash, |hash, &byte| (hash ^ byte as u32).wrapping_mul(0x0100_0193))
+}
+
+/// Capacity tracking for one collection
+struct Growth {
+    last: usize,
+    hash: u32,
+    changes: u32,
+}
+
+impl Growth {
+    fn record(&mut self, capacity: usize) {
+        if capacity != self.last {
+            self.changes += 1;
+            self.last = capacity;
+        }
+        self.hash = fnv1a(self.hash, capacity as u32);
+    }
+}
+
+/// Run the collection growth core
+///
+/// Applies the scripted operations to a `VecDeque<u32>` and a `Vec<u32>`
+/// and records their capacity after every step. Growth and shrink policy
+/// live in `std` (and `shrink_to_fit` and reserves go through the global
+/// allocator), so a different toolchain, allocator or `usize` width in the
+/// guest changes the capacity trace while the contents must not change.
+///
+/// Target behaviors:
+/// - Amortized growth factors and `reserve` vs `reserve_exact` rounding
+/// - Ring buffer wraparound in `VecDeque` (`as_slices` split point)
+/// - Reserves that overflow a 32-bit `usize` or exhaust guest memory
+pub fn run(input: CollectionGrowthInput) -> CollectionGrowthOutput {
+    let mut deque: VecDeque<u32> = VecDeque::new();
+    let mut vec: Vec<u32> = Vec::new();
+    let mut deque_growth = Growth { last: 0, hash: FNV_OFFSET, changes: 0 };
+    let mut vec_growth = Growth { last: 0, hash: FNV_OFFSET, changes: 0 };
+    let mut reserve_failures = 0u32;
+    let mut popped_hash = FNV_OFFSET;
+
+    for op in &input.ops {
+        match *op {
+            Op::PushBack(value) => {
+                deque.push_back(value);
+                vec.push(value);
+            }
+            Op::PushFront(value) => {
+                deque.push_front(value);
+                vec.insert(0, value);
+            }
+            Op::PopBack => {
+                if let Some(value) = deque.pop_back() {
+                    popped_hash = fnv1a(popped_hash, value);
+                }
+                vec.pop();
+            }
+            Op::PopFront => {
+                if let Some(value) = deque.pop_front() {
+                    popped_hash = fnv1a(popped_hash, value);
+                }
+                if !vec.is_empty() {
+                    vec.remove(0);
+                }
+            }
+            Op::Reserve(additional) => {
+                reserve_failures += deque.try_reserve(additional as usize).is_err() as u32;
+                reserve_failures += vec.try_reserve(additional as usize).is_err() as u32;
+            }
+            Op::ReserveExact(additional) => {
+                reserve_failures += deque.try_reserve_exact(additional as usize).is_err() as u32;
+                reserve_failures += vec.try_reserve_exact(additional as usize).is_err() as u32;
+            }
+            Op::ShrinkToFit => {
+                deque.shrink_to_fit();
+                vec.shrink_to_fit();
+            }
+            Op::Truncate(len) => {
+                deque.truncate(len as usize);
+                vec.truncate(len as usize);
+            }
+            Op::Clear => {
+                deque.clear();
+                vec.clear();
+            }
+            Op::Extend(n) => {
+                deque.extend(0..n);
+                vec.extend(0..n);
+            }
+            Op::RotateLeft(n) => {
+                if !vec.is_empty() {
+                    let mid = n as usize % vec.len();
+                    deque.rotate_left(mid);
+                    vec.rotate_left(mid);
+                }
+            }
+        }
+        deque_growth.record(deque.capacity());
+        vec_growth.record(vec.capacity());
+    }
+
+    CollectionGrowthOutput {
+        deque_len: deque.len() as u32,
+        deque_capacity: deque.capacity() as u32,
+        deque_capacity_hash: deque_growth.hash,
+        deque_capacity_changes: deque_growth.changes,
+        deque_front_slice_len: deque.as_slices().0.len() as u32,
+        deque_digest: deque.iter().fold(FNV_OFFSET, |hash, &value| fnv1a(hash, value)),
+        vec_len: vec.len() as u32,
+        vec_capacity: vec.capacity() as u32,
+        vec_capacity_hash: vec_growth.hash,
+        vec_capacity_changes: vec_growth.changes,
+        vec_digest: vec.iter().fold(FNV_OFFSET, |hash, &value| fnv1a(hash, value)),
+        reserve_failures,
+        popped_hash,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_ops(ops: Vec<Op>) -> CollectionGrowthOutput {
+        run(CollectionGrowthInput { ops })
+    }
+
+    #[test]
+    fn test_contents_agree() {
+        let output = run_ops(vec![
+            Op::Extend(10),
+            Op::PushFront(100),
+            Op::PopBack,
+            Op::RotateLeft(13),
+            Op::PopFront,
+            Op::Truncate(5),
+        ]);
+        assert_eq!(output.deque_digest, output.vec_digest);
+        assert_eq!((output.deque_len, output.vec_len), (5, 5));
+    }
+
+    #[test]
+    fn test_ring_buffer_wraps() {
+        // Popping from the front and pushing at the back wraps the head around
+        let mut ops = vec![Op::ReserveExact(8), Op::Extend(8)];
+        ops.extend((0..4).map(|_| Op::PopFront));
+        ops.extend((0..4).map(Op::PushBack));
+        let output = run_ops(ops);
+        assert_eq!(output.deque_len, 8);
+        assert!(output.deque_front_slice_len < 8);
+        assert_eq!(output.deque_digest, output.vec_digest);
+    }
+
+    #[test]
+    fn test_reserve_and_shrink() {
+        let output = run_ops(vec![Op::ReserveExact(100), Op::PushBack(1), Op::ShrinkToFit]);
+        assert_eq!(output.vec_capacity, 1);
+        assert_eq!(output.vec_capacity_changes, 2);
+        assert_eq!(output.reserve_failures, 0);
+
+        // 4 * u32::MAX bytes overflows isize on 32-bit targets; on 64-bit
+        // hosts it may or may not be granted
+        let output = run_ops(vec![Op::Reserve(u32::MAX), Op::Clear, Op::ShrinkToFit]);
+        assert!(output.reserve_failures <= 2);
+        assert_eq!((output.deque_len, output.vec_capacity), (0, 0));
+    }
+}
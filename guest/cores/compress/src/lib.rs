@@ -0,0 +1,150 @@
+use commit_codec::{CommitValue, Commits};
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec;
+use serde::{Deserialize, Serialize};
+
+/// Input for compression core
+/// The payload is generated in-guest from a pattern so inputs stay small
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressInput {
+    /// One of "zeros", "text", "incrementing", "random"
+    pub pattern: String,
+    /// Payload size in bytes
+    pub size: u32,
+    /// Deflate level (0-10)
+    pub level: u8,
+}
+
+/// Output for compression core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressOutput {
+    /// FNV-1a hash of the generated payload
+    pub data_hash: u32,
+    /// Length of the raw deflate stream
+    pub compressed_len: u32,
+    /// FNV-1a hash of the raw deflate stream
+    pub compressed_hash: u32,
+    /// Length after inflating the stream again
+    pub inflated_len: u32,
+    /// Whether inflate(deflate(data)) == data
+    pub roundtrip_ok: bool,
+}
+
+impl Commits for CompressOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.data_hash),
+            CommitValue::from(self.compressed_len),
+            CommitValue::from(self.compressed_hash),
+            CommitValue::from(self.inflated_len),
+            CommitValue::from(self.roundtrip_ok),
+        ]
+    }
+}
+
+/// Run the compression core
+///
+/// Deflates a generated payload with miniz_oxide and inflates it again.
+/// The compressed bytes are hashed too, so both correctness and
+/// compressor determinism are compared across targets. This is also a
+/// realistic, allocation-heavy workload for cycle profiling.
+///
+/// Target behaviors:
+/// - Allocation-heavy workloads (large, growing buffers)
+/// - Table-driven bit manipulation (LZ77 matching, Huffman coding)
+/// - Determinism of compressed output across targets
+pub fn run(input: CompressInput) -> CompressOutput {
+    let data = generate(&input.pattern, input.size as usize);
+
+    let compressed = compress_to_vec(&data, input.level);
+    let (inflated_len, roundtrip_ok) = match decompress_to_vec(&compressed) {
+        Ok(inflated) => (inflated.len() as u32, inflated == data),
+        Err(_) => (0, false),
+    };
+
+    CompressOutput {
+        data_hash: fnv1a(&data),
+        compressed_len: compressed.len() as u32,
+        compressed_hash: fnv1a(&compressed),
+        inflated_len,
+        roundtrip_ok,
+    }
+}
+
+/// Generate a deterministic payload of `size` bytes
+fn generate(pattern: &str, size: usize) -> Vec<u8> {
+    match pattern {
+        "zeros" => vec![0u8; size],
+        "text" => b"the quick brown fox jumps over the lazy dog. "
+            .iter()
+            .copied()
+            .cycle()
+            .take(size)
+            .collect(),
+        "incrementing" => (0..size).map(|i| i as u8).collect(),
+        "random" => {
+            // xorshift32, seeded from the size so every input is reproducible
+            let mut state = 0x9e37_79b9u32 ^ size as u32;
+            (0..size)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 17;
+                    state ^= state << 5;
+                    state as u8
+                })
+                .collect()
+        }
+        _ => panic!("Unknown pattern: {}", pattern),
+    }
+}
+
+/// 32-bit FNV-1a hash (small, dependency-free, identical on every target)
+fn fnv1a(bytes: &[u8]) -> u32 {
+    let mut hash = 0x811c9dc5u32;
+    for &byte in bytes {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_pattern(pattern: &str, size: u32, level: u8) -> CompressOutput {
+        run(CompressInput {
+            pattern: pattern.to_string(),
+            size,
+            level,
+        })
+    }
+
+    #[test]
+    fn test_roundtrip_all_patterns() {
+        for pattern in ["zeros", "text", "incrementing", "random"] {
+            let output = run_pattern(pattern, 4096, 6);
+            assert!(output.roundtrip_ok, "pattern {}", pattern);
+            assert_eq!(output.inflated_len, 4096);
+        }
+    }
+
+    #[test]
+    fn test_zeros_compress_well() {
+        let output = run_pattern("zeros", 65536, 6);
+        assert!(output.compressed_len < 1024);
+    }
+
+    #[test]
+    fn test_empty_payload() {
+        let output = run_pattern("random", 0, 6);
+        assert!(output.roundtrip_ok);
+        assert_eq!(output.inflated_len, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown pattern")]
+    fn test_unknown_pattern() {
+        run_pattern("gzip", 16, 6);
+    }
+}
@@ -0,0 +1,175 @@
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+
+/// One value of a rich enum, using serde's default (externally tagged)
+/// representation: `"Unit"`, `{"Tuple": [1, -2]}`, `{"Struct": {...}}`,
+/// `{"Nested": {"Ok": 5}}`, `{"Boxed": {...}}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Shape {
+    /// Unit variant
+    Unit,
+    /// Tuple variant
+    Tuple(u32, i32),
+    /// Struct variant
+    Struct { id: u32, tag: String },
+    /// Nested Option<Result<..>> payload
+    Nested(Option<Result<u32, String>>),
+    /// Recursive variant
+    Boxed(Box<Shape>),
+}
+
+/// Input for enum shapes core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnumShapesInput {
+    /// Shapes to summarize, in order
+    pub shapes: Vec<Shape>,
+}
+
+/// Output for enum shapes core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnumShapesOutput {
+    /// Number of top-level shapes
+    pub shape_count: u32,
+    /// Base-5 fold of the discriminants (outermost first, through `Boxed`)
+    pub discriminant_signature: u32,
+    /// FNV-1a hash of the payload-derived values
+    pub value_hash: u32,
+    /// `Nested(None)` count
+    pub nested_none: u32,
+    /// `Nested(Some(Ok(_)))` count
+    pub nested_ok: u32,
+    /// `Nested(Some(Err(_)))` count
+    pub nested_err: u32,
+}
+
+impl Commits for EnumShapesOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.shape_count),
+            CommitValue::from(self.discriminant_signature),
+            CommitValue::from(self.value_hash),
+            CommitValue::from(self.nested_none),
+            CommitValue::from(self.nested_ok),
+            CommitValue::from(self.nested_err),
+        ]
+    }
+}
+
+/// Run the enum shapes core
+///
+/// Walks every shape (following `Boxed` variants) and reduces discriminants
+/// and payloads to u32 summaries, so serde's enum handling and the adapter's
+/// encoding conventions can be diffed across targets.
+///
+/// Target behaviors:
+/// - serde_json enum representations (unit, tuple, struct, nested variants)
+/// - Option/Result layout and deserialization in the guest
+/// - Recursive (boxed) enum deserialization depth
+pub fn run(input: EnumShapesInput) -> EnumShapesOutput {
+    let mut output = EnumShapesOutput {
+        shape_count: input.shapes.len() as u32,
+        discriminant_signature: 0,
+        value_hash: 0x811c9dc5,
+        nested_none: 0,
+        nested_ok: 0,
+        nested_err: 0,
+    };
+
+    for shape in &input.shapes {
+        let mut current = shape;
+        loop {
+            output.discriminant_signature = output
+                .discriminant_signature
+                .wrapping_mul(5)
+                .wrapping_add(discriminant(current));
+
+            let value = match current {
+                Shape::Unit => 0,
+                Shape::Tuple(a, b) => a ^ (*b as u32),
+                Shape::Struct { id, tag } => id.wrapping_add(tag.len() as u32),
+                Shape::Nested(None) => {
+                    output.nested_none += 1;
+                    0
+                }
+                Shape::Nested(Some(Ok(v))) => {
+                    output.nested_ok += 1;
+                    *v
+                }
+                Shape::Nested(Some(Err(message))) => {
+                    output.nested_err += 1;
+                    message.len() as u32 | 0x8000_0000
+                }
+                Shape::Boxed(inner) => {
+                    current = inner;
+                    continue;
+                }
+            };
+
+            output.value_hash = fnv1a_extend(output.value_hash, &value.to_le_bytes());
+            break;
+        }
+    }
+
+    output
+}
+
+/// Stable discriminant index (declaration order, independent of layout)
+fn discriminant(shape: &Shape) -> u32 {
+    match shape {
+        Shape::Unit => 0,
+        Shape::Tuple(..) => 1,
+        Shape::Struct { .. } => 2,
+        Shape::Nested(_) => 3,
+        Shape::Boxed(_) => 4,
+    }
+}
+
+/// Continue a 32-bit FNV-1a hash over more bytes
+fn fnv1a_extend(mut hash: u32, bytes: &[u8]) -> u32 {
+    for &byte in bytes {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_externally_tagged_json() {
+        let input: EnumShapesInput = serde_json::from_str(
+            r#"{"shapes": ["Unit", {"Tuple": [1, -2]}, {"Struct": {"id": 7, "tag": "ab"}}]}"#,
+        )
+        .unwrap();
+        let output = run(input);
+        assert_eq!(output.shape_count, 3);
+        // ((0 * 5 + 0) * 5 + 1) * 5 + 2
+        assert_eq!(output.discriminant_signature, 7);
+    }
+
+    #[test]
+    fn test_nested_option_result() {
+        let input: EnumShapesInput = serde_json::from_str(
+            r#"{"shapes": [{"Nested": null}, {"Nested": {"Ok": 5}}, {"Nested": {"Err": "boom"}}]}"#,
+        )
+        .unwrap();
+        let output = run(input);
+        assert_eq!(output.nested_none, 1);
+        assert_eq!(output.nested_ok, 1);
+        assert_eq!(output.nested_err, 1);
+    }
+
+    #[test]
+    fn test_boxed_follows_inner_shape() {
+        let boxed = run(EnumShapesInput {
+            shapes: vec![Shape::Boxed(Box::new(Shape::Tuple(3, 4)))],
+        });
+        let plain = run(EnumShapesInput {
+            shapes: vec![Shape::Tuple(3, 4)],
+        });
+        assert_eq!(boxed.value_hash, plain.value_hash);
+        assert_eq!(boxed.discriminant_signature, 4 * 5 + 1);
+    }
+}
@@ -0,0 +1,140 @@
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+use std::env;
+
+/// Input for environment probe core
+/// Selects which parts of the process environment surface to observe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvProbeInput {
+    /// Variable looked up with `std::env::var` (e.g., "PATH")
+    pub lookup_var: String,
+    /// Whether the vars hash covers values as well as names
+    pub include_values: bool,
+}
+
+/// Output for environment probe core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvProbeOutput {
+    /// Number of entries in `std::env::vars_os()`
+    pub vars_count: u32,
+    /// FNV-1a hash of the sorted `NAME` (or `NAME=VALUE`) lines
+    pub vars_hash: u32,
+    /// Number of entries in `std::env::args_os()`
+    pub args_count: u32,
+    /// Whether `std::env::current_dir()` succeeded
+    pub current_dir_ok: bool,
+    /// FNV-1a hash of the current directory path (0 if unavailable)
+    pub current_dir_hash: u32,
+    /// 0 = not present, 1 = present, 2 = present but not valid unicode
+    pub lookup_status: u32,
+}
+
+impl Commits for EnvProbeOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.vars_count),
+            CommitValue::from(self.vars_hash),
+            CommitValue::from(self.args_count),
+            CommitValue::from(self.current_dir_ok),
+            CommitValue::from(self.current_dir_hash),
+            CommitValue::from(self.lookup_status),
+        ]
+    }
+}
+
+/// Run the environment probe core
+///
+/// Observes the environment surface a guest program can see and reduces it
+/// to counts and hashes. Natively these reflect the runner's process; zkVM
+/// guests typically emulate an empty environment or reject the syscalls.
+///
+/// Target behaviors:
+/// - Environment/argv emulation differences (empty vs inherited vs panic)
+/// - `current_dir` support in guests without a filesystem
+/// - Unicode handling of OS strings
+pub fn run(input: EnvProbeInput) -> EnvProbeOutput {
+    let mut var_lines: Vec<String> = env::vars_os()
+        .map(|(name, value)| {
+            let name = name.to_string_lossy();
+            if input.include_values {
+                format!("{}={}", name, value.to_string_lossy())
+            } else {
+                name.into_owned()
+            }
+        })
+        .collect();
+    var_lines.sort();
+
+    let vars_count = var_lines.len() as u32;
+    let vars_hash = fnv1a(var_lines.join("\n").as_bytes());
+
+    let args_count = env::args_os().count() as u32;
+
+    let (current_dir_ok, current_dir_hash) = match env::current_dir() {
+        Ok(dir) => (true, fnv1a(dir.to_string_lossy().as_bytes())),
+        Err(_) => (false, 0),
+    };
+
+    let lookup_status = match env::var(&input.lookup_var) {
+        Ok(_) => 1,
+        Err(env::VarError::NotPresent) => 0,
+        Err(env::VarError::NotUnicode(_)) => 2,
+    };
+
+    EnvProbeOutput {
+        vars_count,
+        vars_hash,
+        args_count,
+        current_dir_ok,
+        current_dir_hash,
+        lookup_status,
+    }
+}
+
+/// 32-bit FNV-1a hash (small, dependency-free, identical on every target)
+fn fnv1a(bytes: &[u8]) -> u32 {
+    let mut hash = 0x811c9dc5u32;
+    for &byte in bytes {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_native_surface_is_visible() {
+        let output = run(EnvProbeInput {
+            lookup_var: "CARGO_PKG_NAME".to_string(),
+            include_values: false,
+        });
+        assert!(output.vars_count > 0);
+        assert!(output.args_count >= 1);
+        assert!(output.current_dir_ok);
+        assert_ne!(output.current_dir_hash, 0);
+    }
+
+    #[test]
+    fn test_missing_var() {
+        let output = run(EnvProbeInput {
+            lookup_var: "ZK_FUZZ_LAB_DEFINITELY_UNSET".to_string(),
+            include_values: false,
+        });
+        assert_eq!(output.lookup_status, 0);
+    }
+
+    #[test]
+    fn test_names_only_hash_is_stable() {
+        let input = EnvProbeInput {
+            lookup_var: "PATH".to_string(),
+            include_values: false,
+        };
+        let first = run(input.clone());
+        let second = run(input);
+        assert_eq!(first.vars_hash, second.vars_hash);
+        assert_eq!(first.vars_count, second.vars_count);
+    }
+}
@@ -1,3 +1,4 @@
+use commit_codec::{CommitValue, Commits};
 use serde::{Deserialize, Serialize};
 
 /// Input for the fibonacci computation
@@ -17,6 +18,16 @@ pub struct FibOutput {
     pub b: u32,
 }
 
+impl Commits for FibOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.n),
+            CommitValue::from(self.a),
+            CommitValue::from(self.b),
+        ]
+    }
+}
+
 /// Pure Rust implementation of fibonacci computation
 /// This is ZKVM-agnostic business logic
 pub fn run(input: FibInput) -> FibOutput {
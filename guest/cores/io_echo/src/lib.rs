@@ -1,3 +1,4 @@
+use commit_codec::{CommitValue, Commits};
 use serde::{Deserialize, Serialize};
 
 /// Input for I/O echo core
@@ -21,6 +22,16 @@ pub struct IoEchoOutput {
     pub last_byte: Option<u8>,
 }
 
+impl Commits for IoEchoOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.length),
+            CommitValue::from(self.first_byte),
+            CommitValue::from(self.last_byte),
+        ]
+    }
+}
+
 /// Run the I/O echo core
 /// 
 /// This core exercises:
@@ -0,0 +1,141 @@
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+
+/// Input for iterator fold core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IteratorFoldInput {
+    /// Values folded by every chain
+    pub values: Vec<f32>,
+    /// Chunk size for the chunked fold (0 treated as 1)
+    pub chunk_size: u32,
+}
+
+/// Output for iterator fold core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IteratorFoldOutput {
+    /// `values.iter().sum::<f32>()` as bits
+    pub sum_forward_bits: u32,
+    /// Sum in reverse order as bits
+    pub sum_reverse_bits: u32,
+    /// Sum of per-chunk sums as bits
+    pub sum_chunked_bits: u32,
+    /// f64 accumulation rounded back to f32, as bits
+    pub sum_f64_bits: u32,
+    /// Wrapping product of the values cast to u32 (saturating casts)
+    pub product_wrapping: u32,
+    /// Index at which a checked product first overflows (len if never)
+    pub overflow_index: u32,
+    /// Fold over a long adapter chain (enumerate, filter, map, zip, skip, step_by)
+    pub chain_result: u32,
+}
+
+impl Commits for IteratorFoldOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.sum_forward_bits),
+            CommitValue::from(self.sum_reverse_bits),
+            CommitValue::from(self.sum_chunked_bits),
+            CommitValue::from(self.sum_f64_bits),
+            CommitValue::from(self.product_wrapping),
+            CommitValue::from(self.overflow_index),
+            CommitValue::from(self.chain_result),
+        ]
+    }
+}
+
+/// Run the iterator fold core
+///
+/// Folds the same values through several iterator chains whose results
+/// depend on association order (float sums) or overflow (integer
+/// products). Iterator adapters may be lowered, vectorized or reassociated
+/// differently per target, so the bit-exact results are compared.
+///
+/// Target behaviors:
+/// - Float summation order (forward, reverse, chunked, widened)
+/// - Integer overflow in wrapping and checked products
+/// - Lowering of long iterator adapter chains
+pub fn run(input: IteratorFoldInput) -> IteratorFoldOutput {
+    let values = &input.values;
+    let chunk_size = input.chunk_size.max(1) as usize;
+
+    let sum_forward: f32 = values.iter().sum();
+    let sum_reverse: f32 = values.iter().rev().sum();
+    let sum_chunked: f32 = values
+        .chunks(chunk_size)
+        .map(|chunk| chunk.iter().sum::<f32>())
+        .sum();
+    let sum_f64 = values.iter().map(|&v| v as f64).sum::<f64>() as f32;
+
+    let integers: Vec<u32> = values.iter().map(|&v| v as u32).collect();
+    let product_wrapping = integers.iter().fold(1u32, |acc, &x| acc.wrapping_mul(x));
+    let overflow_index = integers
+        .iter()
+        .scan(Some(1u32), |acc, &x| {
+            *acc = acc.and_then(|p| p.checked_mul(x));
+            Some(acc.is_some())
+        })
+        .position(|ok| !ok)
+        .unwrap_or(integers.len()) as u32;
+
+    let chain_result = values
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| i % 3 != 0)
+        .map(|(_, v)| v.to_bits() as u64)
+        .zip(integers.iter().skip(1))
+        .skip(1)
+        .step_by(2)
+        .fold(0u64, |acc, (bits, &int)| acc.rotate_left(7) ^ bits.wrapping_add(int as u64))
+        as u32;
+
+    IteratorFoldOutput {
+        sum_forward_bits: sum_forward.to_bits(),
+        sum_reverse_bits: sum_reverse.to_bits(),
+        sum_chunked_bits: sum_chunked.to_bits(),
+        sum_f64_bits: sum_f64.to_bits(),
+        product_wrapping,
+        overflow_index,
+        chain_result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_association_order_matters() {
+        // 1e8 + 1 + ... + 1 loses the ones when summed forward in f32
+        let mut values = vec![1.0e8f32];
+        values.extend(std::iter::repeat_n(1.0, 100));
+        let output = run(IteratorFoldInput {
+            values,
+            chunk_size: 10,
+        });
+        assert_eq!(f32::from_bits(output.sum_forward_bits), 1.0e8);
+        assert_eq!(f32::from_bits(output.sum_reverse_bits), 1.0e8 + 100.0);
+        assert_eq!(f32::from_bits(output.sum_f64_bits), 1.0e8 + 100.0);
+    }
+
+    #[test]
+    fn test_product_overflow() {
+        let output = run(IteratorFoldInput {
+            values: vec![65536.0, 2.0, 65536.0, 3.0],
+            chunk_size: 2,
+        });
+        assert_eq!(output.overflow_index, 2);
+        assert_eq!(output.product_wrapping, 0);
+    }
+
+    #[test]
+    fn test_empty_values() {
+        let output = run(IteratorFoldInput {
+            values: vec![],
+            chunk_size: 0,
+        });
+        // The empty f32 sum is -0.0 or +0.0 depending on the toolchain
+        assert_eq!(f32::from_bits(output.sum_forward_bits), 0.0);
+        assert_eq!(output.product_wrapping, 1);
+        assert_eq!(output.overflow_index, 0);
+    }
+}
@@ -0,0 +1,160 @@
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Input for adversarial JSON core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonAdversarialInput {
+    /// JSON document parsed inside the guest
+    pub document: String,
+}
+
+/// Output for adversarial JSON core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonAdversarialOutput {
+    /// 0 = parsed, 1 = syntax error, 2 = unexpected EOF, 3 = data error, 4 = I/O error
+    pub parse_status: u32,
+    /// Line of the parse error (0 if parsed)
+    pub error_line: u32,
+    /// Column of the parse error (0 if parsed)
+    pub error_column: u32,
+    /// Maximum nesting depth of the parsed value (scalars are depth 1)
+    pub max_depth: u32,
+    /// Number of values in the parsed document
+    pub node_count: u32,
+    /// FNV-1a hash of every number's `Display` form, in document order
+    pub numbers_hash: u32,
+    /// FNV-1a hash of every string and object key, in document order
+    pub strings_hash: u32,
+}
+
+impl Commits for JsonAdversarialOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.parse_status),
+            CommitValue::from(self.error_line),
+            CommitValue::from(self.error_column),
+            CommitValue::from(self.max_depth),
+            CommitValue::from(self.node_count),
+            CommitValue::from(self.numbers_hash),
+            CommitValue::from(self.strings_hash),
+        ]
+    }
+}
+
+/// Run the adversarial JSON core
+///
+/// Parses a guest-controlled document with serde_json inside the guest and
+/// commits the outcome: the error category and position, or a structural
+/// summary of the parsed value. Deep nesting exercises serde_json's
+/// recursion limit and the guest stack; numbers and escapes exercise
+/// float parsing and UTF-16 surrogate handling.
+///
+/// Target behaviors:
+/// - Recursion limits and stack depth in-guest vs native
+/// - Huge, tiny and out-of-range numbers (f64 parsing and formatting)
+/// - Invalid or unpaired UTF-16 escapes
+pub fn run(input: JsonAdversarialInput) -> JsonAdversarialOutput {
+    let mut output = JsonAdversarialOutput {
+        parse_status: 0,
+        error_line: 0,
+        error_column: 0,
+        max_depth: 0,
+        node_count: 0,
+        numbers_hash: FNV_OFFSET,
+        strings_hash: FNV_OFFSET,
+    };
+
+    match serde_json::from_str::<Value>(&input.document) {
+        Ok(value) => {
+            output.max_depth = summarize(&value, 1, &mut output);
+        }
+        Err(e) => {
+            output.parse_status = match e.classify() {
+                serde_json::error::Category::Syntax => 1,
+                serde_json::error::Category::Eof => 2,
+                serde_json::error::Category::Data => 3,
+                serde_json::error::Category::Io => 4,
+            };
+            output.error_line = e.line() as u32;
+            output.error_column = e.column() as u32;
+        }
+    }
+
+    output
+}
+
+/// Walk the value in document order, returning its depth
+fn summarize(value: &Value, depth: u32, output: &mut JsonAdversarialOutput) -> u32 {
+    output.node_count += 1;
+    match value {
+        Value::Number(n) => {
+            output.numbers_hash = fnv1a_extend(output.numbers_hash, n.to_string().as_bytes());
+            depth
+        }
+        Value::String(s) => {
+            output.strings_hash = fnv1a_extend(output.strings_hash, s.as_bytes());
+            depth
+        }
+        Value::Array(items) => items
+            .iter()
+            .map(|item| summarize(item, depth + 1, output))
+            .fold(depth, u32::max),
+        Value::Object(entries) => entries
+            .iter()
+            .map(|(key, item)| {
+                output.strings_hash = fnv1a_extend(output.strings_hash, key.as_bytes());
+                summarize(item, depth + 1, output)
+            })
+            .fold(depth, u32::max),
+        Value::Null | Value::Bool(_) => depth,
+    }
+}
+
+const FNV_OFFSET: u32 = 0x811c9dc5;
+
+/// Continue a 32-bit FNV-1a hash over more bytes
+fn fnv1a_extend(mut hash: u32, bytes: &[u8]) -> u32 {
+    for &byte in bytes {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(document: &str) -> JsonAdversarialOutput {
+        run(JsonAdversarialInput {
+            document: document.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_valid_document() {
+        let output = parse(r#"{"a": [1, 2.5, "x"], "b": null}"#);
+        assert_eq!(output.parse_status, 0);
+        assert_eq!(output.max_depth, 3);
+        assert_eq!(output.node_count, 6);
+    }
+
+    #[test]
+    fn test_recursion_limit() {
+        let within = "[".repeat(100) + &"]".repeat(100);
+        assert_eq!(parse(&within).max_depth, 100);
+
+        let beyond = "[".repeat(1000) + &"]".repeat(1000);
+        let output = parse(&beyond);
+        assert_eq!(output.parse_status, 1);
+        assert_eq!(output.error_line, 1);
+    }
+
+    #[test]
+    fn test_error_categories() {
+        assert_eq!(parse("[1, 2").parse_status, 2);
+        assert_eq!(parse(r#""\ud800""#).parse_status, 1);
+        assert_eq!(parse("1e400").parse_status, 1);
+    }
+}
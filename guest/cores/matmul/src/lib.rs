@@ -0,0 +1,169 @@
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+
+/// Input for matmul core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatmulInput {
+    /// Rows of A and of the result
+    pub rows: u32,
+    /// Columns of A, rows of B
+    pub inner: u32,
+    /// Columns of B and of the result
+    pub cols: u32,
+    /// Seed the matrix elements are generated from
+    pub seed: u32,
+    /// Loop nest: "ijk", "ikj", "jki" or "blocked"
+    pub loop_order: String,
+    /// Tile size for the blocked loop nest (0 treated as 1)
+    pub block_size: u32,
+}
+
+/// Output for matmul core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatmulOutput {
+    /// Rows of the result
+    pub rows: u32,
+    /// Columns of the result
+    pub cols: u32,
+    /// Wrapping sum of the result's diagonal
+    pub trace: u32,
+    /// FNV-1a digest of the result elements (row-major, little-endian)
+    pub digest: u64,
+}
+
+impl Commits for MatmulOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.rows),
+            CommitValue::from(self.cols),
+            CommitValue::from(self.trace),
+            CommitValue::from(self.digest),
+        ]
+    }
+}
+
+/// Deterministic matrix elements (xorshift32; a zero seed is remapped)
+fn generate(len: usize, seed: u32) -> Vec<u32> {
+    let mut state = if seed == 0 { 0x9e37_79b9 } else { seed };
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        })
+        .collect()
+}
+
+/// Element count of a `rows x cols` matrix
+fn area(rows: u32, cols: u32) -> usize {
+    (rows as usize)
+        .checked_mul(cols as usize)
+        .unwrap_or_else(|| panic!("matrix too large: {}x{}", rows, cols))
+}
+
+/// Run the matmul core
+///
+/// Multiplies an `rows x inner` matrix by an `inner x cols` matrix with
+/// wrapping u32 arithmetic and commits a digest of the result. The loop
+/// nest is chosen by the input: every order computes the same products,
+/// but walks memory differently, so cycle counts show the cost of each
+/// access pattern while the digest must stay identical.
+///
+/// Target behaviors:
+/// - Wrapping multiply-accumulate (`mul`/`add` lowering on riscv32)
+/// - Cache and paging cost of row- vs column-major traversal
+/// - `usize` overflow of matrix sizes on 32-bit targets
+pub fn run(input: MatmulInput) -> MatmulOutput {
+    let (m, k, n) = (input.rows as usize, input.inner as usize, input.cols as usize);
+    let a = generate(area(input.rows, input.inner), input.seed);
+    let b = generate(area(input.inner, input.cols), input.seed.wrapping_add(1));
+    let mut c = vec![0u32; area(input.rows, input.cols)];
+
+    let mut mac = |i: usize, p: usize, j: usize| {
+        c[i * n + j] = c[i * n + j].wrapping_add(a[i * k + p].wrapping_mul(b[p * n + j]));
+    };
+    match input.loop_order.as_str() {
+        "ijk" => (0..m).for_each(|i| (0..n).for_each(|j| (0..k).for_each(|p| mac(i, p, j)))),
+        "ikj" => (0..m).for_each(|i| (0..k).for_each(|p| (0..n).for_each(|j| mac(i, p, j)))),
+        "jki" => (0..n).for_each(|j| (0..k).for_each(|p| (0..m).for_each(|i| mac(i, p, j)))),
+        "blocked" => {
+            let block = input.block_size.max(1) as usize;
+            for i0 in (0..m).step_by(block) {
+                for p0 in (0..k).step_by(block) {
+                    for j0 in (0..n).step_by(block) {
+                        for i in i0..(i0 + block).min(m) {
+                            for p in p0..(p0 + block).min(k) {
+                                for j in j0..(j0 + block).min(n) {
+                                    mac(i, p, j);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        other => panic!("Unknown loop order: {}", other),
+    }
+
+    let trace = (0..m.min(n)).fold(0u32, |acc, i| acc.wrapping_add(c[i * n + i]));
+    let digest = c.iter().flat_map(|x| x.to_le_bytes()).fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+
+    MatmulOutput {
+        rows: input.rows,
+        cols: input.cols,
+        trace,
+        digest,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(rows: u32, inner: u32, cols: u32, loop_order: &str) -> MatmulInput {
+        MatmulInput {
+            rows,
+            inner,
+            cols,
+            seed: 7,
+            loop_order: loop_order.to_string(),
+            block_size: 5,
+        }
+    }
+
+    #[test]
+    fn test_loop_orders_agree() {
+        let reference = run(input(13, 9, 11, "ijk"));
+        for order in ["ikj", "jki", "blocked"] {
+            let output = run(input(13, 9, 11, order));
+            assert_eq!((output.trace, output.digest), (reference.trace, reference.digest), "{}", order);
+        }
+        assert_eq!((reference.rows, reference.cols), (13, 11));
+    }
+
+    #[test]
+    fn test_small_product() {
+        // 1x1 times 1x1 is a single wrapping multiply
+        let output = run(input(1, 1, 1, "ijk"));
+        let a = generate(1, 7)[0];
+        let b = generate(1, 8)[0];
+        assert_eq!(output.trace, a.wrapping_mul(b));
+    }
+
+    #[test]
+    fn test_empty_inner_dimension() {
+        // A 3x0 by 0x3 product is the 3x3 zero matrix
+        let output = run(input(3, 0, 3, "blocked"));
+        assert_eq!(output.trace, 0);
+        assert_eq!(output.digest, run(input(3, 0, 3, "jki")).digest);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown loop order")]
+    fn test_unknown_loop_order() {
+        run(input(1, 1, 1, "kij"));
+    }
+}
@@ -0,0 +1,164 @@
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Seconds since the Unix epoch for 2020-01-01T00:00:00Z.
+/// Any real wall clock reads later than this; stubbed clocks usually read 0.
+const PLAUSIBLE_EPOCH_SECS: u64 = 1_577_836_800;
+
+/// Status code for a probe that was not requested
+pub const NOT_PROBED: u32 = 0;
+
+/// Input for nondeterminism probe core
+/// Selects which nondeterministic std APIs to call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NondeterminismProbeInput {
+    /// Call `SystemTime::now()`
+    pub probe_system_time: bool,
+    /// Call `Instant::now()` repeatedly
+    pub probe_instant: bool,
+    /// Call `rand::random::<u32>()` repeatedly
+    pub probe_random: bool,
+    /// Number of samples taken by the instant/random probes (minimum 2)
+    pub samples: u32,
+}
+
+/// Output for nondeterminism probe core
+///
+/// Every field is a coarse status code rather than a raw reading, so native
+/// and zkVM results are comparable even though the underlying values are not.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NondeterminismProbeOutput {
+    /// 0 = not probed, 1 = plausible wall clock (after 2020),
+    /// 2 = before 2020 (stubbed clock), 3 = before the Unix epoch
+    pub system_time_status: u32,
+    /// 0 = not probed, 1 = samples never went backwards, 2 = went backwards
+    pub instant_status: u32,
+    /// 0 = not probed, 1 = draws were not all identical, 2 = constant draws
+    pub random_status: u32,
+}
+
+impl Commits for NondeterminismProbeOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.system_time_status),
+            CommitValue::from(self.instant_status),
+            CommitValue::from(self.random_status),
+        ]
+    }
+}
+
+/// Run the nondeterminism probe core
+///
+/// Calls the requested nondeterministic APIs and reduces each to a status
+/// code. Natively every probe reports 1; zkVM guests may panic on an
+/// unsupported syscall, return a fixed clock, or return constant randomness.
+///
+/// Target behaviors:
+/// - Unsupported time/random syscalls (guest panic vs native success)
+/// - Stubbed clocks returning the epoch
+/// - Deterministic or constant RNG sources inside the guest
+pub fn run(input: NondeterminismProbeInput) -> NondeterminismProbeOutput {
+    let samples = input.samples.max(2) as usize;
+
+    let system_time_status = if input.probe_system_time {
+        probe_system_time()
+    } else {
+        NOT_PROBED
+    };
+
+    let instant_status = if input.probe_instant {
+        probe_instant(samples)
+    } else {
+        NOT_PROBED
+    };
+
+    let random_status = if input.probe_random {
+        probe_random(samples)
+    } else {
+        NOT_PROBED
+    };
+
+    NondeterminismProbeOutput {
+        system_time_status,
+        instant_status,
+        random_status,
+    }
+}
+
+fn probe_system_time() -> u32 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(since_epoch) if since_epoch >= Duration::from_secs(PLAUSIBLE_EPOCH_SECS) => 1,
+        Ok(_) => 2,
+        Err(_) => 3,
+    }
+}
+
+fn probe_instant(samples: usize) -> u32 {
+    let readings: Vec<Instant> = (0..samples)
+        .map(|_| {
+            std::hint::black_box((0..64u32).sum::<u32>());
+            Instant::now()
+        })
+        .collect();
+
+    if readings.windows(2).all(|pair| pair[0] <= pair[1]) {
+        1
+    } else {
+        2
+    }
+}
+
+fn probe_random(samples: usize) -> u32 {
+    let first: u32 = rand::random();
+    let all_identical = (1..samples).all(|_| rand::random::<u32>() == first);
+
+    if all_identical {
+        2
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nothing_probed() {
+        let output = run(NondeterminismProbeInput {
+            probe_system_time: false,
+            probe_instant: false,
+            probe_random: false,
+            samples: 4,
+        });
+        assert_eq!(output.system_time_status, NOT_PROBED);
+        assert_eq!(output.instant_status, NOT_PROBED);
+        assert_eq!(output.random_status, NOT_PROBED);
+    }
+
+    #[test]
+    fn test_native_probes_are_healthy() {
+        let output = run(NondeterminismProbeInput {
+            probe_system_time: true,
+            probe_instant: true,
+            probe_random: true,
+            samples: 16,
+        });
+        assert_eq!(output.system_time_status, 1);
+        assert_eq!(output.instant_status, 1);
+        assert_eq!(output.random_status, 1);
+    }
+
+    #[test]
+    fn test_single_sample_is_raised_to_two() {
+        let output = run(NondeterminismProbeInput {
+            probe_system_time: false,
+            probe_instant: true,
+            probe_random: true,
+            samples: 0,
+        });
+        assert_eq!(output.instant_status, 1);
+        assert_eq!(output.random_status, 1);
+    }
+}
@@ -0,0 +1,150 @@
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+
+/// Input for OOM probe core
+/// Allocates `chunks` buffers of `chunk_size` bytes and keeps them all alive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OomProbeInput {
+    /// Size of each allocation in bytes
+    pub chunk_size: u32,
+    /// Number of allocations to attempt
+    pub chunks: u32,
+    /// Use `try_reserve_exact` (report failures) instead of `Vec::with_capacity` (abort on failure)
+    pub fallible: bool,
+}
+
+/// Output for OOM probe core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OomProbeOutput {
+    /// Number of allocations that succeeded before the first failure
+    pub chunks_allocated: u32,
+    /// 0 = no failure, 1 = capacity overflow, 2 = allocator returned an error
+    pub failure: u32,
+    /// Total successfully allocated memory in KiB (saturating)
+    pub total_kb: u32,
+    /// Sum over chunks of the first and last byte read back after writing
+    pub checksum: u32,
+}
+
+impl Commits for OomProbeOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.chunks_allocated),
+            CommitValue::from(self.failure),
+            CommitValue::from(self.total_kb),
+            CommitValue::from(self.checksum),
+        ]
+    }
+}
+
+/// Run the OOM probe core
+///
+/// Pushes allocations towards (and past) the target's memory limit and
+/// reports how far it got. Natively, large allocations usually succeed
+/// lazily thanks to overcommit; zkVM guests have a small, fixed heap and
+/// a 32-bit address space, so they may report an error, abort, or (with a
+/// buggy allocator) hand out overlapping memory, which the checksum catches.
+///
+/// Target behaviors:
+/// - Allocator failure handling (error vs abort)
+/// - `isize::MAX` capacity limits on 32-bit targets
+/// - Heap exhaustion and overlapping allocations
+pub fn run(input: OomProbeInput) -> OomProbeOutput {
+    let size = input.chunk_size as usize;
+    let mut buffers: Vec<Vec<u8>> = Vec::new();
+    let mut failure = 0;
+
+    for _ in 0..input.chunks {
+        if size > isize::MAX as usize {
+            failure = 1;
+            break;
+        }
+
+        // Reserve capacity without initializing it, so native runs don't
+        // commit every page of a multi-GB chunk
+        let buffer = if input.fallible {
+            let mut buffer = Vec::new();
+            if buffer.try_reserve_exact(size).is_err() {
+                failure = 2;
+                break;
+            }
+            buffer
+        } else {
+            Vec::with_capacity(size)
+        };
+        buffers.push(buffer);
+    }
+
+    // Write the first and last byte of each chunk, then read them all back
+    // after every write is done (overlapping chunks corrupt the checksum)
+    for (i, buffer) in buffers.iter_mut().enumerate() {
+        let spare = &mut buffer.spare_capacity_mut()[..size];
+        if let Some(first) = spare.first_mut() {
+            first.write(i as u8);
+        }
+        if let Some(last) = spare.last_mut() {
+            last.write((i as u8).wrapping_add(1));
+        }
+    }
+
+    let mut checksum = 0u32;
+    for buffer in &mut buffers {
+        let spare = &mut buffer.spare_capacity_mut()[..size];
+        if let (Some(first), Some(last)) = (spare.first(), spare.last()) {
+            // SAFETY: both bytes were written in the loop above
+            let (first, last) = unsafe { (first.assume_init(), last.assume_init()) };
+            checksum = checksum.wrapping_add(first as u32).wrapping_add(last as u32);
+        }
+    }
+
+    let total_bytes = buffers.len() as u64 * size as u64;
+
+    OomProbeOutput {
+        chunks_allocated: buffers.len() as u32,
+        failure,
+        total_kb: (total_bytes / 1024).min(u32::MAX as u64) as u32,
+        checksum,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_allocations() {
+        let output = run(OomProbeInput {
+            chunk_size: 4096,
+            chunks: 4,
+            fallible: true,
+        });
+        assert_eq!(output.chunks_allocated, 4);
+        assert_eq!(output.failure, 0);
+        assert_eq!(output.total_kb, 16);
+        // first bytes 0..4 plus last bytes 1..5
+        assert_eq!(output.checksum, 6 + 10);
+    }
+
+    #[test]
+    fn test_zero_sized_chunks() {
+        let output = run(OomProbeInput {
+            chunk_size: 0,
+            chunks: 8,
+            fallible: false,
+        });
+        assert_eq!(output.chunks_allocated, 8);
+        assert_eq!(output.total_kb, 0);
+        assert_eq!(output.checksum, 0);
+    }
+
+    #[test]
+    fn test_single_byte_chunk_aliases_first_and_last() {
+        let output = run(OomProbeInput {
+            chunk_size: 1,
+            chunks: 1,
+            fallible: true,
+        });
+        // The last write wins when first and last are the same byte
+        assert_eq!(output.checksum, 2);
+    }
+}
@@ -0,0 +1,178 @@
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::panic;
+use std::rc::Rc;
+
+/// Input for panic-in-Drop core
+/// Selects how panics interact with destructors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PanicDropInput {
+    /// One of "none", "unwind_through_drop", "panic_in_drop", "double_panic"
+    pub mode: String,
+    /// Number of counting guards alive when the panic happens
+    pub guards: u32,
+}
+
+/// Output for panic-in-Drop core (only returned if the panic was recovered or never happened)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PanicDropOutput {
+    /// Number of body stages completed (0-2)
+    pub stages_completed: u32,
+    /// Number of counting guards whose `Drop` ran
+    pub drops_run: u32,
+    /// Whether a panic was caught by `catch_unwind`
+    pub recovered: bool,
+}
+
+impl Commits for PanicDropOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.stages_completed),
+            CommitValue::from(self.drops_run),
+            CommitValue::from(self.recovered),
+        ]
+    }
+}
+
+/// Shared progress counters observed by the guards
+#[derive(Default)]
+struct Progress {
+    stages: Cell<u32>,
+    drops: Cell<u32>,
+}
+
+/// Guard that records its own drop
+struct CountingGuard(Rc<Progress>);
+
+impl Drop for CountingGuard {
+    fn drop(&mut self) {
+        self.0.drops.set(self.0.drops.get() + 1);
+    }
+}
+
+/// Guard that panics from `Drop`
+struct BombGuard {
+    /// Only panic if the thread is already unwinding (double panic)
+    only_while_panicking: bool,
+}
+
+impl Drop for BombGuard {
+    fn drop(&mut self) {
+        if !self.only_while_panicking || std::thread::panicking() {
+            panic!("Panic in Drop");
+        }
+    }
+}
+
+/// Run the panic-in-Drop core
+///
+/// Natively, panics unwind: destructors run and `catch_unwind` recovers, so
+/// the core returns how far it got. zkVM guests are typically built with
+/// `panic = "abort"`, so the same input ends the guest with a panic instead.
+/// `double_panic` aborts on every target (Rust aborts on a panic during
+/// unwinding).
+///
+/// Target behaviors:
+/// - Unwind vs abort semantics
+/// - Destructors running (or not) during unwinding
+/// - Panic-while-panicking handling
+pub fn run(input: PanicDropInput) -> PanicDropOutput {
+    let progress = Rc::new(Progress::default());
+
+    let outcome = match input.mode.as_str() {
+        "none" => {
+            guarded_body(&progress, input.guards, None, false);
+            Ok(())
+        }
+        "unwind_through_drop" => catch(|| guarded_body(&progress, input.guards, None, true)),
+        "panic_in_drop" => catch(|| {
+            guarded_body(
+                &progress,
+                input.guards,
+                Some(BombGuard {
+                    only_while_panicking: false,
+                }),
+                false,
+            )
+        }),
+        "double_panic" => catch(|| {
+            guarded_body(
+                &progress,
+                input.guards,
+                Some(BombGuard {
+                    only_while_panicking: true,
+                }),
+                true,
+            )
+        }),
+        _ => panic!("Unknown mode: {}", input.mode),
+    };
+
+    PanicDropOutput {
+        stages_completed: progress.stages.get(),
+        drops_run: progress.drops.get(),
+        recovered: outcome.is_err(),
+    }
+}
+
+/// Create the guards, then optionally panic from the body
+fn guarded_body(progress: &Rc<Progress>, guards: u32, bomb: Option<BombGuard>, panic_in_body: bool) {
+    // Declared first so it is dropped last, after every counting guard
+    let _bomb = bomb;
+    let _guards: Vec<CountingGuard> = (0..guards)
+        .map(|_| CountingGuard(Rc::clone(progress)))
+        .collect();
+    progress.stages.set(1);
+
+    if panic_in_body {
+        panic!("Panic in body");
+    }
+    progress.stages.set(2);
+}
+
+fn catch<F: FnOnce()>(f: F) -> std::thread::Result<()> {
+    panic::catch_unwind(panic::AssertUnwindSafe(f))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_mode(mode: &str, guards: u32) -> PanicDropOutput {
+        run(PanicDropInput {
+            mode: mode.to_string(),
+            guards,
+        })
+    }
+
+    #[test]
+    fn test_no_panic() {
+        let output = run_mode("none", 3);
+        assert_eq!(output.stages_completed, 2);
+        assert_eq!(output.drops_run, 3);
+        assert!(!output.recovered);
+    }
+
+    #[test]
+    fn test_unwind_runs_destructors() {
+        let output = run_mode("unwind_through_drop", 4);
+        assert_eq!(output.stages_completed, 1);
+        assert_eq!(output.drops_run, 4);
+        assert!(output.recovered);
+    }
+
+    #[test]
+    fn test_panic_in_drop_is_recovered() {
+        let output = run_mode("panic_in_drop", 2);
+        assert_eq!(output.stages_completed, 2);
+        assert_eq!(output.drops_run, 2);
+        assert!(output.recovered);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown mode")]
+    fn test_unknown_mode() {
+        run_mode("triple_panic", 1);
+    }
+}
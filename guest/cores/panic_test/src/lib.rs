@@ -1,3 +1,4 @@
+use commit_codec::{CommitValue, Commits};
 use serde::{Deserialize, Serialize};
 
 /// Input for panic test core
@@ -18,6 +19,15 @@ pub struct PanicOutput {
     pub status_code: u32,
 }
 
+impl Commits for PanicOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.should_panic_u32),
+            CommitValue::from(self.status_code),
+        ]
+    }
+}
+
 /// Run the panic test core
 /// 
 /// Panics if input.should_panic is true, otherwise returns success.
@@ -0,0 +1,153 @@
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+
+/// Input for parse round-trip core
+/// Tests `str::parse` and `Display` consistency for integer and float types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParseRoundtripInput {
+    /// Text to parse as u32, i64 and f64 (e.g., "+42", "inf", "NaN", "4294967296")
+    pub text: String,
+}
+
+/// Output for parse round-trip core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParseRoundtripOutput {
+    /// Whether `text.parse::<u32>()` succeeded
+    pub u32_ok: bool,
+    /// FNV-1a hash of the re-formatted u32 (0 if parsing failed)
+    pub u32_hash: u32,
+    /// Whether `text.parse::<i64>()` succeeded
+    pub i64_ok: bool,
+    /// FNV-1a hash of the re-formatted i64 (0 if parsing failed)
+    pub i64_hash: u32,
+    /// Whether `text.parse::<f64>()` succeeded
+    pub f64_ok: bool,
+    /// FNV-1a hash of the re-formatted f64 (0 if parsing failed)
+    pub f64_hash: u32,
+}
+
+impl Commits for ParseRoundtripOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.u32_ok),
+            CommitValue::from(self.u32_hash),
+            CommitValue::from(self.i64_ok),
+            CommitValue::from(self.i64_hash),
+            CommitValue::from(self.f64_ok),
+            CommitValue::from(self.f64_hash),
+        ]
+    }
+}
+
+/// Run the parse round-trip core
+///
+/// Parses the same text as u32, i64 and f64, formats each successful parse
+/// back with `Display`, and hashes the result so the commit stream stays
+/// fixed-size regardless of the text length.
+///
+/// Target vulnerabilities:
+/// - Parsing corner cases (leading `+`, overflow, "inf", "NaN") differing across std builds
+/// - Float formatting differences (shortest round-trip representation)
+/// - Soft-float vs hardware-float parsing paths on riscv32
+pub fn run(input: ParseRoundtripInput) -> ParseRoundtripOutput {
+    let (u32_ok, u32_hash) = roundtrip::<u32>(&input.text);
+    let (i64_ok, i64_hash) = roundtrip::<i64>(&input.text);
+    let (f64_ok, f64_hash) = roundtrip::<f64>(&input.text);
+
+    ParseRoundtripOutput {
+        u32_ok,
+        u32_hash,
+        i64_ok,
+        i64_hash,
+        f64_ok,
+        f64_hash,
+    }
+}
+
+/// Parse `text` as `T` and hash its `Display` form
+fn roundtrip<T>(text: &str) -> (bool, u32)
+where
+    T: std::str::FromStr + std::fmt::Display,
+{
+    match text.parse::<T>() {
+        Ok(value) => (true, fnv1a(value.to_string().as_bytes())),
+        Err(_) => (false, 0),
+    }
+}
+
+/// 32-bit FNV-1a hash (small, dependency-free, identical on every target)
+fn fnv1a(bytes: &[u8]) -> u32 {
+    let mut hash = 0x811c9dc5u32;
+    for &byte in bytes {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_text(text: &str) -> ParseRoundtripOutput {
+        run(ParseRoundtripInput {
+            text: text.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_plain_integer() {
+        let output = run_text("42");
+        assert!(output.u32_ok);
+        assert!(output.i64_ok);
+        assert!(output.f64_ok);
+        assert_eq!(output.u32_hash, fnv1a(b"42"));
+        assert_eq!(output.i64_hash, fnv1a(b"42"));
+        assert_eq!(output.f64_hash, fnv1a(b"42"));
+    }
+
+    #[test]
+    fn test_leading_plus() {
+        let output = run_text("+7");
+        assert!(output.u32_ok);
+        assert_eq!(output.u32_hash, fnv1a(b"7"));
+    }
+
+    #[test]
+    fn test_u32_overflow() {
+        let output = run_text("4294967296");
+        assert!(!output.u32_ok);
+        assert_eq!(output.u32_hash, 0);
+        assert!(output.i64_ok);
+        assert_eq!(output.i64_hash, fnv1a(b"4294967296"));
+    }
+
+    #[test]
+    fn test_negative() {
+        let output = run_text("-1");
+        assert!(!output.u32_ok);
+        assert!(output.i64_ok);
+        assert_eq!(output.i64_hash, fnv1a(b"-1"));
+    }
+
+    #[test]
+    fn test_float_specials() {
+        let inf = run_text("inf");
+        assert!(!inf.u32_ok);
+        assert!(!inf.i64_ok);
+        assert!(inf.f64_ok);
+        assert_eq!(inf.f64_hash, fnv1a(b"inf"));
+
+        let nan = run_text("NaN");
+        assert!(nan.f64_ok);
+        assert_eq!(nan.f64_hash, fnv1a(b"NaN"));
+    }
+
+    #[test]
+    fn test_garbage() {
+        let output = run_text("12abc");
+        assert!(!output.u32_ok);
+        assert!(!output.i64_ok);
+        assert!(!output.f64_ok);
+    }
+}
@@ -1,4 +1,6 @@
+use commit_codec::{CommitValue, Commits};
 use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
 
 /// Input for simple struct core
 /// Tests struct serialization, string handling, and ABI compatibility
@@ -23,6 +25,21 @@ pub struct SimpleStructOutput {
     pub field2_chars: u32,
     /// Echo of field3
     pub field3_echo: bool,
+    /// Number of extended grapheme clusters in field2 (may differ from chars
+    /// for combining marks, ZWJ sequences, flags and CRLF)
+    pub field2_graphemes: u32,
+}
+
+impl Commits for SimpleStructOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.field1_echo),
+            CommitValue::from(self.field2_len),
+            CommitValue::from(self.field2_chars),
+            CommitValue::from(self.field3_echo),
+            CommitValue::from(self.field2_graphemes),
+        ]
+    }
 }
 
 /// Run the simple struct core
@@ -30,7 +47,7 @@ pub struct SimpleStructOutput {
 /// Tests:
 /// - Struct layout and padding
 /// - String encoding (UTF-8 validation)
-/// - Byte vs char length (important for unicode)
+/// - Byte vs char vs grapheme length (important for unicode)
 /// - Serialization consistency between native and RISC-V
 /// 
 /// Target vulnerabilities:
@@ -40,12 +57,14 @@ pub struct SimpleStructOutput {
 pub fn run(input: SimpleStructInput) -> SimpleStructOutput {
     let field2_len = input.field2.len() as u32;        // Byte length
     let field2_chars = input.field2.chars().count() as u32; // Character count
+    let field2_graphemes = input.field2.graphemes(true).count() as u32; // User-perceived characters
 
     SimpleStructOutput {
         field1_echo: input.field1,
         field2_len,
         field2_chars,
         field3_echo: input.field3,
+        field2_graphemes,
     }
 }
 
@@ -64,7 +83,7 @@ mod tests {
         assert_eq!(output.field1_echo, 42);
         assert_eq!(output.field2_len, 5);
         assert_eq!(output.field2_chars, 5);
-        assert_eq!(output.field3_echo, true);
+        assert!(output.field3_echo);
     }
 
     #[test]
@@ -78,7 +97,7 @@ mod tests {
         assert_eq!(output.field1_echo, 0);
         assert_eq!(output.field2_len, 0);
         assert_eq!(output.field2_chars, 0);
-        assert_eq!(output.field3_echo, false);
+        assert!(!output.field3_echo);
     }
 
     #[test]
@@ -92,7 +111,22 @@ mod tests {
         assert_eq!(output.field1_echo, 1);
         assert_eq!(output.field2_len, 9); // 4 (emoji) + 1 (space) + 4 ("Rust")
         assert_eq!(output.field2_chars, 6); // 1 (emoji) + 1 (space) + 4 ("Rust")
-        assert_eq!(output.field3_echo, true);
+        assert_eq!(output.field2_graphemes, 6);
+        assert!(output.field3_echo);
+    }
+
+    #[test]
+    fn test_graphemes_differ_from_chars() {
+        // Decomposed é, a ZWJ family, two flags and CRLF
+        let input = SimpleStructInput {
+            field1: 2,
+            field2: "e\u{301}\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{1f1e9}\u{1f1ea}\u{1f1fa}\u{1f1f8}\r\n".to_string(),
+            field3: true,
+        };
+        let output = run(input);
+        assert_eq!(output.field2_len, 3 + 18 + 16 + 2);
+        assert_eq!(output.field2_chars, 2 + 5 + 4 + 2);
+        assert_eq!(output.field2_graphemes, 1 + 1 + 2 + 1);
     }
 
     #[test]
@@ -107,7 +141,7 @@ mod tests {
         assert_eq!(output.field1_echo, 99);
         assert_eq!(output.field2_len, 1000);
         assert_eq!(output.field2_chars, 1000);
-        assert_eq!(output.field3_echo, false);
+        assert!(!output.field3_echo);
     }
 
     #[test]
@@ -121,7 +155,7 @@ mod tests {
         assert_eq!(output.field1_echo, u32::MAX);
         assert_eq!(output.field2_len, 3);
         assert_eq!(output.field2_chars, 3);
-        assert_eq!(output.field3_echo, true);
+        assert!(output.field3_echo);
     }
 }
 
@@ -0,0 +1,159 @@
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Input for sorting core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SortingInput {
+    /// Integer keys to sort
+    pub keys: Vec<i32>,
+    /// Divisor for the coarse key used by the tie-sensitive sorts (0 treated as 1)
+    pub tie_bucket: i32,
+    /// f32 keys as raw bits (so NaNs, -0.0 and infinities can be expressed in JSON)
+    pub float_bits: Vec<u32>,
+}
+
+/// Output for sorting core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SortingOutput {
+    /// Original indices after a stable sort by coarse key
+    pub stable_digest: u32,
+    /// Original indices after an unstable sort by coarse key (tie order is unspecified)
+    pub unstable_tie_digest: u32,
+    /// Keys after `sort_unstable`
+    pub sorted_digest: u32,
+    /// Float bits after sorting by `f32::total_cmp`
+    pub total_cmp_digest: u32,
+    /// Float bits after sorting by `partial_cmp` with NaN treated as equal
+    pub partial_cmp_digest: u32,
+    /// Keys after a stable descending sort with a custom comparator
+    pub descending_digest: u32,
+    /// Number of distinct keys (`dedup` after sorting)
+    pub unique_count: u32,
+}
+
+impl Commits for SortingOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.stable_digest),
+            CommitValue::from(self.unstable_tie_digest),
+            CommitValue::from(self.sorted_digest),
+            CommitValue::from(self.total_cmp_digest),
+            CommitValue::from(self.partial_cmp_digest),
+            CommitValue::from(self.descending_digest),
+            CommitValue::from(self.unique_count),
+        ]
+    }
+}
+
+/// Run the sorting core
+///
+/// Sorts the same data with `sort`, `sort_unstable` and custom comparators
+/// and commits digests of the results. Stable sorts and total orders are
+/// fully specified, so any difference is a bug; the tie order of unstable
+/// sorts and the result of an inconsistent (NaN) comparator are not, so
+/// they expose algorithm differences between the standard libraries used
+/// natively and in the guest.
+///
+/// Target behaviors:
+/// - Stable vs unstable sort implementations
+/// - `total_cmp` ordering of NaN, -0.0 and infinities
+/// - Comparators that violate total order (may panic on newer toolchains)
+pub fn run(input: SortingInput) -> SortingOutput {
+    let bucket = if input.tie_bucket == 0 { 1 } else { input.tie_bucket };
+    let indexed: Vec<(i32, u32)> = input
+        .keys
+        .iter()
+        .enumerate()
+        .map(|(i, &key)| (key.wrapping_div(bucket), i as u32))
+        .collect();
+
+    let mut stable = indexed.clone();
+    stable.sort_by_key(|&(coarse, _)| coarse);
+
+    let mut unstable = indexed;
+    unstable.sort_unstable_by_key(|&(coarse, _)| coarse);
+
+    let mut sorted = input.keys.clone();
+    sorted.sort_unstable();
+
+    let floats: Vec<f32> = input.float_bits.iter().map(|&bits| f32::from_bits(bits)).collect();
+
+    let mut total = floats.clone();
+    total.sort_by(|a, b| a.total_cmp(b));
+
+    let mut partial = floats;
+    partial.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+    let mut descending = input.keys;
+    descending.sort_by(|a, b| b.cmp(a));
+
+    let mut unique = sorted.clone();
+    unique.dedup();
+
+    SortingOutput {
+        stable_digest: digest(stable.iter().map(|&(_, i)| i)),
+        unstable_tie_digest: digest(unstable.iter().map(|&(_, i)| i)),
+        sorted_digest: digest(sorted.iter().map(|&k| k as u32)),
+        total_cmp_digest: digest(total.iter().map(|f| f.to_bits())),
+        partial_cmp_digest: digest(partial.iter().map(|f| f.to_bits())),
+        descending_digest: digest(descending.iter().map(|&k| k as u32)),
+        unique_count: unique.len() as u32,
+    }
+}
+
+/// FNV-1a hash over the little-endian bytes of a u32 sequence
+fn digest(values: impl Iterator<Item = u32>) -> u32 {
+    let mut hash = 0x811c9dc5u32;
+    for value in values {
+        for byte in value.to_le_bytes() {
+            hash ^= byte as u32;
+            hash = hash.wrapping_mul(0x01000193);
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(keys: Vec<i32>, float_bits: Vec<u32>) -> SortingInput {
+        SortingInput {
+            keys,
+            tie_bucket: 10,
+            float_bits,
+        }
+    }
+
+    #[test]
+    fn test_stable_sort_keeps_tie_order() {
+        // Coarse keys: 1, 0, 1, 0 -> stable order of indices is 1, 3, 0, 2
+        let output = run(input(vec![15, 3, 12, 7], vec![]));
+        assert_eq!(output.stable_digest, digest([1u32, 3, 0, 2].into_iter()));
+        assert_eq!(output.sorted_digest, digest([3u32, 7, 12, 15].into_iter()));
+        assert_eq!(output.descending_digest, digest([15u32, 12, 7, 3].into_iter()));
+    }
+
+    #[test]
+    fn test_total_cmp_orders_nan_and_zero() {
+        let nan = f32::NAN.to_bits();
+        let neg_nan = (-f32::NAN).to_bits();
+        let output = run(input(
+            vec![],
+            vec![nan, 1.0f32.to_bits(), 0.0f32.to_bits(), (-0.0f32).to_bits(), neg_nan],
+        ));
+        let expected = [neg_nan, (-0.0f32).to_bits(), 0.0f32.to_bits(), 1.0f32.to_bits(), nan];
+        assert_eq!(output.total_cmp_digest, digest(expected.into_iter()));
+    }
+
+    #[test]
+    fn test_unique_count_and_min_bucket() {
+        let output = run(SortingInput {
+            keys: vec![i32::MIN, -1, i32::MIN, 5, 5],
+            tie_bucket: -1,
+            float_bits: vec![],
+        });
+        assert_eq!(output.unique_count, 3);
+    }
+}
@@ -0,0 +1,223 @@
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, TryLockError};
+use std::thread;
+
+/// Most workers the core starts (more are clamped, so a mutated input
+/// cannot exhaust the host's thread limit)
+pub const MAX_WORKERS: u32 = 256;
+
+/// Input for thread API core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadApiInput {
+    /// Workers to start (clamped to [`MAX_WORKERS`])
+    pub workers: u32,
+    /// Loop iterations per worker
+    pub iterations: u32,
+}
+
+/// Output for thread API core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadApiOutput {
+    /// Workers after clamping
+    pub workers: u32,
+    /// Workers started on their own thread
+    pub spawned: u32,
+    /// Workers run on the main thread because spawning failed
+    pub inline: u32,
+    /// Spawned threads joined without error
+    pub joined: u32,
+    /// AtomicU32 total (wrapping)
+    pub atomic_total: u32,
+    /// Total accumulated under a Mutex (wrapping)
+    pub mutex_total: u32,
+    /// The main thread's thread-local worker counter
+    pub main_thread_local: u32,
+    /// FNV-1a over the results of single-threaded atomic operations
+    pub atomic_ops_hash: u32,
+    /// `try_lock` on a held Mutex reported `WouldBlock`
+    pub try_lock_blocked: bool,
+    /// `available_parallelism()` succeeded
+    pub parallelism_available: bool,
+    /// The target has 64-bit atomics (`target_has_atomic = "64"`)
+    pub has_atomic_64: bool,
+    /// `thread::current().name()` is set on the main thread
+    pub main_thread_named: bool,
+}
+
+impl Commits for ThreadApiOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.workers),
+            CommitValue::from(self.spawned),
+            CommitValue::from(self.inline),
+            CommitValue::from(self.joined),
+            CommitValue::from(self.atomic_total),
+            CommitValue::from(self.mutex_total),
+            CommitValue::from(self.main_thread_local),
+            CommitValue::from(self.atomic_ops_hash),
+            CommitValue::from(self.try_lock_blocked),
+            CommitValue::from(self.parallelism_available),
+            CommitValue::from(self.has_atomic_64),
+            CommitValue::from(self.main_thread_named),
+        ]
+    }
+}
+
+thread_local! {
+    /// Workers that ran on the current thread
+    static WORKERS_HERE: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Shared state every worker updates
+struct Shared {
+    atomic: AtomicU32,
+    mutex: Mutex<u32>,
+}
+
+/// Worker `index`: adds `index + 1` to both totals `iterations` times
+fn work(shared: &Shared, index: u32, iterations: u32) {
+    WORKERS_HERE.with(|count| count.set(count.get() + 1));
+    let step = index.wrapping_add(1);
+    for _ in 0..iterations {
+        shared.atomic.fetch_add(step, Ordering::Relaxed);
+        let mut total = shared.mutex.lock().unwrap();
+        *total = total.wrapping_add(step);
+    }
+}
+
+const FNV_OFFSET: u32 = 0x811c_9dc5;
+
+fn fnv1a(hash: u32, value: u32) -> u32 {
+    value
+        .to_le_bytes()
+        .iter()
+        .fold(hash, |hash, &byte| (hash ^ byte as u32).wrapping_mul(0x0100_0193))
+}
+
+/// Single-threaded atomic read-modify-write results, hashed
+fn atomic_ops_hash(seed: u32) -> u32 {
+    let atomic = AtomicU32::new(seed);
+    let results = [
+        atomic.fetch_add(u32::MAX, Ordering::SeqCst),
+        atomic.fetch_sub(7, Ordering::SeqCst),
+        atomic.fetch_and(0xf0f0_f0f0, Ordering::AcqRel),
+        atomic.fetch_or(0x0101_0101, Ordering::AcqRel),
+        atomic.fetch_xor(seed, Ordering::Release),
+        atomic.fetch_max(0x8000_0000, Ordering::Acquire),
+        atomic.fetch_min(seed | 1, Ordering::Relaxed),
+        atomic.swap(seed.rotate_left(7), Ordering::SeqCst),
+        atomic.compare_exchange(seed, 1, Ordering::SeqCst, Ordering::Relaxed).unwrap_or_else(|v| v),
+        atomic.compare_exchange(seed.rotate_left(7), 2, Ordering::SeqCst, Ordering::Relaxed).unwrap_or_else(|v| v),
+        atomic.load(Ordering::SeqCst),
+    ];
+    results.iter().fold(FNV_OFFSET, |hash, &value| fnv1a(hash, value))
+}
+
+/// Run the thread API core
+///
+/// Starts `workers` workers with `std::thread::Builder::spawn`, which
+/// returns an error instead of panicking where threads are unsupported.
+/// A worker that could not be spawned runs inline on the main thread, so
+/// the totals are the same either way and only the bookkeeping records
+/// how the runtime behaved. Natively every worker gets a thread; a
+/// single-threaded zkVM runtime is expected to refuse all of them. The
+/// expected (non-equal) relation between the targets is declared as
+/// `relations` in the core's manifest.
+///
+/// Target behaviors:
+/// - `thread::Builder::spawn`, `JoinHandle::join` and `available_parallelism` on a single-threaded runtime
+/// - `Mutex` locking and `try_lock` while held, `thread_local!` storage
+/// - Atomic read-modify-write operations and 64-bit atomic support on a 32-bit target
+pub fn run(input: ThreadApiInput) -> ThreadApiOutput {
+    let workers = input.workers.min(MAX_WORKERS);
+    let shared = Arc::new(Shared {
+        atomic: AtomicU32::new(0),
+        mutex: Mutex::new(0),
+    });
+
+    let mut handles = Vec::new();
+    let mut inline = 0u32;
+    for index in 0..workers {
+        let worker_shared = Arc::clone(&shared);
+        let iterations = input.iterations;
+        let spawned = thread::Builder::new()
+            .name(format!("worker-{}", index))
+            .spawn(move || work(&worker_shared, index, iterations));
+        match spawned {
+            Ok(handle) => handles.push(handle),
+            Err(_) => {
+                work(&shared, index, input.iterations);
+                inline += 1;
+            }
+        }
+    }
+    let spawned = handles.len() as u32;
+    let joined = handles.into_iter().filter_map(|handle| handle.join().ok()).count() as u32;
+
+    let try_lock_blocked = {
+        let _guard = shared.mutex.lock().unwrap();
+        matches!(shared.mutex.try_lock(), Err(TryLockError::WouldBlock))
+    };
+    let mutex_total = *shared.mutex.lock().unwrap();
+
+    ThreadApiOutput {
+        workers,
+        spawned,
+        inline,
+        joined,
+        atomic_total: shared.atomic.load(Ordering::SeqCst),
+        mutex_total,
+        main_thread_local: WORKERS_HERE.with(Cell::get),
+        atomic_ops_hash: atomic_ops_hash(input.iterations),
+        try_lock_blocked,
+        parallelism_available: thread::available_parallelism().is_ok(),
+        has_atomic_64: cfg!(target_has_atomic = "64"),
+        main_thread_named: thread::current().name().is_some(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_native_spawns() {
+        let output = run(ThreadApiInput { workers: 4, iterations: 100 });
+        assert_eq!((output.spawned, output.inline, output.joined), (4, 0, 4));
+        // (1 + 2 + 3 + 4) * 100
+        assert_eq!(output.atomic_total, 1000);
+        assert_eq!(output.mutex_total, 1000);
+        assert!(output.try_lock_blocked);
+        assert!(output.parallelism_available);
+    }
+
+    #[test]
+    fn test_workers_clamped() {
+        let output = run(ThreadApiInput { workers: u32::MAX, iterations: 0 });
+        assert_eq!(output.workers, MAX_WORKERS);
+        assert_eq!(output.atomic_total, 0);
+
+        let output = run(ThreadApiInput { workers: 0, iterations: 5 });
+        assert_eq!((output.workers, output.spawned, output.joined), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_inline_workers_counted_per_thread() {
+        // Spawned workers count on their own thread, inline ones on this one
+        let before = WORKERS_HERE.with(Cell::get);
+        let output = run(ThreadApiInput { workers: 3, iterations: 1 });
+        assert_eq!(output.main_thread_local, before);
+
+        let shared = Shared {
+            atomic: AtomicU32::new(u32::MAX),
+            mutex: Mutex::new(u32::MAX),
+        };
+        work(&shared, 1, 1);
+        assert_eq!(WORKERS_HERE.with(Cell::get), before + 1);
+        assert_eq!(shared.atomic.load(Ordering::SeqCst), 1);
+        assert_eq!(*shared.mutex.lock().unwrap(), 1);
+    }
+}
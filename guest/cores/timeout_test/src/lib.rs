@@ -1,3 +1,4 @@
+use commit_codec::{CommitValue, Commits};
 use serde::{Deserialize, Serialize};
 
 /// Input for timeout test core
@@ -14,6 +15,14 @@ pub struct TimeoutOutput {
     pub completed: u64,
 }
 
+impl Commits for TimeoutOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.completed),
+        ]
+    }
+}
+
 /// Run the timeout test core
 /// 
 /// If iterations == 0, runs an infinite loop (will timeout).
@@ -0,0 +1,149 @@
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+use std::mem::{align_of, size_of};
+
+/// Input for type punning core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypePunningInput {
+    /// Raw guest bytes to reinterpret
+    pub bytes: Vec<u8>,
+    /// Byte offset of the 4-byte window (wraps around `bytes.len()`)
+    pub offset: u32,
+}
+
+/// Output for type punning core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypePunningOutput {
+    /// `u32::from_ne_bytes` of the window
+    pub ne_word: u32,
+    /// The window written to a `#[repr(C)]` union as bytes and read back as u32
+    pub union_word: u32,
+    /// `ptr::read_unaligned::<u32>` at the (possibly unaligned) offset
+    pub unaligned_word: u32,
+    /// `f32::from_bits(word).to_bits()` (NaN payload preservation)
+    pub f32_bits: u32,
+    /// `(f32::from_bits(word) * 1.0).to_bits()` (NaN canonicalization)
+    pub f32_mul_bits: u32,
+    /// `(word as i32) >> 7`, reinterpreted as u32 (sign extension)
+    pub i32_shift: u32,
+    /// size_of::<usize>, align_of::<u64>, align_of::<u128>, align_of::<f64>, one byte each
+    pub layout_signature: u32,
+}
+
+impl Commits for TypePunningOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.ne_word),
+            CommitValue::from(self.union_word),
+            CommitValue::from(self.unaligned_word),
+            CommitValue::from(self.f32_bits),
+            CommitValue::from(self.f32_mul_bits),
+            CommitValue::from(self.i32_shift),
+            CommitValue::from(self.layout_signature),
+        ]
+    }
+}
+
+/// Byte/word pun (the `transmute::<[u8; 4], u32>` equivalent)
+#[repr(C)]
+union Word {
+    bytes: [u8; 4],
+    word: u32,
+}
+
+/// Run the type punning core
+///
+/// Reinterprets guest bytes as integers and floats through safe conversions,
+/// a `#[repr(C)]` union pun and unaligned pointer reads (all within defined behavior).
+/// Native hosts and riscv32 are both little-endian, so the words should
+/// agree; `layout_signature` records the layout assumptions that do differ.
+///
+/// Target behaviors:
+/// - Endianness assumptions (`from_ne_bytes` vs union pun vs pointer reads)
+/// - Unaligned loads on riscv32
+/// - NaN payload handling in float bit casts and arithmetic
+/// - Type sizes and alignments (usize, u64, u128, f64)
+pub fn run(input: TypePunningInput) -> TypePunningOutput {
+    // Pad so a 4-byte window always fits, even for empty input
+    let offset = if input.bytes.is_empty() {
+        0
+    } else {
+        input.offset as usize % input.bytes.len()
+    };
+    let mut padded = input.bytes;
+    padded.extend_from_slice(&[0u8; 4]);
+
+    let mut window = [0u8; 4];
+    for (i, slot) in window.iter_mut().enumerate() {
+        *slot = u8::from_ne_bytes([padded[offset + i]]);
+    }
+
+    let ne_word = u32::from_ne_bytes(window);
+
+    // SAFETY: both fields are 4 bytes and every bit pattern is a valid u32
+    let union_word = unsafe { Word { bytes: window }.word };
+
+    // SAFETY: offset + 4 <= padded.len(), and read_unaligned has no alignment requirement
+    let unaligned_word = unsafe { std::ptr::read_unaligned(padded.as_ptr().add(offset) as *const u32) };
+
+    let float = f32::from_bits(ne_word);
+    let f32_bits = float.to_bits();
+    let f32_mul_bits = (std::hint::black_box(float) * 1.0).to_bits();
+
+    let i32_shift = ((ne_word as i32) >> 7) as u32;
+
+    let layout_signature = size_of::<usize>() as u32
+        | (align_of::<u64>() as u32) << 8
+        | (align_of::<u128>() as u32) << 16
+        | (align_of::<f64>() as u32) << 24;
+
+    TypePunningOutput {
+        ne_word,
+        union_word,
+        unaligned_word,
+        f32_bits,
+        f32_mul_bits,
+        i32_shift,
+        layout_signature,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reinterpretations_agree() {
+        let output = run(TypePunningInput {
+            bytes: vec![0xAA, 0x01, 0x02, 0x03, 0x04],
+            offset: 1,
+        });
+        assert_eq!(output.ne_word, u32::from_ne_bytes([1, 2, 3, 4]));
+        assert_eq!(output.union_word, output.ne_word);
+        assert_eq!(output.unaligned_word, output.ne_word);
+    }
+
+    #[test]
+    fn test_sign_extension() {
+        let output = run(TypePunningInput {
+            bytes: 0x8000_0000u32.to_ne_bytes().to_vec(),
+            offset: 0,
+        });
+        assert_eq!(output.i32_shift, 0xFF00_0000);
+    }
+
+    #[test]
+    fn test_empty_and_wrapping_offset() {
+        let empty = run(TypePunningInput {
+            bytes: vec![],
+            offset: 17,
+        });
+        assert_eq!(empty.ne_word, 0);
+
+        let wrapped = run(TypePunningInput {
+            bytes: vec![1, 2, 3, 4],
+            offset: 4,
+        });
+        assert_eq!(wrapped.ne_word, u32::from_ne_bytes([1, 2, 3, 4]));
+    }
+}
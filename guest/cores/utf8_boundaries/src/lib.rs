@@ -0,0 +1,176 @@
+use commit_codec::{CommitValue, Commits};
+use serde::{Deserialize, Serialize};
+
+/// Input for UTF-8 boundaries core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Utf8BoundariesInput {
+    /// Raw bytes, valid UTF-8 or not
+    pub data: Vec<u8>,
+    /// Byte offsets into the lossy string; each is boundary-checked and
+    /// consecutive pairs are sliced
+    pub offsets: Vec<u32>,
+}
+
+/// Output for UTF-8 boundaries core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Utf8BoundariesOutput {
+    /// `String::from_utf8` succeeded
+    pub utf8_ok: bool,
+    /// `Utf8Error::valid_up_to` (the full length if valid)
+    pub valid_up_to: u32,
+    /// `Utf8Error::error_len` (0 if valid or the input ends mid-sequence)
+    pub error_len: u32,
+    /// Byte length of `from_utf8_lossy`
+    pub lossy_len: u32,
+    /// U+FFFD characters in the lossy string
+    pub replacement_count: u32,
+    /// FNV-1a of the lossy string's bytes
+    pub lossy_hash: u32,
+    /// Characters in the lossy string
+    pub char_count: u32,
+    /// FNV-1a over every `(byte index, char)` of `char_indices`
+    pub char_indices_hash: u32,
+    /// FNV-1a over `is_char_boundary` of every offset
+    pub boundary_hash: u32,
+    /// FNV-1a over the slices between consecutive offsets (or a marker where slicing fails)
+    pub slice_hash: u32,
+    /// Slices that fell on char boundaries
+    pub slices_ok: u32,
+}
+
+impl Commits for Utf8BoundariesOutput {
+    fn commits(&self) -> Vec<CommitValue> {
+        vec![
+            CommitValue::from(self.utf8_ok),
+            CommitValue::from(self.valid_up_to),
+            CommitValue::from(self.error_len),
+            CommitValue::from(self.lossy_len),
+            CommitValue::from(self.replacement_count),
+            CommitValue::from(self.lossy_hash),
+            CommitValue::from(self.char_count),
+            CommitValue::from(self.char_indices_hash),
+            CommitValue::from(self.boundary_hash),
+            CommitValue::from(self.slice_hash),
+            CommitValue::from(self.slices_ok),
+        ]
+    }
+}
+
+/// FNV-1a, continued from `hash`
+fn fnv1a(hash: u32, bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(hash, |hash, &byte| (hash ^ byte as u32).wrapping_mul(0x0100_0193))
+}
+
+const FNV_OFFSET: u32 = 0x811c_9dc5;
+
+/// Run the UTF-8 boundaries core
+///
+/// Validates the bytes with `String::from_utf8`, repairs them with
+/// `from_utf8_lossy`, walks `char_indices`, and slices the repaired string
+/// at the given offsets. Slicing off a char boundary panics, and a panic
+/// aborts a zkVM guest, so the core slices with `str::get` (None instead of
+/// a panic) and checks `is_char_boundary`; native and guest then report
+/// the same outcome without relying on unwinding.
+///
+/// Target behaviors:
+/// - Validation of overlong, surrogate, out-of-range and truncated sequences
+/// - Replacement-character insertion by `from_utf8_lossy`
+/// - Char boundary checks at arbitrary (including out-of-range) offsets
+pub fn run(input: Utf8BoundariesInput) -> Utf8BoundariesOutput {
+    let (utf8_ok, valid_up_to, error_len) = match std::str::from_utf8(&input.data) {
+        Ok(s) => (true, s.len() as u32, 0),
+        Err(e) => (false, e.valid_up_to() as u32, e.error_len().unwrap_or(0) as u32),
+    };
+
+    let lossy = String::from_utf8_lossy(&input.data);
+    let replacement_count = lossy.chars().filter(|&c| c == char::REPLACEMENT_CHARACTER).count() as u32;
+
+    let mut char_count = 0u32;
+    let mut char_indices_hash = FNV_OFFSET;
+    for (index, c) in lossy.char_indices() {
+        char_count += 1;
+        char_indices_hash = fnv1a(char_indices_hash, &(index as u32).to_le_bytes());
+        char_indices_hash = fnv1a(char_indices_hash, &(c as u32).to_le_bytes());
+    }
+
+    let boundary_hash = input.offsets.iter().fold(FNV_OFFSET, |hash, &offset| {
+        fnv1a(hash, &[lossy.is_char_boundary(offset as usize) as u8])
+    });
+
+    let mut slice_hash = FNV_OFFSET;
+    let mut slices_ok = 0u32;
+    for pair in input.offsets.windows(2) {
+        let (start, end) = (pair[0] as usize, pair[1] as usize);
+        match lossy.get(start..end) {
+            Some(slice) => {
+                slices_ok += 1;
+                slice_hash = fnv1a(slice_hash, &(slice.len() as u32).to_le_bytes());
+                slice_hash = fnv1a(slice_hash, slice.as_bytes());
+            }
+            None => slice_hash = fnv1a(slice_hash, &[0xff]),
+        }
+    }
+
+    Utf8BoundariesOutput {
+        utf8_ok,
+        valid_up_to,
+        error_len,
+        lossy_len: lossy.len() as u32,
+        replacement_count,
+        lossy_hash: fnv1a(FNV_OFFSET, lossy.as_bytes()),
+        char_count,
+        char_indices_hash,
+        boundary_hash,
+        slice_hash,
+        slices_ok,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_bytes(data: &[u8], offsets: &[u32]) -> Utf8BoundariesOutput {
+        run(Utf8BoundariesInput {
+            data: data.to_vec(),
+            offsets: offsets.to_vec(),
+        })
+    }
+
+    #[test]
+    fn test_valid_multibyte() {
+        // "aé中🦀": chars start at 0, 1, 3, 6; length 10
+        let output = run_bytes("aé中🦀".as_bytes(), &[0, 1, 3, 6, 10]);
+        assert!(output.utf8_ok);
+        assert_eq!((output.valid_up_to, output.lossy_len, output.char_count), (10, 10, 4));
+        assert_eq!(output.slices_ok, 4);
+
+        let off_boundary = run_bytes("aé中🦀".as_bytes(), &[0, 2, 4, 11]);
+        assert_eq!(off_boundary.slices_ok, 0);
+        assert_ne!(off_boundary.boundary_hash, output.boundary_hash);
+    }
+
+    #[test]
+    fn test_invalid_sequences() {
+        // Overlong NUL: invalid at 1, error length 1, each byte replaced
+        let output = run_bytes(&[b'a', 0xc0, 0x80], &[]);
+        assert!(!output.utf8_ok);
+        assert_eq!((output.valid_up_to, output.error_len), (1, 1));
+        assert_eq!(output.replacement_count, 2);
+
+        // Truncated 3-byte sequence at the end: error_len is None
+        let output = run_bytes(&[b'a', 0xe2, 0x82], &[]);
+        assert_eq!((output.valid_up_to, output.error_len), (1, 0));
+        assert_eq!(output.replacement_count, 1);
+        assert_eq!(output.lossy_len, 4);
+    }
+
+    #[test]
+    fn test_out_of_range_offsets() {
+        let output = run_bytes(b"abc", &[2, u32::MAX, 1, 3]);
+        // 2..MAX and MAX..1 fail; 1..3 succeeds
+        assert_eq!(output.slices_ok, 1);
+    }
+}
@@ -0,0 +1,167 @@
+//! Chunked input delivery shared by the SP1 runner and guest adapters
+//!
+//! An input written with `SP1Stdin::write(&Vec<u8>)` is bincode-serialized
+//! on the host and deserialized again in the guest, which then holds the
+//! raw stdin buffer and the decoded copy at once. For inputs in the tens of
+//! MB that doubles guest memory at the worst moment. In chunked mode the
+//! runner writes a small [`Header`] the usual way, then the input as raw
+//! chunks of at most [`CHUNK_SIZE`] bytes (`SP1Stdin::write_slice`); the
+//! guest reads the header and [`read_chunks`] copies each chunk
+//! (`sp1_zkvm::io::read_vec`) into one buffer allocated up front.
+//!
+//! A header starts with [`MAGIC`], a NUL byte no JSON input starts with, so
+//! an adapter tells the modes apart from the first `Vec<u8>` it reads. The
+//! crate has no SP1 dependency: the guest passes its read function in.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::fmt;
+
+/// First bytes of a chunked-input header
+pub const MAGIC: &[u8; 8] = b"\0chunked";
+
+/// Largest chunk the runner writes
+pub const CHUNK_SIZE: usize = 1 << 20;
+
+/// Header bytes: [`MAGIC`], the total length (u64 LE), the chunk count (u32 LE)
+const HEADER_LEN: usize = MAGIC.len() + 8 + 4;
+
+/// What the guest reads before the chunks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    /// Input length, over all chunks
+    pub total_len: u64,
+    /// Number of chunks that follow
+    pub chunks: u32,
+}
+
+impl Header {
+    /// Header of an input of `len` bytes split into [`CHUNK_SIZE`] chunks
+    pub fn for_len(len: usize) -> Header {
+        Header {
+            total_len: len as u64,
+            chunks: len.div_ceil(CHUNK_SIZE) as u32,
+        }
+    }
+
+    /// Bytes the runner writes (as a `Vec<u8>`) before the chunks
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&self.total_len.to_le_bytes());
+        bytes.extend_from_slice(&self.chunks.to_le_bytes());
+        bytes
+    }
+
+    /// The header `bytes` hold (None if they are not one, e.g. JSON input)
+    pub fn decode(bytes: &[u8]) -> Option<Header> {
+        if bytes.len() != HEADER_LEN || !bytes.starts_with(MAGIC) {
+            return None;
+        }
+        let (total_len, chunks) = bytes[MAGIC.len()..].split_at(8);
+        Some(Header {
+            total_len: u64::from_le_bytes(total_len.try_into().ok()?),
+            chunks: u32::from_le_bytes(chunks.try_into().ok()?),
+        })
+    }
+}
+
+/// The chunks the runner writes for `input`, in order
+pub fn chunks(input: &[u8]) -> core::slice::Chunks<'_, u8> {
+    input.chunks(CHUNK_SIZE)
+}
+
+/// A chunked input that does not match its header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChunkError {
+    /// The chunks add up to a different length than the header's
+    LengthMismatch { expected: u64, actual: u64 },
+    /// A chunk longer than [`CHUNK_SIZE`]
+    OversizedChunk { index: u32, len: usize },
+}
+
+impl fmt::Display for ChunkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChunkError::LengthMismatch { expected, actual } => {
+                write!(f, "chunks hold {} bytes, header says {}", actual, expected)
+            }
+            ChunkError::OversizedChunk { index, len } => {
+                write!(f, "chunk {} is {} bytes, over the {} byte limit", index, len, CHUNK_SIZE)
+            }
+        }
+    }
+}
+
+/// Read the chunks after `header` with `read_chunk` into one buffer
+///
+/// The buffer is allocated once, at the header's length, so reading never
+/// reallocates. Only the chunk being copied exists twice.
+pub fn read_chunks(header: &Header, mut read_chunk: impl FnMut() -> Vec<u8>) -> Result<Vec<u8>, ChunkError> {
+    let mut input = Vec::with_capacity(header.total_len as usize);
+    for index in 0..header.chunks {
+        let chunk = read_chunk();
+        if chunk.len() > CHUNK_SIZE {
+            return Err(ChunkError::OversizedChunk { index, len: chunk.len() });
+        }
+        input.extend_from_slice(&chunk);
+        if input.len() as u64 > header.total_len {
+            break;
+        }
+    }
+    if input.len() as u64 != header.total_len {
+        return Err(ChunkError::LengthMismatch {
+            expected: header.total_len,
+            actual: input.len() as u64,
+        });
+    }
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_round_trip() {
+        let input: Vec<u8> = (0..CHUNK_SIZE * 2 + 5).map(|i| i as u8).collect();
+        let header = Header::for_len(input.len());
+        assert_eq!(header.chunks, 3);
+        assert_eq!(Header::decode(&header.encode()), Some(header));
+
+        let mut written = chunks(&input).map(<[u8]>::to_vec);
+        assert_eq!(read_chunks(&header, || written.next().unwrap()), Ok(input));
+
+        let empty = Header::for_len(0);
+        assert_eq!((empty.chunks, read_chunks(&empty, || unreachable!())), (0, Ok(Vec::new())));
+    }
+
+    #[test]
+    fn test_not_a_header() {
+        assert_eq!(Header::decode(br#"{"data": [1, 2, 3, 4, 5, 6, 7, 8, 9]}"#), None);
+        assert_eq!(Header::decode(MAGIC), None);
+    }
+
+    #[test]
+    fn test_mismatched_chunks() {
+        let header = Header { total_len: 4, chunks: 2 };
+        assert_eq!(
+            read_chunks(&header, || vec![1, 2, 3]),
+            Err(ChunkError::LengthMismatch { expected: 4, actual: 6 })
+        );
+        let header = Header { total_len: 8, chunks: 1 };
+        assert_eq!(
+            read_chunks(&header, || vec![1]),
+            Err(ChunkError::LengthMismatch { expected: 8, actual: 1 })
+        );
+        let header = Header { total_len: CHUNK_SIZE as u64 + 1, chunks: 1 };
+        assert_eq!(
+            read_chunks(&header, || vec![0; CHUNK_SIZE + 1]),
+            Err(ChunkError::OversizedChunk { index: 0, len: CHUNK_SIZE + 1 })
+        );
+    }
+}
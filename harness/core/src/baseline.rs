@@ -0,0 +1,335 @@
+//! Timing and cycle baselines with statistical anomaly detection
+//!
+//! Keeps a rolling window of `elapsed_ms` (native, SP1) and SP1 cycle
+//! counts per core and input-size bucket in `artifacts/baselines.json`.
+//! A run far above its baseline (more than [`ANOMALY_SIGMA`] standard
+//! deviations) is a *performance divergence*: logged to
+//! `artifacts/perf_divergences.csv`, separately from correctness
+//! divergences in `summary.csv`.
+//!
+//! Anomalous samples stay out of the window, so one slow run can't drag the
+//! baseline up. [`REBASELINE_AFTER`] anomalies in a row are a level shift
+//! instead (a slower machine, a new SP1 version): they replace the window,
+//! and later runs are judged against the new level.
+
+use anyhow::{Context, Result};
+use rust_eq_oracle::{Diff, RunResult, Status};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Standard deviations above the mean that make a run anomalous
+pub const ANOMALY_SIGMA: f64 = 5.0;
+
+/// Samples kept per metric (older samples roll out)
+pub const WINDOW: usize = 50;
+
+/// Samples required before a metric is checked
+pub const MIN_SAMPLES: usize = 5;
+
+/// Consecutive anomalies of a metric after which they become its baseline
+pub const REBASELINE_AFTER: usize = 5;
+
+const BASELINES_PATH: &str = "artifacts/baselines.json";
+const PERF_CSV_PATH: &str = "artifacts/perf_divergences.csv";
+
+/// Rolling window of one metric
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RollingStats {
+    samples: Vec<f64>,
+    /// Anomalous values seen in a row since the last normal one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    streak: Vec<f64>,
+}
+
+impl RollingStats {
+    pub fn push(&mut self, value: f64) {
+        if self.samples.len() == WINDOW {
+            self.samples.remove(0);
+        }
+        self.samples.push(value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.samples.iter().sum::<f64>() / self.samples.len().max(1) as f64
+    }
+
+    /// Sample standard deviation (0 with fewer than two samples)
+    pub fn stddev(&self) -> f64 {
+        if self.samples.len() < 2 {
+            return 0.0;
+        }
+        let mean = self.mean();
+        let variance = self.samples.iter().map(|v| (v - mean).powi(2)).sum::<f64>()
+            / (self.samples.len() - 1) as f64;
+        variance.sqrt()
+    }
+
+    /// How many standard deviations `value` lies above the mean, if the
+    /// window is full enough to judge
+    ///
+    /// The deviation is floored at 1% of the mean (and 1 unit) so perfectly
+    /// stable baselines don't flag every bit of jitter.
+    pub fn sigma_above(&self, value: f64) -> Option<f64> {
+        if self.samples.len() < MIN_SAMPLES {
+            return None;
+        }
+        let mean = self.mean();
+        let deviation = self.stddev().max(mean * 0.01).max(1.0);
+        Some((value - mean) / deviation)
+    }
+}
+
+/// Baselines of one core at one input size
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub native_ms: RollingStats,
+    pub sp1_ms: RollingStats,
+    pub sp1_cycles: RollingStats,
+}
+
+/// All baselines, keyed by `<core>/<size bucket>`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Baselines {
+    pub entries: BTreeMap<String, BaselineEntry>,
+}
+
+/// A run that was statistically slower than its baseline
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerfAnomaly {
+    pub metric: &'static str,
+    pub value: f64,
+    pub mean: f64,
+    pub stddev: f64,
+    pub sigma: f64,
+}
+
+impl Baselines {
+    pub fn load() -> Result<Self> {
+        match fs::read(BASELINES_PATH) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .with_context(|| format!("Failed to parse {}", BASELINES_PATH)),
+            Err(_) => Ok(Baselines::default()),
+        }
+    }
+
+    /// Write via a synced temp file and rename, so readers (and a crash)
+    /// never leave a partial file
+    pub fn save(&self, run_id: &str) -> Result<()> {
+        use std::io::Write;
+
+        let tmp = PathBuf::from(format!("{}.{}.tmp", BASELINES_PATH, run_id));
+        let mut file = fs::File::create(&tmp).with_context(|| format!("Failed to create {}", tmp.display()))?;
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp, BASELINES_PATH).with_context(|| format!("Failed to write {}", BASELINES_PATH))?;
+        Ok(())
+    }
+
+    /// Check a run against its baseline, then fold it in
+    ///
+    /// Anomalous samples are held back until [`REBASELINE_AFTER`] of them in
+    /// a row replace the window.
+    pub fn observe(&mut self, key: &str, native: &RunResult, sp1: &RunResult) -> Vec<PerfAnomaly> {
+        let entry = self.entries.entry(key.to_string()).or_default();
+        let metrics = [
+            ("native_ms", &mut entry.native_ms, Some(native.elapsed_ms as f64)),
+            ("sp1_ms", &mut entry.sp1_ms, Some(sp1.elapsed_ms as f64)),
+            ("sp1_cycles", &mut entry.sp1_cycles, sp1.meta["cycles"].as_f64()),
+        ];
+
+        let mut anomalies = Vec::new();
+        for (metric, stats, value) in metrics {
+            let Some(value) = value else { continue };
+            match stats.sigma_above(value) {
+                Some(sigma) if sigma > ANOMALY_SIGMA => {
+                    anomalies.push(PerfAnomaly {
+                        metric,
+                        value,
+                        mean: stats.mean(),
+                        stddev: stats.stddev(),
+                        sigma,
+                    });
+                    stats.streak.push(value);
+                    if stats.streak.len() >= REBASELINE_AFTER {
+                        stats.samples = std::mem::take(&mut stats.streak);
+                    }
+                }
+                _ => {
+                    stats.streak.clear();
+                    stats.push(value);
+                }
+            }
+        }
+        anomalies
+    }
+}
+
+/// Power-of-two bucket of the input size, e.g. "1024B"
+pub fn size_bucket(input_path: &Path) -> String {
+    let len = fs::metadata(input_path).map(|m| m.len()).unwrap_or(0);
+    format!("{}B", len.max(1).next_power_of_two())
+}
+
+/// Check a run's timings and cycles against the baselines and log anomalies
+///
+/// Only passing runs with both sides OK are measured; anything else says
+/// nothing about performance.
+pub fn check_performance(
+    run_id: &str,
+    core_name: &str,
+    input_path: &Path,
+    native: &RunResult,
+    sp1: &RunResult,
+    diff: &Diff,
+) -> Result<Vec<PerfAnomaly>> {
+    if !diff.equal || native.status != Status::Ok || sp1.status != Status::Ok {
+        return Ok(vec![]);
+    }
+
+    let bucket = size_bucket(input_path);
+    let mut baselines = Baselines::load()?;
+    let anomalies = baselines.observe(&format!("{}/{}", core_name, bucket), native, sp1);
+    baselines.save(run_id)?;
+
+    if !anomalies.is_empty() {
+        log_anomalies(run_id, core_name, &bucket, input_path, &anomalies)?;
+    }
+    for anomaly in &anomalies {
+        println!(
+            "   🐢 Performance divergence: {} = {:.0} ({:.1}σ above mean {:.1})",
+            anomaly.metric, anomaly.value, anomaly.sigma, anomaly.mean
+        );
+    }
+
+    Ok(anomalies)
+}
+
+fn log_anomalies(
+    run_id: &str,
+    core_name: &str,
+    bucket: &str,
+    input_path: &Path,
+    anomalies: &[PerfAnomaly],
+) -> Result<()> {
+    use std::io::Write;
+
+    // Only the process that creates the file writes the header
+    if let Ok(mut file) = fs::OpenOptions::new().write(true).create_new(true).open(PERF_CSV_PATH) {
+        let mut header = csv::Writer::from_writer(Vec::new());
+        header.write_record([
+            "run_id", "core", "size_bucket", "input", "metric", "value", "mean", "stddev", "sigma",
+        ])?;
+        file.write_all(&header.into_inner()?)?;
+    }
+
+    // All rows go out in one O_APPEND write, so concurrent runs don't interleave
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for anomaly in anomalies {
+        writer.write_record([
+            run_id,
+            core_name,
+            bucket,
+            &input_path.display().to_string(),
+            anomaly.metric,
+            &format!("{:.0}", anomaly.value),
+            &format!("{:.2}", anomaly.mean),
+            &format!("{:.2}", anomaly.stddev),
+            &format!("{:.2}", anomaly.sigma),
+        ])?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .open(PERF_CSV_PATH)
+        .with_context(|| format!("Failed to open {}", PERF_CSV_PATH))?;
+    file.write_all(&writer.into_inner()?)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ok_result(elapsed_ms: u128, cycles: u64) -> RunResult {
+        RunResult {
+            status: Status::Ok,
+            elapsed_ms,
+            commits: vec![],
+            meta: serde_json::json!({ "cycles": cycles }),
+        }
+    }
+
+    #[test]
+    fn test_rolling_window() {
+        let mut stats = RollingStats::default();
+        for v in 0..(WINDOW + 10) {
+            stats.push(v as f64);
+        }
+        assert_eq!(stats.len(), WINDOW);
+        assert_eq!(stats.mean(), (10..WINDOW + 10).sum::<usize>() as f64 / WINDOW as f64);
+    }
+
+    #[test]
+    fn test_no_verdict_before_min_samples() {
+        let mut stats = RollingStats::default();
+        stats.push(10.0);
+        assert_eq!(stats.sigma_above(1000.0), None);
+    }
+
+    #[test]
+    fn test_slow_run_flagged_and_not_folded_in() {
+        let mut baselines = Baselines::default();
+        for ms in [100, 102, 98, 101, 99, 100] {
+            assert!(baselines.observe("fib/16B", &ok_result(1, 0), &ok_result(ms, 5000)).is_empty());
+        }
+
+        let anomalies = baselines.observe("fib/16B", &ok_result(1, 0), &ok_result(400, 5000));
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].metric, "sp1_ms");
+        assert_eq!(baselines.entries["fib/16B"].sp1_ms.len(), 6);
+    }
+
+    #[test]
+    fn test_level_shift_rebaselines() {
+        let mut baselines = Baselines::default();
+        for ms in [100, 102, 98, 101, 99, 100] {
+            baselines.observe("fib/16B", &ok_result(1, 0), &ok_result(ms, 5000));
+        }
+        // A normal run between anomalies resets the streak
+        for _ in 0..REBASELINE_AFTER - 1 {
+            assert_eq!(baselines.observe("fib/16B", &ok_result(1, 0), &ok_result(400, 5000)).len(), 1);
+        }
+        assert!(baselines.observe("fib/16B", &ok_result(1, 0), &ok_result(100, 5000)).is_empty());
+        assert_eq!(baselines.entries["fib/16B"].sp1_ms.len(), 7);
+
+        // Enough anomalies in a row become the new baseline
+        for _ in 0..REBASELINE_AFTER {
+            assert_eq!(baselines.observe("fib/16B", &ok_result(1, 0), &ok_result(400, 5000)).len(), 1);
+        }
+        let sp1_ms = &baselines.entries["fib/16B"].sp1_ms;
+        assert_eq!((sp1_ms.len(), sp1_ms.mean()), (REBASELINE_AFTER, 400.0));
+        assert!(baselines.observe("fib/16B", &ok_result(1, 0), &ok_result(402, 5000)).is_empty());
+        // Only the metric that shifted was replaced
+        assert_eq!(baselines.entries["fib/16B"].sp1_cycles.len(), 6 + 2 * REBASELINE_AFTER + 1);
+    }
+
+    #[test]
+    fn test_stable_baseline_tolerates_jitter() {
+        let mut baselines = Baselines::default();
+        for _ in 0..10 {
+            baselines.observe("fib/16B", &ok_result(2, 0), &ok_result(100, 5000));
+        }
+        // 1ms of jitter on a 2ms native run must not be a 5σ event
+        assert!(baselines.observe("fib/16B", &ok_result(3, 0), &ok_result(100, 5000)).is_empty());
+    }
+}
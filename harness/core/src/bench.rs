@@ -0,0 +1,335 @@
+//! Proof size and proving/verification time benchmarks
+//!
+//! `harness bench-prove` proves a representative input per core and appends a
+//! [`BenchRecord`] to `artifacts/bench/prove.csv`. Each row carries the SP1
+//! and rustc versions, so the file tracks proving cost across toolchain
+//! upgrades; [`find_regressions`] compares a new row with the previous one
+//! for the same core, target and proof mode.
+//!
+//! Rows also record the number of shards the proof covers and the input's
+//! size. Shards drive proving cost, so an input that needs far more shards
+//! per KiB than the core's other inputs is a cheap way to make a prover do
+//! a lot of work; [`find_shard_explosion`] flags it.
+
+use anyhow::{Context, Result};
+use rust_eq_oracle::{RunResult, Status};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+const BENCH_CSV_PATH: &str = "artifacts/bench/prove.csv";
+
+/// Relative increase over the previous benchmark that counts as a regression
+pub const REGRESSION_THRESHOLD: f64 = 0.2;
+
+/// Shards per input KiB, relative to the core's median, that count as an explosion
+pub const SHARD_EXPLOSION_FACTOR: f64 = 4.0;
+
+/// Which SP1 proof to generate (mirrors `sp1-runner --proof-mode`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofMode {
+    Core,
+    Compressed,
+    Groth16,
+    Plonk,
+}
+
+impl ProofMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProofMode::Core => "core",
+            ProofMode::Compressed => "compressed",
+            ProofMode::Groth16 => "groth16",
+            ProofMode::Plonk => "plonk",
+        }
+    }
+}
+
+impl FromStr for ProofMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "core" => Ok(ProofMode::Core),
+            "compressed" => Ok(ProofMode::Compressed),
+            "groth16" => Ok(ProofMode::Groth16),
+            "plonk" => Ok(ProofMode::Plonk),
+            _ => Err(format!("unknown proof mode '{}' (core, compressed, groth16, plonk)", s)),
+        }
+    }
+}
+
+/// One benchmark row in `artifacts/bench/prove.csv`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchRecord {
+    pub timestamp: String,
+    pub core: String,
+    pub input: String,
+    pub zkvm_target: String,
+    pub sp1_version: String,
+    pub rustc_version: String,
+    pub elf_sha256: Option<String>,
+    pub proof_mode: String,
+    pub status: Status,
+    pub cycles: Option<u64>,
+    pub setup_ms: Option<u64>,
+    pub prove_ms: Option<u64>,
+    pub verify_ms: Option<u64>,
+    pub proof_size_bytes: Option<u64>,
+    /// Input file size, sidecars included
+    #[serde(default)]
+    pub input_bytes: Option<u64>,
+    /// Shards the proof covers (estimated for compressed, Groth16 and Plonk proofs)
+    #[serde(default)]
+    pub shards: Option<u64>,
+    /// Campaign the benchmark ran under (`harness --campaign`)
+    #[serde(default)]
+    pub campaign: Option<String>,
+}
+
+impl BenchRecord {
+    /// Build a row from an `sp1-runner --prove` result
+    pub fn from_result(
+        core_name: &str,
+        input_path: &Path,
+        proof_mode: ProofMode,
+        result: &RunResult,
+        env: &crate::ReproEnv,
+    ) -> Self {
+        let meta = |key: &str| result.meta[key].as_u64();
+        BenchRecord {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            core: core_name.to_string(),
+            input: input_path.display().to_string(),
+            zkvm_target: env.zkvm_target.clone(),
+            sp1_version: env.sp1_version.clone(),
+            rustc_version: env.rustc_version.clone(),
+            elf_sha256: env.elf_sha256.clone(),
+            proof_mode: proof_mode.as_str().to_string(),
+            status: result.status,
+            cycles: meta("cycles"),
+            setup_ms: meta("setup_ms"),
+            prove_ms: meta("prove_ms"),
+            verify_ms: meta("verify_ms"),
+            proof_size_bytes: meta("proof_size_bytes"),
+            input_bytes: Some(crate::cost_model::input_bytes(core_name, input_path)),
+            shards: meta("shards"),
+            campaign: crate::campaign::current_id(),
+        }
+    }
+
+    /// The (metric, value) pairs checked for regressions
+    fn metrics(&self) -> [(&'static str, Option<u64>); 4] {
+        [
+            ("prove_ms", self.prove_ms),
+            ("verify_ms", self.verify_ms),
+            ("proof_size_bytes", self.proof_size_bytes),
+            ("shards", self.shards),
+        ]
+    }
+
+    /// Shards per KiB of input; inputs under 1 KiB count as 1 KiB, so tiny
+    /// inputs do not look explosive
+    fn shards_per_kib(&self) -> Option<f64> {
+        let kib = (self.input_bytes? as f64 / 1024.0).max(1.0);
+        Some(self.shards? as f64 / kib)
+    }
+}
+
+/// Append a row to `artifacts/bench/prove.csv` (header on first write)
+pub fn append_bench_record(record: &BenchRecord) -> Result<()> {
+    let path = Path::new(BENCH_CSV_PATH);
+    fs::create_dir_all(path.parent().unwrap())?;
+    let needs_header = !path.exists();
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", BENCH_CSV_PATH))?;
+    let mut writer = csv::WriterBuilder::new().has_headers(needs_header).from_writer(file);
+    writer.serialize(record)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Every row in `artifacts/bench/prove.csv` (empty if there is none yet)
+pub fn load_bench_records() -> Result<Vec<BenchRecord>> {
+    if !Path::new(BENCH_CSV_PATH).exists() {
+        return Ok(Vec::new());
+    }
+    let mut reader = csv::Reader::from_path(BENCH_CSV_PATH)?;
+    reader
+        .deserialize()
+        .collect::<Result<Vec<BenchRecord>, _>>()
+        .with_context(|| format!("Failed to parse {}", BENCH_CSV_PATH))
+}
+
+/// A metric that grew past [`REGRESSION_THRESHOLD`] since the previous run
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchRegression {
+    pub metric: &'static str,
+    pub previous: u64,
+    pub current: u64,
+    /// SP1 version of the run compared against
+    pub previous_sp1_version: String,
+}
+
+impl BenchRegression {
+    /// Relative increase (0.5 = 50% worse)
+    pub fn increase(&self) -> f64 {
+        self.current as f64 / self.previous.max(1) as f64 - 1.0
+    }
+}
+
+/// Compare `current` with the latest successful earlier row for the same
+/// core, input, target and proof mode
+pub fn find_regressions(history: &[BenchRecord], current: &BenchRecord) -> Vec<BenchRegression> {
+    let Some(previous) = history.iter().rev().find(|r| {
+        r.status == Status::Ok
+            && r.core == current.core
+            && r.input == current.input
+            && r.zkvm_target == current.zkvm_target
+            && r.proof_mode == current.proof_mode
+    }) else {
+        return Vec::new();
+    };
+    if current.status != Status::Ok {
+        return Vec::new();
+    }
+
+    previous
+        .metrics()
+        .into_iter()
+        .zip(current.metrics())
+        .filter_map(|((metric, previous_value), (_, current_value))| {
+            let (previous_value, current_value) = (previous_value?, current_value?);
+            (current_value as f64 > previous_value as f64 * (1.0 + REGRESSION_THRESHOLD)).then(|| BenchRegression {
+                metric,
+                previous: previous_value,
+                current: current_value,
+                previous_sp1_version: previous.sp1_version.clone(),
+            })
+        })
+        .collect()
+}
+
+/// An input whose shard count is out of proportion to its size
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShardExplosion {
+    pub shards: u64,
+    pub input_bytes: u64,
+    pub shards_per_kib: f64,
+    /// Median shards per KiB of the core's earlier rows
+    pub baseline_per_kib: f64,
+}
+
+impl ShardExplosion {
+    /// How many times the baseline rate this input needs
+    pub fn factor(&self) -> f64 {
+        self.shards_per_kib / self.baseline_per_kib
+    }
+}
+
+/// Compare `current`'s shards per input KiB with the median of the earlier
+/// successful rows for the same core, target and proof mode
+///
+/// None without a baseline, or if `current` stays under
+/// [`SHARD_EXPLOSION_FACTOR`] times it.
+pub fn find_shard_explosion(history: &[BenchRecord], current: &BenchRecord) -> Option<ShardExplosion> {
+    if current.status != Status::Ok {
+        return None;
+    }
+    let shards_per_kib = current.shards_per_kib()?;
+    let mut rates: Vec<f64> = history
+        .iter()
+        .filter(|r| {
+            r.status == Status::Ok
+                && r.core == current.core
+                && r.zkvm_target == current.zkvm_target
+                && r.proof_mode == current.proof_mode
+        })
+        .filter_map(BenchRecord::shards_per_kib)
+        .collect();
+    if rates.is_empty() {
+        return None;
+    }
+    rates.sort_by(f64::total_cmp);
+    let baseline_per_kib = rates[rates.len() / 2];
+    (shards_per_kib > baseline_per_kib * SHARD_EXPLOSION_FACTOR).then(|| ShardExplosion {
+        shards: current.shards.unwrap_or_default(),
+        input_bytes: current.input_bytes.unwrap_or_default(),
+        shards_per_kib,
+        baseline_per_kib,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(sp1_version: &str, prove_ms: u64, proof_size_bytes: u64) -> BenchRecord {
+        BenchRecord {
+            timestamp: String::new(),
+            core: "fib".to_string(),
+            input: "inputs/fib_24.json".to_string(),
+            zkvm_target: crate::ZKVM_TARGET.to_string(),
+            sp1_version: sp1_version.to_string(),
+            rustc_version: String::new(),
+            elf_sha256: None,
+            proof_mode: "compressed".to_string(),
+            status: Status::Ok,
+            cycles: Some(1000),
+            setup_ms: Some(10),
+            prove_ms: Some(prove_ms),
+            verify_ms: Some(50),
+            proof_size_bytes: Some(proof_size_bytes),
+            input_bytes: Some(16),
+            shards: Some(1),
+            campaign: None,
+        }
+    }
+
+    #[test]
+    fn test_regression_against_latest_ok_run() {
+        let mut failed = record("5.2.3", 1, 1);
+        failed.status = Status::InfraError;
+        let history = vec![record("5.2.1", 1000, 100), record("5.2.2", 10_000, 100), failed];
+
+        // Compared with 5.2.2 (the failed run is skipped): proving +10% is noise, proofs +50% is not
+        let regressions = find_regressions(&history, &record("5.2.3", 11_000, 150));
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].metric, "proof_size_bytes");
+        assert_eq!(regressions[0].previous_sp1_version, "5.2.2");
+        assert!((regressions[0].increase() - 0.5).abs() < 1e-9);
+
+        let mut groth16 = record("5.2.3", 100_000, 100_000);
+        groth16.proof_mode = "groth16".to_string();
+        assert!(find_regressions(&history, &groth16).is_empty());
+    }
+
+    #[test]
+    fn test_shard_explosion_relative_to_input_size() {
+        let sized = |input_bytes: u64, shards: u64| BenchRecord {
+            input_bytes: Some(input_bytes),
+            shards: Some(shards),
+            ..record("5.2.3", 1000, 100)
+        };
+        // 1, 2 and 4 shards per KiB: the median is 2
+        let history = vec![sized(16, 1), sized(4096, 8), sized(8192, 32)];
+
+        // A 1 MiB input with 1024 shards keeps pace with its size
+        assert!(find_shard_explosion(&history, &sized(1 << 20, 1024)).is_none());
+        // A tiny input counts as 1 KiB: 8 shards is at the limit, 9 past it
+        assert!(find_shard_explosion(&history, &sized(10, 8)).is_none());
+        let explosion = find_shard_explosion(&history, &sized(10, 9)).unwrap();
+        assert_eq!((explosion.shards, explosion.input_bytes), (9, 10));
+        assert!((explosion.factor() - 4.5).abs() < 1e-9);
+
+        // No baseline for another proof mode, and none without a shard count
+        let mut core = sized(10, 100);
+        core.proof_mode = "core".to_string();
+        assert!(find_shard_explosion(&history, &core).is_none());
+        assert!(find_shard_explosion(&history, &BenchRecord { shards: None, ..sized(10, 9) }).is_none());
+    }
+}
@@ -0,0 +1,164 @@
+//! Threshold bisection over parametric mutations
+//!
+//! Strategies whose inputs are a function of one number (`size`, `n`,
+//! `iterations`; see `MutationStrategy::bisect_param`) only probe a few
+//! values of it. When two neighbouring probed values disagree (one passes,
+//! the other diverges), the harness binary-searches the values in between
+//! to find the exact pair where the behavior flips, and records it in the
+//! campaign's `bisect.json`.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Default probes spent on one transition (enough for a 2^24-wide gap)
+pub const DEFAULT_MAX_PROBES: usize = 24;
+
+/// Outcome of running the input for one parameter value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Probe {
+    pub value: u64,
+    pub passed: bool,
+    /// Oracle reason if it diverged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Where a parametric strategy flips between passing and diverging
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Threshold {
+    pub strategy: String,
+    pub param: String,
+    /// Closest passing value to the flip
+    pub passing: u64,
+    /// Closest diverging value to the flip
+    pub failing: u64,
+    /// Whether `passing` and `failing` are adjacent (the threshold is exact)
+    pub exact: bool,
+    /// Oracle reason at `failing`
+    pub failing_reason: Option<String>,
+    /// Values run during the search, in order
+    pub probes: Vec<Probe>,
+}
+
+impl std::fmt::Display for Threshold {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {}={} passes, {}={} diverges",
+            self.strategy, self.param, self.passing, self.param, self.failing
+        )?;
+        if !self.exact {
+            write!(f, " (not narrowed to adjacent values)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Neighbouring probed values whose outcomes differ, as (passing, failing)
+///
+/// Values probed more than once count by their first outcome.
+pub fn transitions(observed: &[Probe]) -> Vec<(Probe, Probe)> {
+    let mut sorted: Vec<&Probe> = observed.iter().collect();
+    sorted.sort_by_key(|probe| probe.value);
+    sorted.dedup_by_key(|probe| probe.value);
+    sorted
+        .windows(2)
+        .filter(|pair| pair[0].passed != pair[1].passed)
+        .map(|pair| {
+            let (a, b) = (pair[0].clone(), pair[1].clone());
+            if a.passed {
+                (a, b)
+            } else {
+                (b, a)
+            }
+        })
+        .collect()
+}
+
+/// Binary-search between a passing and a failing value
+///
+/// `probe` runs one value; it returns None when the run says nothing about
+/// the input (an infrastructure failure), which ends the search early.
+/// Stops after `max_probes` runs even if the gap is not closed yet.
+pub fn bisect(
+    strategy: &str,
+    param: &str,
+    passing: &Probe,
+    failing: &Probe,
+    max_probes: usize,
+    mut probe: impl FnMut(u64) -> Result<Option<Probe>>,
+) -> Result<Threshold> {
+    let (mut pass, mut fail) = (passing.value, failing.value);
+    let mut failing_reason = failing.reason.clone();
+    let mut probes = Vec::new();
+
+    while pass.abs_diff(fail) > 1 && probes.len() < max_probes {
+        let mid = pass.min(fail) + pass.abs_diff(fail) / 2;
+        let Some(result) = probe(mid)? else {
+            break;
+        };
+        if result.passed {
+            pass = mid;
+        } else {
+            fail = mid;
+            failing_reason = result.reason.clone();
+        }
+        probes.push(result);
+    }
+
+    Ok(Threshold {
+        strategy: strategy.to_string(),
+        param: param.to_string(),
+        passing: pass,
+        failing: fail,
+        exact: pass.abs_diff(fail) <= 1,
+        failing_reason,
+        probes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(value: u64, passed: bool) -> Probe {
+        Probe {
+            value,
+            passed,
+            reason: (!passed).then(|| format!("diverged at {}", value)),
+        }
+    }
+
+    #[test]
+    fn test_transitions() {
+        let observed = [probe(1024, false), probe(0, true), probe(512, true), probe(2048, false), probe(4096, true)];
+        let found = transitions(&observed);
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].0.value, found[0].1.value), (512, 1024));
+        assert_eq!((found[1].0.value, found[1].1.value), (4096, 2048));
+        assert!(transitions(&[probe(1, true), probe(2, true)]).is_empty());
+    }
+
+    #[test]
+    fn test_bisect() {
+        // Diverges from 700 upwards
+        let run = |value: u64| Ok(Some(probe(value, value < 700)));
+        let threshold = bisect("length_bias", "size", &probe(512, true), &probe(1024, false), 24, run).unwrap();
+        assert_eq!((threshold.passing, threshold.failing), (699, 700));
+        assert!(threshold.exact);
+        assert_eq!(threshold.failing_reason.as_deref(), Some("diverged at 700"));
+        assert_eq!(threshold.probes.len(), 9);
+        assert_eq!(threshold.to_string(), "length_bias: size=699 passes, size=700 diverges");
+
+        // Failing below the passing value, with a probe budget
+        let run = |value: u64| Ok(Some(probe(value, value >= 100)));
+        let threshold = bisect("fibonacci_values", "n", &probe(1000, true), &probe(0, false), 3, run).unwrap();
+        assert_eq!(threshold.probes.len(), 3);
+        assert!(!threshold.exact);
+        assert!(threshold.failing < 100 && threshold.passing >= 100);
+
+        // An inconclusive probe stops the search
+        let threshold = bisect("x", "n", &probe(0, true), &probe(8, false), 24, |_| Ok(None)).unwrap();
+        assert_eq!((threshold.passing, threshold.failing, threshold.exact), (0, 8, false));
+    }
+}
@@ -0,0 +1,176 @@
+//! Commit-stream bucketing of fuzz inputs
+//!
+//! Native runs cost milliseconds, SP1 runs seconds to minutes. Many
+//! mutations drive a core down the same path: a dozen ways of overflowing
+//! the same field all commit the same values natively. With
+//! `fuzz --commit-buckets N`, every input first runs natively, inputs are
+//! bucketed by their native status and commit stream, and only the `N`
+//! smallest inputs of each bucket go to SP1 first. The rest of a bucket is
+//! redundant: it runs on SP1 after every bucket's representatives
+//! (`--redundant defer`) or not at all (`--redundant skip`).
+//!
+//! Native infrastructure failures say nothing about the input, so they are
+//! never bucketed. The buckets are written to `<fuzz dir>/buckets.json`.
+
+use rust_eq_oracle::{RunResult, Status};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Commits shown per bucket in `buckets.json`
+const PREVIEW_COMMITS: usize = 8;
+
+/// Inputs with the same native status and commits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bucket {
+    /// `b_` and the first 12 hex digits of the SHA-256 of status and commits
+    pub key: String,
+    pub status: Status,
+    /// The first commits of the stream
+    pub commits: Vec<serde_json::Value>,
+    pub commit_count: usize,
+    /// Members run on SP1 first (mutation numbers, smallest inputs first)
+    pub representatives: Vec<usize>,
+    /// The other members
+    pub redundant: Vec<usize>,
+}
+
+/// How the SP1 runs of a core's inputs are ordered
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bucketing {
+    pub per_bucket: usize,
+    /// Largest bucket first
+    pub buckets: Vec<Bucket>,
+    /// Inputs not bucketed (native infrastructure failures)
+    pub unbucketed: Vec<usize>,
+}
+
+/// One natively pre-executed input
+pub struct Member<'a> {
+    /// Mutation number
+    pub id: usize,
+    pub native: &'a RunResult,
+    pub input_bytes: usize,
+}
+
+fn bucket_key(status: Status, commits: &[serde_json::Value]) -> String {
+    use sha2::{Digest, Sha256};
+
+    let contents = serde_json::to_vec(&(status, commits)).expect("commits serialize");
+    format!("b_{}", &format!("{:x}", Sha256::digest(&contents))[..12])
+}
+
+/// Bucket `members` by native commit stream, keeping the `per_bucket`
+/// smallest inputs of each as representatives
+pub fn bucket(members: &[Member], per_bucket: usize) -> Bucketing {
+    let mut by_key: BTreeMap<String, (Bucket, Vec<(usize, usize)>)> = BTreeMap::new();
+    let mut unbucketed = Vec::new();
+    for member in members {
+        let native = member.native;
+        if native.status.is_infra() {
+            unbucketed.push(member.id);
+            continue;
+        }
+        let key = bucket_key(native.status, &native.commits);
+        let (_, sizes) = by_key.entry(key.clone()).or_insert_with(|| {
+            let bucket = Bucket {
+                key,
+                status: native.status,
+                commits: native.commits.iter().take(PREVIEW_COMMITS).cloned().collect(),
+                commit_count: native.commits.len(),
+                representatives: Vec::new(),
+                redundant: Vec::new(),
+            };
+            (bucket, Vec::new())
+        });
+        sizes.push((member.input_bytes, member.id));
+    }
+
+    let mut buckets: Vec<Bucket> = by_key
+        .into_values()
+        .map(|(mut bucket, mut sizes)| {
+            sizes.sort();
+            let ids = sizes.into_iter().map(|(_, id)| id);
+            bucket.representatives = ids.clone().take(per_bucket).collect();
+            bucket.redundant = ids.skip(per_bucket).collect();
+            bucket.representatives.sort();
+            bucket.redundant.sort();
+            bucket
+        })
+        .collect();
+    buckets.sort_by_key(|bucket| std::cmp::Reverse(bucket.representatives.len() + bucket.redundant.len()));
+    Bucketing {
+        per_bucket,
+        buckets,
+        unbucketed,
+    }
+}
+
+impl Bucketing {
+    /// Inputs to run on SP1 first: representatives and unbucketed inputs,
+    /// in mutation order
+    pub fn first(&self) -> Vec<usize> {
+        let mut first: Vec<usize> = self
+            .buckets
+            .iter()
+            .flat_map(|bucket| bucket.representatives.iter().copied())
+            .chain(self.unbucketed.iter().copied())
+            .collect();
+        first.sort();
+        first
+    }
+
+    /// Redundant inputs, in mutation order
+    pub fn redundant(&self) -> Vec<usize> {
+        let mut redundant: Vec<usize> = self.buckets.iter().flat_map(|bucket| bucket.redundant.iter().copied()).collect();
+        redundant.sort();
+        redundant
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(status: Status, commits: &[u64]) -> RunResult {
+        RunResult {
+            status,
+            elapsed_ms: 0,
+            commits: commits.iter().map(|&value| value.into()).collect(),
+            meta: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn test_bucket() {
+        let overflow = result(Status::Ok, &[u64::MAX, 1]);
+        let normal = result(Status::Ok, &[5, 0]);
+        let panic = result(Status::Panic, &[]);
+        let infra = result(Status::InfraError, &[]);
+        let members = [
+            Member { id: 1, native: &overflow, input_bytes: 40 },
+            Member { id: 2, native: &normal, input_bytes: 20 },
+            Member { id: 3, native: &overflow, input_bytes: 10 },
+            Member { id: 4, native: &overflow, input_bytes: 30 },
+            Member { id: 5, native: &panic, input_bytes: 20 },
+            Member { id: 6, native: &infra, input_bytes: 20 },
+            Member { id: 7, native: &infra, input_bytes: 20 },
+        ];
+
+        let bucketing = bucket(&members, 1);
+        assert_eq!(bucketing.buckets.len(), 3);
+        let largest = &bucketing.buckets[0];
+        assert_eq!((largest.status, largest.commit_count), (Status::Ok, 2));
+        // The smallest input represents the bucket
+        assert_eq!((largest.representatives.clone(), largest.redundant.clone()), (vec![3], vec![1, 4]));
+        assert_eq!(bucketing.unbucketed, vec![6, 7]);
+        assert_eq!(bucketing.first(), vec![2, 3, 5, 6, 7]);
+        assert_eq!(bucketing.redundant(), vec![1, 4]);
+
+        // Same status and commits, same key
+        assert_eq!(bucket_key(Status::Ok, &overflow.commits), largest.key);
+        assert_ne!(bucket_key(Status::Panic, &overflow.commits), largest.key);
+
+        let two = bucket(&members, 2);
+        assert_eq!(two.redundant(), vec![1]);
+    }
+}
@@ -0,0 +1,273 @@
+//! Guest build results
+//!
+//! `cargo prove build` output is captured to `artifacts/builds/` instead of
+//! streaming into the console. The warnings and errors in it are counted,
+//! and every build appends a [`BuildResult`] (duration, diagnostics, ELF
+//! hash, campaign) to `artifacts/builds.jsonl`, so a campaign's builds can
+//! be audited next to its runs.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+const BUILDS_DIR: &str = "artifacts/builds";
+const BUILDS_PATH: &str = "artifacts/builds.jsonl";
+
+/// Diagnostic messages kept per build (the log has all of them)
+pub const MAX_DIAGNOSTICS: usize = 20;
+
+/// One guest build, as recorded in `artifacts/builds.jsonl`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildResult {
+    pub core: String,
+    pub started_at: String,
+    pub duration_ms: u128,
+    pub success: bool,
+    pub warnings: usize,
+    pub errors: usize,
+    /// First [`MAX_DIAGNOSTICS`] warning/error lines
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<String>,
+    /// Full `cargo prove build` output
+    pub log_path: PathBuf,
+    /// SHA-256 of the built ELF (None if the build failed)
+    pub elf_sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub campaign: Option<String>,
+}
+
+impl BuildResult {
+    /// One-line console status, e.g. `12.3s, 2 warnings, ELF 3fa2c1d0`
+    pub fn summary(&self) -> String {
+        let plural = |n: usize, what: &str| format!("{} {}{}", n, what, if n == 1 { "" } else { "s" });
+        let mut parts = vec![format!("{:.1}s", self.duration_ms as f64 / 1000.0)];
+        if self.errors > 0 {
+            parts.push(plural(self.errors, "error"));
+        }
+        parts.push(plural(self.warnings, "warning"));
+        if let Some(hash) = &self.elf_sha256 {
+            parts.push(format!("ELF {}", &hash[..8.min(hash.len())]));
+        }
+        parts.join(", ")
+    }
+
+    /// First error line of a failed build, for error messages
+    pub fn first_error(&self) -> Option<&str> {
+        self.diagnostics
+            .iter()
+            .find(|line| line.starts_with("error"))
+            .map(String::as_str)
+    }
+}
+
+/// Run a `cargo prove build` command for a core with its output captured
+///
+/// Writes the log, counts its diagnostics and appends the result to
+/// `artifacts/builds.jsonl`; `elf_path` is hashed if the build succeeds.
+/// A failed build is an error naming the log and the first error line.
+pub fn run_build(mut cmd: Command, core_name: &str, elf_path: Option<&Path>) -> Result<BuildResult> {
+    let started_at = chrono::Utc::now();
+    let output = cmd.output().context("Failed to execute cargo prove build")?;
+    let duration_ms = (chrono::Utc::now() - started_at).num_milliseconds().max(0) as u128;
+
+    let log = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let (warnings, errors, diagnostics) = parse_diagnostics(&log);
+    let success = output.status.success();
+    let result = BuildResult {
+        core: core_name.to_string(),
+        started_at: started_at.to_rfc3339(),
+        duration_ms,
+        success,
+        warnings,
+        errors,
+        diagnostics,
+        log_path: write_log(core_name, &log)?,
+        elf_sha256: elf_path.filter(|_| success).and_then(crate::sha256_file),
+        campaign: crate::campaign::current_id(),
+    };
+    append_build_result(&result)?;
+
+    if !success {
+        anyhow::bail!(
+            "cargo prove build failed ({}; log: {}){}",
+            result.summary(),
+            result.log_path.display(),
+            result.first_error().map(|error| format!(": {}", error)).unwrap_or_default()
+        );
+    }
+    Ok(result)
+}
+
+/// Run `build` for every core, at most `jobs` at a time
+///
+/// Meant for guest builds, whose output [`run_build`] captures, so
+/// concurrent builds only interleave as whole progress lines. Each core's
+/// outcome is independent of the others. Returns the outcomes in `cores`
+/// order.
+pub fn build_all<T: Send>(cores: &[&str], jobs: usize, build: impl Fn(&str) -> T + Sync) -> Vec<T> {
+    let next = AtomicUsize::new(0);
+    let outcomes: Mutex<Vec<Option<T>>> = Mutex::new(cores.iter().map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, cores.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(core_name) = cores.get(index) else {
+                    break;
+                };
+                let outcome = build(core_name);
+                outcomes.lock().unwrap()[index] = Some(outcome);
+            });
+        }
+    });
+    outcomes
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|outcome| outcome.expect("every core is built once"))
+        .collect()
+}
+
+/// Warning and error counts of a build log, with the first diagnostic lines
+///
+/// Counts rustc/cargo diagnostic headers (`warning: ...`, `error[E0308]: ...`)
+/// and skips cargo's summaries (`generated N warnings`, `could not compile`,
+/// `aborting due to`). A `[sp1]` prefix from the SP1 build is ignored.
+pub fn parse_diagnostics(log: &str) -> (usize, usize, Vec<String>) {
+    let (mut warnings, mut errors) = (0, 0);
+    let mut diagnostics = Vec::new();
+    for line in log.lines() {
+        let line = line.trim_start();
+        let line = line.strip_prefix("[sp1]").map(str::trim_start).unwrap_or(line);
+        let Some((level, message)) = diagnostic_header(line) else {
+            continue;
+        };
+        let summary = message.contains(" generated ") && message.contains(" warning")
+            || message.starts_with("could not compile")
+            || message.starts_with("aborting due to");
+        if summary {
+            continue;
+        }
+        if level == "warning" {
+            warnings += 1;
+        } else {
+            errors += 1;
+        }
+        if diagnostics.len() < MAX_DIAGNOSTICS {
+            diagnostics.push(line.to_string());
+        }
+    }
+    (warnings, errors, diagnostics)
+}
+
+/// `("warning" | "error", message)` if `line` starts a diagnostic
+fn diagnostic_header(line: &str) -> Option<(&'static str, &str)> {
+    for level in ["warning", "error"] {
+        let Some(rest) = line.strip_prefix(level) else {
+            continue;
+        };
+        // Optional code, as in `error[E0308]:`
+        let rest = match rest.strip_prefix('[') {
+            Some(code) => code.split_once(']')?.1,
+            None => rest,
+        };
+        if let Some(message) = rest.strip_prefix(": ") {
+            return Some((level, message));
+        }
+    }
+    None
+}
+
+/// Write a build's output to `artifacts/builds/<core>_<timestamp>.log`
+pub fn write_log(core_name: &str, log: &str) -> Result<PathBuf> {
+    fs::create_dir_all(BUILDS_DIR)?;
+    let path = Path::new(BUILDS_DIR).join(format!(
+        "{}_{}.log",
+        core_name,
+        chrono::Utc::now().format("%Y%m%d_%H%M%S%.3f")
+    ));
+    fs::write(&path, log).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Append a result to `artifacts/builds.jsonl` (one `O_APPEND` write per line)
+pub fn append_build_result(result: &BuildResult) -> Result<()> {
+    let mut line = serde_json::to_vec(result)?;
+    line.push(b'\n');
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(BUILDS_PATH)
+        .context("Failed to open artifacts/builds.jsonl")?
+        .write_all(&line)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let log = "\
+[sp1]    Compiling fib-core v0.1.0
+[sp1] warning: unused variable: `x`
+[sp1]  --> src/lib.rs:3:9
+warning: unused import: `std::fs`
+error[E0308]: mismatched types
+error: expected `;`, found `}`
+warning: `fib-core` (lib) generated 2 warnings
+error: could not compile `fib-core` (lib) due to 2 previous errors
+error: aborting due to 2 previous errors
+warnings: not a diagnostic
+";
+        let (warnings, errors, diagnostics) = parse_diagnostics(log);
+        assert_eq!((warnings, errors), (2, 2));
+        assert_eq!(diagnostics[0], "warning: unused variable: `x`");
+        assert_eq!(diagnostics[2], "error[E0308]: mismatched types");
+
+        let result = BuildResult {
+            core: "fib".to_string(),
+            started_at: String::new(),
+            duration_ms: 12_345,
+            success: false,
+            warnings,
+            errors,
+            diagnostics,
+            log_path: PathBuf::from("artifacts/builds/fib.log"),
+            elf_sha256: None,
+            campaign: None,
+        };
+        assert_eq!(result.summary(), "12.3s, 2 errors, 2 warnings");
+        assert_eq!(result.first_error(), Some("error[E0308]: mismatched types"));
+    }
+
+    #[test]
+    fn test_build_all_bounds_jobs() {
+        let cores = ["a", "b", "c", "d", "e", "f", "g"];
+        let (running, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let outcomes = build_all(&cores, 3, |core_name| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            running.fetch_sub(1, Ordering::SeqCst);
+            anyhow::ensure!(core_name != "c", "{} failed", core_name);
+            Ok(core_name.to_uppercase())
+        });
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        assert!(peak.load(Ordering::SeqCst) > 1);
+        assert_eq!(outcomes.len(), cores.len());
+        assert_eq!(outcomes[1].as_ref().unwrap(), "B");
+        assert!(outcomes[2].is_err());
+        assert_eq!(outcomes[6].as_ref().unwrap(), "G");
+        assert!(build_all(&[], 4, |_| ()).is_empty());
+    }
+}
@@ -0,0 +1,194 @@
+//! Named campaigns
+//!
+//! `harness --campaign nightly-sp1-4.0 --tag nightly fuzz ...` runs under a
+//! campaign: every run log, `summary.csv` row, `runs.jsonl` record and report
+//! written by this process carries the campaign id, so several investigations
+//! can share one `artifacts/` directory and still be told apart. The campaign
+//! is process-wide (like the run ID session) and its metadata is kept in
+//! `artifacts/campaigns/<id>.json`.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const CAMPAIGNS_DIR: &str = "artifacts/campaigns";
+
+static CURRENT: OnceLock<Campaign> = OnceLock::new();
+
+/// The campaign runs are recorded under
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Campaign {
+    pub id: String,
+    /// Free-form labels (e.g. `nightly`, `sp1-4.0`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl Campaign {
+    /// Tags joined with `;` (the `tags` column of `summary.csv`)
+    pub fn tags_column(&self) -> String {
+        self.tags.join(";")
+    }
+}
+
+/// `artifacts/campaigns/<id>.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignMetadata {
+    pub id: String,
+    pub created_at: String,
+    pub last_started_at: String,
+    /// Every tag the campaign was started with
+    pub tags: BTreeSet<String>,
+    /// Harness invocations under this campaign
+    pub sessions: Vec<CampaignSession>,
+}
+
+/// One harness invocation under a campaign
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignSession {
+    pub started_at: String,
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// Campaign ids end up in file names and CSV cells
+pub fn validate_id(id: &str) -> Result<()> {
+    anyhow::ensure!(
+        !id.is_empty()
+            && !id.starts_with('.')
+            && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')),
+        "Invalid campaign id '{}' (use letters, digits, '-', '_' and '.')",
+        id
+    );
+    anyhow::ensure!(
+        !crate::layout::RESERVED.contains(&id),
+        "Invalid campaign id '{}' (artifacts/{}/ holds other artifacts)",
+        id,
+        id
+    );
+    Ok(())
+}
+
+/// Run the rest of this process under `id`, recording the session in the
+/// campaign's metadata
+///
+/// Tags accumulate across sessions. Fails if a different campaign was
+/// already started in this process.
+pub fn start(id: &str, tags: &[String], command: &str) -> Result<&'static Campaign> {
+    validate_id(id)?;
+    let tags: Vec<String> = tags.iter().map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()).collect();
+    anyhow::ensure!(
+        tags.iter().all(|tag| !tag.contains([';', ',', '\n'])),
+        "Campaign tags may not contain ';', ',' or newlines"
+    );
+
+    let campaign = Campaign {
+        id: id.to_string(),
+        tags,
+    };
+    let current = CURRENT.get_or_init(|| campaign.clone());
+    anyhow::ensure!(
+        *current == campaign,
+        "Campaign '{}' is already active in this process",
+        current.id
+    );
+
+    record_session(current, command)?;
+    Ok(current)
+}
+
+/// The campaign this process runs under (None if none was started)
+pub fn current() -> Option<&'static Campaign> {
+    CURRENT.get()
+}
+
+/// Id of the current campaign (for records that store only the id)
+pub fn current_id() -> Option<String> {
+    current().map(|campaign| campaign.id.clone())
+}
+
+fn metadata_path(id: &str) -> PathBuf {
+    Path::new(CAMPAIGNS_DIR).join(format!("{}.json", id))
+}
+
+/// Metadata of a campaign (None if it was never started)
+pub fn load_metadata(id: &str) -> Result<Option<CampaignMetadata>> {
+    let path = metadata_path(id);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let metadata = serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(Some(metadata))
+}
+
+/// Metadata of every campaign under `artifacts/campaigns/`, oldest first
+pub fn list_campaigns() -> Result<Vec<CampaignMetadata>> {
+    let Ok(entries) = fs::read_dir(CAMPAIGNS_DIR) else {
+        return Ok(Vec::new());
+    };
+    let mut campaigns = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let text = fs::read_to_string(&path)?;
+            campaigns.push(
+                serde_json::from_str::<CampaignMetadata>(&text)
+                    .with_context(|| format!("Failed to parse {}", path.display()))?,
+            );
+        }
+    }
+    campaigns.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    Ok(campaigns)
+}
+
+fn record_session(campaign: &Campaign, command: &str) -> Result<()> {
+    let now = Utc::now().to_rfc3339();
+    let mut metadata = load_metadata(&campaign.id)?.unwrap_or_else(|| CampaignMetadata {
+        id: campaign.id.clone(),
+        created_at: now.clone(),
+        last_started_at: now.clone(),
+        tags: BTreeSet::new(),
+        sessions: Vec::new(),
+    });
+    metadata.last_started_at = now.clone();
+    metadata.tags.extend(campaign.tags.iter().cloned());
+    metadata.sessions.push(CampaignSession {
+        started_at: now,
+        command: command.to_string(),
+        tags: campaign.tags.clone(),
+    });
+
+    fs::create_dir_all(CAMPAIGNS_DIR)?;
+    let path = metadata_path(&campaign.id);
+    fs::write(&path, serde_json::to_string_pretty(&metadata)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_campaign_ids() {
+        assert!(validate_id("nightly-sp1-4.0").is_ok());
+        assert!(validate_id("issue_42").is_ok());
+        assert!(validate_id("").is_err());
+        assert!(validate_id("..").is_err());
+        assert!(validate_id("a/b").is_err());
+        assert!(validate_id("two words").is_err());
+        assert!(validate_id("mutations").is_err());
+
+        let campaign = Campaign {
+            id: "nightly".to_string(),
+            tags: vec!["sp1-4.0".to_string(), "ci".to_string()],
+        };
+        assert_eq!(campaign.tags_column(), "sp1-4.0;ci");
+    }
+}
@@ -0,0 +1,207 @@
+//! Commit count inference
+//!
+//! Cores without a commit schema commit a fixed number of `u32`s, which
+//! `sp1-runner --num-commits` checks the public values against. The harness
+//! runs each guest ELF once on its core's base input without a count,
+//! derives the count from the raw public values (4 bytes per `u32`) and
+//! caches it in `artifacts/commit_counts.json`, keyed by ELF hash. When the
+//! trial fails, the count falls back to the `commit_fields` the core's
+//! manifest declares (see [`crate::commit_fields_for_core`]).
+//!
+//! Cores whose commit count depends on the input declare a schema ending in
+//! a repeated type (e.g. `commits = "u32*"`) and are never inferred.
+
+use crate::sha256_file;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use rust_eq_oracle::{RunResult, Status};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+pub const CACHE_PATH: &str = "artifacts/commit_counts.json";
+
+/// Commit count inferred for one guest ELF
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InferredCommits {
+    pub core: String,
+    pub num_commits: usize,
+    /// Raw public values length of the trial run, in bytes
+    pub public_values_len: usize,
+    /// Input of the trial run
+    pub input: String,
+    pub inferred_at: String,
+}
+
+/// Inferred counts by ELF SHA-256 (empty if nothing was inferred yet)
+pub fn load_cache() -> BTreeMap<String, InferredCommits> {
+    fs::read_to_string(CACHE_PATH)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// The cache file, read once per process and kept up to date by [`store`]
+fn cache() -> &'static Mutex<BTreeMap<String, InferredCommits>> {
+    static CACHE: OnceLock<Mutex<BTreeMap<String, InferredCommits>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(load_cache()))
+}
+
+/// Count cached for an ELF hash
+fn cached(elf_sha256: &str) -> Option<usize> {
+    let cache = cache().lock().unwrap_or_else(|e| e.into_inner());
+    cache.get(elf_sha256).map(|cached| cached.num_commits)
+}
+
+fn store(elf_sha256: &str, inferred: &InferredCommits) -> Result<()> {
+    cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(elf_sha256.to_string(), inferred.clone());
+    // Merged into the file as it is now, in case another process added to it
+    let mut on_disk = load_cache();
+    on_disk.insert(elf_sha256.to_string(), inferred.clone());
+    fs::create_dir_all("artifacts")?;
+    let tmp = format!("{}.{}.tmp", CACHE_PATH, std::process::id());
+    fs::write(&tmp, serde_json::to_string_pretty(&on_disk)?)?;
+    fs::rename(&tmp, CACHE_PATH).with_context(|| format!("Failed to write {}", CACHE_PATH))
+}
+
+/// `(num_commits, public_values_len)` of a trial run without `--num-commits`
+///
+/// The run must pass and its public values must be whole `u32`s, each one
+/// decoded as a commit.
+pub fn count_from_trial(result: &RunResult) -> Result<(usize, usize)> {
+    anyhow::ensure!(
+        result.status == Status::Ok,
+        "trial run ended in {:?}{}",
+        result.status,
+        result.meta["error"].as_str().map(|e| format!(": {}", e)).unwrap_or_default()
+    );
+    let hex = result.meta["public_values_hex"]
+        .as_str()
+        .context("trial run reported no public_values_hex")?;
+    let len = hex.len() / 2;
+    anyhow::ensure!(
+        len % 4 == 0,
+        "{} bytes of public values are not whole u32 commits; declare a commit schema",
+        len
+    );
+    anyhow::ensure!(
+        result.commits.len() == len / 4,
+        "{} commits decoded from {} bytes of public values",
+        result.commits.len(),
+        len
+    );
+    Ok((len / 4, len))
+}
+
+/// Number of `commit_fields` a core declares (0 = variable or unknown)
+fn declared_num_commits(core_name: &str) -> usize {
+    match crate::commit_fields_for_core(core_name) {
+        Ok(fields) if !fields.iter().any(|field| field.ends_with('*')) => fields.len(),
+        _ => 0,
+    }
+}
+
+/// Count cached for the ELF at `elf_path`, else the declared one (never
+/// runs a trial)
+pub fn cached_num_commits(core_name: &str, elf_path: &Path) -> usize {
+    sha256_file(elf_path)
+        .and_then(|elf_sha256| cached(&elf_sha256))
+        .unwrap_or_else(|| declared_num_commits(core_name))
+}
+
+/// Number of u32s the guest at `elf_path` commits: cached for its hash,
+/// inferred by a trial run on first use, or the declared count
+pub fn num_commits(core_name: &str, runner: Option<&Path>, elf_path: &Path) -> usize {
+    // One cell per ELF: parallel runs of a new ELF wait for a single trial,
+    // while runs of other ELFs go ahead
+    static COUNTS: Mutex<BTreeMap<String, Arc<OnceLock<usize>>>> = Mutex::new(BTreeMap::new());
+
+    let fallback = declared_num_commits(core_name);
+    let Some(elf_sha256) = sha256_file(elf_path) else {
+        return fallback;
+    };
+    if let Some(num_commits) = cached(&elf_sha256) {
+        return num_commits;
+    }
+    let cell = COUNTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(elf_sha256.clone())
+        .or_default()
+        .clone();
+    *cell.get_or_init(|| infer_or_fallback(core_name, runner, elf_path, &elf_sha256, fallback))
+}
+
+/// Run the trial, reporting the outcome; `fallback` if it fails
+fn infer_or_fallback(core_name: &str, runner: Option<&Path>, elf_path: &Path, elf_sha256: &str, fallback: usize) -> usize {
+    match infer(core_name, runner, elf_path, elf_sha256) {
+        Ok(inferred) => {
+            println!(
+                "   🔢 {}: {} commits inferred from {} (cached in {})",
+                core_name, inferred.num_commits, inferred.input, CACHE_PATH
+            );
+            if fallback > 0 && fallback != inferred.num_commits {
+                println!("      ⚠️  The manifest of {} declares {} commit fields", core_name, fallback);
+            }
+            inferred.num_commits
+        }
+        Err(e) => {
+            println!(
+                "   ⚠️  Could not infer the commit count of {}: {:#} (using {})",
+                core_name, e, fallback
+            );
+            fallback
+        }
+    }
+}
+
+fn infer(core_name: &str, runner: Option<&Path>, elf_path: &Path, elf_sha256: &str) -> Result<InferredCommits> {
+    let input_path = crate::base_input_for_core(core_name)?;
+    let mut cmd = crate::sp1_runner_base_command(runner, elf_path, &input_path, core_name);
+    if let Some(max_cycles) = crate::get_max_cycles_for_core(core_name) {
+        cmd.args(["--max-cycles", &max_cycles.to_string()]);
+    }
+    let result = crate::run_sp1_runner_command(cmd, elf_path, core_name)?;
+    let (num_commits, public_values_len) = count_from_trial(&result)?;
+
+    let inferred = InferredCommits {
+        core: core_name.to_string(),
+        num_commits,
+        public_values_len,
+        input: input_path.display().to_string(),
+        inferred_at: Utc::now().to_rfc3339(),
+    };
+    store(elf_sha256, &inferred)?;
+    Ok(inferred)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trial(status: Status, hex: &str, commits: usize) -> RunResult {
+        RunResult {
+            status,
+            elapsed_ms: 1,
+            commits: (0..commits).map(|i| serde_json::json!(i)).collect(),
+            meta: serde_json::json!({ "runner": "sp1", "public_values_hex": hex }),
+        }
+    }
+
+    #[test]
+    fn test_count_from_trial() {
+        let hex = "0100000002000000ffffffff";
+        assert_eq!(count_from_trial(&trial(Status::Ok, hex, 3)).unwrap(), (3, 12));
+        assert_eq!(count_from_trial(&trial(Status::Ok, "", 0)).unwrap(), (0, 0));
+
+        assert!(count_from_trial(&trial(Status::Panic, hex, 3)).is_err());
+        let partial = count_from_trial(&trial(Status::Ok, "01000000ff", 1)).unwrap_err();
+        assert!(partial.to_string().contains("declare a commit schema"), "{}", partial);
+        assert!(count_from_trial(&trial(Status::Ok, hex, 2)).is_err());
+    }
+}
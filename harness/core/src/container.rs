@@ -0,0 +1,212 @@
+//! Hermetic runner execution in pinned container images
+//!
+//! With `harness --container`, the runners run inside the images listed in
+//! `fuzz.toml` instead of through the host's cargo and toolchains, so an
+//! SP1 or rustc upgrade on the host cannot skew results between campaigns.
+//! The ELF and input are mounted read-only at fixed paths, with the input's
+//! binary sidecars next to it, `artifacts/` is mounted read-write, and the
+//! container has no network.
+//!
+//! ```toml
+//! [[containers]]
+//! target = "sp1"              # sp1, native or native-checked
+//! image = "zk-fuzz-lab/runners@sha256:..."
+//! runner = "/usr/local/bin/sp1-runner"
+//! ```
+//!
+//! Targets without an image keep running on the host. Matrix cells with
+//! their own `runner` binary are not containerised.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Container targets: the SP1 runner and the two native runner builds
+pub const TARGETS: [&str; 3] = ["sp1", "native", "native-checked"];
+
+/// Where a mounted ELF appears inside the container
+pub const ELF_MOUNT: &str = "/zkfuzz/guest.elf";
+/// Where a mounted input appears inside the container
+pub const INPUT_MOUNT: &str = "/zkfuzz/input.json";
+/// Working directory inside the container (`artifacts/` is mounted below it)
+const WORKDIR: &str = "/zkfuzz";
+
+static ACTIVE: OnceLock<Vec<ContainerImage>> = OnceLock::new();
+
+/// One `[[containers]]` entry in `fuzz.toml`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContainerImage {
+    /// Runner the image replaces (see [`TARGETS`])
+    pub target: String,
+    /// Image reference; pin it by digest (`name@sha256:...`) for reproducible runs
+    pub image: String,
+    /// Runner binary inside the image
+    pub runner: String,
+    /// Container engine (default: `docker`; `podman` takes the same arguments)
+    #[serde(default)]
+    pub engine: Option<String>,
+    /// Extra `run` arguments (e.g. `["--cpus", "2", "--memory", "8g"]`)
+    #[serde(default)]
+    pub run_args: Vec<String>,
+}
+
+impl ContainerImage {
+    pub fn validate(&self, idx: usize) -> Result<()> {
+        anyhow::ensure!(
+            TARGETS.contains(&self.target.as_str()),
+            "containers[{}]: unknown target '{}' ({})",
+            idx,
+            self.target,
+            TARGETS.join(", ")
+        );
+        anyhow::ensure!(!self.image.is_empty(), "containers[{}]: image must not be empty", idx);
+        anyhow::ensure!(!self.runner.is_empty(), "containers[{}]: runner must not be empty", idx);
+        Ok(())
+    }
+
+    /// Whether the image is pinned by digest rather than a mutable tag
+    pub fn is_pinned(&self) -> bool {
+        self.image.contains("@sha256:")
+    }
+
+    pub fn engine(&self) -> &str {
+        self.engine.as_deref().unwrap_or("docker")
+    }
+
+    /// `<engine> run` invocation of the image's runner, with `mounts`
+    /// (host path, container path) bound read-only and `artifacts/`
+    /// read-write; runner arguments are appended by the caller
+    pub fn command<P: AsRef<Path>, S: AsRef<str>>(&self, mounts: &[(P, S)]) -> Command {
+        let mut cmd = Command::new(self.engine());
+        cmd.args(["run", "--rm", "--network", "none", "--workdir", WORKDIR]);
+        for (host, container) in mounts {
+            cmd.args(["--volume", &format!("{}:{}:ro", absolute(host.as_ref()).display(), container.as_ref())]);
+        }
+        cmd.args(["--volume", &format!("{}:{}/artifacts", absolute(Path::new("artifacts")).display(), WORKDIR)])
+            .args(&self.run_args)
+            .arg(&self.image)
+            .arg(&self.runner);
+        cmd
+    }
+}
+
+/// Mounts of an input at [`INPUT_MOUNT`] and of the sidecars it names
+/// (see `input_preprocess::Preprocess::sidecars`) in the same folder,
+/// where the runners' preprocessing looks for them
+pub fn input_mounts(input_path: &Path, sidecars: &[String]) -> Vec<(PathBuf, String)> {
+    let mut mounts = vec![(input_path.to_path_buf(), INPUT_MOUNT.to_string())];
+    let reserved = [INPUT_MOUNT, ELF_MOUNT].map(|mount| mount.rsplit('/').next().unwrap_or(mount));
+    for name in sidecars.iter().filter(|name| !reserved.contains(&name.as_str()) && name.as_str() != "artifacts") {
+        mounts.push((input_path.with_file_name(name), format!("{}/{}", WORKDIR, name)));
+    }
+    mounts
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Run the configured targets in their containers for the rest of this
+/// process
+///
+/// Fails if no image is configured or the container engine is missing.
+pub fn enable(images: Vec<ContainerImage>) -> Result<&'static [ContainerImage]> {
+    anyhow::ensure!(
+        !images.is_empty(),
+        "--container needs at least one [[containers]] image in {}",
+        crate::matrix::CONFIG_PATH
+    );
+    for image in &images {
+        let engine = image.engine();
+        Command::new(engine)
+            .arg("--version")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .with_context(|| format!("Container engine '{}' is not available", engine))?;
+    }
+    std::fs::create_dir_all("artifacts")?;
+    Ok(ACTIVE.get_or_init(|| images))
+}
+
+/// Whether [`enable`] was called in this process
+pub fn enabled() -> bool {
+    ACTIVE.get().is_some()
+}
+
+/// Image a target runs in (None if containers are off or the target has none)
+pub fn image_for(target: &str) -> Option<&'static ContainerImage> {
+    ACTIVE.get()?.iter().find(|image| image.target == target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_command() {
+        let image = ContainerImage {
+            target: "sp1".to_string(),
+            image: "runners@sha256:abc".to_string(),
+            runner: "/usr/local/bin/sp1-runner".to_string(),
+            engine: Some("podman".to_string()),
+            run_args: vec!["--cpus".to_string(), "2".to_string()],
+        };
+        assert!(image.is_pinned());
+
+        let mut cmd = image.command(&[(Path::new("inputs/fib_24.json"), INPUT_MOUNT)]);
+        cmd.args(["--input", INPUT_MOUNT]);
+        let args: Vec<String> = cmd.get_args().map(|a| a.to_string_lossy().into_owned()).collect();
+        assert_eq!(cmd.get_program(), "podman");
+        assert_eq!(&args[..6], ["run", "--rm", "--network", "none", "--workdir", "/zkfuzz"]);
+
+        let input_volume = format!("{}:/zkfuzz/input.json:ro", absolute(Path::new("inputs/fib_24.json")).display());
+        assert!(input_volume.starts_with('/'));
+        assert_eq!(args[7], input_volume);
+        assert!(args[9].ends_with("/artifacts:/zkfuzz/artifacts"));
+        assert_eq!(
+            &args[10..],
+            ["--cpus", "2", "runners@sha256:abc", "/usr/local/bin/sp1-runner", "--input", "/zkfuzz/input.json"]
+        );
+    }
+
+    #[test]
+    fn test_container_command_with_sidecar() {
+        let image = ContainerImage {
+            target: "native".to_string(),
+            image: "runners@sha256:abc".to_string(),
+            runner: "/usr/local/bin/native-runner".to_string(),
+            engine: None,
+            run_args: vec![],
+        };
+        let input = Path::new("artifacts/mutations/x/mutation_0001/input.json");
+        let mounts = input_mounts(input, &["input.bin".to_string(), "input.json".to_string()]);
+        assert_eq!(mounts.len(), 2, "a sidecar cannot shadow the input");
+        let args: Vec<String> =
+            image.command(&mounts).get_args().map(|a| a.to_string_lossy().into_owned()).collect();
+        assert_eq!(args[7], format!("{}:/zkfuzz/input.json:ro", absolute(input).display()));
+        assert_eq!(
+            args[9],
+            format!("{}:/zkfuzz/input.bin:ro", absolute(&input.with_file_name("input.bin")).display())
+        );
+        assert!(args[11].ends_with("/artifacts:/zkfuzz/artifacts"));
+    }
+
+    #[test]
+    fn test_parse_containers() {
+        let config = crate::matrix::FuzzConfig::parse(
+            "[[containers]]\ntarget = \"native\"\nimage = \"runners:latest\"\nrunner = \"/usr/local/bin/native-runner\"\n",
+        )
+        .unwrap();
+        assert!(!config.containers[0].is_pinned());
+        assert_eq!(config.containers[0].engine(), "docker");
+
+        let unknown = "[[containers]]\ntarget = \"risc0\"\nimage = \"x\"\nrunner = \"y\"\n";
+        assert!(crate::matrix::FuzzConfig::parse(unknown).is_err());
+        let duplicate = "[[containers]]\ntarget = \"sp1\"\nimage = \"x\"\nrunner = \"y\"\n".repeat(2);
+        assert!(crate::matrix::FuzzConfig::parse(&duplicate).is_err());
+    }
+}
@@ -0,0 +1,247 @@
+//! Dedup of mutations against the historical corpus
+//!
+//! Every logged run records the SHA-256 of its input, the zkVM target and
+//! the guest ELF hash in `artifacts/runs.jsonl`. Before a fuzz campaign
+//! executes a mutation, it looks the input up here and skips it if the
+//! same core already ran the identical input against the same target and
+//! ELF in an earlier campaign, saving the SP1 cycles.
+//!
+//! `runs.jsonl` is the store. So that a campaign doesn't re-read the whole
+//! history, the lookup table is kept in `artifacts/corpus_index.json`
+//! together with how far into `runs.jsonl` it goes; [`ExecutedCorpus::load`]
+//! only reads the records appended since. The index is rebuilt from the
+//! start if `runs.jsonl` was replaced or truncated.
+
+use crate::cost_model::{RunRecord, RUNS_PATH};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+pub const INDEX_PATH: &str = "artifacts/corpus_index.json";
+
+/// Inputs already executed, keyed by (core, target, ELF hash, input hash)
+#[derive(Debug, Default)]
+pub struct ExecutedCorpus {
+    runs: HashMap<(String, String, String, String), String>,
+}
+
+/// `artifacts/corpus_index.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Snapshot {
+    /// Bytes of `runs.jsonl` indexed
+    offset: u64,
+    /// First line of `runs.jsonl`, to notice it was replaced
+    first_line: String,
+    runs: Vec<Executed>,
+}
+
+/// One indexed input
+#[derive(Debug, Serialize, Deserialize)]
+struct Executed {
+    core: String,
+    target: String,
+    elf_sha256: String,
+    input_sha256: String,
+    run_id: String,
+}
+
+impl ExecutedCorpus {
+    /// Load from `artifacts/runs.jsonl` (empty if there is no history yet)
+    pub fn load() -> Self {
+        match Self::load_in(Path::new(RUNS_PATH), Path::new(INDEX_PATH)) {
+            Ok(corpus) => corpus,
+            Err(e) => {
+                eprintln!("   ⚠️  Not deduplicating against earlier campaigns: {:#}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Bring the index at `index_path` up to date with `runs_path` and load it
+    fn load_in(runs_path: &Path, index_path: &Path) -> Result<Self> {
+        let mut file = match fs::File::open(runs_path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to open {}", runs_path.display())),
+        };
+        let first_line = first_line(&mut file)?;
+        let len = file.metadata()?.len();
+        let snapshot = fs::read_to_string(index_path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<Snapshot>(&text).ok())
+            .filter(|snapshot| snapshot.offset <= len && snapshot.first_line == first_line)
+            .unwrap_or_default();
+
+        let mut corpus = ExecutedCorpus::default();
+        for executed in snapshot.runs {
+            corpus
+                .runs
+                .entry((executed.core, executed.target, executed.elf_sha256, executed.input_sha256))
+                .or_insert(executed.run_id);
+        }
+
+        // Only whole lines: one being appended is read next time
+        let mut appended = Vec::new();
+        file.seek(SeekFrom::Start(snapshot.offset))?;
+        file.read_to_end(&mut appended)?;
+        let complete = appended.iter().rposition(|byte| *byte == b'\n').map_or(0, |at| at + 1);
+        if complete == 0 && snapshot.offset > 0 {
+            return Ok(corpus);
+        }
+        let records: Vec<RunRecord> = String::from_utf8_lossy(&appended[..complete])
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        corpus.extend(&records);
+
+        let snapshot = Snapshot {
+            offset: snapshot.offset + complete as u64,
+            first_line,
+            runs: corpus
+                .runs
+                .iter()
+                .map(|((core, target, elf_sha256, input_sha256), run_id)| Executed {
+                    core: core.clone(),
+                    target: target.clone(),
+                    elf_sha256: elf_sha256.clone(),
+                    input_sha256: input_sha256.clone(),
+                    run_id: run_id.clone(),
+                })
+                .collect(),
+        };
+        let tmp = index_path.with_extension(format!("json.{}.tmp", std::process::id()));
+        fs::write(&tmp, serde_json::to_string(&snapshot)?)?;
+        fs::rename(&tmp, index_path).with_context(|| format!("Failed to write {}", index_path.display()))?;
+        Ok(corpus)
+    }
+
+    /// Index records that carry full provenance and a real result
+    ///
+    /// Runs where either side hit an infrastructure failure are left out,
+    /// so they are retried rather than skipped.
+    pub fn from_records(records: &[RunRecord]) -> Self {
+        let mut corpus = ExecutedCorpus::default();
+        corpus.extend(records);
+        corpus
+    }
+
+    /// Add records (see [`ExecutedCorpus::from_records`]); the earliest run
+    /// of an input is kept
+    fn extend(&mut self, records: &[RunRecord]) {
+        for record in records {
+            if record.native_status.is_infra() || record.sp1_status.is_infra() {
+                continue;
+            }
+            let (Some(input), Some(target), Some(elf)) =
+                (&record.input_sha256, &record.zkvm_target, &record.elf_sha256)
+            else {
+                continue;
+            };
+            self.runs
+                .entry((record.core.clone(), target.clone(), elf.clone(), input.clone()))
+                .or_insert_with(|| record.run_id.clone());
+        }
+    }
+
+    /// Run ID of an earlier run of this exact input, if any
+    pub fn find(&self, core: &str, target: &str, elf_sha256: &str, input_sha256: &str) -> Option<&str> {
+        self.runs
+            .get(&(
+                core.to_string(),
+                target.to_string(),
+                elf_sha256.to_string(),
+                input_sha256.to_string(),
+            ))
+            .map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.runs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+}
+
+/// The first line of a file, leaving it positioned anywhere
+fn first_line(file: &mut fs::File) -> Result<String> {
+    use std::io::BufRead;
+
+    let mut line = String::new();
+    std::io::BufReader::new(&mut *file).read_line(&mut line)?;
+    Ok(line)
+}
+
+/// SHA-256 of input bytes, hex-encoded like [`crate::sha256_file`]
+pub fn input_sha256(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_eq_oracle::Status;
+
+    fn record(run_id: &str, input: &str, sp1_status: Status) -> RunRecord {
+        RunRecord {
+            run_id: run_id.to_string(),
+            core: "fib".to_string(),
+            sp1_status,
+            input_sha256: Some(input.to_string()),
+            zkvm_target: Some("t".to_string()),
+            elf_sha256: Some("elf".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_finds_identical_input_and_skips_infra_errors() {
+        let corpus = ExecutedCorpus::from_records(&[
+            record("run_a", "aaa", Status::Ok),
+            record("run_b", "bbb", Status::InfraError),
+        ]);
+        assert_eq!(corpus.find("fib", "t", "elf", "aaa"), Some("run_a"));
+        assert_eq!(corpus.find("fib", "t", "other_elf", "aaa"), None);
+        assert_eq!(corpus.find("fib", "t", "elf", "bbb"), None);
+        assert_eq!(corpus.len(), 1);
+    }
+
+    #[test]
+    fn test_load_reads_only_appended_records() {
+        let dir = std::env::temp_dir().join(format!("harness-corpus-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let (runs, index) = (dir.join("runs.jsonl"), dir.join("corpus_index.json"));
+        let line = |record: RunRecord| serde_json::to_string(&record).unwrap() + "\n";
+
+        assert!(ExecutedCorpus::load_in(&runs, &index).unwrap().is_empty());
+        fs::write(&runs, line(record("run_a", "aaa", Status::Ok))).unwrap();
+        assert_eq!(ExecutedCorpus::load_in(&runs, &index).unwrap().len(), 1);
+
+        // A line still being written is left for the next load
+        let mut text = fs::read_to_string(&runs).unwrap() + &line(record("run_b", "bbb", Status::Ok));
+        text.push_str("{\"run_id\": \"run_c\"");
+        fs::write(&runs, &text).unwrap();
+        let corpus = ExecutedCorpus::load_in(&runs, &index).unwrap();
+        assert_eq!(corpus.find("fib", "t", "elf", "bbb"), Some("run_b"));
+        let mut snapshot: Snapshot = serde_json::from_str(&fs::read_to_string(&index).unwrap()).unwrap();
+        assert_eq!(snapshot.offset as usize, text.rfind('\n').unwrap() + 1);
+
+        // The index alone answers for what it covers
+        snapshot.runs.iter_mut().filter(|run| run.run_id == "run_a").for_each(|run| run.run_id = "run_x".to_string());
+        fs::write(&index, serde_json::to_string(&snapshot).unwrap()).unwrap();
+        assert_eq!(ExecutedCorpus::load_in(&runs, &index).unwrap().find("fib", "t", "elf", "aaa"), Some("run_x"));
+
+        // A replaced history is indexed from the start
+        fs::write(&runs, line(record("run_d", "ddd", Status::Ok))).unwrap();
+        let corpus = ExecutedCorpus::load_in(&runs, &index).unwrap();
+        assert_eq!((corpus.len(), corpus.find("fib", "t", "elf", "ddd")), (1, Some("run_d")));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
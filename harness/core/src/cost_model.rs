@@ -0,0 +1,414 @@
+//! Input-size-aware cost model
+//!
+//! Every logged run appends a [`RunRecord`] to `artifacts/runs.jsonl`.
+//! After a campaign, [`fit_cost_models`] fits SP1 cycles against input size
+//! per core (linear and quadratic least squares) and compares how fast zkVM
+//! cost grows relative to native time. A core whose cycles grow
+//! superlinearly while native time does not is a DoS risk for zkVM users:
+//! a small increase in input buys the attacker a large increase in proving
+//! cost.
+
+use crate::{sha256_file, ReproEnv};
+use anyhow::{Context, Result};
+use rust_eq_oracle::{Diff, DiffKind, RunResult, Status};
+use serde::{Deserialize, Serialize};
+use source_mutator::MutationOp;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+pub(crate) const RUNS_PATH: &str = "artifacts/runs.jsonl";
+
+/// Growth-exponent margin by which zkVM cost must outgrow native time to be flagged
+pub const SUPERLINEAR_MARGIN: f64 = 0.25;
+
+/// Distinct input sizes needed before a core is fitted
+pub const MIN_SIZES: usize = 3;
+
+/// One run, as recorded in `artifacts/runs.jsonl`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub run_id: String,
+    pub core: String,
+    pub input: String,
+    pub input_bytes: u64,
+    pub native_status: Status,
+    pub sp1_status: Status,
+    pub equal: bool,
+    /// Category of an inequality (absent for equal runs and older records)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff_kind: Option<DiffKind>,
+    pub native_ms: u128,
+    pub sp1_ms: u128,
+    pub sp1_cycles: Option<u64>,
+    /// SHA-256 of the input file (for corpus dedup; absent in older records)
+    #[serde(default)]
+    pub input_sha256: Option<String>,
+    #[serde(default)]
+    pub zkvm_target: Option<String>,
+    #[serde(default)]
+    pub elf_sha256: Option<String>,
+    /// Strategy, parameters and parent hash of a fuzzed input (None for plain runs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mutation: Option<MutationOp>,
+    /// Campaign the run belongs to (`harness --campaign`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub campaign: Option<String>,
+    /// SP1 instructions executed per opcode (`meta.opcode_counts`; see
+    /// [`crate::opcode_mix`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sp1_opcodes: Option<BTreeMap<String, u64>>,
+}
+
+/// An equal run where both sides finished `OK` and nothing else is known
+///
+/// A base for records built field by field (`RunRecord { core, ..Default::default() }`).
+impl Default for RunRecord {
+    fn default() -> Self {
+        RunRecord {
+            run_id: String::new(),
+            core: String::new(),
+            input: String::new(),
+            input_bytes: 0,
+            native_status: Status::Ok,
+            sp1_status: Status::Ok,
+            equal: true,
+            diff_kind: None,
+            native_ms: 0,
+            sp1_ms: 0,
+            sp1_cycles: None,
+            input_sha256: None,
+            zkvm_target: None,
+            elf_sha256: None,
+            mutation: None,
+            campaign: None,
+            sp1_opcodes: None,
+        }
+    }
+}
+
+impl RunRecord {
+    pub fn new(
+        run_id: &str,
+        core_name: &str,
+        input_path: &Path,
+        native: &RunResult,
+        sp1: &RunResult,
+        diff: &Diff,
+        env: &ReproEnv,
+    ) -> Self {
+        RunRecord {
+            run_id: run_id.to_string(),
+            core: core_name.to_string(),
+            input: input_path.display().to_string(),
+            input_bytes: input_bytes(core_name, input_path),
+            native_status: native.status,
+            sp1_status: sp1.status,
+            equal: diff.equal,
+            diff_kind: diff.kind.clone(),
+            native_ms: native.elapsed_ms,
+            sp1_ms: sp1.elapsed_ms,
+            sp1_cycles: sp1.meta["cycles"].as_u64(),
+            input_sha256: sha256_file(input_path),
+            zkvm_target: Some(env.zkvm_target.clone()),
+            elf_sha256: env.elf_sha256.clone(),
+            mutation: None,
+            campaign: crate::campaign::current_id(),
+            sp1_opcodes: serde_json::from_value(sp1.meta["opcode_counts"].clone()).ok(),
+        }
+    }
+}
+
+/// Size of an input file and the sidecar files it refers to
+pub(crate) fn input_bytes(core_name: &str, input_path: &Path) -> u64 {
+    let file_len = |path: &Path| fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let sidecars = match (crate::input_preprocess(core_name), fs::read(input_path)) {
+        (Ok(preprocess), Ok(bytes)) => preprocess.sidecars(&bytes),
+        _ => Vec::new(),
+    };
+    file_len(input_path) + sidecars.iter().map(|name| file_len(&input_path.with_file_name(name))).sum::<u64>()
+}
+
+/// Append a record to `artifacts/runs.jsonl` (one `O_APPEND` write per line)
+pub fn append_run_record(record: &RunRecord) -> Result<()> {
+    let mut line = serde_json::to_vec(record)?;
+    line.push(b'\n');
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(RUNS_PATH)
+        .context("Failed to open artifacts/runs.jsonl")?
+        .write_all(&line)?;
+    Ok(())
+}
+
+/// Read all records from `artifacts/runs.jsonl`, skipping malformed lines
+pub fn load_run_records() -> Result<Vec<RunRecord>> {
+    let content = fs::read_to_string(RUNS_PATH)
+        .with_context(|| format!("No run records at {} (run `harness fuzz` first)", RUNS_PATH))?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Least-squares polynomial `y = c0 + c1·x + c2·x²` (c2 = 0 for linear fits)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fit {
+    pub coefficients: [f64; 3],
+    pub r_squared: f64,
+}
+
+/// Fitted cost model of one core
+#[derive(Debug, Clone)]
+pub struct CostModel {
+    pub core: String,
+    /// Runs used (both sides OK, cycles known)
+    pub samples: usize,
+    pub distinct_sizes: usize,
+    /// Cycles vs input bytes
+    pub linear: Fit,
+    pub quadratic: Fit,
+    /// Growth exponent k of cycles ~ bytes^k (log-log slope)
+    pub cycles_exponent: f64,
+    /// Growth exponent of native elapsed_ms ~ bytes^k
+    pub native_exponent: f64,
+}
+
+impl CostModel {
+    /// "quadratic" if a growing x² term removes at least half of the
+    /// variance a line leaves unexplained
+    pub fn best_fit(&self) -> &'static str {
+        let linear_residual = 1.0 - self.linear.r_squared;
+        let quadratic_residual = 1.0 - self.quadratic.r_squared;
+        if self.quadratic.coefficients[2] > 0.0
+            && linear_residual > 1e-6
+            && quadratic_residual < linear_residual * 0.5
+        {
+            "quadratic"
+        } else {
+            "linear"
+        }
+    }
+
+    /// zkVM cost grows faster than native time by more than the margin
+    pub fn is_superlinear(&self) -> bool {
+        self.cycles_exponent - self.native_exponent > SUPERLINEAR_MARGIN
+    }
+}
+
+/// Fit a cost model per core from run records
+///
+/// Only runs where both sides finished OK and SP1 reported cycles are used;
+/// cores with fewer than [`MIN_SIZES`] distinct input sizes are skipped.
+pub fn fit_cost_models(records: &[RunRecord]) -> Vec<CostModel> {
+    let mut by_core: BTreeMap<&str, Vec<&RunRecord>> = BTreeMap::new();
+    for record in records {
+        let both_ok = record.native_status == Status::Ok && record.sp1_status == Status::Ok;
+        if both_ok && record.sp1_cycles.is_some() {
+            by_core.entry(&record.core).or_default().push(record);
+        }
+    }
+
+    by_core
+        .into_iter()
+        .filter_map(|(core, runs)| {
+            let mut sizes: Vec<u64> = runs.iter().map(|r| r.input_bytes).collect();
+            sizes.sort_unstable();
+            sizes.dedup();
+            if sizes.len() < MIN_SIZES {
+                return None;
+            }
+
+            let xs: Vec<f64> = runs.iter().map(|r| r.input_bytes as f64).collect();
+            let cycles: Vec<f64> = runs.iter().map(|r| r.sp1_cycles.unwrap() as f64).collect();
+            let native: Vec<f64> = runs.iter().map(|r| r.native_ms as f64).collect();
+
+            Some(CostModel {
+                core: core.to_string(),
+                samples: runs.len(),
+                distinct_sizes: sizes.len(),
+                linear: polyfit(&xs, &cycles, 1),
+                quadratic: polyfit(&xs, &cycles, 2),
+                cycles_exponent: log_log_slope(&xs, &cycles),
+                native_exponent: log_log_slope(&xs, &native),
+            })
+        })
+        .collect()
+}
+
+/// Least-squares polynomial fit of degree 1 or 2
+///
+/// x is scaled to [0, 1] before solving the normal equations to keep them
+/// well conditioned for MB-sized inputs; coefficients are scaled back.
+pub fn polyfit(xs: &[f64], ys: &[f64], degree: usize) -> Fit {
+    let scale = xs.iter().cloned().fold(0.0, f64::max).max(1.0);
+    let terms = degree + 1;
+
+    // Normal equations A·c = b with A[i][j] = Σ x^(i+j), b[i] = Σ y·x^i
+    let mut a = [[0.0f64; 4]; 3];
+    for (&x, &y) in xs.iter().zip(ys) {
+        let x = x / scale;
+        for (i, row) in a.iter_mut().enumerate().take(terms) {
+            for (j, cell) in row.iter_mut().enumerate().take(terms) {
+                *cell += x.powi((i + j) as i32);
+            }
+            row[3] += y * x.powi(i as i32);
+        }
+    }
+    let solved = solve(&mut a, terms);
+
+    let mut coefficients = [0.0; 3];
+    for (i, c) in solved.iter().enumerate().take(terms) {
+        coefficients[i] = c / scale.powi(i as i32);
+    }
+
+    let mean = ys.iter().sum::<f64>() / ys.len().max(1) as f64;
+    let ss_tot: f64 = ys.iter().map(|y| (y - mean).powi(2)).sum();
+    let ss_res: f64 = xs
+        .iter()
+        .zip(ys)
+        .map(|(&x, &y)| {
+            let predicted = coefficients[0] + coefficients[1] * x + coefficients[2] * x * x;
+            (y - predicted).powi(2)
+        })
+        .sum();
+    let r_squared = if ss_tot > 0.0 { 1.0 - ss_res / ss_tot } else { 1.0 };
+
+    Fit {
+        coefficients,
+        r_squared,
+    }
+}
+
+/// Gauss-Jordan elimination on an augmented `n x (n+1)` system (singular → 0)
+fn solve(a: &mut [[f64; 4]; 3], n: usize) -> [f64; 3] {
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+            .unwrap();
+        a.swap(col, pivot);
+        if a[col][col].abs() < 1e-12 {
+            continue;
+        }
+        let pivot_row = a[col];
+        for (row, cells) in a.iter_mut().enumerate().take(n) {
+            if row != col {
+                let factor = cells[col] / pivot_row[col];
+                for (cell, pivot) in cells.iter_mut().zip(pivot_row).skip(col) {
+                    *cell -= factor * pivot;
+                }
+            }
+        }
+    }
+
+    let mut solution = [0.0; 3];
+    for i in 0..n {
+        if a[i][i].abs() >= 1e-12 {
+            solution[i] = a[i][3] / a[i][i];
+        }
+    }
+    solution
+}
+
+/// Slope of ln(y) against ln(x) over points with x, y > 0 (0 if undefined)
+pub fn log_log_slope(xs: &[f64], ys: &[f64]) -> f64 {
+    let (lx, ly): (Vec<f64>, Vec<f64>) = xs
+        .iter()
+        .zip(ys)
+        .filter(|(&x, &y)| x > 0.0 && y > 0.0)
+        .map(|(x, y)| (x.ln(), y.ln()))
+        .unzip();
+    if lx.len() < 2 {
+        return 0.0;
+    }
+    slope(&lx, &ly)
+}
+
+fn slope(xs: &[f64], ys: &[f64]) -> f64 {
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let cov: f64 = xs.iter().zip(ys).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let var: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    if var > 0.0 {
+        cov / var
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(core: &str, bytes: u64, native_ms: u128, cycles: u64) -> RunRecord {
+        RunRecord {
+            core: core.to_string(),
+            input_bytes: bytes,
+            native_ms,
+            sp1_cycles: Some(cycles),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_polyfit_recovers_quadratic() {
+        let xs: Vec<f64> = (1..=8).map(|i| (i * 1000) as f64).collect();
+        let ys: Vec<f64> = xs.iter().map(|x| 5.0 + 2.0 * x + 0.5 * x * x).collect();
+        let fit = polyfit(&xs, &ys, 2);
+        assert!((fit.coefficients[2] - 0.5).abs() < 1e-6);
+        assert!(fit.r_squared > 0.999_999);
+    }
+
+    #[test]
+    fn test_linear_core_not_flagged() {
+        let records: Vec<RunRecord> = [1024u64, 4096, 16384, 65536]
+            .iter()
+            .map(|&n| record("io_echo", n, n as u128 / 100 + 1, 10 * n + 5000))
+            .collect();
+        let models = fit_cost_models(&records);
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].best_fit(), "linear");
+        assert!(!models[0].is_superlinear());
+    }
+
+    #[test]
+    fn test_quadratic_zkvm_cost_flagged() {
+        let records: Vec<RunRecord> = [1024u64, 4096, 16384, 65536]
+            .iter()
+            .map(|&n| record("sorting", n, n as u128 / 100 + 1, n * n / 10))
+            .collect();
+        let models = fit_cost_models(&records);
+        assert_eq!(models[0].best_fit(), "quadratic");
+        assert!(models[0].is_superlinear());
+    }
+
+    #[test]
+    fn test_mutation_op_round_trips() {
+        let mut op = MutationOp::new(
+            source_mutator::MutationStrategy::BoundaryValues,
+            serde_json::json!({ "a": u32::MAX, "operation": "add" }),
+        );
+        op.parent_sha256 = "ab".repeat(32);
+        let mut with_op = record("arithmetic", 16, 1, 100);
+        with_op.mutation = Some(op.clone());
+
+        let line = serde_json::to_string(&with_op).unwrap();
+        assert!(line.contains(r#""strategy":"boundary_values""#));
+        let parsed: RunRecord = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed.mutation, Some(op));
+
+        // Records written before mutations were structured still load
+        let mut legacy: serde_json::Value = serde_json::to_value(record("fib", 16, 1, 100)).unwrap();
+        legacy.as_object_mut().unwrap().remove("mutation");
+        assert!(serde_json::from_value::<RunRecord>(legacy).unwrap().mutation.is_none());
+    }
+
+    #[test]
+    fn test_too_few_sizes_skipped() {
+        let records = vec![record("fib", 16, 1, 100), record("fib", 16, 1, 100)];
+        assert!(fit_cost_models(&records).is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+//! Distributed fuzzing: coordinator work queue and wire protocol
+//!
+//! `harness coordinate` generates the mutations of a fuzz campaign and hands
+//! them out in batches to `harness worker --connect <addr>` processes on other
+//! machines. Workers execute native and SP1 and send the raw [`RunResult`]s
+//! back; the coordinator compares, logs and deduplicates them centrally, so
+//! `artifacts/` on the coordinator looks as if the campaign ran locally.
+//!
+//! Tasks are leased, not handed over: a task whose worker does not report
+//! back within the lease timeout (crashed machine, lost connection) is handed
+//! out again, and a late duplicate result is ignored.
+//!
+//! Workers authenticate with a token shared with the coordinator, and say
+//! which SP1 version they run and which ELF produced each result. The
+//! coordinator only accepts results from the [`Toolchain`] it runs itself,
+//! so a misconfigured worker (or anything else that can reach the port)
+//! cannot log and promote results from another guest.
+
+use anyhow::Result;
+use rust_eq_oracle::RunResult;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// One mutated input to execute
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Task {
+    pub id: u64,
+    pub core: String,
+    /// Mutation label, for worker output
+    pub label: String,
+    pub input: serde_json::Value,
+}
+
+/// `POST /work`: a worker asking for tasks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkRequest {
+    pub worker: String,
+    pub max_tasks: usize,
+    pub sp1_version: String,
+}
+
+/// Reply to `POST /work`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkBatch {
+    pub tasks: Vec<Task>,
+    /// No task is left or in flight; the worker can exit
+    pub finished: bool,
+}
+
+/// Both sides of one executed task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskResult {
+    pub id: u64,
+    pub native: RunResult,
+    pub sp1: RunResult,
+    /// SHA-256 of the guest ELF SP1 ran (None if it failed to build)
+    pub elf_sha256: Option<String>,
+}
+
+/// `POST /results`: a worker reporting executed tasks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultBatch {
+    pub worker: String,
+    pub sp1_version: String,
+    pub results: Vec<TaskResult>,
+}
+
+/// Reply to `POST /results`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultAck {
+    /// Results recorded (duplicates of already completed tasks are dropped)
+    pub accepted: usize,
+    /// Results from another guest ELF, left for another worker
+    pub rejected: usize,
+}
+
+/// The SP1 version and guest ELFs the coordinator accepts results from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toolchain {
+    pub sp1_version: String,
+    /// SHA-256 of each core's ELF
+    pub elf_sha256: BTreeMap<String, String>,
+}
+
+impl Toolchain {
+    /// Refuse a worker running another SP1 version
+    pub fn check_worker(&self, sp1_version: &str) -> Result<()> {
+        anyhow::ensure!(
+            sp1_version == self.sp1_version,
+            "worker runs SP1 '{}', the coordinator runs '{}'",
+            sp1_version,
+            self.sp1_version
+        );
+        Ok(())
+    }
+
+    /// Refuse a result of `core` from another ELF
+    ///
+    /// A result without an ELF is accepted only as an infrastructure failure
+    /// (the worker could not build the guest).
+    pub fn check_result(&self, core: &str, result: &TaskResult) -> Result<()> {
+        let expected = self.elf_sha256.get(core).map(String::as_str);
+        match result.elf_sha256.as_deref() {
+            None if result.sp1.status.is_infra() => Ok(()),
+            None => anyhow::bail!("{} result without an ELF hash", core),
+            Some(actual) if Some(actual) == expected => Ok(()),
+            Some(actual) => anyhow::bail!(
+                "{} ELF {} does not match the coordinator's {}",
+                core,
+                actual,
+                expected.unwrap_or("(none)")
+            ),
+        }
+    }
+}
+
+struct Lease {
+    worker: String,
+    since: Instant,
+    task: Task,
+}
+
+/// Tasks of a distributed campaign, leased to workers until they report back
+pub struct WorkQueue {
+    pending: VecDeque<Task>,
+    leased: BTreeMap<u64, Lease>,
+    completed: usize,
+    total: usize,
+    lease_timeout: Duration,
+}
+
+impl WorkQueue {
+    pub fn new(tasks: Vec<Task>, lease_timeout: Duration) -> Self {
+        WorkQueue {
+            total: tasks.len(),
+            pending: tasks.into(),
+            leased: BTreeMap::new(),
+            completed: 0,
+            lease_timeout,
+        }
+    }
+
+    /// Lease up to `max_tasks` tasks to `worker`, first re-queueing tasks
+    /// whose lease expired
+    pub fn lease(&mut self, worker: &str, max_tasks: usize) -> WorkBatch {
+        self.lease_at(worker, max_tasks, Instant::now())
+    }
+
+    fn lease_at(&mut self, worker: &str, max_tasks: usize, now: Instant) -> WorkBatch {
+        let expired: Vec<u64> = self
+            .leased
+            .iter()
+            .filter(|(_, lease)| now.duration_since(lease.since) >= self.lease_timeout)
+            .map(|(id, _)| *id)
+            .collect();
+        // Ahead of the untouched tasks, in their original order
+        for id in expired.into_iter().rev() {
+            let lease = self.leased.remove(&id).unwrap();
+            println!("   ⏰ Task {} timed out on worker '{}', re-queued", id, lease.worker);
+            self.pending.push_front(lease.task);
+        }
+
+        let count = max_tasks.max(1).min(self.pending.len());
+        let tasks: Vec<Task> = self.pending.drain(..count).collect();
+        for task in &tasks {
+            self.leased.insert(
+                task.id,
+                Lease {
+                    worker: worker.to_string(),
+                    since: now,
+                    task: task.clone(),
+                },
+            );
+        }
+        WorkBatch {
+            tasks,
+            finished: self.is_finished(),
+        }
+    }
+
+    /// Mark a task done; false if it is unknown or was already completed
+    pub fn complete(&mut self, id: u64) -> bool {
+        if self.leased.remove(&id).is_some() {
+            self.completed += 1;
+            return true;
+        }
+        // Reported after its lease expired and before it was handed out again
+        match self.pending.iter().position(|task| task.id == id) {
+            Some(idx) => {
+                self.pending.remove(idx);
+                self.completed += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.pending.is_empty() && self.leased.is_empty()
+    }
+
+    /// (completed, in flight, total)
+    pub fn progress(&self) -> (usize, usize, usize) {
+        (self.completed, self.leased.len(), self.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tasks(n: u64) -> Vec<Task> {
+        (0..n)
+            .map(|id| Task {
+                id,
+                core: "fib".to_string(),
+                label: format!("m{}", id),
+                input: serde_json::json!({ "n": id }),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_leases_expire_and_duplicates_are_dropped() {
+        let start = Instant::now();
+        let mut queue = WorkQueue::new(tasks(3), Duration::from_secs(60));
+
+        let a = queue.lease_at("a", 2, start);
+        assert_eq!(a.tasks.iter().map(|t| t.id).collect::<Vec<_>>(), vec![0, 1]);
+        let b = queue.lease_at("b", 2, start);
+        assert_eq!(b.tasks.iter().map(|t| t.id).collect::<Vec<_>>(), vec![2]);
+        assert!(!b.finished);
+        assert!(queue.complete(2));
+        assert!(!queue.complete(2));
+
+        // Worker "a" went silent: its tasks go to "b" once the lease expires
+        assert!(queue.lease_at("b", 5, start + Duration::from_secs(30)).tasks.is_empty());
+        let retried = queue.lease_at("b", 5, start + Duration::from_secs(61));
+        assert_eq!(retried.tasks.iter().map(|t| t.id).collect::<Vec<_>>(), vec![0, 1]);
+        assert!(queue.complete(0) && queue.complete(1));
+        assert!(!queue.complete(0), "late result from 'a' is a duplicate");
+
+        assert!(queue.is_finished());
+        assert_eq!(queue.progress(), (3, 0, 3));
+        assert!(queue.lease_at("c", 1, start).finished);
+    }
+
+    #[test]
+    fn test_toolchain_checks() {
+        let toolchain = Toolchain {
+            sp1_version: "cargo-prove sp1 (5.2.2)".to_string(),
+            elf_sha256: BTreeMap::from([("fib".to_string(), "ab".repeat(32))]),
+        };
+        assert!(toolchain.check_worker("cargo-prove sp1 (5.2.2)").is_ok());
+        assert!(toolchain.check_worker("cargo-prove sp1 (4.0.0)").is_err());
+
+        use rust_eq_oracle::Status;
+        let run = |status| RunResult {
+            status,
+            elapsed_ms: 0,
+            commits: vec![],
+            meta: serde_json::json!({}),
+        };
+        let result = |elf: Option<String>, status| TaskResult {
+            id: 0,
+            native: run(Status::Ok),
+            sp1: run(status),
+            elf_sha256: elf,
+        };
+        assert!(toolchain.check_result("fib", &result(Some("ab".repeat(32)), Status::Ok)).is_ok());
+        assert!(toolchain.check_result("fib", &result(Some("cd".repeat(32)), Status::Ok)).is_err());
+        assert!(toolchain.check_result("arithmetic", &result(Some("ab".repeat(32)), Status::Ok)).is_err());
+        assert!(toolchain.check_result("fib", &result(None, Status::Ok)).is_err());
+        assert!(toolchain.check_result("fib", &result(None, Status::InfraError)).is_ok());
+    }
+}
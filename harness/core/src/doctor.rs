@@ -0,0 +1,326 @@
+//! Setup self-test for `harness doctor`
+//!
+//! A campaign on a machine without the SP1 toolchain, with guests that do
+//! not build or with a runner that never times out only shows it hours
+//! later, as a summary full of INFRA_ERROR and BUILD_FAILURE rows. The
+//! doctor checks the setup up front, each check saying how to fix what it
+//! found:
+//!
+//! - the SP1 toolchain (`cargo prove`, the `succinct` Rust toolchain) and rustc
+//! - the canary guests build (or, with `--skip-build`, have fresh ELFs)
+//! - [`CANARY`] round-trips through both runners with its known commits
+//! - an endless loop ends as TIMEOUT natively and as CYCLE_LIMIT or TIMEOUT
+//!   on SP1
+
+use crate::{elf_path_for_core, guest_path_for_core, RetryPolicy};
+use rust_eq_oracle::{RunResult, Status};
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+/// A core, an input and the commits every correct setup produces for it
+pub struct KnownAnswer {
+    pub core: &'static str,
+    pub input: &'static str,
+    pub commits: &'static [u64],
+}
+
+/// `fib(24)` modulo 7919: n, a, b
+pub const CANARY: KnownAnswer = KnownAnswer {
+    core: "fib",
+    input: "inputs/fib_24.json",
+    commits: &[24, 6773, 3754],
+};
+
+/// Core and input that loop forever
+const ENDLESS_CORE: &str = "timeout_test";
+const ENDLESS_INPUT: &str = "inputs/timeout_infinite.json";
+
+/// Native timeout given to the endless loop
+const NATIVE_TIMEOUT_SECS: u64 = 2;
+/// `sp1-runner`'s default timeout
+const SP1_TIMEOUT_SECS: u64 = 30;
+/// How long a runner may take beyond its timeout (startup, `cargo run`)
+const TIMEOUT_SLACK: Duration = Duration::from_secs(20);
+
+/// Guests the checks run
+pub const GUESTS: [&str; 2] = [CANARY.core, ENDLESS_CORE];
+
+const INSTALL_SP1: &str = "install SP1 with `curl -L https://sp1.succinct.xyz | bash`, then run `sp1up`";
+
+/// How one check went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Pass(String),
+    /// What is wrong, and how to fix it
+    Fail { problem: String, fix: String },
+    /// Not run, because an earlier check failed
+    Skip(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub outcome: Outcome,
+}
+
+impl Check {
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Check {
+        Check {
+            name: name.into(),
+            outcome: Outcome::Pass(detail.into()),
+        }
+    }
+
+    fn fail(name: impl Into<String>, problem: impl Into<String>, fix: impl Into<String>) -> Check {
+        Check {
+            name: name.into(),
+            outcome: Outcome::Fail {
+                problem: problem.into(),
+                fix: fix.into(),
+            },
+        }
+    }
+
+    fn skip(name: impl Into<String>, why: impl Into<String>) -> Check {
+        Check {
+            name: name.into(),
+            outcome: Outcome::Skip(why.into()),
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        matches!(self.outcome, Outcome::Pass(_))
+    }
+}
+
+/// `cargo prove`, the `succinct` toolchain and rustc
+pub fn toolchain() -> Vec<Check> {
+    let mut checks = Vec::new();
+    let sp1 = crate::get_sp1_version();
+    checks.push(if sp1 == "unknown" {
+        Check::fail("SP1 toolchain", "`cargo prove --version` failed", INSTALL_SP1)
+    } else {
+        Check::pass("SP1 toolchain", sp1)
+    });
+
+    let toolchains = Command::new("rustup")
+        .args(["toolchain", "list"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned());
+    checks.push(match toolchains {
+        Some(list) if list.lines().any(|line| line.contains("succinct")) => {
+            Check::pass("succinct Rust toolchain", "installed")
+        }
+        Some(_) => Check::fail("succinct Rust toolchain", "not in `rustup toolchain list`", "run `sp1up`"),
+        None => Check::fail("succinct Rust toolchain", "`rustup` not found", "install rustup from https://rustup.rs, then run `sp1up`"),
+    });
+
+    let rustc = crate::get_rustc_version();
+    checks.push(if rustc == "unknown" {
+        Check::fail("rustc", "`rustc --version` failed", "install Rust from https://rustup.rs")
+    } else {
+        Check::pass("rustc", rustc)
+    });
+    checks
+}
+
+/// Build the guest of `core_name`, or with `skip_build` check its ELF
+pub fn guest(core_name: &str, skip_build: bool, retry: &RetryPolicy) -> Check {
+    let name = format!("{} guest", core_name);
+    let guest_path = guest_path_for_core(core_name);
+    if skip_build {
+        let elf_path = elf_path_for_core(core_name);
+        if !elf_path.exists() {
+            return Check::fail(name, format!("no ELF at {}", elf_path.display()), "run without --skip-build to build it");
+        }
+        return match crate::provenance::find_stale_core(core_name) {
+            Some(stale) => Check::fail(name, stale.to_string(), "run without --skip-build to rebuild it"),
+            None => Check::pass(name, "ELF present and up to date"),
+        };
+    }
+    match retry.run("SP1 guest build", || crate::build_sp1_guest(&guest_path)).0 {
+        Ok(build) => Check::pass(name, format!("built ({})", build.summary())),
+        Err(e) => Check::fail(
+            name,
+            format!("{:#}", e),
+            format!(
+                "fix the SP1 toolchain checks above if any failed, else build by hand with `cd {} && cargo prove build`",
+                guest_path.display()
+            ),
+        ),
+    }
+}
+
+/// Hold a run of the canary to its known commits
+pub fn check_known_answer(runner: &str, result: &RunResult, known: &KnownAnswer) -> Check {
+    let name = format!("{} round trip", runner);
+    let expected: Vec<serde_json::Value> = known.commits.iter().map(|&value| value.into()).collect();
+    match result.status {
+        Status::Ok if result.commits == expected => {
+            Check::pass(name, format!("{} on {}: {:?} in {} ms", known.core, known.input, known.commits, result.elapsed_ms))
+        }
+        Status::Ok => Check::fail(
+            name,
+            format!(
+                "{} on {} committed {} instead of {}",
+                known.core,
+                known.input,
+                serde_json::Value::from(result.commits.clone()),
+                serde_json::Value::from(expected)
+            ),
+            "the runner or its commit decoding is broken; rebuild from a clean checkout",
+        ),
+        status => Check::fail(
+            name,
+            format!("{} on {} ended with {:?}: {}", known.core, known.input, status, failure_detail(result)),
+            match runner {
+                "native" => "check that `cargo build --release --bin native-runner` works".to_string(),
+                _ => format!("check that `cargo build --release --bin sp1-runner` works; {}", INSTALL_SP1),
+            },
+        ),
+    }
+}
+
+/// The error, panic or stderr a failed result carries
+fn failure_detail(result: &RunResult) -> String {
+    ["error", "panic_msg", "stderr"]
+        .iter()
+        .find_map(|key| result.meta.get(key).and_then(|value| value.as_str()))
+        .and_then(|text| text.lines().find(|line| !line.trim().is_empty()))
+        .unwrap_or("no details")
+        .to_string()
+}
+
+/// Run [`CANARY`] natively and on SP1 (`sp1_ok`: its guest built)
+pub fn round_trips(sp1_ok: bool, retry: &RetryPolicy) -> Vec<Check> {
+    let input = Path::new(CANARY.input);
+    let native = retry.run_runner("native", || crate::run_native_runner(CANARY.core, input));
+    let mut checks = vec![check_known_answer("native", &native, &CANARY)];
+    checks.push(if sp1_ok {
+        let sp1 = retry.run_runner("sp1", || crate::run_sp1_runner(&elf_path_for_core(CANARY.core), input, CANARY.core));
+        check_known_answer("SP1", &sp1, &CANARY)
+    } else {
+        Check::skip("SP1 round trip", format!("the {} guest did not build", CANARY.core))
+    });
+    checks
+}
+
+/// Hold the endless loop's result on one runner to the statuses that mean
+/// it was stopped
+fn check_stopped(runner: &str, result: &RunResult, wall: Duration, limit: Duration, stopped: &[Status], fix: &str) -> Check {
+    let name = format!("{} timeout", runner);
+    if result.status.is_infra() {
+        return Check::fail(
+            name,
+            format!("the runner did not run: {}", failure_detail(result)),
+            "fix the round trip checks above first",
+        );
+    }
+    if !stopped.contains(&result.status) {
+        return Check::fail(
+            name,
+            format!("an endless loop ended with {:?}: {}", result.status, failure_detail(result)),
+            fix,
+        );
+    }
+    if wall > limit {
+        return Check::fail(
+            name,
+            format!("stopped as {:?}, but only after {:.1}s", result.status, wall.as_secs_f64()),
+            fix,
+        );
+    }
+    Check::pass(name, format!("endless loop stopped as {:?} after {:.1}s", result.status, wall.as_secs_f64()))
+}
+
+/// Run the endless loop natively with a short timeout and on SP1 against
+/// its cycle budget
+pub fn timeouts(sp1_ok: bool) -> Vec<Check> {
+    let start = Instant::now();
+    let native = Command::new("cargo")
+        .args(["run", "--profile", "release", "--bin", "native-runner", "--"])
+        .args(["--core", ENDLESS_CORE, "--input", ENDLESS_INPUT, "--timeout", &NATIVE_TIMEOUT_SECS.to_string()])
+        .output()
+        .map_err(anyhow::Error::from)
+        .and_then(|output| serde_json::from_slice::<RunResult>(&output.stdout).map_err(Into::into));
+    let native = match native {
+        Ok(result) => result,
+        Err(e) => RunResult {
+            status: Status::InfraError,
+            elapsed_ms: 0,
+            commits: vec![],
+            meta: serde_json::json!({ "error": format!("{:#}", e) }),
+        },
+    };
+    let mut checks = vec![check_stopped(
+        "native",
+        &native,
+        start.elapsed(),
+        Duration::from_secs(NATIVE_TIMEOUT_SECS) + TIMEOUT_SLACK,
+        &[Status::Timeout],
+        "the native runner's worker watchdog is not stopping runs; check `native-runner --timeout`",
+    )];
+
+    if !sp1_ok {
+        checks.push(Check::skip("SP1 timeout", format!("the {} guest did not build", ENDLESS_CORE)));
+        return checks;
+    }
+    let start = Instant::now();
+    let sp1 = crate::run_sp1_runner(&elf_path_for_core(ENDLESS_CORE), Path::new(ENDLESS_INPUT), ENDLESS_CORE)
+        .unwrap_or_else(|e| RunResult {
+            status: Status::InfraError,
+            elapsed_ms: 0,
+            commits: vec![],
+            meta: serde_json::json!({ "error": format!("{:#}", e) }),
+        });
+    // Stopped by the core's cycle budget, or else by the runner's timeout
+    checks.push(check_stopped(
+        "SP1",
+        &sp1,
+        start.elapsed(),
+        Duration::from_secs(SP1_TIMEOUT_SECS) + TIMEOUT_SLACK,
+        &[Status::CycleLimit, Status::Timeout],
+        "the SP1 runner is not enforcing `--max-cycles` or `--timeout`; check `sp1-runner` and the cycle budget of timeout_test",
+    ));
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(status: Status, commits: &[u64]) -> RunResult {
+        RunResult {
+            status,
+            elapsed_ms: 1,
+            commits: commits.iter().map(|&value| value.into()).collect(),
+            meta: serde_json::json!({ "error": "sp1-runner failed: no ELF\nmore" }),
+        }
+    }
+
+    #[test]
+    fn test_known_answer() {
+        assert!(check_known_answer("native", &result(Status::Ok, &[24, 6773, 3754]), &CANARY).passed());
+
+        let wrong = check_known_answer("native", &result(Status::Ok, &[24, 6773, 3755]), &CANARY);
+        let Outcome::Fail { problem, .. } = wrong.outcome else { panic!("{:?}", wrong) };
+        assert_eq!(problem, "fib on inputs/fib_24.json committed [24,6773,3755] instead of [24,6773,3754]");
+
+        let infra = check_known_answer("SP1", &result(Status::InfraError, &[]), &CANARY);
+        let Outcome::Fail { problem, fix } = infra.outcome else { panic!("{:?}", infra) };
+        assert!(problem.ends_with("InfraError: sp1-runner failed: no ELF"), "{}", problem);
+        assert!(fix.contains("sp1up"));
+    }
+
+    #[test]
+    fn test_check_stopped() {
+        let limit = Duration::from_secs(5);
+        let stopped = [Status::Timeout];
+        assert!(check_stopped("native", &result(Status::Timeout, &[]), Duration::from_secs(2), limit, &stopped, "").passed());
+        assert!(!check_stopped("native", &result(Status::Timeout, &[]), Duration::from_secs(9), limit, &stopped, "").passed());
+        assert!(!check_stopped("native", &result(Status::Ok, &[0]), Duration::from_secs(1), limit, &stopped, "").passed());
+    }
+}
@@ -0,0 +1,151 @@
+//! Environment snapshots
+//!
+//! The SP1 and rustc versions and the git revision are the same for every
+//! run of a harness process, but asking `cargo prove`, `rustc` and `git`
+//! for them costs several process spawns per run. They are captured once
+//! per process ([`current`]) into a [`Snapshot`] saved as
+//! `artifacts/environments/<id>.json`, and each `summary.csv` row records
+//! only the snapshot's `env_id`. A long-lived process that notices the
+//! environment changed under it (`harness soak`) [`replace`]s the snapshot,
+//! so later rows are logged under the new id.
+//!
+//! Snapshot ids are hashes of their contents, so processes (and campaign
+//! sessions) with the same toolchain and revision share one file, and a
+//! row's `env_id` always resolves to what it was logged with.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Where snapshots are saved, next to `summary.csv`
+pub const ENVIRONMENTS_DIR: &str = "artifacts/environments";
+
+/// Toolchain and source revision shared by a process's runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// `env_` and the first 12 hex digits of the SHA-256 of the other fields
+    pub id: String,
+    pub sp1_version: String,
+    pub rustc_version: String,
+    pub zkvm_target: String,
+    /// `HEAD` of the checkout (None outside a git repository)
+    pub git_commit: Option<String>,
+    /// Uncommitted changes under [`crate::provenance::SOURCE_PATHS`] when
+    /// the snapshot was captured
+    pub git_dirty: Option<bool>,
+}
+
+impl Snapshot {
+    /// A snapshot of the given values, with its id
+    pub fn new(
+        sp1_version: &str,
+        rustc_version: &str,
+        zkvm_target: &str,
+        git_commit: Option<String>,
+        git_dirty: Option<bool>,
+    ) -> Snapshot {
+        use sha2::{Digest, Sha256};
+
+        let mut snapshot = Snapshot {
+            id: String::new(),
+            sp1_version: sp1_version.to_string(),
+            rustc_version: rustc_version.to_string(),
+            zkvm_target: zkvm_target.to_string(),
+            git_commit,
+            git_dirty,
+        };
+        let contents = serde_json::to_vec(&snapshot).expect("snapshots serialize");
+        snapshot.id = format!("env_{}", &format!("{:x}", Sha256::digest(&contents))[..12]);
+        snapshot
+    }
+
+    /// Ask the toolchain and git
+    pub fn capture() -> Snapshot {
+        let git = crate::provenance::GitInfo::capture();
+        Snapshot::new(
+            &crate::get_sp1_version(),
+            &crate::get_rustc_version(),
+            crate::ZKVM_TARGET,
+            git.as_ref().map(|git| git.commit.clone()),
+            git.map(|git| git.dirty),
+        )
+    }
+
+    /// Write `<dir>/<id>.json` unless it already exists
+    pub fn save_in(&self, dir: &Path) -> Result<()> {
+        let path = path_in(dir, &self.id);
+        if path.exists() {
+            return Ok(());
+        }
+        fs::create_dir_all(dir)?;
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+fn path_in(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.json", id))
+}
+
+static CURRENT: Mutex<Option<Arc<Snapshot>>> = Mutex::new(None);
+
+/// The snapshot of this process, captured and saved on first use
+///
+/// A snapshot that cannot be saved is still used; its rows then reference
+/// an id with no file, and a warning says so.
+pub fn current() -> Arc<Snapshot> {
+    let mut current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+    current.get_or_insert_with(|| Arc::new(saved(Snapshot::capture()))).clone()
+}
+
+/// Make `snapshot` this process's snapshot from now on, saving it
+pub fn replace(snapshot: Snapshot) {
+    let snapshot = Arc::new(saved(snapshot));
+    *CURRENT.lock().unwrap_or_else(|e| e.into_inner()) = Some(snapshot);
+}
+
+fn saved(snapshot: Snapshot) -> Snapshot {
+    if let Err(e) = snapshot.save_in(Path::new(ENVIRONMENTS_DIR)) {
+        eprintln!("⚠️  Failed to save environment snapshot {}: {:#}", snapshot.id, e);
+    }
+    snapshot
+}
+
+/// Load a snapshot by id from `artifacts/environments/`
+pub fn load(id: &str) -> Result<Option<Snapshot>> {
+    load_in(Path::new(ENVIRONMENTS_DIR), id)
+}
+
+fn load_in(dir: &Path, id: &str) -> Result<Option<Snapshot>> {
+    let path = path_in(dir, id);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&text)
+        .map(Some)
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_follow_contents() {
+        let snapshot = Snapshot::new("sp1 5.2.4", "rustc 1.90.0", crate::ZKVM_TARGET, Some("abc".to_string()), Some(false));
+        assert!(snapshot.id.starts_with("env_") && snapshot.id.len() == 16, "{}", snapshot.id);
+        let same = Snapshot::new("sp1 5.2.4", "rustc 1.90.0", crate::ZKVM_TARGET, Some("abc".to_string()), Some(false));
+        assert_eq!(snapshot.id, same.id);
+        let dirty = Snapshot::new("sp1 5.2.4", "rustc 1.90.0", crate::ZKVM_TARGET, Some("abc".to_string()), Some(true));
+        assert_ne!(snapshot.id, dirty.id);
+
+        let dir = std::env::temp_dir().join(format!("harness-environments-{}", std::process::id()));
+        snapshot.save_in(&dir).unwrap();
+        assert_eq!(load_in(&dir, &snapshot.id).unwrap(), Some(snapshot));
+        assert_eq!(load_in(&dir, &dirty.id).unwrap(), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,1196 @@
+//! Fuzz campaigns
+//!
+//! `harness fuzz` generates each core's inputs ([`prepare_fuzz_plan`]: the
+//! mutations of its base and promoted seeds, or its whole value grid),
+//! checks them against the core's input schema, and runs every input on
+//! native and SP1 ([`run_fuzzing`]). Inputs an earlier campaign ran against
+//! the same ELF are skipped (see [`crate::corpus`]), inputs can be bucketed
+//! by native commit stream first (see [`crate::buckets`]), pass/diverge
+//! flips of parametric strategies are bisected (see [`crate::bisect`]), and
+//! divergences and cycle outliers are promoted to seeds (see
+//! [`crate::promotion`]).
+
+use crate::bisect::{Probe, Threshold};
+use crate::promotion::PromotionReason;
+use crate::relations::compare_with_relations;
+use crate::{
+    build_failure_result, build_sp1_guest, elf_path_for_core, guest_path_for_core, load_input, log_mutation_result,
+    new_run_id, parse_cores_arg, run_native_runner_sandboxed, run_sp1_runner, sha256_file, write_input, RetryPolicy,
+    ZKVM_TARGET,
+};
+use anyhow::{Context, Result};
+use rust_eq_oracle::{Diff, RunResult};
+use source_mutator::MutatedInput;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// How a fuzz plan picks its inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FuzzMode {
+    /// Mutators, grammars and seeds (a sample of the input space)
+    Sample,
+    /// Every point of the core's value grid (arithmetic, panic_test)
+    Exhaustive,
+}
+
+impl FromStr for FuzzMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sample" => Ok(FuzzMode::Sample),
+            "exhaustive" => Ok(FuzzMode::Exhaustive),
+            _ => Err(format!("unknown fuzz mode '{}' (sample, exhaustive)", s)),
+        }
+    }
+}
+
+/// What a fuzz campaign does with inputs that violate the core's input schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidInputs {
+    /// Abort before anything runs
+    Fail,
+    /// Report them and run the rest
+    Skip,
+    /// Run them too (malformed-input testing), counted apart from the results
+    Run,
+}
+
+impl FromStr for InvalidInputs {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fail" => Ok(InvalidInputs::Fail),
+            "skip" => Ok(InvalidInputs::Skip),
+            "run" => Ok(InvalidInputs::Run),
+            _ => Err(format!("unknown invalid-input policy '{}' (fail, skip, run)", s)),
+        }
+    }
+}
+
+/// What a bucketed fuzz run does with a bucket's redundant inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Redundant {
+    /// Run them on SP1 after every bucket's representatives
+    Defer,
+    /// Do not run them on SP1 (listed in buckets.json)
+    Skip,
+}
+
+impl FromStr for Redundant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "defer" => Ok(Redundant::Defer),
+            "skip" => Ok(Redundant::Skip),
+            _ => Err(format!("unknown redundant-input policy '{}' (defer, skip)", s)),
+        }
+    }
+}
+
+/// Which inputs a campaign generates, and what it does with invalid ones
+#[derive(Debug, Clone, Copy)]
+pub struct InputOptions {
+    pub mode: FuzzMode,
+    /// Seed for cores whose manifest declares an input grammar (logged as rng_seed)
+    pub seed: u64,
+    /// Exhaustive mode: evenly spaced values per numeric field, besides its boundary values
+    pub resolution: usize,
+    /// Exhaustive mode: most inputs per core (a larger grid is an error, never truncated)
+    pub budget: usize,
+    pub invalid_inputs: InvalidInputs,
+}
+
+/// Commit-stream bucketing (see [`crate::buckets`])
+#[derive(Debug, Clone, Copy)]
+pub struct BucketOptions {
+    /// Inputs per distinct native commit stream sent to SP1 first (0 disables)
+    pub commit_buckets: usize,
+    pub redundant: Redundant,
+}
+
+/// `harness fuzz` flags
+pub struct FuzzOptions {
+    pub skip_build: bool,
+    pub build_jobs: usize,
+    pub allow_stale_elf: bool,
+    pub sandbox: bool,
+    pub dedup: bool,
+    pub inputs: InputOptions,
+    pub buckets: BucketOptions,
+    pub bisect_max_probes: usize,
+}
+
+/// Run input mutation fuzzing on specified cores
+pub fn run_fuzzing(cores_arg: &str, options: &FuzzOptions, retry: &RetryPolicy) -> Result<()> {
+    let cores_to_fuzz = parse_cores_arg(cores_arg)?;
+
+    println!("🔄 Starting input mutation fuzzing...");
+    println!("   Cores: {}", cores_to_fuzz.join(", "));
+    println!();
+
+    let mut total_mutations = 0;
+    let mut total_passed = 0;
+    let mut total_divergences = 0;
+    let mut total_infra_errors = 0;
+    let mut total_skipped = 0;
+    let mut total_schema_violations = 0;
+    let mut total_redundant = 0;
+    let mut total_invalid_runs = 0;
+    let overall_start = std::time::Instant::now();
+
+    // Inputs executed in earlier campaigns (skipped unless --no-dedup)
+    let corpus = if options.dedup {
+        crate::corpus::ExecutedCorpus::load()
+    } else {
+        Default::default()
+    };
+
+    // Build every SP1 guest up front, several at a time. A core whose guest
+    // fails to build is still fuzzed, with the failure as its SP1 result.
+    let build_errors = if options.skip_build {
+        cores_to_fuzz.iter().map(|_| None).collect()
+    } else {
+        build_guests(&cores_to_fuzz, options.build_jobs, retry)
+    };
+
+    // Fuzz each core. A core that fails (stale ELF, unreadable base input,
+    // schema violations, ...) is reported and the campaign moves on.
+    let mut statuses = Vec::new();
+    for (core_name, build_error) in cores_to_fuzz.into_iter().zip(build_errors) {
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("📦 Core: {}", core_name);
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!();
+
+        let build = match (&build_error, options.skip_build) {
+            (Some(_), _) => BuildStatus::Failed,
+            (None, true) => BuildStatus::Skipped,
+            (None, false) => BuildStatus::Built,
+        };
+        let result = if options.skip_build {
+            crate::provenance::ensure_fresh([core_name], options.allow_stale_elf)
+        } else {
+            Ok(())
+        }
+        .and_then(|()| fuzz_single_core(core_name, options, build_error, &corpus, retry));
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => {
+                println!("   ❌ Core '{}' failed: {:#}", core_name, e);
+                println!();
+                statuses.push(CoreStatus { core: core_name, build, outcome: Err(format!("{:#}", e)) });
+                continue;
+            }
+        };
+
+        total_mutations += result.total;
+        total_passed += result.passed;
+        total_divergences += result.divergences;
+        total_infra_errors += result.infra_errors;
+        total_skipped += result.skipped;
+        total_schema_violations += result.schema_violations;
+        total_redundant += result.redundant;
+        total_invalid_runs += result.invalid_runs;
+        statuses.push(CoreStatus { core: core_name, build, outcome: Ok(result) });
+
+        println!();
+    }
+
+    let overall_elapsed = overall_start.elapsed();
+
+    // Overall summary
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("🎯 Fuzzing Complete!");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!();
+    println!("📊 Overall Summary:");
+    println!("   Total mutations: {}", total_mutations);
+    // No mutations at all if every core failed
+    let percent = |count: usize| count as f64 / total_mutations.max(1) as f64 * 100.0;
+    println!("   Passed: {} ({:.1}%)", total_passed, percent(total_passed));
+    println!("   Divergences: {} ({:.1}%)", total_divergences, percent(total_divergences));
+    if total_infra_errors > 0 {
+        println!("   Infra errors (not compared): {}", total_infra_errors);
+    }
+    if total_skipped > 0 {
+        println!("   Skipped (already executed in an earlier campaign): {}", total_skipped);
+    }
+    if total_schema_violations > 0 {
+        println!("   Skipped (violates the input schema): {}", total_schema_violations);
+    }
+    if total_invalid_runs > 0 {
+        println!("   Invalid inputs run (not compared): {}", total_invalid_runs);
+    }
+    if total_redundant > 0 {
+        println!("   Skipped (redundant native commit stream): {}", total_redundant);
+    }
+    println!("   Total time: {:.1}s", overall_elapsed.as_secs_f64());
+    println!();
+    println!("💾 All results logged to artifacts/summary.csv");
+    
+    if total_divergences > 0 {
+        println!("   🔧 Divergence artifacts in artifacts/");
+    }
+
+    // Per-core status
+    println!();
+    println!("📋 Per-core Status:");
+    let width = statuses.iter().map(|status| status.core.len()).max().unwrap_or(0);
+    for status in &statuses {
+        let outcome = match &status.outcome {
+            Ok(result) => format!(
+                "fuzzed: {} run, {} passed, {} divergences",
+                result.total, result.passed, result.divergences
+            ),
+            Err(e) => format!("fuzz failed: {}", e.lines().next().unwrap_or_default()),
+        };
+        let icon = match (&status.outcome, status.build) {
+            (Err(_), _) => "❌",
+            (Ok(_), BuildStatus::Failed) => "⚠️ ",
+            (Ok(_), _) => "✅",
+        };
+        println!(
+            "   {} {:width$}  build {:7}  {}",
+            icon,
+            status.core,
+            status.build.as_str(),
+            outcome,
+            width = width
+        );
+    }
+
+    let failed: Vec<&str> = statuses
+        .iter()
+        .filter(|status| status.outcome.is_err())
+        .map(|status| status.core)
+        .collect();
+    if !failed.is_empty() {
+        anyhow::bail!("{} of {} cores failed: {}", failed.len(), statuses.len(), failed.join(", "));
+    }
+
+    Ok(())
+}
+
+/// How one core of a campaign went, for the final summary
+struct CoreStatus<'a> {
+    core: &'a str,
+    build: BuildStatus,
+    outcome: std::result::Result<FuzzResult, String>,
+}
+
+/// What happened to a core's SP1 guest before a campaign fuzzed it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BuildStatus {
+    Built,
+    /// Not rebuilt (`--skip-build`)
+    Skipped,
+    Failed,
+}
+
+impl BuildStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            BuildStatus::Built => "built",
+            BuildStatus::Skipped => "skipped",
+            BuildStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Build the SP1 guests of `cores`, at most `jobs` at a time
+///
+/// Build output goes to `artifacts/builds/`, so the console only gets one
+/// line per finished build. Returns each core's build error (with the
+/// retries taken), in `cores` order.
+pub fn build_guests(cores: &[&str], jobs: usize, retry: &RetryPolicy) -> Vec<Option<(anyhow::Error, u32)>> {
+    println!("📦 Building {} SP1 guest(s), {} at a time...", cores.len(), jobs.max(1));
+    let finished = std::sync::atomic::AtomicUsize::new(0);
+    let build_errors = crate::build::build_all(cores, jobs, |core_name| {
+        let (result, retries) = retry.run("SP1 guest build", || build_sp1_guest(&guest_path_for_core(core_name)));
+        let done = finished.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+        match result {
+            Ok(build) => {
+                println!("   ✅ [{}/{}] {} ({})", done, cores.len(), core_name, build.summary());
+                None
+            }
+            Err(e) => {
+                println!("   ❌ [{}/{}] {}: {:#}", done, cores.len(), core_name, e);
+                Some((e, retries))
+            }
+        }
+    });
+    println!();
+    build_errors
+}
+
+/// Plan a fuzz campaign without executing anything (`fuzz --dry-run`)
+///
+/// Generates and validates every core's mutations, writes the usual plan
+/// and coverage files plus `estimate.json`, and projects the runtime from
+/// the mean per-run timings of earlier runs in `artifacts/runs.jsonl`.
+pub fn plan_fuzzing(cores_arg: &str, inputs: &InputOptions) -> Result<()> {
+    let cores_to_fuzz = parse_cores_arg(cores_arg)?;
+
+    println!("📝 Planning input mutation fuzzing (dry run, nothing is executed)...");
+    println!("   Cores: {}", cores_to_fuzz.join(", "));
+    println!();
+
+    // No history yet is fine: those cores just get no estimate
+    let records = crate::cost_model::load_run_records().unwrap_or_default();
+
+    let mut total_mutations = 0;
+    let mut total_invalid = 0;
+    let mut projected_ms = 0.0;
+    let mut cores_without_history = Vec::new();
+
+    for core_name in cores_to_fuzz {
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("📦 Core: {}", core_name);
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!();
+
+        let plan = prepare_fuzz_plan(core_name, inputs)?;
+
+        // Validate every mutation against the core's input schema and the
+        // base input's shape
+        let mut seen = std::collections::HashSet::new();
+        let mut invalid = Vec::new();
+        for (idx, mutation) in plan.mutations.iter().enumerate() {
+            let mut issues = validate_mutation(&plan.base_input_json, &mutation.input_json);
+            if let Some(error) = &plan.schema_errors[idx] {
+                issues.insert(0, format!("violates the input schema: {}", error));
+            }
+            if !seen.insert(mutation.input_json.to_string()) {
+                issues.push("duplicate of an earlier mutation".to_string());
+            }
+            if !issues.is_empty() {
+                invalid.push(serde_json::json!({
+                    "mutation": idx + 1,
+                    "mutation_op": mutation.op.to_string(),
+                    "issues": issues,
+                }));
+            }
+        }
+        if invalid.is_empty() {
+            println!("   ✅ All {} mutations valid", plan.mutations.len());
+        } else {
+            println!("   ⚠️  {} of {} mutations have issues:", invalid.len(), plan.mutations.len());
+            for entry in &invalid {
+                println!("      #{} {}: {}", entry["mutation"], entry["mutation_op"], entry["issues"]);
+            }
+        }
+
+        // Project runtime from this core's history
+        let history: Vec<_> = records.iter().filter(|r| r.core == core_name).collect();
+        let mean_ms = |f: fn(&crate::cost_model::RunRecord) -> u128| {
+            history.iter().map(|r| f(r) as f64).sum::<f64>() / history.len() as f64
+        };
+        let estimate = if history.is_empty() {
+            cores_without_history.push(core_name);
+            println!("   ⏱️  No timing history: runtime unknown");
+            serde_json::Value::Null
+        } else {
+            let native_ms = mean_ms(|r| r.native_ms);
+            let sp1_ms = mean_ms(|r| r.sp1_ms);
+            let core_ms = plan.mutations.len() as f64 * (native_ms + sp1_ms);
+            projected_ms += core_ms;
+            println!(
+                "   ⏱️  ~{} (mean native {:.0}ms + SP1 {:.0}ms per mutation over {} runs)",
+                format_duration_ms(core_ms),
+                native_ms,
+                sp1_ms,
+                history.len()
+            );
+            serde_json::json!({
+                "history_runs": history.len(),
+                "mean_native_ms": native_ms,
+                "mean_sp1_ms": sp1_ms,
+                "projected_ms": core_ms,
+            })
+        };
+
+        let estimate_json = serde_json::json!({
+            "core": core_name,
+            "campaign": crate::campaign::current_id(),
+            "mutations": plan.mutations.len(),
+            "executions": plan.mutations.len() * 2,
+            "invalid": invalid,
+            "estimate": estimate,
+        });
+        fs::write(
+            plan.fuzz_artifacts_dir.join("estimate.json"),
+            serde_json::to_string_pretty(&estimate_json)?,
+        )?;
+        println!("   📄 Plan: {}", plan.fuzz_artifacts_dir.display());
+        println!();
+
+        total_mutations += plan.mutations.len();
+        total_invalid += invalid.len();
+    }
+
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("📊 Dry-run Summary:");
+    println!("   Total mutations: {}", total_mutations);
+    println!("   Total executions: {} (native + SP1)", total_mutations * 2);
+    println!("   Mutations with issues: {}", total_invalid);
+    println!(
+        "   Projected runtime: ~{} (excluding guest builds)",
+        format_duration_ms(projected_ms)
+    );
+    if !cores_without_history.is_empty() {
+        println!("   ⚠️  Not included (no history): {}", cores_without_history.join(", "));
+    }
+
+    Ok(())
+}
+
+/// Shape problems of a mutation relative to the base input
+///
+/// Mutations vary values, so a mutation whose top-level fields or value
+/// types differ from the base input would most likely be rejected by the
+/// core's deserializer rather than exercise it.
+fn validate_mutation(base: &serde_json::Value, mutation: &serde_json::Value) -> Vec<String> {
+    let mut issues = Vec::new();
+    let (Some(base), Some(mutation)) = (base.as_object(), mutation.as_object()) else {
+        if base.is_object() != mutation.is_object() {
+            issues.push("not the same JSON type as the base input".to_string());
+        }
+        return issues;
+    };
+    for key in base.keys().filter(|key| !mutation.contains_key(*key)) {
+        issues.push(format!("missing field '{}'", key));
+    }
+    for key in mutation.keys().filter(|key| !base.contains_key(*key)) {
+        issues.push(format!("unknown field '{}'", key));
+    }
+    let kind = |v: &serde_json::Value| match v {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "bool",
+        serde_json::Value::Number(_) => "number",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    };
+    for (key, value) in mutation {
+        if let Some(base_value) = base.get(key) {
+            // null stands in for Option::None, so it may replace any type
+            if !base_value.is_null() && !value.is_null() && kind(base_value) != kind(value) {
+                issues.push(format!("field '{}' is {} (base input: {})", key, kind(value), kind(base_value)));
+            }
+        }
+    }
+    issues
+}
+
+/// Human-readable duration for runtime projections
+fn format_duration_ms(ms: f64) -> String {
+    let secs = ms / 1000.0;
+    if secs < 60.0 {
+        format!("{:.0}s", secs)
+    } else if secs < 3600.0 {
+        format!("{:.1}min", secs / 60.0)
+    } else {
+        format!("{:.1}h", secs / 3600.0)
+    }
+}
+
+#[derive(Debug)]
+struct FuzzResult {
+    total: usize,
+    passed: usize,
+    divergences: usize,
+    infra_errors: usize,
+    skipped: usize,
+    /// Skipped for violating the input schema (`--invalid-inputs skip`)
+    schema_violations: usize,
+    /// Not run on SP1 (`--redundant skip`)
+    redundant: usize,
+    /// Run despite violating the input schema (`--invalid-inputs run`),
+    /// counted in `total` but not compared
+    invalid_runs: usize,
+}
+
+/// Mutations generated for one core, with their plan written to disk
+pub struct FuzzPlan {
+    pub base_input_json: serde_json::Value,
+    pub mutations: Vec<source_mutator::MutatedInput>,
+    /// Per mutation, why it violates the core's input schema (None if valid)
+    pub schema_errors: Vec<Option<String>>,
+    pub fuzz_artifacts_dir: PathBuf,
+}
+
+impl FuzzPlan {
+    /// Abort on schema violations unless the campaign allows invalid inputs
+    ///
+    /// A mutation that does not deserialize never reaches the core, so under
+    /// the default policy it is treated as a generator bug.
+    pub fn enforce_input_schema(&self, core_name: &str, policy: InvalidInputs) -> Result<()> {
+        let violations: Vec<String> = self
+            .mutations
+            .iter()
+            .zip(&self.schema_errors)
+            .filter_map(|(mutation, error)| error.as_ref().map(|error| format!("{}: {}", mutation.op, error)))
+            .collect();
+        if policy != InvalidInputs::Fail || violations.is_empty() {
+            return Ok(());
+        }
+        anyhow::bail!(
+            "{} of {} {} mutations violate the core's input schema (see {}); \
+             fix the generator, or pass --invalid-inputs skip|run:\n  {}",
+            violations.len(),
+            self.mutations.len(),
+            core_name,
+            self.fuzz_artifacts_dir.join("schema_violations.json").display(),
+            violations.iter().take(5).cloned().collect::<Vec<_>>().join("\n  ")
+        )
+    }
+}
+
+/// Generate a core's mutations and write plan.json and coverage.json
+pub fn prepare_fuzz_plan(core_name: &str, inputs: &InputOptions) -> Result<FuzzPlan> {
+    let (base_input_json, mutations) = match inputs.mode {
+        FuzzMode::Sample => sample_inputs(core_name, inputs.seed)?,
+        FuzzMode::Exhaustive => enumerate_inputs(core_name, inputs)?,
+    };
+
+    println!("   ✅ Generated {} mutations", mutations.len());
+
+    // Calculate and display statistics
+    if core_name == "io_echo" {
+        let stats = source_mutator::calculate_size_stats(&mutations);
+        println!();
+        println!("   📊 Size Distribution:");
+        println!("      Min: {} bytes", stats.min_size.unwrap_or(0));
+        println!("      Max: {} bytes", stats.max_size.unwrap_or(0));
+        if let Some(max) = stats.max_size {
+            if max >= 1024 {
+                println!("           ({:.2} KB)", max as f64 / 1024.0);
+            }
+            if max >= 1048576 {
+                println!("           ({:.2} MB)", max as f64 / 1048576.0);
+            }
+        }
+        println!("      Total sizes: {}", stats.total_count);
+    }
+    println!();
+
+    // Create artifacts directory for this fuzzing run
+    let fuzz_run_id = new_run_id(&format!("fuzz_{}", core_name));
+    let fuzz_artifacts_dir = PathBuf::from("artifacts/mutations").join(&fuzz_run_id);
+    fs::create_dir_all("artifacts/mutations")?;
+    fs::create_dir(&fuzz_artifacts_dir)?;
+
+    // Save mutation plan
+    let plan_path = fuzz_artifacts_dir.join("plan.json");
+    let plan_json = serde_json::to_string_pretty(&mutations.iter().map(|m| {
+        serde_json::json!({
+            "mutation_op": m.op.to_string(),
+            "op": &m.op,
+            "base": &m.base_input_path,
+        })
+    }).collect::<Vec<_>>())?;
+    fs::write(&plan_path, plan_json)?;
+    if let Some(campaign) = crate::campaign::current() {
+        fs::write(
+            fuzz_artifacts_dir.join("campaign.json"),
+            serde_json::to_string_pretty(campaign)?,
+        )?;
+    }
+
+    // Save which input regions the mutations cover
+    let coverage = source_mutator::coverage_report(core_name, &mutations);
+    fs::write(
+        fuzz_artifacts_dir.join("coverage.json"),
+        serde_json::to_string_pretty(&coverage)?,
+    )?;
+
+    // Check every input against the core's input type before anything runs
+    let inputs_json: Vec<serde_json::Value> = mutations.iter().map(|m| m.input_json.clone()).collect();
+    let schema_errors = crate::check_inputs(core_name, &inputs_json, &fuzz_artifacts_dir.join("inputs.jsonl"))?;
+    let violations: Vec<serde_json::Value> = mutations
+        .iter()
+        .zip(&schema_errors)
+        .enumerate()
+        .filter_map(|(idx, (mutation, error))| {
+            error.as_ref().map(|error| {
+                serde_json::json!({
+                    "mutation": idx + 1,
+                    "mutation_op": mutation.op.to_string(),
+                    "error": error,
+                })
+            })
+        })
+        .collect();
+    if violations.is_empty() {
+        println!("   🔍 All {} inputs match the {} input schema", mutations.len(), core_name);
+    } else {
+        println!("   🔍 {} of {} inputs violate the {} input schema", violations.len(), mutations.len(), core_name);
+        fs::write(
+            fuzz_artifacts_dir.join("schema_violations.json"),
+            serde_json::to_string_pretty(&violations)?,
+        )?;
+    }
+
+    let gaps: Vec<String> = coverage
+        .fields
+        .iter()
+        .filter(|(_, field)| !field.gaps.is_empty())
+        .map(|(path, field)| format!("{}: {}", path, field.gaps.join(", ")))
+        .collect();
+    if !gaps.is_empty() {
+        println!("   🕳️  Coverage gaps (see coverage.json):");
+        for gap in &gaps {
+            println!("      {}", gap);
+        }
+        println!();
+    }
+
+    Ok(FuzzPlan {
+        base_input_json,
+        mutations,
+        schema_errors,
+        fuzz_artifacts_dir,
+    })
+}
+
+/// Mutate every named base seed of a core and its promoted seeds
+fn sample_inputs(
+    core_name: &str,
+    seed: u64,
+) -> Result<(serde_json::Value, Vec<source_mutator::MutatedInput>)> {
+    // Every named base seed of this core is mutated
+    let base_seeds = crate::base_seeds_for_core(core_name)?;
+    for base_seed in &base_seeds {
+        println!("   Base input: {} ({})", base_seed.path.display(), base_seed.name);
+    }
+
+    // Generate mutations (mutators that ignore the base input would only
+    // repeat themselves across seeds). Seeds a manifest declares also run
+    // as they are.
+    println!("   Generating mutations...");
+    let mut base_input_json = serde_json::Value::Null;
+    let mut mutations = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for base_seed in &base_seeds {
+        let seed_json = load_input(core_name, &base_seed.path)
+            .with_context(|| format!("Failed to load base seed {}", base_seed.path.display()))?;
+        let seed_path = base_seed.path.to_str().unwrap();
+        if base_seed.name != crate::DEFAULT_SEED && seen.insert(seed_json.to_string()) {
+            let mut input = source_mutator::base_seed_input(&base_seed.name, &seed_json, seed_path);
+            input.op.parent_sha256 = sha256_file(&base_seed.path).unwrap_or_default();
+            mutations.push(input);
+        }
+        let seed_mutations = source_mutator::generate_mutations(core_name, &seed_json, seed_path, seed)?;
+        mutations.extend(
+            seed_mutations
+                .into_iter()
+                .filter(|m| seen.insert(m.input_json.to_string()))
+                .map(|mut m| {
+                    m.op.base_seed = Some(base_seed.name.clone());
+                    m
+                }),
+        );
+        if base_input_json.is_null() {
+            base_input_json = seed_json;
+        }
+    }
+
+    // Promoted seeds (earlier divergences and cycle outliers) are mutated too
+    let promoted = crate::promotion::promoted_seeds(core_name)?;
+    if !promoted.is_empty() {
+        println!("   🌱 Promoted seeds: {}", promoted.len());
+        for seed_path in &promoted {
+            let seed_json = load_input(core_name, seed_path)
+                .with_context(|| format!("Failed to load promoted seed {}", seed_path.display()))?;
+            let seed_mutations =
+                source_mutator::generate_mutations(core_name, &seed_json, seed_path.to_str().unwrap(), seed)?;
+            // Mutators that ignore the base input would only repeat themselves
+            mutations.extend(
+                seed_mutations
+                    .into_iter()
+                    .filter(|m| seen.insert(m.input_json.to_string())),
+            );
+        }
+    }
+
+    Ok((base_input_json, mutations))
+}
+
+/// Enumerate a core's whole value grid, refusing grids larger than the budget
+fn enumerate_inputs(
+    core_name: &str,
+    inputs: &InputOptions,
+) -> Result<(serde_json::Value, Vec<source_mutator::MutatedInput>)> {
+    let base_input_path = crate::base_input_for_core(core_name)?;
+    println!("   Base input: {}", base_input_path.display());
+    println!("   Enumerating value grid (resolution {})...", inputs.resolution);
+
+    let mut mutations = source_mutator::enumerate_inputs(
+        core_name,
+        base_input_path.to_str().unwrap(),
+        inputs.resolution,
+    )?;
+    if mutations.len() > inputs.budget {
+        anyhow::bail!(
+            "Value grid of {} has {} points, over the budget of {} (lower --resolution or raise --budget)",
+            core_name,
+            mutations.len(),
+            inputs.budget
+        );
+    }
+
+    let parent_sha256 = sha256_file(&base_input_path).unwrap_or_default();
+    for mutation in &mut mutations {
+        mutation.op.parent_sha256 = parent_sha256.clone();
+    }
+    let base_input_json = load_input(core_name, &base_input_path)
+        .with_context(|| format!("Failed to load base input {}", base_input_path.display()))?;
+    Ok((base_input_json, mutations))
+}
+
+/// Fuzz a single core with input mutations
+///
+/// The guest was built beforehand (see [`build_guests`]); `build_error` is
+/// its failure, if any.
+fn fuzz_single_core(
+    core_name: &str,
+    options: &FuzzOptions,
+    build_error: Option<(anyhow::Error, u32)>,
+    corpus: &crate::corpus::ExecutedCorpus,
+    retry: &RetryPolicy,
+) -> Result<FuzzResult> {
+    let sandbox = options.sandbox;
+    let plan = prepare_fuzz_plan(core_name, &options.inputs)?;
+    plan.enforce_input_schema(core_name, options.inputs.invalid_inputs)?;
+    let FuzzPlan {
+        mutations,
+        schema_errors,
+        fuzz_artifacts_dir,
+        ..
+    } = plan;
+    let relations = crate::relations::load_relations(core_name)?;
+
+    println!("   🧪 Testing mutations...");
+    println!();
+
+    let mut executed = 0;
+    let mut passed = 0;
+    let mut divergences = 0;
+    let mut infra_errors = 0;
+    let mut skipped = 0;
+    let mut schema_violations = 0;
+    let mut invalid_runs = 0;
+    let mut promoted = 0;
+    let mut latency = crate::latency::CoreLatency::default();
+
+    if let Some((e, _)) = &build_error {
+        println!("   ❌ SP1 guest build failed: {:#}", e);
+        println!();
+    }
+
+    // Dedup key: the ELF this campaign runs (none if the build failed)
+    let elf_path = elf_path_for_core(core_name);
+    let elf_sha256 = if build_error.is_none() { sha256_file(&elf_path) } else { None };
+
+    // Outcomes per parametric strategy, for bisecting pass/diverge flips
+    let mut observed: std::collections::BTreeMap<&str, (&MutatedInput, Vec<Probe>)> = Default::default();
+
+    // Inputs to execute: (mutation index, input path)
+    let total = mutations.len();
+    let mut pending = Vec::new();
+    for (idx, mutation) in mutations.iter().enumerate() {
+        let mutation_num = idx + 1;
+        let input_text = serde_json::to_string_pretty(&mutation.input_json)?;
+
+        // Invalid inputs only get here with --invalid-inputs skip|run
+        if let Some(error) = schema_errors[idx].as_deref() {
+            if options.inputs.invalid_inputs == InvalidInputs::Skip {
+                schema_violations += 1;
+                println!(
+                    "   ⏭️  Mutation {}/{}: {} | violates the input schema: {}",
+                    mutation_num, total, mutation.op, error
+                );
+                continue;
+            }
+        }
+
+        // Skip inputs an earlier campaign already ran against this ELF
+        let input_sha256 = crate::corpus::input_sha256(input_text.as_bytes());
+        let earlier_run = elf_sha256
+            .as_deref()
+            .and_then(|elf| corpus.find(core_name, ZKVM_TARGET, elf, &input_sha256));
+        if let Some(run_id) = earlier_run {
+            skipped += 1;
+            println!(
+                "   ⏭️  Mutation {}/{}: {} | already executed (run {})",
+                mutation_num, total, mutation.op, run_id
+            );
+            continue;
+        }
+
+        // Each mutation gets its own working directory
+        let mutation_dir = fuzz_artifacts_dir.join(format!("mutation_{:04}", mutation_num));
+        fs::create_dir(&mutation_dir)?;
+        let temp_input_path = mutation_dir.join("input.json");
+        write_input(core_name, &mutation.input_json, &temp_input_path)?;
+        pending.push((idx, temp_input_path));
+    }
+
+    // Retrying an invalid input cannot help
+    let no_retry = RetryPolicy { retries: 0, ..*retry };
+    let retry_for = |idx: usize| if schema_errors[idx].is_some() { &no_retry } else { retry };
+    let run_native = |idx: usize, input_path: &Path| {
+        retry_for(idx).run_runner("native", || run_native_runner_sandboxed(core_name, input_path, sandbox))
+    };
+
+    // With --commit-buckets, run every input natively first and put the
+    // redundant members of each commit-stream bucket last (or drop them)
+    let mut native_results: Vec<Option<RunResult>> = vec![None; total];
+    let mut redundant = 0;
+    if options.buckets.commit_buckets > 0 && build_error.is_none() && pending.len() > 1 {
+        println!("   🪣 Native pre-execution of {} inputs...", pending.len());
+        for (idx, input_path) in &pending {
+            native_results[*idx] = Some(run_native(*idx, input_path));
+        }
+        let sizes: Vec<usize> = pending
+            .iter()
+            .map(|(idx, _)| mutations[*idx].input_json.to_string().len())
+            .collect();
+        let members: Vec<crate::buckets::Member> = pending
+            .iter()
+            .zip(&sizes)
+            .map(|((idx, _), &input_bytes)| crate::buckets::Member {
+                id: idx + 1,
+                native: native_results[*idx].as_ref().expect("pre-executed"),
+                input_bytes,
+            })
+            .collect();
+        let bucketing = crate::buckets::bucket(&members, options.buckets.commit_buckets);
+        let buckets_path = fuzz_artifacts_dir.join("buckets.json");
+        fs::write(&buckets_path, serde_json::to_string_pretty(&bucketing)?)?;
+
+        let first = bucketing.first();
+        let deferred = bucketing.redundant();
+        println!(
+            "      {} distinct commit streams: {} inputs go to SP1 first, {} redundant ({})",
+            bucketing.buckets.len(),
+            first.len(),
+            deferred.len(),
+            match options.buckets.redundant {
+                Redundant::Defer => "run last",
+                Redundant::Skip => "skipped",
+            }
+        );
+        println!("      💾 Buckets saved to {}", buckets_path.display());
+        println!();
+
+        let mut by_num: std::collections::BTreeMap<usize, (usize, PathBuf)> =
+            pending.drain(..).map(|(idx, path)| (idx + 1, (idx, path))).collect();
+        let order = match options.buckets.redundant {
+            Redundant::Defer => [first, deferred].concat(),
+            Redundant::Skip => {
+                redundant = deferred.len();
+                first
+            }
+        };
+        pending = order.into_iter().filter_map(|num| by_num.remove(&num)).collect();
+    }
+
+    // Test each mutation
+    for (idx, temp_input_path) in pending {
+        let mutation = &mutations[idx];
+        let mutation_num = idx + 1;
+        let schema_error = schema_errors[idx].as_deref();
+
+        // Run differential test
+        let retry = retry_for(idx);
+        let native_result = match native_results[idx].take() {
+            Some(result) => result,
+            None => run_native(idx, &temp_input_path),
+        };
+        let sp1_result = match &build_error {
+            Some((e, retries)) => build_failure_result(e, *retries),
+            None => retry.run_runner("sp1", || {
+                run_sp1_runner(&elf_path, &temp_input_path, core_name)
+            }),
+        };
+
+        // Compare
+        let diff = compare_with_relations(&relations, &mutation.input_json, &native_result, &sp1_result);
+
+        // Track stats
+        latency.record(&native_result, &sp1_result, &temp_input_path.display().to_string());
+
+        let param_value = mutation
+            .op
+            .strategy
+            .bisect_param()
+            .and_then(|param| mutation.op.params.get(param))
+            .and_then(|value| value.as_u64());
+        if let Some(value) = param_value.filter(|_| !native_result.status.is_infra() && !sp1_result.status.is_infra()) {
+            observed.entry(mutation.op.strategy.as_str()).or_insert((mutation, Vec::new())).1.push(Probe {
+                value,
+                passed: diff.equal,
+                reason: diff.reason.clone(),
+            });
+        }
+
+        // Infrastructure failures are kept out of the divergence count, and
+        // invalid inputs out of all three
+        executed += 1;
+        if schema_error.is_some() {
+            invalid_runs += 1;
+        } else if diff.equal {
+            passed += 1;
+        } else if native_result.status.is_infra() || sp1_result.status.is_infra() {
+            infra_errors += 1;
+        } else {
+            divergences += 1;
+        }
+
+        // Display progress
+        let status_icon = if diff.equal { "✅" } else { "❌" };
+        println!(
+            "   {} Mutation {}/{}: {} | Native: {:?} ({}ms) | SP1: {:?} ({}ms) | Equal: {}",
+            status_icon,
+            mutation_num,
+            total,
+            mutation.op,
+            native_result.status,
+            native_result.elapsed_ms,
+            sp1_result.status,
+            sp1_result.elapsed_ms,
+            diff.equal,
+        );
+
+        if !diff.equal {
+            if let Some(reason) = &diff.reason {
+                println!("      Reason: {}", reason);
+            }
+        }
+
+        if log_and_promote(core_name, &temp_input_path, native_result, sp1_result, diff, mutation)? {
+            promoted += 1;
+        }
+    }
+
+    let mut thresholds = Vec::new();
+    if build_error.is_none() && options.bisect_max_probes > 0 {
+        for (template, probes) in observed.values() {
+            thresholds.extend(bisect_thresholds(core_name, template, probes, &fuzz_artifacts_dir, options, retry)?);
+        }
+        if !thresholds.is_empty() {
+            let path = fuzz_artifacts_dir.join("bisect.json");
+            fs::write(&path, serde_json::to_string_pretty(&thresholds)?)?;
+            println!("   💾 Thresholds saved to {}", path.display());
+        }
+    }
+
+    // Latency percentiles, with the slowest inputs
+    let latency = latency.summary();
+    let latency_path = fuzz_artifacts_dir.join("latency.json");
+    fs::write(&latency_path, serde_json::to_string_pretty(&latency)?)?;
+
+    println!();
+    println!("   📊 Timing Statistics:");
+    crate::latency::print_latency("Native", &latency.native);
+    crate::latency::print_latency("SP1", &latency.sp1);
+    println!("      💾 Saved to {}", latency_path.display());
+    println!();
+    println!("   ✅ Core '{}' fuzzing complete!", core_name);
+    println!("      Total: {}", executed);
+    // Nothing executed if every mutation was skipped
+    println!("      Passed: {} ({:.1}%)", passed, passed as f64 / executed.max(1) as f64 * 100.0);
+    println!("      Divergences: {}", divergences);
+    if infra_errors > 0 {
+        println!("      Infra errors: {}", infra_errors);
+    }
+    if skipped > 0 {
+        println!("      Skipped (already executed): {}", skipped);
+    }
+    if redundant > 0 {
+        println!("      Skipped (redundant native commit stream): {}", redundant);
+    }
+    if schema_violations > 0 {
+        println!("      Skipped (violates the input schema): {}", schema_violations);
+    }
+    if invalid_runs > 0 {
+        println!("      Invalid inputs run (not compared): {}", invalid_runs);
+    }
+    if promoted > 0 {
+        println!("      Promoted to {}: {}", crate::promotion::promoted_dir(core_name).display(), promoted);
+    }
+    for threshold in &thresholds {
+        println!("      Threshold: {}", threshold);
+    }
+
+    Ok(FuzzResult {
+        total: executed,
+        passed,
+        divergences,
+        infra_errors,
+        skipped,
+        schema_violations,
+        redundant,
+        invalid_runs,
+    })
+}
+
+/// Bisect every pass/diverge flip among a parametric strategy's outcomes
+///
+/// Each probe's input is kept in `<fuzz dir>/bisect/<param>_<value>/` so the
+/// values either side of a threshold can be rerun with `harness run`.
+/// Probes are not logged as runs or promoted.
+fn bisect_thresholds(
+    core_name: &str,
+    template: &MutatedInput,
+    observed: &[Probe],
+    fuzz_artifacts_dir: &Path,
+    options: &FuzzOptions,
+    retry: &RetryPolicy,
+) -> Result<Vec<Threshold>> {
+    let strategy = template.op.strategy;
+    let Some(param) = strategy.bisect_param() else {
+        return Ok(Vec::new());
+    };
+    let elf_path = elf_path_for_core(core_name);
+    let relations = crate::relations::load_relations(core_name)?;
+
+    let mut thresholds = Vec::new();
+    for (passing, failing) in crate::bisect::transitions(observed) {
+        println!();
+        println!(
+            "   🔎 Bisecting {}: {}={} passes, {}={} diverges",
+            strategy.as_str(),
+            param,
+            passing.value,
+            param,
+            failing.value
+        );
+        let threshold = crate::bisect::bisect(
+            strategy.as_str(),
+            param,
+            &passing,
+            &failing,
+            options.bisect_max_probes,
+            |value| {
+                let Some(mutation) = source_mutator::parametric_mutation(strategy, value, &template.base_input_path)
+                else {
+                    return Ok(None);
+                };
+                let probe_dir = fuzz_artifacts_dir.join("bisect").join(format!("{}_{}", param, value));
+                fs::create_dir_all(&probe_dir)?;
+                let input_path = probe_dir.join("input.json");
+                write_input(core_name, &mutation.input_json, &input_path)?;
+
+                let native_result = retry.run_runner("native", || {
+                    run_native_runner_sandboxed(core_name, &input_path, options.sandbox)
+                });
+                let sp1_result = retry.run_runner("sp1", || run_sp1_runner(&elf_path, &input_path, core_name));
+                if native_result.status.is_infra() || sp1_result.status.is_infra() {
+                    println!("      ⚠️  {}={}: infrastructure failure, stopping", param, value);
+                    return Ok(None);
+                }
+                let diff = compare_with_relations(&relations, &mutation.input_json, &native_result, &sp1_result);
+                println!(
+                    "      {} {}={} | Native: {:?} | SP1: {:?}",
+                    if diff.equal { "✅" } else { "❌" },
+                    param,
+                    value,
+                    native_result.status,
+                    sp1_result.status
+                );
+                Ok(Some(Probe {
+                    value,
+                    passed: diff.equal,
+                    reason: diff.reason,
+                }))
+            },
+        )?;
+        println!("   🎯 {}", threshold);
+        thresholds.push(threshold);
+    }
+    Ok(thresholds)
+}
+
+/// Log a fuzzed run (CSV row, run record, repro folder) and promote
+/// divergences and cycle-count outliers to base seeds
+///
+/// Returns whether the input was promoted.
+pub fn log_and_promote(
+    core_name: &str,
+    input_path: &Path,
+    native_result: RunResult,
+    sp1_result: RunResult,
+    diff: Diff,
+    mutation: &MutatedInput,
+) -> Result<bool> {
+    // Real divergences (not infra failures) are worth mutating further
+    let diverged = !diff.equal && !native_result.status.is_infra() && !sp1_result.status.is_infra();
+
+    // Log to CSV with mutation metadata
+    let core_path = PathBuf::from(format!("guest/cores/{}", core_name));
+    let logged = log_mutation_result(&core_path, input_path, native_result, sp1_result, diff, mutation)?;
+
+    let reason = if diverged {
+        Some(PromotionReason::Divergence)
+    } else if logged.perf_anomalies.iter().any(|a| a.metric == "sp1_cycles") {
+        Some(PromotionReason::CycleOutlier)
+    } else {
+        None
+    };
+    let Some(reason) = reason else {
+        return Ok(false);
+    };
+    match crate::promotion::promote_input(core_name, input_path, reason, &mutation.op.to_string())? {
+        Some(seed) => {
+            println!("      🌱 Promoted to {}", seed.display());
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_mutation_reports_shape_changes() {
+        let base = json!({"a": 1, "b": "x", "c": null});
+        assert!(validate_mutation(&base, &json!({"a": 2, "b": "y", "c": 5})).is_empty());
+        assert!(validate_mutation(&base, &json!({"a": null, "b": "y", "c": null})).is_empty());
+        assert_eq!(
+            validate_mutation(&base, &json!({"a": "2", "c": null, "d": 0})),
+            vec![
+                "missing field 'b'".to_string(),
+                "unknown field 'd'".to_string(),
+                "field 'a' is string (base input: number)".to_string(),
+            ]
+        );
+        assert_eq!(
+            validate_mutation(&base, &json!([1])),
+            vec!["not the same JSON type as the base input".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_format_duration_ms() {
+        assert_eq!(format_duration_ms(1_500.0), "2s");
+        assert_eq!(format_duration_ms(90_000.0), "1.5min");
+        assert_eq!(format_duration_ms(5_400_000.0), "1.5h");
+    }
+
+    #[test]
+    fn test_policies_parse_their_cli_names() {
+        assert_eq!("exhaustive".parse::<FuzzMode>(), Ok(FuzzMode::Exhaustive));
+        assert_eq!("run".parse::<InvalidInputs>(), Ok(InvalidInputs::Run));
+        assert_eq!("defer".parse::<Redundant>(), Ok(Redundant::Defer));
+        assert!("Skip".parse::<Redundant>().is_err());
+    }
+}
@@ -0,0 +1,418 @@
+//! Minimal HTTP/1.1 server and client for the harness APIs
+//!
+//! `harness serve` and the distributed coordinator only need a handful of
+//! JSON endpoints and file downloads, so this is a blocking,
+//! thread-per-connection server over `std::net` rather than an async stack:
+//! one request per connection (`Connection: close`), bodies sized by
+//! `Content-Length`. [`post_json`] is the matching client used by workers.
+//!
+//! Both servers can start processes, so every endpoint but `/health` wants
+//! `Authorization: Bearer <token>` ([`Request::authorize`]). Requests that
+//! carry an `Origin` header come from a browser page and are refused, and
+//! JSON bodies must be sent as `application/json`, which a cross-site form
+//! cannot do.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Largest request body accepted
+pub const MAX_BODY_BYTES: usize = 1 << 20;
+
+/// A parsed request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    /// Path without the query string (e.g. `/campaigns/nightly/stop`)
+    pub path: String,
+    pub query: BTreeMap<String, String>,
+    /// Headers by lowercased name
+    pub headers: BTreeMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Read one request from a connection
+    pub fn read(reader: &mut impl BufRead) -> Result<Request> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+            anyhow::bail!("Malformed request line: {:?}", line.trim_end());
+        };
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+        let mut headers = BTreeMap::new();
+        loop {
+            let mut header = String::new();
+            anyhow::ensure!(reader.read_line(&mut header)? > 0, "Connection closed in headers");
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+            }
+        }
+        let content_length: usize = match headers.get("content-length") {
+            Some(value) => value.parse().context("Invalid Content-Length")?,
+            None => 0,
+        };
+        anyhow::ensure!(content_length <= MAX_BODY_BYTES, "Request body too large ({} bytes)", content_length);
+
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+        Ok(Request {
+            method: method.to_string(),
+            path: percent_decode(path),
+            query: parse_query(query),
+            headers,
+            body,
+        })
+    }
+
+    /// Query parameter `key`
+    pub fn query(&self, key: &str) -> Option<&str> {
+        self.query.get(key).map(String::as_str)
+    }
+
+    /// Header `name` (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_ascii_lowercase()).map(String::as_str)
+    }
+
+    /// The error response for a request that is not allowed, if it is not
+    ///
+    /// Browser requests (with an `Origin` header) are refused outright, and
+    /// anything else must carry `Authorization: Bearer <token>`.
+    pub fn authorize(&self, token: &str) -> Option<Response> {
+        if self.header("origin").is_some() {
+            return Some(Response::error(403, "cross-origin requests are not allowed"));
+        }
+        let given = self.header("authorization").and_then(|value| value.strip_prefix("Bearer "));
+        match given {
+            Some(given) if constant_time_eq(given.trim().as_bytes(), token.as_bytes()) => None,
+            _ => Some(Response::error(401, "missing or wrong API token")),
+        }
+    }
+
+    /// Path split into its non-empty segments (`/a/b/` -> `["a", "b"]`)
+    pub fn segments(&self) -> Vec<&str> {
+        self.path.split('/').filter(|s| !s.is_empty()).collect()
+    }
+
+    /// Body parsed as JSON (an empty body parses as `{}`)
+    ///
+    /// A non-empty body must be sent as `Content-Type: application/json`.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        if self.body.is_empty() {
+            return serde_json::from_slice(b"{}").context("Invalid JSON body");
+        }
+        let content_type = self.header("content-type").unwrap_or_default();
+        anyhow::ensure!(
+            content_type.split(';').next().unwrap_or_default().trim().eq_ignore_ascii_case("application/json"),
+            "Expected a Content-Type of application/json, got {:?}",
+            content_type
+        );
+        let body: &[u8] = &self.body;
+        serde_json::from_slice(body).context("Invalid JSON body")
+    }
+}
+
+/// A response to send back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn json(status: u16, value: &impl Serialize) -> Response {
+        match serde_json::to_vec_pretty(value) {
+            Ok(body) => Response::bytes(status, "application/json", body),
+            Err(e) => Response::error(500, &e.to_string()),
+        }
+    }
+
+    /// `{"error": message}`
+    pub fn error(status: u16, message: &str) -> Response {
+        let body = serde_json::to_vec(&serde_json::json!({ "error": message })).unwrap_or_default();
+        Response::bytes(status, "application/json", body)
+    }
+
+    pub fn bytes(status: u16, content_type: &str, body: Vec<u8>) -> Response {
+        Response {
+            status,
+            content_type: content_type.to_string(),
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    /// Add a header (e.g. `Content-Disposition`)
+    pub fn header(mut self, name: &str, value: &str) -> Response {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> Result<()> {
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.status,
+            reason_phrase(self.status),
+            self.content_type,
+            self.body.len()
+        )?;
+        for (name, value) in &self.headers {
+            write!(writer, "{}: {}\r\n", name, value)?;
+        }
+        writer.write_all(b"\r\n")?;
+        writer.write_all(&self.body)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        _ if status >= 500 => "Internal Server Error",
+        _ => "",
+    }
+}
+
+/// Serve requests from `listener` until the process exits, handling each
+/// connection on its own thread
+pub fn serve<H>(listener: TcpListener, handler: H) -> Result<()>
+where
+    H: Fn(&Request) -> Response + Send + Sync + 'static,
+{
+    let handler = Arc::new(handler);
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let handler = Arc::clone(&handler);
+        std::thread::spawn(move || {
+            if let Err(e) = handle_connection(stream, handler.as_ref()) {
+                eprintln!("   ⚠️  HTTP connection failed: {:#}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle_connection(mut stream: TcpStream, handler: &dyn Fn(&Request) -> Response) -> Result<()> {
+    let response = match Request::read(&mut BufReader::new(&stream)) {
+        Ok(request) => handler(&request),
+        Err(e) => Response::error(400, &format!("{:#}", e)),
+    };
+    response.write_to(&mut stream)
+}
+
+/// POST `body` as JSON to `http://<addr><path>`, with `token` as the bearer
+/// token if given, and parse the JSON reply
+///
+/// Non-2xx replies are errors carrying the server's `error` message.
+pub fn post_json<T: DeserializeOwned>(
+    addr: &str,
+    path: &str,
+    token: Option<&str>,
+    body: &impl Serialize,
+    timeout: Duration,
+) -> Result<T> {
+    let body = serde_json::to_vec(body)?;
+    let mut stream = TcpStream::connect(addr).with_context(|| format!("Failed to connect to {}", addr))?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        path,
+        addr,
+        body.len()
+    )?;
+    if let Some(token) = token {
+        write!(stream, "Authorization: Bearer {}\r\n", token)?;
+    }
+    write!(stream, "\r\n")?;
+    stream.write_all(&body)?;
+    stream.flush()?;
+
+    let mut reader = BufReader::new(stream);
+    let mut status_line = String::new();
+    reader.read_line(&mut status_line)?;
+    let status: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .with_context(|| format!("Malformed response from {}: {:?}", addr, status_line.trim_end()))?;
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        anyhow::ensure!(reader.read_line(&mut header)? > 0, "Connection closed in response headers");
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = Some(value.trim().parse::<usize>().context("Invalid Content-Length")?);
+            }
+        }
+    }
+    let mut reply = Vec::new();
+    match content_length {
+        Some(len) => {
+            reply.resize(len, 0);
+            reader.read_exact(&mut reply)?;
+        }
+        None => {
+            reader.read_to_end(&mut reply)?;
+        }
+    }
+
+    if !(200..300).contains(&status) {
+        let message = serde_json::from_slice::<serde_json::Value>(&reply)
+            .ok()
+            .and_then(|v| v["error"].as_str().map(str::to_string))
+            .unwrap_or_else(|| String::from_utf8_lossy(&reply).into_owned());
+        anyhow::bail!("{}{} returned {}: {}", addr, path, status, message);
+    }
+    serde_json::from_slice(&reply).with_context(|| format!("Invalid JSON from {}{}", addr, path))
+}
+
+/// A fresh random API token (160 random bits, hex)
+pub fn new_token() -> String {
+    format!("{:020x}{:020x}", ulid::Ulid::new().random(), ulid::Ulid::new().random())
+}
+
+/// Compare without returning early at the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn parse_query(query: &str) -> BTreeMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(&value.replace('+', " ")))
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request() {
+        let raw = b"POST /campaigns/night%2D1/stop?core=fib&tag=a+b HTTP/1.1\r\nHost: x\r\ncontent-length: 13\r\nContent-Type: application/json\r\n\r\n{\"id\": \"n1\"}\n";
+        let request = Request::read(&mut &raw[..]).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.segments(), vec!["campaigns", "night-1", "stop"]);
+        assert_eq!(request.query("core"), Some("fib"));
+        assert_eq!(request.query("tag"), Some("a b"));
+        assert_eq!(request.json::<serde_json::Value>().unwrap()["id"], "n1");
+
+        let get = Request::read(&mut &b"GET /health HTTP/1.1\r\n\r\n"[..]).unwrap();
+        assert!(get.body.is_empty() && get.query.is_empty());
+        assert!(Request::read(&mut &b"\r\n"[..]).is_err());
+
+        // A cross-site form can only send text/plain and friends
+        let form = b"POST /campaigns HTTP/1.1\r\nContent-Type: text/plain\r\nContent-Length: 2\r\n\r\n{}";
+        let form = Request::read(&mut &form[..]).unwrap();
+        assert!(form.json::<serde_json::Value>().is_err());
+    }
+
+    #[test]
+    fn test_authorize() {
+        let request = |headers: &str| {
+            let raw = format!("GET /stats HTTP/1.1\r\n{}\r\n", headers);
+            Request::read(&mut raw.as_bytes()).unwrap()
+        };
+        let token = new_token();
+        assert_eq!(token.len(), 40);
+        assert_ne!(token, new_token());
+
+        assert_eq!(request(&format!("Authorization: Bearer {}\r\n", token)).authorize(&token), None);
+        assert_eq!(request("").authorize(&token).unwrap().status, 401);
+        assert_eq!(request("authorization: Bearer nope\r\n").authorize(&token).unwrap().status, 401);
+        let browser = request(&format!("Origin: https://evil.example\r\nAuthorization: Bearer {}\r\n", token));
+        assert_eq!(browser.authorize(&token).unwrap().status, 403);
+    }
+
+    #[test]
+    fn test_serve_round_trip() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            serve(listener, |request| {
+                if let Some(denied) = request.authorize("secret") {
+                    return denied;
+                }
+                match request.segments().as_slice() {
+                    ["echo"] => Response::bytes(200, "text/plain", request.body.clone()),
+                    _ => Response::error(404, "no such endpoint"),
+                }
+            })
+        });
+
+        let exchange = |raw: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(raw.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let ok = exchange("POST /echo HTTP/1.1\r\nAuthorization: Bearer secret\r\nContent-Length: 5\r\n\r\nhello");
+        assert!(ok.starts_with("HTTP/1.1 200 OK\r\n"), "{}", ok);
+        assert!(ok.ends_with("\r\n\r\nhello"));
+        assert!(exchange("GET /nope HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n").starts_with("HTTP/1.1 404 Not Found"));
+        assert!(exchange("GET /echo HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 401 Unauthorized"));
+
+        let timeout = Duration::from_secs(5);
+        let echoed: serde_json::Value =
+            post_json(&addr.to_string(), "/echo", Some("secret"), &serde_json::json!({ "n": 1 }), timeout).unwrap();
+        assert_eq!(echoed["n"], 1);
+        let err = post_json::<serde_json::Value>(&addr.to_string(), "/nope", Some("secret"), &(), timeout).unwrap_err();
+        assert!(err.to_string().contains("404: no such endpoint"), "{}", err);
+        let err = post_json::<serde_json::Value>(&addr.to_string(), "/echo", None, &(), timeout).unwrap_err();
+        assert!(err.to_string().contains("401: missing or wrong API token"), "{}", err);
+    }
+}
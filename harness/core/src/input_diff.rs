@@ -0,0 +1,247 @@
+//! Structural diff of a mutated input against its base seed
+//!
+//! When a mutated input diverges, the repro folder gets `input_diff.json`
+//! listing which fields the mutation changed and by how much (numeric
+//! deltas, length changes, first differing character), so triage starts
+//! from the fields that matter instead of a side-by-side read of two JSON
+//! files. Large values are summarized rather than copied.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Most changes listed before the rest are only counted
+const MAX_CHANGES: usize = 100;
+/// An array with more differing elements than this is reported as one change
+const MAX_ELEMENT_CHANGES: usize = 8;
+/// Longest string quoted in full
+const MAX_STRING_CHARS: usize = 64;
+/// Longest array quoted in full
+const MAX_ARRAY_ITEMS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// Same type, different value
+    Changed,
+    /// Array or string length changed
+    Resized,
+    /// Field only in the mutated input
+    Added,
+    /// Field only in the base input
+    Removed,
+    /// The JSON type changed (e.g. number to string)
+    TypeChanged,
+}
+
+/// One field the mutation changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    /// JSONPath of the field (e.g. `$.data`, `$.ops[2].value`)
+    pub path: String,
+    pub kind: ChangeKind,
+    /// Base value (summarized if large; None if added)
+    pub base: Option<Value>,
+    /// Mutated value (summarized if large; None if removed)
+    pub mutated: Option<Value>,
+    /// How much it changed (e.g. `+255`, `length 3 -> 262144`)
+    pub detail: String,
+}
+
+/// Fields a mutated input changed relative to its base seed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputDiff {
+    pub base_input: String,
+    pub changes: Vec<FieldChange>,
+    /// Changes beyond the first [`MAX_CHANGES`], not listed
+    pub omitted: usize,
+}
+
+impl InputDiff {
+    pub fn new(base_input: &str, base: &Value, mutated: &Value) -> InputDiff {
+        let mut changes = Vec::new();
+        diff_value("$", base, mutated, &mut changes);
+        let omitted = changes.len().saturating_sub(MAX_CHANGES);
+        changes.truncate(MAX_CHANGES);
+        InputDiff {
+            base_input: base_input.to_string(),
+            changes,
+            omitted,
+        }
+    }
+}
+
+impl std::fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = serde_json::to_value(self.kind).ok();
+        let kind = kind.as_ref().and_then(Value::as_str).unwrap_or("changed");
+        write!(f, "{} ({}: {})", self.path, kind, self.detail)
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// `value`, or a short description of it if it is too large to quote
+fn summarize(value: &Value) -> Value {
+    match value {
+        Value::String(s) if s.chars().count() > MAX_STRING_CHARS => {
+            let prefix: String = s.chars().take(MAX_STRING_CHARS).collect();
+            Value::String(format!("{}... ({} chars)", prefix, s.chars().count()))
+        }
+        Value::Array(items) if items.len() > MAX_ARRAY_ITEMS => {
+            Value::String(format!("<array of {} items>", items.len()))
+        }
+        Value::Object(fields) if serde_json::to_string(value).map_or(0, |s| s.len()) > 4 * MAX_STRING_CHARS => {
+            Value::String(format!("<object with {} fields>", fields.len()))
+        }
+        _ => value.clone(),
+    }
+}
+
+fn change(path: &str, kind: ChangeKind, base: Option<&Value>, mutated: Option<&Value>, detail: String) -> FieldChange {
+    FieldChange {
+        path: path.to_string(),
+        kind,
+        base: base.map(summarize),
+        mutated: mutated.map(summarize),
+        detail,
+    }
+}
+
+fn numeric_delta(base: &serde_json::Number, mutated: &serde_json::Number) -> String {
+    let as_int = |n: &serde_json::Number| n.as_i64().map(i128::from).or_else(|| n.as_u64().map(i128::from));
+    match (as_int(base), as_int(mutated)) {
+        (Some(a), Some(b)) => format!("{:+}", b - a),
+        _ => {
+            let (a, b) = (base.as_f64().unwrap_or(f64::NAN), mutated.as_f64().unwrap_or(f64::NAN));
+            format!("{:+}", b - a)
+        }
+    }
+}
+
+fn diff_value(path: &str, base: &Value, mutated: &Value, out: &mut Vec<FieldChange>) {
+    if base == mutated {
+        return;
+    }
+    match (base, mutated) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, value) in a {
+                let field = format!("{}.{}", path, key);
+                match b.get(key) {
+                    Some(other) => diff_value(&field, value, other, out),
+                    None => out.push(change(&field, ChangeKind::Removed, Some(value), None, "removed".to_string())),
+                }
+            }
+            for (key, value) in b.iter().filter(|(key, _)| !a.contains_key(*key)) {
+                let field = format!("{}.{}", path, key);
+                out.push(change(&field, ChangeKind::Added, None, Some(value), "added".to_string()));
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            if a.len() != b.len() {
+                out.push(change(
+                    path,
+                    ChangeKind::Resized,
+                    Some(base),
+                    Some(mutated),
+                    format!("length {} -> {} ({:+})", a.len(), b.len(), b.len() as i128 - a.len() as i128),
+                ));
+            }
+            let mut elements = Vec::new();
+            for (index, (x, y)) in a.iter().zip(b).enumerate() {
+                diff_value(&format!("{}[{}]", path, index), x, y, &mut elements);
+            }
+            let differing = a.iter().zip(b).filter(|(x, y)| x != y).count();
+            if differing > MAX_ELEMENT_CHANGES {
+                let first = a.iter().zip(b).position(|(x, y)| x != y).unwrap_or(0);
+                out.push(change(
+                    path,
+                    ChangeKind::Changed,
+                    Some(base),
+                    Some(mutated),
+                    format!(
+                        "{} of {} shared elements differ (first at index {})",
+                        differing,
+                        a.len().min(b.len()),
+                        first
+                    ),
+                ));
+            } else {
+                out.extend(elements);
+            }
+        }
+        (Value::String(a), Value::String(b)) => {
+            let first = a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count();
+            let (len_a, len_b) = (a.chars().count(), b.chars().count());
+            let (kind, detail) = if len_a != len_b {
+                (
+                    ChangeKind::Resized,
+                    format!("length {} -> {} chars, first difference at char {}", len_a, len_b, first),
+                )
+            } else {
+                (ChangeKind::Changed, format!("first difference at char {}", first))
+            };
+            out.push(change(path, kind, Some(base), Some(mutated), detail));
+        }
+        (Value::Number(a), Value::Number(b)) => {
+            out.push(change(path, ChangeKind::Changed, Some(base), Some(mutated), numeric_delta(a, b)));
+        }
+        (Value::Bool(a), Value::Bool(b)) => {
+            out.push(change(path, ChangeKind::Changed, Some(base), Some(mutated), format!("{} -> {}", a, b)));
+        }
+        _ => out.push(change(
+            path,
+            ChangeKind::TypeChanged,
+            Some(base),
+            Some(mutated),
+            format!("{} -> {}", type_name(base), type_name(mutated)),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_input_diff() {
+        let base = json!({ "a": 10, "b": 3, "op": "add", "data": [1, 2, 3], "name": "x", "flag": true });
+        let mutated = json!({ "a": 4294967295u64, "b": 3, "op": 7, "data": [1, 9, 3, 4], "flag": false, "extra": null });
+        let diff = InputDiff::new("inputs/base.json", &base, &mutated);
+
+        let summary: Vec<String> = diff.changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            summary,
+            [
+                "$.a (changed: +4294967285)",
+                "$.data (resized: length 3 -> 4 (+1))",
+                "$.data[1] (changed: +7)",
+                "$.flag (changed: true -> false)",
+                "$.name (removed: removed)",
+                "$.op (type_changed: string -> number)",
+                "$.extra (added: added)",
+            ]
+        );
+        assert_eq!(diff.omitted, 0);
+        assert_eq!(diff.changes[6].base, None);
+
+        // Large values are summarized, many element changes collapsed
+        let big = json!({ "data": vec![0u8; 1000], "text": "a".repeat(100) });
+        let changed = json!({ "data": vec![1u8; 1000], "text": "ab".repeat(50) });
+        let diff = InputDiff::new("base", &big, &changed);
+        assert_eq!(diff.changes.len(), 2);
+        assert_eq!(diff.changes[0].detail, "1000 of 1000 shared elements differ (first at index 0)");
+        assert_eq!(diff.changes[0].base, Some(json!("<array of 1000 items>")));
+        assert_eq!(diff.changes[1].detail, "first difference at char 1");
+        assert!(diff.changes[1].mutated.as_ref().unwrap().as_str().unwrap().ends_with("(100 chars)"));
+    }
+}
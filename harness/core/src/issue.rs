@@ -0,0 +1,195 @@
+//! Divergences as GitHub issue markdown
+//!
+//! `harness export-issue` renders a divergence signature's smallest run as
+//! a report to paste into an upstream issue: the environment, the input,
+//! both `RunResult`s, the oracle's diff and explanation, and how to
+//! reproduce it. Long blocks are cut at [`MAX_BLOCK_CHARS`] so the report
+//! stays under GitHub's 65536-character body limit; the full files are in
+//! the run's bundle (`harness bundle`).
+
+use crate::severity::Severity;
+use crate::RunLog;
+use serde::Serialize;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Longest code block before it is truncated
+pub const MAX_BLOCK_CHARS: usize = 8000;
+
+/// What goes into one issue
+pub struct IssueReport<'a> {
+    pub signature: &'a str,
+    /// Runs sharing the signature
+    pub runs: usize,
+    pub log: &'a RunLog,
+    /// The input file's contents
+    pub input: &'a [u8],
+    /// Sidecar files the input refers to, with their sizes
+    pub sidecars: &'a [(String, u64)],
+    pub severity: Option<&'a Severity>,
+}
+
+/// `text` cut to [`MAX_BLOCK_CHARS`], with a note if it was
+fn truncated(text: &str) -> String {
+    match text.char_indices().nth(MAX_BLOCK_CHARS) {
+        Some((end, _)) => format!(
+            "{}\n... ({} more characters; see the run's bundle)",
+            &text[..end],
+            text.chars().count() - MAX_BLOCK_CHARS
+        ),
+        None => text.to_string(),
+    }
+}
+
+fn pretty(value: &impl Serialize) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|e| format!("<unserializable: {}>", e))
+}
+
+impl IssueReport<'_> {
+    pub fn render(&self) -> String {
+        let log = self.log;
+        let core = Path::new(&log.core_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| log.core_path.clone());
+        let reason = log.diff.reason.as_deref().unwrap_or("results differ");
+        let mut out = String::new();
+
+        let _ = writeln!(out, "## {}: {}\n", core, reason);
+        let _ = writeln!(
+            out,
+            "Native ended with `{:?}`, SP1 with `{:?}`, on the same input.\n",
+            log.native_result.status, log.sp1_result.status
+        );
+        let _ = writeln!(out, "- Fingerprint: `{}` ({} runs, this one: `{}`)", self.signature, self.runs, log.run_id);
+        if let Some(severity) = self.severity {
+            let _ = writeln!(out, "- Severity: {}", severity);
+        }
+        if let Some(kind) = &log.diff.kind {
+            let _ = writeln!(out, "- Diff kind: `{}`", kind.as_str());
+        }
+
+        let _ = writeln!(out, "\n### Environment\n");
+        let _ = writeln!(out, "| | |\n|---|---|");
+        match &log.environment {
+            Some(env) => {
+                let _ = writeln!(out, "| SP1 | `{}` |", env.sp1_version);
+                let _ = writeln!(out, "| rustc | `{}` |", env.rustc_version);
+                let _ = writeln!(out, "| Target | `{}` |", env.zkvm_target);
+                let _ = writeln!(out, "| Guest ELF SHA-256 | `{}` |", env.elf_sha256.as_deref().unwrap_or("unknown"));
+                if let Some(toolchain) = &env.elf_sp1_toolchain {
+                    let _ = writeln!(out, "| ELF built with | `{}` |", toolchain);
+                }
+                let _ = writeln!(out, "| zk-fuzz-lab revision | `{}` |", env.describe_revision());
+            }
+            None => {
+                let _ = writeln!(out, "| Environment | not recorded (run logged before environment capture) |");
+            }
+        }
+        let _ = writeln!(out, "| Logged | {} |", log.timestamp);
+
+        let _ = writeln!(out, "\n### Input\n");
+        let _ = writeln!(out, "`{}` ({} bytes):\n", log.input_path, self.input.len());
+        let _ = writeln!(out, "```json\n{}\n```", truncated(String::from_utf8_lossy(self.input).trim_end()));
+        for (name, size) in self.sidecars {
+            let _ = writeln!(out, "\nPlus the sidecar `{}` ({} bytes), in the run's bundle.", name, size);
+        }
+
+        let _ = writeln!(out, "\n### Results\n");
+        for (label, result) in [("Native", &log.native_result), ("SP1", &log.sp1_result)] {
+            let _ = writeln!(
+                out,
+                "<details><summary>{}: {:?}, {} commits in {} ms</summary>\n\n```json\n{}\n```\n\n</details>\n",
+                label,
+                result.status,
+                result.commits.len(),
+                result.elapsed_ms,
+                truncated(&pretty(result))
+            );
+        }
+
+        let _ = writeln!(out, "### Diff\n");
+        let _ = writeln!(out, "```json\n{}\n```\n", truncated(&pretty(&log.diff)));
+        let explanation = rust_eq_oracle::explain::explain(&log.native_result, &log.sp1_result, &log.diff);
+        let _ = writeln!(out, "```\n{}```\n", explanation);
+
+        let revision = log
+            .environment
+            .as_ref()
+            .and_then(|env| env.git_commit.as_deref())
+            .unwrap_or("<commit>");
+        let _ = writeln!(out, "### Reproduce\n");
+        let _ = writeln!(
+            out,
+            "In a [zk-fuzz-lab](https://github.com/ivanvgreiff/zk-fuzz-lab) checkout at `{}`, save the input as `input.json` and run:\n",
+            revision
+        );
+        let _ = writeln!(out, "```bash\nharness run --core guest/cores/{} --input input.json\n```\n", core);
+        let _ = writeln!(
+            out,
+            "`harness bundle {}` packages the guest ELF, input, run log, commit schema and a repro script into one archive for this issue.",
+            log.run_id
+        );
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_eq_oracle::{Diff, DiffKind, RunResult, Status};
+
+    #[test]
+    fn test_render() {
+        let result = |status, commits: Vec<serde_json::Value>| RunResult {
+            status,
+            commits,
+            elapsed_ms: 3,
+            meta: serde_json::json!({}),
+        };
+        let log = RunLog {
+            schema_version: crate::run_log::SCHEMA_VERSION,
+            run_id: "20251021_041009_panic_test_x-0000".to_string(),
+            timestamp: "2025-10-21T04:10:09Z".to_string(),
+            core_path: "guest/cores/panic_test".to_string(),
+            input_path: "artifacts/default/panic_test/r/input.json".to_string(),
+            native_result: result(Status::Ok, vec![serde_json::json!(1)]),
+            sp1_result: result(Status::Panic, Vec::new()),
+            diff: Diff {
+                equal: false,
+                kind: Some(DiffKind::StatusMismatch {
+                    native: Status::Ok,
+                    zkvm: Status::Panic,
+                }),
+                reason: Some("status mismatch: native=Ok, zkvm=Panic".to_string()),
+                timing_delta_ms: None,
+                common_prefix_len: None,
+                first_divergent_index: None,
+                relations: Vec::new(),
+            },
+            environment: None,
+            checked_native: None,
+            campaign: None,
+            expectation: None,
+        };
+        let big_input = format!("{{\"data\": \"{}\"}}", "a".repeat(MAX_BLOCK_CHARS + 10));
+        let report = IssueReport {
+            signature: "panic_test|Ok|Panic|status mismatch",
+            runs: 3,
+            log: &log,
+            input: big_input.as_bytes(),
+            sidecars: &[],
+            severity: None,
+        }
+        .render();
+
+        assert!(report.starts_with("## panic_test: status mismatch: native=Ok, zkvm=Panic\n"), "{}", report);
+        for section in ["### Environment", "### Input", "### Results", "### Diff", "### Reproduce"] {
+            assert!(report.contains(section), "missing {}", section);
+        }
+        assert!(report.contains("`panic_test|Ok|Panic|status mismatch` (3 runs"));
+        assert!(report.contains("<summary>SP1: Panic, 0 commits in 3 ms</summary>"));
+        assert!(report.contains("... (22 more characters; see the run's bundle)"));
+        assert!(report.contains("harness run --core guest/cores/panic_test --input input.json"));
+    }
+}
@@ -0,0 +1,224 @@
+//! Latency histograms
+//!
+//! Native and SP1 latencies are reported as percentiles, since a mean over
+//! a campaign hides both the typical run and the slow tail. Samples go into
+//! HDR-style buckets: exact below 32 ms, then 32 buckets per power of two,
+//! so percentiles are within ~3% of the true value and memory stays bounded
+//! however many mutations run. The slowest samples are kept with their
+//! inputs, so the outliers can be rerun with `harness run`.
+
+use crate::cost_model::RunRecord;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Buckets per power of two (and the bound below which values are exact)
+const SUB_BUCKETS: u64 = 32;
+/// Slowest samples kept per histogram
+pub const OUTLIERS: usize = 3;
+
+/// A slow sample and the input that produced it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Outlier {
+    pub ms: u64,
+    pub input: String,
+}
+
+/// Latency samples of one runner
+#[derive(Debug, Clone, Default)]
+pub struct Histogram {
+    /// Lowest value of each bucket → samples in it
+    buckets: BTreeMap<u64, u64>,
+    count: u64,
+    total_ms: u128,
+    max_ms: u64,
+    /// Slowest first
+    slowest: Vec<Outlier>,
+}
+
+/// Lowest value of the bucket holding `value`
+fn bucket_of(value: u64) -> u64 {
+    if value < SUB_BUCKETS {
+        return value;
+    }
+    let shift = bucket_shift(value);
+    (value >> shift) << shift
+}
+
+/// Highest value of the bucket starting at `low`
+fn bucket_high(low: u64) -> u64 {
+    if low < SUB_BUCKETS {
+        return low;
+    }
+    low + ((1 << bucket_shift(low)) - 1)
+}
+
+fn bucket_shift(value: u64) -> u32 {
+    (63 - value.leading_zeros()) - SUB_BUCKETS.trailing_zeros()
+}
+
+impl Histogram {
+    /// Add one sample of `ms` milliseconds on `input`
+    pub fn record(&mut self, ms: u128, input: &str) {
+        let ms = u64::try_from(ms).unwrap_or(u64::MAX);
+        *self.buckets.entry(bucket_of(ms)).or_default() += 1;
+        self.count += 1;
+        self.total_ms += u128::from(ms);
+        self.max_ms = self.max_ms.max(ms);
+        if self.slowest.len() < OUTLIERS || self.slowest.last().is_some_and(|outlier| ms > outlier.ms) {
+            let at = self.slowest.partition_point(|outlier| outlier.ms >= ms);
+            self.slowest.insert(at, Outlier { ms, input: input.to_string() });
+            self.slowest.truncate(OUTLIERS);
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Smallest bucket bound at or below which `p`% of the samples fall
+    /// (capped at the largest sample; 0 without samples)
+    pub fn percentile(&self, p: f64) -> u64 {
+        let rank = ((p / 100.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (&low, &count) in &self.buckets {
+            seen += count;
+            if seen >= rank {
+                return bucket_high(low).min(self.max_ms);
+            }
+        }
+        self.max_ms
+    }
+
+    pub fn summary(&self) -> LatencySummary {
+        LatencySummary {
+            count: self.count,
+            mean_ms: if self.count == 0 { 0.0 } else { self.total_ms as f64 / self.count as f64 },
+            p50_ms: self.percentile(50.0),
+            p90_ms: self.percentile(90.0),
+            p99_ms: self.percentile(99.0),
+            max_ms: self.max_ms,
+            slowest: self.slowest.clone(),
+        }
+    }
+}
+
+/// Percentiles of a [`Histogram`], with its slowest samples
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencySummary {
+    pub count: u64,
+    pub mean_ms: f64,
+    pub p50_ms: u64,
+    pub p90_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+    pub slowest: Vec<Outlier>,
+}
+
+impl fmt::Display for LatencySummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "p50 {}ms, p90 {}ms, p99 {}ms, max {}ms (mean {:.1}ms)",
+            self.p50_ms, self.p90_ms, self.p99_ms, self.max_ms, self.mean_ms
+        )
+    }
+}
+
+/// Native and SP1 latencies of one core
+///
+/// Infrastructure failures are left out of their runner's histogram: their
+/// elapsed time says nothing about the core.
+#[derive(Debug, Clone, Default)]
+pub struct CoreLatency {
+    pub native: Histogram,
+    pub sp1: Histogram,
+}
+
+/// [`CoreLatency`] as percentiles
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoreLatencySummary {
+    pub native: LatencySummary,
+    pub sp1: LatencySummary,
+}
+
+impl CoreLatency {
+    pub fn record(&mut self, native: &rust_eq_oracle::RunResult, sp1: &rust_eq_oracle::RunResult, input: &str) {
+        if !native.status.is_infra() {
+            self.native.record(native.elapsed_ms, input);
+        }
+        if !sp1.status.is_infra() {
+            self.sp1.record(sp1.elapsed_ms, input);
+        }
+    }
+
+    pub fn summary(&self) -> CoreLatencySummary {
+        CoreLatencySummary {
+            native: self.native.summary(),
+            sp1: self.sp1.summary(),
+        }
+    }
+}
+
+/// Latencies per core of logged runs (`runs.jsonl`)
+pub fn by_core(records: &[RunRecord]) -> BTreeMap<String, CoreLatency> {
+    let mut cores: BTreeMap<String, CoreLatency> = BTreeMap::new();
+    for record in records {
+        let latency = cores.entry(record.core.clone()).or_default();
+        if !record.native_status.is_infra() {
+            latency.native.record(record.native_ms, &record.input);
+        }
+        if !record.sp1_status.is_infra() {
+            latency.sp1.record(record.sp1_ms, &record.input);
+        }
+    }
+    cores
+}
+
+/// Print one runner's latency percentiles and slowest inputs
+pub fn print_latency(label: &str, latency: &LatencySummary) {
+    if latency.count == 0 {
+        println!("      {}: no runs", label);
+        return;
+    }
+    println!("      {}: {}", label, latency);
+    for outlier in &latency.slowest {
+        println!("         {:>7}ms  {}", outlier.ms, outlier.input);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buckets() {
+        assert_eq!((bucket_of(7), bucket_high(7)), (7, 7));
+        assert_eq!((bucket_of(31), bucket_high(31)), (31, 31));
+        assert_eq!((bucket_of(32), bucket_high(32)), (32, 32));
+        assert_eq!((bucket_of(65), bucket_high(64)), (64, 65));
+        assert_eq!((bucket_of(1000), bucket_high(bucket_of(1000))), (992, 1007));
+        assert_eq!(bucket_high(bucket_of(u64::MAX)), u64::MAX);
+    }
+
+    #[test]
+    fn test_percentiles_and_outliers() {
+        let mut histogram = Histogram::default();
+        assert_eq!(histogram.summary().p99_ms, 0);
+        for ms in 1..=100 {
+            histogram.record(ms, &format!("input_{}.json", ms));
+        }
+        histogram.record(5000, "slow.json");
+
+        let summary = histogram.summary();
+        assert_eq!(summary.count, 101);
+        assert_eq!(summary.p50_ms, 51);
+        assert!((91..=93).contains(&summary.p90_ms), "{}", summary.p90_ms);
+        assert!((100..=103).contains(&summary.p99_ms), "{}", summary.p99_ms);
+        assert_eq!(summary.max_ms, 5000);
+        assert_eq!(histogram.percentile(100.0), 5000);
+        let slowest: Vec<(u64, &str)> = summary.slowest.iter().map(|o| (o.ms, o.input.as_str())).collect();
+        assert_eq!(slowest, vec![(5000, "slow.json"), (100, "input_100.json"), (99, "input_99.json")]);
+        assert_eq!(summary.to_string(), format!("p50 51ms, p90 {}ms, p99 {}ms, max 5000ms (mean 99.5ms)", summary.p90_ms, summary.p99_ms));
+    }
+}
@@ -0,0 +1,443 @@
+//! Run artifact layout
+//!
+//! Every logged run gets a folder `artifacts/<campaign>/<core>/<run_id>/`
+//! (campaign `default` outside `--campaign`) holding its `run_log.json` and,
+//! for divergences, the repro files. `artifacts/index.json` maps run IDs to
+//! their folders, so `repro`, `bundle` and `serve` find a run without walking
+//! the tree.
+//!
+//! Logging a run appends one line to `artifacts/index.jsonl` instead of
+//! rewriting the whole index; readers replay those lines over
+//! `index.json`. Once the log passes [`COMPACT_AFTER`] lines, the next
+//! [`load_index`] folds it into `index.json`.
+//!
+//! Runs logged before this layout (`artifacts/<run_id>.json` and
+//! `artifacts/<run_id>/`) are still found: a run missing from the index
+//! triggers a rescan of `artifacts/` that indexes both layouts.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const ARTIFACTS_DIR: &str = "artifacts";
+const INDEX_FILE: &str = "index.json";
+const INDEX_LOG_FILE: &str = "index.jsonl";
+
+/// Lines of `index.jsonl` after which it is folded into `index.json`
+pub const COMPACT_AFTER: usize = 1000;
+
+/// Campaign folder of runs logged outside a campaign
+pub const DEFAULT_CAMPAIGN: &str = "default";
+
+/// `artifacts/` entries that are not campaign folders (and so not campaign ids)
+pub const RESERVED: &[&str] = &[
+    "bench", "builds", "bundles", "campaigns", "encoding", "environments", "matrix", "mutations", "notify", "properties",
+    "purity", "reports", "replay", "scale", "soak",
+];
+
+/// Where one run's artifacts live
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub core: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub campaign: Option<String>,
+    pub timestamp: String,
+    pub equal: bool,
+    /// The run log
+    pub log: PathBuf,
+    /// Folder holding the log and repro files (None for legacy passing runs,
+    /// which only have `artifacts/<run_id>.json`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<PathBuf>,
+    /// Logged before the `<campaign>/<core>/<run_id>/` layout
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub legacy: bool,
+}
+
+/// `artifacts/index.json`: run ID → [`IndexEntry`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunIndex {
+    pub runs: BTreeMap<String, IndexEntry>,
+}
+
+/// One line of `artifacts/index.jsonl`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct IndexLine {
+    run_id: String,
+    #[serde(flatten)]
+    entry: IndexEntry,
+}
+
+/// `artifacts/<campaign>/<core>/<run_id>/`
+pub fn run_dir(campaign: Option<&str>, core_name: &str, run_id: &str) -> PathBuf {
+    Path::new(ARTIFACTS_DIR)
+        .join(campaign.unwrap_or(DEFAULT_CAMPAIGN))
+        .join(core_name)
+        .join(run_id)
+}
+
+/// Folder of a run logged by this process (under the current campaign)
+pub fn current_run_dir(core_name: &str, run_id: &str) -> PathBuf {
+    run_dir(crate::campaign::current_id().as_deref(), core_name, run_id)
+}
+
+/// Write a run's log into its folder and add it to the index
+///
+/// The folder is created with `create_dir`, so a run ID collision fails
+/// loudly instead of overwriting another run. Returns the folder.
+pub fn write_run_log(log: &crate::RunLog, log_json: &str) -> Result<PathBuf> {
+    let core_name = core_name_of(log)?;
+    let dir = current_run_dir(&core_name, &log.run_id);
+    fs::create_dir_all(dir.parent().expect("run folders have a parent"))?;
+    fs::create_dir(&dir).with_context(|| format!("Failed to create run folder {}", dir.display()))?;
+    let log_path = dir.join("run_log.json");
+    fs::write(&log_path, log_json)?;
+    record_run(&log.run_id, entry_for(log, log_path, Some(dir.clone()), false)?)?;
+    Ok(dir)
+}
+
+/// Load the run index, compacting `artifacts/index.jsonl` if it grew long
+///
+/// Without an index (a tree from before this layout), `artifacts/` is
+/// scanned and the index written first.
+pub fn load_index() -> Result<RunIndex> {
+    let root = Path::new(ARTIFACTS_DIR);
+    if !index_path(root).exists() && !index_log_path(root).exists() {
+        let index = scan(root)?;
+        if !index.runs.is_empty() {
+            save_index_in(root, &index)?;
+        }
+        return Ok(index);
+    }
+    if count_lines(&index_log_path(root)) > COMPACT_AFTER {
+        compact_in(root)?;
+    }
+    load_index_in(root)
+}
+
+/// Add or replace a run in the index
+///
+/// One line appended to `artifacts/index.jsonl` in a single write, so
+/// concurrent runs never rewrite or clobber each other's entries.
+pub fn record_run(run_id: &str, entry: IndexEntry) -> Result<()> {
+    record_run_in(Path::new(ARTIFACTS_DIR), run_id, entry)
+}
+
+fn record_run_in(root: &Path, run_id: &str, entry: IndexEntry) -> Result<()> {
+    use std::io::Write;
+
+    let line = IndexLine {
+        run_id: run_id.to_string(),
+        entry,
+    };
+    fs::create_dir_all(root)?;
+    let path = index_log_path(root);
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.write_all(format!("{}\n", serde_json::to_string(&line)?).as_bytes())
+        .with_context(|| format!("Failed to append to {}", path.display()))
+}
+
+/// Locate a run by ID: the index first, then a rescan of both layouts
+///
+/// A rescan rewrites the index, so legacy runs are indexed on first lookup.
+pub fn find_run(run_id: &str) -> Result<Option<IndexEntry>> {
+    let root = Path::new(ARTIFACTS_DIR);
+    if let Some(entry) = load_index_in(root)?.runs.remove(run_id) {
+        if entry.log.is_file() {
+            return Ok(Some(entry));
+        }
+    }
+    let mut index = scan(root)?;
+    save_index_in(root, &index)?;
+    Ok(index.runs.remove(run_id))
+}
+
+fn index_path(root: &Path) -> PathBuf {
+    root.join(INDEX_FILE)
+}
+
+fn index_log_path(root: &Path) -> PathBuf {
+    root.join(INDEX_LOG_FILE)
+}
+
+fn count_lines(path: &Path) -> usize {
+    fs::read(path)
+        .map(|bytes| bytes.iter().filter(|byte| **byte == b'\n').count())
+        .unwrap_or(0)
+}
+
+/// `index.json` with the lines of `index.jsonl` applied in order
+fn load_index_in(root: &Path) -> Result<RunIndex> {
+    let path = index_path(root);
+    let mut index = if path.exists() {
+        let text = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))?
+    } else {
+        RunIndex::default()
+    };
+    replay(&index_log_path(root), &mut index)?;
+    Ok(index)
+}
+
+/// Apply the lines of an index log to `index`
+///
+/// A line that does not parse (a write cut short by a crash) is skipped.
+fn replay(path: &Path, index: &mut RunIndex) -> Result<()> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        if let Ok(line) = serde_json::from_str::<IndexLine>(line) {
+            index.runs.insert(line.run_id, line.entry);
+        }
+    }
+    Ok(())
+}
+
+/// Fold `index.jsonl` into `index.json`
+///
+/// The log is renamed away first, so runs logged meanwhile start a new one
+/// instead of being truncated. A reader between the rename and the rewrite
+/// misses the folded entries; [`find_run`] recovers them by rescanning.
+fn compact_in(root: &Path) -> Result<()> {
+    let log_path = index_log_path(root);
+    let folding = root.join(format!("{}.{}.tmp", INDEX_LOG_FILE, std::process::id()));
+    if fs::rename(&log_path, &folding).is_err() {
+        // Another process got there first
+        return Ok(());
+    }
+    let mut index = load_index_in(root)?;
+    replay(&folding, &mut index)?;
+    save_index_in(root, &index)?;
+    fs::remove_file(&folding).with_context(|| format!("Failed to remove {}", folding.display()))
+}
+
+fn save_index_in(root: &Path, index: &RunIndex) -> Result<()> {
+    fs::create_dir_all(root)?;
+    let path = index_path(root);
+    let tmp = root.join(format!("{}.{}.tmp", INDEX_FILE, std::process::id()));
+    fs::write(&tmp, serde_json::to_string_pretty(index)?)?;
+    fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Index every run log under `root`, in either layout
+///
+/// Where a run exists in both layouts, the `<campaign>/<core>/<run_id>/`
+/// folder wins. Unreadable logs are skipped with a warning.
+fn scan(root: &Path) -> Result<RunIndex> {
+    let mut index = RunIndex::default();
+    let Ok(entries) = fs::read_dir(root) else {
+        return Ok(index);
+    };
+    let mut legacy = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if path.is_file() {
+            // Legacy passing run: artifacts/<run_id>.json (other JSON files
+            // here, like baselines.json, have no run_id)
+            if let Some(run_id) = name.strip_suffix(".json").filter(|_| has_run_id(&path)) {
+                legacy.push((run_id.to_string(), path, None));
+            }
+        } else if path.join("run_log.json").is_file() {
+            // Legacy divergence: artifacts/<run_id>/run_log.json
+            legacy.push((name, path.join("run_log.json"), Some(path)));
+        } else if path.is_dir() && !RESERVED.contains(&name.as_str()) {
+            for run_dir in subdirs(&path).iter().flat_map(|core_dir| subdirs(core_dir)) {
+                let log_path = run_dir.join("run_log.json");
+                if !log_path.is_file() {
+                    continue;
+                }
+                if let Some(log) = read_log(&log_path) {
+                    if let Ok(entry) = entry_for(&log, log_path, Some(run_dir), false) {
+                        index.runs.insert(log.run_id, entry);
+                    }
+                }
+            }
+        }
+    }
+    for (run_id, log_path, dir) in legacy {
+        if index.runs.contains_key(&run_id) {
+            continue;
+        }
+        // A legacy divergence folder also left a log at the root; prefer the folder
+        if dir.is_none() && root.join(&run_id).join("run_log.json").is_file() {
+            continue;
+        }
+        if let Some(log) = read_log(&log_path).filter(|log| log.run_id == run_id) {
+            if let Ok(entry) = entry_for(&log, log_path, dir, true) {
+                index.runs.insert(run_id, entry);
+            }
+        }
+    }
+    Ok(index)
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries.flatten().map(|entry| entry.path()).filter(|path| path.is_dir()).collect()
+}
+
+/// Whether a JSON file is an object with a `run_id`
+fn has_run_id(path: &Path) -> bool {
+    fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(&bytes).ok())
+        .is_some_and(|value| value.get("run_id").is_some())
+}
+
+/// A run log, or None with a warning if it does not parse
+fn read_log(path: &Path) -> Option<crate::RunLog> {
+    match crate::run_log::load(path) {
+        Ok(log) => Some(log),
+        Err(e) => {
+            eprintln!("   ⚠️  Not indexing {}: {:#}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn core_name_of(log: &crate::RunLog) -> Result<String> {
+    Path::new(&log.core_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(str::to_string)
+        .with_context(|| format!("Invalid core path in run log: {}", log.core_path))
+}
+
+fn entry_for(log: &crate::RunLog, log_path: PathBuf, dir: Option<PathBuf>, legacy: bool) -> Result<IndexEntry> {
+    Ok(IndexEntry {
+        core: core_name_of(log)?,
+        campaign: log.campaign.as_ref().map(|campaign| campaign.id.clone()),
+        timestamp: log.timestamp.clone(),
+        equal: log.diff.equal,
+        log: log_path,
+        dir,
+        legacy,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_eq_oracle::{Diff, RunResult, Status};
+
+    fn log(run_id: &str, equal: bool) -> String {
+        let result = RunResult {
+            status: Status::Ok,
+            commits: Vec::new(),
+            elapsed_ms: 1,
+            meta: serde_json::json!({}),
+        };
+        serde_json::to_string(&crate::RunLog {
+            schema_version: crate::run_log::SCHEMA_VERSION,
+            run_id: run_id.to_string(),
+            timestamp: "2025-10-21T04:02:25Z".to_string(),
+            core_path: "guest/cores/fib".to_string(),
+            input_path: "inputs/fib_24.json".to_string(),
+            native_result: result.clone(),
+            sp1_result: result,
+            diff: Diff {
+                equal,
+                kind: None,
+                reason: None,
+                timing_delta_ms: None,
+                common_prefix_len: None,
+                first_divergent_index: None,
+                relations: Vec::new(),
+            },
+            environment: None,
+            checked_native: None,
+            campaign: None,
+            expectation: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_scan_indexes_both_layouts() {
+        let root = std::env::temp_dir().join(format!("harness-layout-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let new_dir = root.join("nightly/fib/run_new");
+        fs::create_dir_all(&new_dir).unwrap();
+        fs::write(new_dir.join("run_log.json"), log("run_new", false)).unwrap();
+        fs::write(root.join("run_pass.json"), log("run_pass", true)).unwrap();
+        fs::create_dir_all(root.join("run_old")).unwrap();
+        fs::write(root.join("run_old/run_log.json"), log("run_old", false)).unwrap();
+        fs::write(root.join("run_old.json"), log("run_old", false)).unwrap();
+        fs::create_dir_all(root.join("matrix")).unwrap();
+        fs::write(root.join("matrix/run_matrix.json"), "{}").unwrap();
+        fs::write(root.join("baselines.json"), "{}").unwrap();
+        // Unknown fields make a log unreadable: warned about, not indexed
+        let bad_dir = root.join("nightly/fib/run_bad");
+        fs::create_dir_all(&bad_dir).unwrap();
+        let mut bad: serde_json::Value = serde_json::from_str(&log("run_bad", false)).unwrap();
+        bad["sp1_result"]["cycles"] = serde_json::json!(1000);
+        fs::write(bad_dir.join("run_log.json"), bad.to_string()).unwrap();
+
+        let index = scan(&root).unwrap();
+        assert_eq!(index.runs.len(), 3);
+        let new = &index.runs["run_new"];
+        assert_eq!((new.core.as_str(), new.legacy, new.equal), ("fib", false, false));
+        assert_eq!(new.dir.as_deref(), Some(new_dir.as_path()));
+        let pass = &index.runs["run_pass"];
+        assert!(pass.legacy && pass.dir.is_none());
+        assert_eq!(index.runs["run_old"].log, root.join("run_old/run_log.json"));
+
+        save_index_in(&root, &index).unwrap();
+        assert_eq!(load_index_in(&root).unwrap().runs, index.runs);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_index_log() {
+        let root = std::env::temp_dir().join(format!("harness-index-log-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let entry = |core: &str, equal| IndexEntry {
+            core: core.to_string(),
+            campaign: None,
+            timestamp: "2025-10-21T04:02:25Z".to_string(),
+            equal,
+            log: PathBuf::from(format!("artifacts/default/{}/run_log.json", core)),
+            dir: None,
+            legacy: false,
+        };
+        record_run_in(&root, "r1", entry("fib", true)).unwrap();
+        record_run_in(&root, "r2", entry("sorting", true)).unwrap();
+        record_run_in(&root, "r1", entry("fib", false)).unwrap();
+        assert!(!index_path(&root).exists());
+        assert_eq!(count_lines(&index_log_path(&root)), 3);
+
+        // A torn last line is skipped
+        let mut log = fs::read_to_string(index_log_path(&root)).unwrap();
+        log.push_str("{\"run_id\": \"r3\", \"co");
+        fs::write(index_log_path(&root), log).unwrap();
+
+        let index = load_index_in(&root).unwrap();
+        assert_eq!(index.runs.len(), 2);
+        assert!(!index.runs["r1"].equal);
+
+        compact_in(&root).unwrap();
+        assert!(!index_log_path(&root).exists());
+        assert_eq!(load_index_in(&root).unwrap().runs, index.runs);
+        record_run_in(&root, "r4", entry("fib", true)).unwrap();
+        assert_eq!(load_index_in(&root).unwrap().runs.len(), 3);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_run_dir() {
+        assert_eq!(run_dir(None, "fib", "r1"), Path::new("artifacts/default/fib/r1"));
+        assert_eq!(run_dir(Some("nightly"), "fib", "r1"), Path::new("artifacts/nightly/fib/r1"));
+    }
+}
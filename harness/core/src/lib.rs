@@ -0,0 +1,2092 @@
+//! Differential-testing core of the zk-fuzz-lab harness
+//!
+//! Builds SP1 guests, runs the native and SP1 runners, compares their
+//! results with the `rust_eq` oracle and logs outcomes to `artifacts/`.
+//! The `harness` binary is a CLI over this library; other test suites and
+//! build scripts can drive differential tests directly:
+//!
+//! ```no_run
+//! use harness_core::DifferentialTest;
+//!
+//! let outcome = DifferentialTest::new("guest/cores/fib", "inputs/fib_24.json")
+//!     .skip_build(true)
+//!     .run()?;
+//! assert!(outcome.diff.equal, "{:?}", outcome.diff.reason);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! All paths are relative to the repository root, which must be the
+//! current working directory.
+//!
+//! Differential tests can also be registered as `#[test]` cases with
+//! [`zk_difftest!`], so `cargo test` runs a smoke subset of cross-target
+//! checks.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use input_preprocess::Preprocess;
+use rust_eq_oracle::expect::{Expectation, ExpectationCheck};
+use rust_eq_oracle::{compare_semantics, Diff, RunResult, SemanticsReport, Status};
+use serde::{Deserialize, Serialize};
+use source_mutator::MutatedInput;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::OnceLock;
+
+pub mod baseline;
+pub mod bisect;
+pub mod bench;
+pub mod buckets;
+pub mod build;
+pub mod campaign;
+pub mod commits;
+pub mod container;
+pub mod corpus;
+pub mod cost_model;
+pub mod distributed;
+pub mod doctor;
+pub mod environment;
+pub mod fuzz;
+pub mod http;
+pub mod input_diff;
+pub mod issue;
+pub mod latency;
+pub mod layout;
+pub mod matrix;
+pub mod notify;
+pub mod opcode_mix;
+pub mod promotion;
+pub mod properties;
+pub mod purity;
+pub mod relations;
+pub mod replay;
+pub mod run_log;
+pub mod sarif;
+pub mod scale;
+pub mod severity;
+pub mod soak;
+pub mod storage;
+pub mod symbolicate;
+pub mod trace;
+pub mod provenance;
+pub mod watch;
+
+/// A single differential test: one core, one input, native vs SP1
+///
+/// Mirrors `harness run`: builds the SP1 guest (unless skipped), runs both
+/// runners with the retry policy, compares the results and (by default)
+/// logs them to `artifacts/`.
+#[derive(Debug, Clone)]
+pub struct DifferentialTest {
+    core_path: PathBuf,
+    input_path: PathBuf,
+    skip_build: bool,
+    retry: RetryPolicy,
+    log_artifacts: bool,
+    verbose: bool,
+    sandbox: bool,
+    checked_native: bool,
+}
+
+/// Results of a differential test
+#[derive(Debug, Clone)]
+pub struct DifferentialOutcome {
+    pub native: RunResult,
+    pub sp1: RunResult,
+    pub diff: Diff,
+    /// Overflow-checked native run and three-way report (with `checked_native`)
+    pub checked_native: Option<CheckedNative>,
+    /// The input's `expected` block held to the results (None if it has none)
+    pub expectation: Option<ExpectationCheck>,
+    /// Run ID under `artifacts/` (None if logging was disabled)
+    pub run_id: Option<String>,
+}
+
+/// Native run built with overflow checks, compared with release native and SP1
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckedNative {
+    pub result: RunResult,
+    pub semantics: SemanticsReport,
+}
+
+impl DifferentialTest {
+    /// Test `core` on `input`
+    ///
+    /// `core` is a core path (e.g., `guest/cores/fib`) or a bare core name
+    /// (e.g., `fib`), which is resolved under `guest/cores/`.
+    pub fn new(core: impl AsRef<Path>, input: impl AsRef<Path>) -> Self {
+        let core = core.as_ref();
+        let core_path = if core.components().count() == 1 {
+            PathBuf::from("guest/cores").join(core)
+        } else {
+            core.to_path_buf()
+        };
+        DifferentialTest {
+            core_path,
+            input_path: input.as_ref().to_path_buf(),
+            skip_build: false,
+            retry: RetryPolicy::default(),
+            log_artifacts: true,
+            verbose: false,
+            sandbox: false,
+            checked_native: false,
+        }
+    }
+
+    /// Skip building the SP1 guest (use the existing ELF)
+    pub fn skip_build(mut self, skip_build: bool) -> Self {
+        self.skip_build = skip_build;
+        self
+    }
+
+    /// Retry policy for infrastructure failures
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Log results, repro folders and the CSV row to `artifacts/` (default: true)
+    pub fn log_artifacts(mut self, log_artifacts: bool) -> Self {
+        self.log_artifacts = log_artifacts;
+        self
+    }
+
+    /// Print step-by-step progress to stdout (default: false)
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Run the native side in the native runner's sandbox (default: false)
+    pub fn sandbox(mut self, sandbox: bool) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Also run native built with overflow checks and classify where release
+    /// native, checked native and SP1 disagree (default: false)
+    pub fn checked_native(mut self, checked_native: bool) -> Self {
+        self.checked_native = checked_native;
+        self
+    }
+
+    /// Build, run both sides, compare and log
+    pub fn run(&self) -> Result<DifferentialOutcome> {
+        let core_name = self
+            .core_path
+            .file_name()
+            .context("Invalid core path")?
+            .to_str()
+            .context("Non-UTF8 core name")?;
+        let input_path = self.input_path.as_path();
+        let guest_path = guest_path_for_core(core_name);
+        let elf_path = elf_path_for_core(core_name);
+
+        // Step 1: Build SP1 guest (unless skip_build is set)
+        let mut build_error = None;
+        if !self.skip_build {
+            self.say("📦 Building SP1 guest...");
+            match self.retry.run("SP1 guest build", || build_sp1_guest(&guest_path)) {
+                (Ok(build), _) => self.say(format!("   ✅ SP1 guest built ({})\n", build.summary())),
+                (Err(e), retries) => {
+                    self.say(format!("   ❌ SP1 guest build failed: {:#}\n", e));
+                    build_error = Some((e, retries));
+                }
+            }
+        } else {
+            self.say("⏩ Skipping SP1 guest build\n");
+        }
+
+        // Step 2: Run native runner
+        self.say("🏃 Running native...");
+        let native = self
+            .retry
+            .run_runner("native", || run_native_runner_sandboxed(core_name, input_path, self.sandbox));
+        self.say(format!("   ✅ Native completed in {}ms\n", native.elapsed_ms));
+
+        // Step 3: Run SP1 runner (a failed build is recorded as BUILD_FAILURE)
+        let sp1 = match &build_error {
+            Some((e, retries)) => build_failure_result(e, *retries),
+            None => {
+                self.say("🏃 Running SP1...");
+                let result = self
+                    .retry
+                    .run_runner("sp1", || run_sp1_runner(&elf_path, input_path, core_name));
+                self.say(format!("   ✅ SP1 completed in {}ms\n", result.elapsed_ms));
+                result
+            }
+        };
+
+        // Step 4: Compare results (three ways with the overflow-checked build)
+        let diff = relations::compare_for_core(core_name, input_path, &native, &sp1)?;
+        let expectation = check_expectation(input_path, &native, &sp1, &diff)?;
+        let checked_native = if self.checked_native {
+            self.say("🏃 Running native (overflow checks)...");
+            let result = self.retry.run_runner("native", || {
+                run_native_runner_with_profile(core_name, input_path, self.sandbox, NativeProfile::Checked)
+            });
+            self.say(format!("   ✅ Checked native completed in {}ms\n", result.elapsed_ms));
+            let semantics = compare_semantics(&native, &result, &sp1);
+            Some(CheckedNative { result, semantics })
+        } else {
+            None
+        };
+
+        // Step 5: Log results
+        let run_id = if self.log_artifacts {
+            Some(log_results_with_checked(
+                &self.core_path,
+                input_path,
+                native.clone(),
+                sp1.clone(),
+                diff.clone(),
+                checked_native.clone(),
+            )?)
+        } else {
+            None
+        };
+
+        Ok(DifferentialOutcome {
+            native,
+            sp1,
+            diff,
+            checked_native,
+            expectation,
+            run_id,
+        })
+    }
+
+    fn say(&self, message: impl std::fmt::Display) {
+        if self.verbose {
+            println!("{}", message);
+        }
+    }
+}
+
+/// Register a differential test as a `#[test]` case
+///
+/// ```ignore
+/// harness_core::zk_difftest!(fib_24, "fib", "inputs/fib_24.json");
+/// ```
+///
+/// The test fails if the native and SP1 results differ. It needs the SP1
+/// toolchain, so it is `#[ignore]`d: run it with `cargo test -- --ignored`.
+#[macro_export]
+macro_rules! zk_difftest {
+    ($name:ident, $core:expr, $input:expr $(,)?) => {
+        #[test]
+        #[ignore = "needs the SP1 toolchain; run with --ignored"]
+        fn $name() {
+            $crate::run_difftest(
+                env!("CARGO_MANIFEST_DIR"),
+                concat!(module_path!(), "::", stringify!($name)),
+                $core,
+                $input,
+            );
+        }
+    };
+}
+
+/// Set in the test process [`run_difftest`] starts in the repository root
+const DIFFTEST_CHILD_VAR: &str = "ZKFUZZ_DIFFTEST_CHILD";
+
+/// Run a differential test from `cargo test` (used by [`zk_difftest!`])
+///
+/// The harness resolves cores, inputs and artifacts against the working
+/// directory, which parallel tests must not change. So the test re-runs
+/// itself (`test`, its `module_path!()::name`) in a child process started
+/// in the repository root, found by walking up from `manifest_dir`; the
+/// child runs the comparison and panics if the results differ.
+pub fn run_difftest(manifest_dir: &str, test: &str, core: &str, input: &str) {
+    if std::env::var_os(DIFFTEST_CHILD_VAR).is_none() {
+        let root = find_repo_root(Path::new(manifest_dir))
+            .unwrap_or_else(|| panic!("no zk-fuzz-lab repository root above {}", manifest_dir));
+        // libtest names tests without the crate's own module
+        let filter = test.split_once("::").map_or(test, |(_, path)| path);
+        let exe = std::env::current_exe().expect("failed to locate the test binary");
+        let output = Command::new(exe)
+            .args([filter, "--exact", "--ignored", "--nocapture"])
+            .env(DIFFTEST_CHILD_VAR, "1")
+            .current_dir(&root)
+            .output()
+            .unwrap_or_else(|e| panic!("failed to re-run {} in {}: {}", filter, root.display(), e));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        print!("{}", stdout);
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        assert!(output.status.success(), "differential test {} on {} failed", core, input);
+        assert!(stdout.contains("1 passed"), "{} did not run in the child process", filter);
+        return;
+    }
+
+    assert!(
+        Path::new("guest/cores").is_dir(),
+        "{} must run in the repository root",
+        DIFFTEST_CHILD_VAR
+    );
+    assert!(
+        get_sp1_version() != "unknown",
+        "differential test {} on {} needs the SP1 toolchain (`cargo prove`)",
+        core,
+        input
+    );
+
+    let outcome = DifferentialTest::new(core, input)
+        .log_artifacts(false)
+        .run()
+        .unwrap_or_else(|e| panic!("differential test {} on {} failed to run: {:#}", core, input, e));
+
+    if let Some(check) = &outcome.expectation {
+        assert!(
+            check.confirmed,
+            "differential test {} on {} gave an unexpected result: {}",
+            core,
+            input,
+            check.mismatches.join("; ")
+        );
+        return;
+    }
+    assert!(
+        outcome.diff.equal,
+        "differential test {} on {} diverged: {}\n  native: {:?} {:?}\n  sp1: {:?} {:?}",
+        core,
+        input,
+        outcome.diff.reason.as_deref().unwrap_or("unknown reason"),
+        outcome.native.status,
+        outcome.native.commits,
+        outcome.sp1.status,
+        outcome.sp1.commits,
+    );
+}
+
+/// Closest ancestor of `dir` that contains `guest/cores`
+fn find_repo_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|ancestor| ancestor.join("guest/cores").is_dir())
+        .map(Path::to_path_buf)
+}
+
+/// Retry policy for infrastructure failures (guest build, runner spawn, SDK setup)
+///
+/// Only failures where no genuine guest result was produced are retried;
+/// PANIC/TIMEOUT/etc. results are never retried.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Extra attempts after an infrastructure failure
+    pub retries: u32,
+    /// Delay before the first retry in milliseconds (doubled on each retry)
+    pub backoff_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            retries: 2,
+            backoff_ms: 500,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Run `attempt` until it succeeds or the retries are used up
+    ///
+    /// Returns the last result together with the number of retries taken.
+    pub fn run<T>(&self, what: &str, mut attempt: impl FnMut() -> Result<T>) -> (Result<T>, u32) {
+        let mut retries = 0;
+        loop {
+            match attempt() {
+                Ok(value) => return (Ok(value), retries),
+                Err(e) if retries < self.retries => {
+                    let delay = self.backoff_ms.saturating_mul(1 << retries.min(16));
+                    println!("   ⚠️  {} failed ({:#}), retrying in {}ms...", what, e, delay);
+                    std::thread::sleep(std::time::Duration::from_millis(delay));
+                    retries += 1;
+                }
+                Err(e) => return (Err(e), retries),
+            }
+        }
+    }
+
+    /// Run a runner with retries, turning a persistent failure into INFRA_ERROR
+    ///
+    /// The retry count is recorded in `meta.retries` whenever it is non-zero.
+    pub fn run_runner(&self, runner: &str, attempt: impl FnMut() -> Result<RunResult>) -> RunResult {
+        let (result, retries) = self.run(&format!("{}-runner", runner), attempt);
+        let mut result = result.unwrap_or_else(|e| RunResult {
+            status: Status::InfraError,
+            elapsed_ms: 0,
+            commits: vec![],
+            meta: serde_json::json!({
+                "runner": runner,
+                "error": format!("{:#}", e),
+            }),
+        });
+        if retries > 0 {
+            record_retries(&mut result, retries);
+        }
+        result
+    }
+}
+
+/// Record how many retries a result needed in its `meta`
+fn record_retries(result: &mut RunResult, retries: u32) {
+    if !result.meta.is_object() {
+        result.meta = serde_json::json!({});
+    }
+    result.meta["retries"] = serde_json::json!(retries);
+}
+
+/// Detailed log of one differential run (`artifacts/<campaign>/<core>/<run_id>/run_log.json`)
+///
+/// Read logs with [`run_log::parse`], which also accepts older schema versions.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RunLog {
+    /// [`run_log::SCHEMA_VERSION`] when written
+    pub schema_version: u32,
+    pub run_id: String,
+    pub timestamp: String,
+    pub core_path: String,
+    pub input_path: String,
+    pub native_result: RunResult,
+    pub sp1_result: RunResult,
+    pub diff: rust_eq_oracle::Diff,
+    #[serde(default)]
+    pub environment: Option<ReproEnv>,
+    /// Overflow-checked native run (`harness run --checked-native`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checked_native: Option<CheckedNative>,
+    /// Campaign the run belongs to (`harness --campaign`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub campaign: Option<campaign::Campaign>,
+    /// The input's `expected` block held to the results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expectation: Option<ExpectationCheck>,
+}
+
+/// Target triple SP1 guests are compiled for
+pub const ZKVM_TARGET: &str = "riscv32im-succinct-zkvm-elf";
+
+/// Toolchain and guest fingerprint recorded with every run
+///
+/// Embedded into repro scripts so a reproduction months later can tell
+/// whether it still runs against the same SP1, rustc and guest ELF.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReproEnv {
+    pub sp1_version: String,
+    pub rustc_version: String,
+    pub zkvm_target: String,
+    /// SHA-256 of the guest ELF (None if it was not built)
+    pub elf_sha256: Option<String>,
+    #[serde(default)]
+    pub elf_size_bytes: Option<u64>,
+    /// ELF modification time (RFC 3339)
+    #[serde(default)]
+    pub elf_built_at: Option<String>,
+    /// SP1 toolchain that built the ELF, which may differ from `sp1_version`
+    /// (see [`provenance`])
+    #[serde(default)]
+    pub elf_sp1_toolchain: Option<String>,
+    /// `HEAD` of the checkout (None outside a git repository)
+    #[serde(default)]
+    pub git_commit: Option<String>,
+    /// Uncommitted changes to the harness, runner, oracle, core or adapter
+    /// sources (see [`provenance::GitInfo`])
+    #[serde(default)]
+    pub git_dirty: Option<bool>,
+}
+
+impl ReproEnv {
+    /// Fingerprint the current toolchain and the given guest ELF
+    ///
+    /// The toolchain and git fields come from the process's
+    /// [`environment::current`] snapshot; only the ELF is examined per call.
+    pub fn capture(elf_path: &Path) -> Self {
+        let elf = provenance::ElfInfo::capture(elf_path);
+        let snapshot = environment::current();
+        ReproEnv {
+            sp1_version: snapshot.sp1_version.clone(),
+            rustc_version: snapshot.rustc_version.clone(),
+            zkvm_target: snapshot.zkvm_target.clone(),
+            elf_sha256: elf.as_ref().map(|elf| elf.sha256.clone()),
+            elf_size_bytes: elf.as_ref().map(|elf| elf.size_bytes),
+            elf_built_at: elf.as_ref().and_then(|elf| elf.built_at.clone()),
+            elf_sp1_toolchain: elf.and_then(|elf| elf.sp1_toolchain),
+            git_commit: snapshot.git_commit.clone(),
+            git_dirty: snapshot.git_dirty,
+        }
+    }
+
+    /// `<commit>`, with ` (uncommitted changes)` if the sources were dirty
+    pub fn describe_revision(&self) -> String {
+        let commit = self.git_commit.as_deref().unwrap_or("unknown");
+        match self.git_dirty {
+            Some(true) => format!("{} (uncommitted changes)", commit),
+            _ => commit.to_string(),
+        }
+    }
+}
+
+/// Generate a run ID that is unique across processes and within one
+///
+/// Format: `<YYYYmmdd_HHMMSS>_<label>_<session>-<seq>`. The timestamp keeps
+/// IDs sortable and readable; `session` is a ULID drawn once per harness
+/// process and `seq` counts runs within it, so parallel fuzzing and
+/// simultaneous campaigns never produce the same ID.
+pub fn new_run_id(label: &str) -> String {
+    static SESSION: OnceLock<String> = OnceLock::new();
+    static SEQUENCE: AtomicU32 = AtomicU32::new(0);
+
+    let session = SESSION.get_or_init(|| ulid::Ulid::new().to_string().to_lowercase());
+    let seq = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    format!(
+        "{}_{}_{}-{:04}",
+        Utc::now().format("%Y%m%d_%H%M%S"),
+        label,
+        session,
+        seq
+    )
+}
+
+/// Cores in `guest/cores/` (every folder with a `Cargo.toml`), by name
+pub fn core_names() -> Result<Vec<String>> {
+    let mut cores = Vec::new();
+    for entry in fs::read_dir("guest/cores").context("Failed to list guest/cores")? {
+        let path = entry?.path();
+        let name = path.file_name().and_then(|name| name.to_str()).map(str::to_string);
+        if let Some(name) = name.filter(|_| path.join("Cargo.toml").is_file()) {
+            cores.push(name);
+        }
+    }
+    cores.sort();
+    Ok(cores)
+}
+
+/// Cores the harness knows how to build, run and fuzz: the folders of
+/// `guest/cores/` (read once)
+pub fn known_cores() -> Result<Vec<&'static str>> {
+    static CORES: OnceLock<Vec<String>> = OnceLock::new();
+    if CORES.get().is_none() {
+        let _ = CORES.set(core_names()?);
+    }
+    Ok(CORES.get().into_iter().flatten().map(String::as_str).collect())
+}
+
+/// Parse a `--cores` argument ("all" or a comma-separated list) and check the names
+pub fn parse_cores_arg(cores_arg: &str) -> Result<Vec<&str>> {
+    let available_cores = known_cores()?;
+    
+    let cores_to_fuzz: Vec<&str> = if cores_arg == "all" {
+        available_cores.clone()
+    } else {
+        cores_arg.split(',').map(|s| s.trim()).collect()
+    };
+
+    // Validate cores
+    for core in &cores_to_fuzz {
+        if !available_cores.contains(core) {
+            anyhow::bail!(
+                "Unknown core: '{}'\n\nAvailable cores: {}",
+                core,
+                available_cores.join(", ")
+            );
+        }
+    }
+
+    Ok(cores_to_fuzz)
+}
+
+/// SP1 guest adapter for a core (convention: adapters/sp1_guest/{core_name}_guest)
+pub fn guest_path_for_core(core_name: &str) -> PathBuf {
+    PathBuf::from(format!("adapters/sp1_guest/{}_guest", core_name))
+}
+
+/// Release ELF produced by `cargo prove build` for a core's guest
+pub fn elf_path_for_core(core_name: &str) -> PathBuf {
+    // ELF filename uses hyphens instead of underscores
+    guest_path_for_core(core_name)
+        .join("target/elf-compilation")
+        .join(ZKVM_TARGET)
+        .join("release")
+        .join(format!("{}-guest", core_name.replace('_', "-")))
+}
+
+/// Hex SHA-256 of a file, or None if it can't be read
+pub fn sha256_file(path: &Path) -> Option<String> {
+    use sha2::{Digest, Sha256};
+
+    let bytes = fs::read(path).ok()?;
+    Some(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+/// Build a guest with `cargo prove build`, recording the toolchain next to
+/// the ELF (see [`provenance`])
+///
+/// The build output goes to a log under `artifacts/builds/` rather than the
+/// console, and the result is appended to `artifacts/builds.jsonl` (see
+/// [`build`]).
+pub fn build_sp1_guest(guest_path: &Path) -> Result<build::BuildResult> {
+    let guest_name = guest_path.file_name().and_then(|name| name.to_str()).unwrap_or("guest");
+    let core_name = guest_name.strip_suffix("_guest");
+    let elf_path = core_name.map(elf_path_for_core);
+
+    let mut cmd = Command::new("cargo");
+    cmd.args(["prove", "build"]).current_dir(guest_path);
+    let result = build::run_build(cmd, core_name.unwrap_or(guest_name), elf_path.as_deref())?;
+
+    if let Some(elf_path) = &elf_path {
+        provenance::record_build(elf_path, &get_sp1_version())?;
+    }
+    Ok(result)
+}
+
+/// RunResult recorded for the SP1 side when its guest failed to build
+pub fn build_failure_result(error: &anyhow::Error, retries: u32) -> RunResult {
+    let mut result = RunResult {
+        status: Status::BuildFailure,
+        elapsed_ms: 0,
+        commits: vec![],
+        meta: serde_json::json!({
+            "runner": "sp1",
+            "error": format!("{:#}", error),
+        }),
+    };
+    if retries > 0 {
+        record_retries(&mut result, retries);
+    }
+    result
+}
+
+/// Run a core natively through `native-runner`
+pub fn run_native_runner(core_name: &str, input_path: &Path) -> Result<RunResult> {
+    run_native_runner_sandboxed(core_name, input_path, false)
+}
+
+/// Cargo profile the native runner is built with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NativeProfile {
+    /// `release`: integer overflow wraps, as in the SP1 guest's release build
+    Release,
+    /// `release-checked`: release codegen with overflow checks and debug
+    /// assertions (debug semantics)
+    Checked,
+}
+
+impl NativeProfile {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NativeProfile::Release => "release",
+            NativeProfile::Checked => "release-checked",
+        }
+    }
+}
+
+/// Run the native runner, optionally with `--sandbox` (cleared env, fixed
+/// locale/TZ, empty temp cwd)
+pub fn run_native_runner_sandboxed(core_name: &str, input_path: &Path, sandbox: bool) -> Result<RunResult> {
+    run_native_runner_with_profile(core_name, input_path, sandbox, NativeProfile::Release)
+}
+
+/// Run the native runner built with the given Cargo profile
+pub fn run_native_runner_with_profile(
+    core_name: &str,
+    input_path: &Path,
+    sandbox: bool,
+    profile: NativeProfile,
+) -> Result<RunResult> {
+    let target = match profile {
+        NativeProfile::Release => "native",
+        NativeProfile::Checked => "native-checked",
+    };
+    let image = container::image_for(target);
+    let mut cmd = match image {
+        Some(image) => {
+            let mut cmd = image.command(&container_input_mounts(core_name, input_path));
+            cmd.args(["--core", core_name, "--input", container::INPUT_MOUNT]);
+            cmd
+        }
+        None => {
+            let mut cmd = Command::new("cargo");
+            cmd.args(["run", "--profile", profile.as_str(), "--bin", "native-runner", "--"])
+                .args(["--core", core_name])
+                .args(["--input", input_path.to_str().unwrap()]);
+            cmd
+        }
+    };
+    if sandbox {
+        cmd.arg("--sandbox");
+    }
+    if let Some(spec) = preprocess_for_core(core_name) {
+        cmd.args(["--preprocess", &spec]);
+    }
+    if let Some(limit) = memory_limit_for_core(core_name) {
+        cmd.args(["--memory-limit", &limit.to_string()]);
+    }
+    let output = cmd.output().context("Failed to run native-runner")?;
+
+    // A panic during unwinding or a failed allocation aborts the whole
+    // process, so the runner never gets to print a RunResult. Report it as
+    // OOM or a panic (zkVM guests abort on every panic, so this is the
+    // comparable outcome).
+    if !output.status.success() && native_runner_aborted(&output) {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let status = if stderr.contains("memory allocation of") {
+            Status::Oom
+        } else {
+            Status::Panic
+        };
+        return Ok(RunResult {
+            status,
+            elapsed_ms: 0,
+            commits: vec![],
+            meta: serde_json::json!({
+                "runner": "native",
+                "aborted": true,
+                "panic_msg": stderr.trim(),
+            }),
+        });
+    }
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "native-runner failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let mut result: RunResult = serde_json::from_slice(&output.stdout)
+        .context("Failed to parse native-runner output")?;
+    if let Some(image) = image {
+        result.meta["container_image"] = serde_json::json!(image.image);
+    }
+    annotate_commits(&mut result, core_name);
+
+    Ok(result)
+}
+
+/// Whether the native-runner process was killed by an abort (e.g., a double panic)
+fn native_runner_aborted(output: &std::process::Output) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if output.status.signal() == Some(6) {
+            return true;
+        }
+    }
+
+    // Container engines exit with 128 + the signal that killed the runner
+    if output.status.code() == Some(128 + 6) {
+        return true;
+    }
+
+    // On Windows `abort` exits with STATUS_STACK_BUFFER_OVERRUN (__fastfail)
+    #[cfg(windows)]
+    {
+        if output.status.code() == Some(0xC000_0409_u32 as i32) {
+            return true;
+        }
+    }
+
+    // `cargo run` reports the child's signal on stderr instead of re-raising it
+    String::from_utf8_lossy(&output.stderr).contains("SIGABRT")
+}
+
+/// Check inputs against a core's input schema without running the core
+///
+/// The inputs are written to `inputs_path` as JSON Lines and `native-runner
+/// --check-inputs` deserializes each into the core's input type, as both
+/// runners do before running it. Returns, per input, why it violates the
+/// schema (None if it is valid).
+pub fn check_inputs(
+    core_name: &str,
+    inputs: &[serde_json::Value],
+    inputs_path: &Path,
+) -> Result<Vec<Option<String>>> {
+    let mut lines = String::new();
+    for input in inputs {
+        lines.push_str(&serde_json::to_string(input)?);
+        lines.push('\n');
+    }
+    fs::write(inputs_path, lines).with_context(|| format!("Failed to write {}", inputs_path.display()))?;
+
+    let mut cmd = match container::image_for("native") {
+        Some(image) => {
+            let mut cmd = image.command(&[(inputs_path, container::INPUT_MOUNT)]);
+            cmd.args(["--core", core_name, "--input", container::INPUT_MOUNT]);
+            cmd
+        }
+        None => {
+            let mut cmd = Command::new("cargo");
+            cmd.args(["run", "--profile", NativeProfile::Release.as_str(), "--bin", "native-runner", "--"])
+                .args(["--core", core_name])
+                .args(["--input", inputs_path.to_str().unwrap()]);
+            cmd
+        }
+    };
+    if let Some(spec) = preprocess_for_core(core_name) {
+        cmd.args(["--preprocess", &spec]);
+    }
+    let output = cmd.arg("--check-inputs").output().context("Failed to run native-runner")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "native-runner --check-inputs failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let errors: Vec<Option<String>> = serde_json::from_slice(&output.stdout)
+        .context("Failed to parse native-runner --check-inputs output")?;
+    anyhow::ensure!(
+        errors.len() == inputs.len(),
+        "native-runner checked {} inputs, expected {}",
+        errors.len(),
+        inputs.len()
+    );
+    Ok(errors)
+}
+
+/// Known-good input a core is fuzzed from (mutation base, commit count trial)
+pub fn base_input_for_core(core_name: &str) -> Result<PathBuf> {
+    let base_input = match core_name {
+        "fib" => "inputs/fib_24.json",
+        "panic_test" => "inputs/panic_no.json",
+        "timeout_test" => "inputs/timeout_finite.json",
+        "io_echo" => "inputs/io_echo_1kb.json",
+        "arithmetic" => "inputs/arithmetic_add_normal.json",
+        "simple_struct" => "inputs/simple_struct_normal.json",
+        "parse_roundtrip" => "inputs/parse_roundtrip_plus.json",
+        "nondeterminism_probe" => "inputs/nondeterminism_probe_all.json",
+        "env_probe" => "inputs/env_probe_path.json",
+        "panic_drop" => "inputs/panic_drop_none.json",
+        "oom_probe" => "inputs/oom_probe_small.json",
+        "checkpoint_loop" => "inputs/checkpoint_loop_1m.json",
+        "enum_shapes" => "inputs/enum_shapes_mixed.json",
+        "type_punning" => "inputs/type_punning_unaligned.json",
+        "compress" => "inputs/compress_text_64kb.json",
+        "json_adversarial" => "inputs/json_adversarial_numbers.json",
+        "sorting" => "inputs/sorting_ties.json",
+        "iterator_fold" => "inputs/iterator_fold_cancellation.json",
+        "matmul" => "inputs/matmul_square_32.json",
+        "binary_decode" => "inputs/binary_decode_valid_bincode.json",
+        "utf8_boundaries" => "inputs/utf8_boundaries_mixed.json",
+        "collection_growth" => "inputs/collection_growth_wraparound.json",
+        "thread_api" => "inputs/thread_api_four_workers.json",
+        _ => anyhow::bail!("Unknown core: {}", core_name),
+    };
+    Ok(PathBuf::from(base_input))
+}
+
+/// Name of the one seed a core without a `seeds` table is fuzzed from
+pub const DEFAULT_SEED: &str = "default";
+
+/// A named input a core is fuzzed from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseSeed {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Base seeds a core is fuzzed from
+///
+/// A core can declare several named seeds in its manifest:
+///
+/// ```toml
+/// [package.metadata.fuzz.seeds]
+/// small = "inputs/io_echo_small.json"
+/// medium = "inputs/io_echo_1kb.json"
+/// adversarial = "inputs/io_echo_empty.json"
+/// ```
+///
+/// Without the table it has one, [`DEFAULT_SEED`], at [`base_input_for_core`]
+/// (which stays the input of single-input commands such as `matrix`).
+/// Declared seeds come in name order, except that the one at
+/// [`base_input_for_core`] (if any) comes first, so mutators that ignore
+/// their base input keep attributing their inputs to it.
+pub fn base_seeds_for_core(core_name: &str) -> Result<Vec<BaseSeed>> {
+    let manifest_path = Path::new("guest/cores").join(core_name).join("Cargo.toml");
+    let manifest = fs::read_to_string(&manifest_path)
+        .with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+    let primary = base_input_for_core(core_name)?;
+    let seeds = match parse_seeds(&manifest).with_context(|| format!("Invalid seeds in {}", manifest_path.display()))? {
+        Some(mut seeds) => {
+            seeds.sort_by_key(|seed| seed.path != primary);
+            seeds
+        }
+        None => vec![BaseSeed {
+            name: DEFAULT_SEED.to_string(),
+            path: primary,
+        }],
+    };
+    for seed in &seeds {
+        anyhow::ensure!(
+            seed.path.is_file(),
+            "Seed '{}' of {} not found: {}",
+            seed.name,
+            core_name,
+            seed.path.display()
+        );
+    }
+    Ok(seeds)
+}
+
+/// `[package.metadata.fuzz.seeds]` of a manifest (None if absent)
+fn parse_seeds(manifest: &str) -> Result<Option<Vec<BaseSeed>>> {
+    let Some(seeds) = source_mutator::grammar::fuzz_metadata_in(manifest, "seeds")? else {
+        return Ok(None);
+    };
+    let seeds = seeds.as_table().context("seeds must be a table of name = \"input path\"")?;
+    anyhow::ensure!(!seeds.is_empty(), "seeds table is empty");
+    let mut parsed = seeds
+        .iter()
+        .map(|(name, path)| {
+            let path = path.as_str().with_context(|| format!("seed '{}': path must be a string", name))?;
+            Ok(BaseSeed {
+                name: name.clone(),
+                path: PathBuf::from(path),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    parsed.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Some(parsed))
+}
+
+/// Commit layout a core declares in its manifest (`[package.metadata.fuzz] commits`)
+///
+/// Cores that commit more than a fixed list of `u32`s ship a decoder spec
+/// such as `"u32,bytes,string*"`, which `sp1-runner --commit-schema` decodes
+/// the public values against. None means a fixed number of u32s (see [`commits`]).
+pub fn commit_schema_for_core(core_name: &str) -> Option<String> {
+    fuzz_metadata_str(core_name, "commits")
+}
+
+/// Input preprocessing a core declares in its manifest (`[package.metadata.fuzz] preprocess`)
+///
+/// A spec such as `"bytes:data"` lets the core's inputs be stored compactly
+/// (`{"data": {"size": 1048576}}`); both runners expand them with
+/// `--preprocess` before deserializing (see `runners/preprocess`).
+pub fn preprocess_for_core(core_name: &str) -> Option<String> {
+    fuzz_metadata_str(core_name, "preprocess")
+}
+
+/// Whether a core's adapter reads JSON input in chunks (`[package.metadata.fuzz] stdin = "chunked"`)
+///
+/// The SP1 runner then writes the input with `--chunked-stdin`, as a header
+/// and raw chunks the guest copies into one buffer (see `guest/stdin_chunks`).
+pub fn chunked_stdin_for_core(core_name: &str) -> bool {
+    fuzz_metadata_str(core_name, "stdin").as_deref() == Some("chunked")
+}
+
+/// Memory budget of a core in bytes (`[package.metadata.fuzz] memory_limit`)
+///
+/// Both runners get it as `--memory-limit`: natively it caps the worker's
+/// heap, on SP1 it caps the memory the guest touches. With the same budget
+/// on both sides, an input that needs more runs out of memory on both, so
+/// campaigns can probe inputs just below and just above it.
+pub fn memory_limit_for_core(core_name: &str) -> Option<u64> {
+    source_mutator::grammar::load_memory_limit(core_name).ok().flatten()
+}
+
+/// Container mounts of an input and the sidecars it names (see
+/// [`container::input_mounts`])
+fn container_input_mounts(core_name: &str, input_path: &Path) -> Vec<(PathBuf, String)> {
+    let sidecars = match (input_preprocess(core_name), fs::read(input_path)) {
+        (Ok(preprocess), Ok(bytes)) => preprocess.sidecars(&bytes),
+        _ => Vec::new(),
+    };
+    container::input_mounts(input_path, &sidecars)
+}
+
+/// [`preprocess_for_core`], parsed (no steps if the core declares none)
+pub fn input_preprocess(core_name: &str) -> Result<Preprocess> {
+    source_mutator::grammar::load_core_preprocess(core_name)
+}
+
+/// Read an input file as the JSON its core deserializes: sidecar files
+/// read in and compact fields expanded
+///
+/// Inputs loaded this way can be mutated, compared and written elsewhere
+/// without losing the files they refer to.
+pub fn load_input(core_name: &str, input_path: &Path) -> Result<serde_json::Value> {
+    let bytes = fs::read(input_path).with_context(|| format!("Failed to read {}", input_path.display()))?;
+    let input_dir = input_path.parent().unwrap_or(Path::new(""));
+    let bytes = input_preprocess(core_name)?
+        .apply(&bytes, input_dir)
+        .with_context(|| format!("Failed to preprocess {}", input_path.display()))?;
+    serde_json::from_slice(&bytes).with_context(|| format!("Failed to parse {}", input_path.display()))
+}
+
+/// Write an input for a core's runners, large byte arrays of the fields
+/// the core preprocesses going to sidecar files next to it
+pub fn write_input(core_name: &str, input: &serde_json::Value, input_path: &Path) -> Result<()> {
+    input_preprocess(core_name)?.write_input(input, input_path)
+}
+
+/// Copy an input file together with the sidecar files it refers to
+pub fn copy_input(core_name: &str, from: &Path, to: &Path) -> Result<()> {
+    input_preprocess(core_name)?.copy_input(from, to)
+}
+
+/// A string under `[package.metadata.fuzz]` in a core's manifest
+fn fuzz_metadata_str(core_name: &str, key: &str) -> Option<String> {
+    fuzz_metadata(core_name, key)?.as_str().map(str::to_string)
+}
+
+/// A value under `[package.metadata.fuzz]` in a core's manifest
+fn fuzz_metadata(core_name: &str, key: &str) -> Option<toml::Value> {
+    source_mutator::grammar::fuzz_metadata(core_name, key).ok().flatten()
+}
+
+/// Output fields a core commits, in order: `[package.metadata.fuzz]
+/// commit_fields` in its manifest
+///
+/// A field committed once per element (`self.items.iter().map(CommitValue::from)`)
+/// is marked with a trailing `*`.
+pub fn commit_fields_for_core(core_name: &str) -> Result<Vec<String>> {
+    let manifest = source_mutator::grammar::core_manifest_path(core_name);
+    let fields = source_mutator::grammar::fuzz_metadata(core_name, "commit_fields")?
+        .with_context(|| format!("{} declares no [package.metadata.fuzz] commit_fields", manifest.display()))?;
+    fields
+        .as_array()
+        .and_then(|fields| fields.iter().map(|field| field.as_str().map(str::to_string)).collect())
+        .with_context(|| {
+            format!("{}: [package.metadata.fuzz] commit_fields must be an array of strings", manifest.display())
+        })
+}
+
+/// Names for `count` commits given a core's [`commit_fields_for_core`]
+///
+/// A repeated field names its elements `field[0]`, `field[1]`, ... A stream
+/// too short for the fields (a truncated run) is named by prefix. None if
+/// the fields cannot account for `count` commits.
+pub fn commit_names(fields: &[String], count: usize) -> Option<Vec<String>> {
+    let repeated = fields.iter().position(|field| field.ends_with('*'));
+    let names: Vec<String> = match repeated {
+        None if count <= fields.len() => fields[..count].to_vec(),
+        None => return None,
+        Some(at) => {
+            let (before, after) = (&fields[..at], &fields[at + 1..]);
+            let field = fields[at].trim_end_matches('*');
+            // A truncated stream never got to the fields after the repeated one
+            let after = if count >= before.len() + after.len() { after } else { &[] };
+            let repeats = count.saturating_sub(before.len() + after.len());
+            before
+                .iter()
+                .cloned()
+                .chain((0..repeats).map(|i| format!("{}[{}]", field, i)))
+                .chain(after.iter().cloned())
+                .take(count)
+                .collect()
+        }
+    };
+    (names.len() == count && count > 0).then_some(names)
+}
+
+/// Attach the core's output field names to a result's commits
+/// (`meta.commit_names`, see [`RunResult`])
+///
+/// Results whose commits the core's fields cannot name are left as they are.
+pub fn annotate_commits(result: &mut RunResult, core_name: &str) {
+    let Ok(fields) = commit_fields_for_core(core_name) else {
+        return;
+    };
+    if let Some(names) = commit_names(&fields, result.commits.len()) {
+        if !result.meta.is_object() {
+            result.meta = serde_json::json!({});
+        }
+        result.meta["commit_names"] = serde_json::json!(names);
+    }
+}
+
+/// Execute a guest ELF through `sp1-runner`
+pub fn run_sp1_runner(elf_path: &Path, input_path: &Path, core_name: &str) -> Result<RunResult> {
+    run_sp1_runner_with_encoding(elf_path, input_path, core_name, InputEncoding::Json)
+}
+
+/// How the SP1 runner delivers the input to the guest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputEncoding {
+    /// JSON bytes, parsed inside the guest (the default path)
+    Json,
+    /// The core's input struct written directly with `SP1Stdin::write`
+    Typed,
+}
+
+impl InputEncoding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InputEncoding::Json => "json",
+            InputEncoding::Typed => "typed",
+        }
+    }
+}
+
+/// Run the SP1 runner with an explicit input encoding
+pub fn run_sp1_runner_with_encoding(
+    elf_path: &Path,
+    input_path: &Path,
+    core_name: &str,
+    encoding: InputEncoding,
+) -> Result<RunResult> {
+    run_sp1_runner_command(sp1_execute_command(None, elf_path, input_path, core_name, encoding), elf_path, core_name)
+}
+
+/// Execution command: runner args plus the encoding and the core's cycle budget
+fn sp1_execute_command(
+    runner: Option<&Path>,
+    elf_path: &Path,
+    input_path: &Path,
+    core_name: &str,
+    encoding: InputEncoding,
+) -> Command {
+    let mut cmd = sp1_runner_command(runner, elf_path, input_path, core_name);
+
+    if encoding == InputEncoding::Typed {
+        cmd.args(["--encoding", encoding.as_str(), "--core", core_name]);
+    }
+
+    // Add the core's cycle budget if it has one
+    if let Some(max_cycles) = get_max_cycles_for_core(core_name) {
+        cmd.args(["--max-cycles", &max_cycles.to_string()]);
+    }
+
+    cmd
+}
+
+/// Prove and verify a guest through `sp1-runner --prove` (see [`bench`])
+pub fn run_sp1_prover(
+    elf_path: &Path,
+    input_path: &Path,
+    core_name: &str,
+    proof_mode: bench::ProofMode,
+) -> Result<RunResult> {
+    let mut cmd = sp1_runner_command(None, elf_path, input_path, core_name);
+    cmd.args(["--prove", "--proof-mode", proof_mode.as_str()]);
+    run_sp1_runner_command(cmd, elf_path, core_name)
+}
+
+/// `sp1-runner` with the ELF, input and commit decoding args
+///
+/// `runner` is a prebuilt runner binary (see [`matrix`]); None runs the
+/// `sp1` container image under `--container` (see [`container`]), or else
+/// the workspace's runner with `cargo run`.
+fn sp1_runner_command(runner: Option<&Path>, elf_path: &Path, input_path: &Path, core_name: &str) -> Command {
+    let mut cmd = sp1_runner_base_command(runner, elf_path, input_path, core_name);
+
+    // Decode against the core's commit schema, or its inferred number of u32 commits
+    if let Some(schema) = commit_schema_for_core(core_name) {
+        cmd.args(["--commit-schema", &schema]);
+    } else {
+        let num_commits = commits::num_commits(core_name, runner, elf_path);
+        if num_commits > 0 {
+            cmd.args(["--num-commits", &num_commits.to_string()]);
+        }
+    }
+    if let Some(spec) = preprocess_for_core(core_name) {
+        cmd.args(["--preprocess", &spec]);
+    }
+    if chunked_stdin_for_core(core_name) {
+        cmd.arg("--chunked-stdin");
+    }
+    if let Some(limit) = memory_limit_for_core(core_name) {
+        cmd.args(["--memory-limit", &limit.to_string()]);
+    }
+
+    cmd
+}
+
+/// `sp1-runner` with the ELF and input only: every complete u32 is decoded
+fn sp1_runner_base_command(runner: Option<&Path>, elf_path: &Path, input_path: &Path, core_name: &str) -> Command {
+    let (mut cmd, elf_arg, input_arg) = match (runner, container::image_for("sp1")) {
+        (Some(runner), _) => (Command::new(runner), elf_path.to_str().unwrap(), input_path.to_str().unwrap()),
+        (None, Some(image)) => {
+            let mut mounts = vec![(elf_path.to_path_buf(), container::ELF_MOUNT.to_string())];
+            mounts.extend(container_input_mounts(core_name, input_path));
+            (image.command(&mounts), container::ELF_MOUNT, container::INPUT_MOUNT)
+        }
+        (None, None) => {
+            let mut cmd = Command::new("cargo");
+            cmd.args(["run", "--release", "--bin", "sp1-runner", "--"]);
+            (cmd, elf_path.to_str().unwrap(), input_path.to_str().unwrap())
+        }
+    };
+    cmd.args(["--elf", elf_arg]).args(["--input", input_arg]);
+    cmd
+}
+
+/// Run an `sp1-runner` command and parse the RunResult it prints, tagged
+/// with the ELF it executed (`meta.elf`) and the core's commit names
+fn run_sp1_runner_command(mut cmd: Command, elf_path: &Path, core_name: &str) -> Result<RunResult> {
+    let image = container::image_for("sp1").filter(|image| cmd.get_program() == image.engine());
+    let output = cmd
+        .output()
+        .context("Failed to run sp1-runner")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "sp1-runner failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let mut result: RunResult = serde_json::from_slice(&output.stdout)
+        .context("Failed to parse sp1-runner output")?;
+    if let Some(image) = image {
+        result.meta["container_image"] = serde_json::json!(image.image);
+    }
+    if let Some(elf) = provenance::ElfInfo::capture(elf_path) {
+        result.meta["elf"] = serde_json::to_value(elf)?;
+    }
+    symbolicate::annotate(&mut result, elf_path);
+    annotate_commits(&mut result, core_name);
+
+    Ok(result)
+}
+
+/// Per-core SP1 cycle budget, `[package.metadata.fuzz] max_cycles` in the
+/// core's manifest (None = no limit, rely on the wall-clock timeout)
+///
+/// Cores that can loop for a guest-controlled number of iterations declare
+/// a budget so runaway guests are cut off deterministically (CYCLE_LIMIT)
+/// instead of racing the timeout.
+pub fn get_max_cycles_for_core(core_name: &str) -> Option<u64> {
+    source_mutator::grammar::load_max_cycles(core_name).ok().flatten()
+}
+
+/// What `log_mutation_result` recorded for one mutation
+#[derive(Debug, Clone)]
+pub struct LoggedMutation {
+    pub run_id: String,
+    /// Metrics far above their baseline (see [`baseline::check_performance`])
+    pub perf_anomalies: Vec<baseline::PerfAnomaly>,
+}
+
+/// Log a run to `artifacts/` (run log, repro folder on divergence, CSV row)
+///
+/// Returns the run ID.
+pub fn log_results(
+    core_path: &Path,
+    input_path: &Path,
+    native_result: RunResult,
+    sp1_result: RunResult,
+    diff: Diff,
+) -> Result<String> {
+    log_results_with_checked(core_path, input_path, native_result, sp1_result, diff, None)
+}
+
+/// Hold a comparison to its input's `expected` block (None if it has none)
+///
+/// See [`rust_eq_oracle::expect`]. Inputs that are not JSON have no block.
+pub fn check_expectation(
+    input_path: &Path,
+    native: &RunResult,
+    sp1: &RunResult,
+    diff: &Diff,
+) -> Result<Option<ExpectationCheck>> {
+    let text =
+        fs::read_to_string(input_path).with_context(|| format!("Failed to read {}", input_path.display()))?;
+    let Ok(input) = serde_json::from_str::<serde_json::Value>(&text) else {
+        return Ok(None);
+    };
+    let expectation = Expectation::from_input(&input)
+        .with_context(|| format!("Invalid `expected` block in {}", input_path.display()))?;
+    Ok(expectation.map(|expected| rust_eq_oracle::expect::check(&expected, native, sp1, diff)))
+}
+
+/// [`log_results`], also recording an overflow-checked native run
+pub fn log_results_with_checked(
+    core_path: &Path,
+    input_path: &Path,
+    native_result: RunResult,
+    sp1_result: RunResult,
+    diff: Diff,
+    checked_native: Option<CheckedNative>,
+) -> Result<String> {
+    // Create artifacts directory if it doesn't exist
+    fs::create_dir_all("artifacts")?;
+
+    // Generate run ID
+    let timestamp = Utc::now();
+    let core_name = core_path.file_name().unwrap().to_str().unwrap();
+    let run_id = new_run_id(core_name);
+    let env = ReproEnv::capture(&elf_path_for_core(core_name));
+    let expectation = check_expectation(input_path, &native_result, &sp1_result, &diff)?;
+    let expected_divergence = expectation.as_ref().is_some_and(|check| check.confirmed);
+
+    // Create run log
+    let log = RunLog {
+        schema_version: run_log::SCHEMA_VERSION,
+        run_id: run_id.clone(),
+        timestamp: timestamp.to_rfc3339(),
+        core_path: core_path.display().to_string(),
+        input_path: input_path.display().to_string(),
+        native_result: native_result.clone(),
+        sp1_result: sp1_result.clone(),
+        diff: diff.clone(),
+        environment: Some(env.clone()),
+        checked_native,
+        campaign: campaign::current().cloned(),
+        expectation,
+    };
+
+    // Write detailed JSON log into the run's folder
+    let log_json = serde_json::to_string_pretty(&log)?;
+    let run_dir = layout::write_run_log(&log, &log_json)?;
+    let published_dir = run_dir.clone();
+
+    println!("   📄 Detailed log: {}", run_dir.join("run_log.json").display());
+
+    // If there's a divergence, add the repro files to the run's folder
+    if !diff.equal {
+        let repro_dir = run_dir;
+
+        // Generate repro script
+        let repro_script = generate_repro_script(core_path, input_path, &env);
+        let repro_path = repro_dir.join("repro.sh");
+        fs::write(&repro_path, repro_script)?;
+
+        // Make script executable (Unix only)
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = fs::metadata(&repro_path)?.permissions();
+            perms.set_mode(0o755);
+            fs::set_permissions(&repro_path, perms)?;
+        }
+
+        // Copy input file to repro folder
+        let input_copy = repro_dir.join("input.json");
+        copy_input(core_name, input_path, &input_copy)?;
+
+        write_captured_output(&repro_dir, &log.native_result, &log.sp1_result)?;
+        write_explanation(&repro_dir, &log)?;
+        write_trace(&repro_dir, core_name, input_path, &log.sp1_result);
+
+        println!("   🔧 Repro folder: {}", repro_dir.display());
+        // A divergence the input documents is not news
+        if !expected_divergence {
+            notify::notify_divergence(&log, &repro_dir, None);
+        }
+    }
+
+    // Append to CSV summary
+    let summary_row = append_to_csv_summary(&run_id, core_path, input_path, &native_result, &sp1_result, &diff, &env)?;
+
+    // Record for cost-model fitting (`harness stats --cost-model`)
+    let record = cost_model::RunRecord::new(&run_id, core_name, input_path, &native_result, &sp1_result, &diff, &env);
+    cost_model::append_run_record(&record)?;
+    // Flag runs far slower than their baseline (logged separately)
+    baseline::check_performance(&run_id, core_name, input_path, &native_result, &sp1_result, &diff)?;
+
+    storage::publish_run(&run_id, Some(&published_dir), &csv_summary_document(&summary_row)?, &record);
+    Ok(run_id)
+}
+
+/// Save what each side printed (`meta.stdout` / `meta.stderr`) next to the repro
+///
+/// Writes `native_stdout.txt`, `sp1_stderr.txt`, etc. for the non-empty ones.
+fn write_captured_output(repro_dir: &Path, native: &RunResult, sp1: &RunResult) -> Result<()> {
+    for (side, result) in [("native", native), ("sp1", sp1)] {
+        for stream in ["stdout", "stderr"] {
+            if let Some(text) = result.meta.get(stream).and_then(|v| v.as_str()) {
+                fs::write(repro_dir.join(format!("{}_{}.txt", side, stream)), text)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Save the oracle's divergence explanation as `explanation.txt`, with the
+/// divergence's [`severity`]
+fn write_explanation(repro_dir: &Path, log: &RunLog) -> Result<()> {
+    let mut text = rust_eq_oracle::explain::explain(&log.native_result, &log.sp1_result, &log.diff).to_string();
+    if let Some(severity) = severity::of_log(log, Some(repro_dir)) {
+        text.push_str(&format!("Severity: {}\n", severity));
+    }
+    fs::write(repro_dir.join("explanation.txt"), text)?;
+    Ok(())
+}
+
+/// Save the SP1 side's execution trace as `sp1_trace.csv` (see [`trace`])
+///
+/// A failed trace is reported and leaves the rest of the repro folder as is.
+fn write_trace(repro_dir: &Path, core_name: &str, input_path: &Path, sp1: &RunResult) {
+    match trace::capture(repro_dir, core_name, input_path, sp1) {
+        Ok(Some(trace)) => println!(
+            "   🔬 SP1 trace: {} (last {} of {} instructions)",
+            trace.path, trace.steps, trace.total_steps
+        ),
+        Ok(None) => {}
+        Err(e) => println!("   ⚠️  SP1 trace failed: {:#}", e),
+    }
+}
+
+/// Save which fields a diverging mutation changed as `input_diff.json`
+///
+/// The base seed is loaded expanded, like the mutation, so a seed stored
+/// compactly or with sidecars compares field by field. Skipped (with a
+/// note) if the base seed can no longer be read.
+fn write_input_diff(repro_dir: &Path, core_name: &str, mutation: &MutatedInput) -> Result<()> {
+    let base = match load_input(core_name, Path::new(&mutation.base_input_path)) {
+        Ok(base) => base,
+        Err(e) => {
+            println!("      ⚠️  Base seed unreadable; no input diff: {:#}", e);
+            return Ok(());
+        }
+    };
+    let diff = input_diff::InputDiff::new(&mutation.base_input_path, &base, &mutation.input_json);
+    for change in diff.changes.iter().take(3) {
+        println!("      🧬 {}", change);
+    }
+    if diff.changes.len() + diff.omitted > 3 {
+        println!("      🧬 ... {} more changed fields in input_diff.json", diff.changes.len() + diff.omitted - 3);
+    }
+    fs::write(repro_dir.join("input_diff.json"), serde_json::to_string_pretty(&diff)?)?;
+    Ok(())
+}
+
+/// Generate a repro script for the given test case
+///
+/// The script pins the environment the run was recorded with: it warns when
+/// the SP1 toolchain, rustc or guest ELF differ, and `--rebuild` rebuilds
+/// the guest and checks the resulting ELF hash before reproducing.
+pub fn generate_repro_script(core_path: &Path, input_path: &Path, env: &ReproEnv) -> String {
+    let core_name = core_path.file_name().unwrap().to_str().unwrap();
+    format!(
+        r#"#!/usr/bin/env bash
+# Repro script generated by zk-fuzz-lab harness
+# Run this script from the repository root
+#
+# Usage: repro.sh [--rebuild]
+#   --rebuild  Rebuild the SP1 guest before reproducing (default: reuse the existing ELF)
+
+set -e
+
+# Environment recorded with the original run
+EXPECTED_SP1_VERSION="{sp1_version}"
+EXPECTED_RUSTC_VERSION="{rustc_version}"
+EXPECTED_ELF_SHA256="{elf_sha256}"
+EXPECTED_GIT_COMMIT="{git_commit}"
+ZKVM_TARGET="{zkvm_target}"
+GUEST_PATH="{guest}"
+ELF_PATH="{elf}"
+
+REBUILD=0
+if [ "$1" = "--rebuild" ]; then
+    REBUILD=1
+fi
+
+MISMATCHES=0
+warn_mismatch() {{
+    echo "⚠️  $1 differs from the recorded run"
+    echo "      recorded: $2"
+    echo "      current:  $3"
+    MISMATCHES=$((MISMATCHES + 1))
+}}
+
+elf_sha256() {{
+    if command -v sha256sum >/dev/null 2>&1; then
+        sha256sum "$1" | cut -d' ' -f1
+    else
+        shasum -a 256 "$1" | cut -d' ' -f1
+    fi
+}}
+
+echo "🔁 Reproducing differential test..."
+echo "   Core: {core}"
+echo "   Input: {input}"
+echo "   Target: $ZKVM_TARGET"
+echo ""
+
+echo "🔍 Checking environment..."
+SP1_VERSION="$(cargo prove --version 2>/dev/null || echo unknown)"
+RUSTC_VERSION="$(rustc --version 2>/dev/null || echo unknown)"
+[ "$SP1_VERSION" = "$EXPECTED_SP1_VERSION" ] || warn_mismatch "SP1 version" "$EXPECTED_SP1_VERSION" "$SP1_VERSION"
+[ "$RUSTC_VERSION" = "$EXPECTED_RUSTC_VERSION" ] || warn_mismatch "rustc version" "$EXPECTED_RUSTC_VERSION" "$RUSTC_VERSION"
+if [ -n "$EXPECTED_GIT_COMMIT" ]; then
+    GIT_COMMIT="$(git rev-parse HEAD 2>/dev/null || echo unknown)"
+    [ "$GIT_COMMIT" = "$EXPECTED_GIT_COMMIT" ] || warn_mismatch "Source commit" "$EXPECTED_GIT_COMMIT" "$GIT_COMMIT"
+fi{dirty_note}
+
+if [ "$REBUILD" = "1" ]; then
+    echo "📦 Rebuilding SP1 guest..."
+    (cd "$GUEST_PATH" && cargo prove build)
+fi
+
+if [ ! -f "$ELF_PATH" ]; then
+    echo "❌ ELF not found: $ELF_PATH (run with --rebuild)"
+    exit 1
+fi
+ELF_SHA256="$(elf_sha256 "$ELF_PATH")"
+[ "$ELF_SHA256" = "$EXPECTED_ELF_SHA256" ] || warn_mismatch "ELF hash" "$EXPECTED_ELF_SHA256" "$ELF_SHA256"
+
+if [ "$MISMATCHES" -gt 0 ]; then
+    echo "⚠️  $MISMATCHES environment mismatch(es): the result may not reproduce"
+else
+    echo "   ✅ Environment matches the recorded run"
+fi
+echo ""
+
+# Run the differential test against the checked ELF
+cargo run --release --bin harness -- run --core {core} --input {input} --skip-build
+"#,
+        sp1_version = env.sp1_version,
+        rustc_version = env.rustc_version,
+        elf_sha256 = env.elf_sha256.as_deref().unwrap_or("unknown"),
+        git_commit = env.git_commit.as_deref().unwrap_or_default(),
+        dirty_note = if env.git_dirty == Some(true) {
+            "\necho \"⚠️  The recorded run had uncommitted source changes, which the commit does not capture\""
+        } else {
+            ""
+        },
+        zkvm_target = env.zkvm_target,
+        guest = guest_path_for_core(core_name).display(),
+        elf = elf_path_for_core(core_name).display(),
+        core = core_path.display(),
+        input = input_path.display(),
+    )
+}
+
+/// Get SP1 version string
+pub fn get_sp1_version() -> String {
+    Command::new("cargo")
+        .args(["prove", "--version"])
+        .output()
+        .ok()
+        .and_then(|output| {
+            if output.status.success() {
+                String::from_utf8(output.stdout).ok()
+            } else {
+                None
+            }
+        })
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Get rustc version string
+pub fn get_rustc_version() -> String {
+    Command::new("rustc")
+        .args(["--version"])
+        .output()
+        .ok()
+        .and_then(|output| {
+            if output.status.success() {
+                String::from_utf8(output.stdout).ok()
+            } else {
+                None
+            }
+        })
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Append run results to CSV summary, returning the encoded row
+fn append_to_csv_summary(
+    run_id: &str,
+    core_path: &Path,
+    input_path: &Path,
+    native_result: &RunResult,
+    sp1_result: &RunResult,
+    diff: &Diff,
+    env: &ReproEnv,
+) -> Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let campaign_columns = csv_campaign_columns();
+    let elf_columns = csv_elf_columns(env);
+
+    // Determine repro_path (the run's folder if divergence, empty otherwise)
+    let repro_path = if !diff.equal {
+        format!("{}/", layout::current_run_dir(core_path.file_name().unwrap().to_str().unwrap(), run_id).display())
+    } else {
+        String::new()
+    };
+
+    // Write data row
+    writer.write_record([
+        run_id,
+        core_path.file_name().unwrap().to_str().unwrap(),
+        &input_path.display().to_string(),
+        &format!("{:?}", native_result.status),
+        &format!("{:?}", sp1_result.status),
+        &diff.equal.to_string(),
+        &diff.reason.clone().unwrap_or_default(),
+        &native_result.elapsed_ms.to_string(),
+        &sp1_result.elapsed_ms.to_string(),
+        &diff.timing_delta_ms.map(|d| d.to_string()).unwrap_or_default(),
+        // Phase 4: Future-proofing columns
+        &repro_path,
+        "hand_written",  // generator (Phase 5 will populate with "mutated", Phase 6 with "rustsmith")
+        "",              // base_seed (empty for now, Phase 5 will populate)
+        "",              // mutation_ops (empty for now, Phase 5 will populate)
+        "",              // rng_seed (empty for now, Phase 6 will populate)
+        "sp1",           // zkvm_target (Phase 8 will add risc0, openvm)
+        &environment::current().id,
+        &campaign_columns[0],
+        &campaign_columns[1],
+        &elf_columns[0],
+        &elf_columns[1],
+        &elf_columns[2],
+        &elf_columns[3],
+    ])?;
+
+    let row = writer.into_inner()?;
+    append_csv_summary_row(&row)?;
+
+    Ok(row)
+}
+
+/// `campaign` and `tags` cells of a `summary.csv` row (empty outside a campaign)
+fn csv_campaign_columns() -> [String; 2] {
+    match campaign::current() {
+        Some(campaign) => [campaign.id.clone(), campaign.tags_column()],
+        None => [String::new(), String::new()],
+    }
+}
+
+/// `elf_*` cells of a `summary.csv` row (empty if the ELF was not built)
+fn csv_elf_columns(env: &ReproEnv) -> [String; 4] {
+    [
+        env.elf_sha256.clone().unwrap_or_default(),
+        env.elf_size_bytes.map(|size| size.to_string()).unwrap_or_default(),
+        env.elf_built_at.clone().unwrap_or_default(),
+        env.elf_sp1_toolchain.clone().unwrap_or_default(),
+    ]
+}
+
+/// Columns of `artifacts/summary.csv`
+const CSV_SUMMARY_HEADER: [&str; 23] = [
+    "run_id",
+    "core",
+    "input",
+    "native_status",
+    "sp1_status",
+    "equal",
+    "reason",
+    "elapsed_native_ms",
+    "elapsed_sp1_ms",
+    "timing_delta_ms",
+    // Phase 4: Future-proofing columns
+    "repro_path",
+    "generator",
+    "base_seed",
+    "mutation_ops",
+    "rng_seed",
+    "zkvm_target",
+    // Toolchain and source revision (see [`environment`])
+    "env_id",
+    // Named campaigns (`harness --campaign <id> --tag <tag>`)
+    "campaign",
+    "tags",
+    // Guest ELF provenance
+    "elf_sha256",
+    "elf_size_bytes",
+    "elf_built_at",
+    "elf_sp1_toolchain",
+];
+
+/// Columns of `summary.csv` before environment snapshots, which repeated
+/// the toolchain and git revision in every row
+const LEGACY_CSV_SUMMARY_HEADER: [&str; 26] = [
+    "run_id",
+    "core",
+    "input",
+    "native_status",
+    "sp1_status",
+    "equal",
+    "reason",
+    "elapsed_native_ms",
+    "elapsed_sp1_ms",
+    "timing_delta_ms",
+    // Phase 4: Future-proofing columns
+    "repro_path",
+    "generator",
+    "base_seed",
+    "mutation_ops",
+    "rng_seed",
+    "zkvm_target",
+    "sp1_version",
+    "rustc_version",
+    // Named campaigns (`harness --campaign <id> --tag <tag>`)
+    "campaign",
+    "tags",
+    // Guest ELF provenance
+    "elf_sha256",
+    "elf_size_bytes",
+    "elf_built_at",
+    "elf_sp1_toolchain",
+    // Source revision
+    "git_commit",
+    "git_dirty",
+];
+
+/// A one-row `summary.csv`: the header and `row`
+fn csv_summary_document(row: &[u8]) -> Result<Vec<u8>> {
+    let mut header = csv::Writer::from_writer(Vec::new());
+    header.write_record(CSV_SUMMARY_HEADER)?;
+    let mut document = header.into_inner()?;
+    document.extend_from_slice(row);
+    Ok(document)
+}
+
+/// Append one encoded row to `artifacts/summary.csv`
+///
+/// Safe to call from concurrent harness processes: the upgrade of an older
+/// file and every append hold an exclusive lock on `summary.csv.lock`, so
+/// no row lands in a file that is being replaced, and only the process that
+/// creates the file writes the header.
+fn append_csv_summary_row(row: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    static UPGRADED: std::sync::Once = std::sync::Once::new();
+
+    let csv_path = PathBuf::from("artifacts/summary.csv");
+    let _lock = lock_csv_summary(&csv_path)?;
+
+    let mut upgrade = Ok(());
+    UPGRADED.call_once(|| upgrade = upgrade_csv_summary(&csv_path));
+    upgrade?;
+
+    if let Ok(mut file) = fs::OpenOptions::new().write(true).create_new(true).open(&csv_path) {
+        let mut header = csv::Writer::from_writer(Vec::new());
+        header.write_record(CSV_SUMMARY_HEADER)?;
+        file.write_all(&header.into_inner()?)?;
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .open(&csv_path)
+        .context("Failed to open artifacts/summary.csv")?;
+    file.write_all(row)?;
+
+    Ok(())
+}
+
+/// Exclusive lock on `summary.csv`, released when the file is dropped
+///
+/// A separate lock file, since an upgrade replaces `summary.csv` itself.
+fn lock_csv_summary(csv_path: &Path) -> Result<fs::File> {
+    let lock_path = csv_path.with_extension("csv.lock");
+    let lock = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("Failed to open {}", lock_path.display()))?;
+    lock.lock().with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+    Ok(lock)
+}
+
+/// Bring a `summary.csv` written by an older harness to the current header,
+/// so old and new rows stay readable as one table
+///
+/// Cells move by column name, and missing columns are left empty. The
+/// toolchain and git cells of rows from before environment snapshots
+/// become snapshots in the `environments/` folder next to the CSV, and the
+/// rows reference them by `env_id`. Callers hold [`lock_csv_summary`].
+fn upgrade_csv_summary(csv_path: &Path) -> Result<()> {
+    let Ok(mut reader) = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_path(csv_path)
+    else {
+        return Ok(());
+    };
+    let mut records = reader.records();
+    let Some(header) = records.next().transpose()? else {
+        return Ok(());
+    };
+    let is_prefix_of =
+        |columns: &[&str]| header.len() <= columns.len() && header.iter().zip(columns).all(|(old, new)| old == *new);
+    let is_older_header = header.len() < CSV_SUMMARY_HEADER.len() && is_prefix_of(&CSV_SUMMARY_HEADER)
+        || is_prefix_of(&LEGACY_CSV_SUMMARY_HEADER);
+    if !is_older_header {
+        return Ok(());
+    }
+
+    let environments_dir = csv_path.with_file_name("environments");
+    let mut saved = std::collections::BTreeSet::new();
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(CSV_SUMMARY_HEADER)?;
+    for record in records {
+        let record = record?;
+        let cell = |column: &str| {
+            header
+                .iter()
+                .position(|name| name == column)
+                .and_then(|i| record.get(i))
+                .unwrap_or_default()
+        };
+        let mut env_id = cell("env_id").to_string();
+        if !cell("sp1_version").is_empty() || !cell("rustc_version").is_empty() {
+            let snapshot = environment::Snapshot::new(
+                cell("sp1_version"),
+                cell("rustc_version"),
+                ZKVM_TARGET,
+                Some(cell("git_commit")).filter(|commit| !commit.is_empty()).map(str::to_string),
+                cell("git_dirty").parse().ok(),
+            );
+            if saved.insert(snapshot.id.clone()) {
+                snapshot.save_in(&environments_dir)?;
+            }
+            env_id = snapshot.id;
+        }
+        writer.write_record(CSV_SUMMARY_HEADER.map(|column| match column {
+            "env_id" => env_id.clone(),
+            column => cell(column).to_string(),
+        }))?;
+    }
+    let upgraded = csv_path.with_extension("csv.upgrade");
+    fs::write(&upgraded, writer.into_inner()?)?;
+    fs::rename(&upgraded, csv_path).context("Failed to upgrade artifacts/summary.csv")?;
+    Ok(())
+}
+
+/// Log mutation result to CSV with mutation metadata
+pub fn log_mutation_result(
+    core_path: &Path,
+    input_path: &Path,
+    native_result: RunResult,
+    sp1_result: RunResult,
+    diff: Diff,
+    mutation: &MutatedInput,
+) -> Result<LoggedMutation> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let campaign_columns = csv_campaign_columns();
+
+    // Generate run ID
+    let timestamp = Utc::now();
+    let run_id = new_run_id(core_path.file_name().unwrap().to_str().unwrap());
+
+    // Determine repro_path (the run's folder if divergence, empty otherwise)
+    let repro_path = if !diff.equal {
+        format!("{}/", layout::current_run_dir(core_path.file_name().unwrap().to_str().unwrap(), &run_id).display())
+    } else {
+        String::new()
+    };
+
+    // Get toolchain and ELF fingerprint
+    let env = ReproEnv::capture(&elf_path_for_core(core_path.file_name().unwrap().to_str().unwrap()));
+    let elf_columns = csv_elf_columns(&env);
+
+    // Convert core name to String to avoid &&str issue
+    let core_name_str = core_path.file_name().unwrap().to_str().unwrap().to_string();
+    
+    let mutation_op = mutation.op.to_string();
+    let base_seed = mutation.op.base_seed.as_deref().unwrap_or(&mutation.base_input_path);
+    let rng_seed = mutation.op.rng_seed.map(|seed| seed.to_string()).unwrap_or_default();
+
+    // Write data row with mutation metadata
+    writer.write_record([
+        &run_id,
+        &core_name_str,
+        &input_path.display().to_string(),
+        &format!("{:?}", native_result.status),
+        &format!("{:?}", sp1_result.status),
+        &diff.equal.to_string(),
+        &diff.reason.clone().unwrap_or_default(),
+        &native_result.elapsed_ms.to_string(),
+        &sp1_result.elapsed_ms.to_string(),
+        &diff.timing_delta_ms.map(|d| d.to_string()).unwrap_or_default(),
+        // Phase 5: Mutation metadata
+        &repro_path,
+        "mutated",          // generator
+        base_seed,                 // base_seed (seed name, or the promoted seed's path)
+        &mutation_op,              // mutation_ops
+        &rng_seed,                 // rng_seed (empty for deterministic mutations)
+        "sp1",              // zkvm_target
+        &environment::current().id,
+        &campaign_columns[0],
+        &campaign_columns[1],
+        &elf_columns[0],
+        &elf_columns[1],
+        &elf_columns[2],
+        &elf_columns[3],
+    ])?;
+
+    let summary_row = writer.into_inner()?;
+    append_csv_summary_row(&summary_row)?;
+
+    // Record for cost-model fitting (`harness stats --cost-model`)
+    let mut record =
+        cost_model::RunRecord::new(&run_id, &core_name_str, input_path, &native_result, &sp1_result, &diff, &env);
+    // The fuzz loop's dedup key: the input in full, sidecars and all
+    let input_text = serde_json::to_string_pretty(&mutation.input_json)?;
+    record.input_sha256 = Some(corpus::input_sha256(input_text.as_bytes()));
+    record.mutation = Some(mutation.op.clone());
+    cost_model::append_run_record(&record)?;
+    // Flag runs far slower than their baseline (logged separately)
+    let perf_anomalies =
+        baseline::check_performance(&run_id, &core_name_str, input_path, &native_result, &sp1_result, &diff)?;
+
+    // If divergence, create repro folder (same as run_differential_test)
+    let mut published_dir = None;
+    if !diff.equal {
+        let repro_script = generate_repro_script(core_path, input_path, &env);
+
+        // Write detailed log into the run's folder
+        let log = RunLog {
+            schema_version: run_log::SCHEMA_VERSION,
+            run_id: run_id.clone(),
+            timestamp: timestamp.to_rfc3339(),
+            core_path: core_path.display().to_string(),
+            input_path: input_path.display().to_string(),
+            native_result,
+            sp1_result,
+            diff,
+            environment: Some(env),
+            checked_native: None,
+            campaign: campaign::current().cloned(),
+            expectation: None,
+        };
+        let repro_dir = layout::write_run_log(&log, &serde_json::to_string_pretty(&log)?)?;
+
+        // Copy input
+        copy_input(&core_name_str, input_path, &repro_dir.join("input.json"))?;
+
+        // Write repro script
+        let repro_path = repro_dir.join("repro.sh");
+        fs::write(&repro_path, repro_script)?;
+
+        // Make executable on Unix
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = fs::metadata(&repro_path)?.permissions();
+            perms.set_mode(0o755);
+            fs::set_permissions(&repro_path, perms)?;
+        }
+
+        write_captured_output(&repro_dir, &log.native_result, &log.sp1_result)?;
+        write_explanation(&repro_dir, &log)?;
+        write_trace(&repro_dir, &core_name_str, input_path, &log.sp1_result);
+        write_input_diff(&repro_dir, &core_name_str, mutation)?;
+        notify::notify_divergence(&log, &repro_dir, Some(mutation_op));
+        published_dir = Some(repro_dir);
+    }
+
+    storage::publish_run(&run_id, published_dir.as_deref(), &csv_summary_document(&summary_row)?, &record);
+    Ok(LoggedMutation { run_id, perf_anomalies })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_seeds() {
+        let seeds = parse_seeds(
+            r#"
+[package]
+name = "io-echo-core"
+
+[package.metadata.fuzz.seeds]
+small = "inputs/io_echo_small.json"
+adversarial = "inputs/io_echo_empty.json"
+"#,
+        )
+        .unwrap()
+        .unwrap();
+        let names: Vec<&str> = seeds.iter().map(|seed| seed.name.as_str()).collect();
+        assert_eq!(names, ["adversarial", "small"]);
+        assert_eq!(seeds[1].path, PathBuf::from("inputs/io_echo_small.json"));
+
+        assert_eq!(parse_seeds("[package]\nname = \"x\"\n").unwrap(), None);
+        assert!(parse_seeds("[package.metadata.fuzz.seeds]\nsmall = 3\n").is_err());
+        assert!(parse_seeds("[package.metadata.fuzz]\nseeds = {}\n").is_err());
+    }
+
+    #[test]
+    fn test_run_ids_are_unique() {
+        let a = new_run_id("fib");
+        let b = new_run_id("fib");
+        assert_ne!(a, b);
+        assert!(a.contains("_fib_"));
+    }
+
+    #[test]
+    fn test_core_name_resolves_under_guest_cores() {
+        let test = DifferentialTest::new("fib", "inputs/fib_24.json");
+        assert_eq!(test.core_path, PathBuf::from("guest/cores/fib"));
+        let test = DifferentialTest::new("guest/cores/io_echo", "inputs/io_echo_1kb.json");
+        assert_eq!(test.core_path, PathBuf::from("guest/cores/io_echo"));
+    }
+
+    #[test]
+    fn test_commit_names() {
+        let fields: Vec<String> = ["length", "first_byte", "last_byte"].map(String::from).to_vec();
+        assert_eq!(commit_names(&fields, 3).unwrap(), fields);
+        assert_eq!(commit_names(&fields, 1).unwrap(), ["length"]);
+        assert_eq!(commit_names(&fields, 4), None);
+        assert_eq!(commit_names(&fields, 0), None);
+
+        let fields: Vec<String> = ["checkpoints*", "final_checksum"].map(String::from).to_vec();
+        assert_eq!(
+            commit_names(&fields, 3).unwrap(),
+            ["checkpoints[0]", "checkpoints[1]", "final_checksum"]
+        );
+        assert_eq!(commit_names(&fields, 1).unwrap(), ["final_checksum"]);
+
+        let fields: Vec<String> = ["n", "items*", "sum", "hash"].map(String::from).to_vec();
+        assert_eq!(commit_names(&fields, 2).unwrap(), ["n", "items[0]"]);
+        assert_eq!(commit_names(&fields, 3).unwrap(), ["n", "sum", "hash"]);
+    }
+
+    #[test]
+    fn test_core_manifests_declare_commit_fields() {
+        let root = find_repo_root(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap();
+        for entry in fs::read_dir(root.join("guest/cores")).unwrap() {
+            let manifest = entry.unwrap().path().join("Cargo.toml");
+            if !manifest.is_file() {
+                continue;
+            }
+            let manifest = fs::read_to_string(&manifest).unwrap();
+            let fields = source_mutator::grammar::fuzz_metadata_in(&manifest, "commit_fields").unwrap();
+            let fields = fields.as_ref().and_then(|fields| fields.as_array()).expect("commit_fields");
+            // A declared layout without repeated fields has one type per field
+            let schema = source_mutator::grammar::fuzz_metadata_in(&manifest, "commits").unwrap();
+            if let Some(schema) = schema.as_ref().and_then(|schema| schema.as_str()).filter(|schema| !schema.contains('*')) {
+                assert_eq!(schema.split(',').count(), fields.len(), "{}", manifest);
+            }
+        }
+    }
+
+    #[test]
+    fn test_repo_root_found_from_crate_dir() {
+        let root = find_repo_root(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap();
+        assert!(root.join("harness/core").is_dir());
+    }
+
+    #[test]
+    fn test_elf_path_uses_hyphenated_name() {
+        assert_eq!(
+            elf_path_for_core("panic_drop"),
+            PathBuf::from(
+                "adapters/sp1_guest/panic_drop_guest/target/elf-compilation/riscv32im-succinct-zkvm-elf/release/panic-drop-guest"
+            )
+        );
+    }
+    #[test]
+    fn test_older_summary_csv_is_upgraded_to_current_header() {
+        let dir = std::env::temp_dir().join(format!("summary-upgrade-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("summary.csv");
+        let old_header = LEGACY_CSV_SUMMARY_HEADER[..18].join(",");
+        fs::write(&path, format!("{}\nrun_1{},sp1 5.2.4,rustc 1.90.0\n", old_header, ",x".repeat(15))).unwrap();
+
+        upgrade_csv_summary(&path).unwrap();
+        let mut reader = csv::Reader::from_path(&path).unwrap();
+        assert_eq!(reader.headers().unwrap(), &csv::StringRecord::from(CSV_SUMMARY_HEADER.to_vec()));
+        let row = reader.records().next().unwrap().unwrap();
+        assert_eq!((&row[0], &row[15], &row[17], &row[18]), ("run_1", "x", "", ""));
+        let snapshot = environment::Snapshot::new("sp1 5.2.4", "rustc 1.90.0", ZKVM_TARGET, None, None);
+        assert_eq!(&row[16], snapshot.id);
+        assert!(dir.join("environments").join(format!("{}.json", snapshot.id)).is_file());
+
+        // Already current: left alone
+        let before = fs::read(&path).unwrap();
+        upgrade_csv_summary(&path).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), before);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,358 @@
+//! SP1 version and guest build profile matrix
+//!
+//! `fuzz.toml` lists SP1 toolchains installed side by side and guest build
+//! profiles (codegen options and features). `harness matrix` builds each
+//! core's guest for every (version, profile) cell with that toolchain's
+//! `cargo-prove`, runs it through a runner linked against the matching
+//! `sp1-sdk`, and compares the results with native and with the first
+//! (reference) cell, so a zkVM upgrade or a codegen option that changes
+//! behaviour shows up as a cross-cell divergence.
+//!
+//! ```toml
+//! [[sp1_versions]]
+//! name = "5.2.2"          # cargo prove + workspace sp1-runner
+//!
+//! [[sp1_versions]]
+//! name = "5.2.3"
+//! cargo_prove = "/opt/sp1/5.2.3/bin/cargo-prove"
+//! runner = "/opt/sp1/5.2.3/sp1-runner"
+//!
+//! [[build_profiles]]
+//! name = "release"        # the guest's own release profile
+//!
+//! [[build_profiles]]
+//! name = "o1-checked"
+//! opt_level = "1"
+//! overflow_checks = true
+//! features = ["sp1-zkvm/embedded"]
+//! ```
+
+use anyhow::{Context, Result};
+use rust_eq_oracle::RunResult;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Harness configuration file (repository root)
+pub const CONFIG_PATH: &str = "fuzz.toml";
+
+/// Per-version guest ELFs (each build overwrites the guest's own target dir)
+const ELF_DIR: &str = "artifacts/matrix/elf";
+
+/// `fuzz.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FuzzConfig {
+    /// SP1 toolchains to test; the first one is the reference
+    #[serde(default)]
+    pub sp1_versions: Vec<Sp1Version>,
+    /// Guest build profiles; the first one is the reference (default: `release` only)
+    #[serde(default)]
+    pub build_profiles: Vec<BuildProfile>,
+    /// Hooks fired on new divergences (see [`crate::notify`])
+    #[serde(default)]
+    pub notify: Vec<crate::notify::NotifyHook>,
+    /// Runner images for `harness --container` (see [`crate::container`])
+    #[serde(default)]
+    pub containers: Vec<crate::container::ContainerImage>,
+    /// Store logged runs are also published to (see [`crate::storage`])
+    #[serde(default)]
+    pub storage: Option<crate::storage::StorageConfig>,
+    /// Tracing of divergent SP1 runs (see [`crate::trace`])
+    #[serde(default)]
+    pub trace: crate::trace::TraceConfig,
+}
+
+/// One installed SP1 toolchain
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Sp1Version {
+    /// Label used in reports and artifact paths
+    pub name: String,
+    /// `cargo-prove` binary of this toolchain (default: `cargo prove` on PATH)
+    #[serde(default)]
+    pub cargo_prove: Option<PathBuf>,
+    /// `sp1-runner` built against this version's `sp1-sdk` (default: the workspace runner)
+    #[serde(default)]
+    pub runner: Option<PathBuf>,
+}
+
+/// Codegen options and features a guest is built with
+///
+/// Unset options keep the guest's own `[profile.release]` (`cargo prove build`
+/// always builds in release); set ones are passed as `CARGO_PROFILE_RELEASE_*`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BuildProfile {
+    /// Label used in reports and artifact paths
+    pub name: String,
+    /// `0`-`3`, `s` or `z`
+    #[serde(default)]
+    pub opt_level: Option<String>,
+    /// `off`, `thin`, `fat`, `true` or `false`
+    #[serde(default)]
+    pub lto: Option<String>,
+    #[serde(default)]
+    pub overflow_checks: Option<bool>,
+    #[serde(default)]
+    pub debug_assertions: Option<bool>,
+    #[serde(default)]
+    pub codegen_units: Option<u32>,
+    /// Cargo features, e.g. `sp1-zkvm/embedded` for the embedded allocator
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// Extra `rustc` flags (`cargo prove build --rustflags`)
+    #[serde(default)]
+    pub rustflags: Vec<String>,
+}
+
+impl BuildProfile {
+    /// The guest's own release profile
+    pub fn release() -> BuildProfile {
+        BuildProfile {
+            name: "release".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// `CARGO_PROFILE_RELEASE_*` overrides for the set options
+    fn profile_env(&self) -> Vec<(&'static str, String)> {
+        let mut env = Vec::new();
+        if let Some(opt_level) = &self.opt_level {
+            env.push(("CARGO_PROFILE_RELEASE_OPT_LEVEL", opt_level.clone()));
+        }
+        if let Some(lto) = &self.lto {
+            env.push(("CARGO_PROFILE_RELEASE_LTO", lto.clone()));
+        }
+        if let Some(overflow_checks) = self.overflow_checks {
+            env.push(("CARGO_PROFILE_RELEASE_OVERFLOW_CHECKS", overflow_checks.to_string()));
+        }
+        if let Some(debug_assertions) = self.debug_assertions {
+            env.push(("CARGO_PROFILE_RELEASE_DEBUG_ASSERTIONS", debug_assertions.to_string()));
+        }
+        if let Some(codegen_units) = self.codegen_units {
+            env.push(("CARGO_PROFILE_RELEASE_CODEGEN_UNITS", codegen_units.to_string()));
+        }
+        env
+    }
+
+    fn validate(&self, idx: usize) -> Result<()> {
+        if let Some(opt_level) = &self.opt_level {
+            anyhow::ensure!(
+                ["0", "1", "2", "3", "s", "z"].contains(&opt_level.as_str()),
+                "build_profiles[{}]: invalid opt_level '{}'",
+                idx,
+                opt_level
+            );
+        }
+        if let Some(lto) = &self.lto {
+            anyhow::ensure!(
+                ["off", "thin", "fat", "true", "false"].contains(&lto.as_str()),
+                "build_profiles[{}]: invalid lto '{}'",
+                idx,
+                lto
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Labels must be unique and usable as a directory name
+fn check_names<'a>(section: &str, names: impl Iterator<Item = &'a str>) -> Result<()> {
+    let mut seen = Vec::new();
+    for (idx, name) in names.enumerate() {
+        anyhow::ensure!(
+            !name.is_empty() && !name.contains(['/', '\\']) && name != "..",
+            "{}[{}]: name must be non-empty and not contain path separators",
+            section,
+            idx
+        );
+        anyhow::ensure!(!seen.contains(&name), "{}: duplicate name '{}'", section, name);
+        seen.push(name);
+    }
+    Ok(())
+}
+
+impl FuzzConfig {
+    /// Parse and validate `fuzz.toml` contents
+    pub fn parse(text: &str) -> Result<FuzzConfig> {
+        let config: FuzzConfig = toml::from_str(text).context("Invalid fuzz.toml")?;
+        check_names("sp1_versions", config.sp1_versions.iter().map(|v| v.name.as_str()))?;
+        check_names("build_profiles", config.build_profiles.iter().map(|p| p.name.as_str()))?;
+        for (idx, profile) in config.build_profiles.iter().enumerate() {
+            profile.validate(idx)?;
+        }
+        check_names("notify", config.notify.iter().map(|h| h.name.as_str()))?;
+        for (idx, hook) in config.notify.iter().enumerate() {
+            hook.validate(idx)?;
+        }
+        check_names("containers", config.containers.iter().map(|c| c.target.as_str()))?;
+        for (idx, image) in config.containers.iter().enumerate() {
+            image.validate(idx)?;
+        }
+        if let Some(storage) = &config.storage {
+            storage.validate()?;
+        }
+        Ok(config)
+    }
+
+    /// Configured build profiles, or just `release` if there are none
+    pub fn profiles(&self) -> Vec<BuildProfile> {
+        if self.build_profiles.is_empty() {
+            vec![BuildProfile::release()]
+        } else {
+            self.build_profiles.clone()
+        }
+    }
+
+    /// Load `fuzz.toml` from the repository root (empty config if absent)
+    pub fn load() -> Result<FuzzConfig> {
+        if !Path::new(CONFIG_PATH).exists() {
+            return Ok(FuzzConfig::default());
+        }
+        let text = fs::read_to_string(CONFIG_PATH).with_context(|| format!("Failed to read {}", CONFIG_PATH))?;
+        FuzzConfig::parse(&text)
+    }
+}
+
+impl Sp1Version {
+    /// Where this version's build of a core's guest with `profile` is kept
+    pub fn elf_path(&self, core_name: &str, profile: &BuildProfile) -> PathBuf {
+        Path::new(ELF_DIR)
+            .join(&self.name)
+            .join(&profile.name)
+            .join(format!("{}-guest", core_name.replace('_', "-")))
+    }
+
+    /// `cargo prove` invocation for this toolchain
+    fn cargo_prove(&self) -> Command {
+        match &self.cargo_prove {
+            Some(path) => {
+                // Cargo passes the subcommand name as the first argument
+                let mut cmd = Command::new(path);
+                cmd.arg("prove");
+                cmd
+            }
+            None => {
+                let mut cmd = Command::new("cargo");
+                cmd.arg("prove");
+                cmd
+            }
+        }
+    }
+
+    /// `cargo prove --version` of this toolchain ("unknown" if it cannot run)
+    pub fn toolchain_version(&self) -> String {
+        self.cargo_prove()
+            .arg("--version")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+
+    /// Build a core's guest with this toolchain and profile, keeping a copy of the ELF
+    ///
+    /// The build is logged like any other (see [`crate::build`]).
+    pub fn build_guest(&self, core_name: &str, profile: &BuildProfile) -> Result<PathBuf> {
+        let mut cmd = self.cargo_prove();
+        cmd.arg("build")
+            .envs(profile.profile_env())
+            .current_dir(crate::guest_path_for_core(core_name));
+        if !profile.features.is_empty() {
+            cmd.args(["--features", &profile.features.join(",")]);
+        }
+        if !profile.rustflags.is_empty() {
+            cmd.args(["--rustflags", &profile.rustflags.join(",")]);
+        }
+        crate::build::run_build(cmd, core_name, Some(&crate::elf_path_for_core(core_name)))
+            .with_context(|| format!("SP1 {} ({})", self.name, profile.name))?;
+
+        let elf_path = self.elf_path(core_name, profile);
+        fs::create_dir_all(elf_path.parent().unwrap())?;
+        fs::copy(crate::elf_path_for_core(core_name), &elf_path).with_context(|| {
+            format!(
+                "Failed to copy the {} ELF built with SP1 {} ({})",
+                core_name, self.name, profile.name
+            )
+        })?;
+        crate::provenance::record_build(&elf_path, &self.toolchain_version())?;
+        Ok(elf_path)
+    }
+
+    /// Execute this version's build of a core's guest with its runner
+    pub fn run(&self, core_name: &str, profile: &BuildProfile, input_path: &Path) -> Result<RunResult> {
+        let cmd = crate::sp1_execute_command(
+            self.runner.as_deref(),
+            &self.elf_path(core_name, profile),
+            input_path,
+            core_name,
+            crate::InputEncoding::Json,
+        );
+        crate::run_sp1_runner_command(cmd, &self.elf_path(core_name, profile), core_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_build_profiles() {
+        let config = FuzzConfig::parse(
+            r#"
+            [[build_profiles]]
+            name = "release"
+
+            [[build_profiles]]
+            name = "o1-checked"
+            opt_level = "1"
+            lto = "off"
+            overflow_checks = true
+            features = ["sp1-zkvm/embedded"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.profiles()[0], BuildProfile::release());
+        assert_eq!(
+            config.build_profiles[1].profile_env(),
+            vec![
+                ("CARGO_PROFILE_RELEASE_OPT_LEVEL", "1".to_string()),
+                ("CARGO_PROFILE_RELEASE_LTO", "off".to_string()),
+                ("CARGO_PROFILE_RELEASE_OVERFLOW_CHECKS", "true".to_string()),
+            ]
+        );
+
+        assert!(FuzzConfig::parse("[[build_profiles]]\nname = \"x\"\nopt_level = \"4\"\n").is_err());
+        assert_eq!(FuzzConfig::parse("").unwrap().profiles(), vec![BuildProfile::release()]);
+    }
+
+    #[test]
+    fn test_parse_versions() {
+        let config = FuzzConfig::parse(
+            r#"
+            [[sp1_versions]]
+            name = "5.2.2"
+
+            [[sp1_versions]]
+            name = "5.2.3"
+            cargo_prove = "/opt/sp1/5.2.3/bin/cargo-prove"
+            runner = "/opt/sp1/5.2.3/sp1-runner"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.sp1_versions.len(), 2);
+        assert_eq!(config.sp1_versions[0].runner, None);
+        assert_eq!(
+            config.sp1_versions[1].elf_path("simple_struct", &BuildProfile::release()),
+            Path::new("artifacts/matrix/elf/5.2.3/release/simple-struct-guest")
+        );
+
+        let duplicate = "[[sp1_versions]]\nname = \"5.2.2\"\n[[sp1_versions]]\nname = \"5.2.2\"\n";
+        assert!(FuzzConfig::parse(duplicate).is_err());
+        assert!(FuzzConfig::parse("[[sp1_versions]]\nname = \"../x\"\n").is_err());
+        assert!(FuzzConfig::parse("").unwrap().sp1_versions.is_empty());
+    }
+}
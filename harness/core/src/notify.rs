@@ -0,0 +1,392 @@
+//! Notification hooks fired when a new divergence is found
+//!
+//! `fuzz.toml` can list `[[notify]]` hooks: a command that receives the
+//! triage JSON on stdin, or a webhook the JSON is POSTed to (through `curl`).
+//! Divergences are deduplicated by [`signature`] across runs and campaigns,
+//! so an overnight campaign that hits the same bug a thousand times notifies
+//! once. Hook failures are reported but never abort a run. `harness soak`
+//! also fires every hook when the environment drifts mid-campaign
+//! ([`notify_drift`], with `"event": "environment_drift"`).
+//!
+//! ```toml
+//! [[notify]]
+//! name = "slack"
+//! webhook = "https://hooks.slack.com/services/..."
+//! format = "slack"        # {"text": ...} summary instead of the triage JSON
+//!
+//! [[notify]]
+//! name = "triage"
+//! command = ["./scripts/on_divergence.sh", "--urgent"]
+//! cores = ["arith"]       # only divergences in these cores (default: all)
+//! ```
+
+use crate::RunLog;
+use anyhow::{Context, Result};
+use rust_eq_oracle::{Diff, RunResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+/// Signatures that were already notified (one per line)
+const SEEN_PATH: &str = "artifacts/notify/seen.txt";
+
+/// Seconds `curl` may take per webhook
+const WEBHOOK_TIMEOUT_SECS: u32 = 10;
+
+static HOOKS: OnceLock<Vec<NotifyHook>> = OnceLock::new();
+
+/// One `[[notify]]` entry in `fuzz.toml`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotifyHook {
+    /// Label used in messages
+    pub name: String,
+    /// Program and arguments; gets the payload on stdin
+    #[serde(default)]
+    pub command: Option<Vec<String>>,
+    /// URL the payload is POSTed to
+    #[serde(default)]
+    pub webhook: Option<String>,
+    #[serde(default)]
+    pub format: NotifyFormat,
+    /// Only fire for these cores (default: all)
+    #[serde(default)]
+    pub cores: Vec<String>,
+}
+
+/// What a hook is sent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyFormat {
+    /// The [`Notice`] as JSON
+    #[default]
+    Json,
+    /// A Slack incoming-webhook message (`{"text": ...}`)
+    Slack,
+}
+
+impl NotifyHook {
+    /// Exactly one of `command` and `webhook` must be set
+    pub fn validate(&self, idx: usize) -> Result<()> {
+        match (&self.command, &self.webhook) {
+            (Some(command), None) => {
+                anyhow::ensure!(!command.is_empty(), "notify[{}]: command must not be empty", idx)
+            }
+            (None, Some(url)) => anyhow::ensure!(
+                url.starts_with("http://") || url.starts_with("https://"),
+                "notify[{}]: webhook must be an http(s) URL",
+                idx
+            ),
+            _ => anyhow::bail!("notify[{}]: set exactly one of `command` and `webhook`", idx),
+        }
+        Ok(())
+    }
+
+    fn applies_to(&self, core_name: &str) -> bool {
+        self.cores.is_empty() || self.cores.iter().any(|core| core == core_name)
+    }
+
+    /// Deliver `notice` to this hook
+    pub fn fire(&self, notice: &Notice) -> Result<()> {
+        self.deliver(
+            notice,
+            &notice.summary(),
+            &[
+                ("ZKFUZZ_RUN_ID", &notice.run_id),
+                ("ZKFUZZ_CORE", &notice.core),
+                ("ZKFUZZ_SIGNATURE", &notice.signature),
+                ("ZKFUZZ_REPRO_DIR", &notice.repro_dir),
+            ],
+        )
+    }
+
+    /// Send `payload` (or `summary`, for Slack) with `env` set for commands
+    fn deliver(&self, payload: &impl Serialize, summary: &str, env: &[(&str, &str)]) -> Result<()> {
+        let body = match self.format {
+            NotifyFormat::Json => serde_json::to_vec_pretty(payload)?,
+            NotifyFormat::Slack => serde_json::to_vec(&serde_json::json!({ "text": summary }))?,
+        };
+
+        let mut cmd = match (&self.command, &self.webhook) {
+            (Some(command), _) => {
+                let mut cmd = Command::new(&command[0]);
+                cmd.args(&command[1..]);
+                cmd
+            }
+            (None, Some(url)) => {
+                let mut cmd = Command::new("curl");
+                cmd.args(["-sS", "--fail", "--max-time", &WEBHOOK_TIMEOUT_SECS.to_string()])
+                    .args(["-X", "POST", "-H", "Content-Type: application/json", "--data-binary", "@-", url])
+                    .stdout(Stdio::null());
+                cmd
+            }
+            (None, None) => anyhow::bail!("hook has neither a command nor a webhook"),
+        };
+        let mut child = cmd
+            .envs(env.iter().copied())
+            .stdin(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to start hook '{}'", self.name))?;
+        // A hook may exit without reading its input
+        let _ = child.stdin.take().unwrap().write_all(&body);
+        let status = child.wait()?;
+        anyhow::ensure!(status.success(), "hook '{}' exited with {}", self.name, status);
+        Ok(())
+    }
+}
+
+/// Payload sent to hooks (the triage JSON)
+#[derive(Debug, Serialize)]
+pub struct Notice<'a> {
+    pub event: &'static str,
+    pub signature: String,
+    /// How urgent the divergence looks (see [`crate::severity`])
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<crate::severity::Severity>,
+    pub run_id: String,
+    pub core: String,
+    pub repro_dir: String,
+    /// Mutation that produced the input (fuzz runs only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mutation: Option<String>,
+    pub run: &'a RunLog,
+}
+
+impl<'a> Notice<'a> {
+    pub fn new(log: &'a RunLog, repro_dir: &Path, mutation: Option<String>) -> Self {
+        let core = core_name(log);
+        Notice {
+            event: "divergence",
+            signature: signature(log),
+            severity: crate::severity::of_log(log, Some(repro_dir)),
+            run_id: log.run_id.clone(),
+            core,
+            repro_dir: repro_dir.display().to_string(),
+            mutation,
+            run: log,
+        }
+    }
+
+    /// One-line description for chat messages
+    pub fn summary(&self) -> String {
+        let campaign = self.run.campaign.as_ref().map(|c| format!(" [{}]", c.id)).unwrap_or_default();
+        let severity = self
+            .severity
+            .as_ref()
+            .map(|severity| format!(" [{} {}]", severity.level.as_str(), severity.score))
+            .unwrap_or_default();
+        format!(
+            "New divergence{} in {}{}: {} (native={:?}, sp1={:?}) — repro: {}",
+            severity,
+            self.core,
+            campaign,
+            self.run.diff.reason.as_deref().unwrap_or("results differ"),
+            self.run.native_result.status,
+            self.run.sp1_result.status,
+            self.repro_dir
+        )
+    }
+}
+
+fn core_name(log: &RunLog) -> String {
+    Path::new(&log.core_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| log.core_path.clone())
+}
+
+/// What makes two divergences "the same": core, both statuses and the kind
+/// of mismatch (the reason up to its first `:`, e.g. `commit stream mismatch
+/// at index 2`), leaving out the concrete values that differ per input
+pub fn signature(log: &RunLog) -> String {
+    divergence_signature(&core_name(log), &log.native_result, &log.sp1_result, &log.diff)
+}
+
+/// [`signature`] of a comparison that has no run log (yet)
+pub fn divergence_signature(core_name: &str, native: &RunResult, sp1: &RunResult, diff: &Diff) -> String {
+    let reason = diff.reason.as_deref().unwrap_or("");
+    let kind = reason.split(':').next().unwrap_or("").trim();
+    format!("{}|{:?}|{:?}|{}", core_name, native.status, sp1.status, kind)
+}
+
+/// Signatures already notified, persisted across runs
+pub struct SeenSignatures {
+    path: PathBuf,
+    seen: BTreeSet<String>,
+}
+
+impl SeenSignatures {
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let seen = match fs::read_to_string(&path) {
+            Ok(text) => text.lines().map(str::to_string).collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeSet::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        Ok(SeenSignatures { path, seen })
+    }
+
+    /// Record `signature`; false if it was seen before
+    pub fn insert(&mut self, signature: &str) -> Result<bool> {
+        if !self.seen.insert(signature.to_string()) {
+            return Ok(false);
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", signature)?;
+        Ok(true)
+    }
+}
+
+/// Hooks from `fuzz.toml` (loaded once; none if the file is absent or invalid)
+fn hooks() -> &'static [NotifyHook] {
+    HOOKS.get_or_init(|| match crate::matrix::FuzzConfig::load() {
+        Ok(config) => config.notify,
+        Err(e) => {
+            eprintln!("   ⚠️  Notification hooks disabled: {:#}", e);
+            Vec::new()
+        }
+    })
+}
+
+/// Fire the configured hooks for a divergence unless its signature was
+/// already notified
+pub fn notify_divergence(log: &RunLog, repro_dir: &Path, mutation: Option<String>) {
+    let hooks = hooks();
+    if hooks.is_empty() {
+        return;
+    }
+    let notice = Notice::new(log, repro_dir, mutation);
+    let is_new = SeenSignatures::load(SEEN_PATH).and_then(|mut seen| seen.insert(&notice.signature));
+    match is_new {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            eprintln!("   ⚠️  Failed to update {}: {:#}", SEEN_PATH, e);
+            return;
+        }
+    }
+
+    for hook in hooks.iter().filter(|hook| hook.applies_to(&notice.core)) {
+        match hook.fire(&notice) {
+            Ok(()) => println!("   🔔 Notified '{}'", hook.name),
+            Err(e) => eprintln!("   ⚠️  Notification hook failed: {:#}", e),
+        }
+    }
+}
+
+/// Fire every configured hook (whatever its `cores`) for a soak whose
+/// environment drifted (see [`crate::soak`])
+pub fn notify_drift(notice: &crate::soak::DriftNotice) {
+    for hook in hooks() {
+        match hook.deliver(notice, &notice.summary(), &[("ZKFUZZ_SOAK_ID", notice.soak_id)]) {
+            Ok(()) => println!("   🔔 Notified '{}'", hook.name),
+            Err(e) => eprintln!("   ⚠️  Notification hook failed: {:#}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_eq_oracle::{compare, Status};
+
+    fn run_log(native_commits: &[&str], sp1_status: Status) -> RunLog {
+        let result = |status, commits: &[&str]| RunResult {
+            status,
+            commits: commits.iter().map(|c| serde_json::json!(c)).collect(),
+            elapsed_ms: 0,
+            meta: serde_json::json!({}),
+        };
+        let native = result(Status::Ok, native_commits);
+        let sp1 = result(sp1_status, &["1", "2"]);
+        RunLog {
+            schema_version: crate::run_log::SCHEMA_VERSION,
+            run_id: "arith_1".to_string(),
+            timestamp: String::new(),
+            core_path: "guest/cores/arith".to_string(),
+            input_path: "inputs/arith.json".to_string(),
+            diff: compare(&native, &sp1),
+            native_result: native,
+            sp1_result: sp1,
+            environment: None,
+            checked_native: None,
+            campaign: None,
+            expectation: None,
+        }
+    }
+
+    #[test]
+    fn test_signature_ignores_values() {
+        let a = run_log(&["1", "3"], Status::Ok);
+        let b = run_log(&["1", "4"], Status::Ok);
+        assert_eq!(signature(&a), "arith|Ok|Ok|commit stream mismatch at index 1");
+        assert_eq!(signature(&a), signature(&b));
+        assert_ne!(signature(&a), signature(&run_log(&["0", "2"], Status::Ok)));
+        assert_ne!(signature(&a), signature(&run_log(&["1", "3"], Status::Panic)));
+
+        let path = std::env::temp_dir().join(format!("notify-seen-{}.txt", std::process::id()));
+        let mut seen = SeenSignatures::load(&path).unwrap();
+        assert!(seen.insert(&signature(&a)).unwrap());
+        assert!(!seen.insert(&signature(&b)).unwrap());
+        // Persisted for the next run
+        assert!(!SeenSignatures::load(&path).unwrap().insert(&signature(&a)).unwrap());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_hooks() {
+        let config = crate::matrix::FuzzConfig::parse(
+            r#"
+            [[notify]]
+            name = "slack"
+            webhook = "https://hooks.slack.com/services/x"
+            format = "slack"
+
+            [[notify]]
+            name = "script"
+            command = ["./on_divergence.sh"]
+            cores = ["arith"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.notify[0].format, NotifyFormat::Slack);
+        assert!(config.notify[1].applies_to("arith") && !config.notify[1].applies_to("fib"));
+
+        assert!(crate::matrix::FuzzConfig::parse("[[notify]]\nname = \"x\"\n").is_err());
+        assert!(crate::matrix::FuzzConfig::parse("[[notify]]\nname = \"x\"\ncommand = []\n").is_err());
+        assert!(crate::matrix::FuzzConfig::parse("[[notify]]\nname = \"x\"\nwebhook = \"ftp://x\"\n").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_hook_receives_triage_json() {
+        let out = std::env::temp_dir().join(format!("notify-hook-{}.json", std::process::id()));
+        let hook = NotifyHook {
+            name: "capture".to_string(),
+            command: Some(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                "cat > \"$0\"; test \"$ZKFUZZ_RUN_ID\" = arith_1".to_string(),
+                out.display().to_string(),
+            ]),
+            webhook: None,
+            format: NotifyFormat::Json,
+            cores: Vec::new(),
+        };
+        let log = run_log(&["1", "3"], Status::Ok);
+        hook.fire(&Notice::new(&log, Path::new("artifacts/arith_1"), None)).unwrap();
+
+        let payload: serde_json::Value = serde_json::from_str(&fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(payload["event"], "divergence");
+        assert_eq!(payload["repro_dir"], "artifacts/arith_1");
+        assert_eq!(payload["run"]["diff"]["equal"], false);
+        fs::remove_file(&out).unwrap();
+    }
+}
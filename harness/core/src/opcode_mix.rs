@@ -0,0 +1,234 @@
+//! Opcode-mix cliffs
+//!
+//! sp1-runner records how many instructions of each opcode an execution ran
+//! (`meta.opcode_counts`), and every logged run keeps them in
+//! `artifacts/runs.jsonl` as `sp1_opcodes`. Outputs can match while the
+//! guest takes a very different path through the code: an input that turns
+//! a word copy into byte loads and stores, or sends a parser down a
+//! fallback. Cycle baselines catch runs that got slower; a change in *what*
+//! the guest executes can hide inside a normal cycle count.
+//!
+//! [`find_cliffs`] turns each run's histogram into the share of each
+//! [`Class`] of instructions and compares it with the runs of the same core
+//! closest in input size (up to [`NEIGHBORS`], within [`MAX_SIZE_RATIO`]).
+//! A share that moved by at least [`MIN_SHIFT`] and by [`CLIFF_SIGMA`]
+//! standard deviations of its neighbours is a [`Cliff`].
+
+use crate::cost_model::RunRecord;
+use rust_eq_oracle::Status;
+use std::collections::BTreeMap;
+
+/// Runs of similar size a run is compared with
+pub const NEIGHBORS: usize = 10;
+
+/// How much larger or smaller than a run its neighbours' inputs may be
+pub const MAX_SIZE_RATIO: f64 = 4.0;
+
+/// Neighbours needed before a run is judged
+pub const MIN_NEIGHBORS: usize = 5;
+
+/// Smallest change in a class's share of instructions that is flagged
+pub const MIN_SHIFT: f64 = 0.10;
+
+/// Standard deviations from the neighbours' mean share that are flagged
+pub const CLIFF_SIGMA: f64 = 5.0;
+
+/// Instruction classes compared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Class {
+    /// Loads and stores
+    Memory,
+    /// Integer arithmetic, logic, shifts and comparisons
+    Alu,
+    MulDiv,
+    Branch,
+    Jump,
+    /// `ecall`s: precompiles, I/O and halting
+    Syscall,
+}
+
+impl Class {
+    pub const ALL: [Class; 6] = [Class::Memory, Class::Alu, Class::MulDiv, Class::Branch, Class::Jump, Class::Syscall];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Class::Memory => "memory",
+            Class::Alu => "alu",
+            Class::MulDiv => "mul_div",
+            Class::Branch => "branch",
+            Class::Jump => "jump",
+            Class::Syscall => "syscall",
+        }
+    }
+
+    /// Class of an SP1 opcode mnemonic (None for `ebreak`, `unimp`, ...)
+    pub fn of(mnemonic: &str) -> Option<Class> {
+        match mnemonic {
+            "lb" | "lh" | "lw" | "lbu" | "lhu" | "sb" | "sh" | "sw" => Some(Class::Memory),
+            "add" | "sub" | "xor" | "or" | "and" | "sll" | "srl" | "sra" | "slt" | "sltu" | "auipc" => Some(Class::Alu),
+            "mul" | "mulh" | "mulhu" | "mulhsu" | "div" | "divu" | "rem" | "remu" => Some(Class::MulDiv),
+            "beq" | "bne" | "blt" | "bge" | "bltu" | "bgeu" => Some(Class::Branch),
+            "jal" | "jalr" => Some(Class::Jump),
+            "ecall" => Some(Class::Syscall),
+            _ => None,
+        }
+    }
+}
+
+/// Share of each class in a histogram (None if it counted nothing)
+pub fn shares(opcodes: &BTreeMap<String, u64>) -> Option<BTreeMap<Class, f64>> {
+    let total: u64 = opcodes.values().sum();
+    if total == 0 {
+        return None;
+    }
+    let mut shares: BTreeMap<Class, f64> = Class::ALL.iter().map(|class| (*class, 0.0)).collect();
+    for (mnemonic, count) in opcodes {
+        if let Some(class) = Class::of(mnemonic) {
+            *shares.entry(class).or_default() += *count as f64 / total as f64;
+        }
+    }
+    Some(shares)
+}
+
+/// A run whose share of one instruction class broke from its neighbours'
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cliff {
+    pub run_id: String,
+    pub core: String,
+    pub input: String,
+    pub input_bytes: u64,
+    /// Whether the outputs matched (a cliff is hidden behavior if so)
+    pub equal: bool,
+    pub class: Class,
+    pub share: f64,
+    /// Mean share of the neighbours
+    pub expected: f64,
+    pub sigma: f64,
+    /// Input sizes of the neighbours (smallest, largest)
+    pub neighbor_bytes: (u64, u64),
+}
+
+/// A run and its class shares
+type Run<'a> = (&'a RunRecord, BTreeMap<Class, f64>);
+
+/// Mean and sample standard deviation
+fn mean_stddev(values: &[f64]) -> (f64, f64) {
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1).max(1) as f64;
+    (mean, variance.sqrt())
+}
+
+/// Runs whose opcode mix shifts abruptly from similar-sized runs of the same
+/// core, largest shifts first
+///
+/// Only runs where SP1 finished (`OK`) with a histogram are compared.
+pub fn find_cliffs(records: &[RunRecord]) -> Vec<Cliff> {
+    let mut by_core: BTreeMap<&str, Vec<Run>> = BTreeMap::new();
+    for record in records {
+        if record.sp1_status != Status::Ok {
+            continue;
+        }
+        if let Some(shares) = record.sp1_opcodes.as_ref().and_then(shares) {
+            by_core.entry(&record.core).or_default().push((record, shares));
+        }
+    }
+
+    let mut cliffs = Vec::new();
+    for runs in by_core.values() {
+        let log_size = |record: &RunRecord| (record.input_bytes.max(1) as f64).ln();
+        for (index, (record, shares)) in runs.iter().enumerate() {
+            let mut neighbors: Vec<&Run> = runs
+                .iter()
+                .enumerate()
+                .filter(|(other, run)| *other != index && (log_size(run.0) - log_size(record)).abs() <= MAX_SIZE_RATIO.ln())
+                .map(|(_, run)| run)
+                .collect();
+            neighbors.sort_by(|a, b| {
+                let distance = |run: &&Run| (log_size(run.0) - log_size(record)).abs();
+                distance(a).total_cmp(&distance(b))
+            });
+            neighbors.truncate(NEIGHBORS);
+            if neighbors.len() < MIN_NEIGHBORS {
+                continue;
+            }
+            let sizes = neighbors.iter().map(|(neighbor, _)| neighbor.input_bytes);
+            let neighbor_bytes = (sizes.clone().min().unwrap_or(0), sizes.max().unwrap_or(0));
+
+            for class in Class::ALL {
+                let values: Vec<f64> = neighbors.iter().map(|(_, shares)| shares[&class]).collect();
+                let (expected, stddev) = mean_stddev(&values);
+                let share = shares[&class];
+                // Floored so perfectly stable neighbours don't flag jitter
+                let sigma = (share - expected) / stddev.max(0.01);
+                if (share - expected).abs() >= MIN_SHIFT && sigma.abs() >= CLIFF_SIGMA {
+                    cliffs.push(Cliff {
+                        run_id: record.run_id.clone(),
+                        core: record.core.clone(),
+                        input: record.input.clone(),
+                        input_bytes: record.input_bytes,
+                        equal: record.equal,
+                        class,
+                        share,
+                        expected,
+                        sigma,
+                        neighbor_bytes,
+                    });
+                }
+            }
+        }
+    }
+    cliffs.sort_by(|a, b| (b.share - b.expected).abs().total_cmp(&(a.share - a.expected).abs()));
+    cliffs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(run_id: &str, input_bytes: u64, loads: u64, adds: u64) -> RunRecord {
+        RunRecord {
+            run_id: run_id.to_string(),
+            core: "io_echo".to_string(),
+            input: format!("inputs/{}.json", run_id),
+            input_bytes,
+            native_ms: 1,
+            sp1_ms: 100,
+            sp1_cycles: Some(loads + adds),
+            sp1_opcodes: Some(BTreeMap::from([("lw".to_string(), loads), ("add".to_string(), adds)])),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_shares() {
+        let opcodes = BTreeMap::from([("lw".to_string(), 1), ("sb".to_string(), 1), ("beq".to_string(), 2)]);
+        let shares = shares(&opcodes).unwrap();
+        assert_eq!(shares[&Class::Memory], 0.5);
+        assert_eq!(shares[&Class::Branch], 0.5);
+        assert_eq!(shares[&Class::Alu], 0.0);
+        assert_eq!(super::shares(&BTreeMap::new()), None);
+    }
+
+    #[test]
+    fn test_find_cliffs() {
+        // A steady 20% memory share, and one input of the same size at 60%
+        let mut records: Vec<RunRecord> = (0..8).map(|i| record(&format!("run_{}", i), 100 + i, 200 + i, 800)).collect();
+        records.push(record("cliff", 104, 600, 400));
+        // Far larger inputs are not its neighbours
+        records.extend((0..8).map(|i| record(&format!("big_{}", i), 1_000_000 + i, 600, 400)));
+
+        let cliffs = find_cliffs(&records);
+        let flagged: Vec<(&str, Class)> = cliffs.iter().map(|cliff| (cliff.run_id.as_str(), cliff.class)).collect();
+        assert_eq!(flagged.len(), 2);
+        assert!(flagged.contains(&("cliff", Class::Memory)));
+        assert!(flagged.contains(&("cliff", Class::Alu)));
+        let memory = cliffs.iter().find(|cliff| cliff.class == Class::Memory).unwrap();
+        assert!((memory.share - 0.6).abs() < 1e-9);
+        assert!((memory.expected - 0.2).abs() < 0.01);
+        assert!(memory.sigma > CLIFF_SIGMA);
+        assert!(memory.equal);
+
+        // Too few runs to judge
+        assert!(find_cliffs(&records[..4]).is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+//! Promotion of interesting mutations into the seed corpus
+//!
+//! A mutation that diverged, or whose SP1 cycle count was far above its
+//! baseline, is copied into `inputs/promoted/<core>/` under a descriptive
+//! name. Every file there is a base seed: later fuzz rounds mutate it
+//! alongside the core's hand-written base input.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PROMOTED_DIR: &str = "inputs/promoted";
+
+/// Why an input was promoted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromotionReason {
+    Divergence,
+    CycleOutlier,
+}
+
+impl PromotionReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            PromotionReason::Divergence => "divergence",
+            PromotionReason::CycleOutlier => "cycle_outlier",
+        }
+    }
+}
+
+/// Directory holding a core's promoted seeds
+pub fn promoted_dir(core_name: &str) -> PathBuf {
+    PathBuf::from(PROMOTED_DIR).join(core_name)
+}
+
+/// File name for a promoted input: `<reason>_<mutation op>_<hash prefix>.json`
+///
+/// The hash keeps names unique per input and makes promoting the same input
+/// twice a no-op.
+pub fn promoted_file_name(reason: PromotionReason, mutation_op: &str, input_sha256: &str) -> String {
+    let op: String = mutation_op
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c.to_ascii_lowercase() } else { '_' })
+        .take(60)
+        .collect();
+    format!("{}_{}_{}.json", reason.as_str(), op.trim_matches('_'), hash_prefix(input_sha256))
+}
+
+fn hash_prefix(input_sha256: &str) -> &str {
+    &input_sha256[..input_sha256.len().min(12)]
+}
+
+/// Copy `input_path` into the core's promoted seeds
+///
+/// Returns the new seed's path, or None if the input was already promoted.
+pub fn promote_input(
+    core_name: &str,
+    input_path: &Path,
+    reason: PromotionReason,
+    mutation_op: &str,
+) -> Result<Option<PathBuf>> {
+    let bytes = fs::read(input_path)
+        .with_context(|| format!("Failed to read {}", input_path.display()))?;
+    let input_sha256 = crate::corpus::input_sha256(&bytes);
+
+    let dir = promoted_dir(core_name);
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    // Already promoted (under any reason or op)?
+    let suffix = format!("_{}.json", hash_prefix(&input_sha256));
+    if promoted_seeds(core_name)?
+        .iter()
+        .any(|seed| seed.to_string_lossy().ends_with(&suffix))
+    {
+        return Ok(None);
+    }
+
+    let path = dir.join(promoted_file_name(reason, mutation_op, &input_sha256));
+    fs::write(&path, &bytes).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(Some(path))
+}
+
+/// A core's promoted seeds, sorted by name (empty if none)
+pub fn promoted_seeds(core_name: &str) -> Result<Vec<PathBuf>> {
+    let dir = promoted_dir(core_name);
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut seeds: Vec<PathBuf> = fs::read_dir(&dir)
+        .with_context(|| format!("Failed to list {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    seeds.sort();
+    Ok(seeds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_promoted_file_name_is_descriptive_and_safe() {
+        let name = promoted_file_name(
+            PromotionReason::Divergence,
+            "boundary_values:0_4294967295_op_Div",
+            "0123456789abcdef",
+        );
+        assert_eq!(name, "divergence_boundary_values_0_4294967295_op_div_0123456789ab.json");
+    }
+}
@@ -0,0 +1,749 @@
+//! Algebraic properties declared in core manifests
+//!
+//! Differential testing only catches targets that disagree; a bug both
+//! targets share (or a wrong core) passes. A property states what must hold
+//! on each target by itself, usually as a metamorphic relation between the
+//! results of an input and a follow-up input derived from it:
+//!
+//! ```toml
+//! [[package.metadata.fuzz.properties]]
+//! name = "add_commutative"
+//! when = 'operation == "add"'          # optional: inputs it applies to
+//! follow_up = { a = "b", b = "a" }     # optional: fields of the second input
+//! holds = "y[0] == x[0] && y[1] == x[1]"
+//! ```
+//!
+//! Expressions use integers, strings, `true`/`false`, top-level input fields
+//! by name (in `when`, `follow_up` and `holds`, always of the original
+//! input), `x[i]` (commit `i` of the original run) and `y[i]` (of the
+//! follow-up run), with `+ - * / %`, comparisons, `&& || !` and
+//! parentheses. Integer arithmetic is exact (overflow is an error, not a
+//! wrap), so `% 4294967296` spells out u32 wrapping. Committed bools are
+//! 0/1 integers.
+
+use crate::{
+    base_input_for_core, build_failure_result, build_sp1_guest, elf_path_for_core, guest_path_for_core, load_input,
+    new_run_id, parse_cores_arg, run_native_runner_sandboxed, run_sp1_runner, write_input, RetryPolicy,
+};
+use anyhow::{Context, Result};
+use rust_eq_oracle::{RunResult, Status};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One `[[package.metadata.fuzz.properties]]` entry
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Property {
+    pub name: String,
+    /// Condition on the input (default: always)
+    #[serde(default)]
+    pub when: Option<String>,
+    /// Follow-up input: fields to replace, each an expression over the original
+    #[serde(default)]
+    pub follow_up: Option<BTreeMap<String, String>>,
+    /// Condition on the commits that must be true
+    pub holds: String,
+}
+
+/// Properties declared in a core's manifest (empty if none)
+pub fn load_properties(core_name: &str) -> Result<Vec<Property>> {
+    let manifest_path = Path::new("guest/cores").join(core_name).join("Cargo.toml");
+    let manifest = fs::read_to_string(&manifest_path)
+        .with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+    parse_manifest(&manifest).with_context(|| format!("Invalid properties in {}", manifest_path.display()))
+}
+
+fn parse_manifest(manifest: &str) -> Result<Vec<Property>> {
+    let Some(properties) = source_mutator::grammar::fuzz_metadata_in(manifest, "properties")? else {
+        return Ok(Vec::new());
+    };
+    let properties: Vec<Property> = properties.try_into()?;
+    for property in &properties {
+        property.validate()?;
+    }
+    Ok(properties)
+}
+
+impl Property {
+    /// Parse every expression, so typos fail when the manifest is loaded
+    pub fn validate(&self) -> Result<()> {
+        let context = |what: &str| format!("property '{}': {}", self.name, what);
+        if let Some(when) = &self.when {
+            parse(when).with_context(|| context("when"))?;
+        }
+        for (field, expr) in self.follow_up.iter().flatten() {
+            parse(expr).with_context(|| context(&format!("follow_up.{}", field)))?;
+        }
+        let holds = parse(&self.holds).with_context(|| context("holds"))?;
+        anyhow::ensure!(
+            self.follow_up.is_some() || !holds.uses_follow_up(),
+            "{}",
+            context("holds refers to y[..] but there is no follow_up")
+        );
+        Ok(())
+    }
+
+    /// Whether the property applies to `input`
+    pub fn applies(&self, input: &Value) -> Result<bool> {
+        match &self.when {
+            Some(when) => parse(when)?.eval(&Scope::input(input))?.as_bool(),
+            None => Ok(true),
+        }
+    }
+
+    /// The follow-up input for `input` (None if the property has no follow-up)
+    pub fn follow_up_input(&self, input: &Value) -> Result<Option<Value>> {
+        let Some(fields) = &self.follow_up else {
+            return Ok(None);
+        };
+        let mut follow_up = input.clone();
+        let object = follow_up.as_object_mut().context("input is not a JSON object")?;
+        for (field, expr) in fields {
+            let value = parse(expr)?.eval(&Scope::input(input))?;
+            object.insert(field.clone(), value.to_json());
+        }
+        Ok(Some(follow_up))
+    }
+
+    /// Whether `holds` is true for the commits of one target
+    pub fn check(&self, input: &Value, x: &[Value], y: Option<&[Value]>) -> Result<bool> {
+        parse(&self.holds)?.eval(&Scope { input, x: Some(x), y })?.as_bool()
+    }
+}
+
+/// Outcome of a property on one target
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "verdict", content = "detail")]
+pub enum Verdict {
+    Holds,
+    Violated,
+    /// A run did not finish with `Ok`, so there is nothing to check
+    Skipped(String),
+    /// `holds` could not be evaluated (e.g. a commit index out of range)
+    Error(String),
+}
+
+impl Verdict {
+    /// Check `property` against one target's runs of the input (`x`) and
+    /// the follow-up input (`y`)
+    pub fn of(property: &Property, input: &Value, x: &RunResult, y: Option<&RunResult>) -> Verdict {
+        for (side, run) in [("original", Some(x)), ("follow-up", y)] {
+            if let Some(run) = run.filter(|run| run.status != Status::Ok) {
+                return Verdict::Skipped(format!("{} run: {:?}", side, run.status));
+            }
+        }
+        match property.check(input, &x.commits, y.map(|y| y.commits.as_slice())) {
+            Ok(true) => Verdict::Holds,
+            Ok(false) => Verdict::Violated,
+            Err(e) => Verdict::Error(format!("{:#}", e)),
+        }
+    }
+}
+
+/// One property checked on one input, per target
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PropertyCheck {
+    pub core: String,
+    pub property: String,
+    pub input: String,
+    /// Follow-up input file (None if the property has no follow-up)
+    pub follow_up_input: Option<String>,
+    pub native: Verdict,
+    pub zkvm: Verdict,
+}
+
+impl PropertyCheck {
+    /// Both targets violate the property: a bug differential testing cannot see
+    pub fn consistent_violation(&self) -> bool {
+        self.native == Verdict::Violated && self.zkvm == Verdict::Violated
+    }
+}
+
+/// Parse an expression without evaluating it
+pub(crate) fn validate_expression(text: &str) -> Result<()> {
+    parse(text).map(|_| ())
+}
+
+/// Evaluate a boolean expression over an input and up to two commit streams
+pub(crate) fn eval_condition(text: &str, input: &Value, x: Option<&[Value]>, y: Option<&[Value]>) -> Result<bool> {
+    parse(text)?.eval(&Scope { input, x, y })?.as_bool()
+}
+
+// ---------------------------------------------------------------------------
+// Expressions
+
+#[derive(Debug, Clone, PartialEq)]
+enum Val {
+    Int(i128),
+    Bool(bool),
+    Str(String),
+}
+
+impl Val {
+    fn from_json(value: &Value, what: &str) -> Result<Val> {
+        match value {
+            Value::Bool(b) => Ok(Val::Bool(*b)),
+            Value::String(s) => Ok(Val::Str(s.clone())),
+            Value::Number(n) => n
+                .as_i64()
+                .map(i128::from)
+                .or_else(|| n.as_u64().map(i128::from))
+                .map(Val::Int)
+                .with_context(|| format!("{} is not an integer: {}", what, n)),
+            other => anyhow::bail!("{} has unsupported type: {}", what, other),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            Val::Int(n) => u64::try_from(*n)
+                .map(Value::from)
+                .or_else(|_| i64::try_from(*n).map(Value::from))
+                .unwrap_or_else(|_| Value::String(n.to_string())),
+            Val::Bool(b) => Value::Bool(*b),
+            Val::Str(s) => Value::String(s.clone()),
+        }
+    }
+
+    fn as_bool(&self) -> Result<bool> {
+        match self {
+            Val::Bool(b) => Ok(*b),
+            other => anyhow::bail!("expected a boolean, got {:?}", other),
+        }
+    }
+
+    fn as_int(&self) -> Result<i128> {
+        match self {
+            Val::Int(n) => Ok(*n),
+            other => anyhow::bail!("expected an integer, got {:?}", other),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl Op {
+    fn precedence(self) -> u8 {
+        match self {
+            Op::Or => 1,
+            Op::And => 2,
+            Op::Eq | Op::Ne | Op::Lt | Op::Le | Op::Gt | Op::Ge => 3,
+            Op::Add | Op::Sub => 4,
+            Op::Mul | Op::Div | Op::Rem => 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Lit(Val),
+    Field(String),
+    Commit { follow_up: bool, index: usize },
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Int(i128),
+    Str(String),
+    Ident(String),
+    Op(Op),
+    Not,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let two = |op| (Token::Op(op), 2);
+        let (token, len) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('|', Some('|')) => two(Op::Or),
+            ('&', Some('&')) => two(Op::And),
+            ('=', Some('=')) => two(Op::Eq),
+            ('!', Some('=')) => two(Op::Ne),
+            ('<', Some('=')) => two(Op::Le),
+            ('>', Some('=')) => two(Op::Ge),
+            ('<', _) => (Token::Op(Op::Lt), 1),
+            ('>', _) => (Token::Op(Op::Gt), 1),
+            ('+', _) => (Token::Op(Op::Add), 1),
+            ('-', _) => (Token::Op(Op::Sub), 1),
+            ('*', _) => (Token::Op(Op::Mul), 1),
+            ('/', _) => (Token::Op(Op::Div), 1),
+            ('%', _) => (Token::Op(Op::Rem), 1),
+            ('!', _) => (Token::Not, 1),
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            ('[', _) => (Token::LBracket, 1),
+            (']', _) => (Token::RBracket, 1),
+            ('"', _) => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&c| c == '"')
+                    .context("unterminated string")?;
+                let s: String = chars[i + 1..i + 1 + end].iter().collect();
+                (Token::Str(s), end + 2)
+            }
+            (c, _) if c.is_ascii_digit() => {
+                let len = chars[i..].iter().take_while(|c| c.is_ascii_digit() || **c == '_').count();
+                let digits: String = chars[i..i + len].iter().filter(|c| **c != '_').collect();
+                (Token::Int(digits.parse().with_context(|| format!("integer too large: {}", digits))?), len)
+            }
+            (c, _) if c.is_alphabetic() || c == '_' => {
+                let len = chars[i..].iter().take_while(|c| c.is_alphanumeric() || **c == '_').count();
+                (Token::Ident(chars[i..i + len].iter().collect()), len)
+            }
+            (c, _) => anyhow::bail!("unexpected character '{}'", c),
+        };
+        tokens.push(token);
+        i += len;
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            other => anyhow::bail!("expected {:?}, found {:?}", expected, other),
+        }
+    }
+
+    /// Precedence climbing: operators binding tighter than `min`
+    fn expr(&mut self, min: u8) -> Result<Expr> {
+        let mut lhs = self.unary()?;
+        while let Some(Token::Op(op)) = self.peek() {
+            let op = *op;
+            if op.precedence() < min {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.expr(op.precedence() + 1)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.unary()?))),
+            Some(Token::Op(Op::Sub)) => Ok(Expr::Neg(Box::new(self.unary()?))),
+            Some(Token::Int(n)) => Ok(Expr::Lit(Val::Int(n))),
+            Some(Token::Str(s)) => Ok(Expr::Lit(Val::Str(s))),
+            Some(Token::LParen) => {
+                let inner = self.expr(0)?;
+                self.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            Some(Token::Ident(name)) => match name.as_str() {
+                "true" => Ok(Expr::Lit(Val::Bool(true))),
+                "false" => Ok(Expr::Lit(Val::Bool(false))),
+                "x" | "y" if self.peek() == Some(&Token::LBracket) => {
+                    self.pos += 1;
+                    let index = match self.next() {
+                        Some(Token::Int(n)) => usize::try_from(n)?,
+                        other => anyhow::bail!("expected a commit index, found {:?}", other),
+                    };
+                    self.expect(Token::RBracket)?;
+                    Ok(Expr::Commit { follow_up: name == "y", index })
+                }
+                _ => Ok(Expr::Field(name)),
+            },
+            other => anyhow::bail!("expected a value, found {:?}", other),
+        }
+    }
+}
+
+fn parse(text: &str) -> Result<Expr> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        pos: 0,
+    };
+    let expr = parser.expr(0)?;
+    if let Some(token) = parser.peek() {
+        anyhow::bail!("unexpected {:?} after expression", token);
+    }
+    Ok(expr)
+}
+
+/// What an expression can refer to
+struct Scope<'a> {
+    input: &'a Value,
+    x: Option<&'a [Value]>,
+    y: Option<&'a [Value]>,
+}
+
+impl<'a> Scope<'a> {
+    fn input(input: &'a Value) -> Scope<'a> {
+        Scope { input, x: None, y: None }
+    }
+}
+
+impl Expr {
+    fn uses_follow_up(&self) -> bool {
+        match self {
+            Expr::Commit { follow_up, .. } => *follow_up,
+            Expr::Not(inner) | Expr::Neg(inner) => inner.uses_follow_up(),
+            Expr::Binary(_, a, b) => a.uses_follow_up() || b.uses_follow_up(),
+            Expr::Lit(_) | Expr::Field(_) => false,
+        }
+    }
+
+    fn eval(&self, scope: &Scope) -> Result<Val> {
+        match self {
+            Expr::Lit(val) => Ok(val.clone()),
+            Expr::Field(name) => {
+                let value = scope.input.get(name).with_context(|| format!("input has no field '{}'", name))?;
+                Val::from_json(value, name)
+            }
+            Expr::Commit { follow_up, index } => {
+                let (side, commits) = if *follow_up { ("y", scope.y) } else { ("x", scope.x) };
+                let commits = commits.with_context(|| format!("{}[..] is not available here", side))?;
+                let value = commits
+                    .get(*index)
+                    .with_context(|| format!("{}[{}] out of range ({} commits)", side, index, commits.len()))?;
+                Val::from_json(value, &format!("{}[{}]", side, index))
+            }
+            Expr::Not(inner) => Ok(Val::Bool(!inner.eval(scope)?.as_bool()?)),
+            Expr::Neg(inner) => Ok(Val::Int(-inner.eval(scope)?.as_int()?)),
+            Expr::Binary(Op::Or, a, b) => Ok(Val::Bool(a.eval(scope)?.as_bool()? || b.eval(scope)?.as_bool()?)),
+            Expr::Binary(Op::And, a, b) => Ok(Val::Bool(a.eval(scope)?.as_bool()? && b.eval(scope)?.as_bool()?)),
+            Expr::Binary(op, a, b) => {
+                let (a, b) = (a.eval(scope)?, b.eval(scope)?);
+                match op {
+                    Op::Eq | Op::Ne => {
+                        anyhow::ensure!(
+                            std::mem::discriminant(&a) == std::mem::discriminant(&b),
+                            "cannot compare {:?} with {:?}",
+                            a,
+                            b
+                        );
+                        Ok(Val::Bool((a == b) == (*op == Op::Eq)))
+                    }
+                    Op::Lt | Op::Le | Op::Gt | Op::Ge => {
+                        let (a, b) = (a.as_int()?, b.as_int()?);
+                        Ok(Val::Bool(match op {
+                            Op::Lt => a < b,
+                            Op::Le => a <= b,
+                            Op::Gt => a > b,
+                            _ => a >= b,
+                        }))
+                    }
+                    _ => {
+                        let (a, b) = (a.as_int()?, b.as_int()?);
+                        let result = match op {
+                            Op::Add => a.checked_add(b),
+                            Op::Sub => a.checked_sub(b),
+                            Op::Mul => a.checked_mul(b),
+                            Op::Div => a.checked_div(b),
+                            _ => a.checked_rem(b),
+                        };
+                        result
+                            .map(Val::Int)
+                            .with_context(|| format!("{} {:?} {} overflows or divides by zero", a, op, b))
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `harness properties`, `purity`, `scale` and `replay-csv` flags
+#[derive(Debug, Clone, Copy)]
+pub struct PropertyOptions {
+    pub skip_build: bool,
+    pub allow_stale_elf: bool,
+    pub sandbox: bool,
+}
+
+/// Check each core's manifest properties on native and SP1 independently
+///
+/// Every input runs once per target; each property that applies to it adds
+/// a run of its follow-up input. Inputs and `report.json` are written to
+/// `artifacts/properties/<run id>/`.
+pub fn check_properties(
+    cores_arg: &str,
+    input: Option<&Path>,
+    options: PropertyOptions,
+    retry: &RetryPolicy,
+) -> Result<()> {
+    let mut cores = Vec::new();
+    for core_name in parse_cores_arg(cores_arg)? {
+        let properties = load_properties(core_name)?;
+        if properties.is_empty() {
+            println!("⏭️  {}: no properties declared", core_name);
+        } else {
+            cores.push((core_name, properties));
+        }
+    }
+    if options.skip_build {
+        crate::provenance::ensure_fresh(cores.iter().map(|(core, _)| *core), options.allow_stale_elf)?;
+    }
+
+    let out_dir = PathBuf::from("artifacts/properties").join(new_run_id("properties"));
+    fs::create_dir_all(&out_dir)?;
+    println!("🧮 Checking properties...");
+    println!();
+
+    let mut checks = Vec::new();
+    for (core_name, properties) in &cores {
+        println!("📦 {} ({} properties)", core_name, properties.len());
+        let elf_path = elf_path_for_core(core_name);
+        let mut build_error = None;
+        if !options.skip_build {
+            if let (Err(e), retries) = retry.run("SP1 guest build", || build_sp1_guest(&guest_path_for_core(core_name))) {
+                println!("   ❌ SP1 guest build failed: {:#}", e);
+                build_error = Some((e, retries));
+            }
+        }
+
+        let inputs = inputs_to_check(core_name, input)?;
+
+        // Both targets on one input file
+        let run_both = |path: &Path| {
+            let native = retry.run_runner("native", || run_native_runner_sandboxed(core_name, path, options.sandbox));
+            let zkvm = match &build_error {
+                Some((e, retries)) => build_failure_result(e, *retries),
+                None => retry.run_runner("sp1", || run_sp1_runner(&elf_path, path, core_name)),
+            };
+            (native, zkvm)
+        };
+
+        for (index, input_json) in inputs.iter().enumerate() {
+            let applicable: Vec<_> = properties
+                .iter()
+                .filter_map(|property| match property.applies(input_json) {
+                    Ok(true) => Some(Ok(property)),
+                    Ok(false) => None,
+                    Err(e) => Some(Err(e.context(format!("property '{}'", property.name)))),
+                })
+                .collect::<Result<_>>()?;
+            if applicable.is_empty() {
+                continue;
+            }
+
+            let input_dir = out_dir.join(core_name).join(format!("input_{:04}", index));
+            fs::create_dir_all(&input_dir)?;
+            let input_path = input_dir.join("input.json");
+            write_input(core_name, input_json, &input_path)?;
+            let (native_x, zkvm_x) = run_both(&input_path);
+
+            for property in applicable {
+                let follow_up_path = match property.follow_up_input(input_json)? {
+                    Some(follow_up) => {
+                        let path = input_dir.join(format!("{}.json", property.name));
+                        write_input(core_name, &follow_up, &path)?;
+                        Some(path)
+                    }
+                    None => None,
+                };
+                let (native_y, zkvm_y) = match &follow_up_path {
+                    Some(path) => {
+                        let (native, zkvm) = run_both(path);
+                        (Some(native), Some(zkvm))
+                    }
+                    None => (None, None),
+                };
+                let check = PropertyCheck {
+                    core: core_name.to_string(),
+                    property: property.name.clone(),
+                    input: input_path.display().to_string(),
+                    follow_up_input: follow_up_path.map(|path| path.display().to_string()),
+                    native: Verdict::of(property, input_json, &native_x, native_y.as_ref()),
+                    zkvm: Verdict::of(property, input_json, &zkvm_x, zkvm_y.as_ref()),
+                };
+                if check.native != Verdict::Holds || check.zkvm != Verdict::Holds {
+                    let icon = if check.consistent_violation() { "🚨" } else { "⚠️ " };
+                    println!(
+                        "   {} {} on {}: native {:?}, SP1 {:?}",
+                        icon, check.property, check.input, check.native, check.zkvm
+                    );
+                }
+                checks.push(check);
+            }
+        }
+        println!();
+    }
+
+    let report_path = out_dir.join("report.json");
+    fs::write(&report_path, serde_json::to_string_pretty(&checks)?)?;
+
+    let count = |f: &dyn Fn(&PropertyCheck) -> bool| checks.iter().filter(|c| f(c)).count();
+    println!("📊 Property checks: {}", checks.len());
+    println!(
+        "   Holds on both targets: {}",
+        count(&|c| c.native == Verdict::Holds && c.zkvm == Verdict::Holds)
+    );
+    println!("   Violated on both targets (consistent bug): {}", count(&|c| c.consistent_violation()));
+    println!(
+        "   Violated on one target: {}",
+        count(&|c| (c.native == Verdict::Violated) != (c.zkvm == Verdict::Violated))
+    );
+    println!(
+        "   Skipped or not evaluable: {}",
+        count(&|c| [&c.native, &c.zkvm].iter().any(|v| matches!(v, Verdict::Skipped(_) | Verdict::Error(_))))
+    );
+    println!("💾 Report saved to {}", report_path.display());
+
+    Ok(())
+}
+
+/// `input` if given, else a core's base input and its distinct mutations
+pub(crate) fn inputs_to_check(core_name: &str, input: Option<&Path>) -> Result<Vec<serde_json::Value>> {
+    match input {
+        Some(path) => Ok(vec![load_input(core_name, path)?]),
+        None => {
+            let base_path = base_input_for_core(core_name)?;
+            let base = load_input(core_name, &base_path)?;
+            let mutations = source_mutator::generate_mutations(core_name, &base, base_path.to_str().unwrap(), 0)?;
+            let mut seen = std::collections::HashSet::new();
+            Ok(std::iter::once(base)
+                .chain(mutations.into_iter().map(|m| m.input_json))
+                .filter(|input| seen.insert(input.to_string()))
+                .collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_expressions() {
+        let input = json!({ "a": 7, "b": 3, "operation": "add", "flag": true });
+        let eval = |text: &str| parse(text).unwrap().eval(&Scope::input(&input)).unwrap();
+        assert_eq!(eval("a + b * 2 - 1"), Val::Int(12));
+        assert_eq!(eval("(a + b) % 4"), Val::Int(2));
+        assert_eq!(eval("-a + 10"), Val::Int(3));
+        assert_eq!(eval(r#"operation == "add" && !(a < b) || false"#), Val::Bool(true));
+        assert_eq!(eval("flag != false"), Val::Bool(true));
+        assert_eq!(eval("4_294_967_295 + 1"), Val::Int(1 << 32));
+
+        let fails = |text: &str| parse(text).and_then(|expr| expr.eval(&Scope::input(&input))).is_err();
+        assert!(fails("a / 0"));
+        assert!(fails("a == \"7\""));
+        assert!(fails("missing + 1"));
+        assert!(fails("x[0] == 1"));
+        assert!(fails("a +"));
+        assert!(fails("(a"));
+        assert!(fails("a b"));
+    }
+
+    #[test]
+    fn test_manifest_properties() {
+        let properties = parse_manifest(
+            r#"
+[package]
+name = "fib-core"
+
+[[package.metadata.fuzz.properties]]
+name = "recurrence"
+when = "n < 4294967295"
+follow_up = { n = "n + 1" }
+holds = "y[1] == x[2] && y[2] == (x[1] + x[2]) % 7919"
+
+[[package.metadata.fuzz.properties]]
+name = "echo"
+holds = "x[0] == n"
+"#,
+        )
+        .unwrap();
+        assert_eq!(properties.len(), 2);
+
+        let recurrence = &properties[0];
+        let input = json!({ "n": 24 });
+        assert!(recurrence.applies(&input).unwrap());
+        assert_eq!(recurrence.follow_up_input(&input).unwrap(), Some(json!({ "n": 25 })));
+        let x = [json!(24), json!(6000), json!(5000)];
+        assert!(recurrence.check(&input, &x, Some(&[json!(25), json!(5000), json!(3081)])).unwrap());
+        assert!(!recurrence.check(&input, &x, Some(&[json!(25), json!(5000), json!(3080)])).unwrap());
+        assert!(!recurrence.applies(&json!({ "n": 4294967295u64 })).unwrap());
+
+        assert!(properties[1].check(&input, &x, None).unwrap());
+        assert_eq!(properties[1].follow_up_input(&input).unwrap(), None);
+
+        let no_follow_up = "[package]\nname = \"x\"\n[[package.metadata.fuzz.properties]]\nname = \"p\"\nholds = \"y[0] == 1\"\n";
+        assert!(parse_manifest(no_follow_up).is_err());
+        let typo = "[package]\nname = \"x\"\n[[package.metadata.fuzz.properties]]\nname = \"p\"\nholds = \"x[0] ==\"\n";
+        assert!(parse_manifest(typo).is_err());
+        assert!(parse_manifest("[package]\nname = \"x\"\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_verdicts() {
+        let property = Property {
+            name: "add_commutative".to_string(),
+            when: Some(r#"operation == "add""#.to_string()),
+            follow_up: Some(BTreeMap::from([("a".to_string(), "b".to_string()), ("b".to_string(), "a".to_string())])),
+            holds: "y[0] == x[0]".to_string(),
+        };
+        let input = json!({ "a": 2, "b": 3, "operation": "add" });
+        assert_eq!(
+            property.follow_up_input(&input).unwrap(),
+            Some(json!({ "a": 3, "b": 2, "operation": "add" }))
+        );
+        assert!(!property.applies(&json!({ "a": 2, "b": 3, "operation": "sub" })).unwrap());
+
+        let run = |status: Status, commits: Vec<Value>| RunResult {
+            status,
+            elapsed_ms: 0,
+            commits,
+            meta: json!({}),
+        };
+        let x = run(Status::Ok, vec![json!(5), json!(0)]);
+        assert_eq!(Verdict::of(&property, &input, &x, Some(&run(Status::Ok, vec![json!(5)]))), Verdict::Holds);
+        assert_eq!(Verdict::of(&property, &input, &x, Some(&run(Status::Ok, vec![json!(6)]))), Verdict::Violated);
+        assert_eq!(
+            Verdict::of(&property, &input, &x, Some(&run(Status::Panic, vec![]))),
+            Verdict::Skipped("follow-up run: Panic".to_string())
+        );
+        assert!(matches!(Verdict::of(&property, &input, &x, Some(&run(Status::Ok, vec![]))), Verdict::Error(_)));
+    }
+}
@@ -0,0 +1,226 @@
+//! Guest ELF provenance
+//!
+//! Every run records which ELF it executed: hash, size, when it was built
+//! and the SP1 toolchain that built it. The toolchain comes from a sidecar
+//! (`<elf>.provenance.json`) written after each harness build; an ELF built
+//! some other way (or rebuilt since) has no matching sidecar and records
+//! the toolchain as unknown rather than guessing from the host.
+//!
+//! [`find_stale`] guards `--skip-build`: an ELF older than its guest
+//! sources was not built from the code being fuzzed.
+//!
+//! Runs also record the git commit of the checkout and whether the harness,
+//! runner, oracle, core or adapter sources had uncommitted changes
+//! ([`GitInfo`]), so a divergence can be traced back to the source that
+//! produced it.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+/// What a run's guest ELF was and how it was built
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ElfInfo {
+    pub sha256: String,
+    pub size_bytes: u64,
+    /// File modification time (RFC 3339)
+    pub built_at: Option<String>,
+    /// `cargo prove --version` of the build (None if not built by the harness)
+    pub sp1_toolchain: Option<String>,
+}
+
+/// Source trees whose changes can change a run's result
+pub const SOURCE_PATHS: &[&str] = &["harness", "runners", "oracles", "guest", "adapters"];
+
+/// Git revision of the checkout a run was made from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitInfo {
+    /// `HEAD` commit hash
+    pub commit: String,
+    /// Uncommitted changes (untracked files included) under [`SOURCE_PATHS`]
+    pub dirty: bool,
+}
+
+impl GitInfo {
+    /// Describe the current checkout (None without git or outside a repository)
+    pub fn capture() -> Option<GitInfo> {
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+        };
+        let commit = git(&["rev-parse", "HEAD"])?.trim().to_string();
+        let mut status = vec!["status", "--porcelain", "--"];
+        status.extend(SOURCE_PATHS);
+        let dirty = !git(&status)?.trim().is_empty();
+        Some(GitInfo { commit, dirty })
+    }
+}
+
+/// Sidecar written next to an ELF after a harness build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BuildRecord {
+    sha256: String,
+    sp1_toolchain: String,
+    recorded_at: String,
+}
+
+fn sidecar_path(elf_path: &Path) -> PathBuf {
+    let mut name = elf_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".provenance.json");
+    elf_path.with_file_name(name)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn rfc3339(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339()
+}
+
+impl ElfInfo {
+    /// Describe the ELF at `elf_path` (None if it does not exist)
+    pub fn capture(elf_path: &Path) -> Option<ElfInfo> {
+        let sha256 = crate::sha256_file(elf_path)?;
+        let metadata = fs::metadata(elf_path).ok()?;
+        let sp1_toolchain = fs::read_to_string(sidecar_path(elf_path))
+            .ok()
+            .and_then(|text| serde_json::from_str::<BuildRecord>(&text).ok())
+            .filter(|record| record.sha256 == sha256)
+            .map(|record| record.sp1_toolchain);
+        Some(ElfInfo {
+            sha256,
+            size_bytes: metadata.len(),
+            built_at: metadata.modified().ok().map(rfc3339),
+            sp1_toolchain,
+        })
+    }
+}
+
+/// Record that `elf_path` was just built with `sp1_toolchain`
+pub fn record_build(elf_path: &Path, sp1_toolchain: &str) -> Result<()> {
+    let sha256 = crate::sha256_file(elf_path)
+        .with_context(|| format!("Built ELF not found: {}", elf_path.display()))?;
+    let record = BuildRecord {
+        sha256,
+        sp1_toolchain: sp1_toolchain.to_string(),
+        recorded_at: Utc::now().to_rfc3339(),
+    };
+    fs::write(sidecar_path(elf_path), serde_json::to_string_pretty(&record)?)
+        .with_context(|| format!("Failed to record provenance of {}", elf_path.display()))
+}
+
+/// A guest ELF older than one of its sources
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleElf {
+    pub elf_path: PathBuf,
+    pub elf_built_at: String,
+    /// Most recently modified source file
+    pub source: PathBuf,
+    pub source_modified_at: String,
+}
+
+impl std::fmt::Display for StaleElf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (built {}) is older than {} (modified {})",
+            self.elf_path.display(),
+            self.elf_built_at,
+            self.source.display(),
+            self.source_modified_at
+        )
+    }
+}
+
+/// Check an ELF against the newest file under `source_roots` (`target/`
+/// directories skipped); None if it is up to date or does not exist
+pub fn find_stale(elf_path: &Path, source_roots: &[PathBuf]) -> Option<StaleElf> {
+    let elf_built = modified(elf_path)?;
+    let snapshot = crate::watch::Snapshot::capture(source_roots);
+    let (source, source_modified) = snapshot.newest()?;
+    (source_modified > elf_built).then(|| StaleElf {
+        elf_path: elf_path.to_path_buf(),
+        elf_built_at: rfc3339(elf_built),
+        source: source.to_path_buf(),
+        source_modified_at: rfc3339(source_modified),
+    })
+}
+
+/// [`find_stale`] for a core's release ELF and its core and guest sources
+pub fn find_stale_core(core_name: &str) -> Option<StaleElf> {
+    let core_path = PathBuf::from(format!("guest/cores/{}", core_name));
+    find_stale(
+        &crate::elf_path_for_core(core_name),
+        &crate::watch::watch_roots(&core_path, core_name),
+    )
+}
+
+/// Refuse to run `--skip-build` against stale ELFs unless `allow_stale`
+pub fn ensure_fresh<'a>(core_names: impl IntoIterator<Item = &'a str>, allow_stale: bool) -> Result<()> {
+    for core_name in core_names {
+        if let Some(stale) = find_stale_core(core_name) {
+            if !allow_stale {
+                anyhow::bail!(
+                    "Stale ELF for {}: {}\n\nRebuild it (drop --skip-build) or pass --allow-stale-elf",
+                    core_name,
+                    stale
+                );
+            }
+            println!("   ⚠️  Fuzzing {} with a stale ELF: {}", core_name, stale);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_elf_info_and_staleness() {
+        let dir = std::env::temp_dir().join(format!("harness-provenance-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        let elf = dir.join("guest.elf");
+        fs::write(&elf, b"\x7fELF").unwrap();
+
+        let info = ElfInfo::capture(&elf).unwrap();
+        assert_eq!(info.size_bytes, 4);
+        assert!(info.built_at.is_some());
+        assert_eq!(info.sp1_toolchain, None);
+
+        record_build(&elf, "cargo-prove sp1 (v4.0.0)").unwrap();
+        assert_eq!(ElfInfo::capture(&elf).unwrap().sp1_toolchain.as_deref(), Some("cargo-prove sp1 (v4.0.0)"));
+        // Rebuilt outside the harness: the sidecar no longer describes it
+        fs::write(&elf, b"\x7fELF2").unwrap();
+        assert_eq!(ElfInfo::capture(&elf).unwrap().sp1_toolchain, None);
+        assert_eq!(ElfInfo::capture(&dir.join("missing.elf")), None);
+
+        let source = dir.join("src/main.rs");
+        fs::write(&source, "fn main() {}").unwrap();
+        let roots = vec![dir.join("src")];
+        let set_modified = |path: &Path, time: SystemTime| {
+            fs::File::options().write(true).open(path).unwrap().set_modified(time).unwrap();
+        };
+        let now = SystemTime::now();
+        set_modified(&source, now - Duration::from_secs(60));
+        set_modified(&elf, now);
+        assert_eq!(find_stale(&elf, &roots), None);
+
+        set_modified(&source, now + Duration::from_secs(60));
+        let stale = find_stale(&elf, &roots).unwrap();
+        assert_eq!(stale.source, source);
+        assert!(stale.to_string().contains("is older than"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,268 @@
+//! Purity: commits as a function of the input alone
+//!
+//! A core is expected to commit the same stream every time it runs on the
+//! same input. If it does not (it reads the clock, a random source or a
+//! hash seed), its divergences cannot be reproduced and its passes mean
+//! little. `harness purity` runs each input several times on each target,
+//! and [`Purity::of`] compares every run with the first: status and
+//! commits, not timing.
+
+use crate::properties::{inputs_to_check, PropertyOptions};
+use crate::{
+    build_failure_result, build_sp1_guest, elf_path_for_core, guest_path_for_core, new_run_id, parse_cores_arg,
+    run_native_runner_sandboxed, run_sp1_runner, write_input, RetryPolicy,
+};
+use anyhow::Result;
+use rust_eq_oracle::{describe_commit_index, RunResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Whether one target's repeated runs of an input agree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "verdict", content = "detail")]
+pub enum Purity {
+    Pure,
+    /// A run differed from the first (which run, and how)
+    Varies(String),
+    /// An infrastructure failure left nothing to compare
+    Skipped(String),
+}
+
+impl Purity {
+    /// Compare repeated runs of one input on one target
+    pub fn of(runs: &[RunResult]) -> Purity {
+        if let Some(run) = runs.iter().find(|run| run.status.is_infra()) {
+            return Purity::Skipped(format!("{:?}", run.status));
+        }
+        let Some((first, rest)) = runs.split_first() else {
+            return Purity::Skipped("no runs".to_string());
+        };
+        for (index, run) in rest.iter().enumerate() {
+            if let Some(variation) = variation(first, run) {
+                return Purity::Varies(format!("run {}: {}", index + 1, variation));
+            }
+        }
+        Purity::Pure
+    }
+}
+
+/// How `run` differs from `first` (None if it does not)
+fn variation(first: &RunResult, run: &RunResult) -> Option<String> {
+    if run.status != first.status {
+        return Some(format!("status {:?}, run 0 {:?}", run.status, first.status));
+    }
+    if let Some(index) = first.commits.iter().zip(&run.commits).position(|(a, b)| a != b) {
+        return Some(format!(
+            "commit {} is {}, run 0 {}",
+            describe_commit_index(first, run, index),
+            run.commits[index],
+            first.commits[index]
+        ));
+    }
+    (run.commits.len() != first.commits.len())
+        .then(|| format!("{} commits, run 0 {}", run.commits.len(), first.commits.len()))
+}
+
+/// One input run repeatedly on one target
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PurityCheck {
+    pub core: String,
+    /// `native` or `sp1`
+    pub target: String,
+    pub input: String,
+    pub runs: usize,
+    pub purity: Purity,
+}
+
+/// Checks of one core on one target
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PurityStats {
+    pub inputs: usize,
+    pub pure: usize,
+    pub varies: usize,
+    pub skipped: usize,
+}
+
+/// Checks per core, then per target
+pub fn summarize(checks: &[PurityCheck]) -> BTreeMap<String, BTreeMap<String, PurityStats>> {
+    let mut stats: BTreeMap<String, BTreeMap<String, PurityStats>> = BTreeMap::new();
+    for check in checks {
+        let entry = stats
+            .entry(check.core.clone())
+            .or_default()
+            .entry(check.target.clone())
+            .or_default();
+        entry.inputs += 1;
+        match check.purity {
+            Purity::Pure => entry.pure += 1,
+            Purity::Varies(_) => entry.varies += 1,
+            Purity::Skipped(_) => entry.skipped += 1,
+        }
+    }
+    stats
+}
+
+/// Run every input `repeats` times on native and on SP1, and report the
+/// cores and targets whose commits vary from run to run
+///
+/// Inputs and `report.json` (every check, plus counts per core and target)
+/// are written to `artifacts/purity/<run id>/`.
+pub fn check_purity(
+    cores_arg: &str,
+    input: Option<&Path>,
+    repeats: usize,
+    options: PropertyOptions,
+    retry: &RetryPolicy,
+) -> Result<()> {
+    anyhow::ensure!(repeats >= 2, "--repeats must be at least 2 to compare runs");
+    let cores = parse_cores_arg(cores_arg)?;
+    if options.skip_build {
+        crate::provenance::ensure_fresh(cores.iter().copied(), options.allow_stale_elf)?;
+    }
+
+    let out_dir = PathBuf::from("artifacts/purity").join(new_run_id("purity"));
+    fs::create_dir_all(&out_dir)?;
+    println!("🔁 Checking purity ({} runs per input and target)...", repeats);
+    println!();
+
+    let mut checks = Vec::new();
+    for core_name in &cores {
+        println!("📦 {}", core_name);
+        let elf_path = elf_path_for_core(core_name);
+        let mut build_error = None;
+        if !options.skip_build {
+            if let (Err(e), retries) = retry.run("SP1 guest build", || build_sp1_guest(&guest_path_for_core(core_name))) {
+                println!("   ❌ SP1 guest build failed: {:#}", e);
+                build_error = Some((e, retries));
+            }
+        }
+
+        let core_dir = out_dir.join(core_name);
+        fs::create_dir_all(&core_dir)?;
+        for (index, input_json) in inputs_to_check(core_name, input)?.iter().enumerate() {
+            let input_path = core_dir.join(format!("input_{:04}.json", index));
+            write_input(core_name, input_json, &input_path)?;
+
+            for target in ["native", "sp1"] {
+                let runs: Vec<RunResult> = (0..repeats)
+                    .map(|_| match (target, &build_error) {
+                        ("native", _) => retry.run_runner("native", || {
+                            run_native_runner_sandboxed(core_name, &input_path, options.sandbox)
+                        }),
+                        (_, Some((e, retries))) => build_failure_result(e, *retries),
+                        _ => retry.run_runner("sp1", || run_sp1_runner(&elf_path, &input_path, core_name)),
+                    })
+                    .collect();
+                let check = PurityCheck {
+                    core: core_name.to_string(),
+                    target: target.to_string(),
+                    input: input_path.display().to_string(),
+                    runs: repeats,
+                    purity: Purity::of(&runs),
+                };
+                if let Purity::Varies(detail) = &check.purity {
+                    println!("   🎲 {} on {}: {}", check.target, check.input, detail);
+                }
+                checks.push(check);
+            }
+        }
+        println!();
+    }
+
+    let stats = summarize(&checks);
+    let report_path = out_dir.join("report.json");
+    fs::write(
+        &report_path,
+        serde_json::to_string_pretty(&serde_json::json!({
+            "repeats": repeats,
+            "stats": stats,
+            "checks": checks,
+        }))?,
+    )?;
+
+    println!("📊 Purity checks: {}", checks.len());
+    for (core_name, targets) in &stats {
+        for (target, stats) in targets {
+            let icon = match (stats.varies, stats.pure) {
+                (0, 0) => "⏭️ ",
+                (0, _) => "✅",
+                _ => "🎲",
+            };
+            println!(
+                "   {} {:<24} {:<6} {:>4} inputs, {:>4} vary, {:>4} skipped",
+                icon, core_name, target, stats.inputs, stats.varies, stats.skipped
+            );
+        }
+    }
+    let varying: Vec<String> = stats
+        .iter()
+        .flat_map(|(core_name, targets)| {
+            targets
+                .iter()
+                .filter(|(_, stats)| stats.varies > 0)
+                .map(move |(target, _)| format!("{} ({})", core_name, target))
+        })
+        .collect();
+    if varying.is_empty() {
+        println!("   No run-to-run variation");
+    } else {
+        println!("   Varying: {}", varying.join(", "));
+    }
+    println!("💾 Report saved to {}", report_path.display());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_eq_oracle::Status;
+    use serde_json::json;
+
+    fn run(status: Status, commits: Vec<serde_json::Value>, elapsed_ms: u128) -> RunResult {
+        RunResult {
+            status,
+            elapsed_ms,
+            commits,
+            meta: json!({ "commit_names": ["seed", "value"] }),
+        }
+    }
+
+    #[test]
+    fn test_purity_of_runs() {
+        let a = run(Status::Ok, vec![json!(1), json!(2)], 5);
+        assert_eq!(Purity::of(&[a.clone(), run(Status::Ok, vec![json!(1), json!(2)], 9)]), Purity::Pure);
+        assert_eq!(
+            Purity::of(&[a.clone(), a.clone(), run(Status::Ok, vec![json!(1), json!(3)], 5)]),
+            Purity::Varies("run 2: commit index 1 (value) is 3, run 0 2".to_string())
+        );
+        assert_eq!(
+            Purity::of(&[a.clone(), run(Status::Panic, vec![json!(1)], 5)]),
+            Purity::Varies("run 1: status Panic, run 0 Ok".to_string())
+        );
+        assert!(matches!(Purity::of(&[a, run(Status::InfraError, vec![], 0)]), Purity::Skipped(_)));
+        assert!(matches!(Purity::of(&[]), Purity::Skipped(_)));
+    }
+
+    #[test]
+    fn test_summarize() {
+        let check = |core: &str, target: &str, purity: Purity| PurityCheck {
+            core: core.to_string(),
+            target: target.to_string(),
+            input: String::new(),
+            runs: 3,
+            purity,
+        };
+        let stats = summarize(&[
+            check("fib", "native", Purity::Pure),
+            check("fib", "sp1", Purity::Pure),
+            check("nondeterminism_probe", "native", Purity::Varies("run 1".to_string())),
+            check("nondeterminism_probe", "native", Purity::Skipped("InfraError".to_string())),
+        ]);
+        assert_eq!(stats["fib"]["sp1"].pure, 1);
+        let probe = &stats["nondeterminism_probe"]["native"];
+        assert_eq!((probe.inputs, probe.varies, probe.skipped), (2, 1, 1));
+    }
+}
@@ -0,0 +1,212 @@
+//! Expected relations between native and zkVM results
+//!
+//! Some cores probe APIs that a zkVM runtime implements differently on
+//! purpose (threads on a single-threaded runtime, for instance), so exact
+//! equality is the wrong oracle for them: what is worth catching is a
+//! change in *how* the results differ. Such a core declares relations in
+//! its manifest, and its native and zkVM results are judged by them:
+//!
+//! ```toml
+//! [[package.metadata.fuzz.relations]]
+//! name = "zkvm_runs_workers_inline"
+//! when = "workers > 0"                 # optional: inputs it applies to
+//! holds = "y[1] == 0 && y[2] == y[0]"
+//! ```
+//!
+//! Expressions are those of [`crate::properties`], with `x[i]` a native
+//! commit and `y[i]` a zkVM commit. When both runs finish with `Ok` and at
+//! least one relation applies, the results pass if every applicable
+//! relation holds, whether or not the commits are equal. Otherwise they
+//! are compared exactly, as for any other core.
+
+use crate::properties::{eval_condition, validate_expression};
+use anyhow::{Context, Result};
+use rust_eq_oracle::{compare, Diff, DiffKind, RunResult, Status};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// One `[[package.metadata.fuzz.relations]]` entry
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Relation {
+    pub name: String,
+    /// Condition on the input (default: always)
+    #[serde(default)]
+    pub when: Option<String>,
+    /// Condition on the native (`x`) and zkVM (`y`) commits that must be true
+    pub holds: String,
+}
+
+/// Relations declared in a core's manifest (empty if none)
+pub fn load_relations(core_name: &str) -> Result<Vec<Relation>> {
+    let manifest_path = Path::new("guest/cores").join(core_name).join("Cargo.toml");
+    let manifest = fs::read_to_string(&manifest_path)
+        .with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+    parse_manifest(&manifest).with_context(|| format!("Invalid relations in {}", manifest_path.display()))
+}
+
+fn parse_manifest(manifest: &str) -> Result<Vec<Relation>> {
+    let Some(relations) = source_mutator::grammar::fuzz_metadata_in(manifest, "relations")? else {
+        return Ok(Vec::new());
+    };
+    let relations: Vec<Relation> = relations.try_into()?;
+    for relation in &relations {
+        let context = |what: &str| format!("relation '{}': {}", relation.name, what);
+        if let Some(when) = &relation.when {
+            validate_expression(when).with_context(|| context("when"))?;
+        }
+        validate_expression(&relation.holds).with_context(|| context("holds"))?;
+    }
+    Ok(relations)
+}
+
+impl Relation {
+    /// Whether the relation applies to `input`
+    pub fn applies(&self, input: &Value) -> Result<bool> {
+        match &self.when {
+            Some(when) => eval_condition(when, input, None, None),
+            None => Ok(true),
+        }
+    }
+
+    /// Whether `holds` is true for these native and zkVM commits
+    pub fn check(&self, input: &Value, native: &[Value], zkvm: &[Value]) -> Result<bool> {
+        eval_condition(&self.holds, input, Some(native), Some(zkvm))
+    }
+}
+
+/// Compare native and zkVM results, judging them by `relations` when any
+/// apply (see the module docs)
+///
+/// The diff lists the relations that held; a violated relation (or one
+/// that cannot be evaluated, e.g. a commit index out of range) makes it
+/// unequal and is named in the reason.
+pub fn compare_with_relations(relations: &[Relation], input: &Value, native: &RunResult, zkvm: &RunResult) -> Diff {
+    let mut diff = compare(native, zkvm);
+    if relations.is_empty() || native.status != Status::Ok || zkvm.status != Status::Ok {
+        return diff;
+    }
+
+    let mut held = Vec::new();
+    let mut failed = Vec::new();
+    let mut violated = Vec::new();
+    for relation in relations {
+        let verdict = relation
+            .applies(input)
+            .and_then(|applies| applies.then(|| relation.check(input, &native.commits, &zkvm.commits)).transpose());
+        match verdict {
+            Ok(None) => {}
+            Ok(Some(true)) => held.push(relation.name.clone()),
+            Ok(Some(false)) => {
+                failed.push(format!("relation '{}' violated", relation.name));
+                violated.push(relation.name.clone());
+            }
+            Err(e) => {
+                failed.push(format!("relation '{}' could not be checked: {:#}", relation.name, e));
+                violated.push(relation.name.clone());
+            }
+        }
+    }
+    if held.is_empty() && failed.is_empty() {
+        return diff;
+    }
+
+    diff.equal = failed.is_empty();
+    diff.kind = (!failed.is_empty()).then_some(DiffKind::RelationViolated { relations: violated });
+    diff.reason = (!failed.is_empty()).then(|| failed.join("; "));
+    diff.relations = held;
+    diff
+}
+
+/// [`compare_with_relations`] with the relations of `core_name` and the
+/// input file the runs used
+pub fn compare_for_core(core_name: &str, input_path: &Path, native: &RunResult, zkvm: &RunResult) -> Result<Diff> {
+    let relations = load_relations(core_name)?;
+    if relations.is_empty() {
+        return Ok(compare(native, zkvm));
+    }
+    let input = fs::read_to_string(input_path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or(Value::Null);
+    Ok(compare_with_relations(&relations, &input, native, zkvm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn run(status: Status, commits: Vec<Value>) -> RunResult {
+        RunResult {
+            status,
+            elapsed_ms: 1,
+            commits,
+            meta: json!({}),
+        }
+    }
+
+    #[test]
+    fn test_manifest_relations() {
+        let relations = parse_manifest(
+            r#"
+[package]
+name = "thread-api-core"
+
+[[package.metadata.fuzz.relations]]
+name = "zkvm_inline"
+when = "workers > 0"
+holds = "y[1] == 0 && y[2] == x[1]"
+"#,
+        )
+        .unwrap();
+        assert_eq!(relations.len(), 1);
+        assert!(relations[0].applies(&json!({ "workers": 2 })).unwrap());
+        assert!(!relations[0].applies(&json!({ "workers": 0 })).unwrap());
+
+        let typo = "[package]\nname = \"x\"\n[[package.metadata.fuzz.relations]]\nname = \"r\"\nholds = \"y[0] ==\"\n";
+        assert!(parse_manifest(typo).is_err());
+        let unknown = "[package]\nname = \"x\"\n[[package.metadata.fuzz.relations]]\nname = \"r\"\nholds = \"true\"\nfollow_up = {}\n";
+        assert!(parse_manifest(unknown).is_err());
+        assert!(parse_manifest("[package]\nname = \"x\"\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_compare_with_relations() {
+        let relations = vec![Relation {
+            name: "zkvm_inline".to_string(),
+            when: Some("workers > 0".to_string()),
+            holds: "y[1] == 0 && y[2] == x[1]".to_string(),
+        }];
+        let input = json!({ "workers": 2 });
+        let native = run(Status::Ok, vec![json!(2), json!(2), json!(0)]);
+
+        // Unequal commits that match the relation pass
+        let zkvm = run(Status::Ok, vec![json!(2), json!(0), json!(2)]);
+        let diff = compare_with_relations(&relations, &input, &native, &zkvm);
+        assert!(diff.equal && diff.kind.is_none());
+        assert_eq!(diff.relations, ["zkvm_inline"]);
+        assert!(diff.first_divergent_index.is_some());
+
+        // Equal commits that break it fail
+        let diff = compare_with_relations(&relations, &input, &native, &native);
+        assert!(!diff.equal);
+        assert_eq!(diff.reason.as_deref(), Some("relation 'zkvm_inline' violated"));
+        assert_eq!(diff.kind.as_ref().map(DiffKind::as_str), Some("relation_violated"));
+
+        // Out-of-range commits are a failure, not a pass
+        let short = run(Status::Ok, vec![json!(2)]);
+        let diff = compare_with_relations(&relations, &input, &native, &short);
+        assert!(!diff.equal);
+        assert!(diff.reason.unwrap().contains("could not be checked"));
+
+        // No applicable relation, or a non-Ok run: exact comparison
+        let diff = compare_with_relations(&relations, &json!({ "workers": 0 }), &native, &zkvm);
+        assert!(!diff.equal && diff.relations.is_empty());
+        let panicked = run(Status::Panic, vec![]);
+        let diff = compare_with_relations(&relations, &input, &native, &panicked);
+        assert!(diff.reason.unwrap().starts_with("status mismatch"));
+    }
+}
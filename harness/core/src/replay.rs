@@ -0,0 +1,393 @@
+//! Replaying `summary.csv` rows
+//!
+//! Every differential run leaves a row in `artifacts/summary.csv`, so the
+//! file doubles as a regression corpus: `harness replay-csv` selects rows
+//! with [`Filter`]s, finds each row's input ([`source_of`]) and runs it
+//! again. An input is taken from the row's repro folder or its recorded
+//! path while those files exist; a mutated input whose file is gone is
+//! regenerated from the row's `base_seed`, `mutation_ops` and `rng_seed`
+//! ([`regenerate_input`]).
+
+use crate::properties::PropertyOptions;
+use crate::{new_run_id, write_input, DifferentialTest, RetryPolicy};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One `summary.csv` row: column name → cell
+pub type SummaryRow = BTreeMap<String, String>;
+
+/// Read every row of a `summary.csv`
+pub fn load_summary(path: &Path) -> Result<Vec<SummaryRow>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_path(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let header = reader.headers()?.clone();
+    reader
+        .records()
+        .map(|record| {
+            let record = record.with_context(|| format!("Failed to parse {}", path.display()))?;
+            Ok(header.iter().zip(record.iter()).map(|(k, v)| (k.to_string(), v.to_string())).collect())
+        })
+        .collect()
+}
+
+/// A `column=value` or `column!=value` row filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub column: String,
+    pub value: String,
+    pub negated: bool,
+}
+
+impl Filter {
+    /// Parse `column=value` or `column!=value`
+    pub fn parse(spec: &str) -> Result<Filter> {
+        let (column, value, negated) = match spec.split_once("!=") {
+            Some((column, value)) => (column, value, true),
+            None => {
+                let (column, value) = spec
+                    .split_once('=')
+                    .with_context(|| format!("Invalid filter '{}' (expected column=value or column!=value)", spec))?;
+                (column, value, false)
+            }
+        };
+        anyhow::ensure!(!column.trim().is_empty(), "Invalid filter '{}': no column", spec);
+        Ok(Filter {
+            column: column.trim().to_string(),
+            value: value.trim().to_string(),
+            negated,
+        })
+    }
+
+    /// Whether a row passes (a row without the column fails)
+    ///
+    /// Values compare case-insensitively, so `sp1_status=panic` matches `Panic`.
+    pub fn matches(&self, row: &SummaryRow) -> bool {
+        row.get(&self.column)
+            .is_some_and(|cell| cell.eq_ignore_ascii_case(&self.value) != self.negated)
+    }
+}
+
+/// Rows passing every filter, after checking that the filtered columns exist
+pub fn select<'a>(rows: &'a [SummaryRow], filters: &[Filter]) -> Result<Vec<&'a SummaryRow>> {
+    if let Some(row) = rows.first() {
+        for filter in filters {
+            anyhow::ensure!(
+                row.contains_key(&filter.column),
+                "Unknown summary.csv column '{}' (columns: {})",
+                filter.column,
+                row.keys().cloned().collect::<Vec<_>>().join(", ")
+            );
+        }
+    }
+    Ok(rows.iter().filter(|row| filters.iter().all(|filter| filter.matches(row))).collect())
+}
+
+/// Where a row's input comes from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum ReplaySource {
+    /// An input file still on disk (the repro folder's copy, or the recorded path)
+    Archived { path: PathBuf },
+    /// A mutated input to regenerate from its base seed
+    Regenerate {
+        base_seed: String,
+        mutation_ops: String,
+        rng_seed: Option<u64>,
+    },
+    /// Neither: the reason
+    Unavailable { reason: String },
+}
+
+/// Find a row's input: archived files first, then regeneration
+pub fn source_of(row: &SummaryRow) -> ReplaySource {
+    let cell = |column: &str| row.get(column).map(String::as_str).unwrap_or_default();
+    let repro_copy = Some(cell("repro_path"))
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| Path::new(dir).join("input.json"));
+    let recorded = Some(cell("input")).filter(|input| !input.is_empty()).map(PathBuf::from);
+    if let Some(path) = repro_copy.into_iter().chain(recorded).find(|path| path.is_file()) {
+        return ReplaySource::Archived { path };
+    }
+    if cell("generator") == "mutated" && !cell("mutation_ops").is_empty() && !cell("base_seed").is_empty() {
+        return ReplaySource::Regenerate {
+            base_seed: cell("base_seed").to_string(),
+            mutation_ops: cell("mutation_ops").to_string(),
+            rng_seed: cell("rng_seed").parse().ok(),
+        };
+    }
+    ReplaySource::Unavailable {
+        reason: format!("input {} is gone and the row records no mutation to regenerate", cell("input")),
+    }
+}
+
+/// Rebuild a mutated input from its base seed and op label
+///
+/// `base_seed` is a seed name from the core's manifest (or `default`), or
+/// the path of the seed file (promoted seeds). The seed is mutated again,
+/// with `rng_seed` for grammar cores, and the mutation whose label is
+/// `mutation_ops` returned. This only reproduces the original input if the
+/// seed file and the mutator are unchanged since the row was logged.
+pub fn regenerate_input(core_name: &str, base_seed: &str, mutation_ops: &str, rng_seed: Option<u64>) -> Result<Value> {
+    let seed_path = match crate::base_seeds_for_core(core_name)?
+        .into_iter()
+        .find(|seed| seed.name == base_seed)
+    {
+        Some(seed) => seed.path,
+        None => PathBuf::from(base_seed),
+    };
+    let seed_json = crate::load_input(core_name, &seed_path)
+        .with_context(|| format!("Failed to load base seed '{}' of {}", base_seed, core_name))?;
+    let seed_path_str = seed_path.to_str().context("Base seed path is not UTF-8")?;
+
+    let mut candidates = vec![source_mutator::base_seed_input(base_seed, &seed_json, seed_path_str)];
+    candidates.extend(source_mutator::generate_mutations(core_name, &seed_json, seed_path_str, rng_seed.unwrap_or(0))?);
+    candidates
+        .into_iter()
+        .find(|mutation| mutation.op.to_string() == mutation_ops)
+        .map(|mutation| mutation.input_json)
+        .with_context(|| {
+            format!(
+                "Mutating base seed '{}' of {} no longer yields '{}' (the seed or the mutator changed)",
+                base_seed, core_name, mutation_ops
+            )
+        })
+}
+
+/// Whether a replay matched the row's recorded outcome
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayOutcome {
+    pub run_id: String,
+    pub core: String,
+    pub source: ReplaySource,
+    /// Recorded and replayed `native_status`, `sp1_status` and `equal`
+    /// (empty for rows that could not be replayed)
+    pub recorded: [String; 3],
+    pub replayed: Option<[String; 3]>,
+}
+
+impl ReplayOutcome {
+    /// Statuses and equality as recorded in a row
+    pub fn recorded_of(row: &SummaryRow) -> [String; 3] {
+        ["native_status", "sp1_status", "equal"].map(|column| row.get(column).cloned().unwrap_or_default())
+    }
+
+    /// Replayed with the recorded statuses and equality
+    pub fn reproduced(&self) -> bool {
+        self.replayed.as_ref() == Some(&self.recorded)
+    }
+}
+
+/// Re-run the `summary.csv` rows matching `filters` and compare each with
+/// its recorded statuses and equality
+///
+/// Inputs come from the row's repro folder or recorded path, or are
+/// regenerated from the row's mutation (see [`crate::replay`]).
+/// Regenerated inputs and `report.json` are written to
+/// `artifacts/replay/<run id>/`. Fails if any replayed row changed outcome.
+pub fn replay_csv(
+    csv_path: &Path,
+    filters: &[String],
+    limit: Option<usize>,
+    options: PropertyOptions,
+    retry: &RetryPolicy,
+) -> Result<()> {
+    let filters = filters.iter().map(|spec| Filter::parse(spec)).collect::<Result<Vec<_>>>()?;
+    let rows = load_summary(csv_path)?;
+    let mut selected = select(&rows, &filters)?;
+    let matching = selected.len();
+    selected.truncate(limit.unwrap_or(usize::MAX));
+    if selected.is_empty() {
+        println!("🔎 No rows of {} match the filters", csv_path.display());
+        return Ok(());
+    }
+
+    let cell = |row: &crate::replay::SummaryRow, column: &str| row.get(column).cloned().unwrap_or_default();
+    let mut cores: Vec<String> = selected.iter().map(|row| cell(row, "core")).collect();
+    cores.sort();
+    cores.dedup();
+    if options.skip_build {
+        crate::provenance::ensure_fresh(cores.iter().map(String::as_str), options.allow_stale_elf)?;
+    }
+
+    let out_dir = PathBuf::from("artifacts/replay").join(new_run_id("replay"));
+    fs::create_dir_all(&out_dir)?;
+    println!(
+        "🔁 Replaying {} of {} matching rows from {} ({})",
+        selected.len(),
+        matching,
+        csv_path.display(),
+        cores.join(", ")
+    );
+    let mut env_ids: Vec<String> = selected.iter().map(|row| cell(row, "env_id")).collect();
+    env_ids.sort();
+    env_ids.dedup();
+    for env_id in env_ids.iter().filter(|id| !id.is_empty() && **id != crate::environment::current().id) {
+        match crate::environment::load(env_id)? {
+            Some(recorded) => println!(
+                "   ℹ️  Some rows were logged under {}: {}, {}{}",
+                env_id,
+                recorded.sp1_version,
+                recorded.rustc_version,
+                recorded.git_commit.map(|commit| format!(", commit {}", commit)).unwrap_or_default()
+            ),
+            None => println!("   ℹ️  Some rows were logged under {} (no snapshot in artifacts/environments/)", env_id),
+        }
+    }
+    println!();
+
+    let mut built = std::collections::HashSet::new();
+    let mut outcomes = Vec::new();
+    for row in selected {
+        let run_id = cell(row, "run_id");
+        let core_name = cell(row, "core");
+        let source = source_of(row);
+        let input_path = match &source {
+            ReplaySource::Archived { path } => Ok(path.clone()),
+            ReplaySource::Regenerate {
+                base_seed,
+                mutation_ops,
+                rng_seed,
+            } => regenerate_input(&core_name, base_seed, mutation_ops, *rng_seed).and_then(|input| {
+                let path = out_dir.join(format!("{}.json", run_id));
+                write_input(&core_name, &input, &path)?;
+                Ok(path)
+            }),
+            ReplaySource::Unavailable { reason } => Err(anyhow::anyhow!("{}", reason)),
+        };
+        let recorded = ReplayOutcome::recorded_of(row);
+        let input_path = match input_path {
+            Ok(path) => path,
+            Err(e) => {
+                println!("   ⏭️  {} ({}): {:#}", run_id, core_name, e);
+                let reason = format!("{:#}", e);
+                outcomes.push(ReplayOutcome {
+                    run_id,
+                    core: core_name,
+                    source: ReplaySource::Unavailable { reason },
+                    recorded,
+                    replayed: None,
+                });
+                continue;
+            }
+        };
+
+        let outcome = DifferentialTest::new(PathBuf::from("guest/cores").join(&core_name), &input_path)
+            .skip_build(options.skip_build || !built.insert(core_name.clone()))
+            .sandbox(options.sandbox)
+            .retry(*retry)
+            .log_artifacts(false)
+            .run()?;
+        let replayed = [
+            format!("{:?}", outcome.native.status),
+            format!("{:?}", outcome.sp1.status),
+            outcome.diff.equal.to_string(),
+        ];
+        let outcome = ReplayOutcome {
+            run_id,
+            core: core_name,
+            source,
+            recorded,
+            replayed: Some(replayed),
+        };
+        let [native, sp1, equal] = outcome.replayed.as_ref().expect("just replayed");
+        if outcome.reproduced() {
+            println!("   ✅ {} ({}): native {}, sp1 {}, equal {}", outcome.run_id, outcome.core, native, sp1, equal);
+        } else {
+            let [was_native, was_sp1, was_equal] = &outcome.recorded;
+            println!(
+                "   ❌ {} ({}): native {}, sp1 {}, equal {} (recorded {}, {}, {})",
+                outcome.run_id, outcome.core, native, sp1, equal, was_native, was_sp1, was_equal
+            );
+        }
+        outcomes.push(outcome);
+    }
+
+    let report_path = out_dir.join("report.json");
+    fs::write(&report_path, serde_json::to_string_pretty(&outcomes)?)?;
+    let replayed = outcomes.iter().filter(|outcome| outcome.replayed.is_some()).count();
+    let changed = outcomes
+        .iter()
+        .filter(|outcome| outcome.replayed.is_some() && !outcome.reproduced())
+        .count();
+    println!();
+    println!(
+        "📊 {} replayed: {} reproduced, {} changed; {} without an input",
+        replayed,
+        replayed - changed,
+        changed,
+        outcomes.len() - replayed
+    );
+    println!("💾 Report saved to {}", report_path.display());
+    if changed > 0 {
+        anyhow::bail!("{} of {} replayed rows no longer match their recorded outcome", changed, replayed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(cells: &[(&str, &str)]) -> SummaryRow {
+        cells.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_filters() {
+        let rows = vec![
+            row(&[("core", "fib"), ("equal", "true"), ("sp1_status", "Ok")]),
+            row(&[("core", "io_echo"), ("equal", "false"), ("sp1_status", "Panic")]),
+            row(&[("core", "fib"), ("equal", "false"), ("sp1_status", "Timeout")]),
+        ];
+        let filters = |specs: &[&str]| specs.iter().map(|spec| Filter::parse(spec).unwrap()).collect::<Vec<_>>();
+
+        assert_eq!(select(&rows, &filters(&["equal=false"])).unwrap().len(), 2);
+        let selected = select(&rows, &filters(&["equal=false", "core=fib"])).unwrap();
+        assert_eq!(selected, vec![&rows[2]]);
+        assert_eq!(select(&rows, &filters(&["sp1_status!=ok"])).unwrap().len(), 2);
+        assert_eq!(select(&rows, &[]).unwrap().len(), 3);
+
+        let error = select(&rows, &filters(&["status=Ok"])).unwrap_err().to_string();
+        assert!(error.contains("Unknown summary.csv column 'status'"), "{}", error);
+        assert!(Filter::parse("equal").is_err());
+        assert!(Filter::parse("=false").is_err());
+    }
+
+    #[test]
+    fn test_source_of() {
+        let dir = std::env::temp_dir().join(format!("harness-replay-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("input.json");
+        std::fs::write(&input, "{}").unwrap();
+        let gone = dir.join("gone.json");
+
+        let archived = row(&[("input", gone.to_str().unwrap()), ("repro_path", &format!("{}/", dir.display()))]);
+        assert_eq!(source_of(&archived), ReplaySource::Archived { path: dir.join("input.json") });
+
+        let mutated = row(&[
+            ("input", gone.to_str().unwrap()),
+            ("generator", "mutated"),
+            ("base_seed", "default"),
+            ("mutation_ops", "length_bias:size=1024"),
+            ("rng_seed", ""),
+        ]);
+        assert_eq!(
+            source_of(&mutated),
+            ReplaySource::Regenerate {
+                base_seed: "default".to_string(),
+                mutation_ops: "length_bias:size=1024".to_string(),
+                rng_seed: None,
+            }
+        );
+
+        let hand_written = row(&[("input", gone.to_str().unwrap()), ("generator", "hand_written")]);
+        assert!(matches!(source_of(&hand_written), ReplaySource::Unavailable { .. }));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,170 @@
+//! Versioned run logs
+//!
+//! Every `run_log.json` carries a `schema_version`. [`parse`] reads logs of
+//! any version the harness has written, upgrading older ones to the current
+//! [`RunLog`] shape, and then checks them strictly: a field the current
+//! schema does not know, in the log or in its run results, diff and
+//! environment, is an error instead of being dropped. Tools built on
+//! artifacts should read logs through this module, so a format change
+//! shows up as an upgrade step here rather than as silently missing data.
+//!
+//! | Version | Changes |
+//! |---|---|
+//! | 1 | No `schema_version`; the earliest logs named the core `seed_path` |
+//! | 2 | `schema_version` added |
+//! | 3 | Commits may be `{name, value}` objects (see `RunResult`) |
+//! | 4 | `diff.kind`: the category of an inequality (see `DiffKind`) |
+//! | 5 | `expectation`: the input's `expected` block and whether it held |
+//! | 6 | `environment.git_commit` / `git_dirty`: the source revision of the run |
+//! | 7 | Run results carry their own `schema_version` (see `RunResult::SCHEMA_VERSION`) |
+
+use crate::RunLog;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Version written by this harness
+pub const SCHEMA_VERSION: u32 = 7;
+
+/// Version of logs written before `schema_version` existed
+const UNVERSIONED: u32 = 1;
+
+/// Read a run log file of any known version
+pub fn load(path: &Path) -> Result<RunLog> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    parse(&bytes).with_context(|| format!("Invalid run log {}", path.display()))
+}
+
+/// Parse a run log of any known version, upgraded to [`SCHEMA_VERSION`]
+pub fn parse(bytes: &[u8]) -> Result<RunLog> {
+    let value: Value = serde_json::from_slice(bytes).context("Run log is not JSON")?;
+    from_value(upgrade(value)?)
+}
+
+/// Parse a run log that must already be at [`SCHEMA_VERSION`]
+pub fn parse_strict(bytes: &[u8]) -> Result<RunLog> {
+    let value: Value = serde_json::from_slice(bytes).context("Run log is not JSON")?;
+    let version = version_of(&value)?;
+    anyhow::ensure!(
+        version == SCHEMA_VERSION,
+        "Run log has schema version {}, expected {}",
+        version,
+        SCHEMA_VERSION
+    );
+    from_value(value)
+}
+
+/// `schema_version` of a log (1 if it has none)
+pub fn version_of(value: &Value) -> Result<u32> {
+    match value.get("schema_version") {
+        None => Ok(UNVERSIONED),
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .filter(|&version| version >= UNVERSIONED)
+            .with_context(|| format!("Invalid schema_version {}", version)),
+    }
+}
+
+/// Rewrite an older log into the current shape, one version at a time
+fn upgrade(mut value: Value) -> Result<Value> {
+    let mut version = version_of(&value)?;
+    anyhow::ensure!(
+        version <= SCHEMA_VERSION,
+        "Run log has schema version {}, newer than this harness supports ({})",
+        version,
+        SCHEMA_VERSION
+    );
+    let log = value.as_object_mut().context("Run log is not a JSON object")?;
+    while version < SCHEMA_VERSION {
+        if version == 1 {
+            if let Some(core_path) = log.remove("seed_path") {
+                log.entry("core_path").or_insert(core_path);
+            }
+        }
+        version += 1;
+        log.insert("schema_version".to_string(), Value::from(version));
+    }
+    Ok(value)
+}
+
+/// Deserialize a current-version log, rejecting unknown fields at any depth
+fn from_value(value: Value) -> Result<RunLog> {
+    let log: RunLog = serde_json::from_value(value.clone()).context("Run log does not match the schema")?;
+    let mut unknown = Vec::new();
+    unknown_fields(&value, &serde_json::to_value(&log)?, "", &mut unknown);
+    anyhow::ensure!(unknown.is_empty(), "Unknown run log fields: {}", unknown.join(", "));
+    Ok(log)
+}
+
+/// Keys of `original` that did not survive a parse and re-serialize
+///
+/// Null and empty values are skipped, since the writers omit those.
+fn unknown_fields(original: &Value, parsed: &Value, path: &str, unknown: &mut Vec<String>) {
+    match (original, parsed) {
+        (Value::Object(original), Value::Object(parsed)) => {
+            for (key, value) in original {
+                let field = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                match parsed.get(key) {
+                    Some(parsed) => unknown_fields(value, parsed, &field, unknown),
+                    None if value.is_null() || value.as_array().is_some_and(Vec::is_empty) => {}
+                    None => unknown.push(field),
+                }
+            }
+        }
+        (Value::Array(original), Value::Array(parsed)) => {
+            for (index, (original, parsed)) in original.iter().zip(parsed).enumerate() {
+                unknown_fields(original, parsed, &format!("{}[{}]", path, index), unknown);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v1_log() -> Value {
+        serde_json::json!({
+            "run_id": "20251021_040225_fib",
+            "timestamp": "2025-10-21T04:02:25Z",
+            "seed_path": "guest/cores/fib",
+            "input_path": "inputs/fib_24.json",
+            "native_result": { "status": "OK", "elapsed_ms": 2, "commits": [46368] },
+            "sp1_result": { "status": "OK", "elapsed_ms": 142, "commits": [46368], "meta": {} },
+            "diff": { "equal": true, "reason": null }
+        })
+    }
+
+    #[test]
+    fn test_parse_upgrades_v1() {
+        let log = parse(v1_log().to_string().as_bytes()).unwrap();
+        assert_eq!(log.schema_version, SCHEMA_VERSION);
+        assert_eq!(log.core_path, "guest/cores/fib");
+        assert!(log.environment.is_none());
+        assert!(parse_strict(v1_log().to_string().as_bytes()).is_err());
+
+        // Round trip at the current version
+        let current = serde_json::to_vec(&log).unwrap();
+        assert_eq!(parse_strict(&current).unwrap().run_id, log.run_id);
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_and_future() {
+        let mut log = v1_log();
+        log["sp1_result"]["cycles"] = serde_json::json!(1000);
+        let error = format!("{:#}", parse(log.to_string().as_bytes()).unwrap_err());
+        assert!(error.contains("sp1_result.cycles"), "{}", error);
+
+        let mut log = v1_log();
+        log["schema_version"] = serde_json::json!(SCHEMA_VERSION + 1);
+        let error = format!("{:#}", parse(log.to_string().as_bytes()).unwrap_err());
+        assert!(error.contains("newer than this harness"), "{}", error);
+
+        let mut log = v1_log();
+        log["schema_version"] = serde_json::json!("2");
+        assert!(parse(log.to_string().as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,268 @@
+//! SARIF export of divergences
+//!
+//! `harness export-sarif` writes the logged divergences as a SARIF 2.1.0
+//! log, the format GitHub code scanning and most security triage tools
+//! import. Each divergence signature becomes one result, reported through
+//! its most severe run, with the signature as its fingerprint so a tool
+//! tracks it across exports. Rules are the divergence kinds.
+//!
+//! Results point into the core's source ([`locate`]): the line a panic
+//! names (`meta.panic_site`, else the panic message, read by
+//! [`crate::symbolicate::panic_location`]), else the declaration of the output field whose commit differed,
+//! else the core's `src/lib.rs`.
+
+use crate::severity::{Level, Severity};
+use crate::symbolicate::{panic_location, PanicSite};
+use crate::RunLog;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+/// Rules: divergence kind, name and description
+const RULES: &[(&str, &str, &str)] = &[
+    ("status_mismatch", "StatusMismatch", "The native and zkVM runs ended with inconsistent statuses"),
+    ("commit_value_mismatch", "CommitValueMismatch", "Both runs finished but committed different values"),
+    ("commit_length_mismatch", "CommitLengthMismatch", "One run committed more values than the other"),
+    ("prefix_mismatch", "PrefixMismatch", "Both runs stopped early with disagreeing commit streams"),
+    ("relation_violated", "RelationViolated", "A relation declared for the core did not hold"),
+    ("divergence", "Divergence", "The runs differ (logged before divergence kinds)"),
+];
+
+/// One divergence signature to report
+pub struct Finding<'a> {
+    pub signature: &'a str,
+    /// Runs sharing the signature
+    pub runs: usize,
+    /// The run reported (the signature's most severe)
+    pub log: &'a RunLog,
+    pub severity: &'a Severity,
+    /// The run's folder under `artifacts/`
+    pub run_dir: Option<&'a Path>,
+}
+
+/// A place in a core's source, relative to the repository root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub uri: String,
+    pub line: Option<u32>,
+}
+
+/// Where in its core's source a divergence points (see the module docs)
+///
+/// `root` is the repository root, searched for the field declarations.
+pub fn locate(root: &Path, log: &RunLog) -> SourceLocation {
+    let core_name = Path::new(&log.core_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let core_dir = format!("guest/cores/{}", core_name);
+
+    let results = [&log.native_result, &log.sp1_result];
+    let annotated = results
+        .iter()
+        .filter_map(|result| serde_json::from_value::<PanicSite>(result.meta.get("panic_site")?.clone()).ok());
+    let parsed = results
+        .iter()
+        .flat_map(|result| ["panic_msg", "stderr"].map(|key| result.meta.get(key).and_then(Value::as_str)))
+        .flatten()
+        .filter_map(panic_location);
+    if let Some(location) = annotated.chain(parsed).find_map(|site| in_core(&site, &core_name)) {
+        return location;
+    }
+
+    let field = match &log.diff.kind {
+        Some(rust_eq_oracle::DiffKind::CommitValueMismatch { name, .. } | rust_eq_oracle::DiffKind::PrefixMismatch { name, .. }) => {
+            name.as_deref()
+        }
+        _ => None,
+    };
+    if let Some(location) = field.and_then(|field| field_declaration(root, &core_dir, field)) {
+        return location;
+    }
+
+    SourceLocation {
+        uri: format!("{}/src/lib.rs", core_dir),
+        line: None,
+    }
+}
+
+/// A panic site as a repository path, if it is in the core's source
+fn in_core(site: &PanicSite, core_name: &str) -> Option<SourceLocation> {
+    let marker = format!("cores/{}/", core_name);
+    let start = site.file.find(&marker)?;
+    Some(SourceLocation {
+        uri: format!("guest/{}", &site.file[start..]),
+        line: Some(site.line),
+    })
+}
+
+/// Declaration of output field `field` (e.g. `first_byte`, or `items[2]`
+/// for a field committed once per element) in the core's sources
+fn field_declaration(root: &Path, core_dir: &str, field: &str) -> Option<SourceLocation> {
+    let field = field.split('[').next()?;
+    let mut sources: Vec<_> = fs::read_dir(root.join(core_dir).join("src"))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    sources.sort();
+    sources.into_iter().find_map(|path| {
+        let text = fs::read_to_string(&path).ok()?;
+        let index = text.lines().position(|line| {
+            let line = line.trim_start();
+            let line = line.strip_prefix("pub ").unwrap_or(line);
+            line.strip_prefix(field).is_some_and(|rest| rest.trim_start().starts_with(':'))
+        })?;
+        Some(SourceLocation {
+            uri: format!("{}/src/{}", core_dir, path.file_name()?.to_string_lossy()),
+            line: Some(index as u32 + 1),
+        })
+    })
+}
+
+fn sarif_level(level: Level) -> &'static str {
+    match level {
+        Level::Critical | Level::High => "error",
+        Level::Medium => "warning",
+        Level::Low => "note",
+    }
+}
+
+fn result(root: &Path, finding: &Finding) -> Value {
+    let log = finding.log;
+    let rule = log.diff.kind.as_ref().map(|kind| kind.as_str()).unwrap_or("divergence");
+    let location = locate(root, log);
+    let mut physical = json!({ "artifactLocation": { "uri": location.uri, "uriBaseId": "%SRCROOT%" } });
+    if let Some(line) = location.line {
+        physical["region"] = json!({ "startLine": line });
+    }
+    json!({
+        "ruleId": rule,
+        "level": sarif_level(finding.severity.level),
+        "message": {
+            "text": format!(
+                "{} (native={:?}, zkvm={:?}); {} runs, e.g. {}",
+                log.diff.reason.as_deref().unwrap_or("results differ"),
+                log.native_result.status,
+                log.sp1_result.status,
+                finding.runs,
+                log.run_id
+            )
+        },
+        "locations": [{ "physicalLocation": physical }],
+        "partialFingerprints": { "divergenceSignature/v1": finding.signature },
+        "properties": {
+            "run_id": log.run_id,
+            "runs": finding.runs,
+            "input": log.input_path,
+            "repro_dir": finding.run_dir.map(|dir| dir.display().to_string()),
+            "severity": finding.severity.score,
+            "severity_factors": finding.severity.factors,
+        },
+    })
+}
+
+/// The SARIF log of `findings`
+pub fn report(root: &Path, findings: &[Finding]) -> Value {
+    let rules: Vec<Value> = RULES
+        .iter()
+        .map(|(id, name, description)| {
+            json!({
+                "id": id,
+                "name": name,
+                "shortDescription": { "text": description },
+                "helpUri": "https://github.com/ivanvgreiff/zk-fuzz-lab/blob/main/oracles/rust_eq/README.md",
+            })
+        })
+        .collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "zk-fuzz-lab",
+                    "informationUri": "https://github.com/ivanvgreiff/zk-fuzz-lab",
+                    "rules": rules,
+                }
+            },
+            "results": findings.iter().map(|finding| result(root, finding)).collect::<Vec<_>>(),
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root() -> std::path::PathBuf {
+        crate::find_repo_root(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap()
+    }
+
+    #[test]
+    fn test_in_core() {
+        let text = "thread 'main' panicked at /home/ci/zk-fuzz-lab/guest/cores/panic_test/src/lib.rs:42:9:\nboom";
+        assert_eq!(
+            in_core(&panic_location(text).unwrap(), "panic_test"),
+            Some(SourceLocation {
+                uri: "guest/cores/panic_test/src/lib.rs".to_string(),
+                line: Some(42),
+            })
+        );
+        // Panics outside the core (e.g. in std) are not the core's location
+        let std = panic_location("panicked at library/core/src/num/mod.rs:10:5").unwrap();
+        assert_eq!(in_core(&std, "panic_test"), None);
+    }
+
+    #[test]
+    fn test_locate_panic_site() {
+        let result = |status, meta| rust_eq_oracle::RunResult {
+            status,
+            elapsed_ms: 0,
+            commits: Vec::new(),
+            meta,
+        };
+        let native = result(rust_eq_oracle::Status::Ok, json!({}));
+        // Symbolicated from the fault registers: no panic message to parse
+        let sp1 = result(
+            rust_eq_oracle::Status::Panic,
+            json!({
+                "panic_msg": "HaltWithNonZeroExitCode(1)",
+                "panic_site": { "file": "/work/guest/cores/panic_test/src/lib.rs", "line": 17, "column": null, "source": "backtrace" },
+            }),
+        );
+        let log = RunLog {
+            schema_version: crate::run_log::SCHEMA_VERSION,
+            run_id: "panic_test_1".to_string(),
+            timestamp: String::new(),
+            core_path: "guest/cores/panic_test".to_string(),
+            input_path: "inputs/panic_test.json".to_string(),
+            diff: rust_eq_oracle::compare(&native, &sp1),
+            native_result: native,
+            sp1_result: sp1,
+            environment: None,
+            checked_native: None,
+            campaign: None,
+            expectation: None,
+        };
+        assert_eq!(
+            locate(&root(), &log),
+            SourceLocation {
+                uri: "guest/cores/panic_test/src/lib.rs".to_string(),
+                line: Some(17),
+            }
+        );
+    }
+
+    #[test]
+    fn test_field_declaration() {
+        let location = field_declaration(&root(), "guest/cores/io_echo", "first_byte").unwrap();
+        assert_eq!(location.uri, "guest/cores/io_echo/src/lib.rs");
+        let line = location.line.unwrap() as usize;
+        let source = fs::read_to_string(root().join(&location.uri)).unwrap();
+        assert!(source.lines().nth(line - 1).unwrap().contains("pub first_byte:"));
+        assert_eq!(field_declaration(&root(), "guest/cores/io_echo", "first_byte[0]"), Some(location));
+        assert_eq!(field_declaration(&root(), "guest/cores/io_echo", "no_such_field"), None);
+    }
+}
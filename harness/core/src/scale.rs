@@ -0,0 +1,402 @@
+//! Input-size scaling probes
+//!
+//! `harness scale` runs one core on a geometric sweep of input sizes and
+//! fits how its cost grows. Each input is the core's base input with one
+//! byte-array field written compactly (`{"size": N}`, expanded by the
+//! runners, see `runners/preprocess`), so a 16 MiB probe costs a few bytes
+//! on disk. [`ScaleReport::fit`] gives the growth exponent `k` of
+//! `cost ~ size^k` overall and between neighbouring sizes; `k` well above
+//! 1 for cycles is a superlinear guest cost, the kind an attacker can buy
+//! proving time with. Unlike [`crate::cost_model`], which fits whatever
+//! sizes campaigns happened to run, the sweep controls the sizes.
+
+use crate::cost_model::{log_log_slope, SUPERLINEAR_MARGIN};
+use crate::properties::PropertyOptions;
+use crate::{
+    base_input_for_core, build_failure_result, build_sp1_guest, elf_path_for_core, guest_path_for_core, load_input,
+    new_run_id, parse_cores_arg, run_native_runner_sandboxed, run_sp1_runner, write_input, RetryPolicy,
+};
+use anyhow::{Context, Result};
+use rust_eq_oracle::{RunResult, Status};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+/// Largest number of sizes one sweep may run
+pub const MAX_POINTS: usize = 64;
+
+/// Parse a byte size such as `4096`, `1k` or `16m` (powers of 1024)
+pub fn parse_size(text: &str) -> Result<u64> {
+    let lower = text.trim().to_ascii_lowercase();
+    let (digits, multiplier) = match lower.chars().last() {
+        Some('k') => (&lower[..lower.len() - 1], 1u64 << 10),
+        Some('m') => (&lower[..lower.len() - 1], 1 << 20),
+        Some('g') => (&lower[..lower.len() - 1], 1 << 30),
+        _ => (lower.as_str(), 1),
+    };
+    let value: u64 = digits
+        .parse()
+        .with_context(|| format!("Invalid size '{}' (expected e.g. 4096, 1k, 16m)", text))?;
+    value
+        .checked_mul(multiplier)
+        .with_context(|| format!("Size '{}' is too large", text))
+}
+
+/// Sizes `from, from·factor, ...` up to and including `to`
+pub fn sweep(from: u64, to: u64, factor: f64) -> Result<Vec<u64>> {
+    anyhow::ensure!(factor > 1.0, "--factor must be greater than 1");
+    anyhow::ensure!(from > 0 && from <= to, "--from must be positive and at most --to");
+    let mut sizes = vec![from];
+    loop {
+        let last = *sizes.last().unwrap();
+        // Round up so small factors still make progress
+        let next = ((last as f64 * factor).ceil() as u64).max(last + 1);
+        if next > to {
+            break;
+        }
+        sizes.push(next);
+        anyhow::ensure!(sizes.len() <= MAX_POINTS, "Sweep has more than {} sizes (raise --factor)", MAX_POINTS);
+    }
+    Ok(sizes)
+}
+
+/// The base input with `field` written as `{"size": size}`
+pub fn input_for_size(base: &Value, field: &str, size: u64) -> Result<Value> {
+    let mut input = base.clone();
+    input
+        .as_object_mut()
+        .context("Base input is not a JSON object")?
+        .insert(field.to_string(), serde_json::json!({ "size": size }));
+    Ok(input)
+}
+
+/// Both runs at one size
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScalePoint {
+    pub size: u64,
+    pub native_status: Status,
+    pub sp1_status: Status,
+    pub native_ms: u128,
+    pub sp1_ms: u128,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sp1_cycles: Option<u64>,
+}
+
+impl ScalePoint {
+    pub fn new(size: u64, native: &RunResult, sp1: &RunResult) -> ScalePoint {
+        ScalePoint {
+            size,
+            native_status: native.status,
+            sp1_status: sp1.status,
+            native_ms: native.elapsed_ms,
+            sp1_ms: sp1.elapsed_ms,
+            sp1_cycles: sp1.meta["cycles"].as_u64(),
+        }
+    }
+
+    /// Cycles per input byte (None without cycles)
+    pub fn cycles_per_byte(&self) -> Option<f64> {
+        self.sp1_cycles.map(|cycles| cycles as f64 / self.size as f64)
+    }
+}
+
+/// Growth exponents fitted over a sweep
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScaleFit {
+    /// Points where both sides finished OK (only these are fitted)
+    pub points: usize,
+    /// `k` of SP1 cycles ~ size^k
+    pub cycles_exponent: f64,
+    /// `k` of SP1 execution time ~ size^k
+    pub sp1_ms_exponent: f64,
+    /// `k` of native time ~ size^k
+    pub native_ms_exponent: f64,
+    /// Cycle exponent between each pair of neighbouring fitted sizes, to
+    /// show where growth takes off: `(from size, to size, k)`
+    pub segments: Vec<(u64, u64, f64)>,
+}
+
+impl ScaleFit {
+    /// Cycles grow faster than linearly, by more than the cost model's margin
+    pub fn is_superlinear(&self) -> bool {
+        self.cycles_exponent > 1.0 + SUPERLINEAR_MARGIN
+    }
+}
+
+/// A sweep of one core
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScaleReport {
+    pub core: String,
+    /// The byte-array field that was sized
+    pub field: String,
+    pub points: Vec<ScalePoint>,
+    pub fit: ScaleFit,
+}
+
+impl ScaleReport {
+    pub fn new(core: &str, field: &str, points: Vec<ScalePoint>) -> ScaleReport {
+        let fit = ScaleReport::fit(&points);
+        ScaleReport {
+            core: core.to_string(),
+            field: field.to_string(),
+            points,
+            fit,
+        }
+    }
+
+    /// Fit the points where both sides finished OK and SP1 reported cycles
+    pub fn fit(points: &[ScalePoint]) -> ScaleFit {
+        let ok: Vec<&ScalePoint> = points
+            .iter()
+            .filter(|p| p.native_status == Status::Ok && p.sp1_status == Status::Ok && p.sp1_cycles.is_some())
+            .collect();
+        let column = |f: fn(&ScalePoint) -> f64| ok.iter().map(|p| f(p)).collect::<Vec<f64>>();
+        let sizes = column(|p| p.size as f64);
+        let cycles = column(|p| p.sp1_cycles.unwrap() as f64);
+        ScaleFit {
+            points: ok.len(),
+            cycles_exponent: log_log_slope(&sizes, &cycles),
+            sp1_ms_exponent: log_log_slope(&sizes, &column(|p| p.sp1_ms as f64)),
+            native_ms_exponent: log_log_slope(&sizes, &column(|p| p.native_ms as f64)),
+            segments: ok
+                .windows(2)
+                .zip(sizes.windows(2).zip(cycles.windows(2)))
+                .map(|(pair, (xs, ys))| (pair[0].size, pair[1].size, log_log_slope(xs, ys)))
+                .collect(),
+        }
+    }
+
+    /// Log-log SVG plot of cycles per byte against input size
+    pub fn to_svg(&self) -> String {
+        const WIDTH: f64 = 640.0;
+        const HEIGHT: f64 = 360.0;
+        const MARGIN: f64 = 60.0;
+        let points: Vec<(f64, f64)> = self
+            .points
+            .iter()
+            .filter_map(|p| Some(((p.size as f64).log2(), p.cycles_per_byte()?.max(f64::MIN_POSITIVE).log2())))
+            .collect();
+        let range = |values: Vec<f64>| {
+            let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            if max > min { (min, max) } else { (min - 1.0, min + 1.0) }
+        };
+        let (x_min, x_max) = range(points.iter().map(|p| p.0).collect());
+        let (y_min, y_max) = range(points.iter().map(|p| p.1).collect());
+        let x = |v: f64| MARGIN + (v - x_min) / (x_max - x_min) * (WIDTH - 2.0 * MARGIN);
+        let y = |v: f64| HEIGHT - MARGIN - (v - y_min) / (y_max - y_min) * (HEIGHT - 2.0 * MARGIN);
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12">"#,
+            w = WIDTH,
+            h = HEIGHT
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="20" text-anchor="middle">{}: cycles per byte of `{}` (k = {:.2})</text>"#,
+            WIDTH / 2.0,
+            self.core,
+            self.field,
+            self.fit.cycles_exponent
+        );
+        let _ = writeln!(
+            svg,
+            r#"<path d="M{m} {m} V{b} H{r}" fill="none" stroke="black"/>"#,
+            m = MARGIN,
+            b = HEIGHT - MARGIN,
+            r = WIDTH - MARGIN
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="middle">input bytes (log scale)</text>"#,
+            WIDTH / 2.0,
+            HEIGHT - 15.0
+        );
+        if !points.is_empty() {
+            let path: Vec<String> = points.iter().map(|&(px, py)| format!("{:.1},{:.1}", x(px), y(py))).collect();
+            let _ = writeln!(svg, r#"<polyline points="{}" fill="none" stroke="steelblue"/>"#, path.join(" "));
+        }
+        for (point, &(px, py)) in self.points.iter().filter(|p| p.sp1_cycles.is_some()).zip(&points) {
+            let _ = writeln!(
+                svg,
+                r#"<circle cx="{:.1}" cy="{:.1}" r="3" fill="steelblue"><title>{} bytes: {} cycles</title></circle>"#,
+                x(px),
+                y(py),
+                point.size,
+                point.sp1_cycles.unwrap_or_default()
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Run a core on each input size of a sweep, on native and SP1, and fit how
+/// its cost grows with size
+///
+/// Inputs, `report.json` (every size and the fitted exponents) and
+/// `scale.svg` (cycles per byte against size, log-log) are written to
+/// `artifacts/scale/<run id>/`.
+pub fn scale_core(
+    core_arg: &str,
+    sizes: Vec<u64>,
+    field: Option<String>,
+    options: PropertyOptions,
+    retry: &RetryPolicy,
+) -> Result<()> {
+    let core_name = *parse_cores_arg(core_arg)?
+        .first()
+        .filter(|_| !core_arg.contains(',') && core_arg != "all")
+        .context("--core takes a single core")?;
+    let field = match field {
+        Some(field) => field,
+        None => crate::input_preprocess(core_name)?
+            .byte_fields()
+            .next()
+            .map(str::to_string)
+            .with_context(|| {
+                format!("Core '{}' declares no `bytes:<field>` preprocessing, so its inputs cannot be sized", core_name)
+            })?,
+    };
+    if options.skip_build {
+        crate::provenance::ensure_fresh([core_name], options.allow_stale_elf)?;
+    }
+
+    let out_dir = PathBuf::from("artifacts/scale").join(new_run_id(core_name));
+    fs::create_dir_all(&out_dir)?;
+    println!(
+        "📈 Scaling {} over `{}`: {} sizes, {} to {} bytes",
+        core_name,
+        field,
+        sizes.len(),
+        sizes[0],
+        sizes[sizes.len() - 1]
+    );
+
+    let elf_path = elf_path_for_core(core_name);
+    let mut build_error = None;
+    if !options.skip_build {
+        if let (Err(e), retries) = retry.run("SP1 guest build", || build_sp1_guest(&guest_path_for_core(core_name))) {
+            println!("   ❌ SP1 guest build failed: {:#}", e);
+            build_error = Some((e, retries));
+        }
+    }
+
+    let base_path = base_input_for_core(core_name)?;
+    let base = load_input(core_name, &base_path)?;
+    let mut points = Vec::new();
+    for size in sizes {
+        let input_path = out_dir.join(format!("size_{}.json", size));
+        write_input(core_name, &input_for_size(&base, &field, size)?, &input_path)?;
+
+        let native = retry.run_runner("native", || run_native_runner_sandboxed(core_name, &input_path, options.sandbox));
+        let sp1 = match &build_error {
+            Some((e, retries)) => build_failure_result(e, *retries),
+            None => retry.run_runner("sp1", || run_sp1_runner(&elf_path, &input_path, core_name)),
+        };
+        let point = ScalePoint::new(size, &native, &sp1);
+        println!(
+            "   {:>10} bytes | Native: {:?} ({}ms) | SP1: {:?} ({}ms, {} cycles{})",
+            size,
+            point.native_status,
+            point.native_ms,
+            point.sp1_status,
+            point.sp1_ms,
+            point.sp1_cycles.map(|c| c.to_string()).unwrap_or_else(|| "?".to_string()),
+            point.cycles_per_byte().map(|c| format!(", {:.1}/byte", c)).unwrap_or_default()
+        );
+        points.push(point);
+    }
+
+    let report = ScaleReport::new(core_name, &field, points);
+    let report_path = out_dir.join("report.json");
+    fs::write(&report_path, serde_json::to_string_pretty(&report)?)?;
+    fs::write(out_dir.join("scale.svg"), report.to_svg())?;
+
+    let fit = &report.fit;
+    println!();
+    if fit.points < 2 {
+        println!("📊 Too few sizes ran OK on both sides to fit ({})", fit.points);
+    } else {
+        println!(
+            "📊 Growth exponents over {} sizes (cost ~ size^k): cycles {:.2}, SP1 time {:.2}, native time {:.2}",
+            fit.points, fit.cycles_exponent, fit.sp1_ms_exponent, fit.native_ms_exponent
+        );
+        for (from, to, k) in &fit.segments {
+            println!("   {:>10} → {:>10} bytes: k = {:.2}", from, to, k);
+        }
+        if fit.is_superlinear() {
+            println!("   ⚠️  Cycles grow superlinearly with input size");
+        } else {
+            println!("   ✅ Cycles grow at most linearly");
+        }
+    }
+    println!("💾 Report saved to {}", report_path.display());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn point(size: u64, cycles: u64) -> ScalePoint {
+        ScalePoint {
+            size,
+            native_status: Status::Ok,
+            sp1_status: Status::Ok,
+            native_ms: size as u128,
+            sp1_ms: cycles as u128,
+            sp1_cycles: Some(cycles),
+        }
+    }
+
+    #[test]
+    fn test_parse_size_and_sweep() {
+        assert_eq!(parse_size("4096").unwrap(), 4096);
+        assert_eq!(parse_size("1k").unwrap(), 1024);
+        assert_eq!(parse_size("16M").unwrap(), 16 << 20);
+        assert!(parse_size("1t").is_err());
+        assert!(parse_size("k").is_err());
+
+        assert_eq!(sweep(1024, 8192, 2.0).unwrap(), vec![1024, 2048, 4096, 8192]);
+        assert_eq!(sweep(1, 3, 1.1).unwrap(), vec![1, 2, 3]);
+        assert!(sweep(1, 8, 1.0).is_err());
+        assert!(sweep(8, 1, 2.0).is_err());
+        assert!(sweep(1, u64::MAX, 1.01).is_err());
+    }
+
+    #[test]
+    fn test_input_for_size() {
+        let base = json!({ "data": [1, 2, 3], "mode": 1 });
+        assert_eq!(input_for_size(&base, "data", 64).unwrap(), json!({ "data": { "size": 64 }, "mode": 1 }));
+        assert!(input_for_size(&json!([1]), "data", 64).is_err());
+    }
+
+    #[test]
+    fn test_fit_finds_superlinear_growth() {
+        // Linear up to 4k, then quadratic
+        let points = vec![point(1024, 1000), point(2048, 2000), point(4096, 4000), point(8192, 16000)];
+        let fit = ScaleReport::fit(&points);
+        assert_eq!(fit.points, 4);
+        assert!((fit.segments[0].2 - 1.0).abs() < 1e-9);
+        assert_eq!((fit.segments[2].0, fit.segments[2].1), (4096, 8192));
+        assert!((fit.segments[2].2 - 2.0).abs() < 1e-9);
+        assert!(fit.cycles_exponent > 1.0 && !ScaleReport::fit(&points[..3]).is_superlinear());
+
+        let quadratic: Vec<ScalePoint> = [1u64, 2, 4, 8].iter().map(|&k| point(k << 10, k * k * 1000)).collect();
+        assert!(ScaleReport::fit(&quadratic).is_superlinear());
+
+        // Failed sizes are left out of the fit
+        let mut failed = point(16384, 0);
+        failed.sp1_status = Status::Timeout;
+        assert_eq!(ScaleReport::fit(&[quadratic.clone(), vec![failed]].concat()).points, 4);
+        let svg = ScaleReport::new("io_echo", "data", quadratic).to_svg();
+        assert_eq!(svg.matches("<circle").count(), 4);
+    }
+}
@@ -0,0 +1,229 @@
+//! Divergence severity
+//!
+//! A heuristic score from 0 to 100 for deciding which findings to take
+//! upstream first. The kind of divergence sets the base score:
+//!
+//! | Divergence | Base |
+//! |---|---|
+//! | Status mismatch on an input native runs `OK` | 60 |
+//! | Other status mismatch (native fails, the zkVM does something else) | 50 |
+//! | Commit mismatch (value, length or prefix) | 40 |
+//! | Declared relation violated | 30 |
+//! | Timeout, cycle limit or OOM on one side only | 20 |
+//!
+//! Small inputs add a bonus, since they make short upstream reports: +20 up
+//! to 64 bytes, +10 up to 1 KiB and +5 up to 64 KiB. A divergence signature
+//! ([`crate::notify::signature`]) scores as its highest-scoring run, which
+//! is usually its smallest input.
+
+use crate::cost_model::RunRecord;
+use crate::RunLog;
+use rust_eq_oracle::{DiffKind, Status};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// Input size bonuses: (up to this many bytes, bonus, label)
+const SIZE_BONUSES: [(u64, u32, &str); 3] = [(64, 20, "minimal"), (1024, 10, "small"), (64 * 1024, 5, "compact")];
+
+/// Coarse bucket of a score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Level {
+    fn of(score: u32) -> Level {
+        match score {
+            70.. => Level::Critical,
+            50..=69 => Level::High,
+            30..=49 => Level::Medium,
+            _ => Level::Low,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Low => "low",
+            Level::Medium => "medium",
+            Level::High => "high",
+            Level::Critical => "critical",
+        }
+    }
+}
+
+/// A divergence's score and what it is made of
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Severity {
+    pub score: u32,
+    pub level: Level,
+    /// Why, e.g. `status mismatch on an OK input (+60)`
+    pub factors: Vec<String>,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.level.as_str(), self.score, self.factors.join(", "))
+    }
+}
+
+fn is_resource_limit(status: Status) -> bool {
+    matches!(status, Status::Timeout | Status::CycleLimit | Status::Oom)
+}
+
+/// Score a divergence (None for runs that agree or were not compared)
+///
+/// `kind` may be missing for records logged before divergence kinds; the
+/// statuses then decide between a status and a commit mismatch.
+pub fn assess(native: Status, zkvm: Status, equal: bool, kind: Option<&DiffKind>, input_bytes: u64) -> Option<Severity> {
+    if equal || native.is_infra() || zkvm.is_infra() {
+        return None;
+    }
+    let status_mismatch = || {
+        if is_resource_limit(native) != is_resource_limit(zkvm) {
+            (20, "timeout, cycle limit or OOM on one side only")
+        } else if native == Status::Ok {
+            (60, "status mismatch on an OK input")
+        } else {
+            (50, "status mismatch")
+        }
+    };
+    let (base, what) = match kind {
+        Some(DiffKind::NotCompared) => return None,
+        Some(DiffKind::StatusMismatch { .. }) => status_mismatch(),
+        Some(DiffKind::CommitLengthMismatch { .. } | DiffKind::CommitValueMismatch { .. } | DiffKind::PrefixMismatch { .. }) => {
+            (40, "commit mismatch")
+        }
+        Some(DiffKind::PanicMessageMismatch { .. }) => (30, "panic message mismatch"),
+        Some(DiffKind::RelationViolated { .. }) => (30, "relation violated"),
+        None if native != zkvm => status_mismatch(),
+        None => (40, "commit mismatch"),
+    };
+
+    let mut score = base;
+    let mut factors = vec![format!("{} (+{})", what, base)];
+    if let Some((_, bonus, label)) = SIZE_BONUSES.iter().find(|(limit, _, _)| input_bytes <= *limit) {
+        score += bonus;
+        factors.push(format!("{} input, {} bytes (+{})", label, input_bytes, bonus));
+    }
+    Some(Severity {
+        score,
+        level: Level::of(score),
+        factors,
+    })
+}
+
+/// Score a logged run (`runs.jsonl`)
+pub fn of_record(record: &RunRecord) -> Option<Severity> {
+    assess(
+        record.native_status,
+        record.sp1_status,
+        record.equal,
+        record.diff_kind.as_ref(),
+        record.input_bytes,
+    )
+}
+
+/// Score a run log
+///
+/// The input is sized from the copy in the run's folder, or the logged
+/// input path if the folder has none.
+pub fn of_log(log: &RunLog, run_dir: Option<&Path>) -> Option<Severity> {
+    let core_name = Path::new(&log.core_path).file_name()?.to_str()?;
+    let input_path = run_dir
+        .map(|dir| dir.join("input.json"))
+        .filter(|path| path.is_file())
+        .unwrap_or_else(|| log.input_path.clone().into());
+    assess(
+        log.native_result.status,
+        log.sp1_result.status,
+        log.diff.equal,
+        log.diff.kind.as_ref(),
+        crate::cost_model::input_bytes(core_name, &input_path),
+    )
+}
+
+/// A divergence signature with its score
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RankedSignature {
+    pub signature: String,
+    /// The highest score among the signature's runs
+    pub severity: Severity,
+    /// Run with that score
+    pub run_id: String,
+    pub runs: usize,
+}
+
+/// Group scored runs (signature, run ID, severity) by signature, most
+/// severe first
+pub fn rank(findings: impl IntoIterator<Item = (String, String, Severity)>) -> Vec<RankedSignature> {
+    let mut by_signature: BTreeMap<String, RankedSignature> = BTreeMap::new();
+    for (signature, run_id, severity) in findings {
+        let ranked = by_signature.entry(signature.clone()).or_insert_with(|| RankedSignature {
+            signature,
+            severity: severity.clone(),
+            run_id: run_id.clone(),
+            runs: 0,
+        });
+        ranked.runs += 1;
+        if severity.score > ranked.severity.score {
+            ranked.severity = severity;
+            ranked.run_id = run_id;
+        }
+    }
+    let mut ranked: Vec<RankedSignature> = by_signature.into_values().collect();
+    ranked.sort_by(|a, b| b.severity.score.cmp(&a.severity.score).then_with(|| a.signature.cmp(&b.signature)));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(native: Status, zkvm: Status, kind: Option<DiffKind>, input_bytes: u64) -> Option<u32> {
+        assess(native, zkvm, false, kind.as_ref(), input_bytes).map(|severity| severity.score)
+    }
+
+    #[test]
+    fn test_ordering_of_kinds() {
+        let status = |native, zkvm| Some(DiffKind::StatusMismatch { native, zkvm });
+        let large = 1 << 20;
+        let ok_input = score(Status::Ok, Status::Panic, status(Status::Ok, Status::Panic), large).unwrap();
+        let commits = score(Status::Ok, Status::Ok, Some(DiffKind::CommitValueMismatch { index: 0, name: None }), large);
+        let timeout = score(Status::Ok, Status::Timeout, status(Status::Ok, Status::Timeout), large).unwrap();
+        assert!(ok_input > commits.unwrap() && commits.unwrap() > timeout);
+        assert_eq!(score(Status::Panic, Status::Ok, status(Status::Panic, Status::Ok), large), Some(50));
+        // Older records without a kind
+        assert_eq!(score(Status::Ok, Status::Panic, None, large), Some(ok_input));
+        assert_eq!(score(Status::Ok, Status::Ok, None, large), commits);
+
+        assert_eq!(score(Status::Ok, Status::InfraError, status(Status::Ok, Status::InfraError), large), None);
+        assert_eq!(score(Status::Ok, Status::Ok, Some(DiffKind::NotCompared), large), None);
+        assert_eq!(assess(Status::Ok, Status::Ok, true, None, 10), None);
+    }
+
+    #[test]
+    fn test_small_inputs_and_ranking() {
+        let minimal = assess(Status::Ok, Status::Ok, false, None, 12).unwrap();
+        assert_eq!((minimal.score, minimal.level), (60, Level::High));
+        assert_eq!(minimal.to_string(), "high (60): commit mismatch (+40), minimal input, 12 bytes (+20)");
+        let small = assess(Status::Ok, Status::Ok, false, None, 1000).unwrap();
+        assert_eq!(small.score, 50);
+        let critical = assess(Status::Ok, Status::Panic, false, None, 40).unwrap();
+        assert_eq!(critical.level, Level::Critical);
+
+        let ranked = rank([
+            ("fib|OK|OK|commit".to_string(), "r1".to_string(), small.clone()),
+            ("fib|OK|PANIC|status".to_string(), "r2".to_string(), critical.clone()),
+            ("fib|OK|OK|commit".to_string(), "r3".to_string(), minimal.clone()),
+        ]);
+        assert_eq!(ranked.len(), 2);
+        assert_eq!((ranked[0].run_id.as_str(), ranked[0].runs), ("r2", 1));
+        assert_eq!((ranked[1].run_id.as_str(), ranked[1].runs, ranked[1].severity.score), ("r3", 2, 60));
+    }
+}
@@ -0,0 +1,253 @@
+//! Environment drift checks for `harness soak`
+//!
+//! A multi-day campaign assumes the toolchain, the checkout and the guest
+//! ELFs stay what they were when it started: `summary.csv` rows record the
+//! process's environment snapshot once ([`crate::environment::current`]),
+//! and a divergence found after someone upgraded SP1 or rebuilt a guest
+//! under the campaign would be misattributed. `harness soak` takes a
+//! [`Fingerprint`] before the first round and again every check interval:
+//!
+//! - a fresh environment snapshot (SP1, rustc, target, git commit and dirty)
+//! - the SHA-256 of each core's guest ELF
+//! - a canary input run on native and SP1 (statuses and commits)
+//!
+//! Any difference from the baseline is a [`Drift`]. Every check is kept in
+//! `artifacts/soak/<soak id>/report.json`.
+
+use crate::environment::Snapshot;
+use anyhow::{Context, Result};
+use rust_eq_oracle::{RunResult, Status};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where soak reports are written
+pub const SOAK_DIR: &str = "artifacts/soak";
+
+/// What the canary input produced on one target
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CanaryResult {
+    pub status: Status,
+    pub commits: Vec<serde_json::Value>,
+}
+
+impl From<&RunResult> for CanaryResult {
+    fn from(result: &RunResult) -> Self {
+        CanaryResult {
+            status: result.status,
+            commits: result.commits.clone(),
+        }
+    }
+}
+
+/// The canary run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Canary {
+    pub core: String,
+    pub input: String,
+    pub native: CanaryResult,
+    pub sp1: CanaryResult,
+}
+
+/// Everything a soak check compares
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    pub environment: Snapshot,
+    /// Core → SHA-256 of its guest ELF (None if the ELF is missing)
+    pub elfs: BTreeMap<String, Option<String>>,
+    pub canary: Canary,
+}
+
+/// One thing that changed since the baseline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Drift {
+    /// What changed, e.g. `sp1_version` or `elf[fib]`
+    pub what: String,
+    pub expected: String,
+    pub found: String,
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} → {}", self.what, self.expected, self.found)
+    }
+}
+
+fn describe<T: fmt::Debug>(value: &Option<T>) -> String {
+    match value {
+        Some(value) => format!("{:?}", value).trim_matches('"').to_string(),
+        None => "none".to_string(),
+    }
+}
+
+/// How the canary's commits on one target changed
+fn commits_drift(expected: &[serde_json::Value], found: &[serde_json::Value]) -> Option<(String, String)> {
+    if expected == found {
+        return None;
+    }
+    match expected.iter().zip(found).position(|(a, b)| a != b) {
+        Some(index) => Some((
+            format!("commit {} = {}", index, expected[index]),
+            format!("commit {} = {}", index, found[index]),
+        )),
+        None => Some((format!("{} commits", expected.len()), format!("{} commits", found.len()))),
+    }
+}
+
+impl Fingerprint {
+    /// What differs in `now` from this baseline (empty if nothing drifted)
+    pub fn drift(&self, now: &Fingerprint) -> Vec<Drift> {
+        let mut drifts = Vec::new();
+        let mut check = |what: String, expected: String, found: String| {
+            if expected != found {
+                drifts.push(Drift { what, expected, found });
+            }
+        };
+
+        let (before, after) = (&self.environment, &now.environment);
+        check("sp1_version".into(), before.sp1_version.clone(), after.sp1_version.clone());
+        check("rustc_version".into(), before.rustc_version.clone(), after.rustc_version.clone());
+        check("zkvm_target".into(), before.zkvm_target.clone(), after.zkvm_target.clone());
+        check("git_commit".into(), describe(&before.git_commit), describe(&after.git_commit));
+        check("git_dirty".into(), describe(&before.git_dirty), describe(&after.git_dirty));
+
+        for (core, sha256) in &self.elfs {
+            let found = now.elfs.get(core).cloned().flatten();
+            check(format!("elf[{}]", core), describe(sha256), describe(&found));
+        }
+
+        let (before, after) = (&self.canary, &now.canary);
+        for (target, expected, found) in [("native", &before.native, &after.native), ("sp1", &before.sp1, &after.sp1)] {
+            check(
+                format!("canary.{}.status", target),
+                format!("{:?}", expected.status),
+                format!("{:?}", found.status),
+            );
+            if let Some((expected, found)) = commits_drift(&expected.commits, &found.commits) {
+                check(format!("canary.{}.commits", target), expected, found);
+            }
+        }
+        drifts
+    }
+}
+
+/// One re-verification during a soak
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Check {
+    /// RFC 3339
+    pub at: String,
+    /// Fuzz rounds finished before the check
+    pub rounds: usize,
+    pub drift: Vec<Drift>,
+}
+
+/// `report.json` of a soak
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    pub id: String,
+    pub cores: Vec<String>,
+    pub started_at: String,
+    pub baseline: Fingerprint,
+    pub checks: Vec<Check>,
+    pub rounds: usize,
+    /// The soak stopped because the environment drifted
+    pub aborted: bool,
+}
+
+impl Report {
+    pub fn dir(&self) -> PathBuf {
+        Path::new(SOAK_DIR).join(&self.id)
+    }
+
+    /// Write `report.json` (after every check, so a killed soak keeps its history)
+    pub fn save(&self) -> Result<PathBuf> {
+        let dir = self.dir();
+        fs::create_dir_all(&dir)?;
+        let path = dir.join("report.json");
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+/// Payload sent to the `fuzz.toml` notification hooks when a check drifts
+#[derive(Debug, Serialize)]
+pub struct DriftNotice<'a> {
+    pub event: &'static str,
+    pub soak_id: &'a str,
+    pub rounds: usize,
+    pub drift: &'a [Drift],
+}
+
+impl<'a> DriftNotice<'a> {
+    pub fn new(soak_id: &'a str, rounds: usize, drift: &'a [Drift]) -> Self {
+        DriftNotice {
+            event: "environment_drift",
+            soak_id,
+            rounds,
+            drift,
+        }
+    }
+
+    /// One-line description for chat messages
+    pub fn summary(&self) -> String {
+        let drift: Vec<String> = self.drift.iter().map(Drift::to_string).collect();
+        format!(
+            "Environment drift in soak {} after {} rounds: {}",
+            self.soak_id,
+            self.rounds,
+            drift.join("; ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fingerprint() -> Fingerprint {
+        let result = |commits: Vec<serde_json::Value>| CanaryResult {
+            status: Status::Ok,
+            commits,
+        };
+        Fingerprint {
+            environment: Snapshot::new("sp1 5.2.4", "rustc 1.90.0", crate::ZKVM_TARGET, Some("abc".to_string()), Some(false)),
+            elfs: BTreeMap::from([("fib".to_string(), Some("11".to_string())), ("io_echo".to_string(), Some("22".to_string()))]),
+            canary: Canary {
+                core: "fib".to_string(),
+                input: "inputs/fib_24.json".to_string(),
+                native: result(vec![serde_json::json!(24), serde_json::json!(46368)]),
+                sp1: result(vec![serde_json::json!(24), serde_json::json!(46368)]),
+            },
+        }
+    }
+
+    #[test]
+    fn test_drift() {
+        let baseline = fingerprint();
+        assert!(baseline.drift(&fingerprint()).is_empty());
+
+        let mut now = fingerprint();
+        now.environment = Snapshot::new("sp1 5.2.5", "rustc 1.90.0", crate::ZKVM_TARGET, Some("abc".to_string()), Some(true));
+        now.elfs.insert("io_echo".to_string(), None);
+        now.canary.sp1.commits[1] = serde_json::json!(1);
+        now.canary.native.commits.pop();
+        let drift: Vec<String> = baseline.drift(&now).iter().map(Drift::to_string).collect();
+        assert_eq!(
+            drift,
+            vec![
+                "sp1_version: sp1 5.2.4 → sp1 5.2.5",
+                "git_dirty: false → true",
+                "elf[io_echo]: 22 → none",
+                "canary.native.commits: 2 commits → 1 commits",
+                "canary.sp1.commits: commit 1 = 46368 → commit 1 = 1",
+            ]
+        );
+
+        let drift = baseline.drift(&now);
+        let notice = DriftNotice::new("soak_1", 3, &drift[..1]);
+        assert_eq!(notice.summary(), "Environment drift in soak soak_1 after 3 rounds: sp1_version: sp1 5.2.4 → sp1 5.2.5");
+    }
+}
@@ -0,0 +1,512 @@
+//! Artifact storage backends
+//!
+//! Runs are always logged under `artifacts/`, which the harness itself reads
+//! back (the run index, baselines, the executed corpus). A `[storage]`
+//! section in `fuzz.toml` adds an [`ArtifactStore`] every logged run is also
+//! published to: a directory (e.g. a shared mount), or an S3 or GCS bucket
+//! written through the `aws` and `gcloud` CLIs. Workers and long campaigns
+//! on several machines can then collect their results in one place.
+//!
+//! ```toml
+//! [storage]
+//! backend = "s3"                  # local, s3 or gcs
+//! bucket = "zk-fuzz-results"
+//! prefix = "nightly"              # key prefix (default: none)
+//! endpoint = "http://minio:9000"  # S3-compatible endpoint (s3 only)
+//! ```
+//!
+//! Object stores cannot append, so each run's `summary.csv` row and
+//! `runs.jsonl` record become objects of their own:
+//!
+//! | Key | Contents |
+//! |---|---|
+//! | `<campaign>/<core>/<run_id>/...` | The run folder, as under `artifacts/` |
+//! | `summary/<run_id>.csv` | `summary.csv` header and the run's row |
+//! | `runs/<run_id>.json` | The run's `runs.jsonl` record |
+//! | `environments/<env_id>.json` | The environment snapshot rows reference (once per snapshot) |
+//!
+//! Publishing never waits for the store. [`publish_run`] queues a run's
+//! objects for a background uploader, which writes everything queued since
+//! its last upload as one batch: a bucket store stages the batch in a
+//! temporary directory and uploads it with a single `aws s3 cp --recursive`
+//! or `gcloud storage rsync`, instead of one CLI process per object.
+//! [`finish`] waits for the queue to drain before the harness exits.
+//!
+//! Like notification hooks, a failed upload is reported but never aborts a
+//! run; the local artifacts are complete either way.
+//!
+//! The store is a mirror, not a backend: the harness still writes and reads
+//! `artifacts/` directly, and nothing reads through [`ArtifactStore`].
+//! Moving the run index, baselines and corpus behind the trait (so a worker
+//! could run without a local `artifacts/`) is the follow-up.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::thread::JoinHandle;
+
+static STORE: OnceLock<Option<Box<dyn ArtifactStore>>> = OnceLock::new();
+
+/// Somewhere artifacts can be written by key
+///
+/// Keys are `/`-separated paths relative to `artifacts/`.
+pub trait ArtifactStore: Send + Sync {
+    /// Where the store writes, for messages (`/mnt/results`, `s3://bucket/prefix`)
+    fn location(&self) -> String;
+
+    /// Write `bytes` at `key`, replacing what was there
+    fn put(&self, key: &str, bytes: &[u8]) -> Result<()>;
+
+    /// Write a file's contents at `key`
+    fn put_file(&self, key: &str, path: &Path) -> Result<()> {
+        let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        self.put(key, &bytes)
+    }
+
+    /// Write several objects (one [`put`](ArtifactStore::put) each unless
+    /// the store can do better)
+    fn put_batch(&self, objects: &[Object]) -> Result<()> {
+        objects.iter().try_for_each(|(key, bytes)| self.put(key, bytes))
+    }
+}
+
+/// A key and the bytes to write there
+pub type Object = (String, Vec<u8>);
+
+/// A directory on the local filesystem
+pub struct LocalStore {
+    root: PathBuf,
+}
+
+impl LocalStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalStore { root: root.into() }
+    }
+}
+
+impl ArtifactStore for LocalStore {
+    fn location(&self) -> String {
+        self.root.display().to_string()
+    }
+
+    fn put(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, bytes).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// An S3 or GCS bucket, written through the provider's CLI
+///
+/// Credentials are whatever the CLI is configured with (`aws configure`,
+/// `AWS_PROFILE`, `gcloud auth`), so none are kept in `fuzz.toml`.
+pub struct BucketStore {
+    backend: Backend,
+    bucket: String,
+    prefix: String,
+    endpoint: Option<String>,
+}
+
+impl BucketStore {
+    /// `s3://bucket/prefix/key` or `gs://bucket/prefix/key`
+    pub fn url(&self, key: &str) -> String {
+        let scheme = match self.backend {
+            Backend::Gcs => "gs",
+            _ => "s3",
+        };
+        let path: Vec<&str> = [self.prefix.as_str(), key]
+            .into_iter()
+            .flat_map(|part| part.split('/'))
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        format!("{}://{}/{}", scheme, self.bucket, path.join("/"))
+    }
+
+    /// The CLI call that uploads its stdin to `key`
+    pub fn upload_command(&self, key: &str) -> Command {
+        let mut cmd = match self.backend {
+            Backend::Gcs => {
+                let mut cmd = Command::new("gcloud");
+                cmd.args(["storage", "cp", "--quiet", "-", &self.url(key)]);
+                cmd
+            }
+            _ => {
+                let mut cmd = Command::new("aws");
+                cmd.args(["s3", "cp", "--only-show-errors", "-", &self.url(key)]);
+                if let Some(endpoint) = &self.endpoint {
+                    cmd.args(["--endpoint-url", endpoint]);
+                }
+                cmd
+            }
+        };
+        cmd.stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::piped());
+        cmd
+    }
+
+    /// The CLI call that uploads the tree under `dir`, each file at its
+    /// path relative to `dir`
+    pub fn sync_command(&self, dir: &Path) -> Command {
+        let mut cmd = match self.backend {
+            Backend::Gcs => {
+                let mut cmd = Command::new("gcloud");
+                cmd.args(["storage", "rsync", "--recursive", "--quiet"]).arg(dir).arg(self.url(""));
+                cmd
+            }
+            _ => {
+                let mut cmd = Command::new("aws");
+                cmd.args(["s3", "cp", "--recursive", "--only-show-errors"]).arg(dir).arg(self.url(""));
+                if let Some(endpoint) = &self.endpoint {
+                    cmd.args(["--endpoint-url", endpoint]);
+                }
+                cmd
+            }
+        };
+        cmd.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped());
+        cmd
+    }
+
+    /// Run an upload command, feeding it `stdin`
+    fn run(&self, mut cmd: Command, stdin: &[u8], target: &str) -> Result<()> {
+        let program = cmd.get_program().to_string_lossy().into_owned();
+        let mut child = cmd.spawn().with_context(|| format!("Failed to start `{}`", program))?;
+        if let Some(mut pipe) = child.stdin.take() {
+            pipe.write_all(stdin)?;
+        }
+        let output = child.wait_with_output()?;
+        anyhow::ensure!(
+            output.status.success(),
+            "Upload to {} failed ({}): {}",
+            target,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        Ok(())
+    }
+}
+
+impl ArtifactStore for BucketStore {
+    fn location(&self) -> String {
+        self.url("")
+    }
+
+    fn put(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        self.run(self.upload_command(key), bytes, &self.url(key))
+    }
+
+    /// Stage the batch in a temporary directory and upload it in one call
+    fn put_batch(&self, objects: &[Object]) -> Result<()> {
+        static BATCHES: AtomicUsize = AtomicUsize::new(0);
+        if let [(key, bytes)] = objects {
+            return self.put(key, bytes);
+        }
+        let staging = std::env::temp_dir().join(format!(
+            "harness-upload-{}-{}",
+            std::process::id(),
+            BATCHES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let result = LocalStore::new(&staging)
+            .put_batch(objects)
+            .and_then(|()| self.run(self.sync_command(&staging), &[], &self.location()));
+        let _ = fs::remove_dir_all(&staging);
+        result
+    }
+}
+
+/// Kind of store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    Local,
+    S3,
+    Gcs,
+}
+
+/// The `[storage]` section of `fuzz.toml`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StorageConfig {
+    pub backend: Backend,
+    /// Directory of a `local` store
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// Bucket of an `s3` or `gcs` store
+    #[serde(default)]
+    pub bucket: Option<String>,
+    /// Key prefix inside the bucket
+    #[serde(default)]
+    pub prefix: String,
+    /// S3-compatible endpoint (MinIO, R2, ...)
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+impl StorageConfig {
+    /// A `local` store needs a `path`, a bucket store a `bucket`
+    pub fn validate(&self) -> Result<()> {
+        match self.backend {
+            Backend::Local => {
+                anyhow::ensure!(self.path.is_some(), "storage: the local backend needs a `path`");
+                anyhow::ensure!(
+                    self.bucket.is_none() && self.endpoint.is_none() && self.prefix.is_empty(),
+                    "storage: `bucket`, `prefix` and `endpoint` only apply to the s3 and gcs backends"
+                );
+                let path = self.path.as_deref().unwrap();
+                anyhow::ensure!(
+                    !path.starts_with("artifacts"),
+                    "storage: `path` must be outside artifacts/, which is always written"
+                );
+            }
+            Backend::S3 | Backend::Gcs => {
+                anyhow::ensure!(self.path.is_none(), "storage: `path` only applies to the local backend");
+                let bucket = self.bucket.as_deref().unwrap_or_default();
+                anyhow::ensure!(
+                    !bucket.is_empty() && !bucket.contains('/'),
+                    "storage: the {:?} backend needs a `bucket` name (without a scheme or path)",
+                    self.backend
+                );
+                anyhow::ensure!(
+                    self.endpoint.is_none() || self.backend == Backend::S3,
+                    "storage: `endpoint` only applies to the s3 backend"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// The store this section describes
+    pub fn open(&self) -> Box<dyn ArtifactStore> {
+        match self.backend {
+            Backend::Local => Box::new(LocalStore::new(self.path.clone().unwrap_or_default())),
+            Backend::S3 | Backend::Gcs => Box::new(BucketStore {
+                backend: self.backend,
+                bucket: self.bucket.clone().unwrap_or_default(),
+                prefix: self.prefix.clone(),
+                endpoint: self.endpoint.clone(),
+            }),
+        }
+    }
+}
+
+/// The store from `fuzz.toml` (loaded once; None if there is no `[storage]`
+/// section, or the file is invalid)
+pub fn store() -> Option<&'static dyn ArtifactStore> {
+    STORE
+        .get_or_init(|| match crate::matrix::FuzzConfig::load() {
+            Ok(config) => config.storage.map(|storage| storage.open()),
+            Err(e) => {
+                eprintln!("   ⚠️  Artifact storage disabled: {:#}", e);
+                None
+            }
+        })
+        .as_deref()
+}
+
+/// A run's objects, queued for the uploader
+struct Job {
+    run_id: String,
+    objects: Vec<Object>,
+}
+
+/// The background uploader: its queue and thread (None once finished)
+static UPLOADER: Mutex<Option<(Sender<Job>, JoinHandle<()>)>> = Mutex::new(None);
+
+/// Upload jobs until the queue closes, batching whatever queued up during
+/// the previous upload
+fn upload(store: &dyn ArtifactStore, jobs: Receiver<Job>) {
+    while let Ok(first) = jobs.recv() {
+        let batch: Vec<Job> = std::iter::once(first).chain(jobs.try_iter()).collect();
+        let objects: Vec<Object> = batch.iter().flat_map(|job| job.objects.iter().cloned()).collect();
+        if let Err(e) = store.put_batch(&objects) {
+            let runs: Vec<&str> = batch.iter().map(|job| job.run_id.as_str()).collect();
+            eprintln!("   ⚠️  Failed to publish {} to {}: {:#}", runs.join(", "), store.location(), e);
+        }
+    }
+}
+
+/// Queue a job, starting the uploader on first use
+fn enqueue(store: &'static dyn ArtifactStore, job: Job) {
+    let mut uploader = UPLOADER.lock().unwrap_or_else(|e| e.into_inner());
+    let (sender, _) = uploader.get_or_insert_with(|| {
+        let (sender, jobs) = mpsc::channel();
+        (sender, std::thread::spawn(move || upload(store, jobs)))
+    });
+    // The uploader only stops once finish() took the sender
+    let _ = sender.send(job);
+}
+
+/// Wait for queued uploads to finish (no-op if nothing was published)
+///
+/// Call before the process exits. A run published afterwards starts a new
+/// uploader.
+pub fn finish() {
+    let uploader = UPLOADER.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some((sender, thread)) = uploader {
+        drop(sender);
+        let _ = thread.join();
+    }
+}
+
+/// Files to publish for one run: (key, source)
+///
+/// Every file of the run folder (if it has one) under its path relative to
+/// `artifacts/`.
+fn run_files(run_dir: Option<&Path>) -> Vec<(String, PathBuf)> {
+    let Some(run_dir) = run_dir else {
+        return Vec::new();
+    };
+    let Ok(relative) = run_dir.strip_prefix("artifacts") else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(run_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(String, PathBuf)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let key = relative.join(path.file_name()?);
+            let key = key.iter().map(|part| part.to_string_lossy()).collect::<Vec<_>>().join("/");
+            Some((key, path))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Publish a logged run to the configured store (no-op without one)
+///
+/// `summary` is the run's `summary.csv` header and row, `record` its
+/// `runs.jsonl` record; `run_dir` its folder under `artifacts/`, if any.
+/// The process's environment snapshot goes out with its first run, and
+/// again after it is replaced.
+pub fn publish_run(run_id: &str, run_dir: Option<&Path>, summary: &[u8], record: &crate::cost_model::RunRecord) {
+    let Some(store) = store() else {
+        return;
+    };
+    static PUBLISHED_ENVIRONMENT: Mutex<Option<String>> = Mutex::new(None);
+    let objects = (|| -> Result<Vec<Object>> {
+        let mut objects = Vec::new();
+        let snapshot = crate::environment::current();
+        let mut published = PUBLISHED_ENVIRONMENT.lock().unwrap_or_else(|e| e.into_inner());
+        if published.as_deref() != Some(snapshot.id.as_str()) {
+            objects.push((format!("environments/{}.json", snapshot.id), serde_json::to_vec_pretty(&*snapshot)?));
+            *published = Some(snapshot.id.clone());
+        }
+        drop(published);
+        objects.push((format!("summary/{}.csv", run_id), summary.to_vec()));
+        objects.push((format!("runs/{}.json", run_id), serde_json::to_vec_pretty(record)?));
+        // Read now: the folder may change before the upload
+        for (key, path) in run_files(run_dir) {
+            let bytes = fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            objects.push((key, bytes));
+        }
+        Ok(objects)
+    })();
+    match objects {
+        Ok(objects) => enqueue(
+            store,
+            Job {
+                run_id: run_id.to_string(),
+                objects,
+            },
+        ),
+        Err(e) => eprintln!("   ⚠️  Failed to publish {} to {}: {:#}", run_id, store.location(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(text: &str) -> Result<StorageConfig> {
+        let config: StorageConfig = toml::from_str(text)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(config("backend = \"s3\"\nbucket = \"results\"\nendpoint = \"http://minio:9000\"").is_ok());
+        assert!(config("backend = \"local\"\npath = \"/mnt/results\"").is_ok());
+        assert!(config("backend = \"local\"").is_err());
+        assert!(config("backend = \"local\"\npath = \"artifacts/mirror\"").is_err());
+        assert!(config("backend = \"gcs\"").is_err());
+        assert!(config("backend = \"gcs\"\nbucket = \"gs://results\"").is_err());
+        assert!(config("backend = \"gcs\"\nbucket = \"results\"\nendpoint = \"http://x\"").is_err());
+        assert!(config("backend = \"ftp\"\nbucket = \"results\"").is_err());
+    }
+
+    #[test]
+    fn test_bucket_urls_and_commands() {
+        let s3 = BucketStore {
+            backend: Backend::S3,
+            bucket: "results".to_string(),
+            prefix: "/nightly/".to_string(),
+            endpoint: Some("http://minio:9000".to_string()),
+        };
+        assert_eq!(s3.url("summary/r1.csv"), "s3://results/nightly/summary/r1.csv");
+        let cmd = s3.upload_command("runs/r1.json");
+        assert_eq!(cmd.get_program(), "aws");
+        let args: Vec<_> = cmd.get_args().map(|arg| arg.to_string_lossy().into_owned()).collect();
+        assert_eq!(
+            args,
+            ["s3", "cp", "--only-show-errors", "-", "s3://results/nightly/runs/r1.json", "--endpoint-url", "http://minio:9000"]
+        );
+
+        let gcs = BucketStore {
+            backend: Backend::Gcs,
+            bucket: "results".to_string(),
+            prefix: String::new(),
+            endpoint: None,
+        };
+        assert_eq!(gcs.location(), "gs://results/");
+        assert_eq!(gcs.upload_command("a/b").get_program(), "gcloud");
+
+        let args = |cmd: Command| cmd.get_args().map(|arg| arg.to_string_lossy().into_owned()).collect::<Vec<_>>();
+        assert_eq!(
+            args(s3.sync_command(Path::new("/tmp/batch"))),
+            ["s3", "cp", "--recursive", "--only-show-errors", "/tmp/batch", "s3://results/nightly", "--endpoint-url", "http://minio:9000"]
+        );
+        assert_eq!(
+            args(gcs.sync_command(Path::new("/tmp/batch"))),
+            ["storage", "rsync", "--recursive", "--quiet", "/tmp/batch", "gs://results/"]
+        );
+    }
+
+    #[test]
+    fn test_upload_batches_queued_jobs() {
+        let root = std::env::temp_dir().join(format!("harness-storage-queue-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let (sender, jobs) = mpsc::channel();
+        for run_id in ["r1", "r2"] {
+            let objects = vec![(format!("runs/{}.json", run_id), b"{}".to_vec())];
+            sender.send(Job { run_id: run_id.to_string(), objects }).unwrap();
+        }
+        drop(sender);
+        upload(&LocalStore::new(&root), jobs);
+        assert!(root.join("runs/r1.json").is_file() && root.join("runs/r2.json").is_file());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_local_store_and_run_files() {
+        let root = std::env::temp_dir().join(format!("harness-storage-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let store = LocalStore::new(&root);
+        store.put("summary/r1.csv", b"run_id\nr1\n").unwrap();
+        assert_eq!(fs::read(root.join("summary/r1.csv")).unwrap(), b"run_id\nr1\n");
+
+        assert!(run_files(Some(Path::new("elsewhere/run"))).is_empty());
+        assert!(run_files(None).is_empty());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
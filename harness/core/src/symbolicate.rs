@@ -0,0 +1,222 @@
+//! Source locations of SP1 guest panics
+//!
+//! An SP1 execution error says only that the guest halted ("exit code 1")
+//! or faulted. Two things locate it in the source:
+//!
+//! - the guest's panic message, which the standard panic hook writes to
+//!   stderr (`panicked at src/lib.rs:42:9:`), captured by sp1-runner as
+//!   `meta.stderr`
+//! - the registers the executor stopped with (`meta.fault.pc` and `ra`,
+//!   see `runners/sp1`), symbolicated against the guest ELF with
+//!   `llvm-addr2line` (or GNU `addr2line` if it understands RISC-V)
+//!
+//! [`annotate`] adds `meta.backtrace` (the frames of `pc` and `ra`,
+//! innermost first, inlined frames included) and `meta.panic_site`: the
+//! panic message's location, else the first frame with a source line.
+//! Guests are built without debug info by default, so frames usually have
+//! a function name but no line; a panic message always has one.
+
+use rust_eq_oracle::{RunResult, Status};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+/// Symbolicators tried in order
+const ADDR2LINE: &[&str] = &["llvm-addr2line", "addr2line"];
+
+/// A source location
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PanicSite {
+    pub file: String,
+    pub line: u32,
+    pub column: Option<u32>,
+    /// `panic_message` or `backtrace`
+    pub source: String,
+}
+
+impl std::fmt::Display for PanicSite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ":{}", column)?;
+        }
+        Ok(())
+    }
+}
+
+/// One symbolicated frame
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Frame {
+    /// Address, as `0x…`
+    pub pc: String,
+    pub function: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+}
+
+/// `file:line[:col]`, if `token` is one
+fn parse_location(token: &str) -> Option<(String, u32, Option<u32>)> {
+    let (rest, last) = token.rsplit_once(':')?;
+    let last: u32 = last.parse().ok()?;
+    match rest.rsplit_once(':') {
+        Some((file, line)) if line.parse::<u32>().is_ok() => Some((file.to_string(), line.parse().ok()?, Some(last))),
+        _ => Some((rest.to_string(), last, None)),
+    }
+}
+
+/// The location in a `panicked at` line of `text`
+///
+/// Handles both `panicked at src/lib.rs:42:9:` (Rust 1.73 and later) and
+/// `panicked at 'message', src/lib.rs:42:9`.
+pub fn panic_location(text: &str) -> Option<PanicSite> {
+    let (_, rest) = text.split_once("panicked at ")?;
+    let rest = match rest.strip_prefix('\'') {
+        Some(quoted) => quoted.split_once("', ")?.1,
+        None => rest,
+    };
+    let token = rest.split_whitespace().next()?.trim_end_matches([':', ',']);
+    let (file, line, column) = parse_location(token)?;
+    Some(PanicSite {
+        file,
+        line,
+        column,
+        source: "panic_message".to_string(),
+    })
+}
+
+/// Parse `addr2line -f -i` output for `pc`: a function line and a
+/// location line per frame
+fn parse_frames(pc: &str, output: &str) -> Vec<Frame> {
+    let lines: Vec<&str> = output.lines().filter(|line| !line.trim().is_empty()).collect();
+    lines
+        .chunks(2)
+        .filter_map(|pair| {
+            let [function, location] = pair else {
+                return None;
+            };
+            let location = location.split(" (discriminator").next().unwrap_or(location).trim();
+            let known = parse_location(location).filter(|(file, line, _)| !file.starts_with("??") && *line > 0);
+            Some(Frame {
+                pc: pc.to_string(),
+                function: Some(function.trim().to_string()).filter(|function| function != "??"),
+                file: known.as_ref().map(|(file, ..)| file.clone()),
+                line: known.map(|(_, line, _)| line),
+            })
+        })
+        .collect()
+}
+
+/// Frames of `pc` (innermost first) in `elf`, or None if no symbolicator
+/// could read it
+pub fn symbolicate(elf: &Path, pc: &str) -> Option<Vec<Frame>> {
+    ADDR2LINE.iter().find_map(|tool| {
+        let output = Command::new(tool).args(["-f", "-C", "-i", "-e"]).arg(elf).arg(pc).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(parse_frames(pc, &String::from_utf8_lossy(&output.stdout)))
+    })
+}
+
+/// Add `meta.backtrace` and `meta.panic_site` to a panicked or OOM SP1 run
+pub fn annotate(result: &mut RunResult, elf: &Path) {
+    if !matches!(result.status, Status::Panic | Status::Oom) {
+        return;
+    }
+    let mut backtrace = Vec::new();
+    if let Some(fault) = result.meta.get("fault") {
+        for register in ["pc", "ra"] {
+            if let Some(address) = fault.get(register).and_then(|address| address.as_str()) {
+                backtrace.extend(symbolicate(elf, address).unwrap_or_default());
+            }
+        }
+    }
+
+    let site = ["stderr", "panic_msg"]
+        .iter()
+        .filter_map(|key| result.meta.get(*key).and_then(|text| text.as_str()))
+        .find_map(panic_location)
+        .or_else(|| {
+            backtrace.iter().find_map(|frame| {
+                Some(PanicSite {
+                    file: frame.file.clone()?,
+                    line: frame.line?,
+                    column: None,
+                    source: "backtrace".to_string(),
+                })
+            })
+        });
+
+    if !backtrace.is_empty() {
+        result.meta["backtrace"] = serde_json::to_value(&backtrace).unwrap_or_default();
+    }
+    if let Some(site) = site {
+        result.meta["panic_site"] = serde_json::to_value(&site).unwrap_or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_location() {
+        let stderr = "thread '<unnamed>' panicked at /work/guest/cores/panic_test/src/lib.rs:42:9:\nboom\n";
+        let site = panic_location(stderr).unwrap();
+        assert_eq!(site.to_string(), "/work/guest/cores/panic_test/src/lib.rs:42:9");
+        assert_eq!(site.source, "panic_message");
+
+        let old = panic_location("thread 'main' panicked at 'attempt to add with overflow', src/lib.rs:7:5").unwrap();
+        assert_eq!((old.file.as_str(), old.line, old.column), ("src/lib.rs", 7, Some(5)));
+        assert_eq!(panic_location("HaltWithNonZeroExitCode(1)"), None);
+    }
+
+    #[test]
+    fn test_parse_frames() {
+        let output = "core::panicking::panic\n/rustc/abc/library/core/src/panicking.rs:75:14\n\
+                      panic_test_core::run\nguest/cores/panic_test/src/lib.rs:42 (discriminator 3)\n";
+        let frames = parse_frames("0x00201a4c", output);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].function.as_deref(), Some("core::panicking::panic"));
+        assert_eq!(frames[0].line, Some(75));
+        assert_eq!(frames[1].file.as_deref(), Some("guest/cores/panic_test/src/lib.rs"));
+        assert_eq!(frames[1].line, Some(42));
+
+        // Without debug info only the function is known
+        let stripped = parse_frames("0x00201a4c", "main\n??:0\n");
+        assert_eq!(
+            stripped,
+            vec![Frame {
+                pc: "0x00201a4c".to_string(),
+                function: Some("main".to_string()),
+                file: None,
+                line: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_annotate() {
+        let mut result = RunResult {
+            status: Status::Panic,
+            elapsed_ms: 0,
+            commits: vec![],
+            meta: serde_json::json!({
+                "panic_msg": "HaltWithNonZeroExitCode(1)",
+                "stderr": "panicked at src/lib.rs:12:5:\nboom",
+            }),
+        };
+        annotate(&mut result, Path::new("/nonexistent.elf"));
+        assert_eq!(result.meta["panic_site"]["file"], "src/lib.rs");
+        assert_eq!(result.meta["panic_site"]["line"], 12);
+        assert!(result.meta.get("backtrace").is_none());
+
+        let mut ok = RunResult {
+            status: Status::Ok,
+            ..result.clone()
+        };
+        ok.meta = serde_json::json!({ "stderr": "panicked at src/lib.rs:12:5:" });
+        annotate(&mut ok, Path::new("/nonexistent.elf"));
+        assert!(ok.meta.get("panic_site").is_none());
+    }
+}
@@ -0,0 +1,155 @@
+//! SP1 execution traces of divergent runs
+//!
+//! Tracing an execution costs far more than executing it, so passing runs
+//! never pay for it. When a run diverges, the harness executes the SP1 side
+//! once more with `sp1-runner --trace` and saves the last instructions it
+//! ran as `sp1_trace.csv` in the repro folder: step, pc, opcode, the
+//! operand values and the next pc. The last ones are the interesting ones
+//! for a panic or a fault, and the bound keeps a long-running guest from
+//! filling the disk.
+//!
+//! Configured by the `[trace]` table of `fuzz.toml`:
+//!
+//! ```toml
+//! [trace]
+//! enabled = true        # default
+//! max_steps = 100000    # instructions kept (default)
+//! ```
+
+use crate::InputEncoding;
+use anyhow::{Context, Result};
+use rust_eq_oracle::{RunResult, Status};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Name of the trace in a repro folder
+pub const TRACE_FILE: &str = "sp1_trace.csv";
+
+/// Instructions kept by default
+pub const DEFAULT_MAX_STEPS: usize = 100_000;
+
+/// `[trace]` in `fuzz.toml`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TraceConfig {
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    #[serde(default = "max_steps")]
+    pub max_steps: usize,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn max_steps() -> usize {
+    DEFAULT_MAX_STEPS
+}
+
+impl Default for TraceConfig {
+    fn default() -> Self {
+        TraceConfig {
+            enabled: enabled(),
+            max_steps: max_steps(),
+        }
+    }
+}
+
+static CONFIG: OnceLock<TraceConfig> = OnceLock::new();
+
+/// The `[trace]` table (defaults if `fuzz.toml` has none or cannot be read)
+pub fn config() -> &'static TraceConfig {
+    CONFIG.get_or_init(|| match crate::matrix::FuzzConfig::load() {
+        Ok(config) => config.trace,
+        Err(e) => {
+            eprintln!("   ⚠️  Using the default trace settings: {:#}", e);
+            TraceConfig::default()
+        }
+    })
+}
+
+/// What `sp1-runner --trace` reports as `meta.trace`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceSummary {
+    pub path: String,
+    /// Instructions in the file
+    pub steps: u64,
+    /// Instructions the execution ran
+    pub total_steps: u64,
+    /// Earlier instructions were dropped
+    pub truncated: bool,
+    /// How the traced execution failed, if it did
+    pub error: Option<String>,
+}
+
+/// Whether the SP1 side of a divergence can be traced
+///
+/// A run that never started (infrastructure) has nothing to trace, and a
+/// timed-out one would run unbounded again.
+pub fn should_trace(sp1: &RunResult) -> bool {
+    !sp1.status.is_infra() && sp1.status != Status::Timeout
+}
+
+/// Trace the SP1 execution of `input_path` into `repro_dir`
+///
+/// Returns None if tracing is disabled or does not apply to the run.
+pub fn capture(repro_dir: &Path, core_name: &str, input_path: &Path, sp1: &RunResult) -> Result<Option<TraceSummary>> {
+    let config = config();
+    // The runner image cannot write into the repro folder
+    if !config.enabled || !should_trace(sp1) || crate::container::image_for("sp1").is_some() {
+        return Ok(None);
+    }
+
+    let trace_path = repro_dir.join(TRACE_FILE);
+    let elf_path = crate::elf_path_for_core(core_name);
+    let mut cmd = crate::sp1_execute_command(None, &elf_path, input_path, core_name, InputEncoding::Json);
+    cmd.arg("--trace").arg(&trace_path);
+    cmd.args(["--trace-max-steps", &config.max_steps.to_string()]);
+    let output = cmd.output().context("Failed to run sp1-runner")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "sp1-runner failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let result: RunResult = serde_json::from_slice(&output.stdout).context("Failed to parse sp1-runner output")?;
+    let trace = result.meta.get("trace").context("sp1-runner did not report a trace")?;
+    if trace.get("path").is_none() {
+        anyhow::bail!("{}", trace["error"].as_str().unwrap_or("sp1-runner could not trace the run"));
+    }
+    Ok(Some(serde_json::from_value(trace.clone())?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config() {
+        let config: crate::matrix::FuzzConfig = toml::from_str("[trace]\nmax_steps = 10\n").unwrap();
+        assert_eq!(
+            config.trace,
+            TraceConfig {
+                enabled: true,
+                max_steps: 10,
+            }
+        );
+        let config: crate::matrix::FuzzConfig = toml::from_str("").unwrap();
+        assert_eq!(config.trace, TraceConfig::default());
+    }
+
+    #[test]
+    fn test_should_trace() {
+        let result = |status| RunResult {
+            status,
+            elapsed_ms: 0,
+            commits: vec![],
+            meta: serde_json::json!({}),
+        };
+        assert!(should_trace(&result(Status::Ok)));
+        assert!(should_trace(&result(Status::Panic)));
+        assert!(should_trace(&result(Status::CycleLimit)));
+        assert!(!should_trace(&result(Status::Timeout)));
+        assert!(!should_trace(&result(Status::InfraError)));
+    }
+}
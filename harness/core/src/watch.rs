@@ -0,0 +1,114 @@
+//! Change detection for `harness watch`
+//!
+//! Polls file modification times instead of using OS file events, so it
+//! behaves the same on every platform and needs no extra dependencies.
+//! `target/` directories are skipped: guest builds write there and would
+//! otherwise retrigger the loop.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Modification time and size of every file under a set of roots
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    files: BTreeMap<PathBuf, (Option<SystemTime>, u64)>,
+}
+
+impl Snapshot {
+    /// Walk `roots` (files or directories); missing roots are skipped
+    pub fn capture(roots: &[PathBuf]) -> Snapshot {
+        let mut snapshot = Snapshot::default();
+        for root in roots {
+            snapshot.visit(root);
+        }
+        snapshot
+    }
+
+    fn visit(&mut self, path: &Path) {
+        let Ok(metadata) = fs::metadata(path) else {
+            return;
+        };
+        if metadata.is_dir() {
+            if path.file_name().is_some_and(|name| name == "target") {
+                return;
+            }
+            let Ok(entries) = fs::read_dir(path) else {
+                return;
+            };
+            for entry in entries.flatten() {
+                self.visit(&entry.path());
+            }
+        } else {
+            self.files
+                .insert(path.to_path_buf(), (metadata.modified().ok(), metadata.len()));
+        }
+    }
+
+    /// Most recently modified file
+    pub fn newest(&self) -> Option<(&Path, SystemTime)> {
+        self.files
+            .iter()
+            .filter_map(|(path, (modified, _))| Some((path.as_path(), (*modified)?)))
+            .max_by_key(|(_, modified)| *modified)
+    }
+
+    /// Files added, removed or modified since `earlier`
+    pub fn changed_since(&self, earlier: &Snapshot) -> Vec<PathBuf> {
+        let mut changed: Vec<PathBuf> = self
+            .files
+            .iter()
+            .filter(|(path, stamp)| earlier.files.get(*path) != Some(stamp))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.extend(
+            earlier
+                .files
+                .keys()
+                .filter(|path| !self.files.contains_key(*path))
+                .cloned(),
+        );
+        changed
+    }
+}
+
+/// Roots whose changes require rebuilding a core's guest: the core's own
+/// crate and its SP1 adapter
+pub fn watch_roots(core_path: &Path, core_name: &str) -> Vec<PathBuf> {
+    vec![core_path.to_path_buf(), crate::guest_path_for_core(core_name)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_modified_added_and_removed_files() {
+        let dir = std::env::temp_dir().join(format!("harness-watch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::create_dir_all(dir.join("target")).unwrap();
+        fs::write(dir.join("src/lib.rs"), "fn a() {}").unwrap();
+        fs::write(dir.join("Cargo.toml"), "[package]").unwrap();
+
+        let roots = vec![dir.clone()];
+        let before = Snapshot::capture(&roots);
+        assert_eq!(Snapshot::capture(&roots).changed_since(&before), Vec::<PathBuf>::new());
+
+        // Build output is ignored
+        fs::write(dir.join("target/guest.elf"), "elf").unwrap();
+        assert!(Snapshot::capture(&roots).changed_since(&before).is_empty());
+
+        fs::write(dir.join("src/lib.rs"), "fn a() { 1; }").unwrap();
+        fs::write(dir.join("src/new.rs"), "").unwrap();
+        fs::remove_file(dir.join("Cargo.toml")).unwrap();
+        let changed = Snapshot::capture(&roots).changed_since(&before);
+        assert_eq!(
+            changed,
+            vec![dir.join("src/lib.rs"), dir.join("src/new.rs"), dir.join("Cargo.toml")]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,11 @@
+//! Smoke subset of native-vs-SP1 differential tests
+//!
+//! Each case builds the SP1 guest, runs both targets and fails on any
+//! divergence. They need `cargo prove`, so they only run with `--ignored`.
+
+use harness_core::zk_difftest;
+
+zk_difftest!(fib_24, "fib", "inputs/fib_24.json");
+zk_difftest!(io_echo_empty, "io_echo", "inputs/io_echo_empty.json");
+zk_difftest!(arithmetic_add_normal, "arithmetic", "inputs/arithmetic_add_normal.json");
+zk_difftest!(simple_struct_normal, "simple_struct", "inputs/simple_struct_normal.json");
@@ -0,0 +1,497 @@
+//! `harness coordinate` / `harness worker`: fuzzing across machines
+//!
+//! The coordinator plans the campaign exactly like `harness fuzz` and serves
+//! the mutations over HTTP (`POST /work`, `POST /results`); workers build the
+//! guests from their own checkout, execute native and SP1, and send the raw
+//! results back. Comparison, logging, promotion and divergence dedup all
+//! happen on the coordinator. See `harness_core::distributed`.
+//!
+//! Both sides share a token (`--token` or `HARNESS_WORKER_TOKEN`; the
+//! coordinator generates and prints one if neither is set). The coordinator
+//! builds the guests too, and refuses results from another SP1 version or
+//! guest ELF.
+
+use anyhow::{Context, Result};
+use harness_core::distributed::{
+    ResultAck, ResultBatch, Task, TaskResult, Toolchain, WorkBatch, WorkQueue, WorkRequest,
+};
+use harness_core::http::{Request, Response};
+use harness_core::{
+    build_failure_result, build_sp1_guest, elf_path_for_core, guest_path_for_core, run_native_runner_sandboxed,
+    run_sp1_runner, sha256_file, RetryPolicy,
+};
+use source_mutator::MutatedInput;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long the coordinator keeps answering `finished` after the last result,
+/// so idle workers learn that the campaign is over
+const LINGER: Duration = Duration::from_secs(5);
+
+/// How long an idle worker waits before asking again
+const IDLE_POLL: Duration = Duration::from_secs(2);
+
+/// Connection attempts before a worker gives up on the coordinator
+const CONNECT_ATTEMPTS: u32 = 6;
+
+/// Environment variable holding the shared worker token
+const TOKEN_VAR: &str = "HARNESS_WORKER_TOKEN";
+
+/// How `harness coordinate` serves and verifies the campaign
+pub struct CoordinatorOptions {
+    pub addr: String,
+    /// Shared worker token (default: `HARNESS_WORKER_TOKEN`, else generated)
+    pub token: Option<String>,
+    pub lease_timeout: Duration,
+    pub skip_build: bool,
+    pub allow_stale_elf: bool,
+    pub build_jobs: usize,
+}
+
+/// How `harness worker` executes its tasks
+pub struct WorkerOptions {
+    /// Shared worker token (default: `HARNESS_WORKER_TOKEN`)
+    pub token: Option<String>,
+    /// Name in the coordinator's logs (default: `<hostname>-<pid>`)
+    pub name: Option<String>,
+    pub batch_size: usize,
+    pub skip_build: bool,
+    pub allow_stale_elf: bool,
+    pub sandbox: bool,
+}
+
+/// `--token`, else `HARNESS_WORKER_TOKEN`
+fn token_from(flag: Option<String>) -> Option<String> {
+    flag.or_else(|| std::env::var(TOKEN_VAR).ok()).filter(|token| !token.is_empty())
+}
+
+/// What the coordinator keeps about each task
+struct Entry {
+    core: String,
+    mutation: MutatedInput,
+    mutation_dir: PathBuf,
+}
+
+/// Results tallied per core, plus global divergence dedup
+#[derive(Default)]
+struct Tally {
+    by_core: BTreeMap<String, (usize, usize, usize, usize)>,
+    /// Divergence signatures (see `harness_core::notify::divergence_signature`)
+    signatures: BTreeSet<String>,
+    /// Results received per worker
+    workers: BTreeMap<String, usize>,
+    /// Results refused per worker (other ELF)
+    rejected: BTreeMap<String, usize>,
+    promoted: usize,
+}
+
+struct Coordinator {
+    token: String,
+    toolchain: Toolchain,
+    queue: Mutex<WorkQueue>,
+    entries: BTreeMap<u64, Entry>,
+    tally: Mutex<Tally>,
+}
+
+/// Plan a fuzz campaign and serve its mutations to workers until all are done
+pub fn coordinate(
+    cores_arg: &str,
+    inputs: &harness_core::fuzz::InputOptions,
+    options: CoordinatorOptions,
+    retry: &RetryPolicy,
+) -> Result<()> {
+    let cores = harness_core::parse_cores_arg(cores_arg)?;
+
+    println!("🛰️  Coordinating distributed fuzzing...");
+    println!("   Cores: {}", cores.join(", "));
+    println!();
+
+    // Worker results are only accepted from the ELFs built here
+    if options.skip_build {
+        harness_core::provenance::ensure_fresh(cores.iter().copied(), options.allow_stale_elf)?;
+    } else {
+        for (core, build_error) in cores.iter().zip(harness_core::fuzz::build_guests(&cores, options.build_jobs, retry)) {
+            if let Some((e, _)) = build_error {
+                anyhow::bail!("The {} guest failed to build, so worker results cannot be verified: {:#}", core, e);
+            }
+        }
+    }
+    let mut elf_sha256 = BTreeMap::new();
+    for core in &cores {
+        let elf = elf_path_for_core(core);
+        let sha = sha256_file(&elf).with_context(|| format!("No ELF for {} at {}", core, elf.display()))?;
+        elf_sha256.insert(core.to_string(), sha);
+    }
+    let toolchain = Toolchain {
+        sp1_version: harness_core::environment::current().sp1_version.clone(),
+        elf_sha256,
+    };
+
+    let mut tasks = Vec::new();
+    let mut entries = BTreeMap::new();
+    for core_name in cores {
+        println!("📦 Core: {}", core_name);
+        let plan = harness_core::fuzz::prepare_fuzz_plan(core_name, inputs)?;
+        plan.enforce_input_schema(core_name, inputs.invalid_inputs)?;
+        for (idx, (mutation, schema_error)) in plan.mutations.into_iter().zip(plan.schema_errors).enumerate() {
+            if schema_error.is_some() && inputs.invalid_inputs == harness_core::fuzz::InvalidInputs::Skip {
+                continue;
+            }
+            let id = tasks.len() as u64;
+            tasks.push(Task {
+                id,
+                core: core_name.to_string(),
+                label: mutation.op.to_string(),
+                input: mutation.input_json.clone(),
+            });
+            entries.insert(
+                id,
+                Entry {
+                    core: core_name.to_string(),
+                    mutation_dir: plan.fuzz_artifacts_dir.join(format!("mutation_{:04}", idx + 1)),
+                    mutation,
+                },
+            );
+        }
+    }
+
+    let total = tasks.len();
+    let generated = token_from(options.token.clone()).is_none();
+    let token = token_from(options.token).unwrap_or_else(harness_core::http::new_token);
+    let coordinator = Arc::new(Coordinator {
+        token: token.clone(),
+        toolchain,
+        queue: Mutex::new(WorkQueue::new(tasks, options.lease_timeout)),
+        entries,
+        tally: Mutex::new(Tally::default()),
+    });
+
+    let listener =
+        TcpListener::bind(&options.addr).with_context(|| format!("Failed to listen on {}", options.addr))?;
+    println!("🌐 Serving {} mutations on {}", total, listener.local_addr()?);
+    println!(
+        "   Start workers with: {}=<token> harness worker --connect <this-host>:{}",
+        TOKEN_VAR,
+        listener.local_addr()?.port()
+    );
+    if generated {
+        println!("   Token: {}", token);
+    }
+    println!();
+
+    let server = Arc::clone(&coordinator);
+    std::thread::spawn(move || {
+        harness_core::http::serve(listener, move |request| server.handle(request))
+    });
+
+    let start = Instant::now();
+    let mut last_progress = None;
+    while !coordinator.queue.lock().unwrap().is_finished() {
+        std::thread::sleep(Duration::from_secs(1));
+        let progress = coordinator.queue.lock().unwrap().progress();
+        if last_progress != Some(progress) {
+            let (completed, in_flight, total) = progress;
+            println!("   📈 {}/{} done, {} in flight", completed, total, in_flight);
+            last_progress = Some(progress);
+        }
+    }
+    std::thread::sleep(LINGER);
+
+    coordinator.print_summary(start.elapsed());
+    Ok(())
+}
+
+impl Coordinator {
+    fn handle(&self, request: &Request) -> Response {
+        if let Some(denied) = request.authorize(&self.token) {
+            return denied;
+        }
+        match (request.method.as_str(), request.segments().as_slice()) {
+            ("POST", ["work"]) => match request.json::<WorkRequest>() {
+                Ok(work) => {
+                    if let Err(e) = self.toolchain.check_worker(&work.sp1_version) {
+                        println!("   🚫 Worker '{}' refused: {:#}", work.worker, e);
+                        return Response::error(409, &format!("{:#}", e));
+                    }
+                    let batch = self.queue.lock().unwrap().lease(&work.worker, work.max_tasks);
+                    if !batch.tasks.is_empty() {
+                        println!("   📤 {} task(s) to '{}'", batch.tasks.len(), work.worker);
+                    }
+                    Response::json(200, &batch)
+                }
+                Err(e) => Response::error(400, &format!("{:#}", e)),
+            },
+            ("POST", ["results"]) => match request.json::<ResultBatch>() {
+                Ok(batch) if self.toolchain.check_worker(&batch.sp1_version).is_err() => {
+                    Response::error(409, "results from another SP1 version are not accepted")
+                }
+                Ok(batch) => match self.record(batch) {
+                    Ok(ack) => Response::json(200, &ack),
+                    Err(e) => Response::error(500, &format!("{:#}", e)),
+                },
+                Err(e) => Response::error(400, &format!("{:#}", e)),
+            },
+            _ => Response::error(404, "no such endpoint"),
+        }
+    }
+
+    /// Compare and log a worker's results
+    fn record(&self, batch: ResultBatch) -> Result<ResultAck> {
+        // Held throughout, so the summary waits for results still being logged
+        let mut tally = self.tally.lock().unwrap();
+        let mut accepted = 0;
+        let mut rejected = 0;
+        for result in batch.results {
+            let Some(entry) = self.entries.get(&result.id) else {
+                continue;
+            };
+            // Left leased, so the task goes to another worker once it expires
+            if let Err(e) = self.toolchain.check_result(&entry.core, &result) {
+                println!("   🚫 Result for task {} from '{}' refused: {:#}", result.id, batch.worker, e);
+                *tally.rejected.entry(batch.worker.clone()).or_default() += 1;
+                rejected += 1;
+                continue;
+            }
+            let TaskResult { id, native, sp1, .. } = result;
+            if !self.queue.lock().unwrap().complete(id) {
+                println!("   ♻️  Duplicate result for task {} from '{}' dropped", id, batch.worker);
+                continue;
+            }
+            accepted += 1;
+
+            fs::create_dir_all(&entry.mutation_dir)?;
+            let input_path = entry.mutation_dir.join("input.json");
+            harness_core::write_input(&entry.core, &entry.mutation.input_json, &input_path)?;
+
+            let diff = harness_core::relations::compare_for_core(&entry.core, &input_path, &native, &sp1)?;
+            let infra = native.status.is_infra() || sp1.status.is_infra();
+            let signature = (!diff.equal && !infra).then(|| {
+                harness_core::notify::divergence_signature(&entry.core, &native, &sp1, &diff)
+            });
+            println!(
+                "   {} [{}] {} {} | Native: {:?} ({}ms) | SP1: {:?} ({}ms) | Equal: {}",
+                if diff.equal { "✅" } else { "❌" },
+                batch.worker,
+                entry.core,
+                entry.mutation.op,
+                native.status,
+                native.elapsed_ms,
+                sp1.status,
+                sp1.elapsed_ms,
+                diff.equal,
+            );
+            if let Some(reason) = diff.reason.as_ref().filter(|_| !diff.equal) {
+                println!("      Reason: {}", reason);
+            }
+
+            let equal = diff.equal;
+            let promoted = harness_core::fuzz::log_and_promote(&entry.core, &input_path, native, sp1, diff, &entry.mutation)?;
+
+            *tally.workers.entry(batch.worker.clone()).or_default() += 1;
+            let (runs, passed, divergent, infra_errors) = tally.by_core.entry(entry.core.clone()).or_default();
+            *runs += 1;
+            if equal {
+                *passed += 1;
+            } else if infra {
+                *infra_errors += 1;
+            } else {
+                *divergent += 1;
+            }
+            if let Some(signature) = signature {
+                if tally.signatures.insert(signature.clone()) {
+                    println!("      🆕 New divergence signature: {}", signature);
+                }
+            }
+            if promoted {
+                tally.promoted += 1;
+            }
+        }
+        Ok(ResultAck { accepted, rejected })
+    }
+
+    fn print_summary(&self, elapsed: Duration) {
+        let tally = self.tally.lock().unwrap();
+        println!();
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("🎯 Distributed Fuzzing Complete!");
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!();
+        for (core, (runs, passed, divergent, infra_errors)) in &tally.by_core {
+            println!(
+                "   {:<22} {:>5} runs, {:>5} passed, {:>5} divergent, {:>3} infra errors",
+                core, runs, passed, divergent, infra_errors
+            );
+        }
+        println!();
+        println!("   Unique divergences: {}", tally.signatures.len());
+        for signature in &tally.signatures {
+            println!("      {}", signature);
+        }
+        if tally.promoted > 0 {
+            println!("   Promoted seeds: {}", tally.promoted);
+        }
+        println!("   Workers:");
+        for (worker, results) in &tally.workers {
+            println!("      {:<28} {:>5} results", worker, results);
+        }
+        for (worker, rejected) in &tally.rejected {
+            println!("      {:<28} {:>5} results refused (other ELF)", worker, rejected);
+        }
+        println!("   Total time: {:.1}s", elapsed.as_secs_f64());
+        println!();
+        println!("💾 All results logged to artifacts/summary.csv");
+    }
+}
+
+/// Execute tasks from the coordinator at `addr` until the campaign is finished
+pub fn work(addr: &str, options: WorkerOptions, retry: &RetryPolicy) -> Result<()> {
+    let WorkerOptions {
+        token,
+        name,
+        batch_size,
+        skip_build,
+        allow_stale_elf,
+        sandbox,
+    } = options;
+    let worker = name.unwrap_or_else(default_worker_name);
+    let token = token_from(token)
+        .with_context(|| format!("Pass the coordinator's token with --token or {}", TOKEN_VAR))?;
+    let sp1_version = harness_core::environment::current().sp1_version.clone();
+    let work_dir = std::env::temp_dir().join(format!("harness-worker-{}", std::process::id()));
+    fs::create_dir_all(&work_dir)?;
+
+    println!("🛠️  Worker '{}' connecting to {}", worker, addr);
+    println!();
+
+    // Guests are built once per core, on first use
+    let mut builds: BTreeMap<String, Option<(anyhow::Error, u32)>> = BTreeMap::new();
+    let mut executed = 0;
+    loop {
+        let request = WorkRequest {
+            worker: worker.clone(),
+            max_tasks: batch_size,
+            sp1_version: sp1_version.clone(),
+        };
+        let batch: WorkBatch = post_with_retry(addr, &token, "/work", &request)?;
+        if batch.tasks.is_empty() {
+            if batch.finished {
+                break;
+            }
+            std::thread::sleep(IDLE_POLL);
+            continue;
+        }
+
+        let mut results = Vec::new();
+        for task in batch.tasks {
+            if skip_build && !builds.contains_key(&task.core) {
+                harness_core::provenance::ensure_fresh([task.core.as_str()], allow_stale_elf)?;
+            }
+            let build_error = builds.entry(task.core.clone()).or_insert_with(|| {
+                if skip_build {
+                    return None;
+                }
+                println!("   📦 Building SP1 guest for {}...", task.core);
+                match retry.run("SP1 guest build", || build_sp1_guest(&guest_path_for_core(&task.core))) {
+                    (Ok(build), _) => {
+                        println!("   ✅ SP1 guest built ({})", build.summary());
+                        None
+                    }
+                    (Err(e), retries) => {
+                        println!("   ❌ SP1 guest build failed: {:#}", e);
+                        Some((e, retries))
+                    }
+                }
+            });
+
+            let input_path = work_dir.join(format!("task_{}.json", task.id));
+            harness_core::write_input(&task.core, &task.input, &input_path)?;
+            let elf_path = elf_path_for_core(&task.core);
+            let mut native =
+                retry.run_runner("native", || run_native_runner_sandboxed(&task.core, &input_path, sandbox));
+            let (mut sp1, elf_sha256) = match build_error {
+                Some((e, retries)) => (build_failure_result(e, *retries), None),
+                None => (
+                    retry.run_runner("sp1", || run_sp1_runner(&elf_path, &input_path, &task.core)),
+                    sha256_file(&elf_path),
+                ),
+            };
+            fs::remove_file(&input_path)?;
+
+            // Recorded in the coordinator's run logs
+            native.meta["worker"] = serde_json::json!(worker);
+            sp1.meta["worker"] = serde_json::json!(worker);
+
+            println!(
+                "   ▶️  Task {} ({} {}) | Native: {:?} ({}ms) | SP1: {:?} ({}ms)",
+                task.id, task.core, task.label, native.status, native.elapsed_ms, sp1.status, sp1.elapsed_ms
+            );
+            results.push(TaskResult {
+                id: task.id,
+                native,
+                sp1,
+                elf_sha256,
+            });
+        }
+
+        executed += results.len();
+        let ack: ResultAck = post_with_retry(
+            addr,
+            &token,
+            "/results",
+            &ResultBatch {
+                worker: worker.clone(),
+                sp1_version: sp1_version.clone(),
+                results,
+            },
+        )?;
+        println!("   📨 Reported, {} accepted", ack.accepted);
+        if ack.rejected > 0 {
+            println!(
+                "   🚫 {} result(s) refused: this worker's ELFs differ from the coordinator's (see its log)",
+                ack.rejected
+            );
+        }
+    }
+
+    let _ = fs::remove_dir_all(&work_dir);
+    println!();
+    println!("✅ Campaign finished; this worker executed {} task(s)", executed);
+    Ok(())
+}
+
+/// POST to the coordinator, retrying with backoff while it is unreachable
+fn post_with_retry<T: serde::de::DeserializeOwned>(
+    addr: &str,
+    token: &str,
+    path: &str,
+    body: &impl serde::Serialize,
+) -> Result<T> {
+    let mut backoff = Duration::from_secs(1);
+    for attempt in 1.. {
+        match harness_core::http::post_json(addr, path, Some(token), body, Duration::from_secs(120)) {
+            Ok(reply) => return Ok(reply),
+            Err(e) if attempt < CONNECT_ATTEMPTS => {
+                println!("   ⚠️  {:#} (retrying in {}s)", e, backoff.as_secs());
+                std::thread::sleep(backoff);
+                backoff *= 2;
+            }
+            Err(e) => return Err(e.context(format!("Coordinator {} unreachable", addr))),
+        }
+    }
+    unreachable!()
+}
+
+/// `<hostname>-<pid>`
+fn default_worker_name() -> String {
+    let host = std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|host| host.trim().to_string())
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| "worker".to_string());
+    format!("{}-{}", host, std::process::id())
+}
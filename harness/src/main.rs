@@ -1,17 +1,40 @@
 use anyhow::{Context, Result};
 use chrono::Utc;
-use clap::{Parser, Subcommand};
-use rust_eq_oracle::{compare, RunResult};
-use serde::{Deserialize, Serialize};
+use clap::{Args, Parser, Subcommand};
+use harness_core::{
+    base_input_for_core, build_failure_result, build_sp1_guest, commit_schema_for_core, elf_path_for_core, generate_repro_script,
+    get_max_cycles_for_core, guest_path_for_core, known_cores, log_results_with_checked, new_run_id, parse_cores_arg,
+    run_native_runner_sandboxed, run_sp1_prover, run_sp1_runner_with_encoding,
+    sha256_file,
+    DifferentialOutcome, DifferentialTest, InputEncoding, ReproEnv, RetryPolicy, RunLog, ZKVM_TARGET,
+};
+use harness_core::fuzz::{BucketOptions, FuzzMode, FuzzOptions, InputOptions, InvalidInputs, Redundant};
+use harness_core::properties::PropertyOptions;
+use rust_eq_oracle::compare;
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+
+mod distributed;
+mod serve;
+mod soak;
 
 #[derive(Parser)]
 #[command(name = "harness")]
 #[command(about = "ZKVM differential fuzzing harness")]
 #[command(version)]
 struct Cli {
+    /// Record runs, artifacts and reports under this campaign id (e.g. nightly-sp1-4.0)
+    #[arg(long, global = true)]
+    campaign: Option<String>,
+
+    /// Free-form campaign tag (repeatable; needs --campaign)
+    #[arg(long = "tag", global = true, requires = "campaign")]
+    tags: Vec<String>,
+
+    /// Run the runners inside the pinned container images configured in fuzz.toml
+    #[arg(long, global = true)]
+    container: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -31,6 +54,16 @@ enum Commands {
         /// Skip building the SP1 guest (use existing ELF)
         #[arg(long)]
         skip_build: bool,
+
+        #[command(flatten)]
+        sandbox: SandboxArgs,
+
+        /// Also run native with overflow checks and compare release native, checked native and SP1
+        #[arg(long)]
+        checked_native: bool,
+
+        #[command(flatten)]
+        retry: RetryArgs,
     },
     
     /// Run input mutation fuzzing on one or more cores
@@ -39,776 +72,2162 @@ enum Commands {
         #[arg(short, long)]
         cores: String,
 
-        /// Skip building the SP1 guests (use existing ELFs)
+        #[command(flatten)]
+        build: BuildArgs,
+
+        /// SP1 guests built at the same time before fuzzing starts
+        #[arg(long, default_value_t = 4)]
+        build_jobs: usize,
+
+        #[command(flatten)]
+        sandbox: SandboxArgs,
+
+        /// Generate and validate mutations and estimate the runtime, without executing anything
         #[arg(long)]
-        skip_build: bool,
+        dry_run: bool,
+
+        /// Execute every mutation, even inputs already run against the same core/target/ELF
+        #[arg(long)]
+        no_dedup: bool,
+
+        #[command(flatten)]
+        inputs: InputArgs,
+
+        #[command(flatten)]
+        buckets: BucketArgs,
+
+        /// Runs spent bisecting each pass/diverge flip of a parametric strategy (0 disables)
+        #[arg(long, default_value_t = harness_core::bisect::DEFAULT_MAX_PROBES)]
+        bisect_max_probes: usize,
+
+        #[command(flatten)]
+        retry: RetryArgs,
     },
-}
 
-#[derive(Debug, Serialize, Deserialize)]
-struct RunLog {
-    run_id: String,
-    timestamp: String,
-    core_path: String,
-    input_path: String,
-    native_result: RunResult,
-    sp1_result: RunResult,
-    diff: rust_eq_oracle::Diff,
-}
+    /// Fuzz in rounds for hours or days, re-verifying toolchain, ELFs and a canary run between rounds
+    Soak {
+        /// Core name to fuzz, comma-separated list or "all"
+        #[arg(short, long)]
+        cores: String,
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+        /// Stop starting rounds after this many hours
+        #[arg(long, default_value_t = 24.0)]
+        hours: f64,
 
-    match cli.command {
-        Commands::Run {
-            core,
-            input,
-            skip_build,
-        } => run_differential_test(&core, &input, skip_build),
-        Commands::Fuzz {
-            cores,
-            skip_build,
-        } => run_fuzzing(&cores, skip_build),
-    }
-}
+        /// Stop after this many rounds
+        #[arg(long)]
+        rounds: Option<usize>,
 
-fn run_differential_test(core_path: &PathBuf, input_path: &PathBuf, skip_build: bool) -> Result<()> {
-    println!("🚀 Starting differential test...");
-    println!("   Core: {}", core_path.display());
-    println!("   Input: {}", input_path.display());
-    println!();
+        /// Minutes between environment checks (checked between rounds)
+        #[arg(long, default_value_t = 60)]
+        check_every_mins: u64,
 
-    // Determine guest path (assume convention: adapters/sp1_guest/{core_name}_guest)
-    let core_name = core_path
-        .file_name()
-        .context("Invalid core path")?
-        .to_str()
-        .context("Non-UTF8 core name")?;
-    
-    let guest_path = PathBuf::from(format!("adapters/sp1_guest/{}_guest", core_name));
-    // ELF filename uses hyphens instead of underscores
-    let elf_name = core_name.replace("_", "-");
-    let elf_path = guest_path
-        .join("target/elf-compilation/riscv32im-succinct-zkvm-elf/release")
-        .join(format!("{}-guest", elf_name));
-
-    // Step 1: Build SP1 guest (unless skip_build is set)
-    if !skip_build {
-        println!("📦 Building SP1 guest...");
-        build_sp1_guest(&guest_path)?;
-        println!("   ✅ SP1 guest built\n");
-    } else {
-        println!("⏩ Skipping SP1 guest build\n");
-    }
+        /// Core whose canary input is rerun at every check (default: the first core)
+        #[arg(long)]
+        canary_core: Option<String>,
 
-    // Step 2: Run native runner
-    println!("🏃 Running native...");
-    let native_result = run_native_runner(core_name, input_path)?;
-    println!("   ✅ Native completed in {}ms\n", native_result.elapsed_ms);
+        /// Canary input (default: the canary core's base input)
+        #[arg(long)]
+        canary_input: Option<PathBuf>,
 
-    // Step 3: Run SP1 runner
-    println!("🏃 Running SP1...");
-    let sp1_result = run_sp1_runner(&elf_path, input_path, core_name)?;
-    println!("   ✅ SP1 completed in {}ms\n", sp1_result.elapsed_ms);
+        /// Stop the soak, or notify the fuzz.toml hooks and continue
+        #[arg(long, value_enum, default_value_t = soak::OnDrift::Abort)]
+        on_drift: soak::OnDrift,
 
-    // Step 4: Compare results
-    println!("🔍 Comparing results...");
-    let diff = compare(&native_result, &sp1_result);
+        #[command(flatten)]
+        build: BuildArgs,
 
-    if diff.equal {
-        println!("   ✅ PASS - Results match!");
-        if let Some(delta) = diff.timing_delta_ms {
-            println!("   📊 Timing delta: {}ms", delta);
-        }
-    } else {
-        println!("   ❌ FAIL - Results differ!");
-        if let Some(reason) = &diff.reason {
-            println!("   📋 Reason: {}", reason);
-        }
-    }
-    println!();
+        /// SP1 guests built at the same time before the first round
+        #[arg(long, default_value_t = 4)]
+        build_jobs: usize,
 
-    // Step 5: Log results
-    println!("💾 Logging results...");
-    log_results(core_path, input_path, native_result, sp1_result, diff)?;
-    println!("   ✅ Results logged to artifacts/\n");
+        #[command(flatten)]
+        sandbox: SandboxArgs,
 
-    Ok(())
-}
+        /// Skip inputs already executed (later rounds then only run inputs new to the corpus)
+        #[arg(long)]
+        dedup: bool,
 
-fn build_sp1_guest(guest_path: &PathBuf) -> Result<()> {
-    let status = Command::new("cargo")
-        .args(["prove", "build"])
-        .current_dir(guest_path)
-        .status()
-        .context("Failed to execute cargo prove build")?;
+        #[command(flatten)]
+        inputs: InputArgs,
 
-    if !status.success() {
-        anyhow::bail!("cargo prove build failed");
-    }
+        #[command(flatten)]
+        buckets: BucketArgs,
 
-    Ok(())
-}
+        /// Runs spent bisecting each pass/diverge flip of a parametric strategy (0 disables)
+        #[arg(long, default_value_t = harness_core::bisect::DEFAULT_MAX_PROBES)]
+        bisect_max_probes: usize,
 
-fn run_native_runner(core_name: &str, input_path: &PathBuf) -> Result<RunResult> {
-    let output = Command::new("cargo")
-        .args(["run", "--release", "--bin", "native-runner", "--"])
-        .args(["--core", core_name])
-        .args(["--input", input_path.to_str().unwrap()])
-        .output()
-        .context("Failed to run native-runner")?;
+        #[command(flatten)]
+        retry: RetryArgs,
+    },
 
-    if !output.status.success() {
-        anyhow::bail!(
-            "native-runner failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        );
-    }
+    /// Plan a fuzz campaign and distribute its mutations to `harness worker` processes
+    Coordinate {
+        /// Core name to fuzz, comma-separated list or "all"
+        #[arg(short, long)]
+        cores: String,
 
-    let result: RunResult = serde_json::from_slice(&output.stdout)
-        .context("Failed to parse native-runner output")?;
+        /// Address workers connect to (e.g. 0.0.0.0:8788 to accept other machines)
+        #[arg(long, default_value = "127.0.0.1:8788")]
+        addr: String,
 
-    Ok(result)
-}
+        /// Token workers must present (default: HARNESS_WORKER_TOKEN, else a generated one is printed)
+        #[arg(long)]
+        token: Option<String>,
 
-fn run_sp1_runner(elf_path: &PathBuf, input_path: &PathBuf, core_name: &str) -> Result<RunResult> {
-    // Determine number of commits based on core
-    let num_commits = match core_name {
-        "fib" => 3,
-        "panic_test" => 2,
-        "timeout_test" => 1,
-        "io_echo" => 3,          // length, first_byte, last_byte
-        "arithmetic" => 2,       // result, overflowed
-        "simple_struct" => 4,    // field1_echo, field2_len, field2_chars, field3_echo
-        _ => {
-            // For unknown cores, don't specify (will try to read until exhausted)
-            0
-        }
-    };
+        #[command(flatten)]
+        inputs: InputArgs,
 
-    let mut cmd = Command::new("cargo");
-    cmd.args(["run", "--release", "--bin", "sp1-runner", "--"])
-        .args(["--elf", elf_path.to_str().unwrap()])
-        .args(["--input", input_path.to_str().unwrap()]);
+        /// Seconds a worker may hold a task before it is handed to another worker
+        #[arg(long, default_value_t = 600)]
+        lease_timeout_secs: u64,
 
-    // Add num-commits if known
-    if num_commits > 0 {
-        cmd.args(["--num-commits", &num_commits.to_string()]);
-    }
+        #[command(flatten)]
+        build: BuildArgs,
 
-    let output = cmd
-        .output()
-        .context("Failed to run sp1-runner")?;
+        /// SP1 guests built at the same time before serving
+        #[arg(long, default_value_t = 4)]
+        build_jobs: usize,
 
-    if !output.status.success() {
-        anyhow::bail!(
-            "sp1-runner failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        );
-    }
+        #[command(flatten)]
+        retry: RetryArgs,
+    },
 
-    let result: RunResult = serde_json::from_slice(&output.stdout)
-        .context("Failed to parse sp1-runner output")?;
+    /// Execute mutations from a `harness coordinate` coordinator
+    Worker {
+        /// Coordinator address (host:port)
+        #[arg(long)]
+        connect: String,
 
-    Ok(result)
-}
+        /// The coordinator's token (default: HARNESS_WORKER_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
 
-fn log_results(
-    core_path: &PathBuf,
-    input_path: &PathBuf,
-    native_result: RunResult,
-    sp1_result: RunResult,
-    diff: rust_eq_oracle::Diff,
-) -> Result<()> {
-    // Create artifacts directory if it doesn't exist
-    fs::create_dir_all("artifacts")?;
-
-    // Generate run ID
-    let timestamp = Utc::now();
-    let run_id = format!(
-        "{}_{}",
-        timestamp.format("%Y%m%d_%H%M%S"),
-        core_path
-            .file_name()
-            .unwrap()
-            .to_str()
-            .unwrap()
-    );
+        /// Worker name in the coordinator's logs (default: <hostname>-<pid>)
+        #[arg(long)]
+        name: Option<String>,
 
-    // Create run log
-    let log = RunLog {
-        run_id: run_id.clone(),
-        timestamp: timestamp.to_rfc3339(),
-        core_path: core_path.display().to_string(),
-        input_path: input_path.display().to_string(),
-        native_result: native_result.clone(),
-        sp1_result: sp1_result.clone(),
-        diff: diff.clone(),
-    };
+        /// Mutations to request at a time
+        #[arg(long, default_value_t = 4)]
+        batch_size: usize,
 
-    // Write detailed JSON log
-    let log_path = PathBuf::from("artifacts").join(format!("{}.json", run_id));
-    let log_json = serde_json::to_string_pretty(&log)?;
-    fs::write(&log_path, &log_json)?;
+        #[command(flatten)]
+        build: BuildArgs,
 
-    println!("   📄 Detailed log: {}", log_path.display());
+        #[command(flatten)]
+        sandbox: SandboxArgs,
 
-    // If there's a divergence, create a repro folder
-    if !diff.equal {
-        let repro_dir = PathBuf::from("artifacts").join(&run_id);
-        fs::create_dir_all(&repro_dir)?;
+        #[command(flatten)]
+        retry: RetryArgs,
+    },
 
-        // Generate repro script
-        let repro_script = generate_repro_script(core_path, input_path);
-        let repro_path = repro_dir.join("repro.sh");
-        fs::write(&repro_path, repro_script)?;
+    /// Package a run into a self-contained tar.gz (input, run log, ELF, commit schema, repro script)
+    Bundle {
+        /// Run ID (e.g., "20251021_041009_panic_test_01jacx...-0000")
+        run_id: String,
 
-        // Make script executable (Unix only)
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = fs::metadata(&repro_path)?.permissions();
-            perms.set_mode(0o755);
-            fs::set_permissions(&repro_path, perms)?;
-        }
+        /// Output path (default: artifacts/bundles/<run_id>.tar.gz)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 
-        // Copy input file to repro folder
-        let input_copy = repro_dir.join("input.json");
-        fs::copy(input_path, &input_copy)?;
+    /// Render a divergence as GitHub issue markdown (environment, input, results, diff, repro steps)
+    ExportIssue {
+        /// Divergence signature as listed by `harness stats` (e.g. "fib|Ok|Panic|status mismatch"), or a run ID
+        fingerprint: String,
+
+        /// Write the markdown here instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 
-        // Write detailed log to repro folder as well
-        let log_copy = repro_dir.join("run_log.json");
-        fs::write(&log_copy, log_json)?;
+    /// Export the logged divergences as a SARIF 2.1.0 log for code-scanning and triage tools
+    ExportSarif {
+        /// Output path
+        #[arg(short, long, default_value = "artifacts/reports/divergences.sarif")]
+        output: PathBuf,
+    },
 
-        println!("   🔧 Repro folder: {}", repro_dir.display());
-    }
+    /// Run one input through SP1 twice (JSON bytes vs typed SP1Stdin writes) and compare
+    EncodingDiff {
+        /// Core name (e.g., "simple_struct")
+        #[arg(short, long)]
+        core: String,
 
-    // Append to CSV summary
-    append_to_csv_summary(&run_id, core_path, input_path, &native_result, &sp1_result, &diff)?;
+        /// Path to input JSON file
+        #[arg(short, long)]
+        input: PathBuf,
 
-    Ok(())
-}
+        /// Skip building the SP1 guest (use existing ELF)
+        #[arg(long)]
+        skip_build: bool,
 
-/// Generate a repro script for the given test case
-fn generate_repro_script(core_path: &PathBuf, input_path: &PathBuf) -> String {
-    format!(
-        r#"#!/usr/bin/env bash
-# Repro script generated by zk-fuzz-lab harness
-# Run this script from the repository root
+        #[command(flatten)]
+        retry: RetryArgs,
+    },
 
-set -e
+    /// Prove each core's base input and record proof size, shards, proving and verification time
+    BenchProve {
+        /// Core name, comma-separated list or "all"
+        #[arg(short, long, default_value = "all")]
+        cores: String,
 
-echo "🔁 Reproducing differential test..."
-echo "   Core: {core}"
-echo "   Input: {input}"
-echo ""
+        /// Input to prove instead of the core's base input (repeatable)
+        #[arg(short, long)]
+        input: Vec<PathBuf>,
 
-# Run the differential test
-make run CORE={core} INPUT={input}
-"#,
-        core = core_path.display(),
-        input = input_path.display(),
-    )
-}
+        /// Proof type to generate (core, compressed, groth16, plonk)
+        #[arg(long, default_value = "compressed")]
+        proof_mode: harness_core::bench::ProofMode,
 
-/// Get SP1 version string
-fn get_sp1_version() -> String {
-    Command::new("cargo")
-        .args(["prove", "--version"])
-        .output()
-        .ok()
-        .and_then(|output| {
-            if output.status.success() {
-                String::from_utf8(output.stdout).ok()
-            } else {
-                None
-            }
-        })
-        .map(|s| s.trim().to_string())
-        .unwrap_or_else(|| "unknown".to_string())
-}
-
-/// Get rustc version string
-fn get_rustc_version() -> String {
-    Command::new("rustc")
-        .args(["--version"])
-        .output()
-        .ok()
-        .and_then(|output| {
-            if output.status.success() {
-                String::from_utf8(output.stdout).ok()
-            } else {
-                None
-            }
-        })
-        .map(|s| s.trim().to_string())
-        .unwrap_or_else(|| "unknown".to_string())
-}
-
-/// Append run results to CSV summary
-fn append_to_csv_summary(
-    run_id: &str,
-    core_path: &PathBuf,
-    input_path: &PathBuf,
-    native_result: &RunResult,
-    sp1_result: &RunResult,
-    diff: &rust_eq_oracle::Diff,
-) -> Result<()> {
-    let csv_path = PathBuf::from("artifacts/summary.csv");
-    
-    // Check if file exists to determine if we need to write header
-    let needs_header = !csv_path.exists();
-
-    // Open file in append mode
-    let file = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&csv_path)?;
-
-    let mut writer = csv::Writer::from_writer(file);
-
-    // Write header if this is a new file
-    if needs_header {
-        writer.write_record(&[
-            "run_id",
-            "core",
-            "input",
-            "native_status",
-            "sp1_status",
-            "equal",
-            "reason",
-            "elapsed_native_ms",
-            "elapsed_sp1_ms",
-            "timing_delta_ms",
-            // Phase 4: Future-proofing columns
-            "repro_path",
-            "generator",
-            "base_seed",
-            "mutation_ops",
-            "rng_seed",
-            "zkvm_target",
-            "sp1_version",
-            "rustc_version",
-        ])?;
-    }
-
-    // Determine repro_path (artifacts/<run_id>/ if divergence, empty otherwise)
-    let repro_path = if !diff.equal {
-        format!("artifacts/{}/", run_id)
-    } else {
-        String::new()
-    };
+        /// Skip building the SP1 guests (use existing ELFs)
+        #[arg(long)]
+        skip_build: bool,
 
-    // Get version strings (cached for performance in future batch runs)
-    let sp1_version = get_sp1_version();
-    let rustc_version = get_rustc_version();
-
-    // Write data row
-    writer.write_record(&[
-        run_id,
-        &core_path.file_name().unwrap().to_str().unwrap(),
-        &input_path.display().to_string(),
-        &format!("{:?}", native_result.status),
-        &format!("{:?}", sp1_result.status),
-        &diff.equal.to_string(),
-        &diff.reason.clone().unwrap_or_else(|| "".to_string()),
-        &native_result.elapsed_ms.to_string(),
-        &sp1_result.elapsed_ms.to_string(),
-        &diff.timing_delta_ms.map(|d| d.to_string()).unwrap_or_else(|| "".to_string()),
-        // Phase 4: Future-proofing columns
-        &repro_path,
-        "hand_written",  // generator (Phase 5 will populate with "mutated", Phase 6 with "rustsmith")
-        "",              // base_seed (empty for now, Phase 5 will populate)
-        "",              // mutation_ops (empty for now, Phase 5 will populate)
-        "",              // rng_seed (empty for now, Phase 6 will populate)
-        "sp1",           // zkvm_target (Phase 8 will add risc0, openvm)
-        &sp1_version,
-        &rustc_version,
-    ])?;
-
-    writer.flush()?;
+        #[command(flatten)]
+        retry: RetryArgs,
+    },
 
-    Ok(())
-}
+    /// Run cores against every SP1 version and build profile in fuzz.toml and compare across them
+    Matrix {
+        /// Core name, comma-separated list or "all"
+        #[arg(short, long, default_value = "all")]
+        cores: String,
 
-/// Run input mutation fuzzing on specified cores
-fn run_fuzzing(cores_arg: &str, skip_build: bool) -> Result<()> {
-    // Parse cores argument
-    let available_cores = vec!["fib", "panic_test", "timeout_test", "io_echo", "arithmetic", "simple_struct"];
-    
-    let cores_to_fuzz: Vec<&str> = if cores_arg == "all" {
-        available_cores.clone()
-    } else {
-        cores_arg.split(',').map(|s| s.trim()).collect()
-    };
+        /// Only these SP1 versions from fuzz.toml (comma-separated)
+        #[arg(long)]
+        versions: Option<String>,
 
-    // Validate cores
-    for core in &cores_to_fuzz {
-        if !available_cores.contains(core) {
-            anyhow::bail!(
-                "Unknown core: '{}'\n\nAvailable cores: {}",
-                core,
-                available_cores.join(", ")
-            );
-        }
-    }
+        /// Only these build profiles from fuzz.toml (comma-separated)
+        #[arg(long)]
+        profiles: Option<String>,
 
-    println!("🔄 Starting input mutation fuzzing...");
-    println!("   Cores: {}", cores_to_fuzz.join(", "));
-    println!();
+        /// Input JSON file (default: each core's base input)
+        #[arg(short, long)]
+        input: Option<PathBuf>,
 
-    let mut total_mutations = 0;
-    let mut total_passed = 0;
-    let mut total_divergences = 0;
-    let overall_start = std::time::Instant::now();
+        /// Skip building the SP1 guests (reuse ELFs from a previous matrix run)
+        #[arg(long)]
+        skip_build: bool,
 
-    // Fuzz each core
-    for core_name in cores_to_fuzz {
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-        println!("📦 Core: {}", core_name);
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-        println!();
+        #[command(flatten)]
+        sandbox: SandboxArgs,
 
-        let result = fuzz_single_core(core_name, skip_build)?;
-        
-        total_mutations += result.total;
-        total_passed += result.passed;
-        total_divergences += result.divergences;
+        #[command(flatten)]
+        retry: RetryArgs,
+    },
 
-        println!();
-    }
+    /// Check the algebraic properties cores declare in their manifest, on each target separately
+    Properties {
+        /// Core name, comma-separated list or "all" (cores without properties are skipped)
+        #[arg(short, long, default_value = "all")]
+        cores: String,
 
-    let overall_elapsed = overall_start.elapsed();
+        /// Input JSON file (default: each core's base input and its mutations)
+        #[arg(short, long)]
+        input: Option<PathBuf>,
 
-    // Overall summary
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("🎯 Fuzzing Complete!");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!();
-    println!("📊 Overall Summary:");
-    println!("   Total mutations: {}", total_mutations);
-    println!("   Passed: {} ({:.1}%)", total_passed, (total_passed as f64 / total_mutations as f64) * 100.0);
-    println!("   Divergences: {} ({:.1}%)", total_divergences, (total_divergences as f64 / total_mutations as f64) * 100.0);
-    println!("   Total time: {:.1}s", overall_elapsed.as_secs_f64());
-    println!();
-    println!("💾 All results logged to artifacts/summary.csv");
-    
-    if total_divergences > 0 {
-        println!("   🔧 Divergence artifacts in artifacts/");
-    }
+        #[command(flatten)]
+        build: BuildArgs,
 
-    Ok(())
-}
+        #[command(flatten)]
+        sandbox: SandboxArgs,
 
-#[derive(Debug)]
-struct FuzzResult {
-    total: usize,
-    passed: usize,
-    divergences: usize,
-}
+        #[command(flatten)]
+        retry: RetryArgs,
+    },
 
-/// Fuzz a single core with input mutations
-fn fuzz_single_core(core_name: &str, skip_build: bool) -> Result<FuzzResult> {
-    // Determine base input path for this core
-    let base_input_path = get_base_input_for_core(core_name)?;
-    
-    println!("   Base input: {}", base_input_path.display());
+    /// Run each input several times per target and flag cores whose commits vary between runs
+    Purity {
+        /// Core name, comma-separated list or "all"
+        #[arg(short, long, default_value = "all")]
+        cores: String,
 
-    // Load base input
-    let base_input_json: serde_json::Value = serde_json::from_slice(&fs::read(&base_input_path)?)?;
+        /// Input JSON file (default: each core's base input and its mutations)
+        #[arg(short, long)]
+        input: Option<PathBuf>,
 
-    // Generate mutations
-    println!("   Generating mutations...");
-    let mutations = source_mutator::generate_mutations(
-        core_name,
-        &base_input_json,
-        base_input_path.to_str().unwrap(),
-    )?;
+        /// Runs per input and target
+        #[arg(long, default_value = "3")]
+        repeats: usize,
 
-    println!("   ✅ Generated {} mutations", mutations.len());
+        #[command(flatten)]
+        build: BuildArgs,
 
-    // Calculate and display statistics
-    if core_name == "io_echo" {
-        let stats = source_mutator::calculate_size_stats(&mutations);
-        println!();
-        println!("   📊 Size Distribution:");
-        println!("      Min: {} bytes", stats.min_size.unwrap_or(0));
-        println!("      Max: {} bytes", stats.max_size.unwrap_or(0));
-        if let Some(max) = stats.max_size {
-            if max >= 1024 {
-                println!("           ({:.2} KB)", max as f64 / 1024.0);
-            }
-            if max >= 1048576 {
-                println!("           ({:.2} MB)", max as f64 / 1048576.0);
-            }
-        }
-        println!("      Total sizes: {}", stats.total_count);
-    }
+        #[command(flatten)]
+        sandbox: SandboxArgs,
 
-    println!();
-    println!("   🧪 Testing mutations...");
-    println!();
+        #[command(flatten)]
+        retry: RetryArgs,
+    },
 
-    // Create artifacts directory for this fuzzing run
-    let timestamp = Utc::now();
-    let fuzz_run_id = format!("{}_fuzz_{}", timestamp.format("%Y%m%d_%H%M%S"), core_name);
-    let fuzz_artifacts_dir = PathBuf::from("artifacts/mutations").join(&fuzz_run_id);
-    fs::create_dir_all(&fuzz_artifacts_dir)?;
-
-    // Save mutation plan
-    let plan_path = fuzz_artifacts_dir.join("plan.json");
-    let plan_json = serde_json::to_string_pretty(&mutations.iter().map(|m| {
-        serde_json::json!({
-            "mutation_op": &m.mutation_op,
-            "base": &m.base_input_path,
-        })
-    }).collect::<Vec<_>>())?;
-    fs::write(&plan_path, plan_json)?;
+    /// Run a core on a geometric sweep of input sizes and fit how its cycles and time grow
+    Scale {
+        /// Core name (its manifest must declare a `bytes:<field>` preprocessing step)
+        #[arg(short, long)]
+        core: String,
 
-    let mut passed = 0;
-    let mut divergences = 0;
-    let mut native_times = Vec::new();
-    let mut sp1_times = Vec::new();
+        /// Smallest input size in bytes (suffixes k, m, g)
+        #[arg(long, default_value = "1k")]
+        from: String,
 
-    let core_path = PathBuf::from(format!("guest/cores/{}", core_name));
+        /// Largest input size in bytes (suffixes k, m, g)
+        #[arg(long, default_value = "1m")]
+        to: String,
 
-    // Build SP1 guest once (unless skip_build)
-    if !skip_build {
-        let guest_path = PathBuf::from(format!("adapters/sp1_guest/{}_guest", core_name));
-        println!("   📦 Building SP1 guest for {}...", core_name);
-        build_sp1_guest(&guest_path)?;
-        println!("   ✅ SP1 guest built");
-        println!();
-    }
+        /// Growth factor between consecutive sizes
+        #[arg(long, default_value = "2")]
+        factor: f64,
 
-    // Test each mutation
-    for (idx, mutation) in mutations.iter().enumerate() {
-        let mutation_num = idx + 1;
-        let total = mutations.len();
+        /// Byte-array field to size (default: the core's first `bytes:<field>` step)
+        #[arg(long)]
+        field: Option<String>,
 
-        // Save mutated input temporarily
-        let temp_input_path = fuzz_artifacts_dir.join(format!("input_{}.json", mutation_num));
-        fs::write(&temp_input_path, serde_json::to_string_pretty(&mutation.input_json)?)?;
+        #[command(flatten)]
+        build: BuildArgs,
 
-        // Run differential test
-        let native_result = run_native_runner(core_name, &temp_input_path)?;
-        let elf_name = core_name.replace("_", "-");
-        let elf_path = PathBuf::from(format!("adapters/sp1_guest/{}_guest", core_name))
-            .join("target/elf-compilation/riscv32im-succinct-zkvm-elf/release")
-            .join(format!("{}-guest", elf_name));
-        let sp1_result = run_sp1_runner(&elf_path, &temp_input_path, core_name)?;
+        #[command(flatten)]
+        sandbox: SandboxArgs,
 
-        // Compare
-        let diff = compare(&native_result, &sp1_result);
+        #[command(flatten)]
+        retry: RetryArgs,
+    },
 
-        // Track stats
-        native_times.push(native_result.elapsed_ms);
-        sp1_times.push(sp1_result.elapsed_ms);
+    /// Re-run the summary.csv rows matching the filters and check their recorded outcomes
+    ReplayCsv {
+        /// Row filter, `column=value` or `column!=value` (repeatable; all must match)
+        #[arg(short, long)]
+        filter: Vec<String>,
 
-        if diff.equal {
-            passed += 1;
-        } else {
-            divergences += 1;
-        }
+        /// Summary to replay
+        #[arg(long, default_value = "artifacts/summary.csv")]
+        csv: PathBuf,
 
-        // Display progress
-        let status_icon = if diff.equal { "✅" } else { "❌" };
-        println!(
-            "   {} Mutation {}/{}: {} | Native: {:?} ({}ms) | SP1: {:?} ({}ms) | Equal: {}",
-            status_icon,
-            mutation_num,
-            total,
-            mutation.mutation_op,
-            native_result.status,
-            native_result.elapsed_ms,
-            sp1_result.status,
-            sp1_result.elapsed_ms,
-            diff.equal,
-        );
+        /// Replay at most this many rows (the first that match)
+        #[arg(long)]
+        limit: Option<usize>,
 
-        if !diff.equal {
-            if let Some(reason) = &diff.reason {
-                println!("      Reason: {}", reason);
-            }
+        #[command(flatten)]
+        build: BuildArgs,
+
+        #[command(flatten)]
+        sandbox: SandboxArgs,
+
+        #[command(flatten)]
+        retry: RetryArgs,
+    },
+
+    /// Re-run a differential test whenever the core, its adapter or the input changes
+    Watch {
+        /// Path to the core (e.g., guest/cores/fib)
+        #[arg(short, long)]
+        core: PathBuf,
+
+        /// Path to input JSON file
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// How often to check for changes, in milliseconds
+        #[arg(long, default_value = "500")]
+        interval_ms: u64,
+
+        /// Skip building the SP1 guest on the first run (use existing ELF)
+        #[arg(long)]
+        skip_build: bool,
+
+        #[command(flatten)]
+        sandbox: SandboxArgs,
+
+        /// Also run native with overflow checks and compare release native, checked native and SP1
+        #[arg(long)]
+        checked_native: bool,
+
+        /// Log every run to artifacts/ like `harness run`
+        #[arg(long)]
+        log: bool,
+
+        #[command(flatten)]
+        retry: RetryArgs,
+    },
+
+    /// Reproduce a logged run (cross-platform replacement for repro.sh)
+    Repro {
+        /// Run ID, or a directory holding run_log.json and input.json (e.g., an extracted bundle)
+        run_id: String,
+
+        /// Rebuild the SP1 guest before reproducing (default: reuse the existing ELF)
+        #[arg(long)]
+        rebuild: bool,
+
+        #[command(flatten)]
+        retry: RetryArgs,
+    },
+
+    /// Check the local setup: SP1 toolchain, guest builds, a known-answer round trip and timeouts
+    Doctor {
+        /// Check the existing ELFs instead of building the guests
+        #[arg(long)]
+        skip_build: bool,
+
+        #[command(flatten)]
+        retry: RetryArgs,
+    },
+
+    /// List cores (with commit schemas and base inputs), zkVM targets and mutation strategies
+    List {
+        /// Print JSON instead of tables
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Summarize logged runs (artifacts/runs.jsonl), optionally of one --campaign
+    Stats {
+        /// Fit cycles-vs-input-size cost models and flag superlinear zkVM cost
+        #[arg(long)]
+        cost_model: bool,
+
+        /// Flag runs whose SP1 opcode mix breaks from similar-sized inputs of the same core
+        #[arg(long)]
+        opcode_mix: bool,
+    },
+
+    /// List named campaigns with their tags and logged runs
+    Campaigns,
+
+    /// Serve an HTTP API to start/stop campaigns, query stats and divergences, and download bundles
+    Serve {
+        /// Address to listen on (requests need the token printed at startup, or HARNESS_API_TOKEN)
+        #[arg(long, default_value = "127.0.0.1:8787")]
+        addr: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Args)]
+struct InputArgs {
+    /// Sample inputs with the mutators, or enumerate the whole value grid (sample, exhaustive)
+    #[arg(long, default_value = "sample")]
+    mode: FuzzMode,
+
+    /// Seed for cores whose manifest declares an input grammar (logged as rng_seed)
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Exhaustive mode: evenly spaced values per numeric field, besides its boundary values
+    #[arg(long, default_value_t = 8)]
+    resolution: usize,
+
+    /// Exhaustive mode: most inputs per core (a larger grid is an error, never truncated)
+    #[arg(long, default_value_t = 4096)]
+    budget: usize,
+
+    /// Inputs that do not deserialize into the core's input type: abort the
+    /// campaign (a generator bug), skip them, or run them on purpose (fail, skip, run)
+    #[arg(long, default_value = "fail")]
+    invalid_inputs: InvalidInputs,
+}
+
+impl From<InputArgs> for InputOptions {
+    fn from(args: InputArgs) -> Self {
+        InputOptions {
+            mode: args.mode,
+            seed: args.seed,
+            resolution: args.resolution,
+            budget: args.budget,
+            invalid_inputs: args.invalid_inputs,
         }
+    }
+}
 
-        // Log to CSV with mutation metadata
-        log_mutation_result(
-            &core_path,
-            &temp_input_path,
-            native_result,
-            sp1_result,
-            diff,
-            &mutation.mutation_op,
-            &mutation.base_input_path,
-        )?;
+/// Commit-stream bucketing (see `harness_core::buckets`)
+#[derive(Debug, Clone, Copy, Args)]
+struct BucketArgs {
+    /// Run every input natively first and send at most this many inputs per
+    /// distinct native commit stream to SP1 first (0 disables)
+    #[arg(long, default_value_t = 0)]
+    commit_buckets: usize,
+
+    /// With --commit-buckets: run the other inputs of a bucket on SP1 last, or skip them (defer, skip)
+    #[arg(long, default_value = "defer")]
+    redundant: Redundant,
+}
+
+impl From<BucketArgs> for BucketOptions {
+    fn from(args: BucketArgs) -> Self {
+        BucketOptions {
+            commit_buckets: args.commit_buckets,
+            redundant: args.redundant,
+        }
     }
+}
 
-    // Calculate timing stats
-    let native_avg = native_times.iter().sum::<u128>() as f64 / native_times.len() as f64;
-    let sp1_avg = sp1_times.iter().sum::<u128>() as f64 / sp1_times.len() as f64;
-    let native_max = native_times.iter().max().unwrap_or(&0);
-    let sp1_max = sp1_times.iter().max().unwrap_or(&0);
+/// Reusing guest ELFs instead of building them
+#[derive(Debug, Clone, Copy, Args)]
+struct BuildArgs {
+    /// Skip building the SP1 guests (use existing ELFs)
+    #[arg(long)]
+    skip_build: bool,
 
-    println!();
-    println!("   📊 Timing Statistics:");
-    println!("      Native: avg {:.1}ms, max {}ms", native_avg, native_max);
-    println!("      SP1: avg {:.1}ms, max {}ms", sp1_avg, sp1_max);
-    println!();
-    println!("   ✅ Core '{}' fuzzing complete!", core_name);
-    println!("      Total: {}", mutations.len());
-    println!("      Passed: {} ({:.1}%)", passed, (passed as f64 / mutations.len() as f64) * 100.0);
-    println!("      Divergences: {}", divergences);
-
-    Ok(FuzzResult {
-        total: mutations.len(),
-        passed,
-        divergences,
-    })
-}
-
-/// Get the base input path for a given core
-fn get_base_input_for_core(core_name: &str) -> Result<PathBuf> {
-    let base_input = match core_name {
-        "fib" => "inputs/fib_24.json",
-        "panic_test" => "inputs/panic_no.json",
-        "timeout_test" => "inputs/timeout_finite.json",
-        "io_echo" => "inputs/io_echo_1kb.json",
-        "arithmetic" => "inputs/arithmetic_add_normal.json",
-        "simple_struct" => "inputs/simple_struct_normal.json",
-        _ => anyhow::bail!("Unknown core: {}", core_name),
-    };
-    Ok(PathBuf::from(base_input))
-}
-
-/// Log mutation result to CSV with mutation metadata
-fn log_mutation_result(
-    core_path: &PathBuf,
-    input_path: &PathBuf,
-    native_result: RunResult,
-    sp1_result: RunResult,
-    diff: rust_eq_oracle::Diff,
-    mutation_op: &str,
-    base_input_path: &str,
-) -> Result<()> {
-    let csv_path = PathBuf::from("artifacts/summary.csv");
-    
-    // Check if file exists to determine if we need to write header
-    let needs_header = !csv_path.exists();
-
-    // Open file in append mode
-    let file = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&csv_path)?;
-
-    let mut writer = csv::Writer::from_writer(file);
-
-    // Write header if this is a new file
-    if needs_header {
-        writer.write_record(&[
-            "run_id",
-            "core",
-            "input",
-            "native_status",
-            "sp1_status",
-            "equal",
-            "reason",
-            "elapsed_native_ms",
-            "elapsed_sp1_ms",
-            "timing_delta_ms",
-            // Phase 4: Future-proofing columns
-            "repro_path",
-            "generator",
-            "base_seed",
-            "mutation_ops",
-            "rng_seed",
-            "zkvm_target",
-            "sp1_version",
-            "rustc_version",
-        ])?;
-    }
-
-    // Generate run ID
-    let timestamp = Utc::now();
-    let run_id = format!(
-        "{}_{}",
-        timestamp.format("%Y%m%d_%H%M%S"),
-        core_path.file_name().unwrap().to_str().unwrap()
-    );
+    /// With --skip-build, run even if an ELF is older than its guest sources
+    #[arg(long)]
+    allow_stale_elf: bool,
+}
 
-    // Determine repro_path (artifacts/<run_id>/ if divergence, empty otherwise)
-    let repro_path = if !diff.equal {
-        format!("artifacts/{}/", run_id)
-    } else {
-        String::new()
-    };
+/// Environment isolation of native runs (`native-runner --sandbox`)
+#[derive(Debug, Clone, Copy, Args)]
+struct SandboxArgs {
+    /// Run native cores with a cleared environment, fixed locale/TZ and a
+    /// scratch temp directory as cwd. Not a chroot: the filesystem stays
+    /// visible, only relative paths stop resolving against the checkout
+    #[arg(long)]
+    sandbox: bool,
+}
 
-    // Get version strings
-    let sp1_version = get_sp1_version();
-    let rustc_version = get_rustc_version();
+/// Retry policy for infrastructure failures (guest build, runner spawn, SDK setup)
+#[derive(Debug, Clone, Copy, Args)]
+struct RetryArgs {
+    /// Extra attempts after an infrastructure failure
+    #[arg(long, default_value_t = 2)]
+    retries: u32,
 
-    // Convert core name to String to avoid &&str issue
-    let core_name_str = core_path.file_name().unwrap().to_str().unwrap().to_string();
-    
-    // Write data row with mutation metadata
-    writer.write_record(&[
-        &run_id,
-        &core_name_str,
-        &input_path.display().to_string(),
-        &format!("{:?}", native_result.status),
-        &format!("{:?}", sp1_result.status),
-        &diff.equal.to_string(),
-        &diff.reason.clone().unwrap_or_else(|| "".to_string()),
-        &native_result.elapsed_ms.to_string(),
-        &sp1_result.elapsed_ms.to_string(),
-        &diff.timing_delta_ms.map(|d| d.to_string()).unwrap_or_else(|| "".to_string()),
-        // Phase 5: Mutation metadata
-        &repro_path,
-        "mutated",          // generator
-        base_input_path,    // base_seed
-        mutation_op,        // mutation_ops
-        "",                 // rng_seed (empty for deterministic)
-        "sp1",              // zkvm_target
-        &sp1_version,
-        &rustc_version,
-    ])?;
-
-    writer.flush()?;
-
-    // If divergence, create repro folder (same as run_differential_test)
-    if !diff.equal {
-        let repro_dir = PathBuf::from("artifacts").join(&run_id);
-        fs::create_dir_all(&repro_dir)?;
-
-        // Copy input
-        fs::copy(input_path, repro_dir.join("input.json"))?;
-
-        // Generate repro script
-        let repro_script = generate_repro_script(core_path, input_path);
-        let repro_path = repro_dir.join("repro.sh");
-        fs::write(&repro_path, repro_script)?;
-
-        // Make executable on Unix
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = fs::metadata(&repro_path)?.permissions();
-            perms.set_mode(0o755);
-            fs::set_permissions(&repro_path, perms)?;
-        }
-
-        // Write detailed log
-        let log = RunLog {
-            run_id: run_id.clone(),
-            timestamp: timestamp.to_rfc3339(),
-            core_path: core_path.display().to_string(),
-            input_path: input_path.display().to_string(),
-            native_result,
-            sp1_result,
-            diff,
+    /// Delay before the first retry in milliseconds (doubled on each retry)
+    #[arg(long, default_value_t = 500)]
+    retry_backoff_ms: u64,
+}
+
+impl From<RetryArgs> for RetryPolicy {
+    fn from(args: RetryArgs) -> Self {
+        RetryPolicy {
+            retries: args.retries,
+            backoff_ms: args.retry_backoff_ms,
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    // Read-only commands use --campaign as a filter instead of starting a session
+    // (and the API server starts campaigns in child processes)
+    let read_only = matches!(
+        cli.command,
+        Commands::Stats { .. }
+            | Commands::List { .. }
+            | Commands::Campaigns
+            | Commands::Serve { .. }
+            | Commands::ExportIssue { .. }
+            | Commands::ExportSarif { .. }
+    );
+    if let (Some(id), false) = (&cli.campaign, read_only) {
+        let command = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+        let campaign = harness_core::campaign::start(id, &cli.tags, &command)?;
+        if campaign.tags.is_empty() {
+            println!("🏷️  Campaign: {}\n", campaign.id);
+        } else {
+            println!("🏷️  Campaign: {} [{}]\n", campaign.id, campaign.tags.join(", "));
+        }
+    }
+
+    if cli.container {
+        let config = harness_core::matrix::FuzzConfig::load()?;
+        for image in harness_core::container::enable(config.containers)? {
+            println!("🐳 {} runner: {} ({})", image.target, image.image, image.runner);
+            if !image.is_pinned() {
+                println!("   ⚠️  Not pinned by digest; the tag may move between campaigns");
+            }
+        }
+        println!();
+    }
+
+    let result = match cli.command {
+        Commands::Run {
+            core,
+            input,
+            skip_build,
+            sandbox: SandboxArgs { sandbox },
+            checked_native,
+            retry,
+        } => run_differential_test(&core, &input, skip_build, sandbox, checked_native, &retry.into()),
+        Commands::Fuzz {
+            cores,
+            build: BuildArgs { skip_build, allow_stale_elf },
+            build_jobs,
+            sandbox: SandboxArgs { sandbox },
+            dry_run,
+            no_dedup,
+            inputs,
+            buckets,
+            bisect_max_probes,
+            retry,
+        } => {
+            if dry_run {
+                harness_core::fuzz::plan_fuzzing(&cores, &inputs.into())
+            } else {
+                let options = FuzzOptions {
+                    skip_build,
+                    build_jobs,
+                    allow_stale_elf,
+                    sandbox,
+                    dedup: !no_dedup,
+                    inputs: inputs.into(),
+                    buckets: buckets.into(),
+                    bisect_max_probes,
+                };
+                harness_core::fuzz::run_fuzzing(&cores, &options, &retry.into())
+            }
+        }
+        Commands::Soak {
+            cores,
+            hours,
+            rounds,
+            check_every_mins,
+            canary_core,
+            canary_input,
+            on_drift,
+            build: BuildArgs { skip_build, allow_stale_elf },
+            build_jobs,
+            sandbox: SandboxArgs { sandbox },
+            dedup,
+            inputs,
+            buckets,
+            bisect_max_probes,
+            retry,
+        } => soak::soak(
+            &cores,
+            &soak::SoakOptions {
+                fuzz: FuzzOptions {
+                    skip_build,
+                    build_jobs,
+                    allow_stale_elf,
+                    sandbox,
+                    dedup,
+                    inputs: inputs.into(),
+                    buckets: buckets.into(),
+                    bisect_max_probes,
+                },
+                duration: std::time::Duration::from_secs_f64(hours.max(0.0) * 3600.0),
+                max_rounds: rounds,
+                check_every: std::time::Duration::from_secs(check_every_mins * 60),
+                canary_core,
+                canary_input,
+                on_drift,
+            },
+            &retry.into(),
+        ),
+        Commands::Coordinate {
+            cores,
+            addr,
+            token,
+            inputs,
+            lease_timeout_secs,
+            build: BuildArgs { skip_build, allow_stale_elf },
+            build_jobs,
+            retry,
+        } => {
+            let options = distributed::CoordinatorOptions {
+                addr,
+                token,
+                lease_timeout: std::time::Duration::from_secs(lease_timeout_secs),
+                skip_build,
+                allow_stale_elf,
+                build_jobs,
+            };
+            distributed::coordinate(&cores, &inputs.into(), options, &retry.into())
+        }
+        Commands::Worker {
+            connect,
+            token,
+            name,
+            batch_size,
+            build: BuildArgs { skip_build, allow_stale_elf },
+            sandbox: SandboxArgs { sandbox },
+            retry,
+        } => {
+            let options = distributed::WorkerOptions {
+                token,
+                name,
+                batch_size,
+                skip_build,
+                allow_stale_elf,
+                sandbox,
+            };
+            distributed::work(&connect, options, &retry.into())
+        }
+        Commands::Bundle { run_id, output } => bundle_run(&run_id, output),
+        Commands::ExportIssue { fingerprint, output } => export_issue(&fingerprint, output, cli.campaign.as_deref()),
+        Commands::ExportSarif { output } => export_sarif(&output, cli.campaign.as_deref()),
+        Commands::EncodingDiff {
+            core,
+            input,
+            skip_build,
+            retry,
+        } => run_encoding_diff(&core, &input, skip_build, &retry.into()),
+        Commands::BenchProve {
+            cores,
+            input,
+            proof_mode,
+            skip_build,
+            retry,
+        } => bench_prove(&cores, &input, proof_mode, skip_build, &retry.into()),
+        Commands::Matrix {
+            cores,
+            versions,
+            profiles,
+            input,
+            skip_build,
+            sandbox: SandboxArgs { sandbox },
+            retry,
+        } => run_version_matrix(
+            &cores,
+            versions.as_deref(),
+            profiles.as_deref(),
+            input.as_deref(),
+            skip_build,
+            sandbox,
+            &retry.into(),
+        ),
+        Commands::Properties {
+            cores,
+            input,
+            build: BuildArgs { skip_build, allow_stale_elf },
+            sandbox: SandboxArgs { sandbox },
+            retry,
+        } => harness_core::properties::check_properties(
+            &cores,
+            input.as_deref(),
+            PropertyOptions {
+                skip_build,
+                allow_stale_elf,
+                sandbox,
+            },
+            &retry.into(),
+        ),
+        Commands::Purity {
+            cores,
+            input,
+            repeats,
+            build: BuildArgs { skip_build, allow_stale_elf },
+            sandbox: SandboxArgs { sandbox },
+            retry,
+        } => harness_core::purity::check_purity(
+            &cores,
+            input.as_deref(),
+            repeats,
+            PropertyOptions {
+                skip_build,
+                allow_stale_elf,
+                sandbox,
+            },
+            &retry.into(),
+        ),
+        Commands::Scale {
+            core,
+            from,
+            to,
+            factor,
+            field,
+            build: BuildArgs { skip_build, allow_stale_elf },
+            sandbox: SandboxArgs { sandbox },
+            retry,
+        } => harness_core::scale::scale_core(
+            &core,
+            harness_core::scale::sweep(
+                harness_core::scale::parse_size(&from)?,
+                harness_core::scale::parse_size(&to)?,
+                factor,
+            )?,
+            field,
+            PropertyOptions {
+                skip_build,
+                allow_stale_elf,
+                sandbox,
+            },
+            &retry.into(),
+        ),
+        Commands::ReplayCsv {
+            filter,
+            csv,
+            limit,
+            build: BuildArgs { skip_build, allow_stale_elf },
+            sandbox: SandboxArgs { sandbox },
+            retry,
+        } => harness_core::replay::replay_csv(
+            &csv,
+            &filter,
+            limit,
+            PropertyOptions {
+                skip_build,
+                allow_stale_elf,
+                sandbox,
+            },
+            &retry.into(),
+        ),
+        Commands::Watch {
+            core,
+            input,
+            interval_ms,
+            skip_build,
+            sandbox: SandboxArgs { sandbox },
+            checked_native,
+            log,
+            retry,
+        } => watch_core(
+            &core,
+            &input,
+            std::time::Duration::from_millis(interval_ms),
+            WatchOptions {
+                skip_build,
+                sandbox,
+                checked_native,
+                log,
+            },
+            &retry.into(),
+        ),
+        Commands::Repro {
+            run_id,
+            rebuild,
+            retry,
+        } => reproduce_run(&run_id, rebuild, &retry.into()),
+        Commands::Doctor { skip_build, retry } => run_doctor(skip_build, &retry.into()),
+        Commands::List { json } => list_capabilities(json),
+        Commands::Stats { cost_model, opcode_mix } => print_stats(cost_model, opcode_mix, cli.campaign.as_deref()),
+        Commands::Campaigns => list_campaigns(),
+        Commands::Serve { addr } => serve::serve(&addr),
+    };
+    // Runs still queued for the artifact store go out before exiting
+    harness_core::storage::finish();
+    result
+}
+
+fn run_differential_test(
+    core_path: &Path,
+    input_path: &Path,
+    skip_build: bool,
+    sandbox: bool,
+    checked_native: bool,
+    retry: &RetryPolicy,
+) -> Result<()> {
+    println!("🚀 Starting differential test...");
+    println!("   Core: {}", core_path.display());
+    println!("   Input: {}", input_path.display());
+    println!();
+
+    // Steps 1-3: Build SP1 guest, run native and SP1 (logging happens below)
+    let outcome = DifferentialTest::new(core_path, input_path)
+        .skip_build(skip_build)
+        .sandbox(sandbox)
+        .checked_native(checked_native)
+        .retry(*retry)
+        .log_artifacts(false)
+        .verbose(true)
+        .run()?;
+
+    // Step 4: Compare results
+    print_outcome(&outcome);
+
+    // Step 5: Log results
+    println!("💾 Logging results...");
+    log_results_with_checked(
+        core_path,
+        input_path,
+        outcome.native,
+        outcome.sp1,
+        outcome.diff,
+        outcome.checked_native,
+    )?;
+    println!("   ✅ Results logged to artifacts/\n");
+
+    // An input that declares its outcome is a test: failing it is an error
+    if let Some(check) = outcome.expectation.filter(|check| !check.confirmed) {
+        anyhow::bail!("Unexpected result for {}: {}", input_path.display(), check.mismatches.join("; "));
+    }
+    Ok(())
+}
+
+/// Flags `harness watch` passes through to each differential test
+struct WatchOptions {
+    skip_build: bool,
+    sandbox: bool,
+    checked_native: bool,
+    log: bool,
+}
+
+/// Run a differential test, then re-run it whenever the core's source, its
+/// SP1 adapter or the input changes (until interrupted)
+///
+/// The guest is only rebuilt when the core or adapter changed; an edit to
+/// the input alone re-runs with the existing ELF.
+fn watch_core(
+    core_path: &Path,
+    input_path: &Path,
+    interval: std::time::Duration,
+    options: WatchOptions,
+    retry: &RetryPolicy,
+) -> Result<()> {
+    use harness_core::watch::{watch_roots, Snapshot};
+
+    let core_name = core_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .context("Invalid core path")?;
+    let build_roots = watch_roots(core_path, core_name);
+    let input_roots = vec![input_path.to_path_buf()];
+
+    println!("👀 Watching {}...", core_name);
+    println!("   Core: {}", core_path.display());
+    println!("   Adapter: {}", guest_path_for_core(core_name).display());
+    println!("   Input: {}", input_path.display());
+    println!("   Press Ctrl-C to stop");
+    println!();
+
+    let mut build_snapshot = Snapshot::capture(&build_roots);
+    let mut input_snapshot = Snapshot::capture(&input_roots);
+    let mut rebuild = !options.skip_build;
+    loop {
+        println!("━━━ {} ━━━", Utc::now().format("%H:%M:%S"));
+        let outcome = DifferentialTest::new(core_path, input_path)
+            .skip_build(!rebuild)
+            .sandbox(options.sandbox)
+            .checked_native(options.checked_native)
+            .retry(*retry)
+            .log_artifacts(options.log)
+            .verbose(true)
+            .run();
+        match outcome {
+            Ok(outcome) => print_outcome(&outcome),
+            Err(e) => println!("   ❌ {:#}
+", e),
+        }
+        println!("👀 Waiting for changes...");
+
+        // Wait for a change, then until the files stop changing (editors
+        // often write several times per save)
+        let changed = loop {
+            std::thread::sleep(interval);
+            let mut build_now = Snapshot::capture(&build_roots);
+            let mut input_now = Snapshot::capture(&input_roots);
+            if build_now == build_snapshot && input_now == input_snapshot {
+                continue;
+            }
+            loop {
+                std::thread::sleep(interval);
+                let build_later = Snapshot::capture(&build_roots);
+                let input_later = Snapshot::capture(&input_roots);
+                if build_later == build_now && input_later == input_now {
+                    break;
+                }
+                (build_now, input_now) = (build_later, input_later);
+            }
+
+            let mut changed = build_now.changed_since(&build_snapshot);
+            rebuild = !changed.is_empty();
+            changed.extend(input_now.changed_since(&input_snapshot));
+            (build_snapshot, input_snapshot) = (build_now, input_now);
+            break changed;
+        };
+
+        println!();
+        for path in &changed {
+            println!("✏️  {}", path.display());
+        }
+    }
+}
+
+/// Print the comparison of a differential test (and the three-way report if any)
+fn print_outcome(outcome: &DifferentialOutcome) {
+    println!("🔍 Comparing results...");
+
+    if outcome.diff.equal {
+        if outcome.diff.relations.is_empty() {
+            println!("   ✅ PASS - Results match!");
+        } else {
+            println!("   ✅ PASS - Results satisfy the declared relations: {}", outcome.diff.relations.join(", "));
+        }
+        if let Some(delta) = outcome.diff.timing_delta_ms {
+            println!("   📊 Timing delta: {}ms", delta);
+        }
+    } else {
+        println!("   ❌ FAIL - Results differ!");
+        if let Some(reason) = &outcome.diff.reason {
+            println!("   📋 Reason: {}", reason);
+        }
+        if let (Some(prefix), Some(index)) = (outcome.diff.common_prefix_len, outcome.diff.first_divergent_index) {
+            println!("   📍 Common prefix: {} commits, first divergence at index {}", prefix, index);
+        }
+        let public_values = |result: &rust_eq_oracle::RunResult| {
+            result.meta.get("public_values_hex").and_then(|v| v.as_str()).map(str::to_string)
+        };
+        if let (Some(native_pv), Some(sp1_pv)) = (public_values(&outcome.native), public_values(&outcome.sp1)) {
+            if native_pv == sp1_pv {
+                println!("   🔢 Public values bytes match: the commits were decoded differently");
+            } else {
+                println!("   🔢 Public values bytes differ: the guest committed different bytes");
+                println!("      native (expected): {}", native_pv);
+                println!("      sp1:               {}", sp1_pv);
+            }
+        }
+        println!("   🧠 Explanation:");
+        for line in rust_eq_oracle::explain::explain(&outcome.native, &outcome.sp1, &outcome.diff)
+            .to_string()
+            .lines()
+        {
+            println!("      {}", line);
+        }
+    }
+    if let Some(check) = &outcome.expectation {
+        let note = check.expected.note.as_deref().map(|note| format!(" ({})", note)).unwrap_or_default();
+        match (check.confirmed, outcome.diff.equal) {
+            (true, false) => println!("   🎯 Expected divergence confirmed{}", note),
+            (true, true) => println!("   🎯 Expected result confirmed{}", note),
+            (false, _) => {
+                println!("   ⚠️  Unexpected result: the input's `expected` block does not hold{}", note);
+                for mismatch in &check.mismatches {
+                    println!("      - {}", mismatch);
+                }
+            }
+        }
+    }
+    println!();
+
+    if let Some(checked) = &outcome.checked_native {
+        println!("🧷 Release vs checked native vs SP1...");
+        let semantics = &checked.semantics;
+        let icon = match semantics.agreement {
+            rust_eq_oracle::Agreement::AllAgree => "✅",
+            rust_eq_oracle::Agreement::Inconclusive => "⚠️ ",
+            _ => "❌",
+        };
+        println!("   {} {}", icon, semantics.agreement.describe());
+        println!("   checked native: {:?}, commits: {}", checked.result.status, checked.result.commits.len());
+        for (label, diff) in [
+            ("release vs checked", &semantics.release_vs_checked),
+            ("checked vs sp1", &semantics.checked_vs_zkvm),
+        ] {
+            if let Some(reason) = &diff.reason {
+                println!("   📋 {}: {}", label, reason);
+            }
+        }
+        println!();
+    }
+}
+
+/// A logged run, as found by [`load_run`]
+struct LoadedRun {
+    log: RunLog,
+    log_json: Vec<u8>,
+    input_path: PathBuf,
+    /// Folder holding the log (and, for divergences, the repro files)
+    dir: Option<PathBuf>,
+}
+
+/// Find a run's log and input
+///
+/// `run_id` is a run ID (looked up in `artifacts/index.json`, old layouts
+/// included) or a directory holding `run_log.json` (a run folder or an
+/// extracted bundle). Divergences have a copy of the input in their folder;
+/// other runs are rerun with the input path the log recorded.
+fn load_run(run_id: &str) -> Result<LoadedRun> {
+    let (log_path, dir) = if Path::new(run_id).join("run_log.json").is_file() {
+        (Path::new(run_id).join("run_log.json"), Some(PathBuf::from(run_id)))
+    } else {
+        let entry = harness_core::layout::find_run(run_id)?
+            .with_context(|| format!("No run log for '{}' in artifacts/index.json or under artifacts/", run_id))?;
+        (entry.log, entry.dir)
+    };
+    let log_json = fs::read(&log_path)
+        .with_context(|| format!("No run log for '{}' at {}", run_id, log_path.display()))?;
+    let log = harness_core::run_log::parse(&log_json)
+        .with_context(|| format!("Failed to parse {}", log_path.display()))?;
+
+    let input_path = match dir.as_ref().map(|dir| dir.join("input.json")) {
+        Some(copy) if copy.exists() => copy,
+        _ => PathBuf::from(&log.input_path),
+    };
+
+    Ok(LoadedRun { log, log_json, input_path, dir })
+}
+
+/// Reproduce a logged run
+///
+/// Does what `repro.sh` does, without needing bash: checks the SP1/rustc
+/// versions and the guest ELF hash against the recorded environment,
+/// optionally rebuilds the guest, reruns the differential test, and reports
+/// whether the recorded outcome reproduced.
+fn reproduce_run(run_id: &str, rebuild: bool, retry: &RetryPolicy) -> Result<()> {
+    let LoadedRun { log, input_path, .. } = load_run(run_id)?;
+    let core_path = PathBuf::from(&log.core_path);
+    let core_name = core_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .context("Invalid core path in run log")?;
+    let elf_path = elf_path_for_core(core_name);
+
+    println!("🔁 Reproducing run {}...", log.run_id);
+    println!("   Core: {}", core_path.display());
+    println!("   Input: {}", input_path.display());
+    println!();
+
+    if rebuild {
+        println!("📦 Rebuilding SP1 guest...");
+        let (result, _) = retry.run("SP1 guest build", || build_sp1_guest(&guest_path_for_core(core_name)));
+        result?;
+        println!();
+    }
+    if !elf_path.exists() {
+        anyhow::bail!("ELF not found: {} (run with --rebuild)", elf_path.display());
+    }
+
+    println!("🔍 Checking environment...");
+    let current = ReproEnv::capture(&elf_path);
+    let mismatches = match &log.environment {
+        Some(recorded) => {
+            let checks = [
+                ("SP1 version", &recorded.sp1_version, &current.sp1_version),
+                ("rustc version", &recorded.rustc_version, &current.rustc_version),
+                ("zkVM target", &recorded.zkvm_target, &current.zkvm_target),
+            ];
+            let mut mismatches = 0;
+            for (what, recorded, current) in checks {
+                if recorded != current {
+                    println!("   ⚠️  {} differs from the recorded run", what);
+                    println!("      recorded: {}", recorded);
+                    println!("      current:  {}", current);
+                    mismatches += 1;
+                }
+            }
+            if recorded.elf_sha256.is_some() && recorded.elf_sha256 != current.elf_sha256 {
+                println!("   ⚠️  ELF hash differs from the recorded run");
+                println!("      recorded: {}", recorded.elf_sha256.as_deref().unwrap_or("unknown"));
+                println!("      current:  {}", current.elf_sha256.as_deref().unwrap_or("unknown"));
+                mismatches += 1;
+            }
+            if recorded.git_commit.is_some() && recorded.git_commit != current.git_commit {
+                println!("   ⚠️  Source commit differs from the recorded run");
+                println!("      recorded: {}", recorded.describe_revision());
+                println!("      current:  {}", current.describe_revision());
+                mismatches += 1;
+            } else if recorded.git_dirty == Some(true) {
+                println!("   ⚠️  The recorded run had uncommitted source changes, which the commit does not capture");
+            }
+            mismatches
+        }
+        None => {
+            println!("   ⚠️  Run log has no recorded environment");
+            1
+        }
+    };
+    if mismatches > 0 {
+        println!("   ⚠️  {} environment mismatch(es): the result may not reproduce", mismatches);
+    } else {
+        println!("   ✅ Environment matches the recorded run");
+    }
+    println!();
+
+    let outcome = DifferentialTest::new(&core_path, &input_path)
+        .skip_build(true)
+        .retry(*retry)
+        .log_artifacts(false)
+        .verbose(true)
+        .run()?;
+
+    println!("🔍 Comparing with the recorded run...");
+    println!(
+        "   Recorded: native {:?}, sp1 {:?}, equal: {}",
+        log.native_result.status, log.sp1_result.status, log.diff.equal
+    );
+    println!(
+        "   Now:      native {:?}, sp1 {:?}, equal: {}",
+        outcome.native.status, outcome.sp1.status, outcome.diff.equal
+    );
+    let reproduced = outcome.diff.equal == log.diff.equal
+        && outcome.native.status == log.native_result.status
+        && outcome.sp1.status == log.sp1_result.status
+        && outcome.native.commits == log.native_result.commits
+        && outcome.sp1.commits == log.sp1_result.commits;
+    if reproduced {
+        println!("   ✅ Reproduced: same statuses and commits as the recorded run");
+    } else {
+        println!("   ❌ Did not reproduce: the outcome differs from the recorded run");
+    }
+
+    Ok(())
+}
+
+/// Package a run into a single tar.gz that can be handed to a zkVM vendor
+///
+/// The archive holds everything needed to reproduce the run outside this
+/// repo: the input, the run log, the guest ELF, the commit schema (what the
+/// guest commits, in order) and the repro script.
+fn bundle_run(run_id: &str, output: Option<PathBuf>) -> Result<()> {
+    use flate2::{write::GzEncoder, Compression};
+
+    let LoadedRun { log, log_json, input_path, dir } = load_run(run_id)?;
+
+    let core_path = PathBuf::from(&log.core_path);
+    let core_name = core_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .context("Invalid core path in run log")?;
+
+    println!("📦 Bundling run {}...", run_id);
+
+    let input = fs::read(&input_path)
+        .with_context(|| format!("Failed to read input {}", input_path.display()))?;
+    // Sidecar files the input refers to go in next to it
+    let sidecars = harness_core::input_preprocess(core_name)?
+        .sidecars(&input)
+        .into_iter()
+        .map(|name| {
+            let path = input_path.with_file_name(&name);
+            let data = fs::read(&path).with_context(|| format!("Failed to read input sidecar {}", path.display()))?;
+            Ok((name, data))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let elf_path = elf_path_for_core(core_name);
+    let elf = fs::read(&elf_path).with_context(|| {
+        format!("Failed to read ELF {} (build the guest first)", elf_path.display())
+    })?;
+    let recorded_hash = log.environment.as_ref().and_then(|env| env.elf_sha256.clone());
+    if recorded_hash.is_some() && recorded_hash != sha256_file(&elf_path) {
+        println!("   ⚠️  ELF differs from the one recorded with the run (rebuilt since?)");
+    }
+
+    let env = log
+        .environment
+        .clone()
+        .unwrap_or_else(|| ReproEnv::capture(&elf_path));
+    // Captured when the run diverged (see `harness_core::trace`)
+    let trace = dir.as_ref().and_then(|dir| fs::read(dir.join(harness_core::trace::TRACE_FILE)).ok());
+    let repro_script = match dir.and_then(|dir| fs::read_to_string(dir.join("repro.sh")).ok()) {
+        Some(script) => script,
+        None => generate_repro_script(&core_path, Path::new(&log.input_path), &env),
+    };
+    let schema = serde_json::to_vec_pretty(&commit_schema(core_name)?)?;
+
+    let output = output
+        .unwrap_or_else(|| PathBuf::from("artifacts/bundles").join(format!("{}.tar.gz", run_id)));
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let file = fs::File::create(&output)
+        .with_context(|| format!("Failed to create {}", output.display()))?;
+    let readme = bundle_readme(run_id, &log, &env);
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    let mut entries: Vec<(&str, &[u8], u32)> = vec![
+        ("input.json", &input, 0o644),
+        ("run_log.json", &log_json, 0o644),
+        ("guest.elf", &elf, 0o644),
+        ("commit_schema.json", &schema, 0o644),
+        ("repro.sh", repro_script.as_bytes(), 0o755),
+        ("README.md", readme.as_bytes(), 0o644),
+    ];
+    entries.extend(sidecars.iter().map(|(name, data)| (name.as_str(), data.as_slice(), 0o644)));
+    if let Some(trace) = &trace {
+        entries.push((harness_core::trace::TRACE_FILE, trace, 0o644));
+    }
+    for (name, data, mode) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(mode);
+        header.set_mtime(Utc::now().timestamp() as u64);
+        header.set_cksum();
+        archive.append_data(&mut header, format!("{}/{}", run_id, name), data)?;
+    }
+    archive.into_inner()?.finish()?;
+
+    println!("   ✅ Bundle written to {}", output.display());
+
+    Ok(())
+}
+
+/// Render a divergence as GitHub issue markdown
+///
+/// `fingerprint` is a divergence signature, whose run with the smallest
+/// input is reported, or a run ID, which is reported itself. The markdown
+/// goes to stdout (status messages to stderr) unless `output` is given.
+fn export_issue(fingerprint: &str, output: Option<PathBuf>, campaign: Option<&str>) -> Result<()> {
+    use harness_core::notify::signature;
+
+    let runs = divergent_runs(campaign)?;
+    let named_run = runs.iter().find(|(_, log)| log.run_id == fingerprint);
+    let fingerprint = named_run.map(|(_, log)| signature(log)).unwrap_or_else(|| fingerprint.to_string());
+    let matching: Vec<_> = runs.iter().filter(|(_, log)| signature(log) == fingerprint).collect();
+    let input_len = |(entry, log): &&(harness_core::layout::IndexEntry, RunLog)| {
+        let copy = entry.dir.as_ref().map(|dir| dir.join("input.json")).filter(|path| path.is_file());
+        fs::metadata(copy.unwrap_or_else(|| PathBuf::from(&log.input_path))).map(|m| m.len()).unwrap_or(u64::MAX)
+    };
+    let (_, chosen) = named_run
+        .or_else(|| matching.iter().copied().min_by_key(input_len))
+        .with_context(|| format!("No logged divergence with signature '{}' (`harness stats` lists them)", fingerprint))?;
+
+    let LoadedRun { log, input_path, dir, .. } = load_run(&chosen.run_id)?;
+    let core_name = Path::new(&log.core_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .context("Invalid core path in run log")?;
+    let input = fs::read(&input_path).with_context(|| format!("Failed to read input {}", input_path.display()))?;
+    let sidecars: Vec<(String, u64)> = harness_core::input_preprocess(core_name)?
+        .sidecars(&input)
+        .into_iter()
+        .map(|name| {
+            let size = fs::metadata(input_path.with_file_name(&name)).map(|m| m.len()).unwrap_or(0);
+            (name, size)
+        })
+        .collect();
+    let severity = harness_core::severity::of_log(&log, dir.as_deref());
+
+    let markdown = harness_core::issue::IssueReport {
+        signature: &fingerprint,
+        runs: matching.len(),
+        log: &log,
+        input: &input,
+        sidecars: &sidecars,
+        severity: severity.as_ref(),
+    }
+    .render();
+    match output {
+        Some(path) => {
+            fs::write(&path, markdown).with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("📝 Issue for {} (run {}) written to {}", fingerprint, log.run_id, path.display());
+        }
+        None => {
+            eprintln!("📝 Issue for {} (run {})", fingerprint, log.run_id);
+            print!("{}", markdown);
+        }
+    }
+    Ok(())
+}
+
+/// Write the logged divergences (under `campaign`, if given) as SARIF,
+/// one result per divergence signature
+fn export_sarif(output: &Path, campaign: Option<&str>) -> Result<()> {
+    let runs = divergent_runs(campaign)?;
+    let ranked = rank_divergences(&runs);
+    let findings: Vec<harness_core::sarif::Finding> = ranked
+        .iter()
+        .filter_map(|ranked| {
+            let (entry, log) = runs.iter().find(|(_, log)| log.run_id == ranked.run_id)?;
+            Some(harness_core::sarif::Finding {
+                signature: &ranked.signature,
+                runs: ranked.runs,
+                log,
+                severity: &ranked.severity,
+                run_dir: entry.dir.as_deref(),
+            })
+        })
+        .collect();
+
+    let root = std::env::current_dir()?;
+    let sarif = harness_core::sarif::report(&root, &findings);
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(output, serde_json::to_string_pretty(&sarif)?)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+    println!("📋 {} divergence signature(s) written to {}", findings.len(), output.display());
+    Ok(())
+}
+
+/// What a core's guest commits, in order, read from its `Commits` impl
+fn commit_schema(core_name: &str) -> Result<serde_json::Value> {
+    // Output fields, in commit order; a repeated field is marked with `*`
+    let commits = harness_core::commit_fields_for_core(core_name)?;
+    let num_commits = harness_core::commits::cached_num_commits(core_name, &elf_path_for_core(core_name));
+
+    // The spec sp1-runner decodes with (declared in the manifest, or plain u32s)
+    let (encoding, decoder) = match commit_schema_for_core(core_name) {
+        Some(spec) => ("bincode-serialized values, types given by `decoder`", spec),
+        None if num_commits > 0 => (
+            "each commit is a bincode-serialized u32 (bool as 0/1)",
+            vec!["u32"; num_commits].join(","),
+        ),
+        None => ("each commit is a bincode-serialized u32 (bool as 0/1)", "u32*".to_string()),
+    };
+
+    Ok(serde_json::json!({
+        "core": core_name,
+        "encoding": encoding,
+        "decoder": decoder,
+        "num_commits": if num_commits > 0 { serde_json::json!(num_commits) } else { serde_json::json!("variable") },
+        "commits": commits,
+    }))
+}
+
+/// README placed at the top of a bundle
+fn bundle_readme(run_id: &str, log: &RunLog, env: &ReproEnv) -> String {
+    format!(
+        r#"# zk-fuzz-lab divergence bundle: {run_id}
+
+- Core: `{core}`
+- Native status: {native:?}
+- SP1 status: {sp1:?}
+- Reason: {reason}
+- SP1 version: {sp1_version}
+- rustc version: {rustc_version}
+- Target: {target}
+- ELF SHA-256: {elf_sha256}
+- Source revision: {revision}
+
+## Contents
+
+- `guest.elf`: SP1 guest program
+- `input.json`: Input; the guest reads it with `sp1_zkvm::io::read::<Vec<u8>>()`, so write these bytes to stdin with `SP1Stdin::write(&bytes)`
+- `commit_schema.json`: Values the guest commits, in order
+- `run_log.json`: Native and SP1 results with the oracle's diff
+- `repro.sh`: Repro script for use inside a zk-fuzz-lab checkout (or, without bash, `harness repro <extracted bundle dir>`)
+- `sp1_trace.csv` (if captured): The last instructions SP1 executed on the input (step, pc, opcode, operand values, next pc)
+
+## Explanation
+
+```
+{explanation}```
+"#,
+        run_id = run_id,
+        core = log.core_path,
+        native = log.native_result.status,
+        sp1 = log.sp1_result.status,
+        reason = log.diff.reason.as_deref().unwrap_or("none (results equal)"),
+        sp1_version = env.sp1_version,
+        rustc_version = env.rustc_version,
+        target = env.zkvm_target,
+        elf_sha256 = env.elf_sha256.as_deref().unwrap_or("unknown"),
+        revision = env.describe_revision(),
+        explanation = rust_eq_oracle::explain::explain(&log.native_result, &log.sp1_result, &log.diff),
+    )
+}
+
+/// Differentially test the serialization boundary itself
+///
+/// The same input is delivered to the SP1 guest once as JSON bytes (parsed by
+/// serde_json inside the guest) and once as the core's input struct written
+/// with `SP1Stdin::write`. Any difference in the commits is a bug in one of
+/// the two serialization paths rather than in the core.
+fn run_encoding_diff(
+    core_name: &str,
+    input_path: &Path,
+    skip_build: bool,
+    retry: &RetryPolicy,
+) -> Result<()> {
+    println!("🔀 Comparing input encodings...");
+    println!("   Core: {}", core_name);
+    println!("   Input: {}", input_path.display());
+    println!();
+
+    let mut build_error = None;
+    if !skip_build {
+        println!("📦 Building SP1 guest...");
+        let guest_path = guest_path_for_core(core_name);
+        match retry.run("SP1 guest build", || build_sp1_guest(&guest_path)) {
+            (Ok(build), _) => println!("   ✅ SP1 guest built ({})\n", build.summary()),
+            (Err(e), retries) => {
+                println!("   ❌ SP1 guest build failed: {:#}\n", e);
+                build_error = Some((e, retries));
+            }
+        }
+    }
+
+    let elf_path = elf_path_for_core(core_name);
+    let run = |encoding: InputEncoding| match &build_error {
+        Some((e, retries)) => build_failure_result(e, *retries),
+        None => {
+            println!("⚡ Running SP1 ({} encoding)...", encoding.as_str());
+            let result = retry.run_runner("sp1", || {
+                run_sp1_runner_with_encoding(&elf_path, input_path, core_name, encoding)
+            });
+            println!("   Status: {:?}, commits: {}", result.status, result.commits.len());
+            result
+        }
+    };
+    let json_result = run(InputEncoding::Json);
+    let typed_result = run(InputEncoding::Typed);
+    println!();
+
+    println!("🔍 Comparing results...");
+    let diff = compare(&json_result, &typed_result);
+    if diff.equal {
+        println!("   ✅ PASS - Both encodings produce the same commits");
+    } else {
+        println!("   ❌ FAIL - Encodings differ!");
+        if let Some(reason) = &diff.reason {
+            println!("   📋 Reason: {}", reason);
+        }
+    }
+    println!();
+
+    let run_id = new_run_id(&format!("encoding_{}", core_name));
+    let dir = PathBuf::from("artifacts/encoding");
+    fs::create_dir_all(&dir)?;
+    let log_path = dir.join(format!("{}.json", run_id));
+    let log = serde_json::json!({
+        "run_id": run_id,
+        "campaign": harness_core::campaign::current_id(),
+        "core": core_name,
+        "input_path": input_path.display().to_string(),
+        "json_result": json_result,
+        "typed_result": typed_result,
+        "diff": diff,
+    });
+    fs::write(&log_path, serde_json::to_string_pretty(&log)?)?;
+    println!("💾 Logged to {}", log_path.display());
+
+    Ok(())
+}
+
+/// Prove each core's base input (or `inputs`) and append proof size, shards
+/// and timings to the benchmark CSV, flagging metrics that regressed since the
+/// previous run and inputs whose shard count explodes relative to their size
+fn bench_prove(
+    cores_arg: &str,
+    inputs: &[PathBuf],
+    proof_mode: harness_core::bench::ProofMode,
+    skip_build: bool,
+    retry: &RetryPolicy,
+) -> Result<()> {
+    use harness_core::bench::{
+        append_bench_record, find_regressions, find_shard_explosion, load_bench_records, BenchRecord,
+    };
+
+    let cores = parse_cores_arg(cores_arg)?;
+    println!("⏱️  Benchmarking SP1 proving ({} proofs)...", proof_mode.as_str());
+    println!("   Cores: {}", cores.join(", "));
+    println!();
+
+    let history = load_bench_records()?;
+    let mut records: Vec<BenchRecord> = Vec::new();
+    let mut regressed = 0;
+    let mut exploded = 0;
+
+    for core_name in &cores {
+        println!("📦 {}", core_name);
+        let input_paths = match inputs {
+            [] => vec![base_input_for_core(core_name)?],
+            inputs => inputs.to_vec(),
         };
-        fs::write(repro_dir.join("run_log.json"), serde_json::to_string_pretty(&log)?)?;
+        let elf_path = elf_path_for_core(core_name);
+
+        let mut build_error = None;
+        if !skip_build {
+            let guest_path = guest_path_for_core(core_name);
+            if let (Err(e), retries) = retry.run("SP1 guest build", || build_sp1_guest(&guest_path)) {
+                println!("   ❌ SP1 guest build failed: {:#}", e);
+                build_error = Some((e, retries));
+            }
+        }
+
+        for input_path in &input_paths {
+            let result = match &build_error {
+                Some((e, retries)) => build_failure_result(e, *retries),
+                None => {
+                    println!("   ⚡ Proving {}...", input_path.display());
+                    retry.run_runner("sp1", || run_sp1_prover(&elf_path, input_path, core_name, proof_mode))
+                }
+            };
+            if let Some(error) = result.meta["error"].as_str() {
+                println!("   ⚠️  {:?}: {}", result.status, error);
+            }
+
+            let env = ReproEnv::capture(&elf_path);
+            let record = BenchRecord::from_result(core_name, input_path, proof_mode, &result, &env);
+            let regressions = find_regressions(&history, &record);
+            for regression in &regressions {
+                println!(
+                    "   ⚠️  {} regressed {:+.0}%: {} -> {} (previous run on SP1 {})",
+                    regression.metric,
+                    regression.increase() * 100.0,
+                    regression.previous,
+                    regression.current,
+                    regression.previous_sp1_version
+                );
+            }
+            if !regressions.is_empty() {
+                regressed += 1;
+            }
+            // Baseline: earlier runs and the inputs already proved in this one
+            let baseline: Vec<BenchRecord> = history.iter().chain(&records).cloned().collect();
+            if let Some(explosion) = find_shard_explosion(&baseline, &record) {
+                println!(
+                    "   💥 Shard explosion: {} shards for {} input bytes ({:.1}x the core's {:.2} shards/KiB)",
+                    explosion.shards,
+                    explosion.input_bytes,
+                    explosion.factor(),
+                    explosion.baseline_per_kib
+                );
+                exploded += 1;
+            }
+
+            append_bench_record(&record)?;
+            records.push(record);
+        }
+        println!();
+    }
+
+    let show = |value: Option<u64>| value.map_or_else(|| "-".to_string(), |v| v.to_string());
+    println!("📊 Proving benchmark (SP1 {}):", records.first().map_or("?", |r| r.sp1_version.as_str()));
+    println!(
+        "   {:<22} {:<12} {:>12} {:>8} {:>10} {:>10} {:>12}",
+        "core", "status", "cycles", "shards", "prove ms", "verify ms", "proof bytes"
+    );
+    for record in &records {
+        println!(
+            "   {:<22} {:<12} {:>12} {:>8} {:>10} {:>10} {:>12}",
+            record.core,
+            format!("{:?}", record.status),
+            show(record.cycles),
+            show(record.shards),
+            show(record.prove_ms),
+            show(record.verify_ms),
+            show(record.proof_size_bytes)
+        );
+    }
+    println!();
+
+    let failed = records.iter().filter(|r| r.status != rust_eq_oracle::Status::Ok).count();
+    println!(
+        "✅ Benchmarked {} proofs ({} failed, {} regressed, {} shard explosions)",
+        records.len(),
+        failed,
+        regressed,
+        exploded
+    );
+    println!("💾 Appended to artifacts/bench/prove.csv");
+
+    Ok(())
+}
+
+/// Run each core on every (SP1 version, build profile) cell from fuzz.toml,
+/// comparing every cell with native and with the first (reference) cell
+fn run_version_matrix(
+    cores_arg: &str,
+    versions_arg: Option<&str>,
+    profiles_arg: Option<&str>,
+    input: Option<&Path>,
+    skip_build: bool,
+    sandbox: bool,
+    retry: &RetryPolicy,
+) -> Result<()> {
+    use harness_core::matrix::{FuzzConfig, CONFIG_PATH};
+
+    let config = FuzzConfig::load()?;
+    let versions = select_by_name(config.sp1_versions.clone(), versions_arg, |v| &v.name, "SP1 version")?;
+    let profiles = select_by_name(config.profiles(), profiles_arg, |p| &p.name, "build profile")?;
+    if versions.is_empty() {
+        anyhow::bail!("No [[sp1_versions]] configured in {}", CONFIG_PATH);
+    }
+    let cores = parse_cores_arg(cores_arg)?;
+    if input.is_some() && cores.len() != 1 {
+        anyhow::bail!("--input needs exactly one core");
+    }
+
+    // Every version with every profile; the first cell is the reference
+    let cells: Vec<_> = versions
+        .iter()
+        .flat_map(|version| profiles.iter().map(move |profile| (version, profile)))
+        .collect();
+    let label = |version: &harness_core::matrix::Sp1Version, profile: &harness_core::matrix::BuildProfile| {
+        format!("{}/{}", version.name, profile.name)
+    };
+    let reference_label = label(cells[0].0, cells[0].1);
+
+    println!("🧮 Running SP1 version matrix...");
+    let toolchains: Vec<String> = versions.iter().map(|v| v.toolchain_version()).collect();
+    for (version, toolchain) in versions.iter().zip(&toolchains) {
+        println!("   {} ({})", version.name, toolchain);
+    }
+    println!("   Profiles: {}", profiles.iter().map(|p| p.name.as_str()).collect::<Vec<_>>().join(", "));
+    println!("   Cores: {}", cores.join(", "));
+    println!();
+
+    let mut entries = Vec::new();
+    let mut reference_divergences = 0;
+    let mut native_divergences = 0;
+
+    for core_name in &cores {
+        let input_path = match input {
+            Some(path) => path.to_path_buf(),
+            None => base_input_for_core(core_name)?,
+        };
+        println!("📦 {} ({})", core_name, input_path.display());
+
+        let native = retry.run_runner("native", || run_native_runner_sandboxed(core_name, &input_path, sandbox));
+        println!("   native: {:?}, commits: {}", native.status, native.commits.len());
+
+        let mut reference: Option<rust_eq_oracle::RunResult> = None;
+        let mut runs = Vec::new();
+        for (version, profile) in &cells {
+            let build_error = if skip_build {
+                None
+            } else {
+                let (built, retries) = retry.run("SP1 guest build", || version.build_guest(core_name, profile));
+                built.err().map(|e| (e, retries))
+            };
+            let result = match &build_error {
+                Some((e, retries)) => build_failure_result(e, *retries),
+                None => retry.run_runner("sp1", || version.run(core_name, profile, &input_path)),
+            };
+
+            let vs_native = harness_core::relations::compare_for_core(core_name, &input_path, &native, &result)?;
+            let vs_reference = reference.as_ref().map(|reference| compare(reference, &result));
+            let cycles = result.meta["cycles"].as_u64();
+            let cycle_delta = reference
+                .as_ref()
+                .and_then(|reference| Some(cycles? as i64 - reference.meta["cycles"].as_u64()? as i64));
+
+            let mut line = format!(
+                "   {:<24} {:<12} cycles {:>12}",
+                label(version, profile),
+                format!("{:?}", result.status),
+                cycles.map_or_else(|| "-".to_string(), |c| c.to_string())
+            );
+            if let Some(delta) = cycle_delta {
+                line.push_str(&format!(" ({:+})", delta));
+            }
+            line.push_str(if vs_native.equal { "  ✅ native" } else { "  ❌ native" });
+            match &vs_reference {
+                Some(diff) if diff.equal => line.push_str(&format!("  ✅ {}", reference_label)),
+                Some(_) => line.push_str(&format!("  ❌ {}", reference_label)),
+                None => {}
+            }
+            println!("{}", line);
+            for (against, diff) in [("native", Some(&vs_native)), (reference_label.as_str(), vs_reference.as_ref())] {
+                if let Some(reason) = diff.and_then(|diff| diff.reason.as_ref()) {
+                    println!("      vs {}: {}", against, reason);
+                }
+            }
+
+            if !vs_native.equal {
+                native_divergences += 1;
+            }
+            if vs_reference.as_ref().is_some_and(|diff| !diff.equal) {
+                reference_divergences += 1;
+            }
+            runs.push(serde_json::json!({
+                "version": version.name,
+                "profile": profile.name,
+                "elf_sha256": sha256_file(&version.elf_path(core_name, profile)),
+                "result": result,
+                "vs_native": vs_native,
+                "vs_reference": vs_reference,
+            }));
+            if reference.is_none() {
+                reference = Some(result);
+            }
+        }
+        println!();
+
+        entries.push(serde_json::json!({
+            "core": core_name,
+            "input_path": input_path.display().to_string(),
+            "native": native,
+            "cells": runs,
+        }));
+    }
+
+    let run_id = new_run_id("matrix");
+    let dir = PathBuf::from("artifacts/matrix");
+    fs::create_dir_all(&dir)?;
+    let log_path = dir.join(format!("{}.json", run_id));
+    let log = serde_json::json!({
+        "run_id": run_id,
+        "campaign": harness_core::campaign::current_id(),
+        "reference": reference_label,
+        "versions": versions.iter().zip(&toolchains).map(|(version, toolchain)| serde_json::json!({
+            "name": version.name,
+            "cargo_prove": version.cargo_prove,
+            "runner": version.runner,
+            "toolchain": toolchain,
+        })).collect::<Vec<_>>(),
+        "profiles": profiles.iter().map(|profile| serde_json::json!({
+            "name": profile.name,
+            "opt_level": profile.opt_level,
+            "lto": profile.lto,
+            "overflow_checks": profile.overflow_checks,
+            "debug_assertions": profile.debug_assertions,
+            "codegen_units": profile.codegen_units,
+            "features": profile.features,
+            "rustflags": profile.rustflags,
+        })).collect::<Vec<_>>(),
+        "cores": entries,
+    });
+    fs::write(&log_path, serde_json::to_string_pretty(&log)?)?;
+
+    println!(
+        "📊 {} cores x {} versions x {} profiles: {} runs differ from native, {} differ from {}",
+        cores.len(),
+        versions.len(),
+        profiles.len(),
+        native_divergences,
+        reference_divergences,
+        reference_label
+    );
+    println!("💾 Logged to {}", log_path.display());
+
+    Ok(())
+}
+
+/// Keep the configured entries named in a comma-separated `--versions` /
+/// `--profiles` argument (all of them if it is not given), in the order given
+fn select_by_name<T: Clone>(items: Vec<T>, names: Option<&str>, name: impl Fn(&T) -> &String, what: &str) -> Result<Vec<T>> {
+    let Some(names) = names else {
+        return Ok(items);
+    };
+    names
+        .split(',')
+        .map(str::trim)
+        .map(|wanted| {
+            items.iter().find(|item| name(item) == wanted).cloned().ok_or_else(|| {
+                let known: Vec<&str> = items.iter().map(|item| name(item).as_str()).collect();
+                anyhow::anyhow!("Unknown {} '{}' (configured: {})", what, wanted, known.join(", "))
+            })
+        })
+        .collect()
+}
+
+/// Run the setup checks of `harness_core::doctor`, failing if any fails
+fn run_doctor(skip_build: bool, retry: &RetryPolicy) -> Result<()> {
+    use harness_core::doctor::{self, Check, Outcome};
+
+    fn print(checks: &[Check]) {
+        for check in checks {
+            match &check.outcome {
+                Outcome::Pass(detail) => println!("   ✅ {}: {}", check.name, detail),
+                Outcome::Fail { problem, fix } => {
+                    println!("   ❌ {}: {}", check.name, problem);
+                    println!("      → {}", fix);
+                }
+                Outcome::Skip(why) => println!("   ⏭️  {}: skipped ({})", check.name, why),
+            }
+        }
+    }
+
+    let mut checks = Vec::new();
+    println!("🩺 Toolchain");
+    let toolchain = doctor::toolchain();
+    print(&toolchain);
+    checks.extend(toolchain);
+
+    println!("📦 Guests");
+    let guests: Vec<Check> = doctor::GUESTS
+        .iter()
+        .map(|core| {
+            let check = doctor::guest(core, skip_build, retry);
+            print(std::slice::from_ref(&check));
+            check
+        })
+        .collect();
+    let (canary_ok, endless_ok) = (guests[0].passed(), guests[1].passed());
+    checks.extend(guests);
+
+    println!("🔁 Known-answer round trip");
+    let round_trips = doctor::round_trips(canary_ok, retry);
+    print(&round_trips);
+    checks.extend(round_trips);
+
+    println!("⏱️  Timeouts");
+    let timeouts = doctor::timeouts(endless_ok);
+    print(&timeouts);
+    checks.extend(timeouts);
+
+    println!();
+    let failed = checks.iter().filter(|check| matches!(check.outcome, Outcome::Fail { .. })).count();
+    if failed > 0 {
+        anyhow::bail!("{} of {} checks failed; fix them before starting a campaign", failed, checks.len());
+    }
+    // Checks are only skipped after a failure
+    println!("🩺 All {} checks passed; ready to fuzz", checks.len());
+    Ok(())
+}
+
+/// Print every campaign in `artifacts/campaigns/` with its tags and run counts
+fn list_campaigns() -> Result<()> {
+    let campaigns = harness_core::campaign::list_campaigns()?;
+    if campaigns.is_empty() {
+        println!("No campaigns yet (start one with `harness --campaign <id> ...`)");
+        return Ok(());
+    }
+
+    let records = harness_core::cost_model::load_run_records().unwrap_or_default();
+    println!("🏷️  Campaigns ({}):", campaigns.len());
+    for campaign in &campaigns {
+        let runs: Vec<_> = records
+            .iter()
+            .filter(|record| record.campaign.as_deref() == Some(campaign.id.as_str()))
+            .collect();
+        let divergent = runs
+            .iter()
+            .filter(|r| !r.equal && !r.native_status.is_infra() && !r.sp1_status.is_infra())
+            .count();
+        println!(
+            "   {:<28} {:>5} runs, {:>5} divergent, {:>3} sessions, last started {}",
+            campaign.id,
+            runs.len(),
+            divergent,
+            campaign.sessions.len(),
+            campaign.last_started_at
+        );
+        if !campaign.tags.is_empty() {
+            println!("      tags: {}", campaign.tags.iter().cloned().collect::<Vec<_>>().join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the cores, zkVM targets and mutation strategies the harness knows
+fn list_capabilities(json: bool) -> Result<()> {
+    let mut cores = Vec::new();
+    for core_name in known_cores()? {
+        let base_input = base_input_for_core(core_name)?;
+        let schema = commit_schema(core_name)?;
+        let strategy = source_mutator::MutationStrategy::for_core(core_name);
+        cores.push(serde_json::json!({
+            "name": core_name,
+            "core_path": format!("guest/cores/{}", core_name),
+            "guest_path": guest_path_for_core(core_name).display().to_string(),
+            "base_input": base_input.display().to_string(),
+            "num_commits": schema["num_commits"],
+            "commits": schema["commits"],
+            "decoder": schema["decoder"],
+            "max_cycles": get_max_cycles_for_core(core_name),
+            "strategy": strategy.map(|s| format!("{:?}", s)),
+        }));
+    }
+    let targets = vec![serde_json::json!({
+        "zkvm": "sp1",
+        "target": ZKVM_TARGET,
+        "runner": "sp1-runner",
+    })];
+    let strategies: Vec<serde_json::Value> = source_mutator::MutationStrategy::ALL
+        .iter()
+        .map(|s| serde_json::json!({ "name": format!("{:?}", s), "description": s.description() }))
+        .collect();
+
+    if json {
+        let listing = serde_json::json!({
+            "cores": cores,
+            "targets": targets,
+            "strategies": strategies,
+        });
+        println!("{}", serde_json::to_string_pretty(&listing)?);
+        return Ok(());
+    }
+
+    println!("📦 Cores ({}):", cores.len());
+    println!("   {:<22} {:<8} {:<22} {:<44} commits", "name", "commits", "strategy", "base input");
+    for core in &cores {
+        let commits: Vec<&str> = core["commits"]
+            .as_array()
+            .map(|c| c.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+        println!(
+            "   {:<22} {:<8} {:<22} {:<44} {}",
+            core["name"].as_str().unwrap_or_default(),
+            core["num_commits"].to_string().trim_matches('"'),
+            core["strategy"].as_str().unwrap_or("-"),
+            core["base_input"].as_str().unwrap_or_default(),
+            commits.join(", ")
+        );
+    }
+    println!();
+    println!("🎯 zkVM targets:");
+    for target in &targets {
+        println!("   {:<8} {}", target["zkvm"].as_str().unwrap_or_default(), target["target"].as_str().unwrap_or_default());
+    }
+    println!();
+    println!("🧬 Mutation strategies ({}):", strategies.len());
+    for strategy in &strategies {
+        println!(
+            "   {:<22} {}",
+            strategy["name"].as_str().unwrap_or_default(),
+            strategy["description"].as_str().unwrap_or_default()
+        );
+    }
+
+    Ok(())
+}
+
+/// Logged divergences (under `campaign`, if given) with their run logs
+///
+/// Unreadable logs are skipped.
+fn divergent_runs(campaign: Option<&str>) -> Result<Vec<(harness_core::layout::IndexEntry, RunLog)>> {
+    let index = harness_core::layout::load_index()?;
+    Ok(index
+        .runs
+        .into_values()
+        .filter(|entry| !entry.equal && campaign.is_none_or(|campaign| entry.campaign.as_deref() == Some(campaign)))
+        .filter_map(|entry| {
+            let log = harness_core::run_log::load(&entry.log).ok()?;
+            Some((entry, log))
+        })
+        .collect())
+}
+
+/// Divergence signatures of `runs`, most severe first
+fn rank_divergences(runs: &[(harness_core::layout::IndexEntry, RunLog)]) -> Vec<harness_core::severity::RankedSignature> {
+    harness_core::severity::rank(runs.iter().filter_map(|(entry, log)| {
+        let severity = harness_core::severity::of_log(log, entry.dir.as_deref())?;
+        Some((harness_core::notify::signature(log), log.run_id.clone(), severity))
+    }))
+}
+
+/// Print runs whose SP1 opcode mix shifts abruptly (see `harness_core::opcode_mix`)
+fn print_opcode_cliffs(records: &[harness_core::cost_model::RunRecord]) {
+    use harness_core::opcode_mix::{find_cliffs, MIN_NEIGHBORS};
+
+    println!();
+    println!("🧮 Opcode-mix cliffs (SP1 instruction classes vs similar-sized inputs):");
+    let histograms = records.iter().filter(|record| record.sp1_opcodes.is_some()).count();
+    if histograms == 0 {
+        println!("   No runs with opcode histograms (logged before sp1-runner recorded them)");
+        return;
+    }
+    let cliffs = find_cliffs(records);
+    if cliffs.is_empty() {
+        println!(
+            "   ✅ No abrupt shifts in {} runs (a run needs {}+ similar-sized runs of its core)",
+            histograms, MIN_NEIGHBORS
+        );
+        return;
+    }
+    for cliff in &cliffs {
+        println!(
+            "   ⚠️  {} {}: {} {:.0}% of instructions vs {:.0}% ({:+.1}σ) for {}-{} byte inputs{}",
+            cliff.core,
+            cliff.run_id,
+            cliff.class.as_str(),
+            cliff.share * 100.0,
+            cliff.expected * 100.0,
+            cliff.sigma,
+            cliff.neighbor_bytes.0,
+            cliff.neighbor_bytes.1,
+            if cliff.equal { ", outputs match" } else { "" }
+        );
+        println!("       {} ({} bytes)", cliff.input, cliff.input_bytes);
+    }
+}
+
+/// Print per-core run counts and latencies and, optionally, opcode-mix
+/// cliffs and fitted cost models
+fn print_stats(cost_model: bool, opcode_mix: bool, campaign: Option<&str>) -> Result<()> {
+    let mut records = harness_core::cost_model::load_run_records()?;
+    if let Some(campaign) = campaign {
+        records.retain(|record| record.campaign.as_deref() == Some(campaign));
+        println!("🏷️  Campaign: {}\n", campaign);
+    }
+
+    println!("📊 Runs per core ({} total):", records.len());
+    let mut by_core: std::collections::BTreeMap<&str, (usize, usize)> = Default::default();
+    for record in &records {
+        let (runs, passed) = by_core.entry(&record.core).or_default();
+        *runs += 1;
+        if record.equal {
+            *passed += 1;
+        }
+    }
+    let latencies = harness_core::latency::by_core(&records);
+    for (core, (runs, passed)) in &by_core {
+        println!("   {:<22} {:>5} runs, {:>5} passed, {:>5} divergent", core, runs, passed, runs - passed);
+        if let Some(latency) = latencies.get(*core) {
+            let latency = latency.summary();
+            harness_core::latency::print_latency("Native", &latency.native);
+            harness_core::latency::print_latency("SP1", &latency.sp1);
+        }
+    }
+
+    // Which mutation strategies find divergences (fuzzed runs only)
+    let mut by_strategy: std::collections::BTreeMap<&str, (usize, usize)> = Default::default();
+    for record in &records {
+        if let Some(op) = &record.mutation {
+            let (runs, divergent) = by_strategy.entry(op.strategy.as_str()).or_default();
+            *runs += 1;
+            if !record.equal && !record.native_status.is_infra() && !record.sp1_status.is_infra() {
+                *divergent += 1;
+            }
+        }
+    }
+    if !by_strategy.is_empty() {
+        println!();
+        println!("🧬 Fuzzed runs per mutation strategy:");
+        for (strategy, (runs, divergent)) in &by_strategy {
+            println!("   {:<22} {:>5} runs, {:>5} divergent", strategy, runs, divergent);
+        }
+    }
+
+    // Which divergences to escalate first
+    let ranked = rank_divergences(&divergent_runs(campaign)?);
+    if !ranked.is_empty() {
+        println!();
+        println!("🚨 Divergence signatures by severity:");
+        for ranked in &ranked {
+            println!(
+                "   {:>3} {:<8} {} ({} runs, e.g. {})",
+                ranked.severity.score,
+                ranked.severity.level.as_str(),
+                ranked.signature,
+                ranked.runs,
+                ranked.run_id
+            );
+            println!("       {}", ranked.severity.factors.join(", "));
+        }
+    }
+
+    if opcode_mix {
+        print_opcode_cliffs(&records);
+    }
+
+    if !cost_model {
+        return Ok(());
+    }
+
+    println!();
+    println!("📈 Cost models (SP1 cycles vs input bytes):");
+    let models = harness_core::cost_model::fit_cost_models(&records);
+    if models.is_empty() {
+        println!(
+            "   No core has runs at {}+ distinct input sizes with cycle counts",
+            harness_core::cost_model::MIN_SIZES
+        );
+        return Ok(());
+    }
+    for model in &models {
+        let [c0, c1, c2] = model.quadratic.coefficients;
+        println!(
+            "   {} {} ({} runs, {} sizes)",
+            if model.is_superlinear() { "⚠️ " } else { "✅" },
+            model.core,
+            model.samples,
+            model.distinct_sizes
+        );
+        println!(
+            "      linear:    cycles ≈ {:.1} + {:.3}·n  (R² {:.4})",
+            model.linear.coefficients[0], model.linear.coefficients[1], model.linear.r_squared
+        );
+        println!(
+            "      quadratic: cycles ≈ {:.1} + {:.3}·n + {:.3e}·n²  (R² {:.4})",
+            c0, c1, c2, model.quadratic.r_squared
+        );
+        println!(
+            "      best fit: {} | growth: cycles ~ n^{:.2}, native ~ n^{:.2}",
+            model.best_fit(),
+            model.cycles_exponent,
+            model.native_exponent
+        );
+    }
+
+    let flagged: Vec<&str> = models
+        .iter()
+        .filter(|m| m.is_superlinear())
+        .map(|m| m.core.as_str())
+        .collect();
+    println!();
+    if flagged.is_empty() {
+        println!("   ✅ No core's zkVM cost grows superlinearly relative to native time");
+    } else {
+        println!(
+            "   ⚠️  zkVM cost grows superlinearly relative to native time (DoS risk): {}",
+            flagged.join(", ")
+        );
     }
 
     Ok(())
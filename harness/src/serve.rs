@@ -0,0 +1,399 @@
+//! `harness serve`: HTTP control API
+//!
+//! Campaigns started through the API run as child `harness --campaign <id>
+//! ...` processes, with their output in `artifacts/campaigns/<id>.log`.
+//! Stats and divergences are read live from `artifacts/runs.jsonl`, which
+//! every run appends to.
+//!
+//! Every endpoint but `/health` requires the token printed at startup (or
+//! taken from `HARNESS_API_TOKEN`) as `Authorization: Bearer <token>`, and
+//! a campaign is described by typed fuzz options rather than a command line,
+//! so a client cannot make the server run any other harness command.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use harness_core::cost_model::RunRecord;
+use harness_core::http::{Request, Response};
+use rust_eq_oracle::DiffKind;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+
+/// A campaign process started through the API
+struct Job {
+    child: Child,
+    started_at: String,
+    args: Vec<String>,
+    log_path: PathBuf,
+}
+
+/// State shared by request handlers
+struct Server {
+    token: String,
+    jobs: Mutex<BTreeMap<String, Job>>,
+}
+
+/// `POST /campaigns` body
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StartCampaign {
+    id: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    fuzz: FuzzRequest,
+}
+
+/// The `harness fuzz` options a campaign can be started with
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FuzzRequest {
+    /// Core names or "all" (default)
+    #[serde(default)]
+    cores: Vec<String>,
+    #[serde(default)]
+    mode: Option<FuzzMode>,
+    seed: Option<u64>,
+    resolution: Option<usize>,
+    budget: Option<usize>,
+    commit_buckets: Option<usize>,
+    retries: Option<u32>,
+    #[serde(default)]
+    skip_build: bool,
+    #[serde(default)]
+    sandbox: bool,
+    #[serde(default)]
+    no_dedup: bool,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FuzzMode {
+    Sample,
+    Exhaustive,
+}
+
+impl FuzzRequest {
+    /// The `harness fuzz ...` arguments, or why the request is invalid
+    fn to_args(&self) -> Result<Vec<String>> {
+        for core in &self.cores {
+            anyhow::ensure!(
+                !core.is_empty() && core.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'),
+                "invalid core name '{}'",
+                core
+            );
+        }
+        let cores = if self.cores.is_empty() { "all".to_string() } else { self.cores.join(",") };
+        let mut args = vec!["fuzz".to_string(), format!("--cores={}", cores)];
+        if let Some(mode) = self.mode {
+            args.push(format!(
+                "--mode={}",
+                match mode {
+                    FuzzMode::Sample => "sample",
+                    FuzzMode::Exhaustive => "exhaustive",
+                }
+            ));
+        }
+        let numbers = [
+            ("seed", self.seed),
+            ("resolution", self.resolution.map(|n| n as u64)),
+            ("budget", self.budget.map(|n| n as u64)),
+            ("commit-buckets", self.commit_buckets.map(|n| n as u64)),
+            ("retries", self.retries.map(u64::from)),
+        ];
+        for (name, value) in numbers {
+            if let Some(value) = value {
+                args.push(format!("--{}={}", name, value));
+            }
+        }
+        for (name, set) in [("skip-build", self.skip_build), ("sandbox", self.sandbox), ("no-dedup", self.no_dedup)] {
+            if set {
+                args.push(format!("--{}", name));
+            }
+        }
+        Ok(args)
+    }
+}
+
+#[derive(Serialize)]
+struct JobStatus {
+    campaign: String,
+    pid: u32,
+    running: bool,
+    /// Exit code once finished (None while running or if killed by a signal)
+    exit_code: Option<i32>,
+    started_at: String,
+    args: Vec<String>,
+    log: String,
+}
+
+#[derive(Default, Serialize)]
+struct Counts {
+    runs: usize,
+    passed: usize,
+    divergent: usize,
+    infra_errors: usize,
+}
+
+impl Counts {
+    fn add(&mut self, record: &RunRecord) {
+        self.runs += 1;
+        if record.native_status.is_infra() || record.sp1_status.is_infra() {
+            self.infra_errors += 1;
+        } else if record.equal {
+            self.passed += 1;
+        } else {
+            self.divergent += 1;
+        }
+    }
+}
+
+/// Serve the control API on `addr` until interrupted
+pub fn serve(addr: &str) -> Result<()> {
+    let listener = TcpListener::bind(addr).with_context(|| format!("Failed to listen on {}", addr))?;
+    let token = match std::env::var("HARNESS_API_TOKEN") {
+        Ok(token) if !token.is_empty() => token,
+        _ => harness_core::http::new_token(),
+    };
+    println!("🌐 Harness API listening on http://{}", listener.local_addr()?);
+    println!("   Token: {}  (send as 'Authorization: Bearer <token>')", token);
+    println!("   GET  /health");
+    println!("   GET  /campaigns              POST /campaigns   POST /campaigns/<id>/stop");
+    println!("   GET  /stats?campaign=<id>    GET  /divergences?campaign=<id>&core=<core>&kind=<kind>");
+    println!("   GET  /bundles/<run_id>");
+
+    let server = Server {
+        token,
+        jobs: Mutex::default(),
+    };
+    harness_core::http::serve(listener, move |request| {
+        let response = server.handle(request);
+        println!("   {} {} -> {}", request.method, request.path, response.status);
+        response
+    })
+}
+
+impl Server {
+    fn handle(&self, request: &Request) -> Response {
+        if request.path != "/health" {
+            if let Some(denied) = request.authorize(&self.token) {
+                return denied;
+            }
+        }
+        let result = match (request.method.as_str(), request.segments().as_slice()) {
+            ("GET", ["health"]) => Ok(Response::json(
+                200,
+                &serde_json::json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }),
+            )),
+            ("GET", ["campaigns"]) => self.list_campaigns(),
+            ("POST", ["campaigns"]) => self.start_campaign(request),
+            ("POST", ["campaigns", id, "stop"]) => self.stop_campaign(id),
+            ("GET", ["stats"]) => stats(request.query("campaign")),
+            ("GET", ["divergences"]) => {
+                divergences(request.query("campaign"), request.query("core"), request.query("kind"))
+            }
+            ("GET", ["bundles", run_id]) => bundle(run_id),
+            (_, ["health" | "campaigns" | "stats" | "divergences" | "bundles", ..]) => {
+                Ok(Response::error(405, "method not allowed"))
+            }
+            _ => Ok(Response::error(404, "no such endpoint")),
+        };
+        result.unwrap_or_else(|e| Response::error(500, &format!("{:#}", e)))
+    }
+
+    /// Every campaign with its run counts and API-started process
+    fn list_campaigns(&self) -> Result<Response> {
+        let records = load_records()?;
+        let mut jobs = self.jobs.lock().unwrap();
+        let metadata: BTreeMap<_, _> = harness_core::campaign::list_campaigns()?
+            .into_iter()
+            .map(|campaign| (campaign.id.clone(), campaign))
+            .collect();
+
+        // Campaigns on disk, plus API-started ones that have not recorded a session yet
+        let mut ids: Vec<&String> = metadata.keys().collect();
+        let job_ids: Vec<String> = jobs.keys().cloned().collect();
+        ids.extend(job_ids.iter().filter(|id| !metadata.contains_key(*id)));
+        let campaigns: Vec<_> = ids
+            .into_iter()
+            .map(|id| {
+                let mut counts = Counts::default();
+                records
+                    .iter()
+                    .filter(|r| r.campaign.as_deref() == Some(id.as_str()))
+                    .for_each(|r| counts.add(r));
+                let job = jobs.get_mut(id).map(|job| job_status(id, job));
+                serde_json::json!({ "id": id, "metadata": metadata.get(id), "counts": counts, "job": job })
+            })
+            .collect();
+        Ok(Response::json(200, &campaigns))
+    }
+
+    /// Start `harness --campaign <id> [--tag ...] fuzz <options>` in the background
+    fn start_campaign(&self, request: &Request) -> Result<Response> {
+        let start: StartCampaign = match request.json() {
+            Ok(start) => start,
+            Err(e) => return Ok(Response::error(400, &format!("{:#}", e))),
+        };
+        if let Err(e) = harness_core::campaign::validate_id(&start.id) {
+            return Ok(Response::error(400, &e.to_string()));
+        }
+        let args = match start.fuzz.to_args() {
+            Ok(args) => args,
+            Err(e) => return Ok(Response::error(400, &format!("{:#}", e))),
+        };
+
+        let mut jobs = self.jobs.lock().unwrap();
+        if let Some(job) = jobs.get_mut(&start.id) {
+            if job.child.try_wait()?.is_none() {
+                return Ok(Response::error(409, &format!("campaign '{}' is already running", start.id)));
+            }
+        }
+
+        fs::create_dir_all("artifacts/campaigns")?;
+        let log_path = PathBuf::from("artifacts/campaigns").join(format!("{}.log", start.id));
+        let log = fs::File::create(&log_path).with_context(|| format!("Failed to create {}", log_path.display()))?;
+
+        let mut cmd = Command::new(std::env::current_exe()?);
+        cmd.args(["--campaign", &start.id]);
+        if harness_core::container::enabled() {
+            cmd.arg("--container");
+        }
+        for tag in &start.tags {
+            cmd.arg(format!("--tag={}", tag));
+        }
+        let child = cmd
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .spawn()
+            .context("Failed to start harness")?;
+        println!("   ▶️  Campaign '{}' started (pid {}): {}", start.id, child.id(), args.join(" "));
+
+        let mut job = Job {
+            child,
+            started_at: Utc::now().to_rfc3339(),
+            args,
+            log_path,
+        };
+        let status = job_status(&start.id, &mut job);
+        jobs.insert(start.id, job);
+        Ok(Response::json(201, &status))
+    }
+
+    fn stop_campaign(&self, id: &str) -> Result<Response> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let Some(job) = jobs.get_mut(id) else {
+            return Ok(Response::error(404, &format!("campaign '{}' was not started by this server", id)));
+        };
+        if job.child.try_wait()?.is_none() {
+            job.child.kill()?;
+            job.child.wait()?;
+            println!("   ⏹️  Campaign '{}' stopped", id);
+        }
+        Ok(Response::json(200, &job_status(id, job)))
+    }
+}
+
+fn job_status(id: &str, job: &mut Job) -> JobStatus {
+    let exit = job.child.try_wait().ok().flatten();
+    JobStatus {
+        campaign: id.to_string(),
+        pid: job.child.id(),
+        running: exit.is_none(),
+        exit_code: exit.and_then(|status| status.code()),
+        started_at: job.started_at.clone(),
+        args: job.args.clone(),
+        log: job.log_path.display().to_string(),
+    }
+}
+
+/// Logged runs (none before the first run)
+fn load_records() -> Result<Vec<RunRecord>> {
+    if !Path::new("artifacts/runs.jsonl").exists() {
+        return Ok(Vec::new());
+    }
+    harness_core::cost_model::load_run_records()
+}
+
+fn campaign_records(campaign: Option<&str>) -> Result<Vec<RunRecord>> {
+    let mut records = load_records()?;
+    if let Some(campaign) = campaign {
+        records.retain(|record| record.campaign.as_deref() == Some(campaign));
+    }
+    Ok(records)
+}
+
+/// Run counts overall, per core and per mutation strategy, and latency
+/// percentiles per core
+fn stats(campaign: Option<&str>) -> Result<Response> {
+    let records = campaign_records(campaign)?;
+    let mut total = Counts::default();
+    let mut by_core: BTreeMap<&str, Counts> = BTreeMap::new();
+    let mut by_strategy: BTreeMap<&str, Counts> = BTreeMap::new();
+    for record in &records {
+        total.add(record);
+        by_core.entry(&record.core).or_default().add(record);
+        if let Some(op) = &record.mutation {
+            by_strategy.entry(op.strategy.as_str()).or_default().add(record);
+        }
+    }
+    Ok(Response::json(
+        200,
+        &serde_json::json!({
+            "campaign": campaign,
+            "total": total,
+            "cores": by_core,
+            "strategies": by_strategy,
+            "latency": harness_core::latency::by_core(&records)
+                .iter()
+                .map(|(core, latency)| (core.clone(), latency.summary()))
+                .collect::<BTreeMap<_, _>>(),
+            "last_run": records.last().map(|r| &r.run_id),
+        }),
+    ))
+}
+
+/// Divergent runs (infrastructure failures excluded), newest first
+fn divergences(campaign: Option<&str>, core: Option<&str>, kind: Option<&str>) -> Result<Response> {
+    let index = harness_core::layout::load_index()?;
+    let divergent: Vec<_> = campaign_records(campaign)?
+        .into_iter()
+        .rev()
+        .filter(|r| !r.equal && !r.native_status.is_infra() && !r.sp1_status.is_infra())
+        .filter(|r| core.is_none_or(|core| r.core == core))
+        .filter(|r| kind.is_none_or(|kind| r.diff_kind.as_ref().map(DiffKind::as_str) == Some(kind)))
+        .map(|r| {
+            let repro_dir = index.runs.get(&r.run_id).and_then(|entry| entry.dir.as_ref());
+            serde_json::json!({
+                "repro_dir": repro_dir.map(|dir| dir.display().to_string()),
+                "bundle": format!("/bundles/{}", r.run_id),
+                "severity": harness_core::severity::of_record(&r),
+                "record": r,
+            })
+        })
+        .collect();
+    Ok(Response::json(200, &divergent))
+}
+
+/// `artifacts/bundles/<run_id>.tar.gz`, built on first download
+fn bundle(run_id: &str) -> Result<Response> {
+    if run_id.is_empty() || !run_id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-')) {
+        return Ok(Response::error(400, "invalid run id"));
+    }
+    if harness_core::layout::find_run(run_id)?.is_none() {
+        return Ok(Response::error(404, &format!("no run '{}'", run_id)));
+    }
+
+    let path = PathBuf::from("artifacts/bundles").join(format!("{}.tar.gz", run_id));
+    if !path.exists() {
+        crate::bundle_run(run_id, Some(path.clone()))?;
+    }
+    Ok(Response::bytes(200, "application/gzip", fs::read(&path)?)
+        .header("Content-Disposition", &format!("attachment; filename=\"{}.tar.gz\"", run_id)))
+}
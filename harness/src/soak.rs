@@ -0,0 +1,220 @@
+//! `harness soak`: fuzz rounds for hours or days, re-verifying the
+//! environment between rounds
+//!
+//! Guests are built once, then every round is a `harness fuzz` with
+//! `--skip-build` and the next `--seed`. Before the first round and whenever
+//! the check interval has passed, the toolchain, git state, guest ELF hashes
+//! and a canary run are compared with the baseline; see
+//! `harness_core::soak`.
+
+use anyhow::Result;
+use chrono::Utc;
+use harness_core::soak::{Canary, Check, Drift, DriftNotice, Fingerprint, Report};
+use harness_core::{
+    base_input_for_core, elf_path_for_core, new_run_id, run_native_runner_sandboxed, run_sp1_runner, sha256_file,
+    RetryPolicy,
+};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// What a soak does when a check finds drift
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OnDrift {
+    /// Stop the soak
+    Abort,
+    /// Fire the `fuzz.toml` notification hooks and keep fuzzing
+    Alert,
+}
+
+/// `harness soak` flags
+pub struct SoakOptions {
+    pub fuzz: harness_core::fuzz::FuzzOptions,
+    /// Stop starting rounds after this long
+    pub duration: Duration,
+    /// Stop after this many rounds
+    pub max_rounds: Option<usize>,
+    pub check_every: Duration,
+    pub canary_core: Option<String>,
+    pub canary_input: Option<PathBuf>,
+    pub on_drift: OnDrift,
+}
+
+/// The canary input and the ELFs to watch
+struct Targets<'a> {
+    elf_cores: Vec<&'a str>,
+    canary_core: &'a str,
+    canary_input: PathBuf,
+}
+
+fn fingerprint(targets: &Targets, sandbox: bool, retry: &RetryPolicy) -> Fingerprint {
+    let core = targets.canary_core;
+    let input = &targets.canary_input;
+    let native = retry.run_runner("native", || run_native_runner_sandboxed(core, input, sandbox));
+    let sp1 = retry.run_runner("sp1", || run_sp1_runner(&elf_path_for_core(core), input, core));
+    Fingerprint {
+        environment: harness_core::environment::Snapshot::capture(),
+        elfs: targets
+            .elf_cores
+            .iter()
+            .map(|core| (core.to_string(), sha256_file(&elf_path_for_core(core))))
+            .collect::<BTreeMap<_, _>>(),
+        canary: Canary {
+            core: core.to_string(),
+            input: input.display().to_string(),
+            native: (&native).into(),
+            sp1: (&sp1).into(),
+        },
+    }
+}
+
+/// Fuzz `cores_arg` in rounds until the duration or round limit is reached
+pub fn soak(cores_arg: &str, options: &SoakOptions, retry: &RetryPolicy) -> Result<()> {
+    let cores = harness_core::parse_cores_arg(cores_arg)?;
+    let canary_core = match &options.canary_core {
+        Some(core) => match harness_core::parse_cores_arg(core)?[..] {
+            [core] => core,
+            _ => anyhow::bail!("--canary-core takes a single core"),
+        },
+        None => cores[0],
+    };
+    let canary_input = match &options.canary_input {
+        Some(path) => path.clone(),
+        None => base_input_for_core(canary_core)?,
+    };
+    let mut elf_cores = cores.clone();
+    if !elf_cores.contains(&canary_core) {
+        elf_cores.push(canary_core);
+    }
+
+    // Every guest is built once; a soak whose guests do not build has
+    // nothing to verify against
+    let fuzz = &options.fuzz;
+    if fuzz.skip_build {
+        harness_core::provenance::ensure_fresh(elf_cores.iter().copied(), fuzz.allow_stale_elf)?;
+    } else {
+        let failed: Vec<&str> = elf_cores
+            .iter()
+            .zip(harness_core::fuzz::build_guests(&elf_cores, fuzz.build_jobs, retry))
+            .filter_map(|(core, error)| error.map(|_| *core))
+            .collect();
+        anyhow::ensure!(failed.is_empty(), "SP1 guest build failed for {}", failed.join(", "));
+    }
+
+    let targets = Targets {
+        elf_cores,
+        canary_core,
+        canary_input,
+    };
+    println!("🧪 Canary: {} on {}", targets.canary_core, targets.canary_input.display());
+    let baseline = fingerprint(&targets, fuzz.sandbox, retry);
+    let canary = &baseline.canary;
+    anyhow::ensure!(
+        !canary.native.status.is_infra() && !canary.sp1.status.is_infra(),
+        "Canary did not run (native {:?}, SP1 {:?}); a soak needs it to detect drift",
+        canary.native.status,
+        canary.sp1.status
+    );
+    println!("   native {:?}, SP1 {:?}", canary.native.status, canary.sp1.status);
+    println!("🌍 Environment: {} ({})", baseline.environment.id, baseline.environment.sp1_version);
+
+    let mut report = Report {
+        id: new_run_id("soak"),
+        cores: cores.iter().map(|core| core.to_string()).collect(),
+        started_at: Utc::now().to_rfc3339(),
+        baseline,
+        checks: Vec::new(),
+        rounds: 0,
+        aborted: false,
+    };
+    let report_path = report.save()?;
+    println!("💾 Soak report: {}", report_path.display());
+    println!(
+        "⏱️  Up to {:.1}h{}, checking every {} min, on drift: {:?}",
+        options.duration.as_secs_f64() / 3600.0,
+        options.max_rounds.map(|rounds| format!(" or {} rounds", rounds)).unwrap_or_default(),
+        options.check_every.as_secs() / 60,
+        options.on_drift
+    );
+    println!();
+
+    let start = Instant::now();
+    let mut last_check = Instant::now();
+    let mut alerted: Vec<Drift> = Vec::new();
+    loop {
+        let done = start.elapsed() >= options.duration
+            || options.max_rounds.is_some_and(|max_rounds| report.rounds >= max_rounds);
+        if done || last_check.elapsed() >= options.check_every {
+            let now = fingerprint(&targets, fuzz.sandbox, retry);
+            let drift = report.baseline.drift(&now);
+            last_check = Instant::now();
+            report.checks.push(Check {
+                at: Utc::now().to_rfc3339(),
+                rounds: report.rounds,
+                drift: drift.clone(),
+            });
+            if drift.is_empty() {
+                println!("✅ Environment check after {} rounds: no drift", report.rounds);
+                report.save()?;
+            } else {
+                println!("🚨 Environment drifted after {} rounds:", report.rounds);
+                for drift in &drift {
+                    println!("   {}", drift);
+                }
+                match options.on_drift {
+                    OnDrift::Abort => {
+                        report.aborted = true;
+                        report.save()?;
+                        anyhow::bail!("Soak aborted: the environment drifted (see {})", report_path.display());
+                    }
+                    OnDrift::Alert => {
+                        report.save()?;
+                        // Later rounds run in the drifted environment; log
+                        // their rows under its env_id, not the stale one
+                        if now.environment.id != harness_core::environment::current().id {
+                            println!("🌍 Environment is now {}", now.environment.id);
+                            harness_core::environment::replace(now.environment.clone());
+                        }
+                        // The same drift is reported once, not at every check
+                        if drift != alerted {
+                            harness_core::notify::notify_drift(&DriftNotice::new(&report.id, report.rounds, &drift));
+                            alerted = drift;
+                        }
+                    }
+                }
+            }
+            println!();
+        }
+        if done {
+            break;
+        }
+
+        report.rounds += 1;
+        println!("🔁 Soak round {} (seed {})", report.rounds, fuzz.inputs.seed + report.rounds as u64 - 1);
+        println!();
+        let mut inputs = fuzz.inputs;
+        inputs.seed += report.rounds as u64 - 1;
+        let round = harness_core::fuzz::FuzzOptions {
+            skip_build: true,
+            build_jobs: fuzz.build_jobs,
+            allow_stale_elf: true,
+            sandbox: fuzz.sandbox,
+            dedup: fuzz.dedup,
+            inputs,
+            buckets: fuzz.buckets,
+            bisect_max_probes: fuzz.bisect_max_probes,
+        };
+        // A failed core does not end the soak; the next check says whether
+        // the environment is to blame
+        if let Err(e) = harness_core::fuzz::run_fuzzing(cores_arg, &round, retry) {
+            println!("⚠️  Round {}: {:#}", report.rounds, e);
+        }
+        println!();
+    }
+
+    let drifted = report.checks.iter().filter(|check| !check.drift.is_empty()).count();
+    println!("🏁 Soak finished: {} rounds in {:.1}h", report.rounds, start.elapsed().as_secs_f64() / 3600.0);
+    println!("   Environment checks: {} ({} with drift)", report.checks.len(), drifted);
+    println!("💾 Soak report: {}", report_path.display());
+    Ok(())
+}
@@ -0,0 +1,824 @@
+//! Grammar-based input generation
+//!
+//! A core can describe the shape of its JSON input in its own `Cargo.toml`
+//! under `[package.metadata.fuzz.grammar]`. The grammar is a tree of typed
+//! nodes with ranges; `Grammar::generate` samples it with a seeded RNG, biased
+//! towards the edges of every range. Each sample is derived from the seed and
+//! its index alone, so the op's `rng_seed` and `sample` parameter identify it.
+//!
+//! Hand-picked inputs a sampler is unlikely to hit go under `cases`: named
+//! nodes generated every round next to the samples, from a seed fixed by the
+//! name. A case is generated once per combination of its `choice` nodes
+//! (outside arrays and options), so one case can cover a small cross product.
+//!
+//! ```toml
+//! [package.metadata.fuzz.grammar]
+//! samples = 16
+//!
+//! [package.metadata.fuzz.grammar.root]
+//! type = "object"
+//! fields.count = { type = "int", min = 0, max = 4294967295 }
+//! fields.name = { type = "string", max_len = 1000, charset = "unicode" }
+//! fields.flags = { type = "array", max_len = 8, items = { type = "bool" } }
+//!
+//! [package.metadata.fuzz.grammar.cases]
+//! long_name = { type = "object", fields = { count = { type = "choice", values = [0, 1] }, name = { type = "repeat", value = "a", count = 1000 }, flags = [] } }
+//! ```
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::{MutatedInput, MutationOp, MutationStrategy};
+
+/// Samples generated when the grammar does not set `samples`
+const DEFAULT_SAMPLES: usize = 16;
+
+/// One in `EDGE_ODDS` draws picks an edge value instead of a uniform one
+const EDGE_ODDS: u64 = 4;
+
+/// Most inputs one case may expand to
+const MAX_CASE_VARIANTS: usize = 256;
+
+/// Characters beyond ASCII used by the `unicode` charset
+const UNICODE_CHARS: &[char] = &['é', 'ß', 'Ω', 'ж', '中', '🦀', '\u{0}', '\u{200b}', '\u{fffd}'];
+
+/// Input grammar declared in a core manifest
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Grammar {
+    /// Number of inputs sampled per fuzz round
+    #[serde(default = "default_samples")]
+    pub samples: usize,
+    /// Shape of the whole input document
+    pub root: Node,
+    /// Fixed inputs generated every round, by name
+    #[serde(default)]
+    pub cases: BTreeMap<String, Node>,
+}
+
+fn default_samples() -> usize {
+    DEFAULT_SAMPLES
+}
+
+/// A value shape in the grammar
+///
+/// A table with a `type` key is a node; any other value is a literal
+/// (`const`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(remote = "Self", tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Node {
+    /// `true` or `false`
+    Bool,
+    /// Integer in `min..=max`; `edges` are extra values drawn with priority
+    Int {
+        min: i64,
+        max: i64,
+        #[serde(default)]
+        edges: Vec<i64>,
+    },
+    /// Finite float in `min..=max`; `edges` are extra values drawn with priority
+    Float {
+        min: f64,
+        max: f64,
+        #[serde(default)]
+        edges: Vec<f64>,
+    },
+    /// String with a length (in chars) in `min_len..=max_len`
+    String {
+        #[serde(default)]
+        min_len: usize,
+        max_len: usize,
+        #[serde(default)]
+        charset: Charset,
+    },
+    /// Array of `items` with a length in `min_len..=max_len`
+    Array {
+        items: Box<Node>,
+        #[serde(default)]
+        min_len: usize,
+        max_len: usize,
+    },
+    /// Object with every field present
+    Object { fields: BTreeMap<String, Node> },
+    /// One of a fixed set of literal values
+    Choice { values: Vec<Value> },
+    /// `null` or a value of `some`
+    Option { some: Box<Node> },
+    /// A literal value
+    Const { value: Value },
+    /// `start`, `start + step`, ... before `end`: integers if `start` and
+    /// `step` are, else floats; `1/x` of each if `reciprocal`
+    Range {
+        start: f64,
+        end: f64,
+        #[serde(default = "default_step")]
+        step: f64,
+        #[serde(default)]
+        reciprocal: bool,
+    },
+    /// A literal string or array repeated `count` times
+    Repeat { value: Value, count: usize },
+    /// Strings or arrays joined end to end
+    Concat { parts: Vec<Node> },
+}
+
+fn default_step() -> f64 {
+    1.0
+}
+
+impl<'de> Deserialize<'de> for Node {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        // Buffered as TOML, which (unlike JSON) can hold the NaN and inf
+        // that validation rejects
+        let value = toml::Value::deserialize(deserializer)?;
+        if value.get("type").is_some_and(toml::Value::is_str) {
+            Node::deserialize(value).map_err(serde::de::Error::custom)
+        } else {
+            let value = Value::deserialize(value).map_err(serde::de::Error::custom)?;
+            Ok(Node::Const { value })
+        }
+    }
+}
+
+/// Characters a `string` node draws from
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Charset {
+    /// Letters and digits
+    Alnum,
+    /// Printable ASCII plus tab and newline
+    #[default]
+    Ascii,
+    /// ASCII mixed with multi-byte, zero-width and NUL characters
+    Unicode,
+}
+
+impl Grammar {
+    /// Parse the grammar out of a core's `Cargo.toml` (None if it has none)
+    pub fn from_manifest(manifest: &str) -> Result<Option<Grammar>> {
+        match fuzz_metadata_in(manifest, "grammar")? {
+            Some(grammar) => {
+                let grammar: Grammar = grammar
+                    .try_into()
+                    .context("Invalid [package.metadata.fuzz.grammar]")?;
+                grammar.root.validate("root")?;
+                for (name, case) in &grammar.cases {
+                    let path = format!("cases.{}", name);
+                    case.validate(&path)?;
+                    anyhow::ensure!(
+                        case.variants() <= MAX_CASE_VARIANTS,
+                        "{}: {} choice combinations (at most {})",
+                        path,
+                        case.variants(),
+                        MAX_CASE_VARIANTS
+                    );
+                }
+                Ok(Some(grammar))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Sample `self.samples` inputs, each seeded from `seed` and its index,
+    /// followed by every variant of every case
+    pub fn generate(&self, seed: u64, base_input_path: &str) -> Vec<MutatedInput> {
+        let samples = (0..self.samples).map(|index| {
+            let mut rng = SplitMix64::new(sample_seed(seed, index));
+            let mut op = MutationOp::new(MutationStrategy::Grammar, serde_json::json!({ "sample": index }));
+            op.rng_seed = Some(seed);
+            MutatedInput {
+                input_json: self.root.sample(&mut rng, None),
+                op,
+                base_input_path: base_input_path.to_string(),
+            }
+        });
+        let cases = self.cases.iter().flat_map(|(name, case)| {
+            let variants = case.variants();
+            (0..variants).map(move |variant| {
+                // Every variant shares the case's random parts
+                let mut rng = SplitMix64::new(case_seed(name));
+                let params = if variants > 1 {
+                    serde_json::json!({ "case": name, "variant": variant })
+                } else {
+                    serde_json::json!({ "case": name })
+                };
+                MutatedInput {
+                    input_json: case.sample(&mut rng, Some(&mut { variant })),
+                    op: MutationOp::new(MutationStrategy::Grammar, params),
+                    base_input_path: base_input_path.to_string(),
+                }
+            })
+        });
+        samples.chain(cases).collect()
+    }
+}
+
+/// Manifest that may declare a core's grammar (relative to the repo root)
+pub fn core_manifest_path(core_name: &str) -> PathBuf {
+    Path::new("guest/cores").join(core_name).join("Cargo.toml")
+}
+
+/// A key under `[package.metadata.fuzz]` of a manifest (None if unset)
+///
+/// The one walk of that table; the harness reads its per-core settings
+/// through it too.
+pub fn fuzz_metadata_in(manifest: &str, key: &str) -> Result<Option<toml::Value>> {
+    let manifest: toml::Table = toml::from_str(manifest).context("Invalid Cargo.toml")?;
+    Ok(manifest
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get("fuzz"))
+        .and_then(|fuzz| fuzz.get(key))
+        .cloned())
+}
+
+/// A key under `[package.metadata.fuzz]` of a core's manifest (None if the
+/// core has no manifest or does not set it)
+pub fn fuzz_metadata(core_name: &str, key: &str) -> Result<Option<toml::Value>> {
+    let path = core_manifest_path(core_name);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let manifest = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    fuzz_metadata_in(&manifest, key).with_context(|| format!("In {}", path.display()))
+}
+
+/// Load a core's grammar from its manifest (None if it declares none)
+pub fn load_core_grammar(core_name: &str) -> Result<Option<Grammar>> {
+    let path = core_manifest_path(core_name);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let manifest = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Grammar::from_manifest(&manifest).with_context(|| format!("In {}", path.display()))
+}
+
+/// `[package.metadata.fuzz.boundary] samples` of a core (None if unset)
+pub fn load_boundary_samples(core_name: &str) -> Result<Option<usize>> {
+    let samples = fuzz_metadata(core_name, "boundary")?.and_then(|boundary| boundary.get("samples").cloned());
+    match samples {
+        Some(samples) => {
+            let samples = samples
+                .as_integer()
+                .and_then(|samples| usize::try_from(samples).ok())
+                .with_context(|| {
+                    format!(
+                        "{}: [package.metadata.fuzz.boundary] samples must be a non-negative integer",
+                        core_manifest_path(core_name).display()
+                    )
+                })?;
+            Ok(Some(samples))
+        }
+        None => Ok(None),
+    }
+}
+
+/// `[package.metadata.fuzz] memory_limit` of a core in bytes (None if unset)
+pub fn load_memory_limit(core_name: &str) -> Result<Option<u64>> {
+    match fuzz_metadata(core_name, "memory_limit")? {
+        Some(limit) => {
+            let limit = limit
+                .as_integer()
+                .and_then(|limit| u64::try_from(limit).ok())
+                .with_context(|| {
+                    format!(
+                        "{}: [package.metadata.fuzz] memory_limit must be a non-negative integer",
+                        core_manifest_path(core_name).display()
+                    )
+                })?;
+            Ok(Some(limit))
+        }
+        None => Ok(None),
+    }
+}
+
+/// `[package.metadata.fuzz] max_cycles` of a core (None if unset)
+pub fn load_max_cycles(core_name: &str) -> Result<Option<u64>> {
+    match fuzz_metadata(core_name, "max_cycles")? {
+        Some(budget) => {
+            let budget = budget
+                .as_integer()
+                .and_then(|budget| u64::try_from(budget).ok())
+                .filter(|budget| *budget > 0)
+                .with_context(|| {
+                    format!(
+                        "{}: [package.metadata.fuzz] max_cycles must be a positive integer",
+                        core_manifest_path(core_name).display()
+                    )
+                })?;
+            Ok(Some(budget))
+        }
+        None => Ok(None),
+    }
+}
+
+/// `[package.metadata.fuzz] preprocess` of a core, parsed (no steps if unset)
+pub fn load_core_preprocess(core_name: &str) -> Result<input_preprocess::Preprocess> {
+    let spec = fuzz_metadata(core_name, "preprocess")?;
+    input_preprocess::Preprocess::parse(spec.as_ref().and_then(|spec| spec.as_str()).unwrap_or_default())
+        .with_context(|| format!("{}: invalid preprocess spec", core_manifest_path(core_name).display()))
+}
+
+impl Node {
+    /// Reject empty ranges and choices up front so sampling cannot fail
+    fn validate(&self, path: &str) -> Result<()> {
+        match self {
+            Node::Bool => {}
+            Node::Int { min, max, .. } => {
+                anyhow::ensure!(min <= max, "{}: min {} > max {}", path, min, max)
+            }
+            Node::Float { min, max, edges } => {
+                anyhow::ensure!(
+                    min.is_finite() && max.is_finite() && min <= max,
+                    "{}: invalid float range {}..={}",
+                    path,
+                    min,
+                    max
+                );
+                anyhow::ensure!(
+                    edges.iter().all(|edge| edge.is_finite()),
+                    "{}: float edges must be finite (JSON has no NaN/inf)",
+                    path
+                );
+            }
+            Node::String { min_len, max_len, .. } => {
+                anyhow::ensure!(min_len <= max_len, "{}: min_len {} > max_len {}", path, min_len, max_len)
+            }
+            Node::Array { items, min_len, max_len } => {
+                anyhow::ensure!(min_len <= max_len, "{}: min_len {} > max_len {}", path, min_len, max_len);
+                items.validate(&format!("{}[]", path))?;
+            }
+            Node::Object { fields } => {
+                for (name, field) in fields {
+                    field.validate(&format!("{}.{}", path, name))?;
+                }
+            }
+            Node::Choice { values } => {
+                anyhow::ensure!(!values.is_empty(), "{}: choice needs at least one value", path)
+            }
+            Node::Option { some } => some.validate(&format!("{}?", path))?,
+            Node::Const { .. } => {}
+            Node::Range { start, end, step, .. } => {
+                anyhow::ensure!(
+                    start.is_finite() && end.is_finite() && step.is_finite() && *step != 0.0,
+                    "{}: invalid range {}..{} step {}",
+                    path,
+                    start,
+                    end,
+                    step
+                );
+            }
+            Node::Repeat { value, .. } => {
+                anyhow::ensure!(
+                    value.is_string() || value.is_array(),
+                    "{}: repeat needs a string or an array",
+                    path
+                )
+            }
+            Node::Concat { parts } => {
+                let kinds: Vec<Option<&str>> = parts.iter().map(Node::sequence_kind).collect();
+                anyhow::ensure!(
+                    kinds.iter().all(|kind| kind.is_some() && *kind == kinds[0]),
+                    "{}: concat parts must all be strings or all be arrays",
+                    path
+                );
+                for (index, part) in parts.iter().enumerate() {
+                    part.validate(&format!("{}[{}]", path, index))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `string` or `array` if every value of the node is one
+    fn sequence_kind(&self) -> Option<&'static str> {
+        let literal = |value: &Value| match value {
+            Value::String(_) => Some("string"),
+            Value::Array(_) => Some("array"),
+            _ => None,
+        };
+        match self {
+            Node::String { .. } => Some("string"),
+            Node::Array { .. } | Node::Range { .. } => Some("array"),
+            Node::Const { value } | Node::Repeat { value, .. } => literal(value),
+            Node::Choice { values } => {
+                let kind = literal(values.first()?)?;
+                values.iter().all(|value| literal(value) == Some(kind)).then_some(kind)
+            }
+            Node::Concat { parts } => parts.first()?.sequence_kind(),
+            _ => None,
+        }
+    }
+
+    /// Combinations of the `choice` nodes a case expands to (choices inside
+    /// arrays and options are sampled instead)
+    fn variants(&self) -> usize {
+        match self {
+            Node::Choice { values } => values.len(),
+            Node::Object { fields } => fields.values().map(Node::variants).fold(1, usize::saturating_mul),
+            Node::Concat { parts } => parts.iter().map(Node::variants).fold(1, usize::saturating_mul),
+            _ => 1,
+        }
+    }
+
+    /// Sample a value; `variant` (for cases) picks the static choices, as a
+    /// mixed-radix number consumed in traversal order
+    fn sample(&self, rng: &mut SplitMix64, mut variant: Option<&mut usize>) -> Value {
+        match self {
+            Node::Bool => Value::Bool(rng.below(2) == 1),
+            Node::Int { min, max, edges } => {
+                let value = if rng.below(EDGE_ODDS) == 0 {
+                    let mut candidates = vec![
+                        *min,
+                        *max,
+                        min.saturating_add(1).min(*max),
+                        max.saturating_sub(1).max(*min),
+                    ];
+                    if *min <= 0 && 0 <= *max {
+                        candidates.push(0);
+                    }
+                    candidates.extend(edges.iter().copied());
+                    candidates[rng.below(candidates.len() as u64) as usize]
+                } else {
+                    let span = (*max as i128 - *min as i128 + 1) as u128;
+                    (*min as i128 + (rng.next_u64() as u128 % span) as i128) as i64
+                };
+                Value::from(value)
+            }
+            Node::Float { min, max, edges } => {
+                let value = if rng.below(EDGE_ODDS) == 0 {
+                    let mut candidates = vec![*min, *max];
+                    if *min <= 0.0 && 0.0 <= *max {
+                        candidates.push(0.0);
+                    }
+                    candidates.extend(edges.iter().copied());
+                    candidates[rng.below(candidates.len() as u64) as usize]
+                } else {
+                    min + (max - min) * rng.unit()
+                };
+                Value::from(value)
+            }
+            Node::String { min_len, max_len, charset } => {
+                let len = sample_len(rng, *min_len, *max_len);
+                Value::String((0..len).map(|_| charset.sample(rng)).collect())
+            }
+            Node::Array { items, min_len, max_len } => {
+                let len = sample_len(rng, *min_len, *max_len);
+                Value::Array((0..len).map(|_| items.sample(rng, None)).collect())
+            }
+            Node::Object { fields } => Value::Object(
+                fields
+                    .iter()
+                    .map(|(name, field)| (name.clone(), field.sample(rng, variant.as_deref_mut())))
+                    .collect(),
+            ),
+            Node::Choice { values } => {
+                let index = match variant {
+                    Some(variant) => {
+                        let index = *variant % values.len();
+                        *variant /= values.len();
+                        index
+                    }
+                    None => rng.below(values.len() as u64) as usize,
+                };
+                values[index].clone()
+            }
+            Node::Option { some } => {
+                if rng.below(EDGE_ODDS) == 0 {
+                    Value::Null
+                } else {
+                    some.sample(rng, None)
+                }
+            }
+            Node::Const { value } => value.clone(),
+            Node::Range { start, end, step, reciprocal } => {
+                let count = ((end - start) / step).ceil().max(0.0) as usize;
+                let integers = !reciprocal && start.fract() == 0.0 && step.fract() == 0.0;
+                Value::Array(
+                    (0..count)
+                        .map(|index| {
+                            let x = start + step * index as f64;
+                            if integers {
+                                Value::from(x as i64)
+                            } else if *reciprocal {
+                                Value::from(1.0 / x)
+                            } else {
+                                Value::from(x)
+                            }
+                        })
+                        .collect(),
+                )
+            }
+            Node::Repeat { value, count } => match value {
+                Value::String(text) => Value::String(text.repeat(*count)),
+                Value::Array(items) => Value::Array(items.iter().cycle().take(items.len() * count).cloned().collect()),
+                other => other.clone(),
+            },
+            Node::Concat { parts } => {
+                let parts: Vec<Value> = parts.iter().map(|part| part.sample(rng, variant.as_deref_mut())).collect();
+                if parts.iter().all(Value::is_string) {
+                    Value::String(parts.iter().filter_map(Value::as_str).collect())
+                } else {
+                    let items = parts.into_iter().flat_map(|part| match part {
+                        Value::Array(items) => items,
+                        other => vec![other],
+                    });
+                    Value::Array(items.collect())
+                }
+            }
+        }
+    }
+}
+
+impl Charset {
+    fn sample(&self, rng: &mut SplitMix64) -> char {
+        const ALNUM: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+        match self {
+            Charset::Alnum => ALNUM[rng.below(ALNUM.len() as u64) as usize] as char,
+            Charset::Ascii => match rng.below(32) {
+                0 => '\t',
+                1 => '\n',
+                _ => (b' ' + rng.below(95) as u8) as char,
+            },
+            Charset::Unicode => {
+                if rng.below(2) == 0 {
+                    UNICODE_CHARS[rng.below(UNICODE_CHARS.len() as u64) as usize]
+                } else {
+                    Charset::Ascii.sample(rng)
+                }
+            }
+        }
+    }
+}
+
+/// Length in `min..=max`: an edge length, or log-uniform so short and long
+/// lengths are both common
+fn sample_len(rng: &mut SplitMix64, min: usize, max: usize) -> usize {
+    if rng.below(EDGE_ODDS) == 0 {
+        return if rng.below(2) == 0 { min } else { max };
+    }
+    let span = (max - min) as u64;
+    let bits = rng.below(65 - span.leading_zeros() as u64);
+    let limit = if bits == 0 { 0 } else { u64::MAX >> (64 - bits) };
+    min + (rng.next_u64() & limit).min(span) as usize
+}
+
+/// Seed for one sample, so samples do not depend on each other
+fn sample_seed(seed: u64, index: usize) -> u64 {
+    seed ^ (index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+/// Seed of a case: FNV-1a of its name, so a case is the same every round
+fn case_seed(name: &str) -> u64 {
+    name.bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// SplitMix64 (fixed algorithm, so seeds reproduce across toolchains)
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..bound` (bound > 0)
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    /// Uniform in `[0, 1]`
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / ((1u64 << 53) - 1) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+[package]
+name = "example"
+
+[package.metadata.fuzz.grammar.root]
+type = "object"
+fields.count = { type = "int", min = -5, max = 5, edges = [3] }
+fields.ratio = { type = "float", min = 0.0, max = 1.0 }
+fields.name = { type = "string", max_len = 8, charset = "alnum" }
+fields.tags = { type = "array", min_len = 1, max_len = 3, items = { type = "choice", values = ["a", "b"] } }
+fields.maybe = { type = "option", some = { type = "bool" } }
+
+[package.metadata.fuzz.grammar.cases.fixed]
+type = "object"
+fields.count = { type = "choice", values = [0, 1, 2] }
+fields.name = { type = "concat", parts = ["x", { type = "repeat", value = "y", count = 3 }] }
+fields.tags = { type = "range", start = 3, end = 0, step = -1 }
+fields.nested = { type = "const", value = { type = "not a node" } }
+fields.maybe = { type = "option", some = { type = "choice", values = [true, false] } }
+"#;
+
+    fn grammar(manifest: &str) -> Grammar {
+        Grammar::from_manifest(manifest).unwrap().unwrap()
+    }
+
+    fn grammar_error(root: &str) -> String {
+        let manifest = format!("[package.metadata.fuzz.grammar.root]\n{}\n", root);
+        format!("{:#}", Grammar::from_manifest(&manifest).unwrap_err())
+    }
+
+    #[test]
+    fn test_from_manifest() {
+        let grammar = grammar(MANIFEST);
+        assert_eq!(grammar.samples, DEFAULT_SAMPLES);
+        assert!(matches!(&grammar.root, Node::Object { fields } if fields.len() == 5));
+        let Node::Object { fields } = &grammar.cases["fixed"] else {
+            panic!("case is not an object");
+        };
+        // Values without a `type` key are literals
+        assert!(matches!(&fields["name"], Node::Concat { parts } if matches!(&parts[0], Node::Const { value } if value == "x")));
+        assert_eq!(grammar.cases["fixed"].variants(), 3);
+
+        assert!(Grammar::from_manifest("[package]\nname = \"example\"\n").unwrap().is_none());
+        assert!(Grammar::from_manifest("[package.metadata.fuzz]\ncommits = \"u32\"\n").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_fuzz_metadata_in() {
+        let manifest = "[package]\nname = \"x\"\n\n[package.metadata.fuzz]\nmemory_limit = 1024\n";
+        assert_eq!(fuzz_metadata_in(manifest, "memory_limit").unwrap(), Some(toml::Value::Integer(1024)));
+        assert_eq!(fuzz_metadata_in(manifest, "preprocess").unwrap(), None);
+        assert_eq!(fuzz_metadata_in("[package]\nname = \"x\"\n", "memory_limit").unwrap(), None);
+        assert!(fuzz_metadata_in("[package", "memory_limit").is_err());
+
+        // Looping cores declare a cycle budget
+        let compress = include_str!("../../../guest/cores/compress/Cargo.toml");
+        assert_eq!(fuzz_metadata_in(compress, "max_cycles").unwrap(), Some(toml::Value::Integer(2_000_000_000)));
+    }
+
+    #[test]
+    fn test_from_manifest_errors() {
+        assert!(grammar_error(r#"type = "int"
+min = 2
+max = 1"#)
+            .contains("root: min 2 > max 1"));
+        assert!(grammar_error(r#"type = "int"
+min = 0
+max = 1
+mx = 2"#)
+            .contains("unknown field `mx`"));
+        assert!(grammar_error(r#"type = "integer""#).contains("unknown variant `integer`"));
+        assert!(grammar_error(r#"type = "float"
+min = 0.0
+max = 1.0
+edges = [inf]"#)
+            .contains("float edges must be finite"));
+        assert!(grammar_error(r#"type = "object"
+fields.items = { type = "array", min_len = 4, max_len = 2, items = { type = "bool" } }"#)
+            .contains("root.items: min_len 4 > max_len 2"));
+        assert!(grammar_error(r#"type = "array"
+max_len = 2
+items = { type = "choice", values = [] }"#)
+            .contains("root[]: choice needs at least one value"));
+        assert!(grammar_error(r#"type = "concat"
+parts = ["a", [1]]"#)
+            .contains("concat parts must all be strings or all be arrays"));
+        assert!(grammar_error(r#"type = "repeat"
+value = 1
+count = 2"#)
+            .contains("repeat needs a string or an array"));
+        assert!(grammar_error(r#"type = "range"
+start = 0
+end = 10
+step = 0"#)
+            .contains("invalid range"));
+
+        let too_many = r#"[package.metadata.fuzz.grammar]
+root = { type = "bool" }
+cases.big = { type = "object", fields = { a = { type = "choice", values = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15] }, b = { type = "choice", values = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16] } } }
+"#;
+        let error = format!("{:#}", Grammar::from_manifest(too_many).unwrap_err());
+        assert!(error.contains("cases.big: 272 choice combinations"), "{}", error);
+    }
+
+    #[test]
+    fn test_samples_are_deterministic() {
+        let grammar = grammar(MANIFEST);
+        let inputs = |grammar: &Grammar, seed| -> Vec<Value> {
+            grammar.generate(seed, "base.json").into_iter().map(|input| input.input_json).collect()
+        };
+        let first = inputs(&grammar, 7);
+        assert_eq!(first, inputs(&grammar, 7));
+        assert_ne!(first[..DEFAULT_SAMPLES], inputs(&grammar, 8)[..DEFAULT_SAMPLES]);
+
+        // A sample depends on the seed and its index only
+        let fewer = Grammar { samples: 4, ..grammar.clone() };
+        assert_eq!(inputs(&fewer, 7)[..4], first[..4]);
+
+        for input in &first[..DEFAULT_SAMPLES] {
+            let count = input["count"].as_i64().unwrap();
+            assert!((-5..=5).contains(&count));
+            assert!((0.0..=1.0).contains(&input["ratio"].as_f64().unwrap()));
+            let name = input["name"].as_str().unwrap();
+            assert!(name.len() <= 8 && name.chars().all(|c| c.is_ascii_alphanumeric()));
+            let tags = input["tags"].as_array().unwrap();
+            assert!((1..=3).contains(&tags.len()) && tags.iter().all(|tag| tag == "a" || tag == "b"));
+            assert!(input["maybe"].is_null() || input["maybe"].is_boolean());
+        }
+
+        let ops: Vec<String> = grammar.generate(7, "base.json").iter().map(|input| input.op.to_string()).collect();
+        assert_eq!(ops[0], "grammar:sample=0");
+        assert_eq!(grammar.generate(7, "base.json")[0].op.rng_seed, Some(7));
+    }
+
+    #[test]
+    fn test_cases() {
+        let grammar = grammar(MANIFEST);
+        let cases: Vec<MutatedInput> = grammar.generate(7, "base.json").split_off(DEFAULT_SAMPLES);
+        assert_eq!(cases.len(), 3);
+        for (variant, case) in cases.iter().enumerate() {
+            assert_eq!(case.input_json["count"], variant);
+            assert_eq!(case.input_json["name"], "xyyy");
+            assert_eq!(case.input_json["tags"], serde_json::json!([3, 2, 1]));
+            assert_eq!(case.input_json["nested"], serde_json::json!({ "type": "not a node" }));
+            assert_eq!(case.op.to_string(), format!("grammar:case=fixed,variant={}", variant));
+            assert_eq!(case.op.rng_seed, None);
+        }
+        // Random parts are fixed by the case name, not the round's seed
+        let other_round = grammar.generate(8, "base.json").split_off(DEFAULT_SAMPLES);
+        let values = |inputs: &[MutatedInput]| inputs.iter().map(|input| input.input_json.clone()).collect::<Vec<_>>();
+        assert_eq!(values(&cases), values(&other_round));
+    }
+
+    #[test]
+    fn test_ranges() {
+        let range = |start, end, step, reciprocal| {
+            Node::Range { start, end, step, reciprocal }.sample(&mut SplitMix64::new(0), None)
+        };
+        assert_eq!(range(0.0, 3.0, 1.0, false), serde_json::json!([0, 1, 2]));
+        assert_eq!(range(-0.5, -3.0, -1.0, false), serde_json::json!([-0.5, -1.5, -2.5]));
+        assert_eq!(range(1.0, 5.0, 2.0, true), serde_json::json!([1.0, 1.0 / 3.0]));
+        assert_eq!(range(3.0, 0.0, 1.0, false), serde_json::json!([]));
+    }
+
+    /// The manifests of the cores with grammars parse, and their cases keep
+    /// the hand-written edge cases they replaced
+    #[test]
+    fn test_core_grammars() {
+        let core = |manifest: &str| {
+            let grammar = grammar(manifest);
+            let cases: BTreeMap<String, Value> = grammar
+                .generate(0, "base.json")
+                .into_iter()
+                .skip(grammar.samples)
+                .map(|input| (input.op.to_string(), input.input_json))
+                .collect();
+            cases
+        };
+
+        let simple_struct = core(include_str!("../../../guest/cores/simple_struct/Cargo.toml"));
+        assert_eq!(simple_struct.len(), 10 + 22);
+        assert_eq!(simple_struct["grammar:case=10kchars"]["field2"].as_str().unwrap().len(), 10_000);
+        assert_eq!(simple_struct["grammar:case=newline"]["field2"], "Hello\nWorld");
+        let combining = simple_struct["grammar:case=combining_marks_10k"]["field2"].as_str().unwrap();
+        assert_eq!((combining.chars().count(), combining.len()), (10_000, 1 + 9_999 * 2));
+        assert_eq!(simple_struct["grammar:case=surrogate_escape_text"]["field2"], "\\ud800\\udc00");
+
+        let sorting = core(include_str!("../../../guest/cores/sorting/Cargo.toml"));
+        assert_eq!(sorting.len(), 7 * 3 + 5);
+        let reversed = &sorting["grammar:case=keys_reversed_1000,variant=2"];
+        assert_eq!(reversed["tie_bucket"], i32::MAX);
+        let keys = reversed["keys"].as_array().unwrap();
+        assert_eq!((keys.len(), &keys[0], &keys[999]), (1000, &Value::from(999), &Value::from(0)));
+        assert_eq!(sorting["grammar:case=keys_random_100000,variant=0"]["keys"].as_array().unwrap().len(), 100_000);
+        assert_eq!(
+            sorting["grammar:case=keys_random_1000,variant=0"]["keys"],
+            sorting["grammar:case=keys_random_1000,variant=1"]["keys"]
+        );
+        let nan = Value::from(f32::NAN.to_bits());
+        assert_eq!(sorting["grammar:case=floats_many_nans"]["float_bits"][0], nan);
+
+        let iterator_fold = core(include_str!("../../../guest/cores/iterator_fold/Cargo.toml"));
+        assert_eq!(iterator_fold.len(), 9 * 3);
+        let big_then_small = iterator_fold["grammar:case=big_then_small,variant=0"]["values"].as_array().unwrap();
+        assert_eq!((big_then_small.len(), big_then_small[0].as_f64()), (1001, Some(1.0e8)));
+        let harmonic = iterator_fold["grammar:case=harmonic_10000,variant=1"]["values"].as_array().unwrap();
+        assert_eq!((harmonic.len(), harmonic[1].as_f64()), (10_000, Some(0.5)));
+        assert_eq!(iterator_fold["grammar:case=negatives,variant=0"]["values"].as_array().unwrap().len(), 100);
+    }
+}
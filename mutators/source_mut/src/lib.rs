@@ -1,51 +1,437 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+pub mod grammar;
 
 /// Represents a generated input mutation
 #[derive(Debug, Clone)]
 pub struct MutatedInput {
     /// The mutated input as JSON
     pub input_json: Value,
-    /// Description of the mutation applied (e.g., "length_bias:1024->2048")
-    pub mutation_op: String,
+    /// The mutation applied
+    pub op: MutationOp,
     /// The base input that was mutated
     pub base_input_path: String,
 }
 
+/// Machine-readable description of a mutation (logged to `artifacts/runs.jsonl`)
+///
+/// `Display` renders the compact label used in the console, the CSV
+/// `mutation_ops` column and promoted seed names, e.g. `length_bias:size=1024`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MutationOp {
+    pub strategy: MutationStrategy,
+    /// Values the strategy picked for this input (numbers and bools keep their type)
+    pub params: serde_json::Map<String, Value>,
+    /// SHA-256 of the parent input file (set by `generate_mutations`)
+    pub parent_sha256: String,
+    /// Seed of the RNG that produced the input (None for deterministic mutations)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rng_seed: Option<u64>,
+    /// Name of the core's base seed the parent input is (set by the
+    /// harness; None for promoted seeds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_seed: Option<String>,
+}
+
+impl MutationOp {
+    /// An op with the given parameters (`params` must be a JSON object)
+    pub fn new(strategy: MutationStrategy, params: Value) -> Self {
+        let params = match params {
+            Value::Object(params) => params,
+            other => panic!("mutation params must be an object, got {}", other),
+        };
+        MutationOp {
+            strategy,
+            params,
+            parent_sha256: String::new(),
+            rng_seed: None,
+            base_seed: None,
+        }
+    }
+}
+
+impl fmt::Display for MutationOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.strategy.as_str())?;
+        for (idx, (name, value)) in self.params.iter().enumerate() {
+            let sep = if idx == 0 { "" } else { "," };
+            match value {
+                Value::String(text) => write!(f, "{}{}={}", sep, name, text)?,
+                other => write!(f, "{}{}={}", sep, name, other)?,
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Input mutation strategies
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MutationStrategy {
     /// Length biasing for Vec<u8> inputs (io_echo)
     LengthBias,
     /// Boundary value testing for arithmetic inputs
     BoundaryValues,
-    /// String variations for struct inputs
-    StringVariations,
     /// Fibonacci number variations
     FibonacciValues,
     /// Boolean variations
     BooleanVariations,
     /// Iteration count variations
     IterationVariations,
+    /// Numeric text variations for parse round-trips
+    ParseVariations,
+    /// Probe selection variations for nondeterministic syscalls
+    ProbeSelection,
+    /// Environment lookup variations
+    EnvLookups,
+    /// Panic/Drop interaction modes
+    PanicDropModes,
+    /// Allocation sizes around guest memory limits
+    AllocationLimits,
+    /// Loop lengths and checkpoint intervals
+    CheckpointIntervals,
+    /// Enum variant and payload shapes
+    EnumShapes,
+    /// Bit patterns at aligned and unaligned offsets
+    BitPatterns,
+    /// Payload patterns, sizes and deflate levels
+    CompressionPayloads,
+    /// Adversarial JSON documents (nesting, numbers, escapes)
+    AdversarialJson,
+    /// Valid and corrupted RLP, borsh and bincode encodings
+    BinaryCodecs,
+    /// Valid and malformed UTF-8 sequences with offsets around char boundaries
+    Utf8Sequences,
+    /// Push/pop/reserve scripts for VecDeque and Vec
+    CollectionScripts,
+    /// Worker and iteration counts for thread, Mutex and atomic APIs
+    ThreadCounts,
+    /// Seeded samples and fixed cases from the input grammar in the core
+    /// manifest (simple_struct's unicode cases were logged as `unicode_strings`)
+    #[serde(alias = "unicode_strings")]
+    Grammar,
+    /// A named base seed from the core manifest, unmutated
+    BaseSeed,
+    /// One point of a core's enumerated value grid (`fuzz --mode exhaustive`)
+    Exhaustive,
+}
+
+impl MutationStrategy {
+    /// Every registered strategy
+    pub const ALL: &'static [MutationStrategy] = &[
+        MutationStrategy::LengthBias,
+        MutationStrategy::BoundaryValues,
+        MutationStrategy::FibonacciValues,
+        MutationStrategy::BooleanVariations,
+        MutationStrategy::IterationVariations,
+        MutationStrategy::ParseVariations,
+        MutationStrategy::ProbeSelection,
+        MutationStrategy::EnvLookups,
+        MutationStrategy::PanicDropModes,
+        MutationStrategy::AllocationLimits,
+        MutationStrategy::CheckpointIntervals,
+        MutationStrategy::EnumShapes,
+        MutationStrategy::BitPatterns,
+        MutationStrategy::CompressionPayloads,
+        MutationStrategy::AdversarialJson,
+        MutationStrategy::BinaryCodecs,
+        MutationStrategy::Utf8Sequences,
+        MutationStrategy::CollectionScripts,
+        MutationStrategy::ThreadCounts,
+        MutationStrategy::Grammar,
+        MutationStrategy::BaseSeed,
+        MutationStrategy::Exhaustive,
+    ];
+
+    /// Snake-case name, as serialized in `MutationOp`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MutationStrategy::LengthBias => "length_bias",
+            MutationStrategy::BoundaryValues => "boundary_values",
+            MutationStrategy::FibonacciValues => "fibonacci_values",
+            MutationStrategy::BooleanVariations => "boolean_variations",
+            MutationStrategy::IterationVariations => "iteration_variations",
+            MutationStrategy::ParseVariations => "parse_variations",
+            MutationStrategy::ProbeSelection => "probe_selection",
+            MutationStrategy::EnvLookups => "env_lookups",
+            MutationStrategy::PanicDropModes => "panic_drop_modes",
+            MutationStrategy::AllocationLimits => "allocation_limits",
+            MutationStrategy::CheckpointIntervals => "checkpoint_intervals",
+            MutationStrategy::EnumShapes => "enum_shapes",
+            MutationStrategy::BitPatterns => "bit_patterns",
+            MutationStrategy::CompressionPayloads => "compression_payloads",
+            MutationStrategy::AdversarialJson => "adversarial_json",
+            MutationStrategy::BinaryCodecs => "binary_codecs",
+            MutationStrategy::Utf8Sequences => "utf8_sequences",
+            MutationStrategy::CollectionScripts => "collection_scripts",
+            MutationStrategy::ThreadCounts => "thread_counts",
+            MutationStrategy::Grammar => "grammar",
+            MutationStrategy::BaseSeed => "base_seed",
+            MutationStrategy::Exhaustive => "exhaustive",
+        }
+    }
+
+    /// Short description of what the strategy varies
+    pub fn description(&self) -> &'static str {
+        match self {
+            MutationStrategy::LengthBias => "Length biasing for Vec<u8> inputs",
+            MutationStrategy::BoundaryValues => "Boundary value testing for arithmetic inputs",
+            MutationStrategy::FibonacciValues => "Fibonacci number variations",
+            MutationStrategy::BooleanVariations => "Boolean variations",
+            MutationStrategy::IterationVariations => "Iteration count variations",
+            MutationStrategy::ParseVariations => "Numeric text variations for parse round-trips",
+            MutationStrategy::ProbeSelection => "Probe selection variations for nondeterministic syscalls",
+            MutationStrategy::EnvLookups => "Environment lookup variations",
+            MutationStrategy::PanicDropModes => "Panic/Drop interaction modes",
+            MutationStrategy::AllocationLimits => "Allocation sizes around guest memory limits",
+            MutationStrategy::CheckpointIntervals => "Loop lengths and checkpoint intervals",
+            MutationStrategy::EnumShapes => "Enum variant and payload shapes",
+            MutationStrategy::BitPatterns => "Bit patterns at aligned and unaligned offsets",
+            MutationStrategy::CompressionPayloads => "Payload patterns, sizes and deflate levels",
+            MutationStrategy::AdversarialJson => "Adversarial JSON documents (nesting, numbers, escapes)",
+            MutationStrategy::BinaryCodecs => "Valid and corrupted RLP, borsh and bincode encodings",
+            MutationStrategy::Utf8Sequences => "Valid and malformed UTF-8 sequences with offsets around char boundaries",
+            MutationStrategy::CollectionScripts => "Push/pop/reserve scripts for VecDeque and Vec",
+            MutationStrategy::ThreadCounts => "Worker and iteration counts for thread, Mutex and atomic APIs",
+            MutationStrategy::Grammar => "Seeded samples and fixed cases from the input grammar in the core manifest",
+            MutationStrategy::BaseSeed => "Named base seeds from the core manifest, run unmutated",
+            MutationStrategy::Exhaustive => "Every point of a core's value grid (exhaustive mode)",
+        }
+    }
+
+    /// The single numeric parameter the strategy's inputs are a function of
+    /// (None if it varies several things at once)
+    ///
+    /// A divergence that appears between two values of this parameter can be
+    /// bisected to an exact threshold with [`parametric_mutation`].
+    pub fn bisect_param(&self) -> Option<&'static str> {
+        match self {
+            MutationStrategy::LengthBias => Some("size"),
+            MutationStrategy::FibonacciValues => Some("n"),
+            MutationStrategy::IterationVariations => Some("iterations"),
+            _ => None,
+        }
+    }
+
+    /// The strategy `generate_mutations` uses for a core (None if unsupported)
+    pub fn for_core(core_name: &str) -> Option<MutationStrategy> {
+        if matches!(grammar::load_core_grammar(core_name), Ok(Some(_))) {
+            return Some(MutationStrategy::Grammar);
+        }
+        Some(match core_name {
+            "io_echo" => MutationStrategy::LengthBias,
+            "arithmetic" => MutationStrategy::BoundaryValues,
+            "fib" => MutationStrategy::FibonacciValues,
+            "panic_test" => MutationStrategy::BooleanVariations,
+            "timeout_test" => MutationStrategy::IterationVariations,
+            "parse_roundtrip" => MutationStrategy::ParseVariations,
+            "nondeterminism_probe" => MutationStrategy::ProbeSelection,
+            "env_probe" => MutationStrategy::EnvLookups,
+            "panic_drop" => MutationStrategy::PanicDropModes,
+            "oom_probe" => MutationStrategy::AllocationLimits,
+            "checkpoint_loop" => MutationStrategy::CheckpointIntervals,
+            "enum_shapes" => MutationStrategy::EnumShapes,
+            "type_punning" => MutationStrategy::BitPatterns,
+            "compress" => MutationStrategy::CompressionPayloads,
+            "json_adversarial" => MutationStrategy::AdversarialJson,
+            "binary_decode" => MutationStrategy::BinaryCodecs,
+            "utf8_boundaries" => MutationStrategy::Utf8Sequences,
+            "collection_growth" => MutationStrategy::CollectionScripts,
+            "thread_api" => MutationStrategy::ThreadCounts,
+            _ => return None,
+        })
+    }
+}
+
+/// A base input as its core deserializes it: sidecar files read in and
+/// compact fields expanded (see `runners/preprocess`)
+fn expand_base_input(core_name: &str, base_input_json: &Value, base_input_path: &str) -> Result<Value> {
+    let input_dir = std::path::Path::new(base_input_path).parent().unwrap_or(std::path::Path::new(""));
+    let bytes = serde_json::to_vec(base_input_json)?;
+    let bytes = grammar::load_core_preprocess(core_name)?
+        .apply(&bytes, input_dir)
+        .with_context(|| format!("Failed to preprocess base input {}", base_input_path))?;
+    Ok(serde_json::from_slice(&bytes)?)
 }
 
 /// Generate mutations for a given core
+///
+/// Cores whose manifest declares an input grammar are sampled from it with
+/// `seed`; the others use their hand-written mutators, which ignore the seed.
+/// Every op records the SHA-256 of the file at `base_input_path`.
 pub fn generate_mutations(
     core_name: &str,
     base_input_json: &Value,
     base_input_path: &str,
+    seed: u64,
+) -> Result<Vec<MutatedInput>> {
+    let parent = std::fs::read(base_input_path)
+        .with_context(|| format!("Failed to read base input {}", base_input_path))?;
+    let parent_sha256 = format!("{:x}", Sha256::digest(&parent));
+
+    // A base input may keep byte arrays in sidecar files: mutate them in full
+    let base_input_json = &expand_base_input(core_name, base_input_json, base_input_path)?;
+
+    let mut mutations = match grammar::load_core_grammar(core_name)? {
+        Some(grammar) => grammar.generate(seed, base_input_path),
+        None => generate_core_mutations(core_name, base_input_json, base_input_path)?,
+    };
+    for mutation in &mut mutations {
+        mutation.op.parent_sha256 = parent_sha256.clone();
+    }
+    Ok(mutations)
+}
+
+/// Dispatch to a core's hand-written mutator
+fn generate_core_mutations(
+    core_name: &str,
+    base_input_json: &Value,
+    base_input_path: &str,
 ) -> Result<Vec<MutatedInput>> {
     match core_name {
         "io_echo" => generate_io_echo_mutations(base_input_json, base_input_path),
         "arithmetic" => generate_arithmetic_mutations(base_input_json, base_input_path),
-        "simple_struct" => generate_simple_struct_mutations(base_input_json, base_input_path),
         "fib" => generate_fib_mutations(base_input_json, base_input_path),
         "panic_test" => generate_panic_test_mutations(base_input_json, base_input_path),
         "timeout_test" => generate_timeout_test_mutations(base_input_json, base_input_path),
+        "parse_roundtrip" => generate_parse_roundtrip_mutations(base_input_json, base_input_path),
+        "nondeterminism_probe" => generate_nondeterminism_probe_mutations(base_input_json, base_input_path),
+        "env_probe" => generate_env_probe_mutations(base_input_json, base_input_path),
+        "panic_drop" => generate_panic_drop_mutations(base_input_json, base_input_path),
+        "oom_probe" => generate_oom_probe_mutations(base_input_json, base_input_path),
+        "checkpoint_loop" => generate_checkpoint_loop_mutations(base_input_json, base_input_path),
+        "enum_shapes" => generate_enum_shapes_mutations(base_input_json, base_input_path),
+        "type_punning" => generate_type_punning_mutations(base_input_json, base_input_path),
+        "compress" => generate_compress_mutations(base_input_json, base_input_path),
+        "json_adversarial" => generate_json_adversarial_mutations(base_input_json, base_input_path),
+        "binary_decode" => generate_binary_decode_mutations(base_input_json, base_input_path),
+        "utf8_boundaries" => generate_utf8_boundaries_mutations(base_input_json, base_input_path),
+        "collection_growth" => generate_collection_growth_mutations(base_input_json, base_input_path),
+        "thread_api" => generate_thread_api_mutations(base_input_json, base_input_path),
         _ => anyhow::bail!("Unknown core: {}", core_name),
     }
 }
 
+/// Cores whose input space [`enumerate_inputs`] can cover
+pub const ENUMERABLE_CORES: &[&str] = &["arithmetic", "panic_test"];
+
+/// u32 values an exhaustive grid covers: the boundary values, plus
+/// `resolution` evenly spaced interior points
+pub fn u32_grid(resolution: usize) -> Vec<u32> {
+    let mut grid = vec![0, 1, 2, u32::MAX / 2, u32::MAX / 2 + 1, u32::MAX - 1, u32::MAX];
+    let step = u32::MAX as u64 / (resolution as u64 + 1);
+    grid.extend((1..=resolution as u64).map(|i| (i * step) as u32));
+    grid.sort_unstable();
+    grid.dedup();
+    grid
+}
+
+/// Every point of a core's input grid at the given resolution
+///
+/// Unlike [`generate_mutations`], nothing is sampled or capped: the result
+/// is the full product of the core's fields (see [`ENUMERABLE_CORES`]),
+/// so its size grows with `resolution` and the caller enforces a budget.
+pub fn enumerate_inputs(core_name: &str, base_input_path: &str, resolution: usize) -> Result<Vec<MutatedInput>> {
+    use serde_json::json;
+
+    // (input, op params)
+    let points: Vec<(Value, Value)> = match core_name {
+        // operation x a x b
+        "arithmetic" => {
+            let grid = u32_grid(resolution);
+            ["add", "sub", "mul", "div"]
+                .iter()
+                .flat_map(|operation| {
+                    let grid = &grid;
+                    grid.iter().flat_map(move |&a| {
+                        grid.iter().map(move |&b| {
+                            let input = json!({ "a": a, "b": b, "operation": operation });
+                            (input.clone(), input)
+                        })
+                    })
+                })
+                .collect()
+        }
+        // should_panic x message (none, empty, and lengths up to 64 KiB of mixed-width chars)
+        "panic_test" => {
+            let mut lengths = vec![1usize, 255, 256, 4096];
+            lengths.extend((1..=resolution).map(|i| i * 65536 / resolution.max(1)));
+            lengths.sort_unstable();
+            lengths.dedup();
+            // Messages are logged by length, not copied into every label
+            let messages = std::iter::once((Value::Null, Value::Null))
+                .chain(std::iter::once((json!(""), json!(0))))
+                .chain(lengths.into_iter().map(|len| {
+                    (json!("panic ñ🦀 ".chars().cycle().take(len).collect::<String>()), json!(len))
+                }));
+            messages
+                .flat_map(|(message, len)| {
+                    [false, true].map(|should_panic| {
+                        (
+                            json!({ "should_panic": should_panic, "panic_msg": message.clone() }),
+                            json!({ "should_panic": should_panic, "panic_msg_chars": len.clone() }),
+                        )
+                    })
+                })
+                .collect()
+        }
+        _ => anyhow::bail!(
+            "{} has no enumerable input space (exhaustive mode supports: {})",
+            core_name,
+            ENUMERABLE_CORES.join(", ")
+        ),
+    };
+
+    Ok(points
+        .into_iter()
+        .map(|(input_json, params)| MutatedInput {
+            input_json,
+            op: MutationOp::new(MutationStrategy::Exhaustive, params),
+            base_input_path: base_input_path.to_string(),
+        })
+        .collect())
+}
+
+/// A named base seed as an (unmutated) input of its own
+///
+/// Hand-written mutators do not depend on their base input, so seeds other
+/// than the first add nothing through them; running each seed as is does.
+pub fn base_seed_input(name: &str, input_json: &Value, base_input_path: &str) -> MutatedInput {
+    let mut op = MutationOp::new(MutationStrategy::BaseSeed, serde_json::json!({ "seed": name }));
+    op.base_seed = Some(name.to_string());
+    MutatedInput {
+        input_json: input_json.clone(),
+        op,
+        base_input_path: base_input_path.to_string(),
+    }
+}
+
+/// The input a parametric strategy generates for `value` of its
+/// [`MutationStrategy::bisect_param`] (None for other strategies)
+pub fn parametric_mutation(strategy: MutationStrategy, value: u64, base_input_path: &str) -> Option<MutatedInput> {
+    let input_json = match strategy {
+        MutationStrategy::LengthBias => {
+            let data: Vec<u8> = (0..value).map(|i| (i % 256) as u8).collect();
+            serde_json::json!({ "data": data })
+        }
+        MutationStrategy::FibonacciValues => serde_json::json!({ "n": value }),
+        MutationStrategy::IterationVariations => serde_json::json!({ "iterations": value }),
+        _ => return None,
+    };
+    let mut params = serde_json::Map::new();
+    params.insert(strategy.bisect_param()?.to_string(), Value::from(value));
+    Some(MutatedInput {
+        input_json,
+        op: MutationOp::new(strategy, Value::Object(params)),
+        base_input_path: base_input_path.to_string(),
+    })
+}
+
 /// Generate io_echo mutations with length biasing (hybrid strategy)
 fn generate_io_echo_mutations(
     _base_input: &Value,
@@ -84,128 +470,65 @@ fn generate_io_echo_mutations(
 
     // Generate input for each size
     for size in all_sizes {
-        let data: Vec<u8> = (0..size).map(|i| (i % 256) as u8).collect();
-        let input_json = serde_json::json!({
-            "data": data
-        });
-
-        let size_desc = if size < 1024 {
-            format!("{}b", size)
-        } else if size < 1048576 {
-            format!("{}kb", size / 1024)
-        } else {
-            format!("{}mb", size / 1048576)
-        };
-
-        mutations.push(MutatedInput {
-            input_json,
-            mutation_op: format!("length_bias:{}", size_desc),
-            base_input_path: base_input_path.to_string(),
-        });
+        mutations.extend(parametric_mutation(MutationStrategy::LengthBias, size, base_input_path));
     }
 
     Ok(mutations)
 }
 
+/// Arithmetic inputs per fuzz round when the manifest sets no
+/// `[package.metadata.fuzz.boundary] samples`: one all-pairs layer
+pub const DEFAULT_ARITHMETIC_SAMPLES: usize = 36;
+
 /// Generate arithmetic mutations with boundary values
 fn generate_arithmetic_mutations(
     _base_input: &Value,
     base_input_path: &str,
 ) -> Result<Vec<MutatedInput>> {
-    let mut mutations = Vec::new();
-
-    let operations = vec!["add", "sub", "mul", "div"];
-    let boundary_values = vec![
-        0,
-        1,
-        2,
-        u32::MAX / 2,
-        u32::MAX - 1,
-        u32::MAX,
-    ];
-
-    // Generate combinations for each operation
-    for op in &operations {
-        for &a in &boundary_values {
-            for &b in &boundary_values {
-                // Skip some redundant combinations to keep count manageable
-                if a == 0 && b == 0 {
-                    continue; // Keep one zero case
-                }
-                
-                let input_json = serde_json::json!({
-                    "a": a,
-                    "b": b,
-                    "operation": op
-                });
-
-                mutations.push(MutatedInput {
-                    input_json,
-                    mutation_op: format!("boundary_values:{}_{}_op_{}", a, b, op),
-                    base_input_path: base_input_path.to_string(),
-                });
-
-                // Limit to ~6 per operation to keep total manageable
-                if mutations.len() >= operations.len() * 6 {
-                    break;
-                }
-            }
-            if mutations.len() >= operations.len() * 6 {
-                break;
-            }
-        }
-    }
-
-    Ok(mutations)
+    let samples = grammar::load_boundary_samples("arithmetic")?.unwrap_or(DEFAULT_ARITHMETIC_SAMPLES);
+    Ok(arithmetic_boundary_mutations(samples, base_input_path))
 }
 
-/// Generate simple_struct mutations with string variations
-fn generate_simple_struct_mutations(
-    _base_input: &Value,
-    base_input_path: &str,
-) -> Result<Vec<MutatedInput>> {
-    let mut mutations = Vec::new();
-
-    // Pre-generate repeated strings to avoid lifetime issues
-    let string_100 = "a".repeat(100);
-    let string_1000 = "a".repeat(1000);
-    let string_10000 = "a".repeat(10000);
-
-    let string_cases: Vec<(String, &str)> = vec![
-        (String::new(), "empty"),
-        ("a".to_string(), "single"),
-        ("hello".to_string(), "short"),
-        (string_100, "100chars"),
-        (string_1000, "1000chars"),
-        (string_10000, "10kchars"),
-        ("🦀".to_string(), "emoji"),
-        ("🦀 Rust zkVM".to_string(), "unicode_mixed"),
-        ("Hello\nWorld".to_string(), "newline"),
-        ("Tab\tSeparated".to_string(), "tab"),
-    ];
-
-    let field1_values = vec![0, 1, 42, u32::MAX];
-    let field3_values = vec![true, false];
+/// All-pairs operation × `a` × `b` boundary inputs, `samples` of them
+///
+/// The operations × `a` × `b` cross product is split into Latin-square
+/// layers: in layer `k`, cell `(a, b)` gets operation `(a + b + k) % 4`. One
+/// layer already pairs every boundary of `a` with every boundary of `b` and
+/// with every operation, so `samples` is raised to one layer at least and
+/// capped at the whole cross product (with a warning). Cells are visited
+/// with a stride coprime to the layer size, so a partial layer spreads over
+/// both operands.
+pub fn arithmetic_boundary_mutations(samples: usize, base_input_path: &str) -> Vec<MutatedInput> {
+    const OPERATIONS: [&str; 4] = ["add", "sub", "mul", "div"];
+    const BOUNDARY_VALUES: [u32; 6] = [0, 1, 2, u32::MAX / 2, u32::MAX - 1, u32::MAX];
+    const STRIDE: usize = 7;
 
-    // Generate combinations
-    for (idx, (string, string_desc)) in string_cases.iter().enumerate() {
-        let field1 = field1_values[idx % field1_values.len()];
-        let field3 = field3_values[idx % field3_values.len()];
-
-        let input_json = serde_json::json!({
-            "field1": field1,
-            "field2": string,
-            "field3": field3
-        });
-
-        mutations.push(MutatedInput {
-            input_json,
-            mutation_op: format!("string_variation:{}", string_desc),
-            base_input_path: base_input_path.to_string(),
-        });
+    let layer = BOUNDARY_VALUES.len() * BOUNDARY_VALUES.len();
+    let clamped = samples.clamp(layer, layer * OPERATIONS.len());
+    if clamped != samples {
+        eprintln!(
+            "   ⚠️  arithmetic boundary samples must be {}..={}; using {} instead of {}",
+            layer,
+            layer * OPERATIONS.len(),
+            clamped,
+            samples
+        );
     }
+    let samples = clamped;
 
-    Ok(mutations)
+    (0..samples)
+        .map(|index| {
+            let cell = (index % layer) * STRIDE % layer;
+            let (a_index, b_index) = (cell / BOUNDARY_VALUES.len(), cell % BOUNDARY_VALUES.len());
+            let op = OPERATIONS[(a_index + b_index + index / layer) % OPERATIONS.len()];
+            let (a, b) = (BOUNDARY_VALUES[a_index], BOUNDARY_VALUES[b_index]);
+            MutatedInput {
+                input_json: serde_json::json!({ "a": a, "b": b, "operation": op }),
+                op: MutationOp::new(MutationStrategy::BoundaryValues, serde_json::json!({ "a": a, "b": b, "operation": op })),
+                base_input_path: base_input_path.to_string(),
+            }
+        })
+        .collect()
 }
 
 /// Generate fib mutations with various n values
@@ -218,15 +541,7 @@ fn generate_fib_mutations(
     let n_values = vec![0, 1, 2, 5, 10, 20, 30, 40, 50, 100, 1000];
 
     for n in n_values {
-        let input_json = serde_json::json!({
-            "n": n
-        });
-
-        mutations.push(MutatedInput {
-            input_json,
-            mutation_op: format!("fib_value:n={}", n),
-            base_input_path: base_input_path.to_string(),
-        });
+        mutations.extend(parametric_mutation(MutationStrategy::FibonacciValues, n, base_input_path));
     }
 
     Ok(mutations)
@@ -260,7 +575,7 @@ fn generate_panic_test_mutations(
 
         mutations.push(MutatedInput {
             input_json,
-            mutation_op: format!("bool_variation:{}", desc),
+            op: MutationOp::new(MutationStrategy::BooleanVariations, serde_json::json!({ "case": desc, "should_panic": should_panic })),
             base_input_path: base_input_path.to_string(),
         });
     }
@@ -288,13 +603,56 @@ fn generate_timeout_test_mutations(
     ];
 
     for iterations in iteration_counts {
+        mutations.extend(parametric_mutation(MutationStrategy::IterationVariations, iterations, base_input_path));
+    }
+
+    Ok(mutations)
+}
+
+/// Generate parse_roundtrip mutations with numeric text corner cases
+fn generate_parse_roundtrip_mutations(
+    _base_input: &Value,
+    base_input_path: &str,
+) -> Result<Vec<MutatedInput>> {
+    let mut mutations = Vec::new();
+
+    let text_cases = vec![
+        ("", "empty"),
+        ("0", "zero"),
+        ("-0", "negative_zero"),
+        ("+42", "leading_plus"),
+        ("00042", "leading_zeros"),
+        (" 42", "leading_space"),
+        ("1_000", "underscore"),
+        ("0x10", "hex_prefix"),
+        ("4294967295", "u32_max"),
+        ("4294967296", "u32_max_plus_1"),
+        ("-1", "negative_one"),
+        ("9223372036854775807", "i64_max"),
+        ("9223372036854775808", "i64_max_plus_1"),
+        ("-9223372036854775808", "i64_min"),
+        ("inf", "inf"),
+        ("-inf", "negative_inf"),
+        ("infinity", "infinity"),
+        ("NaN", "nan_upper"),
+        ("nan", "nan_lower"),
+        ("0.1", "one_tenth"),
+        ("1.", "trailing_dot"),
+        (".5", "leading_dot"),
+        ("1e308", "near_f64_max"),
+        ("1e309", "f64_overflow"),
+        ("2.2250738585072014e-308", "f64_min_positive"),
+        ("5e-324", "f64_subnormal"),
+    ];
+
+    for (text, desc) in text_cases {
         let input_json = serde_json::json!({
-            "iterations": iterations
+            "text": text
         });
 
         mutations.push(MutatedInput {
             input_json,
-            mutation_op: format!("iteration_variation:{}", iterations),
+            op: MutationOp::new(MutationStrategy::ParseVariations, serde_json::json!({ "case": desc })),
             base_input_path: base_input_path.to_string(),
         });
     }
@@ -302,6 +660,727 @@ fn generate_timeout_test_mutations(
     Ok(mutations)
 }
 
+/// Generate nondeterminism_probe mutations covering every probe combination
+fn generate_nondeterminism_probe_mutations(
+    _base_input: &Value,
+    base_input_path: &str,
+) -> Result<Vec<MutatedInput>> {
+    let mut mutations = Vec::new();
+
+    let sample_counts = [2, 16];
+
+    // Every non-empty subset of {system_time, instant, random}
+    for mask in 1u8..8 {
+        let probe_system_time = mask & 0b001 != 0;
+        let probe_instant = mask & 0b010 != 0;
+        let probe_random = mask & 0b100 != 0;
+
+        for &samples in &sample_counts {
+            let input_json = serde_json::json!({
+                "probe_system_time": probe_system_time,
+                "probe_instant": probe_instant,
+                "probe_random": probe_random,
+                "samples": samples
+            });
+
+            mutations.push(MutatedInput {
+                input_json,
+                op: MutationOp::new(
+                    MutationStrategy::ProbeSelection,
+                    serde_json::json!({
+                        "system_time": probe_system_time,
+                        "instant": probe_instant,
+                        "random": probe_random,
+                        "samples": samples
+                    }),
+                ),
+                base_input_path: base_input_path.to_string(),
+            });
+        }
+    }
+
+    Ok(mutations)
+}
+
+/// Generate env_probe mutations with different variable lookups
+fn generate_env_probe_mutations(
+    _base_input: &Value,
+    base_input_path: &str,
+) -> Result<Vec<MutatedInput>> {
+    let mut mutations = Vec::new();
+
+    let lookup_cases = vec![
+        ("PATH", "path"),
+        ("HOME", "home"),
+        ("RUST_LOG", "rust_log"),
+        ("SP1_PROVER", "sp1_prover"),
+        ("", "empty_name"),
+        ("ZK_FUZZ_LAB_UNSET", "unset"),
+        ("🦀", "unicode_name"),
+    ];
+
+    for (lookup_var, desc) in lookup_cases {
+        for include_values in [false, true] {
+            let input_json = serde_json::json!({
+                "lookup_var": lookup_var,
+                "include_values": include_values
+            });
+
+            mutations.push(MutatedInput {
+                input_json,
+                op: MutationOp::new(MutationStrategy::EnvLookups, serde_json::json!({ "case": desc, "include_values": include_values })),
+                base_input_path: base_input_path.to_string(),
+            });
+        }
+    }
+
+    Ok(mutations)
+}
+
+/// Generate panic_drop mutations over every mode and a few guard counts
+fn generate_panic_drop_mutations(
+    _base_input: &Value,
+    base_input_path: &str,
+) -> Result<Vec<MutatedInput>> {
+    let mut mutations = Vec::new();
+
+    let modes = vec!["none", "unwind_through_drop", "panic_in_drop", "double_panic"];
+    let guard_counts = vec![0, 1, 16];
+
+    for mode in &modes {
+        for &guards in &guard_counts {
+            let input_json = serde_json::json!({
+                "mode": mode,
+                "guards": guards
+            });
+
+            mutations.push(MutatedInput {
+                input_json,
+                op: MutationOp::new(MutationStrategy::PanicDropModes, serde_json::json!({ "mode": mode, "guards": guards })),
+                base_input_path: base_input_path.to_string(),
+            });
+        }
+    }
+
+    Ok(mutations)
+}
+
+/// Generate oom_probe mutations with allocation sizes up to and past guest memory limits
+///
+/// If the core declares a `memory_limit`, inputs allocating just below and
+/// just above it are added, to probe the boundary itself.
+fn generate_oom_probe_mutations(
+    _base_input: &Value,
+    base_input_path: &str,
+) -> Result<Vec<MutatedInput>> {
+    let mut mutations = Vec::new();
+
+    // (chunk_size, chunks): small, near typical zkVM heap sizes, past them,
+    // and a single chunk larger than isize::MAX on 32-bit targets
+    let mut shapes: Vec<(u64, u32)> = vec![
+        (4096, 16),
+        (1024 * 1024, 64),
+        (64 * 1024 * 1024, 8),
+        (256 * 1024 * 1024, 4),
+        (1024 * 1024 * 1024, 2),
+        (0x8000_0000, 1),
+        (0xFFFF_FFFF, 1),
+    ];
+    // 7/8 and 9/8 of the limit, in 64 chunks' worth of allocations
+    if let Some(limit) = grammar::load_memory_limit("oom_probe")? {
+        let chunk_size = (limit / 64).clamp(4096, u64::from(u32::MAX));
+        for eighths in [7, 9] {
+            let chunks = (limit / 8 * eighths).div_ceil(chunk_size);
+            shapes.push((chunk_size, u32::try_from(chunks).unwrap_or(u32::MAX)));
+        }
+    }
+
+    for &(chunk_size, chunks) in &shapes {
+        for fallible in [true, false] {
+            let input_json = serde_json::json!({
+                "chunk_size": chunk_size,
+                "chunks": chunks,
+                "fallible": fallible
+            });
+
+            mutations.push(MutatedInput {
+                input_json,
+                op: MutationOp::new(MutationStrategy::AllocationLimits, serde_json::json!({ "chunk_size": chunk_size, "chunks": chunks, "fallible": fallible })),
+                base_input_path: base_input_path.to_string(),
+            });
+        }
+    }
+
+    Ok(mutations)
+}
+
+/// Generate checkpoint_loop mutations: loop lengths around the runner timeouts,
+/// each with coarse and fine checkpoint intervals
+fn generate_checkpoint_loop_mutations(
+    _base_input: &Value,
+    base_input_path: &str,
+) -> Result<Vec<MutatedInput>> {
+    let mut mutations = Vec::new();
+
+    let iteration_counts: Vec<u64> = vec![1_000, 1_000_000, 100_000_000, 10_000_000_000];
+    // Checkpoints per run (bounded so the commit stream stays small)
+    let checkpoint_counts: Vec<u64> = vec![1, 10, 100];
+
+    for &iterations in &iteration_counts {
+        for &count in &checkpoint_counts {
+            let input_json = serde_json::json!({
+                "iterations": iterations,
+                "checkpoint_every": iterations / count
+            });
+
+            mutations.push(MutatedInput {
+                input_json,
+                op: MutationOp::new(MutationStrategy::CheckpointIntervals, serde_json::json!({ "iterations": iterations, "checkpoints": count })),
+                base_input_path: base_input_path.to_string(),
+            });
+        }
+    }
+
+    Ok(mutations)
+}
+
+/// Generate enum_shapes mutations covering every variant, payload edge cases and nesting
+fn generate_enum_shapes_mutations(
+    _base_input: &Value,
+    base_input_path: &str,
+) -> Result<Vec<MutatedInput>> {
+    let mut mutations = Vec::new();
+
+    // Nest a shape inside `depth` Boxed variants (kept below serde_json's recursion limit)
+    let boxed = |depth: usize, inner: Value| {
+        (0..depth).fold(inner, |shape, _| serde_json::json!({ "Boxed": shape }))
+    };
+
+    let cases: Vec<(&str, Value)> = vec![
+        ("empty", serde_json::json!([])),
+        ("unit", serde_json::json!(["Unit"])),
+        ("tuple_zero", serde_json::json!([{ "Tuple": [0, 0] }])),
+        ("tuple_extremes", serde_json::json!([{ "Tuple": [u32::MAX, i32::MIN] }])),
+        ("struct_empty_tag", serde_json::json!([{ "Struct": { "id": u32::MAX, "tag": "" } }])),
+        ("struct_unicode_tag", serde_json::json!([{ "Struct": { "id": 1, "tag": "🦀é" } }])),
+        ("nested_none", serde_json::json!([{ "Nested": null }])),
+        ("nested_ok_max", serde_json::json!([{ "Nested": { "Ok": u32::MAX } }])),
+        ("nested_err_empty", serde_json::json!([{ "Nested": { "Err": "" } }])),
+        ("boxed_1", serde_json::json!([boxed(1, serde_json::json!("Unit"))])),
+        ("boxed_100", serde_json::json!([boxed(100, serde_json::json!({ "Nested": { "Ok": 7 } }))])),
+        ("many_units", Value::Array(vec![serde_json::json!("Unit"); 1000])),
+        (
+            "mixed",
+            serde_json::json!([
+                "Unit",
+                { "Tuple": [1, -1] },
+                { "Struct": { "id": 42, "tag": "x" } },
+                { "Nested": { "Err": "e" } },
+                boxed(3, serde_json::json!({ "Tuple": [2, 3] }))
+            ]),
+        ),
+    ];
+
+    for (desc, shapes) in cases {
+        let input_json = serde_json::json!({ "shapes": shapes });
+
+        mutations.push(MutatedInput {
+            input_json,
+            op: MutationOp::new(MutationStrategy::EnumShapes, serde_json::json!({ "case": desc })),
+            base_input_path: base_input_path.to_string(),
+        });
+    }
+
+    Ok(mutations)
+}
+
+/// Generate type_punning mutations: interesting bit patterns at every offset mod 4
+fn generate_type_punning_mutations(
+    _base_input: &Value,
+    base_input_path: &str,
+) -> Result<Vec<MutatedInput>> {
+    let mut mutations = Vec::new();
+
+    let patterns: Vec<(u32, &str)> = vec![
+        (0x0000_0000, "zero"),
+        (0x8000_0000, "sign_bit"),
+        (0x7FC0_0000, "quiet_nan"),
+        (0x7F80_0001, "signaling_nan"),
+        (0xFFC0_1234, "negative_nan_payload"),
+        (0x0000_0001, "subnormal"),
+        (0x7F80_0000, "infinity"),
+        (0xDEAD_BEEF, "deadbeef"),
+    ];
+
+    for (pattern, desc) in patterns {
+        for offset in 0..4u32 {
+            // Place the pattern at `offset` inside an 8-byte buffer of 0xAA filler
+            let mut bytes = vec![0xAAu8; 8];
+            bytes[offset as usize..offset as usize + 4].copy_from_slice(&pattern.to_le_bytes());
+
+            let input_json = serde_json::json!({
+                "bytes": bytes,
+                "offset": offset
+            });
+
+            mutations.push(MutatedInput {
+                input_json,
+                op: MutationOp::new(MutationStrategy::BitPatterns, serde_json::json!({ "pattern": desc, "offset": offset })),
+                base_input_path: base_input_path.to_string(),
+            });
+        }
+    }
+
+    Ok(mutations)
+}
+
+/// Generate compress mutations over payload patterns, sizes and deflate levels
+fn generate_compress_mutations(
+    _base_input: &Value,
+    base_input_path: &str,
+) -> Result<Vec<MutatedInput>> {
+    let mut mutations = Vec::new();
+
+    let patterns = vec!["zeros", "text", "incrementing", "random"];
+    let sizes: Vec<u32> = vec![0, 1, 65536, 1024 * 1024];
+    let levels: Vec<u8> = vec![0, 1, 6, 10];
+
+    for pattern in &patterns {
+        for &size in &sizes {
+            for &level in &levels {
+                let input_json = serde_json::json!({
+                    "pattern": pattern,
+                    "size": size,
+                    "level": level
+                });
+
+                mutations.push(MutatedInput {
+                    input_json,
+                    op: MutationOp::new(MutationStrategy::CompressionPayloads, serde_json::json!({ "pattern": pattern, "size": size, "level": level })),
+                    base_input_path: base_input_path.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(mutations)
+}
+
+/// Generate json_adversarial mutations: deep nesting, extreme numbers and broken escapes
+fn generate_json_adversarial_mutations(
+    _base_input: &Value,
+    base_input_path: &str,
+) -> Result<Vec<MutatedInput>> {
+    let mut mutations = Vec::new();
+
+    let mut documents: Vec<(String, String)> = Vec::new();
+
+    // Nesting around serde_json's recursion limit (128) and far beyond it
+    for depth in [1usize, 64, 127, 128, 129, 10_000] {
+        documents.push((
+            format!("nested_arrays_{}", depth),
+            "[".repeat(depth) + &"]".repeat(depth),
+        ));
+    }
+    documents.push((
+        "nested_objects_127".to_string(),
+        "{\"a\":".repeat(127) + "null" + &"}".repeat(127),
+    ));
+    documents.push(("unclosed_10000".to_string(), "[".repeat(10_000)));
+
+    let numbers = vec![
+        ("u64_max", "18446744073709551615".to_string()),
+        ("u64_max_plus_one", "18446744073709551616".to_string()),
+        ("i64_min", "-9223372036854775808".to_string()),
+        ("negative_zero", "-0".to_string()),
+        ("f64_max", "1.7976931348623157e308".to_string()),
+        ("overflow_exponent", "1e400".to_string()),
+        ("underflow_exponent", "1e-400".to_string()),
+        ("subnormal", "5e-324".to_string()),
+        ("thousand_digits", "9".repeat(1000)),
+        ("long_fraction", format!("0.{}1", "0".repeat(500))),
+        ("leading_zero", "01".to_string()),
+        ("nan_literal", "NaN".to_string()),
+    ];
+    for (desc, number) in numbers {
+        documents.push((format!("number_{}", desc), number));
+    }
+
+    let strings = vec![
+        ("lone_high_surrogate", r#""\ud800""#),
+        ("lone_low_surrogate", r#""\udc00""#),
+        ("valid_surrogate_pair", r#""\ud83e\udd80""#),
+        ("reversed_surrogate_pair", r#""\udd80\ud83e""#),
+        ("bad_hex_escape", r#""\uZZZZ""#),
+        ("escaped_nul", r#""\u0000""#),
+        ("duplicate_keys", r#"{"a": 1, "a": 2}"#),
+        ("trailing_comma", "[1, 2,]"),
+        ("byte_order_mark", "\u{feff}{}"),
+        ("empty", ""),
+    ];
+    for (desc, document) in strings {
+        documents.push((format!("string_{}", desc), document.to_string()));
+    }
+
+    for (desc, document) in documents {
+        mutations.push(MutatedInput {
+            input_json: serde_json::json!({ "document": document }),
+            op: MutationOp::new(MutationStrategy::AdversarialJson, serde_json::json!({ "case": desc })),
+            base_input_path: base_input_path.to_string(),
+        });
+    }
+
+    Ok(mutations)
+}
+
+/// Where the fields of a hand-encoded `binary_decode` record start
+struct RecordLayout {
+    bytes: Vec<u8>,
+    name_len: usize,
+    name: usize,
+    flag: usize,
+    option_tag: usize,
+}
+
+/// Field values of the `binary_decode` record every encoding carries
+const RECORD_ID: u64 = 7;
+const RECORD_NAME: &str = "héllo";
+const RECORD_VALUES: [u32; 3] = [1, 2, 3];
+const RECORD_PAYLOAD: u8 = 0xab;
+
+/// The record in bincode's fixint layout (u64 length prefixes)
+fn bincode_record() -> RecordLayout {
+    let mut bytes = RECORD_ID.to_le_bytes().to_vec();
+    let name_len = bytes.len();
+    bytes.extend((RECORD_NAME.len() as u64).to_le_bytes());
+    let name = bytes.len();
+    bytes.extend(RECORD_NAME.as_bytes());
+    bytes.extend((RECORD_VALUES.len() as u64).to_le_bytes());
+    bytes.extend(RECORD_VALUES.iter().flat_map(|v| v.to_le_bytes()));
+    let flag = bytes.len();
+    bytes.push(1);
+    let option_tag = bytes.len();
+    bytes.extend([1, 1, 0, 0, 0, 0, 0, 0, 0, RECORD_PAYLOAD]);
+    RecordLayout { bytes, name_len, name, flag, option_tag }
+}
+
+/// The record in borsh's layout (u32 length prefixes)
+fn borsh_record() -> RecordLayout {
+    let mut bytes = RECORD_ID.to_le_bytes().to_vec();
+    let name_len = bytes.len();
+    bytes.extend((RECORD_NAME.len() as u32).to_le_bytes());
+    let name = bytes.len();
+    bytes.extend(RECORD_NAME.as_bytes());
+    bytes.extend((RECORD_VALUES.len() as u32).to_le_bytes());
+    bytes.extend(RECORD_VALUES.iter().flat_map(|v| v.to_le_bytes()));
+    let flag = bytes.len();
+    bytes.push(1);
+    let option_tag = bytes.len();
+    bytes.extend([1, 1, 0, 0, 0, RECORD_PAYLOAD]);
+    RecordLayout { bytes, name_len, name, flag, option_tag }
+}
+
+/// RLP header for a string (`base` 0x80) or list (`base` 0xc0) of `len` bytes
+fn rlp_header(base: u8, len: usize) -> Vec<u8> {
+    if len <= 55 {
+        return vec![base + len as u8];
+    }
+    let be = (len as u64).to_be_bytes();
+    let len_bytes = &be[be.iter().position(|&b| b != 0).unwrap_or(7)..];
+    let mut header = vec![base + 55 + len_bytes.len() as u8];
+    header.extend(len_bytes);
+    header
+}
+
+fn rlp_string(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        return bytes.to_vec();
+    }
+    let mut out = rlp_header(0x80, bytes.len());
+    out.extend(bytes);
+    out
+}
+
+fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload = items.concat();
+    let mut out = rlp_header(0xc0, payload.len());
+    out.extend(payload);
+    out
+}
+
+/// Minimal big-endian integer, as RLP requires
+fn rlp_uint(value: u64) -> Vec<u8> {
+    let be = value.to_be_bytes();
+    rlp_string(&be[be.iter().position(|&b| b != 0).unwrap_or(8)..])
+}
+
+/// The record as `[id, name, [values...], flag, [payload]]`, with `id` and
+/// `flag` already encoded (so they can be made non-canonical or invalid)
+fn rlp_record(id: Vec<u8>, flag: Vec<u8>) -> Vec<u8> {
+    let values: Vec<Vec<u8>> = RECORD_VALUES.iter().map(|&v| rlp_uint(v as u64)).collect();
+    rlp_list(&[
+        id,
+        rlp_string(RECORD_NAME.as_bytes()),
+        rlp_list(&values),
+        flag,
+        rlp_list(&[rlp_string(&[RECORD_PAYLOAD])]),
+    ])
+}
+
+/// Generate binary_decode mutations: valid, truncated and corrupted encodings
+/// of the same record in every codec, decoded by every codec
+fn generate_binary_decode_mutations(
+    _base_input: &Value,
+    base_input_path: &str,
+) -> Result<Vec<MutatedInput>> {
+    let mut cases: Vec<(String, Vec<u8>, u64)> = Vec::new();
+    let limit = 1 << 20;
+
+    let valid_rlp = rlp_record(rlp_uint(RECORD_ID), rlp_uint(1));
+    let encodings = [("bincode", bincode_record()), ("borsh", borsh_record())];
+    for (codec, layout) in &encodings {
+        let bytes = &layout.bytes;
+        cases.push((format!("{}_valid", codec), bytes.clone(), limit));
+        cases.push((format!("{}_truncated_1", codec), bytes[..bytes.len() - 1].to_vec(), limit));
+        cases.push((format!("{}_truncated_half", codec), bytes[..bytes.len() / 2].to_vec(), limit));
+        cases.push((format!("{}_trailing_byte", codec), [&bytes[..], &[0xff]].concat(), limit));
+
+        let mut patch = |desc: &str, offset: usize, replacement: &[u8], limit: u64| {
+            let mut patched = bytes.clone();
+            patched[offset..offset + replacement.len()].copy_from_slice(replacement);
+            cases.push((format!("{}_{}", codec, desc), patched, limit));
+        };
+        patch("invalid_bool", layout.flag, &[2], limit);
+        patch("invalid_option_tag", layout.option_tag, &[2], limit);
+        patch("invalid_utf8", layout.name, &[0xff], limit);
+        if *codec == "bincode" {
+            // Truncates to the real length if cast to a 32-bit usize unchecked
+            patch("name_len_2pow32_plus_len", layout.name_len, &((1u64 << 32) + RECORD_NAME.len() as u64).to_le_bytes(), limit);
+            patch("name_len_max_limited", layout.name_len, &u64::MAX.to_le_bytes(), limit);
+            patch("name_len_max_unlimited", layout.name_len, &u64::MAX.to_le_bytes(), 0);
+        } else {
+            patch("name_len_max", layout.name_len, &u32::MAX.to_le_bytes(), limit);
+        }
+    }
+
+    cases.push(("rlp_valid".to_string(), valid_rlp.clone(), limit));
+    cases.push(("rlp_truncated_1".to_string(), valid_rlp[..valid_rlp.len() - 1].to_vec(), limit));
+    cases.push(("rlp_trailing_byte".to_string(), [&valid_rlp[..], &[0xff]].concat(), limit));
+    let rlp_cases = [
+        ("rlp_non_canonical_single_byte", rlp_record(vec![0x81, RECORD_ID as u8], rlp_uint(1))),
+        ("rlp_leading_zero_id", rlp_record(vec![0x82, 0x00, RECORD_ID as u8], rlp_uint(1))),
+        ("rlp_invalid_bool", rlp_record(rlp_uint(RECORD_ID), rlp_uint(2))),
+        ("rlp_id_9_bytes", rlp_record(rlp_string(&[1; 9]), rlp_uint(1))),
+        // Length-of-length wider than a 32-bit usize (fits in 64 bits)
+        ("rlp_list_len_5_bytes", vec![0xfc, 0x00, 0x00, 0x00, 0x00, 0x10]),
+        ("rlp_list_len_past_end", [&[0xf8, 0xff][..], &valid_rlp[1..]].concat()),
+    ];
+    for (desc, bytes) in rlp_cases {
+        cases.push((desc.to_string(), bytes, limit));
+    }
+
+    cases.push(("empty".to_string(), vec![], limit));
+    cases.push(("all_bytes".to_string(), (0..=255u8).collect(), limit));
+
+    Ok(cases
+        .into_iter()
+        .map(|(case, data, bincode_limit)| MutatedInput {
+            input_json: serde_json::json!({
+                "data": data,
+                "codecs": ["rlp", "borsh", "bincode"],
+                "bincode_limit": bincode_limit,
+            }),
+            op: MutationOp::new(
+                MutationStrategy::BinaryCodecs,
+                serde_json::json!({ "case": case, "bincode_limit": bincode_limit }),
+            ),
+            base_input_path: base_input_path.to_string(),
+        })
+        .collect())
+}
+
+/// Generate utf8_boundaries mutations: valid and malformed sequences, sliced
+/// at every offset up to one past the end plus `u32::MAX`
+fn generate_utf8_boundaries_mutations(
+    _base_input: &Value,
+    base_input_path: &str,
+) -> Result<Vec<MutatedInput>> {
+    let sequences: Vec<(&str, Vec<u8>)> = vec![
+        ("ascii", b"hello".to_vec()),
+        ("two_byte", "é".as_bytes().to_vec()),
+        ("three_byte", "中".as_bytes().to_vec()),
+        ("four_byte", "🦀".as_bytes().to_vec()),
+        ("mixed", "aé中🦀z".as_bytes().to_vec()),
+        ("bom_and_nul", "\u{feff}a\u{0}b".as_bytes().to_vec()),
+        ("zero_width_joiner", "👩\u{200d}💻".as_bytes().to_vec()),
+        ("max_scalar", "\u{10ffff}".as_bytes().to_vec()),
+        ("overlong_nul", vec![0xc0, 0x80]),
+        ("overlong_slash", vec![0xe0, 0x80, 0xaf]),
+        ("surrogate_high", vec![0xed, 0xa0, 0x80]),
+        ("surrogate_pair_cesu8", vec![0xed, 0xa0, 0xbd, 0xed, 0xb8, 0x80]),
+        ("above_max_scalar", vec![0xf4, 0x90, 0x80, 0x80]),
+        ("truncated_two_of_three", vec![b'a', 0xe2, 0x82]),
+        ("truncated_three_of_four", vec![0xf0, 0x9f, 0xa6]),
+        ("lone_continuation", vec![0x80, b'a', 0xbf]),
+        ("invalid_lead_bytes", vec![0xf5, 0xf8, 0xfe, 0xff]),
+        ("interrupted_sequence", vec![0xe2, b'a', 0x82, 0xac]),
+        ("empty", vec![]),
+    ];
+
+    let mut mutations = Vec::new();
+    for (desc, data) in sequences {
+        let mut offsets: Vec<u32> = (0..=data.len() as u32 + 1).collect();
+        offsets.push(u32::MAX);
+        mutations.push(MutatedInput {
+            input_json: serde_json::json!({ "data": data, "offsets": offsets }),
+            op: MutationOp::new(MutationStrategy::Utf8Sequences, serde_json::json!({ "case": desc })),
+            base_input_path: base_input_path.to_string(),
+        });
+    }
+
+    // Long text with a malformed byte in the middle; offsets stride across it
+    let mut long = "aé中🦀".repeat(4096).into_bytes();
+    let middle = long.len() / 2;
+    long[middle] = 0xff;
+    let offsets: Vec<u32> = (0..long.len() as u32 + 2).step_by(997).chain([u32::MAX]).collect();
+    mutations.push(MutatedInput {
+        input_json: serde_json::json!({ "data": long, "offsets": offsets }),
+        op: MutationOp::new(MutationStrategy::Utf8Sequences, serde_json::json!({ "case": "long_mixed_with_invalid_byte" })),
+        base_input_path: base_input_path.to_string(),
+    });
+
+    Ok(mutations)
+}
+
+/// Generate collection_growth mutations: growth, wraparound and reserve scripts,
+/// plus seeded random scripts
+fn generate_collection_growth_mutations(
+    _base_input: &Value,
+    base_input_path: &str,
+) -> Result<Vec<MutatedInput>> {
+    use serde_json::json;
+
+    let repeat = |n: usize, op: Value| std::iter::repeat_n(op, n);
+    let mut scripts: Vec<(String, Vec<Value>)> = vec![
+        ("push_back_1000".to_string(), (0..1000).map(|i| json!({ "push_back": i })).collect()),
+        ("extend_100000".to_string(), vec![json!({ "extend": 100_000 })]),
+        (
+            "alternating_front_back".to_string(),
+            (0..200).map(|i| if i % 2 == 0 { json!({ "push_front": i }) } else { json!({ "push_back": i }) }).collect(),
+        ),
+        (
+            "wraparound".to_string(),
+            [json!({ "reserve_exact": 8 }), json!({ "extend": 8 })]
+                .into_iter()
+                .chain(repeat(5, json!("pop_front")))
+                .chain((0..5).map(|i| json!({ "push_back": i })))
+                .collect(),
+        ),
+        (
+            "queue_steady_state".to_string(),
+            (0..500).flat_map(|i| [json!({ "push_back": i }), json!({ "push_back": i }), json!("pop_front")]).collect(),
+        ),
+        (
+            "reserve_then_overflow".to_string(),
+            vec![json!({ "reserve": 10 }), json!({ "extend": 11 }), json!({ "reserve": 1 })],
+        ),
+        (
+            "reserve_exact_then_push".to_string(),
+            vec![json!({ "reserve_exact": 7 }), json!({ "extend": 7 }), json!({ "push_back": 1 })],
+        ),
+        (
+            "shrink_after_clear".to_string(),
+            vec![json!({ "extend": 1000 }), json!("clear"), json!("shrink_to_fit"), json!({ "push_back": 1 })],
+        ),
+        (
+            "truncate_and_shrink".to_string(),
+            vec![json!({ "extend": 1000 }), json!({ "truncate": 3 }), json!("shrink_to_fit"), json!({ "rotate_left": 1 })],
+        ),
+        (
+            "rotate_wrapped".to_string(),
+            vec![json!({ "extend": 16 }), json!("pop_front"), json!("pop_front"), json!({ "push_back": 99 }), json!({ "rotate_left": 7 })],
+        ),
+        ("pop_empty".to_string(), vec![json!("pop_front"), json!("pop_back"), json!({ "rotate_left": 3 })]),
+    ];
+
+    // Reserves around the guest's memory and a 32-bit usize
+    for additional in [1u32 << 20, 1 << 26, 1 << 29, u32::MAX] {
+        scripts.push((
+            format!("reserve_{}", additional),
+            vec![json!({ "push_back": 1 }), json!({ "reserve": additional }), json!({ "push_back": 2 }), json!("shrink_to_fit")],
+        ));
+    }
+
+    // Seeded random scripts (small values, so reserves stay cheap)
+    for seed in 1..=4u64 {
+        let mut state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        let mut next = |bound: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % bound
+        };
+        let ops = (0..500)
+            .map(|_| {
+                let value = next(64);
+                match next(10) {
+                    0..=2 => json!({ "push_back": value }),
+                    3 => json!({ "push_front": value }),
+                    4 => json!("pop_back"),
+                    5 => json!("pop_front"),
+                    6 => json!({ "reserve": value }),
+                    7 => json!({ "reserve_exact": value }),
+                    8 => json!({ "rotate_left": value }),
+                    _ => [json!("shrink_to_fit"), json!({ "truncate": value }), json!({ "extend": value })][next(3) as usize].clone(),
+                }
+            })
+            .collect();
+        scripts.push((format!("random_seed_{}", seed), ops));
+    }
+
+    Ok(scripts
+        .into_iter()
+        .map(|(script, ops)| MutatedInput {
+            input_json: json!({ "ops": ops }),
+            op: MutationOp::new(MutationStrategy::CollectionScripts, json!({ "script": script })),
+            base_input_path: base_input_path.to_string(),
+        })
+        .collect())
+}
+
+/// Generate thread_api mutations: no workers, one, a few, at and past the
+/// clamp
+fn generate_thread_api_mutations(_base_input: &Value, base_input_path: &str) -> Result<Vec<MutatedInput>> {
+    use serde_json::json;
+
+    // Iterations stay small: every one is an atomic add and a Mutex lock
+    // per worker, executed inline (so in sequence) in the zkVM
+    let cases: [(u32, u32); 10] = [
+        (0, 1000),
+        (1, 0),
+        (1, 1),
+        (2, 1000),
+        (8, 100),
+        (64, 10),
+        (64, 1000),
+        (256, 1),
+        (257, 1),
+        (u32::MAX, 0),
+    ];
+
+    Ok(cases
+        .iter()
+        .map(|&(workers, iterations)| MutatedInput {
+            input_json: json!({ "workers": workers, "iterations": iterations }),
+            op: MutationOp::new(
+                MutationStrategy::ThreadCounts,
+                json!({ "workers": workers, "iterations": iterations }),
+            ),
+            base_input_path: base_input_path.to_string(),
+        })
+        .collect())
+}
+
 /// Statistics about generated mutations
 #[derive(Debug, Clone)]
 pub struct MutationStats {
@@ -332,3 +1411,391 @@ pub fn calculate_size_stats(mutations: &[MutatedInput]) -> MutationStats {
     }
 }
 
+
+/// Coverage of one input field across a mutation campaign
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FieldCoverage {
+    /// JSON kinds seen ("integer", "float", "string", "bool", "array", ...)
+    pub kinds: BTreeSet<String>,
+    /// Hits per value/length bucket (categorical fields: per value)
+    pub buckets: BTreeMap<String, usize>,
+    /// Uncovered buckets between the smallest and largest covered one
+    /// (floats: special classes never generated)
+    pub gaps: Vec<String>,
+}
+
+/// Which input regions a mutation campaign exercised
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CoverageReport {
+    pub core: String,
+    pub total_inputs: usize,
+    /// Per field path (`data`, `data[]` for elements, `outer.inner` for nesting)
+    pub fields: BTreeMap<String, FieldCoverage>,
+    /// Categorical field × other field bucket matrices, keyed "`a`×`b`"
+    pub combinations: BTreeMap<String, BTreeMap<String, BTreeMap<String, usize>>>,
+}
+
+/// Integer magnitude buckets, in order
+const INTEGER_BUCKETS: [&str; 10] = [
+    "< -2^31",
+    "-2^31..-1",
+    "0",
+    "1",
+    "2..255",
+    "256..65535",
+    "65536..2^31-1",
+    "2^31..2^32-1",
+    "2^32..2^63-1",
+    ">= 2^63",
+];
+
+/// Length buckets for strings and arrays, in order
+const LENGTH_BUCKETS: [&str; 8] = [
+    "len 0",
+    "len 1",
+    "len 2..15",
+    "len 16..255",
+    "len 256..4095",
+    "len 4KB..64KB",
+    "len 64KB..1MB",
+    "len > 1MB",
+];
+
+/// Float classes every float field should see (JSON has no NaN or
+/// infinities, so inputs cannot carry them)
+const FLOAT_CLASSES: [&str; 4] = ["zero", "-0.0", "subnormal", "normal"];
+
+/// Strings up to this length are treated as categorical values
+const CATEGORICAL_MAX_LEN: usize = 32;
+
+/// Build a coverage report for a core's mutations
+///
+/// Every field is bucketed generically: integers by magnitude, floats by
+/// class, strings and arrays by length (short strings also by value), and
+/// scalar array elements under `field[]`. Categorical fields (bools, short
+/// strings) are crossed with every other field, e.g. `operation×a` for
+/// arithmetic, so untried combinations stand out.
+pub fn coverage_report(core_name: &str, mutations: &[MutatedInput]) -> CoverageReport {
+    let mut report = CoverageReport {
+        core: core_name.to_string(),
+        total_inputs: mutations.len(),
+        ..Default::default()
+    };
+
+    for mutation in mutations {
+        let mut observed = Vec::new();
+        observe_value("", &mutation.input_json, &mut observed);
+
+        for (path, kind, bucket) in &observed {
+            let field = report.fields.entry(path.clone()).or_default();
+            field.kinds.insert(kind.to_string());
+            *field.buckets.entry(bucket.clone()).or_default() += 1;
+        }
+
+        // Only top-level, non-element fields take part in combinations
+        let top_level: Vec<&(String, &str, String)> = observed
+            .iter()
+            .filter(|(path, _, _)| !path.contains('.') && !path.ends_with("[]"))
+            .collect();
+        for (cat_path, cat_kind, cat_value) in &top_level {
+            if !is_categorical(cat_kind, cat_value) {
+                continue;
+            }
+            for (other_path, _, other_bucket) in &top_level {
+                if other_path == cat_path {
+                    continue;
+                }
+                *report
+                    .combinations
+                    .entry(format!("{}×{}", cat_path, other_path))
+                    .or_default()
+                    .entry(cat_value.clone())
+                    .or_default()
+                    .entry(other_bucket.clone())
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    for field in report.fields.values_mut() {
+        field.gaps = coverage_gaps(field);
+    }
+
+    report
+}
+
+/// Record (path, kind, bucket) triples for a value and its children
+fn observe_value(path: &str, value: &Value, out: &mut Vec<(String, &'static str, String)>) {
+    let field = |suffix: &str| {
+        if path.is_empty() {
+            suffix.to_string()
+        } else {
+            format!("{}.{}", path, suffix)
+        }
+    };
+
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                observe_value(&field(key), child, out);
+            }
+        }
+        Value::Array(items) => {
+            out.push((path.to_string(), "array", length_bucket(items.len()).to_string()));
+            for item in items.iter().filter(|item| !item.is_array() && !item.is_object()) {
+                observe_value(&format!("{}[]", path), item, out);
+            }
+        }
+        Value::String(s) => {
+            let bucket = if s.chars().count() <= CATEGORICAL_MAX_LEN {
+                format!("\"{}\"", s)
+            } else {
+                length_bucket(s.len()).to_string()
+            };
+            out.push((path.to_string(), "string", bucket));
+        }
+        Value::Bool(b) => out.push((path.to_string(), "bool", b.to_string())),
+        Value::Number(n) => {
+            let entry = match (n.as_i64(), n.as_u64(), n.as_f64()) {
+                (Some(i), _, _) => ("integer", integer_bucket(i as i128)),
+                (None, Some(u), _) => ("integer", integer_bucket(u as i128)),
+                (_, _, Some(f)) => ("float", float_class(f)),
+                _ => ("float", "normal"),
+            };
+            out.push((path.to_string(), entry.0, entry.1.to_string()));
+        }
+        Value::Null => out.push((path.to_string(), "null", "null".to_string())),
+    }
+}
+
+fn is_categorical(kind: &str, value: &str) -> bool {
+    kind == "bool" || (kind == "string" && value.starts_with('"'))
+}
+
+fn integer_bucket(v: i128) -> &'static str {
+    match v {
+        v if v < -(1 << 31) => INTEGER_BUCKETS[0],
+        v if v < 0 => INTEGER_BUCKETS[1],
+        0 => INTEGER_BUCKETS[2],
+        1 => INTEGER_BUCKETS[3],
+        v if v <= 255 => INTEGER_BUCKETS[4],
+        v if v <= 65535 => INTEGER_BUCKETS[5],
+        v if v < 1 << 31 => INTEGER_BUCKETS[6],
+        v if v < 1 << 32 => INTEGER_BUCKETS[7],
+        v if v < 1 << 63 => INTEGER_BUCKETS[8],
+        _ => INTEGER_BUCKETS[9],
+    }
+}
+
+fn length_bucket(len: usize) -> &'static str {
+    match len {
+        0 => LENGTH_BUCKETS[0],
+        1 => LENGTH_BUCKETS[1],
+        2..=15 => LENGTH_BUCKETS[2],
+        16..=255 => LENGTH_BUCKETS[3],
+        256..=4095 => LENGTH_BUCKETS[4],
+        4096..=65535 => LENGTH_BUCKETS[5],
+        65536..=1048576 => LENGTH_BUCKETS[6],
+        _ => LENGTH_BUCKETS[7],
+    }
+}
+
+fn float_class(f: f64) -> &'static str {
+    if f == 0.0 && f.is_sign_negative() {
+        "-0.0"
+    } else if f == 0.0 {
+        "zero"
+    } else if f.is_subnormal() {
+        "subnormal"
+    } else {
+        "normal"
+    }
+}
+
+/// Uncovered buckets inside the covered range (floats: missing classes)
+fn coverage_gaps(field: &FieldCoverage) -> Vec<String> {
+    let interior = |order: &[&str]| -> Vec<String> {
+        let covered: Vec<usize> = order
+            .iter()
+            .enumerate()
+            .filter(|(_, bucket)| field.buckets.contains_key(**bucket))
+            .map(|(i, _)| i)
+            .collect();
+        match (covered.first(), covered.last()) {
+            (Some(&lo), Some(&hi)) => order[lo..=hi]
+                .iter()
+                .filter(|bucket| !field.buckets.contains_key(**bucket))
+                .map(|bucket| bucket.to_string())
+                .collect(),
+            _ => vec![],
+        }
+    };
+
+    let mut gaps = Vec::new();
+    if field.kinds.contains("integer") {
+        gaps.extend(interior(&INTEGER_BUCKETS));
+    }
+    if field.kinds.contains("array") || field.kinds.contains("string") {
+        gaps.extend(interior(&LENGTH_BUCKETS));
+    }
+    if field.kinds.contains("float") {
+        gaps.extend(
+            FLOAT_CLASSES
+                .iter()
+                .filter(|class| !field.buckets.contains_key(**class))
+                .map(|class| class.to_string()),
+        );
+    }
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(a, b, operation)` of each arithmetic input
+    fn triples(samples: usize) -> Vec<(u64, u64, String)> {
+        arithmetic_boundary_mutations(samples, "base.json")
+            .into_iter()
+            .map(|input| {
+                let json = &input.input_json;
+                (json["a"].as_u64().unwrap(), json["b"].as_u64().unwrap(), json["operation"].as_str().unwrap().to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_u32_grid() {
+        let boundaries = vec![0, 1, 2, u32::MAX / 2, u32::MAX / 2 + 1, u32::MAX - 1, u32::MAX];
+        assert_eq!(u32_grid(0), boundaries);
+        // The single interior point of resolution 1 is u32::MAX / 2 again
+        assert_eq!(u32_grid(1), boundaries);
+        let grid = u32_grid(3);
+        assert_eq!(grid.len(), boundaries.len() + 3);
+        assert!(grid.contains(&1_073_741_823) && grid.contains(&2_147_483_646) && grid.contains(&3_221_225_469));
+        assert!(boundaries.iter().all(|value| grid.contains(value)));
+        assert!(grid.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(u32_grid(100).len(), boundaries.len() + 100);
+    }
+
+    #[test]
+    fn test_enumerate_inputs() {
+        for resolution in [0, 1, 3] {
+            let inputs = enumerate_inputs("arithmetic", "base.json", resolution).unwrap();
+            let grid = u32_grid(resolution).len();
+            assert_eq!(inputs.len(), 4 * grid * grid);
+            let distinct: BTreeSet<String> = inputs.iter().map(|input| input.input_json.to_string()).collect();
+            assert_eq!(distinct.len(), inputs.len());
+            assert!(inputs.iter().all(|input| input.op.strategy == MutationStrategy::Exhaustive));
+        }
+
+        // should_panic x (no message, empty, 1/255/256/4096 chars and
+        // `resolution` lengths up to 64 KiB, deduplicated)
+        let panic_test = |resolution| enumerate_inputs("panic_test", "base.json", resolution).unwrap();
+        assert_eq!(panic_test(0).len(), 2 * 6);
+        assert_eq!(panic_test(1).len(), 2 * 7);
+        assert_eq!(panic_test(4).len(), 2 * 10);
+        // 4096 is one of the fixed lengths already
+        assert_eq!(panic_test(16).len(), 2 * (6 + 15));
+        for input in panic_test(4) {
+            let chars = input.input_json["panic_msg"].as_str().map(|message| message.chars().count());
+            let logged = input.op.params["panic_msg_chars"].as_u64().map(|chars| chars as usize);
+            assert_eq!(chars, logged);
+        }
+
+        let error = enumerate_inputs("sorting", "base.json", 4).unwrap_err().to_string();
+        assert!(error.contains("sorting has no enumerable input space"), "{}", error);
+        assert!(error.contains("arithmetic, panic_test"));
+    }
+
+    fn inputs(values: &[Value]) -> Vec<MutatedInput> {
+        values
+            .iter()
+            .map(|input_json| MutatedInput {
+                input_json: input_json.clone(),
+                op: MutationOp::new(MutationStrategy::BoundaryValues, serde_json::json!({})),
+                base_input_path: "base.json".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_coverage_report() {
+        use serde_json::json;
+        let report = coverage_report(
+            "example",
+            &inputs(&[
+                json!({ "a": 0, "op": "add", "data": [1, 300], "outer": { "ratio": 0.5 } }),
+                json!({ "a": 70000, "op": "sub", "data": [], "outer": { "ratio": -0.0 } }),
+                json!({ "a": 70000, "op": "add", "data": "x".repeat(100), "outer": { "ratio": 1e-310 } }),
+            ]),
+        );
+        assert_eq!(report.total_inputs, 3);
+
+        let a = &report.fields["a"];
+        assert_eq!(a.kinds, BTreeSet::from(["integer".to_string()]));
+        assert_eq!(a.buckets, BTreeMap::from([("0".to_string(), 1), ("65536..2^31-1".to_string(), 2)]));
+        assert_eq!(a.gaps, ["1", "2..255", "256..65535"]);
+
+        // Arrays and long strings share a field's length buckets; scalar
+        // elements are bucketed under `data[]`
+        let data = &report.fields["data"];
+        assert_eq!(data.kinds, BTreeSet::from(["array".to_string(), "string".to_string()]));
+        assert_eq!(data.gaps, ["len 1"]);
+        assert_eq!(report.fields["data[]"].buckets.len(), 2);
+
+        let ratio = &report.fields["outer.ratio"];
+        assert_eq!(ratio.buckets.keys().collect::<Vec<_>>(), ["-0.0", "normal", "subnormal"]);
+        assert_eq!(ratio.gaps, ["zero"]);
+
+        // Short strings are categorical and crossed with the other top-level fields
+        assert_eq!(report.fields["op"].buckets["\"add\""], 2);
+        let op_a = &report.combinations["op×a"];
+        assert_eq!(op_a["\"add\""], BTreeMap::from([("0".to_string(), 1), ("65536..2^31-1".to_string(), 1)]));
+        assert_eq!(op_a["\"sub\""].len(), 1);
+        assert!(!report.combinations.contains_key("a×op"));
+        assert!(!report.combinations.keys().any(|key| key.contains("outer") || key.contains("[]")));
+    }
+
+    #[test]
+    fn test_coverage_gaps() {
+        let field = |kind: &str, buckets: &[&str]| FieldCoverage {
+            kinds: BTreeSet::from([kind.to_string()]),
+            buckets: buckets.iter().map(|bucket| (bucket.to_string(), 1)).collect(),
+            gaps: Vec::new(),
+        };
+        assert!(coverage_gaps(&field("integer", &["0"])).is_empty());
+        assert_eq!(coverage_gaps(&field("integer", &["-2^31..-1", "1"])), ["0"]);
+        assert_eq!(coverage_gaps(&field("string", &["len 0", "len 16..255"])), ["len 1", "len 2..15"]);
+        // Floats miss whichever classes they never hit, NaN and inf aside
+        assert_eq!(coverage_gaps(&field("float", &["normal"])), ["zero", "-0.0", "subnormal"]);
+        assert!(coverage_gaps(&field("float", &FLOAT_CLASSES)).is_empty());
+        assert!(coverage_gaps(&field("bool", &["true"])).is_empty());
+    }
+
+    #[test]
+    fn test_arithmetic_all_pairs() {
+        let layer = triples(DEFAULT_ARITHMETIC_SAMPLES);
+        assert_eq!(layer.len(), 36);
+        let pairs = |pick: fn(&(u64, u64, String)) -> (String, String)| {
+            layer.iter().map(pick).collect::<BTreeSet<_>>().len()
+        };
+        // Every a with every b, every operand with every operation
+        assert_eq!(pairs(|(a, b, _)| (a.to_string(), b.to_string())), 36);
+        assert_eq!(pairs(|(a, _, op)| (a.to_string(), op.clone())), 24);
+        assert_eq!(pairs(|(_, b, op)| (b.to_string(), op.clone())), 24);
+    }
+
+    #[test]
+    fn test_arithmetic_cross_product() {
+        let full = triples(144);
+        assert_eq!(full.iter().collect::<BTreeSet<_>>().len(), 144);
+        // A partial second layer extends the first one without repeating it
+        let partial = triples(50);
+        assert_eq!(partial[..36], full[..36]);
+        assert_eq!(partial.iter().collect::<BTreeSet<_>>().len(), 50);
+        // Out-of-range counts are clamped
+        assert_eq!(triples(10), triples(36));
+        assert_eq!(triples(1000), full);
+    }
+}
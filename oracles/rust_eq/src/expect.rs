@@ -0,0 +1,158 @@
+//! Expected-divergence assertions
+//!
+//! Some inputs diverge on purpose, to document a known zkVM behavior. Such
+//! an input carries an `expected` block next to the core's fields, which
+//! the runners ignore:
+//!
+//! ```json
+//! {
+//!   "should_panic": true,
+//!   "expected": { "zkvm_status": "PANIC", "kind": "commit_length_mismatch" }
+//! }
+//! ```
+//!
+//! [`check`] holds a comparison to it: every field given must match, and a
+//! block with no statuses or kind expects the results to differ somehow.
+//! A confirmed expectation turns a known divergence into a passing
+//! negative test; anything else is an unexpected result.
+
+use crate::{Diff, RunResult, Status};
+use serde::{Deserialize, Serialize};
+
+/// Category names [`Expectation::kind`] accepts (see [`crate::DiffKind`])
+pub const KINDS: &[&str] = &[
+    "not_compared",
+    "status_mismatch",
+    "commit_length_mismatch",
+    "commit_value_mismatch",
+    "prefix_mismatch",
+    "relation_violated",
+];
+
+/// The `expected` block of an input file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expectation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub native_status: Option<Status>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zkvm_status: Option<Status>,
+    /// [`crate::DiffKind`] name of the expected inequality
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// Why the divergence is expected (for people; never checked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl Expectation {
+    /// Read the `expected` block of an input's JSON (None if it has none)
+    pub fn from_input(input: &serde_json::Value) -> anyhow::Result<Option<Expectation>> {
+        let Some(block) = input.get("expected") else {
+            return Ok(None);
+        };
+        let expectation: Expectation = serde_json::from_value(block.clone())?;
+        if let Some(kind) = &expectation.kind {
+            anyhow::ensure!(
+                KINDS.contains(&kind.as_str()),
+                "unknown kind '{}' (expected one of: {})",
+                kind,
+                KINDS.join(", ")
+            );
+        }
+        Ok(Some(expectation))
+    }
+}
+
+/// An [`Expectation`] held to a comparison
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectationCheck {
+    pub expected: Expectation,
+    /// Whether the results matched every expected field
+    pub confirmed: bool,
+    /// What did not match (empty if confirmed)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mismatches: Vec<String>,
+}
+
+/// Hold the results of a comparison to an input's expectation
+pub fn check(expected: &Expectation, native: &RunResult, zkvm: &RunResult, diff: &Diff) -> ExpectationCheck {
+    let mut mismatches = Vec::new();
+    for (side, want, got) in [
+        ("native", expected.native_status, native.status),
+        ("zkvm", expected.zkvm_status, zkvm.status),
+    ] {
+        if let Some(want) = want.filter(|&want| want != got) {
+            mismatches.push(format!("expected {} status {:?}, got {:?}", side, want, got));
+        }
+    }
+    match (&expected.kind, &diff.kind) {
+        (Some(want), Some(got)) if want != got.as_str() => {
+            mismatches.push(format!("expected {}, got {}", want, got.as_str()));
+        }
+        (Some(want), None) => mismatches.push(format!("expected {}, but the results are equal", want)),
+        _ => {}
+    }
+    let unconstrained = expected.native_status.is_none() && expected.zkvm_status.is_none() && expected.kind.is_none();
+    if unconstrained && diff.equal {
+        mismatches.push("expected a divergence, but the results are equal".to_string());
+    }
+    ExpectationCheck {
+        expected: expected.clone(),
+        confirmed: mismatches.is_empty(),
+        mismatches,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compare;
+    use serde_json::json;
+
+    fn result(status: Status, commits: Vec<serde_json::Value>) -> RunResult {
+        RunResult {
+            status,
+            elapsed_ms: 1,
+            commits,
+            meta: json!({}),
+        }
+    }
+
+    #[test]
+    fn test_check_expectation() {
+        let input = json!({
+            "should_panic": true,
+            "expected": { "zkvm_status": "PANIC", "kind": "status_mismatch", "note": "known" }
+        });
+        let expected = Expectation::from_input(&input).unwrap().unwrap();
+        assert_eq!(expected.zkvm_status, Some(Status::Panic));
+
+        let native = result(Status::Ok, vec![json!(1)]);
+        let zkvm = result(Status::Panic, vec![]);
+        let confirmed = check(&expected, &native, &zkvm, &compare(&native, &zkvm));
+        assert!(confirmed.confirmed, "{:?}", confirmed.mismatches);
+
+        let unexpected = check(&expected, &native, &native, &compare(&native, &native));
+        assert!(!unexpected.confirmed);
+        assert_eq!(
+            unexpected.mismatches,
+            vec![
+                "expected zkvm status Panic, got Ok",
+                "expected status_mismatch, but the results are equal"
+            ]
+        );
+
+        // An empty block only expects some divergence
+        let any = Expectation::default();
+        assert!(check(&any, &native, &zkvm, &compare(&native, &zkvm)).confirmed);
+        assert!(!check(&any, &native, &native, &compare(&native, &native)).confirmed);
+    }
+
+    #[test]
+    fn test_expectation_from_input() {
+        assert_eq!(Expectation::from_input(&json!({ "n": 24 })).unwrap(), None);
+        assert!(Expectation::from_input(&json!({ "expected": { "kind": "panic" } })).is_err());
+        assert!(Expectation::from_input(&json!({ "expected": { "zkvm": "PANIC" } })).is_err());
+    }
+}
@@ -0,0 +1,445 @@
+//! Human-readable divergence explanations
+//!
+//! [`explain`] turns a [`Diff`] and the two results behind it into a short
+//! narrative ("SP1 committed 0 at index 2 where native committed 256") and
+//! runs heuristic matchers for common bug classes over the first divergent
+//! commit and the statuses ("this looks like a u8 truncation"). The
+//! heuristics only suggest where to look; they never change the verdict.
+
+use crate::{describe_commit_difference, describe_commit_index, Diff, RunResult, Status};
+use serde::{Deserialize, Serialize};
+
+/// Longest commit value quoted in a narrative
+const MAX_VALUE_CHARS: usize = 80;
+
+/// A bug class a divergence resembles
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suspect {
+    /// Short identifier (e.g. `u8_truncation`, `off_by_one`)
+    pub pattern: String,
+    /// Sentence fragment completing "This looks like ..."
+    pub detail: String,
+}
+
+/// Narrative and suspected bug classes of a comparison
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Explanation {
+    pub narrative: Vec<String>,
+    pub suspects: Vec<Suspect>,
+}
+
+impl std::fmt::Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.narrative {
+            writeln!(f, "{}", line)?;
+        }
+        for suspect in &self.suspects {
+            writeln!(f, "This looks like {}.", suspect.detail)?;
+        }
+        Ok(())
+    }
+}
+
+fn suspect(pattern: &str, detail: String) -> Suspect {
+    Suspect {
+        pattern: pattern.to_string(),
+        detail,
+    }
+}
+
+/// Name of the zkVM side (`meta.runner`, e.g. "SP1")
+fn zkvm_label(zkvm: &RunResult) -> String {
+    match zkvm.meta.get("runner").and_then(|runner| runner.as_str()) {
+        Some("sp1") | None => "SP1".to_string(),
+        Some(runner) => runner.to_string(),
+    }
+}
+
+fn panic_message(result: &RunResult) -> Option<&str> {
+    ["panic_msg", "error"]
+        .iter()
+        .find_map(|key| result.meta.get(*key).and_then(|v| v.as_str()))
+}
+
+/// `file:line` of a panic (`meta.panic_site`, set by the harness for SP1)
+fn panic_site(result: &RunResult) -> Option<String> {
+    let site = result.meta.get("panic_site")?;
+    Some(format!("{}:{}", site.get("file")?.as_str()?, site.get("line")?))
+}
+
+fn quote(value: &serde_json::Value) -> String {
+    let text = value.to_string();
+    match text.char_indices().nth(MAX_VALUE_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text,
+    }
+}
+
+fn describe_run(label: &str, result: &RunResult) -> String {
+    let mut line = format!(
+        "{}: {:?}, {} commits in {} ms",
+        label,
+        result.status,
+        result.commits.len(),
+        result.elapsed_ms
+    );
+    if result.status != Status::Ok {
+        if let Some(message) = panic_message(result) {
+            line.push_str(&format!(" ({})", message.lines().next().unwrap_or_default()));
+        }
+        if let Some(site) = panic_site(result) {
+            line.push_str(&format!(", panicked at {}", site));
+        }
+    }
+    line
+}
+
+/// Explain how `native` and `zkvm` diverge according to `diff`
+pub fn explain(native: &RunResult, zkvm: &RunResult, diff: &Diff) -> Explanation {
+    let zk = zkvm_label(zkvm);
+    let mut narrative = vec![describe_run("Native", native), describe_run(&zk, zkvm)];
+    let mut suspects = Vec::new();
+
+    if native.status.is_infra() || zkvm.status.is_infra() {
+        narrative.push("The runs were not compared: one of them failed for infrastructure reasons.".to_string());
+        return Explanation { narrative, suspects };
+    }
+    if diff.equal {
+        narrative.push("Both runs agree.".to_string());
+        return Explanation { narrative, suspects };
+    }
+
+    if native.status != zkvm.status {
+        narrative.push(format!(
+            "{} ended with {:?} where native ended with {:?}.",
+            zk, zkvm.status, native.status
+        ));
+        suspects.extend(status_suspects(native, zkvm, &zk));
+    }
+
+    let prefix = diff.common_prefix_len.unwrap_or(0);
+    if let Some(index) = diff.first_divergent_index {
+        if prefix > 0 {
+            narrative.push(format!("The commit streams agree on the first {} commits.", prefix));
+        }
+        let at = describe_commit_index(native, zkvm, index);
+        match (native.commits.get(index), zkvm.commits.get(index)) {
+            (Some(a), Some(b)) => {
+                match describe_commit_difference(a, b) {
+                    Some(detail) => narrative.push(format!("At {}, {}.", at, detail)),
+                    None => narrative.push(format!(
+                        "At {}, {} committed {} where native committed {}.",
+                        at,
+                        zk,
+                        quote(b),
+                        quote(a)
+                    )),
+                }
+                suspects.extend(value_suspects(a, b, &zk));
+            }
+            (Some(a), None) => {
+                narrative.push(format!(
+                    "{} stopped after {} commits; native went on to commit {} at {} ({} in total).",
+                    zk,
+                    index,
+                    quote(a),
+                    at,
+                    native.commits.len()
+                ));
+                if native.status == Status::Ok && zkvm.status == Status::Ok {
+                    suspects.push(suspect(
+                        "missing_commits",
+                        format!("{} skipped part of the output (an early return or a loop that ran fewer times)", zk),
+                    ));
+                }
+            }
+            (None, Some(b)) => {
+                narrative.push(format!(
+                    "Native stopped after {} commits; {} went on to commit {} at {} ({} in total).",
+                    index,
+                    zk,
+                    quote(b),
+                    at,
+                    zkvm.commits.len()
+                ));
+                if native.status == Status::Ok && zkvm.status == Status::Ok {
+                    suspects.push(suspect(
+                        "extra_commits",
+                        format!("{} produced more output than native (a loop that ran more times)", zk),
+                    ));
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    Explanation { narrative, suspects }
+}
+
+/// Heuristics for a status mismatch
+fn status_suspects(native: &RunResult, zkvm: &RunResult, zk: &str) -> Vec<Suspect> {
+    let mut suspects = Vec::new();
+    for (label, result, other) in [("native", native, zkvm), (zk, zkvm, native)] {
+        if other.status != Status::Ok {
+            continue;
+        }
+        let message = panic_message(result).unwrap_or_default().to_lowercase();
+        match result.status {
+            Status::Panic if message.contains("overflow") => suspects.push(suspect(
+                "overflow_check",
+                format!("an integer overflow that only {} checks (it panicked, the other side wrapped)", label),
+            )),
+            Status::Panic
+                if ["unsupported", "not supported", "unimplemented", "not implemented", "syscall"]
+                    .iter()
+                    .any(|needle| message.contains(needle)) =>
+            {
+                suspects.push(suspect(
+                    "unsupported_api",
+                    format!("a syscall or std API that {} does not support", label),
+                ))
+            }
+            Status::Timeout | Status::CycleLimit => suspects.push(suspect(
+                "budget",
+                format!("a budget difference: {} ran out of time or cycles where the other side finished", label),
+            )),
+            Status::Oom => suspects.push(oom_suspect(label, result, other)),
+            _ => {}
+        }
+    }
+    suspects
+}
+
+/// Why one side ran out of memory and the other did not
+///
+/// With the same `meta.memory_limit` on both sides (`--memory-limit`), the
+/// limit is not the difference: the two targets need different amounts of
+/// memory for the same input.
+fn oom_suspect(label: &str, result: &RunResult, other: &RunResult) -> Suspect {
+    let limit = result.meta.get("memory_limit").and_then(|limit| limit.as_u64());
+    let used = result
+        .meta
+        .get("memory_bytes")
+        .and_then(|bytes| bytes.as_u64())
+        .map(|bytes| format!(" ({} bytes touched)", bytes))
+        .unwrap_or_default();
+    match limit {
+        Some(limit) if other.meta.get("memory_limit").and_then(|limit| limit.as_u64()) == Some(limit) => suspect(
+            "memory_footprint",
+            format!(
+                "a larger memory footprint: {} needed more than the shared {}-byte limit{} where the other side fit",
+                label, limit, used
+            ),
+        ),
+        Some(limit) => suspect(
+            "memory_limit",
+            format!("a memory limit: {} ran out of its {}-byte budget{} where the other side did not", label, limit, used),
+        ),
+        None => suspect(
+            "memory_limit",
+            format!("a memory limit: {} ran out of memory where the other side did not", label),
+        ),
+    }
+}
+
+/// Heuristics for the first pair of differing commits
+fn value_suspects(native: &serde_json::Value, zkvm: &serde_json::Value, zk: &str) -> Vec<Suspect> {
+    fn as_int(value: &serde_json::Value) -> Option<i128> {
+        value
+            .as_u64()
+            .map(i128::from)
+            .or_else(|| value.as_i64().map(i128::from))
+    }
+    if let (Some(a), Some(b)) = (as_int(native), as_int(zkvm)) {
+        return integer_suspects(a, b, zk);
+    }
+
+    let (a, b) = match (native, zkvm) {
+        (serde_json::Value::String(a), serde_json::Value::String(b)) => (a.as_bytes().to_vec(), b.as_bytes().to_vec()),
+        (serde_json::Value::Array(a), serde_json::Value::Array(b)) => {
+            let bytes = |items: &[serde_json::Value]| -> Option<Vec<u8>> {
+                items.iter().map(|v| u8::try_from(v.as_u64()?).ok()).collect()
+            };
+            match (bytes(a), bytes(b)) {
+                (Some(a), Some(b)) => {
+                    if a.len().min(b.len()) == 0 && a.len().max(b.len()) == 1 {
+                        let none_side = if a.is_empty() { "native" } else { zk };
+                        return vec![suspect(
+                            "option_mismatch",
+                            format!("an Option mismatch: None on {}, Some on the other side", none_side),
+                        )];
+                    }
+                    (a, b)
+                }
+                _ => return Vec::new(),
+            }
+        }
+        _ => return Vec::new(),
+    };
+    let (shorter, longer, side) = if a.len() < b.len() { (&a, &b, "native") } else { (&b, &a, zk) };
+    if shorter.len() < longer.len() && longer.starts_with(shorter) {
+        return vec![suspect(
+            "truncated_data",
+            format!(
+                "truncated data: {}'s value is the first {} of {} bytes",
+                side,
+                shorter.len(),
+                longer.len()
+            ),
+        )];
+    }
+    Vec::new()
+}
+
+/// Heuristics for two differing integers
+fn integer_suspects(native: i128, zkvm: i128, zk: &str) -> Vec<Suspect> {
+    let mut suspects = Vec::new();
+
+    // Widest mask first: 256 -> 0 is a u8 truncation, 2^32 -> 0 a u32 one
+    for bits in [32u32, 16, 8] {
+        let mask = (1i128 << bits) - 1;
+        let truncated = [(native, zkvm, zk), (zkvm, native, "native")]
+            .into_iter()
+            .find(|(full, cut, _)| *full > mask && *cut == full & mask);
+        if let Some((full, cut, side)) = truncated {
+            suspects.push(suspect(
+                &format!("u{}_truncation", bits),
+                format!(
+                    "a u{} truncation: {} kept only the low {} bits of {} ({:#x} & {:#x} = {})",
+                    bits, side, bits, full, full, mask, cut
+                ),
+            ));
+            break;
+        }
+    }
+
+    let delta = native - zkvm;
+    for bits in [32u32, 64] {
+        if delta.abs() == 1i128 << bits {
+            let pattern = if native < 0 || zkvm < 0 { "sign_reinterpretation" } else { "wraparound" };
+            suspects.push(suspect(
+                pattern,
+                format!("a {}-bit wraparound or signed/unsigned reinterpretation (the values differ by exactly 2^{})", bits, bits),
+            ));
+        }
+    }
+    if delta.abs() == 1 {
+        suspects.push(suspect("off_by_one", "an off-by-one (the values differ by 1)".to_string()));
+    }
+    if native >= 0 && zkvm >= 0 {
+        let xor = native ^ zkvm;
+        // Against 0 any power of two is one bit away; zero_value covers it
+        if xor.count_ones() == 1 && native != 0 && zkvm != 0 {
+            suspects.push(suspect(
+                "bit_flip",
+                format!("a single flipped bit (bit {})", xor.trailing_zeros()),
+            ));
+        }
+        let swapped = |a: i128, b: i128| {
+            (a <= u32::MAX as i128 && b <= u32::MAX as i128 && (a as u32).swap_bytes() as i128 == b)
+                || (a as u64).swap_bytes() as i128 == b
+        };
+        if swapped(native, zkvm) {
+            suspects.push(suspect("byte_swap", "an endianness bug (the bytes are swapped)".to_string()));
+        }
+    }
+
+    for (value, other, side) in [(zkvm, native, zk), (native, zkvm, "native")] {
+        if value == 0 {
+            suspects.push(suspect(
+                "zero_value",
+                format!("{} skipped a computation or committed a default value (it committed 0, the other side {})", side, other),
+            ));
+        } else if value == u32::MAX as i128 || value == u64::MAX as i128 || value == -1 {
+            suspects.push(suspect(
+                "all_ones",
+                format!("{} hit an error sentinel or cast -1 to unsigned (it committed {})", side, value),
+            ));
+        }
+    }
+    suspects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compare;
+    use serde_json::json;
+
+    fn result(status: Status, commits: Vec<serde_json::Value>, meta: serde_json::Value) -> RunResult {
+        RunResult {
+            status,
+            elapsed_ms: 5,
+            commits,
+            meta,
+        }
+    }
+
+    fn patterns(explanation: &Explanation) -> Vec<&str> {
+        explanation.suspects.iter().map(|s| s.pattern.as_str()).collect()
+    }
+
+    #[test]
+    fn test_explain_commit_mismatch() {
+        let native = result(Status::Ok, vec![json!(1), json!(2), json!(256)], json!({ "runner": "native" }));
+        let zkvm = result(Status::Ok, vec![json!(1), json!(2), json!(0)], json!({ "runner": "sp1" }));
+        let explanation = explain(&native, &zkvm, &compare(&native, &zkvm));
+
+        assert_eq!(explanation.narrative[0], "Native: Ok, 3 commits in 5 ms");
+        assert!(explanation.narrative.contains(&"The commit streams agree on the first 2 commits.".to_string()));
+        assert!(explanation.narrative.contains(&"At index 2, SP1 committed 0 where native committed 256.".to_string()));
+        assert_eq!(patterns(&explanation), ["u8_truncation", "zero_value"]);
+        assert!(explanation.to_string().contains("This looks like a u8 truncation: SP1 kept only the low 8 bits of 256"));
+
+        let wrapped = |n: u64, z: u64| {
+            let native = result(Status::Ok, vec![json!(n)], json!({}));
+            let zkvm = result(Status::Ok, vec![json!(z)], json!({}));
+            explain(&native, &zkvm, &compare(&native, &zkvm))
+        };
+        assert_eq!(patterns(&wrapped(1 << 32, 0)), ["u32_truncation", "wraparound", "zero_value"]);
+        assert_eq!(patterns(&wrapped(41, 42)), ["off_by_one"]);
+        assert_eq!(patterns(&wrapped(0x12345678, 0x78563412)), ["byte_swap"]);
+        assert_eq!(patterns(&wrapped(5, 4294967295)), ["all_ones"]);
+        assert_eq!(patterns(&wrapped(8, 12)), ["bit_flip"]);
+    }
+
+    #[test]
+    fn test_explain_status_and_shape() {
+        let native = result(Status::Ok, vec![json!(7), json!([1])], json!({}));
+        let zkvm = result(Status::Ok, vec![json!(7), json!([])], json!({ "runner": "sp1" }));
+        let explanation = explain(&native, &zkvm, &compare(&native, &zkvm));
+        assert!(explanation.narrative.contains(&"At index 1, bytes differ at byte 0 (native len 1, zkvm len 0).".to_string()));
+        assert_eq!(patterns(&explanation), ["option_mismatch"]);
+
+        let native = result(Status::Ok, vec![json!(1), json!(2)], json!({}));
+        let zkvm = result(Status::Ok, vec![json!(1)], json!({}));
+        let explanation = explain(&native, &zkvm, &compare(&native, &zkvm));
+        assert!(explanation.narrative[3].starts_with("SP1 stopped after 1 commits; native went on to commit 2"));
+        assert_eq!(patterns(&explanation), ["missing_commits"]);
+
+        let native = result(Status::Panic, vec![], json!({ "panic_msg": "attempt to add with overflow" }));
+        let zkvm = result(Status::Ok, vec![json!(0)], json!({}));
+        let explanation = explain(&native, &zkvm, &compare(&native, &zkvm));
+        assert!(explanation.narrative[0].ends_with("(attempt to add with overflow)"));
+        assert_eq!(explanation.narrative[2], "SP1 ended with Ok where native ended with Panic.");
+        assert_eq!(patterns(&explanation), ["overflow_check"]);
+
+        let site = json!({ "panic_msg": "exit code 1", "panic_site": { "file": "src/lib.rs", "line": 42 } });
+        let zkvm = result(Status::Panic, vec![], site);
+        let explanation = explain(&native, &zkvm, &compare(&native, &zkvm));
+        assert_eq!(explanation.narrative[1], "SP1: Panic, 0 commits in 5 ms (exit code 1), panicked at src/lib.rs:42");
+
+        let native = result(Status::Ok, vec![json!(1)], json!({ "memory_limit": 1024 }));
+        let zkvm = result(Status::Oom, vec![], json!({ "memory_limit": 1024, "memory_bytes": 2048 }));
+        let explanation = explain(&native, &zkvm, &compare(&native, &zkvm));
+        assert_eq!(patterns(&explanation), ["memory_footprint"]);
+        assert!(explanation.suspects[0].detail.contains("(2048 bytes touched)"), "{:?}", explanation.suspects);
+        let unlimited = result(Status::Ok, vec![json!(1)], json!({}));
+        assert_eq!(patterns(&explain(&unlimited, &zkvm, &compare(&unlimited, &zkvm))), ["memory_limit"]);
+
+        let infra = result(Status::BuildFailure, vec![], json!({}));
+        let explanation = explain(&native, &infra, &compare(&native, &infra));
+        assert!(explanation.suspects.is_empty());
+        assert!(explanation.narrative[2].contains("not compared"));
+    }
+}
@@ -1,3 +1,6 @@
+pub mod expect;
+pub mod explain;
+
 use serde::{Deserialize, Serialize};
 
 /// Status of a program execution
@@ -12,10 +15,63 @@ pub enum Status {
     /// Execution timed out
     #[serde(rename = "TIMEOUT")]
     Timeout,
+    /// Execution exceeded its cycle budget (zkVM only)
+    #[serde(rename = "CYCLE_LIMIT")]
+    CycleLimit,
+    /// Execution ran out of memory (allocation failure or guest memory limit)
+    #[serde(rename = "OOM")]
+    Oom,
+    /// The program could not be built (e.g., `cargo prove build` failed or no ELF)
+    #[serde(rename = "BUILD_FAILURE")]
+    BuildFailure,
+    /// The runner itself failed (bad input file, unknown core, crashed tooling)
+    #[serde(rename = "INFRA_ERROR")]
+    InfraError,
+}
+
+impl Status {
+    /// Whether this status describes the harness rather than the program
+    /// (such results carry no information about the program's behavior)
+    pub fn is_infra(&self) -> bool {
+        matches!(self, Status::BuildFailure | Status::InfraError)
+    }
+
+    /// Whether the program stopped without finishing (possibly with a truncated commit stream)
+    pub fn is_truncated(&self) -> bool {
+        !matches!(self, Status::Ok) && !self.is_infra()
+    }
+}
+
+/// Status-equivalence matrix: whether a native and a zkVM status describe
+/// the same program behavior
+///
+/// Identical statuses are consistent, and so are native TIMEOUT and zkVM
+/// CYCLE_LIMIT (both mean "did not finish within its budget"; the budgets
+/// are just measured differently). Infrastructure statuses are never
+/// consistent with anything, not even themselves.
+pub fn statuses_consistent(native: Status, zkvm: Status) -> bool {
+    if native.is_infra() || zkvm.is_infra() {
+        return false;
+    }
+    match (native, zkvm) {
+        (a, b) if a == b => true,
+        (Status::Timeout, Status::CycleLimit) | (Status::CycleLimit, Status::Timeout) => true,
+        _ => false,
+    }
 }
 
 /// Result of running a program (native or ZKVM)
+///
+/// Commits are compared by position. When `meta.commit_names` names every
+/// commit (the harness attaches the core's output field names), the commits
+/// serialize as `[{"name": "length", "value": 5}, ...]` instead of bare
+/// values, and deserialize back into values plus `meta.commit_names`.
+///
+/// The serialized form carries a `schema_version` ([`RunResult::SCHEMA_VERSION`];
+/// results without one are version 1). A result from a newer runner fails
+/// to deserialize instead of being misread.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "RunResultRepr", try_from = "RunResultRepr")]
 pub struct RunResult {
     /// Execution status
     pub status: Status,
@@ -23,60 +79,495 @@ pub struct RunResult {
     pub elapsed_ms: u128,
     /// Sequence of committed values (in order)
     pub commits: Vec<serde_json::Value>,
-    /// Optional metadata (panic message, etc.)
-    #[serde(default)]
+    /// Optional metadata (panic message, commit names, etc.)
     pub meta: serde_json::Value,
 }
 
+impl RunResult {
+    /// Version of the serialized form written by this crate
+    ///
+    /// | Version | Changes |
+    /// |---|---|
+    /// | 1 | No `schema_version` |
+    /// | 2 | `schema_version` added |
+    pub const SCHEMA_VERSION: u32 = 2;
+
+    /// Longest stdout/stderr capture kept in `meta`
+    pub const MAX_CAPTURE_BYTES: usize = 64 * 1024;
+
+    /// Add a run's non-empty output to `meta.stdout` / `meta.stderr`,
+    /// truncated to [`RunResult::MAX_CAPTURE_BYTES`] on a character boundary
+    ///
+    /// Both runners keep what the program printed (panic messages included)
+    /// this way, so the two sides' captures compare like for like.
+    pub fn attach_output(&mut self, stdout: &[u8], stderr: &[u8]) {
+        let Some(meta) = self.meta.as_object_mut() else {
+            return;
+        };
+        for (key, bytes) in [("stdout", stdout), ("stderr", stderr)] {
+            if bytes.is_empty() {
+                continue;
+            }
+            let text = String::from_utf8_lossy(bytes);
+            let mut end = text.len().min(Self::MAX_CAPTURE_BYTES);
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            meta.insert(key.to_string(), serde_json::Value::from(&text[..end]));
+        }
+    }
+
+    /// Output field name of the commit at `index` (`meta.commit_names`)
+    pub fn commit_name(&self, index: usize) -> Option<&str> {
+        self.meta.get("commit_names")?.get(index)?.as_str()
+    }
+}
+
+/// Serialized form of [`RunResult`]
+#[derive(Serialize, Deserialize)]
+struct RunResultRepr {
+    #[serde(default = "unversioned")]
+    schema_version: u32,
+    status: Status,
+    elapsed_ms: u128,
+    commits: Vec<serde_json::Value>,
+    #[serde(default)]
+    meta: serde_json::Value,
+}
+
+impl From<RunResult> for RunResultRepr {
+    fn from(result: RunResult) -> Self {
+        let RunResult { status, elapsed_ms, mut commits, mut meta } = result;
+        let names = meta
+            .get("commit_names")
+            .and_then(|names| names.as_array())
+            .filter(|names| !names.is_empty() && names.len() == commits.len() && names.iter().all(|name| name.is_string()))
+            .cloned();
+        if let Some(names) = names {
+            commits = names
+                .into_iter()
+                .zip(commits)
+                .map(|(name, value)| serde_json::json!({ "name": name, "value": value }))
+                .collect();
+            if let Some(meta) = meta.as_object_mut() {
+                meta.remove("commit_names");
+            }
+        }
+        RunResultRepr {
+            schema_version: RunResult::SCHEMA_VERSION,
+            status,
+            elapsed_ms,
+            commits,
+            meta,
+        }
+    }
+}
+
+/// Version of results written before `schema_version` existed
+fn unversioned() -> u32 {
+    1
+}
+
+impl TryFrom<RunResultRepr> for RunResult {
+    type Error = String;
+
+    fn try_from(repr: RunResultRepr) -> Result<Self, String> {
+        let RunResultRepr { schema_version, status, elapsed_ms, mut commits, mut meta } = repr;
+        if !(unversioned()..=RunResult::SCHEMA_VERSION).contains(&schema_version) {
+            return Err(format!(
+                "run result has schema version {}, this crate reads 1 to {}",
+                schema_version,
+                RunResult::SCHEMA_VERSION
+            ));
+        }
+        let named = |commit: &serde_json::Value| {
+            commit.as_object().is_some_and(|object| {
+                object.len() == 2 && object.get("name").is_some_and(|name| name.is_string()) && object.contains_key("value")
+            })
+        };
+        if !commits.is_empty() && commits.iter().all(named) {
+            let mut names = Vec::with_capacity(commits.len());
+            for commit in &mut commits {
+                let mut object = std::mem::take(commit.as_object_mut().expect("named commits are objects"));
+                names.push(object.remove("name").expect("named commits have a name"));
+                *commit = object.remove("value").expect("named commits have a value");
+            }
+            if !meta.is_object() {
+                meta = serde_json::json!({});
+            }
+            meta["commit_names"] = serde_json::Value::Array(names);
+        }
+        Ok(RunResult { status, elapsed_ms, commits, meta })
+    }
+}
+
+/// Output field name of the commit at `index`, from either side
+fn commit_name(native: &RunResult, zkvm: &RunResult, index: usize) -> Option<String> {
+    native.commit_name(index).or_else(|| zkvm.commit_name(index)).map(str::to_string)
+}
+
+/// `index 4`, or `index 4 (field2_len)` if either side names the commit
+pub fn describe_commit_index(native: &RunResult, zkvm: &RunResult, index: usize) -> String {
+    match commit_name(native, zkvm, index) {
+        Some(name) => format!("index {} ({})", index, name),
+        None => format!("index {}", index),
+    }
+}
+
+/// Why two results are unequal, for code that branches on the category
+/// (the [`Diff::reason`] string is for people)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DiffKind {
+    /// One side failed for infrastructure reasons; nothing was compared
+    NotCompared,
+    /// The statuses are inconsistent (see [`statuses_consistent`])
+    StatusMismatch { native: Status, zkvm: Status },
+    /// Both finished, and one commit stream is a strict prefix of the other
+    CommitLengthMismatch { native_len: usize, zkvm_len: usize },
+    /// Both finished with different commits at `index`
+    CommitValueMismatch {
+        index: usize,
+        /// Output field of the commit (`meta.commit_names`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+    /// Both stopped early, and their streams disagree at `index` before
+    /// either ends
+    PrefixMismatch {
+        index: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+    /// Both panicked, with different messages (see [`panic_message`])
+    PanicMessageMismatch { native: String, zkvm: String },
+    /// Declared relations were violated or could not be checked
+    RelationViolated { relations: Vec<String> },
+}
+
+impl DiffKind {
+    /// Category name, e.g. `commit_value_mismatch`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiffKind::NotCompared => "not_compared",
+            DiffKind::StatusMismatch { .. } => "status_mismatch",
+            DiffKind::CommitLengthMismatch { .. } => "commit_length_mismatch",
+            DiffKind::CommitValueMismatch { .. } => "commit_value_mismatch",
+            DiffKind::PrefixMismatch { .. } => "prefix_mismatch",
+            DiffKind::PanicMessageMismatch { .. } => "panic_message_mismatch",
+            DiffKind::RelationViolated { .. } => "relation_violated",
+        }
+    }
+
+    /// Commit index the results diverge at, for commit mismatches
+    pub fn index(&self) -> Option<usize> {
+        match self {
+            DiffKind::CommitValueMismatch { index, .. } | DiffKind::PrefixMismatch { index, .. } => Some(*index),
+            DiffKind::CommitLengthMismatch { native_len, zkvm_len } => Some(*native_len.min(zkvm_len)),
+            _ => None,
+        }
+    }
+}
+
 /// Result of comparing two RunResults
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Diff {
     /// Whether the results are equal
     pub equal: bool,
-    /// Reason for inequality (if any)
+    /// Category of the inequality (None if equal, and in logs that predate it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<DiffKind>,
+    /// Human-readable reason for inequality (if any)
     pub reason: Option<String>,
     /// Timing delta in milliseconds (informational only)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timing_delta_ms: Option<u128>,
+    /// Number of leading commits both streams agree on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub common_prefix_len: Option<usize>,
+    /// First index where the streams differ or one of them ends early
+    /// (None if the streams are identical)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_divergent_index: Option<usize>,
+    /// Declared relations the results were judged by instead of exact
+    /// equality (empty for a plain comparison)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relations: Vec<String>,
+}
+
+/// Length of the longest common prefix of two commit streams
+pub fn common_prefix_len(a: &[serde_json::Value], b: &[serde_json::Value]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+/// Where two byte-array or string commits first differ
+///
+/// Byte arrays (`Vec<u8>` commits, decoded as arrays of numbers) and strings
+/// can be large, so mismatches between them are reported by offset and
+/// length instead of by value. None for any other pair of values.
+pub fn describe_commit_difference(native: &serde_json::Value, zkvm: &serde_json::Value) -> Option<String> {
+    fn as_bytes(value: &serde_json::Value) -> Option<Vec<u8>> {
+        value
+            .as_array()?
+            .iter()
+            .map(|byte| u8::try_from(byte.as_u64()?).ok())
+            .collect()
+    }
+
+    let (kind, a, b) = match (native, zkvm) {
+        (serde_json::Value::String(a), serde_json::Value::String(b)) => {
+            ("strings", a.as_bytes().to_vec(), b.as_bytes().to_vec())
+        }
+        _ => ("bytes", as_bytes(native)?, as_bytes(zkvm)?),
+    };
+    let offset = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    Some(format!(
+        "{} differ at byte {} (native len {}, zkvm len {})",
+        kind,
+        offset,
+        a.len(),
+        b.len()
+    ))
+}
+
+/// The message of a panicked run, normalized for comparison
+///
+/// Taken from the standard panic hook's output in `meta.stderr`, which both
+/// runners capture: `panicked at src/lib.rs:42:9:` followed by the message
+/// (Rust 1.73 and later), or `panicked at 'message', src/lib.rs:42:9`. The
+/// thread name and location are dropped, since they differ between a native
+/// thread and the guest, and whitespace is collapsed. Without hook output,
+/// the native runner's `meta.panic_msg` (the panic payload) is used; sp1's
+/// is the executor's error, not the guest's message. None if the message is
+/// unknown.
+pub fn panic_message(result: &RunResult) -> Option<String> {
+    let from_hook = |text: &str| -> Option<String> {
+        let (_, rest) = text.split_once("panicked at ")?;
+        let message = match rest.strip_prefix('\'') {
+            Some(quoted) => quoted.rsplit_once("', ")?.0.to_string(),
+            None => {
+                let (_, after) = rest.split_once('\n')?;
+                let lines: Vec<&str> = after.lines().take_while(|line| !line.starts_with("note: ")).collect();
+                lines.join("\n")
+            }
+        };
+        Some(message)
+    };
+    let text = |key: &str| result.meta.get(key).and_then(|value| value.as_str());
+    let message = match text("stderr").and_then(from_hook) {
+        Some(message) => message,
+        None if result.meta.get("runner").and_then(|runner| runner.as_str()) == Some("native") => {
+            let panic_msg = text("panic_msg")?;
+            from_hook(panic_msg).unwrap_or_else(|| panic_msg.to_string())
+        }
+        None => return None,
+    };
+    let message = message.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!message.is_empty()).then_some(message)
 }
 
 /// Compare two RunResults for equality
 ///
 /// This is the core oracle logic for A1 differential testing.
 /// It compares:
-/// 1. Status (OK/PANIC/TIMEOUT)
+/// 1. Status (see [`statuses_consistent`])
 /// 2. Commit streams (must be exactly equal if both OK)
-/// 3. Timing (recorded but not used for equality)
+/// 3. Panic messages, if both panicked and both are known (see
+///    [`panic_message`])
+/// 4. Timing (recorded but not used for equality)
+///
+/// A run that timed out or panicked may still carry a truncated commit
+/// stream (e.g., from a checkpointing core). Truncated streams are compared
+/// by prefix: the diff reports the longest common prefix and the first
+/// divergent index, and two truncated runs with the same status are only
+/// unequal if their streams disagree before one of them ends.
 pub fn compare(native: &RunResult, zkvm: &RunResult) -> Diff {
-    // 1. Compare status first
-    if native.status != zkvm.status {
-        return Diff {
-            equal: false,
-            reason: Some(format!(
-                "status mismatch: native={:?}, zkvm={:?}",
+    let prefix_len = common_prefix_len(&native.commits, &zkvm.commits);
+    let first_divergent_index = if native.commits == zkvm.commits {
+        None
+    } else {
+        Some(prefix_len)
+    };
+    let timing_delta_ms = Some(native.elapsed_ms.abs_diff(zkvm.elapsed_ms));
+    let diff = |kind: Option<DiffKind>, reason: Option<String>| Diff {
+        equal: kind.is_none(),
+        kind,
+        reason,
+        timing_delta_ms,
+        common_prefix_len: Some(prefix_len),
+        first_divergent_index,
+        relations: Vec::new(),
+    };
+
+    // 0. Infrastructure failures mean there is nothing to compare
+    if native.status.is_infra() || zkvm.status.is_infra() {
+        return diff(
+            Some(DiffKind::NotCompared),
+            Some(format!(
+                "not compared (infrastructure failure): native={:?}, zkvm={:?}",
                 native.status, zkvm.status
             )),
-            timing_delta_ms: Some(native.elapsed_ms.abs_diff(zkvm.elapsed_ms)),
+        );
+    }
+
+    // 1. Compare status first (using the status-equivalence matrix)
+    if !statuses_consistent(native.status, zkvm.status) {
+        let mut reason = format!(
+            "status mismatch: native={:?}, zkvm={:?}",
+            native.status, zkvm.status
+        );
+        if !native.commits.is_empty() || !zkvm.commits.is_empty() {
+            reason.push_str(&format!(
+                " (common prefix: {} commits, native={}, zkvm={})",
+                prefix_len,
+                native.commits.len(),
+                zkvm.commits.len()
+            ));
+        }
+        let kind = DiffKind::StatusMismatch {
+            native: native.status,
+            zkvm: zkvm.status,
         };
+        return diff(Some(kind), Some(reason));
     }
 
     // 2. If both OK, compare the commit streams exactly
     if native.status == Status::Ok && native.commits != zkvm.commits {
-        return Diff {
-            equal: false,
-            reason: Some(format!(
-                "commit stream mismatch: native={:?} vs zkvm={:?}",
-                native.commits, zkvm.commits
-            )),
-            timing_delta_ms: Some(native.elapsed_ms.abs_diff(zkvm.elapsed_ms)),
+        let detail = native
+            .commits
+            .get(prefix_len)
+            .zip(zkvm.commits.get(prefix_len))
+            .and_then(|(a, b)| describe_commit_difference(a, b));
+        let reason = match detail {
+            Some(detail) => format!(
+                "commit stream mismatch at {}: {}",
+                describe_commit_index(native, zkvm, prefix_len),
+                detail
+            ),
+            None => format!(
+                "commit stream mismatch at {}: native={:?} vs zkvm={:?}",
+                describe_commit_index(native, zkvm, prefix_len),
+                native.commits,
+                zkvm.commits
+            ),
+        };
+        let kind = if prefix_len == native.commits.len().min(zkvm.commits.len()) {
+            DiffKind::CommitLengthMismatch {
+                native_len: native.commits.len(),
+                zkvm_len: zkvm.commits.len(),
+            }
+        } else {
+            DiffKind::CommitValueMismatch {
+                index: prefix_len,
+                name: commit_name(native, zkvm, prefix_len),
+            }
         };
+        return diff(Some(kind), Some(reason));
+    }
+
+    // 3. Both truncated consistently: the shorter stream must be a prefix
+    let shorter = native.commits.len().min(zkvm.commits.len());
+    if native.status.is_truncated() && prefix_len < shorter {
+        let (a, b) = (&native.commits[prefix_len], &zkvm.commits[prefix_len]);
+        let detail = describe_commit_difference(a, b).unwrap_or_else(|| format!("native={} vs zkvm={}", a, b));
+        let kind = DiffKind::PrefixMismatch {
+            index: prefix_len,
+            name: commit_name(native, zkvm, prefix_len),
+        };
+        return diff(
+            Some(kind),
+            Some(format!(
+                "commit stream prefix mismatch at {}: {}",
+                describe_commit_index(native, zkvm, prefix_len),
+                detail
+            )),
+        );
+    }
+
+    // 4. Both panicked: the messages must agree where both are known
+    if native.status == Status::Panic && zkvm.status == Status::Panic {
+        if let (Some(native_msg), Some(zkvm_msg)) = (panic_message(native), panic_message(zkvm)) {
+            if native_msg != zkvm_msg {
+                let reason = format!("panic message mismatch: native={:?} vs zkvm={:?}", native_msg, zkvm_msg);
+                let kind = DiffKind::PanicMessageMismatch {
+                    native: native_msg,
+                    zkvm: zkvm_msg,
+                };
+                return diff(Some(kind), Some(reason));
+            }
+        }
     }
 
-    // 3. Results are equal
-    Diff {
-        equal: true,
-        reason: None,
-        timing_delta_ms: Some(native.elapsed_ms.abs_diff(zkvm.elapsed_ms)),
+    // 5. Results are equal
+    diff(None, None)
+}
+
+/// How release native, overflow-checked native and the zkVM relate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Agreement {
+    /// All three results match
+    AllAgree,
+    /// Release native and the zkVM match; overflow checks change the result
+    /// (the core silently wraps somewhere)
+    CheckedDiffers,
+    /// Checked native and the zkVM match; the zkVM behaves like debug Rust
+    ReleaseDiffers,
+    /// Both native builds match; the zkVM is the odd one out
+    ZkvmDiffers,
+    /// No two results match
+    AllDiffer,
+    /// One of the runs failed for infrastructure reasons
+    Inconclusive,
+}
+
+impl Agreement {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Agreement::AllAgree => "release native, checked native and zkVM agree",
+            Agreement::CheckedDiffers => "overflow checks change the result (release native and zkVM wrap silently)",
+            Agreement::ReleaseDiffers => "zkVM matches checked native, not release native",
+            Agreement::ZkvmDiffers => "zkVM differs from both native builds",
+            Agreement::AllDiffer => "release native, checked native and zkVM all differ",
+            Agreement::Inconclusive => "not compared (infrastructure failure)",
+        }
+    }
+}
+
+/// Three-way comparison of release native, checked native and zkVM
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SemanticsReport {
+    pub agreement: Agreement,
+    pub release_vs_checked: Diff,
+    pub checked_vs_zkvm: Diff,
+}
+
+/// Compare a core's release-native, overflow-checked-native and zkVM results
+///
+/// The release-native vs zkVM diff is the usual [`compare`]; the report adds
+/// the two diffs involving the checked build and classifies which side is
+/// the odd one out.
+pub fn compare_semantics(release: &RunResult, checked: &RunResult, zkvm: &RunResult) -> SemanticsReport {
+    let release_vs_checked = compare(release, checked);
+    let release_vs_zkvm = compare(release, zkvm);
+    let checked_vs_zkvm = compare(checked, zkvm);
+
+    let agreement = if [release, checked, zkvm].iter().any(|result| result.status.is_infra()) {
+        Agreement::Inconclusive
+    } else {
+        match (release_vs_checked.equal, release_vs_zkvm.equal, checked_vs_zkvm.equal) {
+            (true, true, _) => Agreement::AllAgree,
+            (false, true, _) => Agreement::CheckedDiffers,
+            (_, false, true) => Agreement::ReleaseDiffers,
+            (true, false, false) => Agreement::ZkvmDiffers,
+            (false, false, false) => Agreement::AllDiffer,
+        }
+    };
+
+    SemanticsReport {
+        agreement,
+        release_vs_checked,
+        checked_vs_zkvm,
     }
 }
 
@@ -125,6 +616,62 @@ mod tests {
         assert!(diff.reason.unwrap().contains("status mismatch"));
     }
 
+    #[test]
+    fn test_panic_message() {
+        let result = |meta| RunResult {
+            status: Status::Panic,
+            elapsed_ms: 0,
+            commits: vec![],
+            meta,
+        };
+        let native = result(json!({
+            "runner": "native",
+            "panic_msg": "index out of bounds",
+            "stderr": "thread 'main' panicked at src/lib.rs:7:5:\nindex out of bounds:  the len is 3\nnote: run with `RUST_BACKTRACE=1`\n",
+        }));
+        assert_eq!(panic_message(&native).as_deref(), Some("index out of bounds: the len is 3"));
+        let old = result(json!({ "stderr": "thread '<unnamed>' panicked at 'boom, twice', /work/src/lib.rs:7:5" }));
+        assert_eq!(panic_message(&old).as_deref(), Some("boom, twice"));
+
+        // Without hook output only the native payload is a guest message
+        let native = result(json!({ "runner": "native", "panic_msg": "boom" }));
+        assert_eq!(panic_message(&native).as_deref(), Some("boom"));
+        let sp1 = result(json!({ "runner": "sp1", "panic_msg": "HaltWithNonZeroExitCode(1)" }));
+        assert_eq!(panic_message(&sp1), None);
+    }
+
+    #[test]
+    fn test_compare_panic_messages() {
+        let result = |stderr: &str| RunResult {
+            status: Status::Panic,
+            elapsed_ms: 0,
+            commits: vec![],
+            meta: json!({ "stderr": stderr }),
+        };
+        let native = result("thread 'main' panicked at src/lib.rs:7:5:\nattempt to add with overflow\n");
+        let same = result("thread '<unnamed>' panicked at /guest/src/lib.rs:7:5:\nattempt to add with overflow");
+        assert!(compare(&native, &same).equal);
+
+        let other = result("panicked at src/lib.rs:9:5:\nattempt to divide by zero");
+        let diff = compare(&native, &other);
+        assert!(!diff.equal);
+        assert_eq!(
+            diff.kind,
+            Some(DiffKind::PanicMessageMismatch {
+                native: "attempt to add with overflow".to_string(),
+                zkvm: "attempt to divide by zero".to_string(),
+            })
+        );
+        assert!(diff.reason.unwrap().starts_with("panic message mismatch"));
+
+        // An unknown message on either side is not a mismatch
+        let unknown = RunResult {
+            meta: json!({ "panic_msg": "HaltWithNonZeroExitCode(1)" }),
+            ..other
+        };
+        assert!(compare(&native, &unknown).equal);
+    }
+
     #[test]
     fn test_compare_commit_mismatch() {
         let native = RunResult {
@@ -144,5 +691,305 @@ mod tests {
         assert!(!diff.equal);
         assert!(diff.reason.unwrap().contains("commit stream mismatch"));
     }
-}
 
+    #[test]
+    fn test_attach_output() {
+        let mut result = RunResult {
+            status: Status::Panic,
+            elapsed_ms: 0,
+            commits: vec![],
+            meta: json!({ "runner": "native" }),
+        };
+        let long = "é".repeat(RunResult::MAX_CAPTURE_BYTES);
+        result.attach_output(b"", long.as_bytes());
+        assert!(result.meta.get("stdout").is_none());
+        let stderr = result.meta["stderr"].as_str().unwrap();
+        // Cut back to the last whole character
+        assert_eq!(stderr.len(), RunResult::MAX_CAPTURE_BYTES);
+        assert!(stderr.chars().all(|c| c == 'é'));
+
+        result.attach_output(b"hi \xff", b"");
+        assert_eq!(result.meta["stdout"], "hi \u{fffd}");
+    }
+
+    #[test]
+    fn test_schema_version() {
+        let result = RunResult {
+            status: Status::Ok,
+            elapsed_ms: 1,
+            commits: vec![json!(1)],
+            meta: json!({}),
+        };
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["schema_version"], RunResult::SCHEMA_VERSION);
+
+        let unversioned: RunResult = serde_json::from_value(json!({ "status": "OK", "elapsed_ms": 1, "commits": [1] })).unwrap();
+        assert_eq!(unversioned.commits, result.commits);
+
+        let mut newer = json;
+        newer["schema_version"] = json!(RunResult::SCHEMA_VERSION + 1);
+        let error = serde_json::from_value::<RunResult>(newer).unwrap_err().to_string();
+        assert!(error.contains("schema version 3"), "{}", error);
+    }
+
+    #[test]
+    fn test_named_commits() {
+        let native = RunResult {
+            status: Status::Ok,
+            elapsed_ms: 10,
+            commits: vec![json!(24), json!(46368), json!(75025)],
+            meta: json!({ "runner": "native", "commit_names": ["n", "a", "b"] }),
+        };
+        let json = serde_json::to_value(&native).unwrap();
+        assert_eq!(json["commits"][2], json!({ "name": "b", "value": 75025 }));
+        assert_eq!(json["meta"], json!({ "runner": "native" }));
+
+        let parsed: RunResult = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.commits, native.commits);
+        assert_eq!(parsed.commit_name(1), Some("a"));
+
+        // Names that do not cover every commit stay in meta
+        let mut partial = native.clone();
+        partial.commits.push(json!(0));
+        let json = serde_json::to_value(&partial).unwrap();
+        assert_eq!(json["commits"][0], json!(24));
+        assert_eq!(json["meta"]["commit_names"], json!(["n", "a", "b"]));
+
+        let zkvm = RunResult {
+            commits: vec![json!(24), json!(46368), json!(75026)],
+            meta: json!({}),
+            ..native.clone()
+        };
+        let diff = compare(&native, &zkvm);
+        let reason = diff.reason.unwrap();
+        assert!(reason.starts_with("commit stream mismatch at index 2 (b):"), "{}", reason);
+        assert_eq!(
+            diff.kind,
+            Some(DiffKind::CommitValueMismatch {
+                index: 2,
+                name: Some("b".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn test_compare_bytes_and_strings() {
+        let run = |commits: Vec<serde_json::Value>| RunResult {
+            status: Status::Ok,
+            elapsed_ms: 1,
+            commits,
+            meta: json!({}),
+        };
+
+        let diff = compare(&run(vec![json!(3), json!([1, 2, 3])]), &run(vec![json!(3), json!([1, 2, 4, 5])]));
+        assert!(!diff.equal);
+        assert_eq!(
+            diff.reason.as_deref(),
+            Some("commit stream mismatch at index 1: bytes differ at byte 2 (native len 3, zkvm len 4)")
+        );
+
+        let diff = compare(&run(vec![json!("héllo")]), &run(vec![json!("hello")]));
+        assert_eq!(
+            diff.reason.as_deref(),
+            Some("commit stream mismatch at index 0: strings differ at byte 1 (native len 6, zkvm len 5)")
+        );
+
+        assert!(compare(&run(vec![json!([]), json!("")]), &run(vec![json!([]), json!("")])).equal);
+        // Numbers and arrays of non-bytes keep the full-stream reason
+        assert_eq!(describe_commit_difference(&json!(1), &json!(2)), None);
+        assert_eq!(describe_commit_difference(&json!([256]), &json!([1])), None);
+    }
+
+    #[test]
+    fn test_compare_truncated_prefix() {
+        let native = RunResult {
+            status: Status::Ok,
+            elapsed_ms: 10,
+            commits: vec![json!(1), json!(2), json!(3), json!(4)],
+            meta: json!({}),
+        };
+        let zkvm = RunResult {
+            status: Status::Timeout,
+            elapsed_ms: 30000,
+            commits: vec![json!(1), json!(2)],
+            meta: json!({}),
+        };
+
+        let diff = compare(&native, &zkvm);
+        assert!(!diff.equal);
+        assert_eq!(diff.common_prefix_len, Some(2));
+        assert_eq!(diff.first_divergent_index, Some(2));
+        assert!(diff.reason.unwrap().contains("common prefix: 2 commits"));
+    }
+
+    #[test]
+    fn test_compare_both_truncated() {
+        let native = RunResult {
+            status: Status::Timeout,
+            elapsed_ms: 30000,
+            commits: vec![json!(1), json!(2), json!(3)],
+            meta: json!({}),
+        };
+        let consistent = RunResult {
+            status: Status::Timeout,
+            elapsed_ms: 30000,
+            commits: vec![json!(1)],
+            meta: json!({}),
+        };
+        let conflicting = RunResult {
+            status: Status::Timeout,
+            elapsed_ms: 30000,
+            commits: vec![json!(1), json!(9)],
+            meta: json!({}),
+        };
+
+        let diff = compare(&native, &consistent);
+        assert!(diff.equal);
+        assert_eq!(diff.first_divergent_index, Some(1));
+
+        let diff = compare(&native, &conflicting);
+        assert!(!diff.equal);
+        assert_eq!(diff.first_divergent_index, Some(1));
+        assert!(diff.reason.unwrap().contains("prefix mismatch at index 1"));
+    }
+
+    #[test]
+    fn test_status_serde_names() {
+        assert_eq!(serde_json::to_value(Status::CycleLimit).unwrap(), json!("CYCLE_LIMIT"));
+        assert_eq!(serde_json::to_value(Status::Oom).unwrap(), json!("OOM"));
+        assert_eq!(serde_json::to_value(Status::BuildFailure).unwrap(), json!("BUILD_FAILURE"));
+        assert_eq!(serde_json::to_value(Status::InfraError).unwrap(), json!("INFRA_ERROR"));
+        let status: Status = serde_json::from_value(json!("TIMEOUT")).unwrap();
+        assert_eq!(status, Status::Timeout);
+    }
+
+    #[test]
+    fn test_timeout_consistent_with_cycle_limit() {
+        let native = RunResult {
+            status: Status::Timeout,
+            elapsed_ms: 30000,
+            commits: vec![json!(1), json!(2)],
+            meta: json!({}),
+        };
+        let zkvm = RunResult {
+            status: Status::CycleLimit,
+            elapsed_ms: 800,
+            commits: vec![],
+            meta: json!({}),
+        };
+
+        assert!(compare(&native, &zkvm).equal);
+        assert!(!statuses_consistent(Status::Panic, Status::Oom));
+    }
+
+    #[test]
+    fn test_infra_failures_are_never_equal() {
+        let native = RunResult {
+            status: Status::Ok,
+            elapsed_ms: 10,
+            commits: vec![json!(24)],
+            meta: json!({}),
+        };
+        let zkvm = RunResult {
+            status: Status::BuildFailure,
+            elapsed_ms: 0,
+            commits: vec![],
+            meta: json!({}),
+        };
+
+        let diff = compare(&native, &zkvm);
+        assert!(!diff.equal);
+        assert!(diff.reason.unwrap().contains("infrastructure failure"));
+        assert!(!statuses_consistent(Status::InfraError, Status::InfraError));
+    }
+
+    #[test]
+    fn test_compare_semantics() {
+        let wrapped = RunResult {
+            status: Status::Ok,
+            elapsed_ms: 1,
+            commits: vec![json!(0)],
+            meta: json!({}),
+        };
+        let overflowed = RunResult {
+            status: Status::Panic,
+            elapsed_ms: 1,
+            commits: vec![],
+            meta: json!({ "panic_msg": "attempt to add with overflow" }),
+        };
+
+        let report = compare_semantics(&wrapped, &overflowed, &wrapped);
+        assert_eq!(report.agreement, Agreement::CheckedDiffers);
+        assert!(!report.release_vs_checked.equal);
+        assert_eq!(compare_semantics(&wrapped, &overflowed, &overflowed).agreement, Agreement::ReleaseDiffers);
+        assert_eq!(compare_semantics(&wrapped, &wrapped, &overflowed).agreement, Agreement::ZkvmDiffers);
+        assert_eq!(compare_semantics(&wrapped, &wrapped, &wrapped).agreement, Agreement::AllAgree);
+
+        let mut infra = wrapped.clone();
+        infra.status = Status::InfraError;
+        assert_eq!(compare_semantics(&wrapped, &infra, &wrapped).agreement, Agreement::Inconclusive);
+    }
+
+    /// Check one `testdata/<case>/` directory; Err describes every mismatch
+    fn check_corpus_case(dir: &std::path::Path) -> Result<(), String> {
+        let read = |name: &str| -> Result<serde_json::Value, String> {
+            let path = dir.join(name);
+            let text = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))
+        };
+        let result = |name: &str| -> Result<RunResult, String> {
+            serde_json::from_value(read(name)?).map_err(|e| format!("{}: {}", name, e))
+        };
+        let diff = compare(&result("native.json")?, &result("zkvm.json")?);
+        let actual = serde_json::to_value(&diff).unwrap();
+        let expected = read("expected.json")?;
+        let expected = expected.as_object().ok_or("expected.json: not an object")?;
+
+        let mut mismatches = Vec::new();
+        for (key, want) in expected {
+            if key == "reason_contains" {
+                let needle = want.as_str().ok_or("reason_contains: not a string")?;
+                if !diff.reason.as_deref().unwrap_or_default().contains(needle) {
+                    mismatches.push(format!("reason {:?} does not contain {:?}", diff.reason, needle));
+                }
+                continue;
+            }
+            // Fields the diff omits (skipped when None) compare as null
+            let got = actual.get(key).unwrap_or(&serde_json::Value::Null);
+            if !["equal", "kind", "reason", "timing_delta_ms", "common_prefix_len", "first_divergent_index"]
+                .contains(&key.as_str())
+            {
+                mismatches.push(format!("unknown expected field '{}'", key));
+            } else if got != want {
+                mismatches.push(format!("{}: expected {}, got {}", key, want, got));
+            }
+        }
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches.join("; "))
+        }
+    }
+
+    /// Data-driven cases from `testdata/` (see `testdata/README.md`)
+    #[test]
+    fn test_corpus() {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata");
+        let mut cases: Vec<_> = std::fs::read_dir(&root)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.is_dir())
+            .collect();
+        cases.sort();
+        assert!(!cases.is_empty(), "no cases in {}", root.display());
+
+        let failures: Vec<String> = cases
+            .iter()
+            .filter_map(|dir| {
+                let name = dir.file_name().unwrap().to_string_lossy();
+                check_corpus_case(dir).err().map(|e| format!("{}: {}", name, e))
+            })
+            .collect();
+        assert!(failures.is_empty(), "{} of {} corpus cases failed:\n{}", failures.len(), cases.len(), failures.join("\n"));
+    }
+}
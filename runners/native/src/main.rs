@@ -1,11 +1,17 @@
+mod sandbox;
+
 use anyhow::{Context, Result};
 use clap::Parser;
+use commit_codec::{CommitValue, Commits};
+use input_preprocess::Preprocess;
 use rust_eq_oracle::{RunResult, Status};
 use std::any::Any;
 use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::panic;
-use std::path::PathBuf;
-use std::sync::mpsc;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
@@ -17,7 +23,8 @@ struct Args {
     #[arg(short, long)]
     core: String,
 
-    /// Path to the input JSON file
+    /// Path to the input JSON file (with --json-lines: a directory of input
+    /// files, or "-" to read input paths from stdin)
     #[arg(short, long)]
     input: PathBuf,
 
@@ -28,22 +35,84 @@ struct Args {
     /// Timeout in seconds (0 = no timeout)
     #[arg(long, default_value = "30")]
     timeout: u64,
+
+    /// Run the core with a cleared environment, fixed locale/TZ and a scratch temp dir as cwd (not a chroot)
+    #[arg(long)]
+    sandbox: bool,
+
+    /// Internal: run the core in this process and stream results to the parent
+    #[arg(long, hide = true)]
+    worker: bool,
+
+    /// Only check that each line of the input (JSON Lines) deserializes into
+    /// the core's input type; prints one error or null per line
+    #[arg(long)]
+    check_inputs: bool,
+
+    /// Expand compact inputs before deserializing them, e.g. "bytes:data"
+    /// (the core's `preprocess` in its manifest)
+    #[arg(long)]
+    preprocess: Option<String>,
+
+    /// Run a batch of inputs, printing one RunResult JSON per line as each
+    /// finishes
+    #[arg(long, conflicts_with_all = ["worker", "check_inputs"])]
+    json_lines: bool,
+
+    /// Cap the core's heap at this many bytes (RLIMIT_DATA of the worker;
+    /// allocations past it fail, reported as OOM)
+    #[arg(long)]
+    memory_limit: Option<u64>,
 }
 
+/// Worker stdout line prefix for a commit streamed before the core finishes
+const PARTIAL_PREFIX: &str = "partial ";
+/// Worker stdout line prefix for the final RunResult
+const RESULT_PREFIX: &str = "result ";
+
+/// How often the parent checks whether the worker has exited
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
 fn main() -> Result<()> {
     let args = Args::parse();
+    let preprocess = Preprocess::parse(args.preprocess.as_deref().unwrap_or_default())
+        .context("Invalid --preprocess")?;
+
+    if args.json_lines {
+        return run_batch(&args);
+    }
 
     // Read input bytes
     let input_bytes = fs::read(&args.input).context("Failed to read input file")?;
+    // Sidecar files sit next to the input
+    let input_dir = args.input.parent().unwrap_or(Path::new(""));
 
-    // Run the core with panic capture and timeout
-    let timeout_duration = if args.timeout > 0 {
-        Some(Duration::from_secs(args.timeout))
-    } else {
-        None
-    };
+    if args.worker {
+        if let Some(limit) = args.memory_limit {
+            set_memory_limit(limit)?;
+        }
+        return run_worker(&args.core, &input_bytes, &preprocess, input_dir);
+    }
 
-    let result = run_core_with_safeguards(&args.core, input_bytes, timeout_duration)?;
+    if args.check_inputs {
+        let errors = input_bytes
+            .split(|&b| b == b'\n')
+            .filter(|line| !line.iter().all(u8::is_ascii_whitespace))
+            .map(|line| match preprocess.apply(line, input_dir) {
+                Ok(line) => check_input_dispatch(&args.core, &line),
+                Err(e) => Ok(Some(format!("{:#}", e))),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let errors_json = serde_json::to_string(&errors)?;
+        match args.output {
+            Some(output_path) => fs::write(output_path, errors_json)?,
+            None => println!("{}", errors_json),
+        }
+        return Ok(());
+    }
+
+    // Run the core in a child process with panic capture and timeout
+    let result = run_core_in_child(&args, &args.input)?;
 
     // Serialize and output
     let result_json = serde_json::to_string_pretty(&result)?;
@@ -57,151 +126,474 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-/// Run a core with panic capture and timeout handling
-fn run_core_with_safeguards(
-    core_name: &str,
-    input_bytes: Vec<u8>,
-    timeout: Option<Duration>,
-) -> Result<RunResult> {
-    let (tx, rx) = mpsc::channel();
-    let core_name = core_name.to_string();
-
-    // Spawn thread to run core
-    let handle = thread::spawn(move || {
-        // Capture panics
-        let panic_result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
-            let start = Instant::now();
-            let commits = run_core_dispatch(&core_name, &input_bytes)?;
-            let elapsed = start.elapsed();
-            
-            Ok::<_, anyhow::Error>(RunResult {
-                status: Status::Ok,
-                elapsed_ms: elapsed.as_millis(),
-                commits,
-                meta: serde_json::json!({"runner": "native"}),
-            })
-        }));
-
-        match panic_result {
-            Ok(Ok(result)) => tx.send(Ok(result)),
-            Ok(Err(e)) => tx.send(Err(e)),
-            Err(panic_err) => {
-                let panic_msg = extract_panic_message(&panic_err);
-                tx.send(Ok(RunResult {
-                    status: Status::Panic,
-                    elapsed_ms: 0,
-                    commits: vec![],
-                    meta: serde_json::json!({
-                        "runner": "native",
-                        "panic_msg": panic_msg,
-                    }),
-                }))
+/// `--timeout` as a duration (None for 0, i.e. no timeout)
+fn timeout_of(secs: u64) -> Option<Duration> {
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// `--json-lines`: run every input of a batch, one RunResult line each
+///
+/// Inputs are the `*.json` files of a directory, in name order, or with
+/// `--input -` the paths read from stdin, one per line. Each input still
+/// runs in its own worker, so the batch only saves the runner's startup.
+/// An input that cannot be run gives an INFRA_ERROR line instead of ending
+/// the batch. Every line carries its input path in `meta.input`.
+fn run_batch(args: &Args) -> Result<()> {
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(fs::File::create(path).with_context(|| format!("Failed to create {}", path.display()))?),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    let mut run = |input_path: &Path| -> Result<()> {
+        let mut result = run_core_in_child(args, input_path).unwrap_or_else(|e| RunResult {
+                status: Status::InfraError,
+                elapsed_ms: 0,
+                commits: vec![],
+                meta: serde_json::json!({
+                    "runner": "native",
+                    "error": format!("{:#}", e),
+                }),
+            });
+        if let Some(meta) = result.meta.as_object_mut() {
+            meta.insert("input".to_string(), serde_json::Value::from(input_path.display().to_string()));
+        }
+        writeln!(out, "{}", serde_json::to_string(&result)?)?;
+        out.flush()?;
+        Ok(())
+    };
+
+    if args.input == Path::new("-") {
+        for line in std::io::stdin().lock().lines() {
+            let line = line.context("Failed to read input paths from stdin")?;
+            if !line.trim().is_empty() {
+                run(Path::new(line.trim()))?;
             }
         }
+    } else {
+        let mut inputs = fs::read_dir(&args.input)
+            .with_context(|| format!("Failed to read input directory {}", args.input.display()))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        inputs.retain(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"));
+        inputs.sort();
+        for input_path in &inputs {
+            run(input_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Run a core in a child process (this binary with `--worker`)
+///
+/// A timed-out core is killed along with its process, so a runaway loop
+/// stops burning CPU immediately instead of skewing the timing of later runs.
+/// Commits a streaming core emits before finishing are forwarded line by
+/// line, so a timeout, panic or abort still reports how far the run got.
+fn run_core_in_child(args: &Args, input_path: &Path) -> Result<RunResult> {
+    let timeout = timeout_of(args.timeout);
+    let exe = std::env::current_exe().context("Failed to locate native-runner executable")?;
+    // Absolute, so the input still resolves from a sandbox cwd
+    let input_path = fs::canonicalize(input_path).context("Failed to resolve input path")?;
+
+    let mut cmd = Command::new(exe);
+    cmd.args(["--worker", "--timeout", "0", "--core", &args.core])
+        .arg("--input")
+        .arg(&input_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(spec) = &args.preprocess {
+        cmd.args(["--preprocess", spec]);
+    }
+    if let Some(limit) = args.memory_limit {
+        cmd.args(["--memory-limit", &limit.to_string()]);
+    }
+    let sandbox = if args.sandbox {
+        let sandbox = sandbox::Sandbox::create()?;
+        sandbox.apply(&mut cmd);
+        Some(sandbox)
+    } else {
+        None
+    };
+    let mut child = cmd.spawn().context("Failed to spawn native-runner worker")?;
+
+    // Collect the worker's streamed output on reader threads so full pipes
+    // never block it
+    let partial_commits = Arc::new(Mutex::new(Vec::new()));
+    let stdout = child.stdout.take().context("Worker stdout not captured")?;
+    let reader_partial_commits = Arc::clone(&partial_commits);
+    let stdout_reader = thread::spawn(move || -> (Option<String>, String) {
+        let mut result_line = None;
+        // Anything else on stdout was printed by the core itself
+        let mut printed = String::new();
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if let Some(commit) = line.strip_prefix(PARTIAL_PREFIX) {
+                if let Ok(commit) = serde_json::from_str(commit) {
+                    lock_commits(&reader_partial_commits).push(commit);
+                }
+            } else if let Some(result) = line.strip_prefix(RESULT_PREFIX) {
+                result_line = Some(result.to_string());
+            } else {
+                printed.push_str(&line);
+                printed.push('\n');
+            }
+        }
+        (result_line, printed)
+    });
+    let mut stderr = child.stderr.take().context("Worker stderr not captured")?;
+    let stderr_reader = thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = stderr.read_to_string(&mut buf);
+        buf
     });
 
     // Wait with timeout
-    let result = if let Some(timeout_duration) = timeout {
-        match rx.recv_timeout(timeout_duration) {
-            Ok(result) => result,
-            Err(mpsc::RecvTimeoutError::Timeout) => {
-                // Thread is still running, mark as timeout
-                Ok(RunResult {
-                    status: Status::Timeout,
-                    elapsed_ms: timeout_duration.as_millis(),
-                    commits: vec![],
-                    meta: serde_json::json!({
-                        "runner": "native",
-                        "timeout_secs": timeout_duration.as_secs(),
-                    }),
-                })
-            }
-            Err(mpsc::RecvTimeoutError::Disconnected) => {
-                anyhow::bail!("Runner thread disconnected unexpectedly")
+    let start = Instant::now();
+    let exit_status = loop {
+        if let Some(status) = child.try_wait().context("Failed to wait for worker")? {
+            break Some(status);
+        }
+        if timeout.is_some_and(|t| start.elapsed() >= t) {
+            break None;
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    if exit_status.is_none() {
+        // Still running: kill it so it stops using the CPU
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+    let (result_line, stdout) = stdout_reader.join().unwrap_or_default();
+    let stderr = stderr_reader.join().unwrap_or_default();
+    let commits = lock_commits(&partial_commits).clone();
+
+    let mut result = match (exit_status, result_line) {
+        (None, _) => {
+            let timeout_duration = timeout.expect("only reachable with a timeout");
+            RunResult {
+                status: Status::Timeout,
+                elapsed_ms: timeout_duration.as_millis(),
+                commits,
+                meta: serde_json::json!({
+                    "runner": "native",
+                    "timeout_secs": timeout_duration.as_secs(),
+                }),
             }
         }
-    } else {
-        // No timeout
-        rx.recv().context("Runner thread disconnected")?
+        (Some(_), Some(line)) => serde_json::from_str(&line).context("Failed to parse worker result")?,
+        // No result: the worker died without unwinding. A panic during
+        // unwinding or a failed allocation aborts the process; report it as
+        // OOM or a panic (zkVM guests abort on every panic, so this is the
+        // comparable outcome).
+        (Some(exit_status), None) => RunResult {
+            status: if stderr.contains("memory allocation of") {
+                Status::Oom
+            } else {
+                Status::Panic
+            },
+            elapsed_ms: start.elapsed().as_millis(),
+            commits,
+            meta: serde_json::json!({
+                "runner": "native",
+                "aborted": true,
+                "exit_status": exit_status.to_string(),
+                "panic_msg": stderr.trim(),
+            }),
+        },
+    };
+
+    // Keep whatever the core printed (including panic messages) for triage
+    result.attach_output(stdout.as_bytes(), stderr.as_bytes());
+
+    if let (Some(sandbox), Some(meta)) = (&sandbox, result.meta.as_object_mut()) {
+        meta.insert("sandbox".to_string(), sandbox.to_json());
+    }
+    if let (Some(limit), Some(meta)) = (args.memory_limit, result.meta.as_object_mut()) {
+        meta.insert("memory_limit".to_string(), limit.into());
+    }
+
+    Ok(result)
+}
+
+/// Limit this process's data segment, which holds the heap (worker side)
+#[cfg(unix)]
+fn set_memory_limit(bytes: u64) -> Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: bytes as libc::rlim_t,
+        rlim_max: bytes as libc::rlim_t,
+    };
+    // SAFETY: `limit` is a valid rlimit for the duration of the call
+    if unsafe { libc::setrlimit(libc::RLIMIT_DATA, &limit) } != 0 {
+        anyhow::bail!("Failed to set --memory-limit: {}", std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_memory_limit(_bytes: u64) -> Result<()> {
+    anyhow::bail!("--memory-limit is only supported on Unix")
+}
+
+/// Worker side: run the core in-process with panic capture
+///
+/// Prints each streamed commit and then the final RunResult to stdout, one
+/// prefixed JSON line each, for `run_core_in_child` to collect.
+fn run_worker(core_name: &str, input_bytes: &[u8], preprocess: &Preprocess, input_dir: &Path) -> Result<()> {
+    let partial_commits = PartialCommits::default();
+
+    // Capture panics
+    let start_monotonic_ns = monotonic_now_ns();
+    let start = Instant::now();
+    let panic_result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        let input_bytes = preprocess.apply(input_bytes, input_dir)?;
+        run_core_dispatch(core_name, &input_bytes, &partial_commits)
+    }));
+    let elapsed = start.elapsed();
+
+    let result = match panic_result {
+        Ok(Ok(commits)) => RunResult {
+            status: Status::Ok,
+            elapsed_ms: elapsed.as_millis(),
+            meta: serde_json::json!({
+                "runner": "native",
+                "start_monotonic_ns": start_monotonic_ns,
+                "public_values_hex": commit_codec::encode(&commits)
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<String>(),
+            }),
+            commits: commits_json(&commits),
+        },
+        // Unknown core or malformed input: the run says nothing about the core
+        Ok(Err(e)) => RunResult {
+            status: Status::InfraError,
+            elapsed_ms: 0,
+            commits: vec![],
+            meta: serde_json::json!({
+                "runner": "native",
+                "error": format!("{:#}", e),
+            }),
+        },
+        // Elapsed is the time until the panic was caught
+        Err(panic_err) => RunResult {
+            status: Status::Panic,
+            elapsed_ms: elapsed.as_millis(),
+            commits: commits_json(&partial_commits.snapshot()),
+            meta: serde_json::json!({
+                "runner": "native",
+                "start_monotonic_ns": start_monotonic_ns,
+                "panic_msg": extract_panic_message(&panic_err),
+            }),
+        },
     };
 
-    // Clean up thread
-    let _ = handle.join();
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{}{}", RESULT_PREFIX, serde_json::to_string(&result)?)?;
+    stdout.flush()?;
+
+    Ok(())
+}
+
+/// Commits as the JSON values a RunResult records
+///
+/// The SP1 runner decodes the same JSON from the public values bytes.
+fn commits_json(commits: &[CommitValue]) -> Vec<serde_json::Value> {
+    commits
+        .iter()
+        .map(|commit| serde_json::to_value(commit).expect("commit values serialize to JSON"))
+        .collect()
+}
+
+/// Current CLOCK_MONOTONIC reading in nanoseconds (None where unavailable)
+///
+/// Comparable across processes on the same boot, unlike `Instant`.
+fn monotonic_now_ns() -> Option<u64> {
+    #[cfg(unix)]
+    {
+        let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+        // SAFETY: `ts` is a valid, writable timespec
+        if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) } == 0 {
+            return Some(ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64);
+        }
+    }
+    None
+}
 
-    result
+/// Commits a streaming core has emitted so far
+///
+/// Each one is forwarded to the parent as soon as it is pushed.
+#[derive(Default)]
+struct PartialCommits {
+    commits: Mutex<Vec<CommitValue>>,
+}
+
+impl PartialCommits {
+    fn push(&self, commit: CommitValue) {
+        let mut stdout = std::io::stdout().lock();
+        let _ = writeln!(stdout, "{}{}", PARTIAL_PREFIX, serde_json::json!(commit));
+        let _ = stdout.flush();
+        lock_commits(&self.commits).push(commit);
+    }
+
+    fn snapshot(&self) -> Vec<CommitValue> {
+        lock_commits(&self.commits).clone()
+    }
+}
+
+/// Lock a commit list, ignoring poisoning (a panicking core may hold it)
+fn lock_commits<T>(commits: &Mutex<Vec<T>>) -> std::sync::MutexGuard<'_, Vec<T>> {
+    commits.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
 }
 
 /// Dispatch to the appropriate core based on name
-fn run_core_dispatch(core_name: &str, input_bytes: &[u8]) -> Result<Vec<serde_json::Value>> {
+///
+/// Streaming cores also push each commit to `partial_commits` as soon as it
+/// is produced; all other cores only return their commits at the end.
+fn run_core_dispatch(
+    core_name: &str,
+    input_bytes: &[u8],
+    partial_commits: &PartialCommits,
+) -> Result<Vec<CommitValue>> {
     match core_name {
         "fib" => {
             let input: fib_core::FibInput = serde_json::from_slice(input_bytes)?;
-            let output = fib_core::run(input);
-            Ok(vec![
-                serde_json::to_value(&output.n)?,
-                serde_json::to_value(&output.a)?,
-                serde_json::to_value(&output.b)?,
-            ])
+            Ok(fib_core::run(input).commits())
         }
         "panic_test" => {
             let input: panic_test_core::PanicInput = serde_json::from_slice(input_bytes)?;
-            let output = panic_test_core::run(input);
-            Ok(vec![
-                serde_json::to_value(&output.should_panic_u32)?,
-                serde_json::to_value(&output.status_code)?,
-            ])
+            Ok(panic_test_core::run(input).commits())
         }
         "timeout_test" => {
             let input: timeout_test_core::TimeoutInput = serde_json::from_slice(input_bytes)?;
-            let output = timeout_test_core::run(input);
-            Ok(vec![
-                serde_json::to_value(&output.completed)?,
-            ])
+            Ok(timeout_test_core::run(input).commits())
         }
         "io_echo" => {
             let input: io_echo_core::IoEchoInput = serde_json::from_slice(input_bytes)?;
-            let output = io_echo_core::run(input);
-            // Encode Option<u8> as u32: 0 for None, 1+value for Some
-            let first_byte_u32 = match output.first_byte {
-                None => 0u32,
-                Some(byte) => 1u32 + byte as u32,
-            };
-            let last_byte_u32 = match output.last_byte {
-                None => 0u32,
-                Some(byte) => 1u32 + byte as u32,
-            };
-            Ok(vec![
-                serde_json::to_value(&output.length)?,
-                serde_json::to_value(&first_byte_u32)?,
-                serde_json::to_value(&last_byte_u32)?,
-            ])
+            Ok(io_echo_core::run(input).commits())
         }
         "arithmetic" => {
             let input: arithmetic_core::ArithmeticInput = serde_json::from_slice(input_bytes)?;
-            let output = arithmetic_core::run(input);
-            // Encode bool as u32: 0 for false, 1 for true
-            let overflowed_u32 = if output.overflowed { 1u32 } else { 0u32 };
-            Ok(vec![
-                serde_json::to_value(&output.result)?,
-                serde_json::to_value(&overflowed_u32)?,
-            ])
+            Ok(arithmetic_core::run(input).commits())
         }
         "simple_struct" => {
             let input: simple_struct_core::SimpleStructInput = serde_json::from_slice(input_bytes)?;
-            let output = simple_struct_core::run(input);
-            // Encode bool as u32: 0 for false, 1 for true
-            let field3_u32 = if output.field3_echo { 1u32 } else { 0u32 };
-            Ok(vec![
-                serde_json::to_value(&output.field1_echo)?,
-                serde_json::to_value(&output.field2_len)?,
-                serde_json::to_value(&output.field2_chars)?,
-                serde_json::to_value(&field3_u32)?,
-            ])
+            Ok(simple_struct_core::run(input).commits())
+        }
+        "parse_roundtrip" => {
+            let input: parse_roundtrip_core::ParseRoundtripInput = serde_json::from_slice(input_bytes)?;
+            Ok(parse_roundtrip_core::run(input).commits())
+        }
+        "nondeterminism_probe" => {
+            let input: nondeterminism_probe_core::NondeterminismProbeInput = serde_json::from_slice(input_bytes)?;
+            Ok(nondeterminism_probe_core::run(input).commits())
+        }
+        "env_probe" => {
+            let input: env_probe_core::EnvProbeInput = serde_json::from_slice(input_bytes)?;
+            Ok(env_probe_core::run(input).commits())
+        }
+        "panic_drop" => {
+            let input: panic_drop_core::PanicDropInput = serde_json::from_slice(input_bytes)?;
+            Ok(panic_drop_core::run(input).commits())
+        }
+        "oom_probe" => {
+            let input: oom_probe_core::OomProbeInput = serde_json::from_slice(input_bytes)?;
+            Ok(oom_probe_core::run(input).commits())
+        }
+        "checkpoint_loop" => {
+            let input: checkpoint_loop_core::CheckpointLoopInput = serde_json::from_slice(input_bytes)?;
+            // Streaming core: checkpoints are committed as they are reached
+            let final_checksum = checkpoint_loop_core::run_with_checkpoints(input, |checksum| {
+                partial_commits.push(CommitValue::from(checksum));
+            });
+            let mut commits = partial_commits.snapshot();
+            commits.push(CommitValue::from(final_checksum));
+            Ok(commits)
+        }
+        "enum_shapes" => {
+            let input: enum_shapes_core::EnumShapesInput = serde_json::from_slice(input_bytes)?;
+            Ok(enum_shapes_core::run(input).commits())
+        }
+        "type_punning" => {
+            let input: type_punning_core::TypePunningInput = serde_json::from_slice(input_bytes)?;
+            Ok(type_punning_core::run(input).commits())
+        }
+        "compress" => {
+            let input: compress_core::CompressInput = serde_json::from_slice(input_bytes)?;
+            Ok(compress_core::run(input).commits())
+        }
+        "json_adversarial" => {
+            let input: json_adversarial_core::JsonAdversarialInput = serde_json::from_slice(input_bytes)?;
+            Ok(json_adversarial_core::run(input).commits())
+        }
+        "sorting" => {
+            let input: sorting_core::SortingInput = serde_json::from_slice(input_bytes)?;
+            Ok(sorting_core::run(input).commits())
+        }
+        "iterator_fold" => {
+            let input: iterator_fold_core::IteratorFoldInput = serde_json::from_slice(input_bytes)?;
+            Ok(iterator_fold_core::run(input).commits())
+        }
+        "matmul" => {
+            let input: matmul_core::MatmulInput = serde_json::from_slice(input_bytes)?;
+            Ok(matmul_core::run(input).commits())
+        }
+        "binary_decode" => {
+            let input: binary_decode_core::BinaryDecodeInput = serde_json::from_slice(input_bytes)?;
+            Ok(binary_decode_core::run(input).commits())
+        }
+        "utf8_boundaries" => {
+            let input: utf8_boundaries_core::Utf8BoundariesInput = serde_json::from_slice(input_bytes)?;
+            Ok(utf8_boundaries_core::run(input).commits())
+        }
+        "collection_growth" => {
+            let input: collection_growth_core::CollectionGrowthInput = serde_json::from_slice(input_bytes)?;
+            Ok(collection_growth_core::run(input).commits())
+        }
+        "thread_api" => {
+            let input: thread_api_core::ThreadApiInput = serde_json::from_slice(input_bytes)?;
+            Ok(thread_api_core::run(input).commits())
         }
         _ => anyhow::bail!("Unknown core: {}", core_name),
     }
 }
 
+/// Why `input_bytes` is not a valid input for the core, without running it
+///
+/// Mirrors the deserialization in [`run_core_dispatch`].
+fn check_input_dispatch(core_name: &str, input_bytes: &[u8]) -> Result<Option<String>> {
+    Ok(match core_name {
+        "fib" => input_error::<fib_core::FibInput>(input_bytes),
+        "panic_test" => input_error::<panic_test_core::PanicInput>(input_bytes),
+        "timeout_test" => input_error::<timeout_test_core::TimeoutInput>(input_bytes),
+        "io_echo" => input_error::<io_echo_core::IoEchoInput>(input_bytes),
+        "arithmetic" => input_error::<arithmetic_core::ArithmeticInput>(input_bytes),
+        "simple_struct" => input_error::<simple_struct_core::SimpleStructInput>(input_bytes),
+        "parse_roundtrip" => input_error::<parse_roundtrip_core::ParseRoundtripInput>(input_bytes),
+        "nondeterminism_probe" => input_error::<nondeterminism_probe_core::NondeterminismProbeInput>(input_bytes),
+        "env_probe" => input_error::<env_probe_core::EnvProbeInput>(input_bytes),
+        "panic_drop" => input_error::<panic_drop_core::PanicDropInput>(input_bytes),
+        "oom_probe" => input_error::<oom_probe_core::OomProbeInput>(input_bytes),
+        "checkpoint_loop" => input_error::<checkpoint_loop_core::CheckpointLoopInput>(input_bytes),
+        "enum_shapes" => input_error::<enum_shapes_core::EnumShapesInput>(input_bytes),
+        "type_punning" => input_error::<type_punning_core::TypePunningInput>(input_bytes),
+        "compress" => input_error::<compress_core::CompressInput>(input_bytes),
+        "json_adversarial" => input_error::<json_adversarial_core::JsonAdversarialInput>(input_bytes),
+        "sorting" => input_error::<sorting_core::SortingInput>(input_bytes),
+        "iterator_fold" => input_error::<iterator_fold_core::IteratorFoldInput>(input_bytes),
+        "matmul" => input_error::<matmul_core::MatmulInput>(input_bytes),
+        "binary_decode" => input_error::<binary_decode_core::BinaryDecodeInput>(input_bytes),
+        "utf8_boundaries" => input_error::<utf8_boundaries_core::Utf8BoundariesInput>(input_bytes),
+        "collection_growth" => input_error::<collection_growth_core::CollectionGrowthInput>(input_bytes),
+        "thread_api" => input_error::<thread_api_core::ThreadApiInput>(input_bytes),
+        _ => anyhow::bail!("Unknown core: {}", core_name),
+    })
+}
+
+/// Deserialization error of an input for the input type `T`, if any
+fn input_error<T: serde::de::DeserializeOwned>(input_bytes: &[u8]) -> Option<String> {
+    serde_json::from_slice::<T>(input_bytes).err().map(|e| e.to_string())
+}
+
 /// Extract panic message from panic payload
 fn extract_panic_message(panic_err: &Box<dyn Any + Send>) -> String {
     if let Some(s) = panic_err.downcast_ref::<&str>() {
@@ -0,0 +1,68 @@
+//! Deterministic environment for sandboxed native runs
+//!
+//! A worker normally inherits the harness's environment variables, locale
+//! and working directory, so a core that reads them can diverge from the
+//! zkVM guest (which sees none of it) for reasons unrelated to the core.
+//! In sandbox mode the worker starts with a cleared environment, a fixed
+//! locale and timezone, and a fresh empty temp directory as its cwd.
+//!
+//! The cwd is a scratch directory, not a chroot: a chroot needs root (or a
+//! user namespace, often disabled in CI containers), and the worker would
+//! lose its own binary and libraries. Absolute paths still reach the whole
+//! filesystem; what the sandbox removes is the harness's environment and
+//! relative paths into the checkout. `meta.sandbox.chroot` says so.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// The only environment variables a sandboxed worker sees
+const SANDBOX_ENV: &[(&str, &str)] = &[("LANG", "C"), ("LC_ALL", "C"), ("TZ", "UTC")];
+
+/// A scratch working directory for one sandboxed run, removed on drop
+pub struct Sandbox {
+    cwd: PathBuf,
+}
+
+impl Sandbox {
+    pub fn create() -> Result<Self> {
+        let cwd = std::env::temp_dir().join(format!("native-runner-sandbox-{}", std::process::id()));
+        fs::create_dir(&cwd)
+            .with_context(|| format!("Failed to create sandbox dir {}", cwd.display()))?;
+        Ok(Sandbox { cwd })
+    }
+
+    /// Configure the worker command to run inside the sandbox
+    pub fn apply(&self, cmd: &mut Command) {
+        cmd.env_clear()
+            .envs(SANDBOX_ENV.iter().copied())
+            .current_dir(&self.cwd);
+
+        // Windows processes fail to start without SystemRoot
+        #[cfg(windows)]
+        if let Some(system_root) = std::env::var_os("SystemRoot") {
+            cmd.env("SystemRoot", system_root);
+        }
+    }
+
+    /// Sandbox configuration, recorded in `RunResult.meta.sandbox`
+    pub fn to_json(&self) -> serde_json::Value {
+        let env: serde_json::Map<String, serde_json::Value> = SANDBOX_ENV
+            .iter()
+            .map(|(key, value)| (key.to_string(), serde_json::Value::from(*value)))
+            .collect();
+        serde_json::json!({
+            "env_cleared": true,
+            "env": env,
+            "cwd": self.cwd.display().to_string(),
+            "chroot": false,
+        })
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.cwd);
+    }
+}
@@ -0,0 +1,294 @@
+//! Input preprocessing shared by the native and SP1 runners
+//!
+//! A core can declare steps that expand a compact input into the one its
+//! `Input` type deserializes, so large inputs need not be stored in full:
+//!
+//! ```toml
+//! # guest/cores/io_echo/Cargo.toml
+//! [package.metadata.fuzz]
+//! preprocess = "bytes:data"
+//! ```
+//!
+//! The harness passes the spec to both runners as `--preprocess`, and they
+//! apply it to the input bytes before anything else reads them. The only
+//! step so far is `bytes:<field>`: a top-level byte-array field may be
+//! written as `{"size": N}`, optionally with `"fill"`, either `"ramp"`
+//! (byte `i` is `i % 256`, the default) or a byte value. Inputs that spell
+//! the field out are passed through unchanged.
+//!
+//! The same step reads a field kept in a binary sidecar file: an input
+//! `{"data_file": "input.bin"}` gets the bytes of `input.bin`, a file in
+//! the input's own folder, as its `data`. [`Preprocess::write_input`]
+//! writes inputs this way, so a 1MB byte array costs 1MB on disk instead
+//! of a 4MB JSON array of numbers, and [`Preprocess::copy_input`] moves an
+//! input together with its sidecars.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::fs;
+use std::path::{Component, Path};
+
+/// Largest byte array a compact spec may expand to
+pub const MAX_EXPANDED_BYTES: u64 = 256 << 20;
+
+/// Shortest byte array [`Preprocess::write_input`] moves to a sidecar
+pub const SIDECAR_MIN_BYTES: usize = 64 << 10;
+
+/// One preprocessing step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// Expand a compact `{"size": N}` byte-array field, or read it from
+    /// the sidecar named by `<field>_file`
+    Bytes(String),
+}
+
+/// Key naming the sidecar file of a byte-array field
+pub fn sidecar_key(field: &str) -> String {
+    format!("{}_file", field)
+}
+
+/// Preprocessing steps, applied in order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Preprocess {
+    pub steps: Vec<Step>,
+}
+
+impl Preprocess {
+    /// Parse a comma-separated spec such as `"bytes:data"`
+    pub fn parse(spec: &str) -> Result<Preprocess> {
+        let steps = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|step| !step.is_empty())
+            .map(|step| match step.split_once(':') {
+                Some(("bytes", field)) if !field.is_empty() => Ok(Step::Bytes(field.to_string())),
+                _ => anyhow::bail!("unknown preprocessing step '{}' (expected bytes:<field>)", step),
+            })
+            .collect::<Result<_>>()?;
+        Ok(Preprocess { steps })
+    }
+
+    /// Fields of the `bytes:<field>` steps, in order
+    pub fn byte_fields(&self) -> impl Iterator<Item = &str> {
+        self.steps.iter().map(|Step::Bytes(field)| field.as_str())
+    }
+
+    /// The input with every step applied (borrowed if no step changed it)
+    ///
+    /// Sidecar files are read from `input_dir`, the input file's folder.
+    pub fn apply<'a>(&self, input_bytes: &'a [u8], input_dir: &Path) -> Result<Cow<'a, [u8]>> {
+        if self.steps.is_empty() {
+            return Ok(Cow::Borrowed(input_bytes));
+        }
+        let Ok(mut input) = serde_json::from_slice::<Value>(input_bytes) else {
+            // Not JSON: leave the error to the core's deserializer
+            return Ok(Cow::Borrowed(input_bytes));
+        };
+        let mut changed = false;
+        for step in &self.steps {
+            match step {
+                Step::Bytes(field) => {
+                    if let Some(value) = input.get_mut(field.as_str()).filter(|value| value.is_object()) {
+                        *value = expand_bytes(value).with_context(|| format!("Invalid compact `{}`", field))?;
+                        changed = true;
+                    }
+                    let key = sidecar_key(field);
+                    if let Some(object) = input.as_object_mut().filter(|object| object.contains_key(&key)) {
+                        anyhow::ensure!(!object.contains_key(field), "`{}` and `{}` are both set", field, key);
+                        let name = object.remove(&key).expect("checked above");
+                        let bytes = read_sidecar(&name, input_dir).with_context(|| format!("Invalid `{}`", key))?;
+                        object.insert(field.clone(), Value::from(bytes));
+                        changed = true;
+                    }
+                }
+            }
+        }
+        if !changed {
+            return Ok(Cow::Borrowed(input_bytes));
+        }
+        Ok(Cow::Owned(serde_json::to_vec(&input)?))
+    }
+
+    /// Write an input to `json_path`, moving byte arrays of at least
+    /// [`SIDECAR_MIN_BYTES`] into sidecar files next to it
+    ///
+    /// A sidecar is named after the JSON file (`input.json` → `input.bin`),
+    /// with the field added when a core has several (`input.data.bin`).
+    /// Other inputs are written as pretty-printed JSON.
+    pub fn write_input(&self, input: &Value, json_path: &Path) -> Result<()> {
+        let mut input = Cow::Borrowed(input);
+        let fields: Vec<&str> = self.byte_fields().collect();
+        for field in &fields {
+            let Some(bytes) = input.get(*field).and_then(as_bytes).filter(|bytes| bytes.len() >= SIDECAR_MIN_BYTES)
+            else {
+                continue;
+            };
+            let stem = json_path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("input");
+            let name = match fields.len() {
+                1 => format!("{}.bin", stem),
+                _ => format!("{}.{}.bin", stem, field),
+            };
+            fs::write(json_path.with_file_name(&name), &bytes)?;
+            let object = input.to_mut().as_object_mut().expect("only objects have fields");
+            object.remove(*field);
+            object.insert(sidecar_key(field), Value::from(name));
+        }
+        fs::write(json_path, serde_json::to_string_pretty(&input)?)
+            .with_context(|| format!("Failed to write {}", json_path.display()))
+    }
+
+    /// Copy an input file and the sidecars it names to `to`
+    ///
+    /// Sidecars keep their names, in `to`'s folder, so the copy's
+    /// references still resolve.
+    pub fn copy_input(&self, from: &Path, to: &Path) -> Result<()> {
+        fs::copy(from, to).with_context(|| format!("Failed to copy {}", from.display()))?;
+        for name in self.sidecars(&fs::read(from)?) {
+            let (from, to) = (from.with_file_name(&name), to.with_file_name(&name));
+            if from != to {
+                fs::copy(&from, &to).with_context(|| format!("Failed to copy {}", from.display()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// File names of the sidecars an input refers to
+    pub fn sidecars(&self, input_bytes: &[u8]) -> Vec<String> {
+        let Ok(input) = serde_json::from_slice::<Value>(input_bytes) else {
+            return Vec::new();
+        };
+        self.byte_fields()
+            .filter_map(|field| input.get(sidecar_key(field))?.as_str())
+            .filter(|name| is_plain_name(name))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// The bytes of a sidecar file, which must sit in `input_dir`
+fn read_sidecar(name: &Value, input_dir: &Path) -> Result<Vec<u8>> {
+    let name = name.as_str().context("expected a file name")?;
+    anyhow::ensure!(is_plain_name(name), "'{}' must be a file name in the input's folder", name);
+    let path = input_dir.join(name);
+    let bytes = fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    anyhow::ensure!(
+        bytes.len() as u64 <= MAX_EXPANDED_BYTES,
+        "{} exceeds {} bytes",
+        path.display(),
+        MAX_EXPANDED_BYTES
+    );
+    Ok(bytes)
+}
+
+/// A bare file name, with no folder part
+fn is_plain_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!((components.next(), components.next()), (Some(Component::Normal(_)), None))
+}
+
+/// A JSON array of byte values as bytes (None for anything else)
+fn as_bytes(value: &Value) -> Option<Vec<u8>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|byte| byte.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+        .collect()
+}
+
+/// `{"size": N, "fill": ...}` as an array of N bytes
+fn expand_bytes(spec: &Value) -> Result<Value> {
+    let spec = spec.as_object().context("expected an object")?;
+    if let Some(key) = spec.keys().find(|key| !matches!(key.as_str(), "size" | "fill")) {
+        anyhow::bail!("unknown key '{}' (expected size and fill)", key);
+    }
+    let size = spec.get("size").and_then(Value::as_u64).context("`size` must be a non-negative integer")?;
+    anyhow::ensure!(size <= MAX_EXPANDED_BYTES, "`size` {} exceeds {} bytes", size, MAX_EXPANDED_BYTES);
+    let bytes: Vec<u8> = match spec.get("fill") {
+        None => ramp(size),
+        Some(Value::String(fill)) if fill == "ramp" => ramp(size),
+        Some(fill) => {
+            let byte = fill
+                .as_u64()
+                .and_then(|byte| u8::try_from(byte).ok())
+                .context("`fill` must be \"ramp\" or a byte value")?;
+            vec![byte; size as usize]
+        }
+    };
+    Ok(Value::from(bytes))
+}
+
+fn ramp(size: u64) -> Vec<u8> {
+    (0..size).map(|i| i as u8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn apply(spec: &str, input: Value) -> Result<Value> {
+        apply_in(spec, input, Path::new("."))
+    }
+
+    fn apply_in(spec: &str, input: Value, dir: &Path) -> Result<Value> {
+        let bytes = serde_json::to_vec(&input).unwrap();
+        Ok(serde_json::from_slice(&Preprocess::parse(spec)?.apply(&bytes, dir)?).unwrap())
+    }
+
+    #[test]
+    fn test_expand_bytes() {
+        let expanded = apply("bytes:data", json!({ "data": { "size": 300 } })).unwrap();
+        let data = expanded["data"].as_array().unwrap();
+        assert_eq!((data.len(), &data[255], &data[256]), (300, &json!(255), &json!(0)));
+
+        let filled = apply("bytes:data", json!({ "data": { "size": 3, "fill": 7 }, "n": 1 })).unwrap();
+        assert_eq!(filled, json!({ "data": [7, 7, 7], "n": 1 }));
+
+        // Spelled-out inputs and other fields are untouched
+        let plain = json!({ "data": [1, 2], "other": { "size": 4 } });
+        assert_eq!(apply("bytes:data", plain.clone()).unwrap(), plain);
+        assert!(matches!(Preprocess::default().apply(b"not json", Path::new(".")), Ok(Cow::Borrowed(_))));
+    }
+
+    #[test]
+    fn test_invalid_specs() {
+        assert!(Preprocess::parse("gzip:data").is_err());
+        assert!(Preprocess::parse("bytes:").is_err());
+        assert_eq!(Preprocess::parse("").unwrap(), Preprocess::default());
+        assert!(apply("bytes:data", json!({ "data": { "size": -1 } })).is_err());
+        assert!(apply("bytes:data", json!({ "data": { "size": 1, "fill": 256 } })).is_err());
+        assert!(apply("bytes:data", json!({ "data": { "size": 1, "seed": 2 } })).is_err());
+        assert!(apply("bytes:data", json!({ "data": { "size": MAX_EXPANDED_BYTES + 1 } })).is_err());
+        assert!(apply("bytes:data", json!({ "data_file": "../secret.bin" })).is_err());
+        assert!(apply("bytes:data", json!({ "data_file": "missing.bin" })).is_err());
+    }
+
+    #[test]
+    fn test_sidecar_round_trip() {
+        let dir = std::env::temp_dir().join(format!("input-preprocess-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("copy")).unwrap();
+        let preprocess = Preprocess::parse("bytes:data").unwrap();
+
+        let bytes: Vec<u8> = (0..SIDECAR_MIN_BYTES).map(|i| (i * 7) as u8).collect();
+        let input = json!({ "data": bytes, "n": 1 });
+        let json_path = dir.join("input.json");
+        preprocess.write_input(&input, &json_path).unwrap();
+        assert_eq!(fs::read(dir.join("input.bin")).unwrap(), bytes);
+        let written: Value = serde_json::from_slice(&fs::read(&json_path).unwrap()).unwrap();
+        assert_eq!(written, json!({ "data_file": "input.bin", "n": 1 }));
+        assert_eq!(apply_in("bytes:data", written.clone(), &dir).unwrap(), input);
+
+        // Copies carry their sidecars along
+        let copy = dir.join("copy/repro.json");
+        preprocess.copy_input(&json_path, &copy).unwrap();
+        assert_eq!(apply_in("bytes:data", written.clone(), &dir.join("copy")).unwrap(), input);
+
+        // Small arrays stay inline, and both forms at once are rejected
+        preprocess.write_input(&json!({ "data": [1, 2] }), &json_path).unwrap();
+        assert_eq!(fs::read_to_string(&json_path).unwrap(), serde_json::to_string_pretty(&json!({ "data": [1, 2] })).unwrap());
+        assert!(apply_in("bytes:data", json!({ "data": [1], "data_file": "input.bin" }), &dir).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,10 +1,20 @@
+mod public_values;
+mod typed_input;
+
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use input_preprocess::Preprocess;
 use rust_eq_oracle::{RunResult, Status};
-use sp1_sdk::{ProverClient, SP1Stdin};
+use sp1_core_executor::events::CpuEvent;
+use sp1_core_executor::{ExecutionError, Executor, ExecutorMode, Program, Register, SP1Context};
+use sp1_sdk::{ExecutionReport, ProverClient, SP1Proof, SP1Stdin};
+use sp1_stark::SP1CoreOpts;
+use std::collections::{BTreeSet, VecDeque};
 use std::fs;
-use std::path::PathBuf;
-use std::sync::mpsc;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
 use std::thread;
 use std::time::{Duration, Instant};
 
@@ -16,7 +26,8 @@ struct Args {
     #[arg(short, long)]
     elf: PathBuf,
 
-    /// Path to the input JSON file
+    /// Path to the input JSON file (with --json-lines: a directory of input
+    /// files, or "-" to read input paths from stdin)
     #[arg(short, long)]
     input: PathBuf,
 
@@ -28,38 +39,113 @@ struct Args {
     #[arg(long, default_value = "30")]
     timeout: u64,
 
-    /// Number of values to read from public_values (if not specified, read until exhausted)
+    /// Number of u32 commits expected in public_values (if not specified, decode every complete commit)
     #[arg(long)]
     num_commits: Option<usize>,
+
+    /// Commit layout for cores that commit other types, e.g. "u32,bytes,string*" (overrides --num-commits)
+    #[arg(long)]
+    commit_schema: Option<String>,
+
+    /// Maximum number of guest cycles before execution is cut off (no limit if not specified)
+    #[arg(long)]
+    max_cycles: Option<u64>,
+
+    /// How the input is delivered to the guest
+    #[arg(long, value_enum, default_value_t = InputEncoding::Json)]
+    encoding: InputEncoding,
+
+    /// Core name (required for --encoding typed)
+    #[arg(long)]
+    core: Option<String>,
+
+    /// Generate and verify a proof instead of only executing (no timeout applies)
+    #[arg(long)]
+    prove: bool,
+
+    /// Proof type generated with --prove
+    #[arg(long, value_enum, default_value_t = ProofMode::Compressed)]
+    proof_mode: ProofMode,
+
+    /// Expand compact inputs before encoding them, e.g. "bytes:data" (the
+    /// core's `preprocess` in its manifest)
+    #[arg(long)]
+    preprocess: Option<String>,
+
+    /// Write JSON input to the guest as a header and raw chunks instead of
+    /// one `Vec<u8>` (the core's `stdin = "chunked"`; its adapter must read
+    /// it with `stdin_chunks`)
+    #[arg(long)]
+    chunked_stdin: bool,
+
+    /// Guest memory budget in bytes: an execution that touches more memory
+    /// is reported as OOM (checked after execution; the SP1 executor has no
+    /// memory limit of its own, so the guest cannot observe it)
+    #[arg(long)]
+    memory_limit: Option<u64>,
+
+    /// Execute a batch of inputs against the ELF, loaded once, printing one
+    /// RunResult JSON per line as each finishes
+    #[arg(long, conflicts_with = "prove")]
+    json_lines: bool,
+
+    /// After executing, execute again with tracing and write the last
+    /// --trace-max-steps instructions to this CSV file (`meta.trace`)
+    #[arg(long, conflicts_with_all = ["prove", "json_lines"])]
+    trace: Option<PathBuf>,
+
+    /// Instructions kept in the --trace file (the last ones executed)
+    #[arg(long, default_value = "100000")]
+    trace_max_steps: usize,
+}
+
+/// Which SP1 proof `--prove` generates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ProofMode {
+    /// One STARK proof per shard
+    Core,
+    /// Shards recursively compressed into a single STARK
+    Compressed,
+    /// Compressed proof wrapped in Groth16 (on-chain verifiable)
+    Groth16,
+    /// Compressed proof wrapped in PLONK (on-chain verifiable)
+    Plonk,
+}
+
+/// How the input reaches the guest through SP1Stdin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum InputEncoding {
+    /// JSON bytes as a `Vec<u8>`, parsed in the guest (default)
+    Json,
+    /// The core's input struct, written with `SP1Stdin::write`
+    Typed,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
-    // Read the ELF file
-    let elf_bytes = fs::read(&args.elf)?;
+    // How to decode the public values
+    let schema = match &args.commit_schema {
+        Some(spec) => public_values::CommitSchema::parse(spec)
+            .map_err(|e| anyhow::anyhow!("Invalid --commit-schema: {}", e))?,
+        None => public_values::CommitSchema::u32s(args.num_commits),
+    };
+    let preprocess = Preprocess::parse(args.preprocess.as_deref().unwrap_or_default())
+        .context("Invalid --preprocess")?;
 
-    // Read the input JSON
-    let input_bytes = fs::read(&args.input)?;
+    // Read the ELF file once (a missing ELF means the guest was never built)
+    let elf = fs::read(&args.elf).map(Arc::new);
 
-    // Run with timeout and panic capture
-    let timeout_duration = if args.timeout > 0 {
-        Some(Duration::from_secs(args.timeout))
-    } else {
-        None
-    };
+    if args.json_lines {
+        return run_batch(&args, &elf, &schema, &preprocess);
+    }
 
-    let result = run_sp1_with_safeguards(
-        elf_bytes,
-        input_bytes,
-        timeout_duration,
-        args.num_commits,
-    )?;
+    let result = run_input(&args, &elf, &schema, &preprocess, &args.input)?;
 
     // Serialize and output
     let result_json = serde_json::to_string_pretty(&result)?;
     
-    if let Some(output_path) = args.output {
+    if let Some(output_path) = &args.output {
         fs::write(output_path, result_json)?;
     } else {
         println!("{}", result_json);
@@ -68,51 +154,327 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// `--json-lines`: execute every input of a batch, one RunResult line each
+///
+/// Inputs are the `*.json` files of a directory, in name order, or with
+/// `--input -` the paths read from stdin, one per line. The ELF is read
+/// once for the whole batch. An input that cannot be run gives an
+/// INFRA_ERROR line instead of ending the batch. Every line carries its
+/// input path in `meta.input`.
+fn run_batch(
+    args: &Args,
+    elf: &std::io::Result<Arc<Vec<u8>>>,
+    schema: &public_values::CommitSchema,
+    preprocess: &Preprocess,
+) -> Result<()> {
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(fs::File::create(path).with_context(|| format!("Failed to create {}", path.display()))?),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    let mut run = |input_path: &Path| -> Result<()> {
+        // A timed-out guest keeps executing on its thread; say so, since it
+        // slows down the inputs after it
+        let background = RUNNING_EXECUTIONS.load(Ordering::SeqCst);
+        let mut result = run_input(args, elf, schema, preprocess, input_path).unwrap_or_else(|e| RunResult {
+            status: Status::InfraError,
+            elapsed_ms: 0,
+            commits: vec![],
+            meta: serde_json::json!({
+                "runner": "sp1",
+                "error": format!("{:#}", e),
+            }),
+        });
+        if let Some(meta) = result.meta.as_object_mut() {
+            meta.insert("input".to_string(), serde_json::Value::from(input_path.display().to_string()));
+            if background > 0 {
+                meta.insert("background_executions".to_string(), serde_json::Value::from(background));
+            }
+        }
+        writeln!(out, "{}", serde_json::to_string(&result)?)?;
+        out.flush()?;
+        Ok(())
+    };
+
+    if args.input == Path::new("-") {
+        for line in std::io::stdin().lock().lines() {
+            let line = line.context("Failed to read input paths from stdin")?;
+            if !line.trim().is_empty() {
+                run(Path::new(line.trim()))?;
+            }
+        }
+    } else {
+        let mut inputs = fs::read_dir(&args.input)
+            .with_context(|| format!("Failed to read input directory {}", args.input.display()))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        inputs.retain(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"));
+        inputs.sort();
+        for input_path in &inputs {
+            run(input_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Execute (or prove) one input against the loaded ELF
+fn run_input(
+    args: &Args,
+    elf: &std::io::Result<Arc<Vec<u8>>>,
+    schema: &public_values::CommitSchema,
+    preprocess: &Preprocess,
+    input_path: &Path,
+) -> Result<RunResult> {
+    // Read the input JSON, expanding a compact one
+    let input_bytes = fs::read(input_path).with_context(|| format!("Failed to read {}", input_path.display()))?;
+    let input_dir = input_path.parent().unwrap_or(Path::new(""));
+    let input_bytes = preprocess.apply(&input_bytes, input_dir)?.into_owned();
+
+    // Encode it for the guest
+    let mut stdin = SP1Stdin::new();
+    match args.encoding {
+        InputEncoding::Json if args.chunked_stdin => {
+            stdin.write(&stdin_chunks::Header::for_len(input_bytes.len()).encode());
+            for chunk in stdin_chunks::chunks(&input_bytes) {
+                stdin.write_slice(chunk);
+            }
+        }
+        InputEncoding::Json => stdin.write(&input_bytes),
+        InputEncoding::Typed => {
+            let core = args
+                .core
+                .as_deref()
+                .context("--encoding typed requires --core")?;
+            typed_input::write_typed_input(core, &input_bytes, &mut stdin)?;
+        }
+    }
+
+    // Run with timeout and panic capture
+    let timeout_duration = if args.timeout > 0 {
+        Some(Duration::from_secs(args.timeout))
+    } else {
+        None
+    };
+
+    // The traced execution needs its own copy of the input
+    let trace_stdin = args.trace.as_ref().map(|_| stdin.clone());
+
+    let mut result = match elf {
+        Ok(elf_bytes) if args.prove => run_sp1_prove(elf_bytes, stdin, schema.clone(), args.proof_mode)?,
+        Ok(elf_bytes) => run_sp1_with_safeguards(
+            Arc::clone(elf_bytes),
+            stdin,
+            args.encoding,
+            timeout_duration,
+            schema.clone(),
+            args.max_cycles,
+        )?,
+        Err(e) => RunResult {
+            status: Status::BuildFailure,
+            elapsed_ms: 0,
+            commits: vec![],
+            meta: serde_json::json!({
+                "runner": "sp1",
+                "error": format!("Failed to read ELF {}: {}", args.elf.display(), e),
+            }),
+        },
+    };
+    if let Some(limit) = args.memory_limit {
+        enforce_memory_limit(&mut result, limit);
+    }
+    // A timed-out guest would run unbounded again
+    if let (Some(path), Some(stdin), Ok(elf_bytes)) = (&args.trace, trace_stdin, elf) {
+        if result.status != Status::Timeout {
+            result.meta["trace"] = match write_trace(elf_bytes, &stdin, args.max_cycles, path, args.trace_max_steps) {
+                Ok(trace) => trace,
+                Err(e) => serde_json::json!({ "error": format!("{:#}", e) }),
+            };
+        }
+    }
+    Ok(result)
+}
+
+/// Cycles per shard of the traced execution, small so each batch of CPU
+/// events is dropped before the next one is produced
+const TRACE_SHARD_SIZE: usize = 1 << 16;
+
+/// Execute with tracing and write the last `max_steps` instructions to
+/// `path` as CSV: step, pc, opcode, the operand values `a` (result), `b`
+/// and `c`, and the next pc
+///
+/// Runs separately from the measured execution, so only callers that ask
+/// for a trace pay for it. Returns the summary kept in `meta.trace`.
+fn write_trace(
+    elf_bytes: &[u8],
+    stdin: &SP1Stdin,
+    max_cycles: Option<u64>,
+    path: &Path,
+    max_steps: usize,
+) -> Result<serde_json::Value> {
+    let program = Program::from(elf_bytes).map_err(|e| anyhow::anyhow!("Failed to load ELF: {}", e))?;
+    let (mut stdout, mut stderr) = (std::io::sink(), std::io::sink());
+    let mut context = SP1Context::builder();
+    context.stdout(&mut stdout).stderr(&mut stderr);
+    if let Some(limit) = max_cycles {
+        context.max_cycles(limit);
+    }
+    let opts = SP1CoreOpts {
+        shard_size: TRACE_SHARD_SIZE,
+        shard_batch_size: 1,
+        ..SP1CoreOpts::default()
+    };
+    let mut executor = Executor::with_context(program, opts, context.build());
+    executor.executor_mode = ExecutorMode::Trace;
+    executor.write_vecs(&stdin.buffer);
+
+    let mut steps: VecDeque<CpuEvent> = VecDeque::new();
+    let mut total_steps = 0u64;
+    let mut keep = |events: Vec<CpuEvent>| {
+        total_steps += events.len() as u64;
+        for event in events {
+            if steps.len() == max_steps {
+                steps.pop_front();
+            }
+            if max_steps > 0 {
+                steps.push_back(event);
+            }
+        }
+    };
+    let error = loop {
+        let outcome = executor.execute();
+        for record in executor.records.drain(..) {
+            keep(record.cpu_events);
+        }
+        match outcome {
+            Ok(true) => break None,
+            Ok(false) => {}
+            Err(e) => {
+                // Events since the last shard boundary are still in the open record
+                keep(std::mem::take(&mut executor.record.cpu_events));
+                break Some(format!("{:?}", e));
+            }
+        }
+    };
+
+    let mut out = std::io::BufWriter::new(
+        fs::File::create(path).with_context(|| format!("Failed to create {}", path.display()))?,
+    );
+    writeln!(out, "step,pc,opcode,a,b,c,next_pc")?;
+    let first_step = total_steps - steps.len() as u64;
+    for (index, event) in steps.iter().enumerate() {
+        writeln!(
+            out,
+            "{},{:#010x},{},{:#x},{:#x},{:#x},{:#010x}",
+            first_step + index as u64,
+            event.pc,
+            executor.program.fetch(event.pc).opcode.mnemonic(),
+            event.a,
+            event.b,
+            event.c,
+            event.next_pc
+        )?;
+    }
+    out.flush()?;
+
+    Ok(serde_json::json!({
+        "path": path.display().to_string(),
+        "steps": steps.len(),
+        "total_steps": total_steps,
+        "truncated": total_steps > steps.len() as u64,
+        "error": error,
+    }))
+}
+
+/// Report an execution that touched more than `limit` bytes as OOM
+///
+/// Its commits are dropped, as a guest stopped by a real limit would not
+/// have finished; `meta.public_values_hex` keeps what it committed.
+fn enforce_memory_limit(result: &mut RunResult, limit: u64) {
+    let Some(meta) = result.meta.as_object_mut() else {
+        return;
+    };
+    meta.insert("memory_limit".to_string(), limit.into());
+    let used = meta.get("memory_bytes").and_then(|bytes| bytes.as_u64());
+    if result.status == Status::Ok && used.is_some_and(|used| used > limit) {
+        meta.insert("oom_cause".to_string(), "memory_limit".into());
+        result.status = Status::Oom;
+        result.commits.clear();
+    }
+}
+
+/// Executions still running on their threads (a timed-out one runs on)
+static RUNNING_EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts an execution in [`RUNNING_EXECUTIONS`] until dropped
+struct RunningExecution;
+
+impl RunningExecution {
+    fn start() -> Self {
+        RUNNING_EXECUTIONS.fetch_add(1, Ordering::SeqCst);
+        RunningExecution
+    }
+}
+
+impl Drop for RunningExecution {
+    fn drop(&mut self) {
+        RUNNING_EXECUTIONS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 /// Run SP1 guest with timeout and panic capture
 fn run_sp1_with_safeguards(
-    elf_bytes: Vec<u8>,
-    input_bytes: Vec<u8>,
+    elf_bytes: Arc<Vec<u8>>,
+    stdin: SP1Stdin,
+    encoding: InputEncoding,
     timeout: Option<Duration>,
-    num_commits: Option<usize>,
+    schema: public_values::CommitSchema,
+    max_cycles: Option<u64>,
 ) -> Result<RunResult> {
     let (tx, rx) = mpsc::channel();
 
     // Spawn thread to run SP1
+    let running = RunningExecution::start();
     let handle = thread::spawn(move || {
-        let result = (|| -> Result<RunResult> {
-            // Create SP1 stdin and write the input
-            let mut stdin = SP1Stdin::new();
-            stdin.write(&input_bytes);
+        let _running = running;
+        // What the guest prints (write syscalls to fd 1/2)
+        let mut guest_stdout = Vec::new();
+        let mut guest_stderr = Vec::new();
 
-            // Create the prover client
-            let client = ProverClient::from_env();
+        let result = (|| -> Result<RunResult> {
+            let program = Program::from(&elf_bytes[..]).map_err(|e| anyhow::anyhow!("Failed to load ELF: {}", e))?;
 
             // Execute (not prove) the program and measure time
             let start = Instant::now();
-            let execution_result = client.execute(&elf_bytes, &stdin).run();
+            let execution_result = execute(program, &stdin, max_cycles, &mut guest_stdout, &mut guest_stderr);
             let elapsed = start.elapsed();
 
             match execution_result {
-                Ok((mut public_values, report)) => {
-                    // Extract commits from public values
-                    let mut commits = Vec::new();
-
-                    if let Some(n) = num_commits {
-                        // Read exact number of commits
-                        for _ in 0..n {
-                            // Read as u32 (generic type - could be improved)
-                            let value: u32 = public_values.read();
-                            commits.push(serde_json::to_value(&value)?);
+                Ok((public_values, report)) => {
+                    // Decode commits from the raw public values buffer
+                    let commits = match public_values::decode_commits(public_values.as_slice(), &schema) {
+                        Ok(commits) => commits,
+                        Err(e) => {
+                            // The guest ran fine but its output doesn't match
+                            // the commit schema: a harness problem, not a result
+                            return Ok(RunResult {
+                                status: Status::InfraError,
+                                elapsed_ms: elapsed.as_millis(),
+                                commits: vec![],
+                                meta: serde_json::json!({
+                                    "runner": "sp1",
+                                    "mode": "execute",
+                                    "error": e.to_string(),
+                                    "decode_error": public_values::decode_error_json(
+                                        &e,
+                                        &schema,
+                                        public_values.as_slice().len()
+                                    ),
+                                    "commit_schema": schema.to_string(),
+                                    "public_values_hex": public_values::to_hex(public_values.as_slice()),
+                                    "cycles": report.total_instruction_count(),
+                                }),
+                            });
                         }
-                    } else {
-                        // Read until exhausted (for now, hardcode for common types)
-                        // TODO: Make this more generic in future phases
-                        while let Ok(value) = std::panic::catch_unwind(
-                            std::panic::AssertUnwindSafe(|| public_values.read::<u32>())
-                        ) {
-                            commits.push(serde_json::to_value(&value)?);
-                        }
-                    }
+                    };
 
                     Ok(RunResult {
                         status: Status::Ok,
@@ -121,27 +483,59 @@ fn run_sp1_with_safeguards(
                         meta: serde_json::json!({
                             "runner": "sp1",
                             "mode": "execute",
+                            "encoding": format!("{:?}", encoding).to_lowercase(),
                             "cycles": report.total_instruction_count(),
+                            // Distinct memory words the guest touched
+                            "memory_bytes": report.touched_memory_addresses * 4,
+                            "opcode_counts": opcode_counts(&report),
+                            "public_values_hex": public_values::to_hex(public_values.as_slice()),
                         }),
                     })
                 }
-                Err(e) => {
-                    // SP1 execution failed (likely panic in guest)
-                    let error_msg = format!("{}", e);
+                Err(ExecutionFailure { error, .. }) if max_cycles.is_some() && is_cycle_limit_error(&error) => {
+                    // Guest ran past its cycle budget (deterministic cut-off)
                     Ok(RunResult {
-                        status: Status::Panic,
+                        status: Status::CycleLimit,
                         elapsed_ms: elapsed.as_millis(),
                         commits: vec![],
                         meta: serde_json::json!({
                             "runner": "sp1",
                             "mode": "execute",
-                            "panic_msg": error_msg,
+                            "max_cycles": max_cycles,
                         }),
                     })
                 }
+                Err(ExecutionFailure { error, fault }) => {
+                    // SP1 execution failed (likely panic in guest, possibly out of memory)
+                    let error_msg = format!("{}", error);
+                    let mut meta = serde_json::json!({
+                        "runner": "sp1",
+                        "mode": "execute",
+                        "panic_msg": error_msg,
+                        "fault": fault,
+                    });
+                    let status = if is_oom_error(&error_msg) {
+                        // The guest's own allocator gave up (its heap is full)
+                        meta["oom_cause"] = "allocator".into();
+                        Status::Oom
+                    } else {
+                        Status::Panic
+                    };
+                    Ok(RunResult {
+                        status,
+                        elapsed_ms: elapsed.as_millis(),
+                        commits: vec![],
+                        meta,
+                    })
+                }
             }
         })();
 
+        let result = result.map(|mut result| {
+            result.attach_output(&guest_stdout, &guest_stderr);
+            result
+        });
+
         tx.send(result)
     });
 
@@ -171,9 +565,214 @@ fn run_sp1_with_safeguards(
         rx.recv().context("SP1 runner thread disconnected")?
     };
 
-    // Clean up thread
-    let _ = handle.join();
+    // Clean up thread (a timed-out thread is still running; joining it would
+    // block until execution finishes, so leave it to die with the process)
+    if handle.is_finished() {
+        let _ = handle.join();
+    }
 
     result
 }
 
+/// Instructions executed per opcode (mnemonic → count, executed ones only)
+fn opcode_counts(report: &ExecutionReport) -> serde_json::Value {
+    report
+        .opcode_counts
+        .iter()
+        .filter(|(_, count)| **count > 0)
+        .map(|(opcode, count)| (opcode.mnemonic().to_string(), serde_json::Value::from(*count)))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+/// A failed execution
+struct ExecutionFailure {
+    error: ExecutionError,
+    /// Where the guest stopped: `pc`, the return address `ra` and the cycle
+    fault: serde_json::Value,
+}
+
+/// Execute the guest once, as `ProverClient::execute` does, returning the
+/// public values and the report
+///
+/// The SDK's execution error carries no machine state, so the guest runs on
+/// an executor kept here, which holds its registers after the error (`pc`
+/// is the faulting instruction, e.g. the halt `ecall` of a panic). The
+/// harness symbolicates both against the ELF.
+fn execute(
+    program: Program,
+    stdin: &SP1Stdin,
+    max_cycles: Option<u64>,
+    stdout: &mut Vec<u8>,
+    stderr: &mut Vec<u8>,
+) -> Result<(Vec<u8>, ExecutionReport), ExecutionFailure> {
+    let mut context = SP1Context::builder();
+    context.stdout(stdout).stderr(stderr);
+    if let Some(limit) = max_cycles {
+        context.max_cycles(limit);
+    }
+    let mut executor = Executor::with_context(program, SP1CoreOpts::default(), context.build());
+    executor.write_vecs(&stdin.buffer);
+    match executor.run_fast() {
+        Ok(()) => Ok((std::mem::take(&mut executor.state.public_values_stream), std::mem::take(&mut executor.report))),
+        Err(error) => Err(ExecutionFailure {
+            fault: serde_json::json!({
+                "pc": format!("{:#010x}", executor.state.pc),
+                "ra": format!("{:#010x}", executor.register(Register::X1)),
+                "cycles": executor.state.global_clk,
+                "error": format!("{:?}", error),
+            }),
+            error,
+        }),
+    }
+}
+
+/// Execute, then prove and verify the guest, timing each step
+///
+/// Used by `harness bench-prove`. The execution pass supplies the cycle count
+/// and stops early if the guest fails; proving a failing guest is pointless.
+/// `elapsed_ms` is the proving time; `meta` adds `verify_ms`,
+/// `proof_size_bytes` (bincode-serialized proof with public values),
+/// `setup_ms`, `cycles`, `shards` and `proof_shape` (see [`proof_shape`]).
+fn run_sp1_prove(
+    elf_bytes: &[u8],
+    stdin: SP1Stdin,
+    schema: public_values::CommitSchema,
+    proof_mode: ProofMode,
+) -> Result<RunResult> {
+    let client = ProverClient::from_env();
+    let mode = format!("{:?}", proof_mode).to_lowercase();
+
+    let start = Instant::now();
+    let (_, report) = match client.execute(elf_bytes, &stdin).run() {
+        Ok(executed) => executed,
+        Err(e) => {
+            let error_msg = format!("{}", e);
+            return Ok(RunResult {
+                status: if is_oom_error(&error_msg) { Status::Oom } else { Status::Panic },
+                elapsed_ms: start.elapsed().as_millis(),
+                commits: vec![],
+                meta: serde_json::json!({
+                    "runner": "sp1",
+                    "mode": "prove",
+                    "proof_mode": mode,
+                    "panic_msg": error_msg,
+                }),
+            });
+        }
+    };
+    let cycles = report.total_instruction_count();
+
+    let start = Instant::now();
+    let (pk, vk) = client.setup(elf_bytes);
+    let setup_ms = start.elapsed().as_millis();
+
+    let start = Instant::now();
+    let prover = client.prove(&pk, &stdin);
+    let proof = match proof_mode {
+        ProofMode::Core => prover.core().run(),
+        ProofMode::Compressed => prover.compressed().run(),
+        ProofMode::Groth16 => prover.groth16().run(),
+        ProofMode::Plonk => prover.plonk().run(),
+    };
+    let prove_ms = start.elapsed().as_millis();
+    let proof = match proof {
+        Ok(proof) => proof,
+        Err(e) => {
+            // The guest executed fine, so a proving failure is the prover's problem
+            return Ok(RunResult {
+                status: Status::InfraError,
+                elapsed_ms: prove_ms,
+                commits: vec![],
+                meta: serde_json::json!({
+                    "runner": "sp1",
+                    "mode": "prove",
+                    "proof_mode": mode,
+                    "error": format!("Proving failed: {}", e),
+                    "cycles": cycles,
+                }),
+            });
+        }
+    };
+
+    let start = Instant::now();
+    let verified = client.verify(&proof, &vk);
+    let verify_ms = start.elapsed().as_millis();
+
+    let proof_size_bytes = bincode::serialized_size(&proof).context("Failed to measure proof size")?;
+    let (shards, shape) = proof_shape(&proof.proof, cycles);
+    let commits = public_values::decode_commits(proof.public_values.as_slice(), &schema).unwrap_or_default();
+
+    let (status, error) = match verified {
+        Ok(()) => (Status::Ok, None),
+        Err(e) => (Status::InfraError, Some(format!("Verification failed: {}", e))),
+    };
+    Ok(RunResult {
+        status,
+        elapsed_ms: prove_ms,
+        commits,
+        meta: serde_json::json!({
+            "runner": "sp1",
+            "mode": "prove",
+            "proof_mode": mode,
+            "cycles": cycles,
+            "setup_ms": setup_ms,
+            "prove_ms": prove_ms,
+            "verify_ms": verify_ms,
+            "proof_size_bytes": proof_size_bytes,
+            "shards": shards,
+            "proof_shape": shape,
+            "error": error,
+            "public_values_hex": public_values::to_hex(proof.public_values.as_slice()),
+        }),
+    })
+}
+
+/// Number of shards a proof covers, and a summary of its shape
+///
+/// A core proof holds one STARK proof per shard, so its count is exact and
+/// the shape lists the chips used and the largest trace (log2 rows).
+/// Compressed, Groth16 and Plonk proofs fold the shards away; for those the
+/// count is estimated from the cycles and the prover's shard size, and the
+/// shape says so (`estimated`).
+fn proof_shape(proof: &SP1Proof, cycles: u64) -> (u64, serde_json::Value) {
+    match proof {
+        SP1Proof::Core(shard_proofs) => {
+            let max_log_degree = shard_proofs
+                .iter()
+                .flat_map(|shard| shard.opened_values.chips.iter().map(|chip| chip.log_degree))
+                .max();
+            let chips: BTreeSet<&str> = shard_proofs
+                .iter()
+                .flat_map(|shard| shard.chip_ordering.keys().map(String::as_str))
+                .collect();
+            let shape = serde_json::json!({
+                "estimated": false,
+                "max_log_degree": max_log_degree,
+                "chips": chips,
+            });
+            (shard_proofs.len() as u64, shape)
+        }
+        _ => {
+            let shard_size = SP1CoreOpts::default().shard_size as u64;
+            let shape = serde_json::json!({ "estimated": true, "shard_size": shard_size });
+            (cycles.div_ceil(shard_size).max(1), shape)
+        }
+    }
+}
+
+/// Whether an execution error is SP1's cycle limit being exceeded
+fn is_cycle_limit_error(error: &impl std::fmt::Display) -> bool {
+    // The executor reports `ExecutionError::ExceededCycleLimit` as
+    // "exceeded cycle limit of N"; match on the message so we don't depend
+    // on how the SDK wraps the error type
+    error.to_string().to_lowercase().contains("exceeded cycle limit")
+}
+
+/// Whether a guest failure message indicates memory exhaustion
+fn is_oom_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    ["memory allocation", "out of memory", "memory limit exceeded"]
+        .iter()
+        .any(|marker| message.contains(marker))
+}
@@ -0,0 +1,67 @@
+//! Schema-driven decoding of the guest's public values buffer
+//!
+//! SP1 serializes every `sp1_zkvm::io::commit` with bincode, back to back.
+//! Instead of calling `SP1PublicValues::read` until it panics, the runner
+//! takes the raw buffer and decodes it against the core's commit schema.
+//! Most adapters commit a sequence of `u32`s (4 little-endian bytes each);
+//! cores that commit other types declare a schema such as `u32,bytes,string*`
+//! in their manifest, which the harness passes as `--commit-schema`.
+//!
+//! The decoder itself is `commit_codec::decode_schema`, the inverse of the
+//! encoding the native runner commits with, so both sides and its property
+//! tests share one implementation.
+
+pub use commit_codec::{CommitSchema, DecodeError};
+
+/// Decode the raw public values into commits following `schema`, as the
+/// JSON values the native runner reports for the same fields
+pub fn decode_commits(
+    bytes: &[u8],
+    schema: &CommitSchema,
+) -> Result<Vec<serde_json::Value>, DecodeError> {
+    let commits = commit_codec::decode_schema(bytes, schema)?;
+    Ok(commits
+        .iter()
+        .map(|commit| serde_json::to_value(commit).expect("commit values serialize to JSON"))
+        .collect())
+}
+
+/// Structured form of a decode error for `RunResult.meta`
+pub fn decode_error_json(
+    error: &DecodeError,
+    schema: &CommitSchema,
+    len: usize,
+) -> serde_json::Value {
+    match *error {
+        DecodeError::Truncated { commit_index, .. } => serde_json::json!({
+            "kind": "truncated",
+            "expected_commits": schema.min_commits(),
+            "decoded_commits": commit_index,
+            "public_values_len": len,
+        }),
+        DecodeError::TrailingBytes { decoded, offset } => serde_json::json!({
+            "kind": "trailing_bytes",
+            "decoded_commits": decoded,
+            "trailing_bytes": len - offset,
+            "public_values_len": len,
+        }),
+        DecodeError::InvalidUtf8 { commit_index, .. }
+        | DecodeError::InvalidBool { commit_index, .. } => {
+            serde_json::json!({
+                "kind": "invalid_value",
+                "commit_index": commit_index,
+                "public_values_len": len,
+            })
+        }
+        DecodeError::UnknownType { commit_index } => serde_json::json!({
+            "kind": "unknown_type",
+            "commit_index": commit_index,
+            "public_values_len": len,
+        }),
+    }
+}
+
+/// Lowercase hex encoding of the raw public values (kept in `RunResult.meta`)
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,46 @@
+//! Typed input encoding for serialization-boundary differential tests
+//!
+//! By default the runner hands the guest its input as JSON bytes, which the
+//! adapter parses with `serde_json`. In typed mode the runner deserializes
+//! the JSON on the host and writes the core's input struct with
+//! `SP1Stdin::write` instead, preceded by an empty `Vec<u8>` marker that
+//! tells the adapter to read the struct directly. Comparing the two catches
+//! bugs in the adapter I/O layer independent of the core logic.
+
+use anyhow::Result;
+use sp1_sdk::SP1Stdin;
+
+/// Write `input_bytes` (JSON) to `stdin` as the core's typed input struct
+pub fn write_typed_input(core_name: &str, input_bytes: &[u8], stdin: &mut SP1Stdin) -> Result<()> {
+    // Empty marker: JSON inputs are never empty
+    stdin.write(&Vec::<u8>::new());
+
+    match core_name {
+        "fib" => stdin.write(&serde_json::from_slice::<fib_core::FibInput>(input_bytes)?),
+        "panic_test" => stdin.write(&serde_json::from_slice::<panic_test_core::PanicInput>(input_bytes)?),
+        "timeout_test" => stdin.write(&serde_json::from_slice::<timeout_test_core::TimeoutInput>(input_bytes)?),
+        "io_echo" => stdin.write(&serde_json::from_slice::<io_echo_core::IoEchoInput>(input_bytes)?),
+        "arithmetic" => stdin.write(&serde_json::from_slice::<arithmetic_core::ArithmeticInput>(input_bytes)?),
+        "simple_struct" => stdin.write(&serde_json::from_slice::<simple_struct_core::SimpleStructInput>(input_bytes)?),
+        "parse_roundtrip" => stdin.write(&serde_json::from_slice::<parse_roundtrip_core::ParseRoundtripInput>(input_bytes)?),
+        "nondeterminism_probe" => stdin.write(&serde_json::from_slice::<nondeterminism_probe_core::NondeterminismProbeInput>(input_bytes)?),
+        "env_probe" => stdin.write(&serde_json::from_slice::<env_probe_core::EnvProbeInput>(input_bytes)?),
+        "panic_drop" => stdin.write(&serde_json::from_slice::<panic_drop_core::PanicDropInput>(input_bytes)?),
+        "oom_probe" => stdin.write(&serde_json::from_slice::<oom_probe_core::OomProbeInput>(input_bytes)?),
+        "checkpoint_loop" => stdin.write(&serde_json::from_slice::<checkpoint_loop_core::CheckpointLoopInput>(input_bytes)?),
+        "enum_shapes" => stdin.write(&serde_json::from_slice::<enum_shapes_core::EnumShapesInput>(input_bytes)?),
+        "type_punning" => stdin.write(&serde_json::from_slice::<type_punning_core::TypePunningInput>(input_bytes)?),
+        "compress" => stdin.write(&serde_json::from_slice::<compress_core::CompressInput>(input_bytes)?),
+        "json_adversarial" => stdin.write(&serde_json::from_slice::<json_adversarial_core::JsonAdversarialInput>(input_bytes)?),
+        "sorting" => stdin.write(&serde_json::from_slice::<sorting_core::SortingInput>(input_bytes)?),
+        "iterator_fold" => stdin.write(&serde_json::from_slice::<iterator_fold_core::IteratorFoldInput>(input_bytes)?),
+        "matmul" => stdin.write(&serde_json::from_slice::<matmul_core::MatmulInput>(input_bytes)?),
+        "binary_decode" => stdin.write(&serde_json::from_slice::<binary_decode_core::BinaryDecodeInput>(input_bytes)?),
+        "utf8_boundaries" => stdin.write(&serde_json::from_slice::<utf8_boundaries_core::Utf8BoundariesInput>(input_bytes)?),
+        "collection_growth" => stdin.write(&serde_json::from_slice::<collection_growth_core::CollectionGrowthInput>(input_bytes)?),
+        "thread_api" => stdin.write(&serde_json::from_slice::<thread_api_core::ThreadApiInput>(input_bytes)?),
+        _ => anyhow::bail!("No typed encoding for core: {}", core_name),
+    }
+
+    Ok(())
+}